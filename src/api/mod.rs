@@ -1,3 +1,7 @@
+pub mod prices;
+pub mod rate_limit;
+
+use rate_limit::RateLimits;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -9,6 +13,10 @@ pub enum ApiProvider {
     Alchemy,
     /// RSK RPC API - Primary RPC for blockchain operations (balances, transactions, etc.)
     RskRpc,
+    /// Blockscout explorer API - Keyless transaction history and token balances
+    Blockscout,
+    /// Official Rootstock explorer API - Keyless transaction history
+    Explorer,
     /// Custom API provider
     Custom(String),
 }
@@ -18,11 +26,32 @@ impl fmt::Display for ApiProvider {
         match self {
             ApiProvider::Alchemy => write!(f, "Alchemy"),
             ApiProvider::RskRpc => write!(f, "RSK RPC"),
+            ApiProvider::Blockscout => write!(f, "Blockscout"),
+            ApiProvider::Explorer => write!(f, "Rootstock Explorer"),
             ApiProvider::Custom(name) => write!(f, "{}", name),
         }
     }
 }
 
+impl ApiProvider {
+    /// History providers in the order they are tried: the preferred one
+    /// first, then the others in the order Alchemy, Blockscout, explorer.
+    /// Raw logs from the RPC node are the last resort after all of these.
+    pub fn history_fallback(preferred: &ApiProvider) -> Vec<ApiProvider> {
+        let mut order = vec![preferred.clone()];
+        for provider in [
+            ApiProvider::Alchemy,
+            ApiProvider::Blockscout,
+            ApiProvider::Explorer,
+        ] {
+            if !order.contains(&provider) {
+                order.push(provider);
+            }
+        }
+        order
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiKey {
     pub key: String,
@@ -67,6 +96,11 @@ impl ApiManager {
 // Integration with the existing config system
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct ApiConfig {
+    /// Provider tried first for transaction history (Alchemy, Blockscout or
+    /// Explorer); the others are used when it fails
     pub default_provider: Option<ApiProvider>,
     pub keys: Vec<ApiKey>,
+    /// Client-side request rates per provider
+    #[serde(default)]
+    pub rate_limits: RateLimits,
 }
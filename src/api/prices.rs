@@ -0,0 +1,314 @@
+//! Fiat prices from the CoinGecko API, shown next to balances, transfer
+//! previews and history when a display currency is configured, and used to
+//! value the portfolio.
+//!
+//! Prices are only looked up on mainnet; test network coins have no market
+//! value. Lookups are cached for a minute so a screen that shows several
+//! amounts asks once, and a failed lookup only leaves the fiat value out.
+
+use crate::config::Config;
+use crate::types::network::Network;
+use crate::utils::{http, redact};
+use alloy::primitives::Address;
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const COINGECKO_URL: &str = "https://api.coingecko.com/api/v3";
+/// CoinGecko id of RBTC, and of the Rootstock platform for token prices
+const COINGECKO_ID: &str = "rootstock";
+const CACHE_TTL: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Quotes by currency and token (`None` for RBTC), with when they were fetched
+type PriceCache = HashMap<(Currency, Option<Address>), (Instant, Option<Quote>)>;
+static CACHE: Mutex<Option<PriceCache>> = Mutex::new(None);
+
+/// Currency fiat equivalents are shown in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Currency {
+    Usd,
+    Eur,
+}
+
+impl Currency {
+    pub const ALL: [Currency; 2] = [Currency::Usd, Currency::Eur];
+
+    /// Code CoinGecko uses for the currency
+    fn code(self) -> &'static str {
+        match self {
+            Currency::Usd => "usd",
+            Currency::Eur => "eur",
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code().to_uppercase())
+    }
+}
+
+impl std::str::FromStr for Currency {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Currency::ALL
+            .into_iter()
+            .find(|currency| currency.code().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| anyhow!("Unsupported currency '{}'; use USD or EUR", s))
+    }
+}
+
+/// Price of one coin or token
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quote {
+    pub price: f64,
+    /// Change of the price over the last 24 hours, in percent
+    pub change_24h: Option<f64>,
+}
+
+/// Prices of RBTC and tokens in the display currency
+#[derive(Debug, Clone)]
+pub struct Prices {
+    pub currency: Currency,
+    quotes: HashMap<Option<Address>, Quote>,
+}
+
+impl Prices {
+    /// Prices of RBTC and `tokens`, or `None` when no display currency is
+    /// configured or the default network is not mainnet
+    pub async fn fetch(config: &Config, tokens: &[Address]) -> Option<Self> {
+        Self::fetch_in(config.display_currency?, config.default_network, tokens).await
+    }
+
+    /// Prices of RBTC and `tokens` on `network` in `currency`, or `None` when
+    /// `network` is not mainnet
+    pub async fn fetch_in(
+        currency: Currency,
+        network: Network,
+        tokens: &[Address],
+    ) -> Option<Self> {
+        if network.is_testnet() {
+            return None;
+        }
+        let mut quotes = HashMap::new();
+        for (token, quote) in cached_quotes(currency, tokens).await {
+            if let Some(quote) = quote {
+                quotes.insert(token, quote);
+            }
+        }
+        Some(Self { currency, quotes })
+    }
+
+    /// Price of one RBTC (`token` `None`) or one whole token
+    pub fn price(&self, token: Option<Address>) -> Option<f64> {
+        self.quotes.get(&token).map(|quote| quote.price)
+    }
+
+    /// Change of the price of RBTC or a token over the last 24 hours, in
+    /// percent
+    pub fn change_24h(&self, token: Option<Address>) -> Option<f64> {
+        self.quotes.get(&token)?.change_24h
+    }
+
+    /// `amount` of `token` in the display currency, e.g. `1234.56 USD`
+    pub fn value(&self, token: Option<Address>, amount: f64) -> Option<String> {
+        self.price(token)
+            .map(|price| format_fiat(amount * price, self.currency))
+    }
+}
+
+/// Amount of fiat with two decimals and the currency code, masked while
+/// output is redacted
+pub fn format_fiat(value: f64, currency: Currency) -> String {
+    redact::amount(format!("{:.2} {}", value, currency))
+}
+
+/// Quotes for RBTC and `tokens`, fetching those not cached in the last minute
+async fn cached_quotes(
+    currency: Currency,
+    tokens: &[Address],
+) -> Vec<(Option<Address>, Option<Quote>)> {
+    let wanted: Vec<Option<Address>> = std::iter::once(None)
+        .chain(tokens.iter().copied().map(Some))
+        .collect();
+    let cached = |token: &Option<Address>| {
+        CACHE
+            .lock()
+            .ok()?
+            .as_ref()?
+            .get(&(currency, *token))
+            .filter(|(fetched, _)| fetched.elapsed() < CACHE_TTL)
+            .map(|(_, quote)| *quote)
+    };
+
+    let missing: Vec<Address> = wanted
+        .iter()
+        .filter(|token| cached(token).is_none())
+        .filter_map(|token| *token)
+        .collect();
+    let mut fetched = HashMap::new();
+    if cached(&None).is_none() {
+        match coin_quote(currency).await {
+            Ok(quote) => {
+                fetched.insert(None, quote);
+            }
+            Err(e) => log::warn!("Failed to fetch the RBTC price: {}", e),
+        }
+    }
+    if !missing.is_empty() {
+        match token_quotes(currency, &missing).await {
+            Ok(quotes) => {
+                for token in missing {
+                    fetched.insert(Some(token), quotes.get(&token).copied());
+                }
+            }
+            Err(e) => log::warn!("Failed to fetch token prices: {}", e),
+        }
+    }
+    if let Ok(mut cache) = CACHE.lock() {
+        let cache = cache.get_or_insert_with(HashMap::new);
+        for (token, quote) in &fetched {
+            cache.insert((currency, *token), (Instant::now(), *quote));
+        }
+    }
+
+    wanted
+        .into_iter()
+        .map(|token| {
+            let quote = fetched
+                .get(&token)
+                .copied()
+                .flatten()
+                .or_else(|| cached(&token).flatten());
+            (token, quote)
+        })
+        .collect()
+}
+
+async fn get(path: &str, query: &[(&str, String)]) -> Result<Value> {
+    let response = http::secure_client()?
+        .get(format!("{}{}", COINGECKO_URL, path))
+        .query(query)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| anyhow!("Price request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(anyhow!("Price API returned {}", response.status()));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| anyhow!("Invalid price response: {}", e))
+}
+
+async fn coin_quote(currency: Currency) -> Result<Option<Quote>> {
+    let body = get(
+        "/simple/price",
+        &[
+            ("ids", COINGECKO_ID.to_string()),
+            ("vs_currencies", currency.code().to_string()),
+            ("include_24hr_change", "true".to_string()),
+        ],
+    )
+    .await?;
+    Ok(quote(&body[COINGECKO_ID], currency))
+}
+
+async fn token_quotes(currency: Currency, tokens: &[Address]) -> Result<HashMap<Address, Quote>> {
+    let addresses = tokens
+        .iter()
+        .map(|token| format!("{:#x}", token))
+        .collect::<Vec<_>>()
+        .join(",");
+    let body = get(
+        &format!("/simple/token_price/{}", COINGECKO_ID),
+        &[
+            ("contract_addresses", addresses),
+            ("vs_currencies", currency.code().to_string()),
+            ("include_24hr_change", "true".to_string()),
+        ],
+    )
+    .await?;
+    Ok(parse_token_quotes(&body, currency))
+}
+
+/// Token quotes from a `simple/token_price` response, keyed by lowercase
+/// contract address
+fn parse_token_quotes(body: &Value, currency: Currency) -> HashMap<Address, Quote> {
+    body.as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(address, prices)| Some((address.parse().ok()?, quote(prices, currency)?)))
+        .collect()
+}
+
+/// Quote from an entry such as `{"usd": 1.0, "usd_24h_change": -0.2}`
+fn quote(prices: &Value, currency: Currency) -> Option<Quote> {
+    Some(Quote {
+        price: price(&prices[currency.code()])?,
+        change_24h: prices[format!("{}_24h_change", currency.code())]
+            .as_f64()
+            .filter(|change| change.is_finite()),
+    })
+}
+
+fn price(value: &Value) -> Option<f64> {
+    value
+        .as_f64()
+        .filter(|price| price.is_finite() && *price > 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prices() {
+        assert_eq!("eur".parse::<Currency>().unwrap(), Currency::Eur);
+        assert_eq!(" USD".parse::<Currency>().unwrap(), Currency::Usd);
+        assert!("gbp".parse::<Currency>().is_err());
+        assert_eq!(Currency::Eur.to_string(), "EUR");
+
+        let body: Value = serde_json::from_str(
+            r#"{"0x0707070707070707070707070707070707070707":{"usd":1.001,"usd_24h_change":-0.25},
+                "0x0808080808080808080808080808080808080808":{"usd":0},
+                "not-an-address":{"usd":2}}"#,
+        )
+        .unwrap();
+        let tokens = parse_token_quotes(&body, Currency::Usd);
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(
+            tokens[&Address::repeat_byte(7)],
+            Quote {
+                price: 1.001,
+                change_24h: Some(-0.25)
+            }
+        );
+
+        let quote = |price| Quote {
+            price,
+            change_24h: None,
+        };
+        let prices = Prices {
+            currency: Currency::Usd,
+            quotes: HashMap::from([
+                (None, quote(60_000.0)),
+                (Some(Address::repeat_byte(7)), quote(1.0)),
+            ]),
+        };
+        assert_eq!(prices.value(None, 0.5).as_deref(), Some("30000.00 USD"));
+        assert_eq!(
+            prices.value(Some(Address::repeat_byte(7)), 12.5).as_deref(),
+            Some("12.50 USD")
+        );
+        assert!(prices.value(Some(Address::repeat_byte(8)), 1.0).is_none());
+    }
+}
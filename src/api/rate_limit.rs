@@ -0,0 +1,215 @@
+//! Client-side rate limits per API provider.
+//!
+//! Each provider gets a token bucket that refills at its configured rate and
+//! holds at most one second's worth of tokens. A request waits until the
+//! bucket can pay for it, so long jobs such as history scans and bulk
+//! transfers slow down instead of being throttled halfway through. Alchemy
+//! requests cost the compute units Alchemy bills for the method; requests to
+//! the other providers cost one token each.
+
+use super::ApiProvider;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Rates per provider; zero turns a limit off
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimits {
+    /// Alchemy compute units per second (the free tier allows 330)
+    pub alchemy_compute_units_per_sec: u32,
+    /// Requests per second to the RSK RPC API
+    pub rsk_rpc_requests_per_sec: u32,
+    /// Requests per second to the Blockscout API
+    pub blockscout_requests_per_sec: u32,
+    /// Requests per second to the Rootstock explorer API
+    pub explorer_requests_per_sec: u32,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            alchemy_compute_units_per_sec: 300,
+            rsk_rpc_requests_per_sec: 20,
+            blockscout_requests_per_sec: 5,
+            explorer_requests_per_sec: 5,
+        }
+    }
+}
+
+impl RateLimits {
+    /// Tokens per second for `provider`, or `None` when it is not limited
+    pub fn rate(&self, provider: &ApiProvider) -> Option<f64> {
+        let rate = match provider {
+            ApiProvider::Alchemy => self.alchemy_compute_units_per_sec,
+            ApiProvider::RskRpc => self.rsk_rpc_requests_per_sec,
+            ApiProvider::Blockscout => self.blockscout_requests_per_sec,
+            ApiProvider::Explorer => self.explorer_requests_per_sec,
+            ApiProvider::Custom(_) => 0,
+        };
+        (rate > 0).then_some(rate as f64)
+    }
+}
+
+/// Compute units Alchemy bills for a JSON-RPC `method`
+pub fn alchemy_compute_units(method: &str) -> u32 {
+    match method {
+        "eth_chainId" | "net_version" => 0,
+        "eth_blockNumber" | "eth_feeHistory" => 10,
+        "eth_getTransactionReceipt" => 15,
+        "eth_getBlockByNumber" => 16,
+        "eth_getTransactionByHash" => 17,
+        "eth_getBalance" | "eth_gasPrice" => 19,
+        "eth_getBlockByHash" => 21,
+        "eth_getLogs" => 75,
+        "eth_estimateGas" => 87,
+        "alchemy_getAssetTransfers" => 150,
+        "eth_sendRawTransaction" => 250,
+        _ => 26,
+    }
+}
+
+/// Cost of one `method` request to `provider`, in that provider's tokens
+pub fn cost(provider: &ApiProvider, method: &str) -> u32 {
+    match provider {
+        ApiProvider::Alchemy => alchemy_compute_units(method),
+        _ => 1,
+    }
+}
+
+/// The hosted provider behind an RPC endpoint URL, if it is one with limits
+pub fn provider_for_endpoint(url: &str) -> Option<ApiProvider> {
+    let host = url::Url::parse(url).ok()?.host_str()?.to_string();
+    if host.ends_with(".alchemy.com") {
+        Some(ApiProvider::Alchemy)
+    } else if host.ends_with("rpc.rootstock.io") || host.ends_with("rpc.testnet.rootstock.io") {
+        Some(ApiProvider::RskRpc)
+    } else {
+        None
+    }
+}
+
+struct TokenBucket {
+    /// Tokens added per second
+    rate: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64, now: Instant) -> Self {
+        Self {
+            rate,
+            tokens: rate,
+            updated: now,
+        }
+    }
+
+    /// Take `cost` tokens, or tell how long until there are enough. A
+    /// request costing more than the bucket holds waits for a full bucket.
+    fn take(&mut self, cost: f64, now: Instant) -> Option<Duration> {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.updated = now;
+
+        let cost = cost.min(self.rate);
+        if self.tokens >= cost {
+            self.tokens -= cost;
+            None
+        } else {
+            Some(Duration::from_secs_f64((cost - self.tokens) / self.rate))
+        }
+    }
+}
+
+static LIMITS: OnceLock<RateLimits> = OnceLock::new();
+static BUCKETS: LazyLock<Mutex<HashMap<String, TokenBucket>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Limits of the active profile, read once per run
+fn limits() -> RateLimits {
+    *LIMITS.get_or_init(|| {
+        crate::config::ConfigManager::new()
+            .and_then(|manager| manager.load())
+            .map(|config| config.api.rate_limits)
+            .unwrap_or_default()
+    })
+}
+
+/// Wait until `provider` can take a request costing `cost` tokens
+pub async fn acquire(provider: &ApiProvider, cost: u32) {
+    let Some(rate) = limits().rate(provider) else {
+        return;
+    };
+    if cost == 0 {
+        return;
+    }
+    loop {
+        let wait = {
+            let mut buckets = BUCKETS.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            buckets
+                .entry(provider.to_string())
+                .or_insert_with(|| TokenBucket::new(rate, now))
+                .take(cost as f64, now)
+        };
+        match wait {
+            None => return,
+            Some(wait) => {
+                log::debug!(
+                    "Waiting {} ms to stay under the {} rate limit",
+                    wait.as_millis(),
+                    provider
+                );
+                tokio::time::sleep(wait).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(300.0, start);
+        assert_eq!(bucket.take(150.0, start), None);
+        assert_eq!(bucket.take(150.0, start), None);
+        // Empty: 150 units take half a second to refill
+        let wait = bucket.take(150.0, start).unwrap();
+        assert_eq!(wait, Duration::from_millis(500));
+        assert_eq!(bucket.take(150.0, start + wait), None);
+        // Never more than a second's worth, and oversized requests still pass
+        assert_eq!(bucket.take(1000.0, start + Duration::from_secs(60)), None);
+        assert!(bucket.take(1.0, start + Duration::from_secs(60)).is_some());
+
+        let limits = RateLimits::default();
+        assert_eq!(limits.rate(&ApiProvider::Alchemy), Some(300.0));
+        let off = RateLimits {
+            blockscout_requests_per_sec: 0,
+            ..limits
+        };
+        assert_eq!(off.rate(&ApiProvider::Blockscout), None);
+        assert_eq!(
+            cost(&ApiProvider::Alchemy, "alchemy_getAssetTransfers"),
+            150
+        );
+        assert_eq!(
+            cost(&ApiProvider::Blockscout, "alchemy_getAssetTransfers"),
+            1
+        );
+
+        assert_eq!(
+            provider_for_endpoint("https://rootstock-mainnet.g.alchemy.com/v2/key"),
+            Some(ApiProvider::Alchemy)
+        );
+        assert_eq!(
+            provider_for_endpoint("https://rpc.testnet.rootstock.io/key"),
+            Some(ApiProvider::RskRpc)
+        );
+        assert_eq!(provider_for_endpoint("https://public-node.rsk.co"), None);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::config::ConfigManager;
+use crate::utils::output;
+use crate::utils::table::TableBuilder;
+use alloy::primitives::Address;
+use anyhow::{Result, anyhow};
+use clap::Parser;
+use colored::Colorize;
+
+/// Normalize an address and print its lowercase, checksummed and bytes32 forms
+#[derive(Parser, Debug)]
+pub struct AddressCommand {
+    /// Address (with or without 0x) or a zero-padded bytes32 value
+    pub address: String,
+
+    /// Chain id for the EIP-1191 checksum (defaults to the configured network)
+    #[arg(long)]
+    pub chain_id: Option<u64>,
+}
+
+impl AddressCommand {
+    pub async fn execute(&self) -> Result<()> {
+        let chain_id = match self.chain_id {
+            Some(chain_id) => chain_id,
+            None => ConfigManager::new()?.load()?.default_network.chain_id(),
+        };
+        let address = parse_address(&self.address, chain_id)?;
+
+        let mut table = TableBuilder::new();
+        table.add_header(&["Format", "Value"]);
+        table.add_row(&["Lowercase", &format!("{:#x}", address)]);
+        table.add_row(&[
+            &format!("Checksum (chain {})", chain_id),
+            &address.to_checksum(Some(chain_id)),
+        ]);
+        table.add_row(&["Checksum (EIP-55)", &address.to_checksum(None)]);
+        table.add_row(&["Bytes32", &to_bytes32(&address)]);
+        table.print();
+        output::emit(&serde_json::json!({
+            "lowercase": format!("{:#x}", address),
+            "checksum": address.to_checksum(Some(chain_id)),
+            "chain_id": chain_id,
+            "eip55": address.to_checksum(None),
+            "bytes32": to_bytes32(&address),
+        }))
+    }
+}
+
+/// Parse an address, accepting a missing 0x prefix and zero-padded bytes32
+/// values. Mixed-case input must carry a valid EIP-1191 checksum for
+/// `chain_id` or a valid EIP-55 checksum.
+pub fn parse_address(input: &str, chain_id: u64) -> Result<Address> {
+    let trimmed = input.trim();
+    let hex = trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+        .unwrap_or(trimmed);
+
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(anyhow!("'{}' contains non-hex characters", input));
+    }
+
+    let hex = match hex.len() {
+        40 => hex,
+        64 => {
+            let (padding, address) = hex.split_at(24);
+            if padding.chars().any(|c| c != '0') {
+                return Err(anyhow!(
+                    "'{}' is a bytes32 value whose first 12 bytes are not zero",
+                    input
+                ));
+            }
+            address
+        }
+        len => {
+            return Err(anyhow!(
+                "'{}' has {} hex characters; expected 40 (address) or 64 (bytes32)",
+                input,
+                len
+            ));
+        }
+    };
+
+    let is_mixed_case =
+        hex.chars().any(|c| c.is_ascii_uppercase()) && hex.chars().any(|c| c.is_ascii_lowercase());
+    let prefixed = format!("0x{}", hex);
+    if is_mixed_case {
+        return Address::parse_checksummed(&prefixed, Some(chain_id))
+            .or_else(|_| Address::parse_checksummed(&prefixed, None))
+            .map_err(|_| {
+                anyhow!(
+                    "'{}' has an invalid checksum for chain {} (and for EIP-55)",
+                    input,
+                    chain_id
+                )
+            });
+    }
+
+    prefixed
+        .parse()
+        .map_err(|e| anyhow!("Invalid address '{}': {}", input, e))
+}
+
+/// Parse an address entered as a recipient. Unlike `parse_address`, a
+/// mixed-case address whose checksum does not match is accepted, with the
+/// warning from `checksum_warning` printed.
+pub fn parse_recipient(input: &str, chain_id: u64) -> Result<Address> {
+    let warning = checksum_warning(input, chain_id);
+    let address = match &warning {
+        Some(_) => parse_address(&input.to_lowercase(), chain_id)?,
+        None => parse_address(input, chain_id)?,
+    };
+    if let Some(warning) = warning {
+        println!("{}: {}", "Warning".yellow().bold(), warning);
+    }
+    Ok(address)
+}
+
+/// Why a mixed-case address does not carry the EIP-1191 checksum Rootstock
+/// uses for `chain_id`: it has the Ethereum (EIP-55) checksum instead, or no
+/// valid checksum at all. `None` for correctly checksummed addresses and
+/// for all-lowercase or all-uppercase ones, which carry no checksum.
+pub fn checksum_warning(input: &str, chain_id: u64) -> Option<String> {
+    let trimmed = input.trim();
+    let hex = trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+        .unwrap_or(trimmed);
+    let is_mixed_case =
+        hex.chars().any(|c| c.is_ascii_uppercase()) && hex.chars().any(|c| c.is_ascii_lowercase());
+    if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) || !is_mixed_case {
+        return None;
+    }
+    let prefixed = format!("0x{}", hex);
+    if Address::parse_checksummed(&prefixed, Some(chain_id)).is_ok() {
+        None
+    } else if Address::parse_checksummed(&prefixed, None).is_ok() {
+        Some(format!(
+            "{} has an Ethereum (EIP-55) checksum rather than the Rootstock checksum for chain {}; make sure it was meant for Rootstock",
+            trimmed, chain_id
+        ))
+    } else {
+        Some(format!(
+            "{} fails the checksum for chain {}; it may contain a typo",
+            trimmed, chain_id
+        ))
+    }
+}
+
+/// The address left-padded with zeros to 32 bytes, as used in ABI encoding
+/// and event topics
+pub fn to_bytes32(address: &Address) -> String {
+    format!("0x{:0>64}", hex::encode(address.as_slice()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_address_formats() {
+        let address = Address::repeat_byte(0xab);
+        let lower = "abababababababababababababababababababab";
+
+        assert_eq!(parse_address(lower, 30).unwrap(), address);
+        assert_eq!(parse_address(&format!("0x{}", lower), 30).unwrap(), address);
+        assert_eq!(parse_address(&to_bytes32(&address), 30).unwrap(), address);
+        assert_eq!(
+            parse_address(&address.to_checksum(Some(30)), 30).unwrap(),
+            address
+        );
+        assert_eq!(
+            parse_address(&address.to_checksum(None), 30).unwrap(),
+            address
+        );
+
+        assert!(parse_address("0x1234", 30).is_err());
+        assert!(parse_address(&format!("0x{}", "1".repeat(64)), 30).is_err());
+    }
+
+    #[test]
+    fn test_bad_checksum_rejected() {
+        let checksummed = Address::repeat_byte(0xab).to_checksum(Some(30));
+        let flipped: String = checksummed
+            .chars()
+            .map(|c| {
+                if c.is_ascii_uppercase() {
+                    c.to_ascii_lowercase()
+                } else if c.is_ascii_lowercase() && c != 'x' {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .collect();
+        assert!(parse_address(&flipped, 30).is_err());
+
+        // Entered as a recipient, the same address is only warned about
+        assert!(checksum_warning(&flipped, 30).unwrap().contains("typo"));
+        assert_eq!(
+            parse_recipient(&flipped, 30).unwrap(),
+            Address::repeat_byte(0xab)
+        );
+    }
+
+    #[test]
+    fn test_checksum_warning() {
+        let address = Address::repeat_byte(0xab);
+        assert_eq!(checksum_warning(&address.to_checksum(Some(30)), 30), None);
+        assert_eq!(checksum_warning(&address.to_checksum(Some(31)), 31), None);
+        assert_eq!(checksum_warning(&format!("{:#x}", address), 30), None);
+        assert!(
+            checksum_warning(&address.to_checksum(None), 30)
+                .unwrap()
+                .contains("EIP-55")
+        );
+        // A testnet checksum does not match on mainnet
+        assert!(checksum_warning(&address.to_checksum(Some(31)), 30).is_some());
+    }
+}
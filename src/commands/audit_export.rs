@@ -0,0 +1,73 @@
+use crate::utils::audit;
+use crate::utils::audit_package::{self, AuditPackage};
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use clap::Parser;
+use colored::Colorize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Export an encrypted package of transactions signed in a period
+#[derive(Parser, Debug)]
+pub struct AuditExportCommand {
+    /// First day of the period (YYYY-MM-DD, UTC)
+    #[arg(long)]
+    pub from: NaiveDate,
+    /// Last day of the period, included (YYYY-MM-DD, UTC)
+    #[arg(long)]
+    pub to: NaiveDate,
+    /// Auditor's secp256k1 public key, hex encoded
+    #[arg(long)]
+    pub recipient: String,
+    /// Where to write the package (default: audit-<from>-<to>.json)
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+}
+
+impl AuditExportCommand {
+    pub async fn execute(&self) -> Result<()> {
+        let output = self
+            .output
+            .clone()
+            .unwrap_or_else(|| default_output(self.from, self.to));
+        export(self.from, self.to, &self.recipient, &output)
+    }
+}
+
+pub fn default_output(from: NaiveDate, to: NaiveDate) -> PathBuf {
+    PathBuf::from(format!("audit-{}-{}.json", from, to))
+}
+
+/// Build the package for `from`..=`to`, encrypt it to `recipient` and write
+/// it to `output`
+pub fn export(from: NaiveDate, to: NaiveDate, recipient: &str, output: &Path) -> Result<()> {
+    let recipient = audit_package::parse_public_key(recipient)?;
+    let package = AuditPackage::build(from, to)?;
+    let encrypted = package.encrypt(&recipient)?;
+    fs::write(output, serde_json::to_string_pretty(&encrypted)?)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+    audit::record_or_warn(
+        "audit_package_exported",
+        None,
+        format!(
+            "{} to {}: {} transactions for {}",
+            from,
+            to,
+            package.transactions.len(),
+            encrypted.recipient
+        ),
+    );
+
+    println!(
+        "{}: Wrote {} signed transactions and {} audit entries from {} to {}",
+        "Success".green().bold(),
+        package.transactions.len(),
+        package.audit_log.len(),
+        from,
+        to
+    );
+    println!("  Package: {}", output.display());
+    println!("  Encrypted to: {}", encrypted.recipient);
+    println!("  Content SHA-256: {}", encrypted.sha256);
+    Ok(())
+}
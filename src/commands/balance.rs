@@ -1,12 +1,18 @@
+use crate::api::prices::Prices;
 use crate::config::ConfigManager;
+use crate::types::network::NetworkService;
 use crate::types::wallet::WalletData;
-use crate::utils::constants;
+use crate::utils::blockscout::{BlockscoutClient, TokenBalance};
+use crate::utils::eth::EthClient;
 use crate::utils::helper::Helper;
+use crate::utils::output;
+use crate::utils::staking;
 use crate::utils::table::TableBuilder;
 use anyhow::{Result, anyhow};
+use chrono::{DateTime, Days, NaiveDate, Utc};
 use clap::Parser;
-use alloy::primitives::Address;
-use std::fs;
+use alloy::primitives::{Address, U256, utils::format_units};
+use alloy::providers::Provider;
 use std::str::FromStr;
 
 #[derive(Parser, Debug)]
@@ -18,41 +24,82 @@ pub struct BalanceCommand {
     /// Optional Token to get Balance for
     #[arg(long)]
     pub token: Option<String>,
+
+    /// Account of the current wallet to check (see `wallet add-account`)
+    #[arg(long)]
+    pub account: Option<u32>,
+
+    /// List every token the address holds, from the Blockscout API
+    #[arg(long, conflicts_with = "token")]
+    pub all: bool,
+
+    /// Report the balance at this past block (needs an archive node)
+    #[arg(long, conflicts_with_all = ["all", "at_date"])]
+    pub at_block: Option<u64>,
+
+    /// Report the balance at the end of this day (YYYY-MM-DD, UTC)
+    #[arg(long, conflicts_with = "all")]
+    pub at_date: Option<NaiveDate>,
 }
 
 impl BalanceCommand {
     pub async fn execute(&self) -> Result<()> {
         // Load config to get the current network
         let config = ConfigManager::new()?.load()?;
-        let network = config.default_network.to_string().to_lowercase();
+        let network = config.default_network.chain_name();
 
-        let (_config, eth_client) = Helper::init_eth_client(&network).await?;
+        let (_config, eth_client) = Helper::init_eth_client(network).await?;
 
         // Get address - use default wallet if none provided
         let address = if let Some(addr) = &self.address {
             Address::from_str(addr).map_err(|_| anyhow!("Invalid address format: {}", addr))?
         } else {
             // Load wallet data to get default wallet
-            let wallet_file = constants::wallet_file_path();
-            if !wallet_file.exists() {
+            let wallet_data = WalletData::load()?;
+            if wallet_data.wallets.is_empty() {
                 return Err(anyhow!(
                     "No wallets found. Please create or import a wallet first."
                 ));
             }
-
-            let data = fs::read_to_string(&wallet_file)?;
-            let wallet_data = serde_json::from_str::<WalletData>(&data)?;
             let default_wallet = wallet_data.get_current_wallet()
                 .ok_or_else(|| anyhow!("No default wallet selected. Please use 'wallet switch' to select a default wallet."))?;
 
-            default_wallet.address
+            let account = self.account.unwrap_or(0);
+            default_wallet
+                .account_address(account)
+                .ok_or_else(|| anyhow!("'{}' has no account #{}", default_wallet.name, account))?
         };
 
-        let (balance, token_name) = if let Some(token) = &self.token {
+        if self.at_block.is_some() || self.at_date.is_some() {
+            return self
+                .print_snapshot(&eth_client, &address, &config.default_network.to_string())
+                .await;
+        }
+
+        if self.all {
+            config
+                .default_network
+                .require(NetworkService::Blockscout, "Listing every token")?;
+            let rbtc = eth_client.get_balance(&address, &None).await?;
+            let tokens = BlockscoutClient::new(config.default_network.is_testnet())?
+                .get_token_balances(&address)
+                .await?;
+            let token_addresses: Vec<Address> = tokens.iter().map(|t| t.address).collect();
+            let prices = Prices::fetch(&config, &token_addresses).await;
+            return print_all_balances(
+                &address,
+                &config.default_network.to_string(),
+                rbtc,
+                &tokens,
+                prices.as_ref(),
+            );
+        }
+
+        let (balance, token_name, token_address) = if let Some(token) = &self.token {
             // Check if it's the RBTC zero address
             if token == "0x0000000000000000000000000000000000000000" {
                 let balance = eth_client.get_balance(&address, &None).await?;
-                (balance, "RBTC".to_string())
+                (balance, config.default_network.native_symbol().to_string(), None)
             } else {
                 let token_address = Address::from_str(token)
                     .map_err(|_| anyhow!("Invalid token address format: {}", token))?;
@@ -66,30 +113,273 @@ impl BalanceCommand {
                     Err(_) => format!("Token (0x{})", &token[2..10]),
                 };
 
-                (balance, token_name)
+                (balance, token_name, Some(token_address))
             }
         } else {
             // Native RBTC balance
             let balance = eth_client.get_balance(&address, &None).await?;
-            (balance, "RBTC".to_string())
+            (balance, config.default_network.native_symbol().to_string(), None)
         };
 
+        // Funds held in staking contracts still belong to the address, so
+        // show them next to the liquid balance
+        let locked_balances = staking::locked_balances(&eth_client, network, &address).await;
+        let locked = token_address
+            .map(|token| staking::locked_total(&locked_balances, &token))
+            .unwrap_or(U256::ZERO);
+
         // Format the balance with appropriate decimals
         // All tokens including RBTC use 18 decimals
         let decimals = 18;
         let balance_str = alloy::primitives::utils::format_units(balance, decimals)
             .map_err(|e| anyhow!("Failed to format balance: {}", e))?;
 
+        let prices = Prices::fetch(&config, &token_address.into_iter().collect::<Vec<_>>()).await;
+        let fiat = prices.as_ref().map(|prices| {
+            let total = format_units(balance + locked, decimals)
+                .ok()
+                .and_then(|total| total.parse::<f64>().ok())
+                .unwrap_or_default();
+            (
+                format!("Value ({})", prices.currency),
+                prices
+                    .value(token_address, total)
+                    .unwrap_or_else(|| "-".to_string()),
+            )
+        });
+
+        output::emit(&serde_json::json!({
+            "address": format!("{:#x}", address),
+            "network": config.default_network.to_string(),
+            "token": token_name,
+            "token_address": token_address.map(|token| format!("{:#x}", token)),
+            "balance": balance_str,
+            "locked": format_units(locked, decimals)?,
+            "total": format_units(balance + locked, decimals)?,
+            "value": fiat.as_ref().map(|(_, value)| value),
+            "currency": prices.as_ref().map(|prices| prices.currency.to_string()),
+            "staking": locked_balances.iter().map(|position| serde_json::json!({
+                "protocol": position.contract.protocol,
+                "contract": position.contract.name,
+                "asset": position.contract.asset,
+                "locked": position.formatted(),
+            })).collect::<Vec<_>>(),
+        }))?;
+
         let mut table = TableBuilder::new();
-        table.add_header(&["Address", "Network", "Token", "Balance"]);
+        if locked.is_zero() {
+            let mut header = vec!["Address", "Network", "Token", "Balance"];
+            let mut row = vec![
+                Helper::format_address(&address),
+                config.default_network.to_string(),
+                token_name,
+                balance_str,
+            ];
+            if let Some((column, value)) = &fiat {
+                header.push(column);
+                row.push(value.clone());
+            }
+            table.add_header(&header);
+            table.add_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
+        } else {
+            let locked_str = alloy::primitives::utils::format_units(locked, decimals)
+                .map_err(|e| anyhow!("Failed to format balance: {}", e))?;
+            let total_str = alloy::primitives::utils::format_units(balance + locked, decimals)
+                .map_err(|e| anyhow!("Failed to format balance: {}", e))?;
+            let mut header = vec!["Address", "Network", "Token", "Liquid", "Locked", "Total"];
+            let mut row = vec![
+                Helper::format_address(&address),
+                config.default_network.to_string(),
+                token_name,
+                balance_str,
+                locked_str,
+                total_str,
+            ];
+            if let Some((column, value)) = &fiat {
+                header.push(column);
+                row.push(value.clone());
+            }
+            table.add_header(&header);
+            table.add_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
+        }
+        table.print();
+
+        if !locked_balances.is_empty() {
+            println!("\nLocked in staking contracts:");
+            let mut locked_table = TableBuilder::new();
+            locked_table.add_header(&["Protocol", "Contract", "Asset", "Locked"]);
+            for position in &locked_balances {
+                locked_table.add_row(&[
+                    &position.contract.protocol,
+                    &position.contract.name,
+                    &position.contract.asset,
+                    &position.formatted(),
+                ]);
+            }
+            locked_table.print();
+        }
+        Ok(())
+    }
+
+    /// Balance at the block given by `--at-block`, or the last block of the
+    /// day given by `--at-date`
+    async fn print_snapshot(
+        &self,
+        eth_client: &EthClient,
+        address: &Address,
+        network: &str,
+    ) -> Result<()> {
+        let head = eth_client.provider().get_block_number().await?;
+        let block = match (self.at_block, self.at_date) {
+            (Some(block), _) if block > head => {
+                return Err(anyhow!(
+                    "Block {} has not been mined yet (latest is {})",
+                    block,
+                    head
+                ));
+            }
+            (Some(block), _) => block,
+            (None, Some(date)) => {
+                let end_of_day = date
+                    .checked_add_days(Days::new(1))
+                    .and_then(|next| next.and_hms_opt(0, 0, 0))
+                    .ok_or_else(|| anyhow!("Invalid date: {}", date))?
+                    .and_utc();
+                if end_of_day > Utc::now() {
+                    return Err(anyhow!("{} has not ended yet", date));
+                }
+                eth_client.block_before(end_of_day).await?
+            }
+            (None, None) => head,
+        };
+        let timestamp = eth_client.get_block_timestamp(block).await?;
+        let time = DateTime::from_timestamp(timestamp as i64, 0)
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .unwrap_or_else(|| "-".to_string());
+
+        let token_address = match self.token.as_deref() {
+            None | Some("0x0000000000000000000000000000000000000000") => None,
+            Some(token) => Some(
+                Address::from_str(token)
+                    .map_err(|_| anyhow!("Invalid token address format: {}", token))?,
+            ),
+        };
+        let token_name = match token_address {
+            None => "RBTC".to_string(),
+            Some(token) => eth_client
+                .get_token_info(token)
+                .await
+                .map(|(_, symbol)| symbol)
+                .unwrap_or_else(|_| format!("Token (0x{:x})", token)),
+        };
+        let balance = eth_client
+            .get_balance_at(address, &token_address, block)
+            .await?;
+
+        let mut table = TableBuilder::new();
+        table.add_header(&["Address", "Network", "Block", "Time", "Token", "Balance"]);
         table.add_row(&[
-            &Helper::format_address(&address),
-            &config.default_network.to_string(),
+            &Helper::format_address(address),
+            network,
+            &block.to_string(),
+            &time,
             &token_name,
-            &balance_str,
+            &format_units(balance, 18)?,
         ]);
-
         table.print();
-        Ok(())
+        output::emit(&serde_json::json!({
+            "address": format!("{:#x}", address),
+            "network": network,
+            "block": block,
+            "timestamp": timestamp,
+            "token": token_name,
+            "token_address": token_address.map(|token| format!("{:#x}", token)),
+            "balance": format_units(balance, 18)?,
+        }))
+    }
+}
+
+/// RBTC and every token balance of an address, in one table, with fiat
+/// values when prices are known
+fn print_all_balances(
+    address: &Address,
+    network: &str,
+    rbtc: U256,
+    tokens: &[TokenBalance],
+    prices: Option<&Prices>,
+) -> Result<()> {
+    let fiat = |token: Option<Address>, amount: &str| {
+        prices.map(|prices| {
+            amount
+                .parse::<f64>()
+                .ok()
+                .and_then(|amount| prices.value(token, amount))
+                .unwrap_or_else(|| "-".to_string())
+        })
+    };
+
+    let mut table = TableBuilder::new();
+    let value_column = prices.map(|prices| format!("Value ({})", prices.currency));
+    let mut header = vec!["Token", "Contract", "Balance"];
+    header.extend(value_column.as_deref());
+    table.add_header(&header);
+
+    let rbtc = format_units(rbtc, 18)?;
+    let mut row = vec!["RBTC".to_string(), "-".to_string(), rbtc.clone()];
+    row.extend(fiat(None, &rbtc));
+    table.add_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
+    let mut json = vec![serde_json::json!({
+        "token": "RBTC",
+        "token_address": null,
+        "balance": rbtc,
+        "value": fiat(None, &rbtc),
+    })];
+    for token in tokens {
+        let balance = format_units(token.value, token.decimals)?;
+        let mut row = vec![
+            token.symbol.clone(),
+            format!("0x{:x}", token.address),
+            balance.clone(),
+        ];
+        row.extend(fiat(Some(token.address), &balance));
+        table.add_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
+        json.push(serde_json::json!({
+            "token": token.symbol,
+            "token_address": format!("{:#x}", token.address),
+            "balance": balance,
+            "value": fiat(Some(token.address), &balance),
+        }));
+    }
+    println!("{} on {}", Helper::format_address(address), network);
+    table.print();
+    output::emit(&serde_json::json!({
+        "address": format!("{:#x}", address),
+        "network": network,
+        "currency": prices.map(|prices| prices.currency.to_string()),
+        "balances": json,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_arguments() {
+        let cmd = BalanceCommand::try_parse_from(["balance", "--at-date", "2024-01-31"]).unwrap();
+        assert_eq!(cmd.at_date, NaiveDate::from_ymd_opt(2024, 1, 31));
+        assert_eq!(cmd.at_block, None);
+
+        let cmd = BalanceCommand::try_parse_from(["balance", "--at-block", "120"]).unwrap();
+        assert_eq!(cmd.at_block, Some(120));
+
+        for args in [
+            &["balance", "--at-block", "1", "--at-date", "2024-01-31"][..],
+            &["balance", "--at-block", "1", "--all"],
+            &["balance", "--at-date", "2024-01-31", "--all"],
+            &["balance", "--at-date", "31/01/2024"],
+        ] {
+            assert!(BalanceCommand::try_parse_from(args).is_err(), "{:?}", args);
+        }
     }
 }
@@ -0,0 +1,254 @@
+//! Batches of commands run one after another in one process, e.g. for
+//! repetitive operational workflows.
+//!
+//! A batch file holds one command line per line, written as on the shell
+//! without the program name, with `#` comments. A YAML playbook holds the
+//! same command lines as a list, optionally under `commands:` next to a
+//! `continue_on_error:` setting. Every line is parsed before the first one
+//! runs, so a typo on the last line does not leave the batch half done.
+//! The wallet is unlocked at most once: its session lasts for the whole
+//! batch.
+
+use crate::commands::root::{Cli, Commands};
+use crate::security::session;
+use crate::utils::output;
+use anyhow::{Context, Result, anyhow};
+use clap::Parser;
+use colored::Colorize;
+use std::io::Read;
+use std::path::Path;
+
+/// One command of a batch
+#[derive(Debug)]
+pub struct BatchCommand {
+    /// Line number in the batch file, for messages
+    pub line: usize,
+    pub text: String,
+    pub command: Commands,
+}
+
+#[derive(Debug, Default)]
+pub struct Batch {
+    pub commands: Vec<BatchCommand>,
+    /// Keep going after a command fails instead of stopping
+    pub continue_on_error: bool,
+}
+
+/// Read the batch in `path`, or from stdin when it is `-`
+pub fn load(path: &Path) -> Result<Batch> {
+    let mut source = String::new();
+    if path == Path::new("-") {
+        std::io::stdin()
+            .read_to_string(&mut source)
+            .context("Failed to read the batch from stdin")?;
+    } else {
+        source = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+    }
+    let yaml = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"))
+        || looks_like_playbook(&source);
+    parse(&source, yaml)
+}
+
+/// Parse a batch, as a YAML playbook or as one command per line
+pub fn parse(source: &str, yaml: bool) -> Result<Batch> {
+    let (lines, continue_on_error) = if yaml {
+        playbook_lines(source)?
+    } else {
+        (plain_lines(source), false)
+    };
+    let mut commands = Vec::new();
+    for (line, text) in lines {
+        let command = parse_command(&text).map_err(|e| anyhow!("Line {}: {}", line, e))?;
+        commands.push(BatchCommand {
+            line,
+            text,
+            command,
+        });
+    }
+    Ok(Batch {
+        commands,
+        continue_on_error,
+    })
+}
+
+/// Whether the first line that is not a comment starts a YAML document
+fn looks_like_playbook(source: &str) -> bool {
+    source
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .is_some_and(|line| {
+            line == "---" || line.starts_with("- ") || line.starts_with("commands:")
+        })
+}
+
+/// Non-empty lines that are not comments, with their line numbers
+fn plain_lines(source: &str) -> Vec<(usize, String)> {
+    source
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line, text)| (line, text.to_string()))
+        .collect()
+}
+
+/// The command lines of a playbook and its `continue_on_error` setting.
+/// Playbooks are the YAML subset needed for a list of command lines: a
+/// sequence of plain or quoted strings, at the top level or under
+/// `commands:`, and `continue_on_error: true|false`.
+fn playbook_lines(source: &str) -> Result<(Vec<(usize, String)>, bool)> {
+    let mut lines = Vec::new();
+    let mut continue_on_error = false;
+    for (i, raw) in source.lines().enumerate() {
+        let line = i + 1;
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed == "---" {
+            continue;
+        }
+        if let Some(item) = trimmed.strip_prefix('-') {
+            if !item.is_empty() && !item.starts_with(' ') {
+                return Err(anyhow!("Line {}: expected `- <command>`", line));
+            }
+            lines.push((line, yaml_scalar(item.trim(), line)?));
+            continue;
+        }
+        match trimmed.split_once(':') {
+            Some(("commands", value)) if value.trim().is_empty() => {}
+            Some(("continue_on_error", value)) => {
+                continue_on_error = match yaml_scalar(value.trim(), line)?.as_str() {
+                    "true" => true,
+                    "false" => false,
+                    other => {
+                        return Err(anyhow!(
+                            "Line {}: continue_on_error is true or false, not '{}'",
+                            line,
+                            other
+                        ));
+                    }
+                }
+            }
+            _ => {
+                return Err(anyhow!(
+                    "Line {}: expected `commands:`, `continue_on_error:` or `- <command>`",
+                    line
+                ));
+            }
+        }
+    }
+    Ok((lines, continue_on_error))
+}
+
+/// A plain, single-quoted or double-quoted YAML string
+fn yaml_scalar(value: &str, line: usize) -> Result<String> {
+    if let Some(quoted) = value.strip_prefix('\'') {
+        let inner = quoted
+            .strip_suffix('\'')
+            .ok_or_else(|| anyhow!("Line {}: unterminated quote", line))?;
+        return Ok(inner.replace("''", "'"));
+    }
+    if let Some(quoted) = value.strip_prefix('"') {
+        let inner = quoted
+            .strip_suffix('"')
+            .ok_or_else(|| anyhow!("Line {}: unterminated quote", line))?;
+        return Ok(inner.replace("\\\"", "\"").replace("\\\\", "\\"));
+    }
+    // A comment after a plain value starts with " #"
+    let value = value.split_once(" #").map_or(value, |(value, _)| value);
+    Ok(value.trim_end().to_string())
+}
+
+/// Parse one command line as the arguments of the program
+pub fn parse_command(text: &str) -> Result<Commands> {
+    let mut words = shlex::split(text).ok_or_else(|| anyhow!("Unbalanced quotes"))?;
+    if words.first().is_some_and(|word| word == "rootstock-wallet") {
+        words.remove(0);
+    }
+    let cli = Cli::try_parse_from(std::iter::once("rootstock-wallet".to_string()).chain(words))
+        .map_err(|e| anyhow!("{}", e.to_string().trim_end()))?;
+    if cli.json || cli.redact || cli.profile.is_some() || cli.batch.is_some() {
+        return Err(anyhow!(
+            "--json, --redact, --profile and --batch apply to the whole batch; give them with --batch"
+        ));
+    }
+    cli.command.ok_or_else(|| anyhow!("No command given"))
+}
+
+/// Run every command of `batch` in order, stopping at the first failure
+/// unless the batch or `continue_on_error` says to keep going
+pub async fn run(batch: &Batch, continue_on_error: bool) -> Result<()> {
+    let continue_on_error = continue_on_error || batch.continue_on_error;
+    session::keep_until_exit();
+
+    let total = batch.commands.len();
+    let mut failed = 0;
+    let mut ran = 0;
+    for (i, step) in batch.commands.iter().enumerate() {
+        println!(
+            "\n{} {}",
+            format!("[{}/{}]", i + 1, total).cyan().bold(),
+            step.text
+        );
+        ran += 1;
+        if let Err(e) = step.command.execute().await {
+            failed += 1;
+            eprintln!("{}: Line {}: {}", "Error".red().bold(), step.line, e);
+            output::emit(&serde_json::json!({
+                "line": step.line,
+                "command": step.text,
+                "error": e.to_string(),
+            }))?;
+            if !continue_on_error {
+                break;
+            }
+        }
+    }
+
+    println!("\nRan {} of {} commands, {} failed", ran, total, failed);
+    if failed > 0 {
+        return Err(anyhow!("{} of {} commands failed", failed, total));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_batch() {
+        let batch = parse(
+            "# morning checks\nbalance --all\n\n  history --limit 5 --incoming\n",
+            false,
+        )
+        .unwrap();
+        assert_eq!(batch.commands.len(), 2);
+        assert_eq!(batch.commands[1].line, 4);
+        assert!(matches!(batch.commands[0].command, Commands::Balance(_)));
+        assert!(!batch.continue_on_error);
+
+        let playbook = "---\ncontinue_on_error: true\ncommands:\n  - balance --all  # RBTC and tokens\n  - 'wallet list'\n  - \"contacts add \\\"Bob Smith\\\" 0x0000000000000000000000000000000000000001 --notes 'from HR'\"\n";
+        assert!(looks_like_playbook(playbook));
+        let batch = parse(playbook, true).unwrap();
+        assert!(batch.continue_on_error);
+        assert_eq!(batch.commands.len(), 3);
+        assert_eq!(batch.commands[0].text, "balance --all");
+        assert_eq!(batch.commands[1].line, 5);
+        assert_eq!(
+            batch.commands[2].text,
+            "contacts add \"Bob Smith\" 0x0000000000000000000000000000000000000001 --notes 'from HR'"
+        );
+        assert!(matches!(batch.commands[2].command, Commands::Contacts(_)));
+
+        // Nothing runs when any line is wrong
+        let err = parse("balance\nbalanse --all\n", false).unwrap_err();
+        assert!(err.to_string().starts_with("Line 2:"));
+        assert!(parse("balance --address 'unterminated", false).is_err());
+        assert!(parse("--json balance", false).is_err());
+        assert!(parse("commands:\n  - balance\nretries: 3\n", true).is_err());
+        assert!(!looks_like_playbook("balance --all\n"));
+    }
+}
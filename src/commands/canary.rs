@@ -0,0 +1,270 @@
+use crate::commands::transfer::{TransferCommand, TransferResult};
+use crate::types::wallet::WalletData;
+use crate::utils::canary::{CanaryPolicy, CanaryStatus, CanaryTransfer, CanaryTransfers};
+use crate::utils::table::TableBuilder;
+use alloy::primitives::{U256, utils::format_units};
+use anyhow::{Result, anyhow};
+use chrono::Local;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+
+/// Send large transfers in two steps, starting with a tiny canary
+#[derive(Parser, Debug)]
+pub struct CanaryCommand {
+    #[command(subcommand)]
+    pub action: CanaryAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CanaryAction {
+    /// Send the canary of a transfer from the current wallet
+    Send {
+        /// Address to send to
+        #[arg(long)]
+        address: String,
+        /// Full amount of the transfer, canary included
+        #[arg(long)]
+        value: f64,
+        /// Token address (for ERC20 transfers)
+        #[arg(long)]
+        token: Option<String>,
+        /// Account of the current wallet to send from
+        #[arg(long)]
+        account: Option<u32>,
+        /// Memo or destination tag, sent with the canary and the rest
+        #[arg(long)]
+        memo: Option<String>,
+        /// Cost center both parts of the transfer are allocated to
+        #[arg(long)]
+        cost_center: Option<String>,
+    },
+    /// List canary transfers
+    List,
+    /// The canary arrived: send the rest of the transfer
+    Confirm { id: u64 },
+    /// Drop a transfer after its canary, without sending the rest
+    Cancel { id: u64 },
+}
+
+impl CanaryCommand {
+    pub async fn execute(&self) -> Result<()> {
+        match &self.action {
+            CanaryAction::Send {
+                address,
+                value,
+                token,
+                account,
+                memo,
+                cost_center,
+            } => {
+                let transfer = TransferCommand {
+                    address: address.clone(),
+                    value: *value,
+                    token: token.clone(),
+                    gas_limit: None,
+                    gas_price: None,
+                    nonce: None,
+                    account: *account,
+                    memo: memo.clone(),
+                    cost_center: cost_center.clone(),
+                    units: None,
+                };
+                let total = parse_value(*value)?;
+                let canary = CanaryPolicy::current()
+                    .canary_value(total)
+                    .ok_or_else(|| anyhow!("The transfer is not larger than the canary amount"))?;
+                let id = send_canary(&transfer, canary).await?;
+                println!(
+                    "Once the recipient confirms it arrived, run `rootstock-wallet canary confirm {}`.",
+                    id
+                );
+                Ok(())
+            }
+            CanaryAction::List => list(),
+            CanaryAction::Confirm { id } => {
+                send_remainder(*id).await?;
+                Ok(())
+            }
+            CanaryAction::Cancel { id } => cancel(*id),
+        }
+    }
+}
+
+fn parse_value(value: f64) -> Result<U256> {
+    let value = alloy::primitives::utils::parse_units(&value.to_string(), 18)
+        .map_err(|e| anyhow!("Invalid amount: {}", e))?;
+    Ok(value.into())
+}
+
+fn to_units(value: U256) -> Result<f64> {
+    format_units(value, 18)?
+        .parse()
+        .map_err(|e| anyhow!("Invalid amount: {}", e))
+}
+
+fn current_wallet_key() -> Result<String> {
+    let wallet_data = WalletData::load()?;
+    let wallet = wallet_data
+        .get_current_wallet()
+        .ok_or_else(|| anyhow!("No default wallet selected. Use `wallet switch` first."))?;
+    Ok(format!("0x{:x}", wallet.address))
+}
+
+/// Send the canary of `transfer` and record the rest as waiting for
+/// confirmation. Returns the id of the canary transfer.
+pub async fn send_canary(transfer: &TransferCommand, canary: U256) -> Result<u64> {
+    let total = parse_value(transfer.value)?;
+    if canary >= total {
+        return Err(anyhow!("The canary must be smaller than the transfer"));
+    }
+    let wallet = current_wallet_key()?;
+
+    println!(
+        "\n{}: Sending a canary of {} first",
+        "Canary".cyan().bold(),
+        format_units(canary, 18)?
+    );
+    let result = TransferCommand {
+        address: transfer.address.clone(),
+        value: to_units(canary)?,
+        token: transfer.token.clone(),
+        gas_limit: transfer.gas_limit,
+        gas_price: transfer.gas_price.clone(),
+        nonce: transfer.nonce,
+        account: transfer.account,
+        memo: transfer.memo.clone(),
+        cost_center: transfer.cost_center.clone(),
+        units: Some(canary),
+    }
+    .execute()
+    .await?;
+
+    let mut canaries = CanaryTransfers::load()?;
+    let id = canaries.add(CanaryTransfer {
+        id: 0,
+        wallet,
+        account: transfer.account.unwrap_or(0),
+        to: result.to,
+        token: result.token_address,
+        symbol: result.token_symbol.unwrap_or_else(|| "RBTC".to_string()),
+        total,
+        canary: result.value,
+        canary_tx: result.tx_hash,
+        remainder_tx: None,
+        memo: transfer.memo.clone(),
+        cost_center: transfer.cost_center.clone(),
+        status: CanaryStatus::AwaitingReceipt,
+        created_at: Local::now(),
+    });
+    canaries.save()?;
+
+    let transfer = canaries.awaiting_mut(id)?;
+    println!(
+        "{}: Canary #{} sent (0x{:x}); {} is waiting until its receipt is confirmed",
+        "Success".green().bold(),
+        id,
+        transfer.canary_tx,
+        transfer.format(transfer.remainder())
+    );
+    Ok(id)
+}
+
+/// Send the rest of a transfer whose canary was confirmed
+pub async fn send_remainder(id: u64) -> Result<TransferResult> {
+    let mut canaries = CanaryTransfers::load()?;
+    let transfer = canaries.awaiting_mut(id)?;
+    if transfer.wallet != current_wallet_key()? {
+        return Err(anyhow!(
+            "Canary #{} was sent from {}; switch to that wallet first",
+            id,
+            transfer.wallet
+        ));
+    }
+
+    println!(
+        "\n{}: Sending the remaining {} to 0x{:x}",
+        "Canary".cyan().bold(),
+        transfer.format(transfer.remainder()),
+        transfer.to
+    );
+    let result = TransferCommand {
+        address: format!("0x{:x}", transfer.to),
+        value: to_units(transfer.remainder())?,
+        token: transfer.token.map(|t| format!("0x{:x}", t)),
+        gas_limit: None,
+        gas_price: None,
+        nonce: None,
+        account: Some(transfer.account),
+        memo: transfer.memo.clone(),
+        cost_center: transfer.cost_center.clone(),
+        units: Some(transfer.remainder()),
+    }
+    .execute()
+    .await?;
+
+    transfer.status = CanaryStatus::Completed;
+    transfer.remainder_tx = Some(result.tx_hash);
+    canaries.save()?;
+    println!(
+        "{}: Canary transfer #{} completed: 0x{:x}",
+        "Success".green().bold(),
+        id,
+        result.tx_hash
+    );
+    Ok(result)
+}
+
+pub fn cancel(id: u64) -> Result<()> {
+    let mut canaries = CanaryTransfers::load()?;
+    let transfer = canaries.awaiting_mut(id)?;
+    transfer.status = CanaryStatus::Cancelled;
+    let remainder = transfer.format(transfer.remainder());
+    canaries.save()?;
+    println!(
+        "{}: Cancelled canary transfer #{}; the remaining {} was not sent",
+        "Success".green().bold(),
+        id,
+        remainder
+    );
+    Ok(())
+}
+
+fn list() -> Result<()> {
+    let canaries = CanaryTransfers::load()?;
+    if canaries.transfers.is_empty() {
+        println!("{}: No canary transfers", "Info".yellow().bold());
+        return Ok(());
+    }
+
+    let mut table = TableBuilder::new();
+    table.add_header(&["ID", "To", "Amount", "Canary", "Status", "Tx"]);
+    for t in &canaries.transfers {
+        table.add_row(&[
+            &t.id.to_string(),
+            &format!("0x{:x}", t.to),
+            &t.format(t.total),
+            &format!("0x{:x}", t.canary_tx),
+            &match t.status {
+                CanaryStatus::AwaitingReceipt => "Awaiting receipt".to_string(),
+                status => format!("{:?}", status),
+            },
+            &t.remainder_tx
+                .map(|h| format!("0x{:x}", h))
+                .unwrap_or_else(|| "-".to_string()),
+        ]);
+    }
+    table.print();
+    Ok(())
+}
+
+/// One-line summary of a canary transfer
+pub fn describe(transfer: &CanaryTransfer) -> String {
+    format!(
+        "#{}: {} to 0x{:x} (canary {} sent {})",
+        transfer.id,
+        transfer.format(transfer.total),
+        transfer.to,
+        transfer.format(transfer.canary),
+        transfer.created_at.format("%Y-%m-%d %H:%M")
+    )
+}
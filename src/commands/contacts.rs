@@ -2,9 +2,21 @@ use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
 use alloy::primitives::Address;
+use alloy::signers::local::PrivateKeySigner;
+use std::collections::HashMap;
 use std::str::FromStr;
 
-use crate::types::contacts::Contact;
+use crate::commands::address::parse_recipient;
+use crate::types::contacts::{Contact, group_members, groups};
+use crate::config::ConfigManager;
+use crate::types::wallet::WalletData;
+use crate::utils::audit;
+use crate::utils::contact_export::{self, ContactsFile, EncryptedContacts, SignedContacts};
+use crate::utils::contact_formats::{self, ContactFormat};
+use crate::utils::helper::unlock_wallet;
+use crate::utils::output;
+use crate::utils::store;
+use crate::utils::{http, rns};
 use crate::utils::table::TableBuilder;
 
 #[derive(Parser, Debug)]
@@ -19,7 +31,7 @@ pub enum ContactsAction {
     Add {
         /// Contact name
         name: String,
-        /// Contact address
+        /// Contact address or RNS name (e.g. alice.rsk)
         address: String,
         /// Notes about the contact
         #[arg(short, long)]
@@ -27,6 +39,12 @@ pub enum ContactsAction {
         /// Tags to associate with the contact
         #[arg(short, long)]
         tags: Vec<String>,
+        /// Refuse to send to this contact without a memo (e.g. exchange deposits)
+        #[arg(long)]
+        memo_required: bool,
+        /// Groups to add the contact to
+        #[arg(short, long = "group")]
+        groups: Vec<String>,
     },
     /// List all contacts
     List,
@@ -42,7 +60,7 @@ pub enum ContactsAction {
         /// New name
         #[arg(long)]
         name: Option<String>,
-        /// New address
+        /// New address or RNS name
         #[arg(long)]
         address: Option<String>,
         /// New notes
@@ -51,6 +69,9 @@ pub enum ContactsAction {
         /// New tags
         #[arg(long)]
         tags: Option<Vec<String>>,
+        /// Whether sends to this contact need a memo
+        #[arg(long)]
+        memo_required: Option<bool>,
     },
     /// Get contact details
     Get {
@@ -62,18 +83,117 @@ pub enum ContactsAction {
         /// Search term
         query: String,
     },
-    /// Save contacts to a file
+    /// Save contacts to a file, signed by the current wallet
     Save {
         /// File path to save contacts
         file: Option<String>,
+        /// Write a plain, unsigned list (e.g. for a personal backup)
+        #[arg(long)]
+        unsigned: bool,
+        /// Encrypt the file with a passphrase, to share over channels that
+        /// should not see the payees
+        #[arg(long)]
+        encrypt: bool,
     },
-    /// Load contacts from a file
+    /// Load contacts from a file after checking its signature
     Load {
         /// File path to load contacts from
         file: Option<String>,
+        /// Address expected to have signed the file, confirmed with the
+        /// sender; not needed when it is your wallet or a verified contact
+        #[arg(long)]
+        signer: Option<String>,
+        /// Accept a file without a signature
+        #[arg(long)]
+        allow_unsigned: bool,
+    },
+    /// Export contacts, with notes and tags, to a CSV or vCard file
+    Export {
+        /// File to write (.csv, .vcf or .vcard)
+        file: String,
+        /// File format; taken from the file extension when not given
+        #[arg(long, value_enum)]
+        format: Option<ContactFormat>,
+    },
+    /// Import contacts from a CSV or vCard file, e.g. from a spreadsheet or
+    /// another address book
+    Import {
+        /// File to read (.csv, .vcf or .vcard)
+        file: String,
+        /// File format; taken from the file extension when not given
+        #[arg(long, value_enum)]
+        format: Option<ContactFormat>,
+    },
+    /// Manage contact groups
+    Group {
+        #[command(subcommand)]
+        action: GroupAction,
+    },
+    /// Generate a challenge for a contact to sign
+    Challenge {
+        /// Contact name or address
+        identifier: String,
+    },
+    /// Verify a contact's signature over their pending challenge
+    Verify {
+        /// Contact name or address
+        identifier: String,
+        /// Signature (0x-prefixed hex) returned by the contact
+        signature: String,
+    },
+}
+
+#[derive(Parser, Debug)]
+pub enum GroupAction {
+    /// List groups with their number of members
+    List,
+    /// Show the members of a group
+    Show {
+        /// Group name
+        group: String,
+    },
+    /// Add contacts to a group, creating it if needed
+    Add {
+        /// Group name
+        group: String,
+        /// Contact names or addresses
+        #[arg(required = true)]
+        contacts: Vec<String>,
+    },
+    /// Remove contacts from a group
+    Remove {
+        /// Group name
+        group: String,
+        /// Contact names or addresses
+        #[arg(required = true)]
+        contacts: Vec<String>,
+    },
+    /// Rename a group
+    Rename {
+        /// Current group name
+        group: String,
+        /// New group name
+        new_name: String,
+    },
+    /// Delete a group; its members stay in your contacts
+    Delete {
+        /// Group name
+        group: String,
     },
 }
 
+/// Parse a contact address, resolving RNS names on the default network.
+/// The resolved address is stored, so the contact keeps working if the name
+/// later points elsewhere.
+async fn contact_address(input: &str) -> Result<Address> {
+    let config = ConfigManager::new()?.load()?;
+    if !rns::is_name(input) {
+        return parse_recipient(input, config.default_network.chain_id());
+    }
+    let provider = http::provider(&config.rpc_url())?;
+    rns::resolve_recipient(&provider, input, config.default_network.chain_id()).await
+}
+
 impl ContactsCommand {
     pub async fn execute(&self) -> Result<()> {
         match &self.action {
@@ -82,9 +202,18 @@ impl ContactsCommand {
                 address,
                 notes,
                 tags,
+                memo_required,
+                groups,
             } => {
-                self.add_contact(name, address, notes.clone(), tags.clone())
-                    .await?
+                self.add_contact(
+                    name,
+                    address,
+                    notes.clone(),
+                    tags.clone(),
+                    *memo_required,
+                    groups,
+                )
+                .await?
             }
             ContactsAction::List => self.list_contacts().await?,
             ContactsAction::Remove { identifier } => self.remove_contact(identifier).await?,
@@ -94,6 +223,7 @@ impl ContactsCommand {
                 address,
                 notes,
                 tags,
+                memo_required,
             } => {
                 self.update_contact(
                     identifier,
@@ -101,13 +231,35 @@ impl ContactsCommand {
                     address.clone(),
                     notes.clone(),
                     tags.clone(),
+                    *memo_required,
                 )
                 .await?
             }
             ContactsAction::Get { identifier } => self.get_contact(identifier).await?,
             ContactsAction::Search { query } => self.search_contacts(query).await?,
-            ContactsAction::Load { file } => self.load_contacts_from_file(file).await?,
-            ContactsAction::Save { file } => self.save_contacts_to_file(file).await?,
+            ContactsAction::Load {
+                file,
+                signer,
+                allow_unsigned,
+            } => {
+                self.load_contacts_from_file(file, signer.as_deref(), *allow_unsigned)
+                    .await?
+            }
+            ContactsAction::Save {
+                file,
+                unsigned,
+                encrypt,
+            } => self.save_contacts_to_file(file, *unsigned, *encrypt).await?,
+            ContactsAction::Export { file, format } => self.export_contacts(file, *format)?,
+            ContactsAction::Import { file, format } => self.import_contacts(file, *format)?,
+            ContactsAction::Group { action } => self.manage_group(action)?,
+            ContactsAction::Challenge { identifier } => {
+                self.issue_challenge(identifier).await?;
+            }
+            ContactsAction::Verify {
+                identifier,
+                signature,
+            } => self.verify_contact(identifier, signature).await?,
         }
         Ok(())
     }
@@ -118,10 +270,16 @@ impl ContactsCommand {
         address: &str,
         notes: Option<String>,
         tags: Vec<String>,
+        memo_required: bool,
+        groups: &[String],
     ) -> Result<()> {
-        let address = Address::from_str(address)?;
+        let address = contact_address(address).await?;
 
-        let contact = Contact::new(name.to_string(), address, notes, tags);
+        let mut contact = Contact::new(name.to_string(), address, notes, tags);
+        contact.memo_required = memo_required;
+        for group in groups {
+            contact.join_group(group);
+        }
         contact.validate()?;
 
         let mut contacts = self.load_contacts()?;
@@ -137,13 +295,22 @@ impl ContactsCommand {
 
         if contacts.is_empty() {
             println!("{}: No contacts found", "Info".yellow().bold());
-            return Ok(());
+            return output::emit(&contacts);
         }
 
+        let config = ConfigManager::new()?.load()?;
+        let names = match http::provider(&config.rpc_url()) {
+            Ok(provider) => {
+                let addresses = contacts.iter().map(|contact| contact.address);
+                rns::reverse_names(&provider, config.default_network.chain_id(), addresses).await
+            }
+            Err(_) => HashMap::new(),
+        };
+
         let mut table = TableBuilder::new();
-        table.add_header(&["Name", "Address", "Tags", "Created"]);
+        table.add_header(&["Name", "Address", "Tags", "Verified", "Memo", "Created"]);
 
-        for contact in contacts {
+        for contact in &contacts {
             let tags = if !contact.tags.is_empty() {
                 contact.tags.join(", ")
             } else {
@@ -152,18 +319,28 @@ impl ContactsCommand {
 
             table.add_row(&[
                 &contact.name,
-                &format!(
-                    "{}{}",
-                    "0x".green(),
-                    contact.address.to_string()[2..].green()
+                &rns::with_name(
+                    format!(
+                        "{}{}",
+                        "0x".green(),
+                        contact.address.to_string()[2..].green()
+                    ),
+                    &contact.address,
+                    &names,
                 ),
                 &tags,
+                if contact.is_verified() { "✅" } else { "-" },
+                if contact.memo_required {
+                    "required"
+                } else {
+                    "-"
+                },
                 &contact.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
             ]);
         }
 
         table.print();
-        Ok(())
+        output::emit(&contacts)
     }
 
     pub async fn remove_contact(&self, identifier: &str) -> Result<()> {
@@ -189,6 +366,7 @@ impl ContactsCommand {
         address: Option<String>,
         notes: Option<String>,
         tags: Option<Vec<String>>,
+        memo_required: Option<bool>,
     ) -> Result<()> {
         let mut contacts = self.load_contacts()?;
 
@@ -201,7 +379,13 @@ impl ContactsCommand {
             contact.name = name;
         }
         if let Some(address) = address {
-            contact.address = address.parse()?;
+            let address = contact_address(&address).await?;
+            if address != contact.address {
+                // A verification only vouches for the address that signed it
+                contact.verification = None;
+                contact.pending_challenge = None;
+            }
+            contact.address = address;
         }
         if let Some(notes) = notes {
             contact.notes = Some(notes);
@@ -209,6 +393,9 @@ impl ContactsCommand {
         if let Some(tags) = tags {
             contact.tags = tags;
         }
+        if let Some(memo_required) = memo_required {
+            contact.memo_required = memo_required;
+        }
 
         self.save_contacts(&contacts)?;
 
@@ -225,7 +412,7 @@ impl ContactsCommand {
             .ok_or_else(|| anyhow::anyhow!("Contact not found"))?;
 
         println!("{}", contact);
-        Ok(())
+        output::emit(contact)
     }
 
     pub async fn search_contacts(&self, query: &str) -> Result<()> {
@@ -238,6 +425,7 @@ impl ContactsCommand {
                     || c.address.to_string().contains(query)
                     || c.notes.as_ref().is_some_and(|n| n.contains(query))
                     || c.tags.iter().any(|t| t.contains(query))
+                    || c.in_group(query)
             })
             .collect();
 
@@ -247,13 +435,13 @@ impl ContactsCommand {
                 "Info".yellow().bold(),
                 query
             );
-            return Ok(());
+            return output::emit(&matching_contacts);
         }
 
         let mut table = TableBuilder::new();
         table.add_header(&["Name", "Address", "Tags", "Created"]);
 
-        for contact in matching_contacts {
+        for contact in &matching_contacts {
             let tags = if !contact.tags.is_empty() {
                 contact.tags.join(", ")
             } else {
@@ -273,49 +461,184 @@ impl ContactsCommand {
         }
 
         table.print();
-        Ok(())
+        output::emit(&matching_contacts)
     }
 
-    pub fn load_contacts(&self) -> Result<Vec<Contact>> {
-        let contacts_path = dirs::data_local_dir()
-            .ok_or_else(|| anyhow::anyhow!("Failed to get data directory"))?
-            .join("rootstock-wallet")
-            .join("contacts.json");
+    pub fn manage_group(&self, action: &GroupAction) -> Result<()> {
+        match action {
+            GroupAction::List => {
+                let groups = groups(&self.load_contacts()?);
+                if groups.is_empty() {
+                    println!("{}: No groups found", "Info".yellow().bold());
+                    return Ok(());
+                }
+                let mut table = TableBuilder::new();
+                table.add_header(&["Group", "Members"]);
+                for (group, members) in groups {
+                    table.add_row(&[&group, &members.to_string()]);
+                }
+                table.print();
+            }
+            GroupAction::Show { group } => {
+                let contacts = self.load_contacts()?;
+                let members = group_members(&contacts, group);
+                if members.is_empty() {
+                    return Err(anyhow::anyhow!("Group '{}' has no members", group));
+                }
+                let mut table = TableBuilder::new();
+                table.add_header(&["Name", "Address", "Memo"]);
+                for contact in members {
+                    table.add_row(&[
+                        &format!("{}{}", contact.name, contact.badge()),
+                        &format!("0x{:x}", contact.address),
+                        if contact.memo_required {
+                            "required"
+                        } else {
+                            "-"
+                        },
+                    ]);
+                }
+                table.print();
+            }
+            GroupAction::Add { group, contacts } => {
+                if group.trim().is_empty() {
+                    return Err(anyhow::anyhow!("Group names cannot be empty"));
+                }
+                let added = self.update_group_members(contacts, |c| c.join_group(group))?;
+                println!(
+                    "{}: {} contact(s) added to '{}'",
+                    "Success".green().bold(),
+                    added,
+                    group
+                );
+            }
+            GroupAction::Remove { group, contacts } => {
+                let removed = self.update_group_members(contacts, |c| c.leave_group(group))?;
+                println!(
+                    "{}: {} contact(s) removed from '{}'",
+                    "Success".green().bold(),
+                    removed,
+                    group
+                );
+            }
+            GroupAction::Rename { group, new_name } => {
+                if new_name.trim().is_empty() {
+                    return Err(anyhow::anyhow!("Group names cannot be empty"));
+                }
+                let mut contacts = self.load_contacts()?;
+                let mut renamed = 0;
+                for contact in contacts.iter_mut().filter(|c| c.in_group(group)) {
+                    contact.leave_group(group);
+                    contact.join_group(new_name);
+                    renamed += 1;
+                }
+                if renamed == 0 {
+                    return Err(anyhow::anyhow!("Group '{}' has no members", group));
+                }
+                self.save_contacts(&contacts)?;
+                println!(
+                    "{}: Group '{}' renamed to '{}'",
+                    "Success".green().bold(),
+                    group,
+                    new_name
+                );
+            }
+            GroupAction::Delete { group } => {
+                let mut contacts = self.load_contacts()?;
+                let removed = contacts
+                    .iter_mut()
+                    .map(|contact| contact.leave_group(group))
+                    .filter(|&left| left)
+                    .count();
+                if removed == 0 {
+                    return Err(anyhow::anyhow!("Group '{}' has no members", group));
+                }
+                self.save_contacts(&contacts)?;
+                println!(
+                    "{}: Group '{}' deleted; its {} member(s) stay in your contacts",
+                    "Success".green().bold(),
+                    group,
+                    removed
+                );
+            }
+        }
+        Ok(())
+    }
 
-        if !contacts_path.exists() {
-            return Ok(Vec::new());
+    /// Apply `change` to each contact named in `identifiers` and save them,
+    /// returning how many it changed
+    fn update_group_members(
+        &self,
+        identifiers: &[String],
+        change: impl Fn(&mut Contact) -> bool,
+    ) -> Result<usize> {
+        let mut contacts = self.load_contacts()?;
+        let mut changed = 0;
+        for identifier in identifiers {
+            let contact = contacts
+                .iter_mut()
+                .find(|c| c.name == *identifier || c.address.to_string() == *identifier)
+                .ok_or_else(|| anyhow::anyhow!("Contact '{}' not found", identifier))?;
+            if change(contact) {
+                changed += 1;
+            }
         }
+        self.save_contacts(&contacts)?;
+        Ok(changed)
+    }
+
+    /// Generate and store a new challenge for the contact, returning it
+    pub async fn issue_challenge(&self, identifier: &str) -> Result<String> {
+        let mut contacts = self.load_contacts()?;
+        let contact = contacts
+            .iter_mut()
+            .find(|c| c.name == identifier || c.address.to_string() == identifier)
+            .ok_or_else(|| anyhow::anyhow!("Contact not found"))?;
 
-        let content = std::fs::read_to_string(contacts_path)?;
-        serde_json::from_str(&content)
-            .map_err(|e| anyhow::anyhow!("Failed to parse contacts: {}", e))
+        let challenge = contact.issue_challenge();
+        self.save_contacts(&contacts)?;
+
+        println!(
+            "{}: Ask the contact to sign this message with their wallet (personal_sign):\n",
+            "Info".blue().bold()
+        );
+        println!("{}\n", challenge);
+        Ok(challenge)
     }
 
-    // pub fn save_contacts(&self, contacts: &[Contact]) -> Result<()> {
-    //     let contacts_path = dirs::data_local_dir()
-    //         .ok_or_else(|| anyhow::anyhow!("Failed to get data directory"))?
-    //         .join("rootstock-wallet")
-    //         .join("contacts.json");
-
-    //     std::fs::create_dir_all(contacts_path.parent().unwrap())?;
-    //     let content = serde_json::to_string_pretty(contacts)?;
-    //     std::fs::write(contacts_path, content)?;
-    //     Ok(())
-    // }
-    pub fn save_contacts(&self, contacts: &[Contact]) -> Result<()> {
-        let contacts_dir = dirs::data_local_dir()
-            .ok_or_else(|| anyhow::anyhow!("Failed to get data directory"))?
-            .join("rootstock-wallet");
+    pub async fn verify_contact(&self, identifier: &str, signature: &str) -> Result<()> {
+        let mut contacts = self.load_contacts()?;
+        let contact = contacts
+            .iter_mut()
+            .find(|c| c.name == identifier || c.address.to_string() == identifier)
+            .ok_or_else(|| anyhow::anyhow!("Contact not found"))?;
 
-        std::fs::create_dir_all(&contacts_dir)?;
+        contact.verify_challenge(signature)?;
+        let name = contact.name.clone();
+        self.save_contacts(&contacts)?;
 
-        let contacts_path = contacts_dir.join("contacts.json");
-        let content = serde_json::to_string_pretty(contacts)?;
-        std::fs::write(contacts_path, content)?;
+        println!(
+            "{}: {} proved ownership of their address ✅",
+            "Success".green().bold(),
+            name
+        );
         Ok(())
     }
 
-    pub async fn save_contacts_to_file(&self, file: &Option<String>) -> Result<()> {
+    pub fn load_contacts(&self) -> Result<Vec<Contact>> {
+        store::open()?.load_contacts()
+    }
+
+    pub fn save_contacts(&self, contacts: &[Contact]) -> Result<()> {
+        store::open()?.save_contacts(contacts)
+    }
+
+    pub async fn save_contacts_to_file(
+        &self,
+        file: &Option<String>,
+        unsigned: bool,
+        encrypt: bool,
+    ) -> Result<()> {
         let contacts = self.load_contacts()?;
 
         let file_path = match file {
@@ -326,18 +649,64 @@ impl ContactsCommand {
             }
         };
 
-        let content = serde_json::to_string_pretty(&contacts)?;
-        std::fs::write(&file_path, content)?;
+        if unsigned {
+            let content = serde_json::to_string_pretty(&contacts)?;
+            std::fs::write(&file_path, encrypt_if(content, encrypt)?)?;
+            println!(
+                "{}: Contacts saved to {} (unsigned{})",
+                "Success".green().bold(),
+                file_path.display(),
+                if encrypt { ", encrypted" } else { "" }
+            );
+            return Ok(());
+        }
+
+        let wallet_data = WalletData::load()?;
+        let wallet = wallet_data
+            .get_current_wallet()
+            .ok_or_else(|| anyhow::anyhow!("No active wallet to sign the export with"))?;
+        if wallet.hardware.is_some() {
+            return Err(anyhow::anyhow!(
+                "Hardware wallets cannot sign contact exports; switch to another wallet or save with --unsigned"
+            ));
+        }
+        let private_key = unlock_wallet(
+            wallet,
+            0,
+            &format!(
+                "Enter password for wallet '{}' to sign the export: ",
+                wallet.name
+            ),
+        )?;
+        let signer = PrivateKeySigner::from_str(&private_key)
+            .map_err(|e| anyhow::anyhow!("Failed to create PrivateKeySigner: {}", e))?;
+        let signed = SignedContacts::sign(&contacts, &signer)?;
+        let content = serde_json::to_string_pretty(&signed)?;
+        std::fs::write(&file_path, encrypt_if(content, encrypt)?)?;
 
         println!(
-            "{}: Contacts saved to {}",
+            "{}: {} contacts saved to {}, signed by {}{}",
             "Success".green().bold(),
-            file_path.display()
+            contacts.len(),
+            file_path.display(),
+            signer.address().to_checksum(None),
+            if encrypt { " and encrypted" } else { "" }
+        );
+        if encrypt {
+            println!("Send the passphrase separately from the file.");
+        }
+        println!(
+            "Share the signing address with your teammates through another channel so they can check it on import."
         );
         Ok(())
     }
 
-    pub async fn load_contacts_from_file(&self, file: &Option<String>) -> Result<()> {
+    pub async fn load_contacts_from_file(
+        &self,
+        file: &Option<String>,
+        signer: Option<&str>,
+        allow_unsigned: bool,
+    ) -> Result<()> {
         let file_path = match file {
             Some(path) => std::path::PathBuf::from(path),
             None => {
@@ -347,18 +716,300 @@ impl ContactsCommand {
         };
 
         let content = std::fs::read_to_string(&file_path)?;
-        let contacts: Vec<Contact> = serde_json::from_str(&content)?;
+        let existing_contacts = self.load_contacts().unwrap_or_default();
+
+        let mut file = contact_export::parse(&content)?;
+        if let ContactsFile::Encrypted(encrypted) = &file {
+            let passphrase = inquire::Password::new(&format!(
+                "Passphrase for {}:",
+                file_path.display()
+            ))
+            .with_display_mode(inquire::PasswordDisplayMode::Masked)
+            .without_confirmation()
+            .prompt()?;
+            file = contact_export::parse(&encrypted.decrypt(&passphrase)?)?;
+        }
 
-        // Merge with existing contacts (optional - you might want to replace instead)
-        let mut existing_contacts = self.load_contacts().unwrap_or_default();
-        existing_contacts.extend(contacts);
+        let contacts: Vec<Contact> = match file {
+            ContactsFile::Signed(signed) => {
+                let shared = signed.verify()?;
+                self.check_export_signer(signed.signer, signer, &existing_contacts)?;
+                println!(
+                    "{}: Signature by {} checked",
+                    "Info".blue().bold(),
+                    signed.signer.to_checksum(None)
+                );
+                shared.into_iter().map(|c| c.into_contact()).collect()
+            }
+            ContactsFile::Unsigned(contacts) => {
+                if !allow_unsigned {
+                    return Err(anyhow::anyhow!(
+                        "{} is not signed, so changed addresses cannot be detected. \
+                         Ask the sender for a signed export, or pass --allow-unsigned if you trust the file.",
+                        file_path.display()
+                    ));
+                }
+                println!(
+                    "{}: {} is not signed; check its addresses before paying them",
+                    "Warning".yellow().bold(),
+                    file_path.display()
+                );
+                contacts
+            }
+            ContactsFile::Encrypted(_) => {
+                return Err(anyhow::anyhow!(
+                    "{} holds another encrypted file",
+                    file_path.display()
+                ));
+            }
+        };
+
+        self.merge_contacts(existing_contacts, contacts, &file_path)
+    }
+
+    /// Add the contacts read from `file_path` to `existing_contacts` and
+    /// save them
+    fn merge_contacts(
+        &self,
+        mut existing_contacts: Vec<Contact>,
+        contacts: Vec<Contact>,
+        file_path: &std::path::Path,
+    ) -> Result<()> {
+        // Existing contacts win: a file must not silently redirect a name
+        // you already pay to another address
+        let (mut added, mut known, mut conflicts) = (0, 0, 0);
+        for contact in contacts {
+            if existing_contacts
+                .iter()
+                .any(|c| c.address == contact.address)
+            {
+                known += 1;
+            } else if let Some(existing) = existing_contacts
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(&contact.name))
+            {
+                println!(
+                    "{}: Skipped '{}': the file has 0x{:x}, your contact has 0x{:x}",
+                    "Warning".yellow().bold(),
+                    contact.name,
+                    contact.address,
+                    existing.address
+                );
+                conflicts += 1;
+            } else {
+                contact.validate()?;
+                existing_contacts.push(contact);
+                added += 1;
+            }
+        }
         self.save_contacts(&existing_contacts)?;
+        audit::record_or_warn(
+            "contacts_imported",
+            None,
+            format!(
+                "{}: {} added, {} already known, {} conflicting",
+                file_path.display(),
+                added,
+                known,
+                conflicts
+            ),
+        );
+
+        println!(
+            "{}: Contacts loaded from {}: {} added, {} already known, {} skipped as conflicting",
+            "Success".green().bold(),
+            file_path.display(),
+            added,
+            known,
+            conflicts
+        );
+        Ok(())
+    }
 
+    pub fn export_contacts(&self, file: &str, format: Option<ContactFormat>) -> Result<()> {
+        let file_path = std::path::PathBuf::from(file);
+        let format = match format {
+            Some(format) => format,
+            None => ContactFormat::of_path(&file_path)?,
+        };
+        let contacts = self.load_contacts()?;
+        std::fs::write(&file_path, contact_formats::write(&contacts, format)?)?;
         println!(
-            "{}: Contacts loaded from {}",
+            "{}: {} contacts exported to {}",
             "Success".green().bold(),
+            contacts.len(),
+            file_path.display()
+        );
+        Ok(())
+    }
+
+    pub fn import_contacts(&self, file: &str, format: Option<ContactFormat>) -> Result<()> {
+        let file_path = std::path::PathBuf::from(file);
+        let format = match format {
+            Some(format) => format,
+            None => ContactFormat::of_path(&file_path)?,
+        };
+        let content = std::fs::read_to_string(&file_path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", file_path.display(), e))?;
+        let chain_id = ConfigManager::new()?.load()?.default_network.chain_id();
+        let (contacts, errors) = contact_formats::read(&content, format, chain_id)?;
+        if !errors.is_empty() {
+            let mut table = TableBuilder::new();
+            table.add_header(&[
+                if format == ContactFormat::Vcard { "Card" } else { "Line" },
+                "Problem",
+            ]);
+            for error in &errors {
+                table.add_row(&[&error.line.to_string(), &error.message]);
+            }
+            table.print();
+            return Err(anyhow::anyhow!(
+                "{} entries of {} cannot be imported; fix them and import the file again",
+                errors.len(),
+                file_path.display()
+            ));
+        }
+        println!(
+            "{}: {} is not signed; check its addresses before paying them",
+            "Warning".yellow().bold(),
             file_path.display()
         );
+        let existing_contacts = self.load_contacts().unwrap_or_default();
+        let contacts = contacts.into_iter().map(|c| c.into_contact()).collect();
+        self.merge_contacts(existing_contacts, contacts, &file_path)
+    }
+
+    /// Accept an export signed by the expected address, or else by one of
+    /// your wallets or a verified contact
+    fn check_export_signer(
+        &self,
+        signer: Address,
+        expected: Option<&str>,
+        contacts: &[Contact],
+    ) -> Result<()> {
+        if let Some(expected) = expected {
+            let expected = Address::from_str(expected.trim())?;
+            if signer != expected {
+                return Err(anyhow::anyhow!(
+                    "The file was signed by 0x{:x}, not by the expected 0x{:x}",
+                    signer,
+                    expected
+                ));
+            }
+            return Ok(());
+        }
+        if WalletData::load()?.find_account(signer).is_some() {
+            return Ok(());
+        }
+        match contacts.iter().find(|c| c.address == signer) {
+            Some(contact) if contact.is_verified() => Ok(()),
+            _ => Err(anyhow::anyhow!(
+                "The file was signed by 0x{:x}, which is neither one of your wallets nor a verified contact. \
+                 Confirm the address with the sender through another channel and pass --signer 0x{:x}",
+                signer,
+                signer
+            )),
+        }
+    }
+}
+
+/// `content` encrypted with a passphrase asked for twice, when `encrypt`
+/// is set
+fn encrypt_if(content: String, encrypt: bool) -> Result<String> {
+    if !encrypt {
+        return Ok(content);
+    }
+    let passphrase = inquire::Password::new("Passphrase for the file:")
+        .with_display_mode(inquire::PasswordDisplayMode::Masked)
+        .with_custom_confirmation_message("Confirm passphrase:")
+        .with_custom_confirmation_error_message("The passphrases don't match.")
+        .prompt()?;
+    if passphrase.is_empty() {
+        return Err(anyhow::anyhow!("The passphrase cannot be empty"));
+    }
+    let encrypted = EncryptedContacts::encrypt(&content, &passphrase)?;
+    Ok(serde_json::to_string_pretty(&encrypted)?)
+}
+
+/// Enforce the "verified contacts only" sending policy for a recipient
+pub fn ensure_verified_recipient(to: &Address) -> Result<()> {
+    let config = crate::config::ConfigManager::new()?.load()?;
+    if !config.require_verified_contacts {
+        return Ok(());
+    }
+
+    let contacts = ContactsCommand {
+        action: ContactsAction::List,
+    }
+    .load_contacts()?;
+    if contacts.iter().any(|c| c.address == *to && c.is_verified()) {
         Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "Sending is restricted to verified contacts and 0x{:x} is not one. \
+             Verify the recipient under Contact Management or disable the policy in Configuration.",
+            to
+        ))
+    }
+}
+
+/// Refuse to send to a contact marked "memo required" without a memo, so an
+/// exchange deposit is not lost for want of its identifier
+pub fn ensure_memo(to: &Address, memo: Option<&str>) -> Result<()> {
+    let contacts = ContactsCommand {
+        action: ContactsAction::List,
+    }
+    .load_contacts()?;
+    check_memo(&contacts, to, memo)
+}
+
+fn check_memo(contacts: &[Contact], to: &Address, memo: Option<&str>) -> Result<()> {
+    if memo.is_some_and(|m| !m.trim().is_empty()) {
+        return Ok(());
+    }
+    let Some(contact) = contacts
+        .iter()
+        .find(|c| c.address == *to && c.memo_required)
+    else {
+        return Ok(());
+    };
+    Err(anyhow::anyhow!(
+        "{} (0x{:x}) requires a memo. Send with `transfer --memo` and the \
+         destination tag or deposit memo the recipient gave you.",
+        contact.name,
+        to
+    ))
+}
+
+/// Whether `to` belongs to a contact marked "memo required"
+pub fn memo_required(to: &Address) -> Result<bool> {
+    let contacts = ContactsCommand {
+        action: ContactsAction::List,
+    }
+    .load_contacts()?;
+    Ok(contacts.iter().any(|c| c.address == *to && c.memo_required))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_memo() {
+        let exchange = Address::repeat_byte(1);
+        let friend = Address::repeat_byte(2);
+        let mut deposit = Contact::new("Exchange".to_string(), exchange, None, vec![]);
+        deposit.memo_required = true;
+        let contacts = [
+            deposit,
+            Contact::new("Friend".to_string(), friend, None, vec![]),
+        ];
+
+        let err = check_memo(&contacts, &exchange, None).unwrap_err().to_string();
+        assert!(err.starts_with("Exchange (0x0101"), "{}", err);
+        assert!(check_memo(&contacts, &exchange, Some("  ")).is_err());
+        assert!(check_memo(&contacts, &exchange, Some("tag 1234")).is_ok());
+        assert!(check_memo(&contacts, &friend, None).is_ok());
+        assert!(check_memo(&contacts, &Address::ZERO, None).is_ok());
     }
 }
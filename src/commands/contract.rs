@@ -0,0 +1,329 @@
+use crate::commands::address::parse_address;
+use crate::commands::pending::{read_client, signing_client};
+use crate::config::ConfigManager;
+use crate::types::network::NetworkService;
+use crate::types::wallet::WalletData;
+use crate::utils::abi_registry::{self, AbiRegistry};
+use crate::utils::blockscout::BlockscoutClient;
+use crate::utils::eth::get_explorer_url;
+use crate::utils::roles::{self, Capability};
+use alloy::dyn_abi::{DynSolType, DynSolValue, FunctionExt, JsonAbiExt};
+use alloy::json_abi::{Function, JsonAbi, StateMutability};
+use alloy::network::TransactionBuilder;
+use alloy::primitives::utils::parse_ether;
+use alloy::primitives::{Address, Bytes, U256, hex};
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionRequest;
+use anyhow::{Result, anyhow};
+use clap::{Args, Parser, Subcommand};
+use colored::Colorize;
+use std::path::PathBuf;
+
+/// Call any contract method through its ABI
+#[derive(Parser, Debug)]
+pub struct ContractCommand {
+    #[command(subcommand)]
+    pub action: ContractAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ContractAction {
+    /// Read a method with `eth_call`; state-changing methods are simulated
+    /// without sending anything
+    Call(ContractArgs),
+    /// Sign and send a state-changing method call from the current wallet
+    Send {
+        #[command(flatten)]
+        call: ContractArgs,
+        /// RBTC to send along with a payable method
+        #[arg(long)]
+        value: Option<String>,
+        /// Account of the current wallet to send from (see `wallet add-account`)
+        #[arg(long)]
+        account: Option<u32>,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct ContractArgs {
+    /// Contract address, or the name it was registered under in the
+    /// Smart Contracts menu
+    pub contract: String,
+    /// Method name, or its full signature (e.g. `transfer(address,uint256)`)
+    /// when the name is overloaded
+    pub method: String,
+    /// Method arguments; arrays and tuples as `[a,b]` and `(a,b)`
+    pub args: Vec<String>,
+    /// ABI JSON file, or a build artifact with an `abi` field. Defaults to
+    /// the registered ABI, then to the explorer's verified source
+    #[arg(long)]
+    pub abi: Option<PathBuf>,
+}
+
+impl ContractCommand {
+    pub async fn execute(&self) -> Result<()> {
+        match &self.action {
+            ContractAction::Call(call) => {
+                let (address, function, input) = call.prepare().await?;
+                let output = read(address, input).await?;
+                let values = function
+                    .abi_decode_output(&output, true)
+                    .map_err(|e| anyhow!("Failed to decode the result: {}", e))?;
+                if values.is_empty() {
+                    println!("(no return value)");
+                }
+                for (param, value) in function.outputs.iter().zip(&values) {
+                    let name = if param.name.is_empty() {
+                        param.selector_type().to_string()
+                    } else {
+                        format!("{} ({})", param.name, param.selector_type())
+                    };
+                    println!("{}: {}", name.bold(), format_value(value));
+                }
+                Ok(())
+            }
+            ContractAction::Send {
+                call,
+                value,
+                account,
+            } => {
+                roles::require(Capability::Transfer)?;
+                let (address, function, input) = call.prepare().await?;
+                let value = match value {
+                    Some(amount) if function.state_mutability != StateMutability::Payable => {
+                        return Err(anyhow!(
+                            "{} is not payable, so it cannot receive {} RBTC",
+                            function.signature(),
+                            amount
+                        ));
+                    }
+                    Some(amount) => {
+                        parse_ether(amount.trim()).map_err(|e| anyhow!("Invalid amount: {}", e))?
+                    }
+                    None => U256::ZERO,
+                };
+                if matches!(
+                    function.state_mutability,
+                    StateMutability::View | StateMutability::Pure
+                ) {
+                    println!(
+                        "{}: {} does not change state; `contract call` reads it without a transaction",
+                        "Note".yellow().bold(),
+                        function.signature()
+                    );
+                }
+
+                let wallet_data = WalletData::load()?;
+                let wallet = wallet_data.get_current_wallet().ok_or_else(|| {
+                    anyhow!("No default wallet selected. Please use 'wallet switch' to select a default wallet.")
+                })?;
+                let account = account.unwrap_or(0);
+                let from = wallet
+                    .account_address(account)
+                    .ok_or_else(|| anyhow!("'{}' has no account #{}", wallet.name, account))?;
+                let client = signing_client(from).await?;
+                let tx_hash = client.send_call(address, value, input).await?;
+                println!(
+                    "{}: Transaction sent: 0x{:x}",
+                    "Success".green().bold(),
+                    tx_hash
+                );
+                let config = ConfigManager::new()?.load()?;
+                if config.default_network.supports(NetworkService::Explorer) {
+                    println!(
+                        "{}",
+                        get_explorer_url(
+                            &format!("0x{:x}", tx_hash),
+                            config.default_network.is_testnet()
+                        )
+                    );
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl ContractArgs {
+    /// Resolve the contract and its ABI, pick the method and encode the call
+    async fn prepare(&self) -> Result<(Address, Function, Bytes)> {
+        let config = ConfigManager::new()?.load()?;
+        let network = if config.default_network.is_testnet() {
+            "testnet"
+        } else {
+            "mainnet"
+        };
+        let registered = AbiRegistry::load()?.get(network, &self.contract).cloned();
+        let address = match &registered {
+            Some(contract) => contract.address,
+            None => parse_address(&self.contract, config.default_network.chain_id())
+                .map_err(|_| {
+                    anyhow!(
+                        "'{}' is neither an address nor a contract registered on {}",
+                        self.contract,
+                        network
+                    )
+                })?,
+        };
+        let abi = match (&self.abi, registered) {
+            (Some(path), _) => abi_registry::read_abi_file(path)?,
+            (None, Some(contract)) => contract.abi,
+            (None, None) => {
+                if !config.default_network.supports(NetworkService::Explorer) {
+                    return Err(anyhow!(
+                        "Give the ABI with --abi; {} has no explorer to fetch it from",
+                        config.default_network
+                    ));
+                }
+                BlockscoutClient::new(config.default_network.is_testnet())?
+                    .get_contract_abi(&address)
+                    .await?
+            }
+        };
+
+        let function = find_function(&abi, &self.method)?.clone();
+        let values = encode_args(&function, &self.args)?;
+        let input = function
+            .abi_encode_input(&values)
+            .map_err(|e| anyhow!("Failed to encode the call: {}", e))?;
+        Ok((address, function, input.into()))
+    }
+}
+
+async fn read(address: Address, input: Bytes) -> Result<Bytes> {
+    let client = read_client().await?;
+    let tx = TransactionRequest::default()
+        .with_to(address)
+        .with_input(input);
+    client
+        .provider()
+        .call(&tx)
+        .await
+        .map_err(|e| anyhow!("Call failed: {}", e))
+}
+
+/// The function called `method`, or with that exact signature. Overloaded
+/// names must be given by signature.
+pub fn find_function<'a>(abi: &'a JsonAbi, method: &str) -> Result<&'a Function> {
+    let method: String = method.chars().filter(|c| !c.is_whitespace()).collect();
+    if method.contains('(') {
+        return abi
+            .functions()
+            .find(|function| function.signature() == method)
+            .ok_or_else(|| anyhow!("The ABI has no function {}", method));
+    }
+    match abi.function(&method).map(Vec::as_slice) {
+        Some([function]) => Ok(function),
+        Some(overloads) => Err(anyhow!(
+            "{} is overloaded; give one of {}",
+            method,
+            overloads
+                .iter()
+                .map(Function::signature)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        None => Err(anyhow!("The ABI has no function named {}", method)),
+    }
+}
+
+/// Parse the arguments of `function` from their text form
+pub fn encode_args(function: &Function, args: &[String]) -> Result<Vec<DynSolValue>> {
+    if args.len() != function.inputs.len() {
+        return Err(anyhow!(
+            "{} takes {} argument(s), got {}",
+            function.signature(),
+            function.inputs.len(),
+            args.len()
+        ));
+    }
+    function
+        .inputs
+        .iter()
+        .zip(args)
+        .enumerate()
+        .map(|(i, (param, arg))| {
+            let ty = DynSolType::parse(&param.selector_type())?;
+            let name = if param.name.is_empty() {
+                format!("argument {}", i + 1)
+            } else {
+                param.name.clone()
+            };
+            ty.coerce_str(arg.trim())
+                .map_err(|e| anyhow!("Invalid {}: {}", name, e))
+        })
+        .collect()
+}
+
+pub fn format_value(value: &DynSolValue) -> String {
+    match value {
+        DynSolValue::Bool(b) => b.to_string(),
+        DynSolValue::Int(i, _) => i.to_string(),
+        DynSolValue::Uint(u, _) => u.to_string(),
+        DynSolValue::Address(a) => a.to_checksum(None),
+        DynSolValue::Function(f) => hex::encode_prefixed(f.as_slice()),
+        DynSolValue::FixedBytes(word, size) => hex::encode_prefixed(&word[..*size]),
+        DynSolValue::Bytes(bytes) => hex::encode_prefixed(bytes),
+        DynSolValue::String(s) => format!("{:?}", s),
+        DynSolValue::Array(values) | DynSolValue::FixedArray(values) => format!(
+            "[{}]",
+            values
+                .iter()
+                .map(format_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        DynSolValue::Tuple(values) => format!(
+            "({})",
+            values
+                .iter()
+                .map(format_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        #[allow(unreachable_patterns)]
+        other => format!("{:?}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_function_and_encode_args() {
+        let abi: JsonAbi = serde_json::from_str(
+            r#"[
+                {"type":"function","name":"transfer","stateMutability":"nonpayable",
+                 "inputs":[{"name":"to","type":"address"},{"name":"amount","type":"uint256"}],
+                 "outputs":[{"name":"","type":"bool"}]},
+                {"type":"function","name":"mint","stateMutability":"nonpayable",
+                 "inputs":[{"name":"amount","type":"uint256"}],"outputs":[]},
+                {"type":"function","name":"mint","stateMutability":"nonpayable",
+                 "inputs":[{"name":"to","type":"address"},{"name":"amount","type":"uint256"}],
+                 "outputs":[]}
+            ]"#,
+        )
+        .unwrap();
+
+        let transfer = find_function(&abi, "transfer").unwrap();
+        let args = vec![
+            "0x1111111111111111111111111111111111111111".to_string(),
+            "1000".to_string(),
+        ];
+        let input = transfer
+            .abi_encode_input(&encode_args(transfer, &args).unwrap())
+            .unwrap();
+        assert_eq!(&input[..4], &hex::decode("a9059cbb").unwrap()[..]);
+        assert!(encode_args(transfer, &args[..1]).is_err());
+        assert!(encode_args(transfer, &["0x12".to_string(), "1".to_string()]).is_err());
+
+        // Overloads are picked by signature
+        assert!(find_function(&abi, "mint").is_err());
+        assert_eq!(
+            find_function(&abi, "mint(address, uint256)").unwrap().inputs.len(),
+            2
+        );
+        assert!(find_function(&abi, "burn").is_err());
+    }
+}
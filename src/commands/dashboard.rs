@@ -0,0 +1,299 @@
+use crate::commands::tokens::TokenRegistry;
+use crate::config::PollingConfig;
+use crate::types::network::{Network, NetworkService};
+use crate::types::transaction::RskTransaction;
+use crate::types::wallet::{Wallet, WalletData};
+use crate::utils::eth::EthClient;
+use crate::utils::helper::Helper;
+use crate::utils::history_cache::HistoryCache;
+use crate::utils::staking::{self, LockedBalance};
+use alloy::primitives::{Address, U256, utils::format_units};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Local};
+use clap::Parser;
+use colored::Colorize;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Export a read-only HTML page with balances and recent history.
+///
+/// Only public data (names, addresses, balances, transactions) is written;
+/// keys and passwords are never read.
+#[derive(Parser, Debug, Clone)]
+pub struct DashboardCommand {
+    /// Wallet names to include (all wallets if empty)
+    #[arg(long)]
+    pub wallets: Vec<String>,
+
+    /// Output HTML file
+    #[arg(short, long, default_value = "dashboard.html")]
+    pub output: PathBuf,
+
+    /// Number of recent transactions per wallet
+    #[arg(long, default_value = "10")]
+    pub limit: usize,
+
+    #[arg(long, default_value = "mainnet")]
+    pub network: String,
+
+    /// Regenerate the page every N seconds instead of exiting
+    #[arg(long)]
+    pub interval: Option<u64>,
+}
+
+struct WalletSnapshot {
+    name: String,
+    address: Address,
+    rbtc: U256,
+    tokens: Vec<(String, String)>,
+    locked: Vec<LockedBalance>,
+    recent: Vec<RskTransaction>,
+}
+
+impl DashboardCommand {
+    pub async fn execute(&self) -> Result<()> {
+        let (config, eth_client) = Helper::init_eth_client(&self.network).await?;
+        // Without a block explorer (e.g. on Regtest) addresses and
+        // transactions are shown without links
+        let network = Network::from_str(&self.network)
+            .ok_or_else(|| anyhow!("Unknown network '{}'", self.network))?;
+        let explorer = network
+            .supports(NetworkService::Explorer)
+            .then(|| config.network.explorer_url.trim_end_matches('/').to_string());
+
+        loop {
+            let html = self.render(&eth_client, &network, explorer.as_deref()).await?;
+            fs::write(&self.output, html)?;
+            println!(
+                "{}: Dashboard written to {}",
+                "Success".green().bold(),
+                self.output.display()
+            );
+
+            match self.interval {
+                Some(secs) => tokio::time::sleep(Duration::from_secs(secs)).await,
+                None => return Ok(()),
+            }
+        }
+    }
+
+    fn selected_wallets(&self) -> Result<Vec<Wallet>> {
+        let wallet_data = WalletData::load()?;
+        if wallet_data.wallets.is_empty() {
+            return Err(anyhow!("No wallets found"));
+        }
+
+        if self.wallets.is_empty() {
+            let mut wallets: Vec<Wallet> = wallet_data.wallets.into_values().collect();
+            wallets.sort_by(|a, b| a.name.cmp(&b.name));
+            return Ok(wallets);
+        }
+
+        self.wallets
+            .iter()
+            .map(|name| {
+                wallet_data
+                    .get_wallet_by_name(name)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Wallet '{}' not found", name))
+            })
+            .collect()
+    }
+
+    async fn snapshot(
+        &self,
+        eth_client: &EthClient,
+        network: &Network,
+        wallet: &Wallet,
+    ) -> WalletSnapshot {
+        let rbtc = eth_client
+            .get_balance(&wallet.address, &None)
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to fetch RBTC balance for {}: {}", wallet.name, e);
+                U256::ZERO
+            });
+
+        let polling = PollingConfig::current();
+        let mut tokens = Vec::new();
+        let registry = TokenRegistry::load().unwrap_or_default();
+        for (symbol, info) in registry.list_tokens(Some(&self.network)) {
+            let Ok(token) = Address::from_str(&info.address) else {
+                continue;
+            };
+            polling.pace_requests().await;
+            if let Ok(balance) = eth_client.get_balance(&wallet.address, &Some(token)).await
+                && !balance.is_zero()
+            {
+                let amount =
+                    format_units(balance, info.decimals).unwrap_or_else(|_| balance.to_string());
+                tokens.push((symbol, amount));
+            }
+        }
+        tokens.sort();
+
+        let locked = staking::locked_balances(eth_client, &self.network, &wallet.address).await;
+
+        let cache = HistoryCache::load().unwrap_or_default();
+        let mut recent = cache.get(network.chain_name(), &wallet.address);
+        recent.sort_by_key(|tx| std::cmp::Reverse(tx.timestamp));
+        recent.truncate(self.limit);
+
+        WalletSnapshot {
+            name: wallet.name.clone(),
+            address: wallet.address,
+            rbtc,
+            tokens,
+            locked,
+            recent,
+        }
+    }
+
+    async fn render(
+        &self,
+        eth_client: &EthClient,
+        network: &Network,
+        explorer: Option<&str>,
+    ) -> Result<String> {
+        let mut snapshots = Vec::new();
+        for wallet in self.selected_wallets()? {
+            snapshots.push(self.snapshot(eth_client, network, &wallet).await);
+        }
+
+        let mut html = String::new();
+        writeln!(
+            html,
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>Rootstock Wallet Dashboard</title>\n<style>{}</style>\n</head>\n<body>",
+            STYLE
+        )?;
+        writeln!(html, "<h1>Rootstock Wallet Dashboard</h1>")?;
+        writeln!(
+            html,
+            "<p class=\"meta\">Network: {} &middot; Generated {}</p>",
+            escape(&self.network),
+            Local::now().format("%Y-%m-%d %H:%M:%S %Z")
+        )?;
+
+        for s in &snapshots {
+            writeln!(html, "<section>\n<h2>{}</h2>", escape(&s.name))?;
+            writeln!(
+                html,
+                "<p>{}</p>",
+                explorer_link(
+                    explorer,
+                    &format!("address/0x{:x}", s.address),
+                    &format!("0x{:x}", s.address)
+                )
+            )?;
+
+            writeln!(html, "<table>\n<tr><th>Asset</th><th>Balance</th></tr>")?;
+            writeln!(
+                html,
+                "<tr><td>RBTC</td><td>{}</td></tr>",
+                format_units(s.rbtc, 18).unwrap_or_else(|_| s.rbtc.to_string())
+            )?;
+            for (symbol, amount) in &s.tokens {
+                writeln!(
+                    html,
+                    "<tr><td>{}</td><td>{}</td></tr>",
+                    escape(symbol),
+                    escape(amount)
+                )?;
+            }
+            for position in &s.locked {
+                writeln!(
+                    html,
+                    "<tr><td>{} <span class=\"meta\">(locked in {})</span></td><td>{}</td></tr>",
+                    escape(&position.contract.asset),
+                    escape(&position.contract.name),
+                    position.formatted()
+                )?;
+            }
+            writeln!(html, "</table>")?;
+
+            if s.recent.is_empty() {
+                writeln!(html, "<p class=\"meta\">No cached transactions</p>")?;
+            } else {
+                writeln!(
+                    html,
+                    "<table>\n<tr><th>Date</th><th>Direction</th><th>Counterparty</th>\
+                     <th>Value (RBTC)</th><th>Status</th><th>Tx</th></tr>"
+                )?;
+                for tx in &s.recent {
+                    let time: DateTime<Local> = tx.timestamp.into();
+                    let (direction, counterparty) = if tx.from == s.address {
+                        ("Out", tx.to)
+                    } else {
+                        ("In", Some(tx.from))
+                    };
+                    writeln!(
+                        html,
+                        "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td>\
+                         <td>{}</td></tr>",
+                        time.format("%Y-%m-%d %H:%M"),
+                        direction,
+                        counterparty
+                            .map(|a| format!("0x{:x}", a))
+                            .unwrap_or_else(|| "Contract creation".to_string()),
+                        format_units(tx.value, 18).unwrap_or_else(|_| tx.value.to_string()),
+                        tx.status,
+                        explorer_link(
+                            explorer,
+                            &format!("tx/0x{:x}", tx.hash),
+                            &format!("0x{}…", &hex::encode(tx.hash)[..10])
+                        )
+                    )?;
+                }
+                writeln!(html, "</table>")?;
+            }
+            writeln!(html, "</section>")?;
+        }
+
+        writeln!(html, "</body>\n</html>")?;
+        Ok(html)
+    }
+}
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin:0.5em 0 1.5em}\
+th,td{border:1px solid #ddd;padding:4px 10px;text-align:left}\
+th{background:#f4f4f4}.meta{color:#777}section{margin-bottom:2em}";
+
+/// A link to `path` on the block explorer, or just `text` without one
+fn explorer_link(explorer: Option<&str>, path: &str, text: &str) -> String {
+    match explorer {
+        Some(base) => format!("<a href=\"{}/{}\">{}</a>", base, path, text),
+        None => text.to_string(),
+    }
+}
+
+/// Escape text for inclusion in HTML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_and_links() {
+        assert_eq!(
+            escape(r#"<script>alert("a & b")</script>"#),
+            "&lt;script&gt;alert(&quot;a &amp; b&quot;)&lt;/script&gt;"
+        );
+        assert_eq!(
+            explorer_link(Some("https://explorer.rsk.co"), "tx/0x12", "0x12…"),
+            "<a href=\"https://explorer.rsk.co/tx/0x12\">0x12…</a>"
+        );
+        // Networks without an explorer get plain text
+        assert_eq!(explorer_link(None, "tx/0x12", "0x12…"), "0x12…");
+    }
+}
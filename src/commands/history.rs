@@ -1,13 +1,31 @@
+use crate::api::ApiProvider;
+use crate::api::prices::Prices;
+use crate::commands::tokens::TokenRegistry;
+use crate::config::ConfigManager;
+use crate::types::network::{Network, NetworkService};
 use crate::types::transaction::{RskTransaction, TransactionStatus};
 use crate::types::wallet::WalletData;
+use crate::utils::accounting::{self, JournalFormat};
 use crate::utils::alchemy::AlchemyClient;
-use crate::utils::{constants, table::TableBuilder};
+use crate::utils::blockscout::BlockscoutClient;
+use crate::utils::categories;
+use crate::utils::cost_centers;
+use crate::utils::history_cache::HistoryCache;
+use crate::utils::helper::Helper;
+use crate::utils::history_export::{self, ExportFormat};
+use crate::utils::http;
+use crate::utils::output;
+use crate::utils::rns;
+use crate::utils::rsk_explorer::RskExplorerClient;
+use crate::utils::transfer_logs;
+use crate::utils::table::TableBuilder;
 use anyhow::Result;
 use chrono::TimeZone;
 use clap::Parser;
 use colored::Colorize;
 use console::style;
-use alloy::primitives::Address;
+use alloy::primitives::{Address, utils::format_units};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::str::FromStr;
 
@@ -22,6 +40,10 @@ pub struct HistoryCommand {
     #[arg(short, long)]
     pub contact: Option<String>,
 
+    /// Account of the current wallet to show (see `wallet add-account`)
+    #[arg(long)]
+    pub account: Option<u32>,
+
     /// Number of transactions to show
     #[arg(short, long, default_value = "10")]
     pub limit: u32,
@@ -43,11 +65,11 @@ pub struct HistoryCommand {
     pub from: Option<String>,
 
     /// End date for filtering (YYYY-MM-DD)
-    #[arg(short, long)]
+    #[arg(long)]
     pub to: Option<String>,
 
     /// Sort by field (timestamp, value, gas)
-    #[arg(short, long, default_value = "timestamp")]
+    #[arg(long, default_value = "timestamp")]
     pub sort_by: String,
 
     /// Sort order (asc/desc)
@@ -58,6 +80,23 @@ pub struct HistoryCommand {
     #[arg(long)]
     pub export_csv: Option<String>,
 
+    /// Export transactions to a beancount file
+    #[arg(long)]
+    pub export_beancount: Option<String>,
+
+    /// Export transactions to a ledger-cli journal
+    #[arg(long)]
+    pub export_ledger: Option<String>,
+
+    /// Export transactions for accounting (csv | json), with token symbols,
+    /// decimal amounts, USD values and status; written to --output
+    #[arg(long, value_parser = parse_export_format, requires = "output")]
+    pub export: Option<ExportFormat>,
+
+    /// File to write the --export output to
+    #[arg(long, requires = "export")]
+    pub output: Option<String>,
+
     /// Show only incoming transactions
     #[arg(short, long)]
     pub incoming: bool,
@@ -70,17 +109,27 @@ pub struct HistoryCommand {
     #[arg(long)]
     pub api_key: Option<String>,
 
+    /// History provider tried first (alchemy | blockscout | explorer).
+    /// Defaults to the configured provider, or Blockscout when no Alchemy key
+    /// is available. The others, then the node's logs, are used if it fails.
+    #[arg(long, value_parser = parse_provider)]
+    pub provider: Option<ApiProvider>,
+
     /// Network to query (mainnet | testnet). Defaults to mainnet.
     #[arg(long, default_value = "mainnet")]
     pub network: String,
+
+    /// Fetch the whole history again instead of only blocks after the last
+    /// sync (Alchemy)
+    #[arg(long)]
+    pub resync: bool,
 }
 
 impl HistoryCommand {
     pub async fn execute(&self) -> Result<()> {
         // 1. Load config and resolve API key
         // let config = Config::load()?;
-        let wallet_file = constants::wallet_file_path();
-        let mut stored_api_key: Option<String> = None;
+        let mut wallet_data = WalletData::load()?;
 
         // If export is requested, ensure we have a filename
         if let Some(filename) = &self.export_csv
@@ -88,36 +137,48 @@ impl HistoryCommand {
         {
             return Err(anyhow::anyhow!("Export filename must end with .csv"));
         }
+        let journal_export = match (&self.export_beancount, &self.export_ledger) {
+            (Some(_), Some(_)) => {
+                anyhow::bail!("Choose either --export-beancount or --export-ledger, not both")
+            }
+            (Some(filename), None) => Some((JournalFormat::Beancount, filename)),
+            (None, Some(filename)) => Some((JournalFormat::Ledger, filename)),
+            (None, None) => None,
+        };
+        let export = match (self.export, &self.output) {
+            (Some(format), Some(filename)) => Some((format, filename)),
+            (None, None) => None,
+            _ => anyhow::bail!("--export and --output must be given together"),
+        };
 
-        // Try to load API key from wallet file
-        if wallet_file.exists() {
-            let data = fs::read_to_string(&wallet_file)?;
-            if let Ok(mut val) = serde_json::from_str::<serde_json::Value>(&data) {
-                if let Some(api_key) = val["alchemyApiKey"].as_str() {
-                    stored_api_key = Some(api_key.to_string());
-                }
+        // Try to load API key from the keyring or wallet database
+        let mut stored_api_key = wallet_data.stored_api_key()?;
 
-                // Persist CLI key if supplied and not yet saved
-                if stored_api_key.is_none() && self.api_key.is_some() {
-                    val["alchemyApiKey"] = serde_json::Value::String(self.api_key.clone().unwrap());
-                    fs::write(&wallet_file, serde_json::to_string_pretty(&val)?)?;
-                    stored_api_key = self.api_key.clone();
-                    println!("{}", "Saved Alchemy API key ✅".green());
-                }
-            }
+        // Persist CLI key if supplied and not yet saved
+        if let (None, Some(key)) = (&stored_api_key, &self.api_key) {
+            wallet_data.set_api_key(key.clone())?;
+            stored_api_key = Some(key.clone());
+            println!("{}", "Saved Alchemy API key ✅".green());
         }
 
-        let final_api_key = self
+        let alchemy_key = self
             .api_key
             .clone()
             .or(stored_api_key)
-            .or(std::env::var("ALCHEMY_API_KEY").ok())
-            .ok_or_else(|| anyhow::anyhow!("Alchemy API key missing – supply --api-key once"))?;
+            .or(std::env::var("ALCHEMY_API_KEY").ok());
+        let provider = self
+            .provider
+            .clone()
+            .or(ConfigManager::new()?.load()?.api.default_provider)
+            .unwrap_or(if alchemy_key.is_some() {
+                ApiProvider::Alchemy
+            } else {
+                ApiProvider::Blockscout
+            });
 
-        let is_testnet = self.network.to_lowercase() == "testnet";
-        if self.network.to_lowercase() != "mainnet" && !is_testnet {
-            anyhow::bail!("Invalid network: use 'mainnet' or 'testnet'");
-        }
+        let network = Network::from_str(&self.network)
+            .ok_or_else(|| anyhow::anyhow!("Invalid network: use 'mainnet' or 'testnet'"))?;
+        let is_testnet = network.is_testnet();
 
         // 2. Get address to query
         let address = if let Some(addr) = &self.address {
@@ -134,41 +195,89 @@ impl HistoryCommand {
         // }
         else {
             // Get current wallet address
-            if !wallet_file.exists() {
+            if wallet_data.wallets.is_empty() {
                 anyhow::bail!("No wallets found. Create or import a wallet first.");
             }
-            let data = fs::read_to_string(&wallet_file)?;
-            let wallet_data = serde_json::from_str::<WalletData>(&data)?;
-            wallet_data
-                .get_current_wallet()
-                .ok_or_else(|| {
-                    anyhow::anyhow!("No default wallet selected. Use `wallet switch` first.")
-                })?
-                .address
+            let wallet = wallet_data.get_current_wallet().ok_or_else(|| {
+                anyhow::anyhow!("No default wallet selected. Use `wallet switch` first.")
+            })?;
+            let account = self.account.unwrap_or(0);
+            wallet.account_address(account).ok_or_else(|| {
+                anyhow::anyhow!("'{}' has no account #{}", wallet.name, account)
+            })?
         };
 
-        // 3. Initialize Alchemy client and fetch transfers
-        let alchemy_client = AlchemyClient::new(final_api_key, is_testnet);
-        let response = alchemy_client
-            .get_asset_transfers(
-                &format!("{:#x}", address),
-                self.limit,
-                self.from.as_deref(),
-                self.to.as_deref(),
-            )
-            .await?;
-
-        // 4. Process transactions
-        let transfers = response["result"]["transfers"]
-            .as_array()
-            .ok_or_else(|| anyhow::anyhow!("Invalid response format from Alchemy"))?;
-
-        let mut txs = Vec::new();
-        for transfer in transfers {
-            // Convert Alchemy transfer to RskTransaction
-            let tx =
-                RskTransaction::from_alchemy_transfer(transfer, &address, &alchemy_client).await?;
-            txs.push(tx);
+        // 3. Fetch transactions from the first history provider that
+        // answers, keeping a local copy for offline views such as activity
+        // statistics
+        let mut cache = HistoryCache::load().unwrap_or_default();
+        let mut fetched = None;
+        for source in ApiProvider::history_fallback(&provider) {
+            let service = match source {
+                ApiProvider::Alchemy => NetworkService::Alchemy,
+                ApiProvider::Blockscout => NetworkService::Blockscout,
+                ApiProvider::Explorer => NetworkService::Explorer,
+                _ => continue,
+            };
+            if !network.supports(service) {
+                continue;
+            }
+            let result = match (&source, &alchemy_key) {
+                (ApiProvider::Alchemy, None) => {
+                    if source == provider {
+                        println!(
+                            "{}",
+                            "⚠️  No Alchemy API key – supply --api-key once. Using the public explorers."
+                                .yellow()
+                        );
+                    }
+                    continue;
+                }
+                (ApiProvider::Alchemy, Some(api_key)) => {
+                    self.fetch_alchemy_history(api_key, &mut cache, network, &address)
+                        .await
+                }
+                (ApiProvider::Blockscout, _) => {
+                    BlockscoutClient::new(is_testnet)?
+                        .get_history(&address, self.limit)
+                        .await
+                }
+                _ => {
+                    RskExplorerClient::new(is_testnet)?
+                        .get_transactions(&address, self.limit)
+                        .await
+                }
+            };
+            match result {
+                Ok(txs) => {
+                    fetched = Some(txs);
+                    break;
+                }
+                Err(e) => println!(
+                    "{}",
+                    format!("⚠️  {} history unavailable ({}), trying the next provider", source, e)
+                        .yellow()
+                ),
+            }
+        }
+        let mut txs = match fetched {
+            Some(txs) => txs,
+            None => {
+                println!(
+                    "{}",
+                    format!(
+                        "No history provider available; reading token transfers of the last {} blocks from the node (RBTC payments are not included)",
+                        transfer_logs::SCAN_BLOCKS
+                    )
+                    .yellow()
+                );
+                let (_, eth_client) = Helper::init_eth_client(&self.network).await?;
+                transfer_logs::recent_transfers(&eth_client, address, self.limit).await?
+            }
+        };
+        cache.merge(network.chain_name(), &address, &txs);
+        if let Err(e) = cache.save() {
+            log::warn!("Failed to update history cache: {}", e);
         }
 
         // 5. Apply filters
@@ -184,7 +293,7 @@ impl HistoryCommand {
         // 6. Handle empty result
         if txs.is_empty() {
             println!("{}", "⚠️  No transactions found.".yellow());
-            return Ok(());
+            return output::emit(&[] as &[history_export::ExportRow]);
         }
 
         // 7. Sort results
@@ -199,6 +308,8 @@ impl HistoryCommand {
         // 8. Export to CSV if requested
         if let Some(filename) = &self.export_csv {
             let mut wtr = csv::Writer::from_path(filename)?;
+            let rules = ConfigManager::new()?.load()?.category_rules;
+            let allocations = cost_centers::load().unwrap_or_default();
 
             // Write header
             wtr.write_record([
@@ -212,11 +323,15 @@ impl HistoryCommand {
                 "Gas Used",
                 "Status",
                 "Block Number",
+                "Category",
+                "Cost Center",
             ])?;
 
             // Write transactions
             for tx in &txs {
-                let record = tx.to_csv_record();
+                let mut record = tx.to_csv_record();
+                record.push_field(categories::categorize(&rules, tx).unwrap_or(""));
+                record.push_field(allocations.get(&tx.hash).map_or("", String::as_str));
                 wtr.write_record(&record)?;
             }
 
@@ -227,13 +342,102 @@ impl HistoryCommand {
                 txs.len(),
                 style(filename).cyan()
             );
-            return Ok(());
+            return output::emit(&exported(filename, txs.len()));
+        }
+
+        // Symbols and decimals of the registered tokens, for the exports
+        let tokens: HashMap<Address, (String, u8)> = TokenRegistry::load()
+            .unwrap_or_default()
+            .list_tokens(Some(network.chain_name()))
+            .into_iter()
+            .filter_map(|(_, info)| {
+                Address::from_str(&info.address)
+                    .ok()
+                    .map(|token| (token, (info.symbol, info.decimals)))
+            })
+            .collect();
+
+        // 9. Export to CSV or JSON for accounting if requested
+        if let Some((format, filename)) = export {
+            let priced: HashSet<Address> = txs
+                .iter()
+                .filter_map(|tx| tx.token_address)
+                .filter(|token| tokens.contains_key(token))
+                .collect();
+            let prices = history_export::fetch_prices(is_testnet, priced).await;
+            let rows = history_export::rows(&txs, address, &tokens, &prices);
+            history_export::write(format, &rows, filename)?;
+            println!(
+                "\n{} Exported {} transactions to {}",
+                style("✓").green().bold(),
+                rows.len(),
+                style(filename).cyan()
+            );
+            if prices.is_empty() {
+                println!("{}", "No USD prices available; fiat values are left empty.".dimmed());
+            }
+            return output::emit(&exported(filename, rows.len()));
+        }
+
+        // 10. Export to a plaintext accounting journal if requested
+        if let Some((format, filename)) = journal_export {
+            let config = ConfigManager::new()?.load()?;
+            let journal = accounting::export(
+                format,
+                &txs,
+                address,
+                &config.category_rules,
+                &config.accounting,
+                &tokens,
+                &cost_centers::load().unwrap_or_default(),
+            );
+            fs::write(filename, journal)?;
+            println!(
+                "\n{} Exported {} transactions to {}",
+                style("✓").green().bold(),
+                txs.len(),
+                style(filename).cyan()
+            );
+            return output::emit(&exported(filename, txs.len()));
         }
 
-        // 9. Display results in terminal
+        // 11. Display results in terminal, with fiat values at current
+        // prices when a display currency is set. With `--json` the table
+        // goes to stderr and the transactions to stdout, as in the JSON
+        // export without fiat values.
+        output::emit(&history_export::rows(&txs, address, &tokens, &HashMap::new()))?;
+        let config = ConfigManager::new()?.load()?;
+        let prices = Prices::fetch(&config, &tokens.keys().copied().collect::<Vec<_>>()).await;
+        let value_column = prices
+            .as_ref()
+            .map(|prices| format!("Value ({}, now)", prices.currency));
+        // RNS names of the counterparties, shown next to their addresses
+        let names = match http::provider(
+            &network.get_rpc_url_with_key(config.get_rsk_rpc_key(), config.get_alchemy_key()),
+        ) {
+            Ok(provider) => {
+                let addresses = txs.iter().flat_map(|tx| [Some(tx.from), tx.to]).flatten();
+                rns::reverse_names(&provider, network.chain_id(), addresses).await
+            }
+            Err(_) => HashMap::new(),
+        };
+        let fiat = |tx: &RskTransaction| {
+            let prices = prices.as_ref()?;
+            let decimals = match tx.token_address {
+                None => 18,
+                Some(token) => tokens.get(&token).map_or(18, |(_, decimals)| *decimals),
+            };
+            let amount = format_units(tx.value, decimals).ok()?.parse::<f64>().ok()?;
+            Some(
+                prices
+                    .value(tx.token_address, amount)
+                    .unwrap_or_else(|| "-".to_string()),
+            )
+        };
+
         let mut table = TableBuilder::new();
         if self.detailed {
-            table.add_header(&[
+            let mut header = vec![
                 "TX Hash",
                 "From",
                 "To",
@@ -243,7 +447,9 @@ impl HistoryCommand {
                 "Gas Used",
                 "Gas Price",
                 "Nonce",
-            ]);
+            ];
+            header.extend(value_column.as_deref());
+            table.add_header(&header);
 
             for tx in &txs {
                 let status_disp = match tx.status {
@@ -262,20 +468,28 @@ impl HistoryCommand {
                     )
                     .unwrap();
 
-                table.add_row(&[
-                    &format!("0x{}", &tx.hash.to_string()[2..]),
-                    &format!("0x{}", &tx.from.to_string()[2..]),
-                    &tx.to
+                let mut row = vec![
+                    format!("0x{}", &tx.hash.to_string()[2..]),
+                    rns::with_name(format!("0x{}", &tx.from.to_string()[2..]), &tx.from, &names),
+                    tx.to
                         .as_ref()
-                        .map(|a| format!("0x{}", &a.to_string()[2..]))
+                        .map(|a| rns::with_name(format!("0x{}", &a.to_string()[2..]), a, &names))
                         .unwrap_or_else(|| "-".into()),
-                    &status_disp.to_string(),
-                    &ts.format("%Y-%m-%d %H:%M:%S").to_string(),
-                    // &tx.block_number.to_string(),
-                ]);
+                    status_disp.to_string(),
+                    ts.format("%Y-%m-%d %H:%M:%S").to_string(),
+                    // tx.block_number.to_string(),
+                ];
+                if let Some(value) = fiat(tx) {
+                    // Keep the value under its column past the unfilled ones
+                    row.resize(9, String::new());
+                    row.push(value);
+                }
+                table.add_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
             }
         } else {
-            table.add_header(&["TX Hash", "From", "To", "Status"]);
+            let mut header = vec!["TX Hash", "From", "To", "Status"];
+            header.extend(value_column.as_deref());
+            table.add_header(&header);
 
             for tx in &txs {
                 let status_disp = match tx.status {
@@ -285,19 +499,145 @@ impl HistoryCommand {
                     TransactionStatus::Unknown => "Unknown".yellow(),
                 };
 
-                table.add_row(&[
-                    &format!("0x{}", &tx.hash.to_string()[2..10]),
-                    &format!("0x{}", &tx.from.to_string()[2..6]),
-                    &tx.to
+                let mut row = vec![
+                    format!("0x{}", &tx.hash.to_string()[2..10]),
+                    rns::with_name(format!("0x{}", &tx.from.to_string()[2..6]), &tx.from, &names),
+                    tx.to
                         .as_ref()
-                        .map(|a| format!("0x{}", &a.to_string()[2..6]))
+                        .map(|a| rns::with_name(format!("0x{}", &a.to_string()[2..6]), a, &names))
                         .unwrap_or_else(|| "-".into()),
-                    &status_disp.to_string(),
-                ]);
+                    status_disp.to_string(),
+                ];
+                row.extend(fiat(tx));
+                table.add_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
             }
         }
 
         table.print();
         Ok(())
     }
+
+    /// History from Alchemy: the requested block range, or otherwise the
+    /// newest transactions after bringing the cache up to date
+    async fn fetch_alchemy_history(
+        &self,
+        api_key: &str,
+        cache: &mut HistoryCache,
+        network: Network,
+        address: &Address,
+    ) -> Result<Vec<RskTransaction>> {
+        let alchemy_client = AlchemyClient::new(api_key.to_string(), network.is_testnet())?;
+        if self.from.is_some() || self.to.is_some() {
+            return fetch_alchemy(
+                &alchemy_client,
+                address,
+                self.limit,
+                self.from.as_deref(),
+                self.to.as_deref(),
+            )
+            .await;
+        }
+        if self.resync {
+            cache.reset_sync(network.chain_name(), address);
+        }
+        sync_alchemy(&alchemy_client, cache, network, address, self.limit).await?;
+        let mut txs = cache.get(network.chain_name(), address);
+        txs.sort_by_key(|t| std::cmp::Reverse(t.timestamp));
+        txs.truncate(self.limit as usize);
+        Ok(txs)
+    }
+}
+
+/// `--json` result of an export to a file
+fn exported(path: &str, transactions: usize) -> serde_json::Value {
+    serde_json::json!({ "path": path, "transactions": transactions })
+}
+
+fn parse_export_format(value: &str) -> Result<ExportFormat, String> {
+    ExportFormat::from_name(value).ok_or_else(|| "use 'csv' or 'json'".to_string())
+}
+
+fn parse_provider(value: &str) -> Result<ApiProvider, String> {
+    match value.to_lowercase().as_str() {
+        "alchemy" => Ok(ApiProvider::Alchemy),
+        "blockscout" => Ok(ApiProvider::Blockscout),
+        "explorer" => Ok(ApiProvider::Explorer),
+        _ => Err("use 'alchemy', 'blockscout' or 'explorer'".to_string()),
+    }
+}
+
+/// Bring the cached history of `address` up to date: fetch the transfers in
+/// blocks after the last synced one, a page at a time, saving the progress
+/// after each page so an interrupted sync resumes where it stopped
+async fn sync_alchemy(
+    alchemy_client: &AlchemyClient,
+    cache: &mut HistoryCache,
+    network: Network,
+    address: &Address,
+    page_size: u32,
+) -> Result<()> {
+    let page_size = page_size.max(SYNC_PAGE_SIZE);
+    loop {
+        let synced = cache.synced_block(network.chain_name(), address);
+        let from_block = format!("0x{:x}", synced.map_or(0, |block| block + 1));
+        let txs = fetch_alchemy(alchemy_client, address, page_size, Some(&from_block), None).await?;
+        cache.merge(network.chain_name(), address, &txs);
+
+        let page_full = txs.len() >= page_size as usize;
+        let next = synced_through(synced, &txs, page_full);
+        if let Some(block) = next {
+            cache.set_synced_block(network.chain_name(), address, block);
+        }
+        cache.save()?;
+        if !page_full || next <= synced {
+            return Ok(());
+        }
+    }
+}
+
+/// Transfers fetched per request when syncing the history cache
+const SYNC_PAGE_SIZE: u32 = 100;
+
+/// Last block whose transfers are all cached after fetching `txs` (in
+/// ascending block order) following `synced`. When the page was full, the
+/// last block may have more transfers, so only the block before it counts.
+fn synced_through(synced: Option<u64>, txs: &[RskTransaction], page_full: bool) -> Option<u64> {
+    let last = txs
+        .iter()
+        .filter_map(|tx| tx.block_number)
+        .map(|block| block.to::<u64>())
+        .max();
+    let through = match last {
+        Some(block) if page_full => block.checked_sub(1),
+        Some(block) => Some(block),
+        None => None,
+    };
+    through.max(synced)
+}
+
+/// Fetch asset transfers from Alchemy, looking up each one's receipt and
+/// block over RPC
+async fn fetch_alchemy(
+    alchemy_client: &AlchemyClient,
+    address: &Address,
+    limit: u32,
+    from_block: Option<&str>,
+    to_block: Option<&str>,
+) -> Result<Vec<RskTransaction>> {
+    let response = alchemy_client
+        .get_asset_transfers(&format!("{:#x}", address), limit, from_block, to_block)
+        .await?;
+
+    let transfers = response["result"]["transfers"]
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("Invalid response format from Alchemy"))?;
+
+    let polling = ConfigManager::new()?.load()?.polling;
+    let mut txs = Vec::new();
+    for transfer in transfers {
+        // Each conversion looks up the receipt and block over RPC
+        polling.pace_requests().await;
+        txs.push(RskTransaction::from_alchemy_transfer(transfer, address, alchemy_client).await?);
+    }
+    Ok(txs)
 }
@@ -1,11 +1,35 @@
+pub mod address;
 pub mod api;
+pub mod audit_export;
 pub mod balance;
+pub mod batch;
+pub mod canary;
+pub mod config;
 pub mod contacts;
+pub mod contract;
+pub mod dashboard;
 pub mod history;
+pub mod offline;
+pub mod payroll;
+pub mod pending;
+pub mod portfolio;
+pub mod profile;
+pub mod proposal;
+pub mod queue;
+pub mod receive;
+pub mod role;
 pub mod root;
+pub mod sign;
+pub mod stats;
+pub mod sync;
 pub mod tokens;
 pub mod transfer;
+pub mod tui;
 pub mod tx;
+pub mod update;
+pub mod validate;
 pub mod wallet;
+pub mod watch;
+pub mod webhook;
 
 pub use root::Commands;
@@ -0,0 +1,374 @@
+use crate::commands::pending::read_client;
+use crate::commands::transfer::parse_gas_price;
+use crate::config::ConfigManager;
+use crate::qr;
+use crate::types::wallet::WalletData;
+use crate::utils::audit;
+use crate::utils::eth::GasOverrides;
+use crate::utils::helper::unlock_wallet;
+use crate::utils::nonce;
+use crate::utils::offline::{self, DecodedTx, SignedTxFile, UnsignedTx};
+use crate::utils::pending::{PendingStore, PendingTx};
+use crate::utils::profiles;
+use crate::utils::roles::{self, Capability};
+use crate::utils::signer::WalletSigner;
+use crate::utils::signing_log::{self, SignedTx};
+use crate::utils::submissions;
+use alloy::primitives::{Address, Bytes, U256, utils::format_units};
+use alloy::providers::Provider;
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{Result, anyhow};
+use clap::Args;
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Options for `tx build --unsigned`
+#[derive(Args, Debug)]
+pub struct BuildArgs {
+    /// Write the transaction out unsigned instead of sending it
+    #[arg(long, required = true)]
+    pub unsigned: bool,
+
+    /// Address to send to
+    #[arg(long)]
+    pub to: String,
+
+    /// Amount to send (in tokens or RBTC)
+    #[arg(long)]
+    pub value: f64,
+
+    /// Token address (for ERC20 transfers)
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Account of the current wallet to send from (see `wallet add-account`)
+    #[arg(long)]
+    pub account: Option<u32>,
+
+    /// Gas limit (defaults to the node's estimate)
+    #[arg(long)]
+    pub gas_limit: Option<u64>,
+
+    /// Gas price in gwei (defaults to the node's gas price)
+    #[arg(long)]
+    pub gas_price: Option<String>,
+
+    /// Nonce override, e.g. when several transactions are signed offline
+    /// before any is broadcast
+    #[arg(long)]
+    pub nonce: Option<u64>,
+
+    /// Memo or destination tag, required by contacts marked "memo required"
+    #[arg(long)]
+    pub memo: Option<String>,
+
+    /// File to write the unsigned transaction to
+    #[arg(long, default_value = "unsigned-tx.json")]
+    pub output: PathBuf,
+
+    /// Also show the transaction as animated QR codes for the offline machine
+    #[arg(long)]
+    pub qr: bool,
+}
+
+/// Build a transfer from the current wallet with nonce, gas and chain id
+/// filled in from the node, and write it out unsigned
+pub async fn build(args: &BuildArgs) -> Result<()> {
+    roles::require(Capability::Prepare)?;
+    let wallet_data = WalletData::load()?;
+    let wallet = wallet_data.get_current_wallet().ok_or_else(|| {
+        anyhow!(
+            "No default wallet selected. Please use 'wallet switch' to select a default wallet."
+        )
+    })?;
+    let account = args.account.unwrap_or(0);
+    let from = wallet.account_address(account).ok_or_else(|| {
+        anyhow!(
+            "'{}' has no account #{}. Use 'wallet add-account' to derive it.",
+            wallet.name,
+            account
+        )
+    })?;
+
+    let config = ConfigManager::new()?.load()?;
+    let network_name = if config.default_network.is_testnet() {
+        "testnet"
+    } else {
+        "mainnet"
+    };
+    let to = Address::from_str(&args.to)
+        .map_err(|_| anyhow!("Invalid recipient address: {}", &args.to))?;
+    profiles::ensure_not_look_alike(network_name, &to)?;
+    crate::commands::contacts::ensure_verified_recipient(&to)?;
+    crate::commands::contacts::ensure_memo(&to, args.memo.as_deref())?;
+
+    let client = read_client().await?;
+    let (token, symbol) = match args.token.as_deref() {
+        None | Some("") | Some("0x0000000000000000000000000000000000000000") => {
+            (None, "RBTC".to_string())
+        }
+        Some(token) => {
+            let token = Address::from_str(token)
+                .map_err(|_| anyhow!("Invalid token address: {}", token))?;
+            profiles::ensure_not_look_alike(network_name, &token)?;
+            let symbol = match client.get_token_info(token).await {
+                Ok((_, symbol)) => symbol,
+                Err(_) => format!("token 0x{:x}", token),
+            };
+            (Some(token), symbol)
+        }
+    };
+    let amount = alloy::primitives::utils::parse_units(&args.value.to_string(), 18)
+        .map_err(|e| anyhow!("Invalid amount: {}", e))?;
+    // Signed on another machine, so the nonce is looked up without holding
+    // a reservation for a transaction this process will not submit
+    let nonce = match args.nonce {
+        Some(nonce) => nonce,
+        None => nonce::peek(client.provider(), from).await?,
+    };
+    let overrides = GasOverrides {
+        gas_limit: args.gas_limit,
+        gas_price: args.gas_price.as_deref().map(parse_gas_price).transpose()?,
+        nonce: Some(nonce),
+    };
+
+    let request = client
+        .build_transfer(
+            from,
+            to,
+            amount.into(),
+            token,
+            overrides,
+            args.memo.as_deref().map(str::as_bytes),
+        )
+        .await?;
+    let description = format!(
+        "Send {} {} from 0x{:x} ({}) to 0x{:x} on {}",
+        args.value, symbol, from, wallet.name, to, config.default_network
+    );
+    let unsigned = UnsignedTx::new(request, description)?;
+    unsigned.save(&args.output)?;
+    audit::record_or_warn(
+        "transaction_built_unsigned",
+        Some(&wallet.name),
+        unsigned.description.clone(),
+    );
+
+    println!(
+        "{}: Unsigned transaction written to {}",
+        "Success".green().bold(),
+        args.output.display()
+    );
+    println!("{}", unsigned.description);
+    println!(
+        "Sign it on the offline machine with `tx sign {}`, then broadcast the result here with `tx broadcast-signed`.",
+        args.output.display()
+    );
+
+    if args.qr {
+        qr::show_animated(&offline::to_frames(&serde_json::to_string(&unsigned)?))?;
+    }
+    Ok(())
+}
+
+/// Sign an unsigned transaction with the wallet it is from. Meant for the
+/// offline machine: nothing here touches the network.
+pub async fn sign(inputs: &[PathBuf], output: &Path, show_qr: bool) -> Result<()> {
+    roles::require(Capability::Transfer)?;
+    let unsigned = UnsignedTx::read(inputs)?;
+    let request = &unsigned.request;
+    let from = request
+        .from
+        .ok_or_else(|| anyhow!("The transaction has no sender"))?;
+    // The offline machine's network must match, or a testnet flow could
+    // produce a mainnet transaction
+    let network = ConfigManager::new()?.load()?.default_network;
+    offline::check_chain_id(request.chain_id, network)?;
+    let chain_id = network.chain_id();
+
+    println!("\n{}", "Transaction to Sign".bold().underline());
+    println!("{}", unsigned.description);
+    println!(
+        "• Built: {}",
+        unsigned.created_at.format("%Y-%m-%d %H:%M UTC")
+    );
+    print_details(
+        from,
+        request.to.and_then(|to| to.to().copied()),
+        request.value.unwrap_or_default(),
+        request.input.input().cloned().unwrap_or_default(),
+        request.nonce,
+        request.gas,
+        request.gas_price,
+        Some(chain_id),
+    )?;
+    if !inquire::Confirm::new("Sign this transaction?")
+        .with_default(false)
+        .prompt()?
+    {
+        println!("{}: Transaction not signed", "Info".yellow().bold());
+        return Ok(());
+    }
+
+    let wallet_data = WalletData::load()?;
+    let (wallet, account) = wallet_data
+        .find_account(from)
+        .ok_or_else(|| anyhow!("0x{:x} is not one of your wallets", from))?;
+    let signer = match &wallet.hardware {
+        Some(hardware) => {
+            let signer = WalletSigner::trezor(&hardware.derivation_path, chain_id).await?;
+            println!("Confirm the transaction on your {}...", hardware.device);
+            signer
+        }
+        None => {
+            let private_key = unlock_wallet(
+                wallet,
+                account,
+                &format!("Enter password for {}: ", wallet.name),
+            )?;
+            let signer = PrivateKeySigner::from_str(&private_key)
+                .map_err(|e| anyhow!("Failed to create PrivateKeySigner: {}", e))?;
+            WalletSigner::local(signer)
+        }
+    };
+    if signer.address() != from {
+        return Err(anyhow!(
+            "The transaction is from 0x{:x}, but the signer has address 0x{:x}",
+            from,
+            signer.address()
+        ));
+    }
+
+    let raw = signer.sign_transaction(request.clone()).await?;
+    let signed = SignedTxFile::new(raw.clone())?;
+    if let Err(e) = signing_log::record(&SignedTx::new(signed.hash, request, raw)) {
+        log::warn!("Failed to record signed transaction: {}", e);
+    }
+    audit::record_or_warn(
+        "transaction_signed_offline",
+        Some(&wallet.name),
+        format!("0x{:x} from 0x{:x}", signed.hash, from),
+    );
+    signed.save(output)?;
+
+    println!(
+        "{}: Signed transaction 0x{:x} written to {}",
+        "Success".green().bold(),
+        signed.hash,
+        output.display()
+    );
+    if show_qr {
+        let hex = format!("0x{}", alloy::primitives::hex::encode(&signed.raw));
+        println!("\n{}", qr::render_terminal(&hex)?);
+        println!("Scan this code, or copy the file, to the online machine.");
+    }
+    Ok(())
+}
+
+/// Broadcast a transaction signed elsewhere, after showing what it does
+pub async fn broadcast_signed(input: &str) -> Result<()> {
+    roles::require(Capability::Prepare)?;
+    let raw = offline::read_signed(input)?;
+    let decoded = DecodedTx::decode(&raw)?;
+
+    let config = ConfigManager::new()?.load()?;
+    offline::check_chain_id(decoded.chain_id, config.default_network)?;
+
+    println!("\n{}", "Signed Transaction".bold().underline());
+    println!("• Hash: 0x{:x}", decoded.hash);
+    print_details(
+        decoded.from,
+        decoded.to,
+        decoded.value,
+        decoded.input.clone(),
+        Some(decoded.nonce),
+        Some(decoded.gas_limit),
+        decoded.gas_price,
+        decoded.chain_id,
+    )?;
+    if !inquire::Confirm::new("Broadcast this transaction?")
+        .with_default(false)
+        .prompt()?
+    {
+        println!("{}: Transaction not broadcast", "Info".yellow().bold());
+        return Ok(());
+    }
+
+    let client = read_client().await?;
+    let node_chain_id = client
+        .provider()
+        .get_chain_id()
+        .await
+        .map_err(|e| anyhow!("Failed to get the node's chain id: {}", e))?;
+    if decoded.chain_id != Some(node_chain_id) {
+        return Err(anyhow!(
+            "The RPC endpoint is on chain {}, not chain {} of {}; check the RPC URL",
+            node_chain_id,
+            config.default_network.chain_id(),
+            config.default_network
+        ));
+    }
+    let hash = client.send_raw(&raw).await?;
+    let request = decoded.request();
+    if let Some(pending) = PendingTx::from_request(hash, &request)
+        && let Err(e) = PendingStore::record(pending)
+    {
+        log::warn!("Failed to record pending transaction: {}", e);
+    }
+    submissions::submitted(hash, &request);
+    audit::record_or_warn(
+        "transaction_broadcast",
+        None,
+        format!("0x{:x} from 0x{:x}", hash, decoded.from),
+    );
+
+    println!(
+        "{}: Transaction broadcast: 0x{:x}",
+        "Success".green().bold(),
+        hash
+    );
+    println!("Follow it with `tx --tx-hash 0x{:x} --follow`.", hash);
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn print_details(
+    from: Address,
+    to: Option<Address>,
+    value: U256,
+    input: Bytes,
+    nonce: Option<u64>,
+    gas_limit: Option<u64>,
+    gas_price: Option<u128>,
+    chain_id: Option<u64>,
+) -> Result<()> {
+    let unknown = || "-".to_string();
+    println!("• From: 0x{:x}", from);
+    println!(
+        "• To: {}",
+        to.map(|to| format!("0x{:x}", to))
+            .unwrap_or_else(|| "(contract creation)".to_string())
+    );
+    println!("• Value: {} RBTC", format_units(value, 18)?);
+    if !input.is_empty() {
+        println!("• Data: {}", input);
+    }
+    println!(
+        "• Nonce: {}",
+        nonce.map(|n| n.to_string()).unwrap_or_else(unknown)
+    );
+    println!(
+        "• Gas: {} at {} gwei",
+        gas_limit.map(|g| g.to_string()).unwrap_or_else(unknown),
+        gas_price
+            .map(|p| format_units(U256::from(p), "gwei"))
+            .transpose()?
+            .unwrap_or_else(unknown)
+    );
+    println!(
+        "• Chain: {}",
+        chain_id.map(|c| c.to_string()).unwrap_or_else(unknown)
+    );
+    Ok(())
+}
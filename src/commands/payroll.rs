@@ -0,0 +1,679 @@
+use crate::commands::contacts::{
+    ContactsAction, ContactsCommand, ensure_memo, ensure_verified_recipient,
+};
+use crate::commands::tokens::TokenRegistry;
+use crate::config::ConfigManager;
+use crate::types::wallet::WalletData;
+use crate::utils::categories::AssetFilter;
+use crate::utils::eth::EthClient;
+use crate::utils::helper::{Config as HelperConfig, WalletConfig, unlock_wallet};
+use crate::utils::payroll::{self, BatchStatus, Payroll, PayrollBatch, RosterEntry, SignedReport};
+use crate::utils::roles::{self, Capability};
+use crate::utils::table::TableBuilder;
+use alloy::primitives::{Address, B256, utils::format_units};
+use alloy::providers::Provider;
+use alloy::signers::{SignerSync, local::PrivateKeySigner};
+use anyhow::{Context, Result, anyhow};
+use clap::{ArgGroup, Parser, Subcommand};
+use colored::Colorize;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Pay a roster of contacts in monthly batches
+#[derive(Parser, Debug)]
+pub struct PayrollCommand {
+    #[command(subcommand)]
+    pub action: PayrollAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PayrollAction {
+    /// Manage the payroll roster
+    Roster {
+        #[command(subcommand)]
+        action: RosterAction,
+    },
+    /// Generate the payout batch for a month from the roster
+    Generate {
+        /// Month to pay, as YYYY-MM (defaults to the current month)
+        #[arg(long)]
+        month: Option<String>,
+    },
+    /// List payroll batches
+    List,
+    /// Show the payouts of a batch
+    Show {
+        #[arg(long)]
+        month: Option<String>,
+    },
+    /// Review a batch and approve it for payment
+    Approve {
+        #[arg(long)]
+        month: Option<String>,
+    },
+    /// Pay an approved batch and archive its signed report
+    Execute {
+        #[arg(long)]
+        month: Option<String>,
+    },
+    /// Write the signed report of a paid batch to a file
+    Report {
+        #[arg(long)]
+        month: Option<String>,
+        /// Output file (defaults to payroll-YYYY-MM.json)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Check the signature of an exported payroll report
+    VerifyReport { file: PathBuf },
+    /// Set the multisend contract used to pay batches, or clear it
+    SetMultisend {
+        /// Contract address; omit to pay each payout separately
+        address: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RosterAction {
+    /// Add contacts to the roster or change their salary
+    #[command(group(ArgGroup::new("recipients").required(true).args(["contact", "tag"])))]
+    Set {
+        /// Contact name
+        #[arg(long)]
+        contact: Option<String>,
+        /// Add every contact with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Monthly amount in asset units
+        #[arg(long)]
+        amount: String,
+        /// Token symbol from the token registry (defaults to RBTC)
+        #[arg(long)]
+        token: Option<String>,
+    },
+    /// List the roster
+    List,
+    /// Remove a contact from the roster
+    Remove { contact: String },
+}
+
+impl PayrollCommand {
+    pub async fn execute(&self) -> Result<()> {
+        let month = |month: &Option<String>| match month {
+            Some(month) => payroll::parse_month(month),
+            None => Ok(payroll::current_month()),
+        };
+        match &self.action {
+            PayrollAction::Roster { action } => roster(action),
+            PayrollAction::Generate { month: m } => generate(&month(m)?),
+            PayrollAction::List => list(),
+            PayrollAction::Show { month: m } => {
+                print_batch(Payroll::load()?.batch(&month(m)?)?);
+                Ok(())
+            }
+            PayrollAction::Approve { month: m } => approve(&month(m)?),
+            PayrollAction::Execute { month: m } => execute_batch(&month(m)?).await,
+            PayrollAction::Report { month: m, output } => export_report(&month(m)?, output),
+            PayrollAction::VerifyReport { file } => verify_report(file),
+            PayrollAction::SetMultisend { address } => set_multisend(address.as_deref()),
+        }
+    }
+}
+
+fn load_contacts() -> Result<Vec<crate::types::contacts::Contact>> {
+    ContactsCommand {
+        action: ContactsAction::List,
+    }
+    .load_contacts()
+}
+
+/// Look up a token by symbol (or address, when several share the symbol) in
+/// the registry for the configured network
+pub(crate) fn resolve_asset(token: Option<&str>) -> Result<AssetFilter> {
+    let Some(symbol) = token else {
+        return Ok(AssetFilter::Rbtc);
+    };
+    if symbol.eq_ignore_ascii_case("RBTC") {
+        return Ok(AssetFilter::Rbtc);
+    }
+    let network = if ConfigManager::new()?.load()?.default_network.is_testnet() {
+        "testnet"
+    } else {
+        "mainnet"
+    };
+    let registry = TokenRegistry::load()
+        .map_err(|e| anyhow!("Failed to load token registry: {}", e))?;
+    let info = registry
+        .resolve(network, symbol)
+        .map_err(|e| anyhow!("{}", e))?;
+    Ok(AssetFilter::Token {
+        symbol: info.symbol.clone(),
+        address: Address::from_str(&info.address)
+            .map_err(|_| anyhow!("Invalid token address: {}", info.address))?,
+        decimals: info.decimals,
+    })
+}
+
+fn roster(action: &RosterAction) -> Result<()> {
+    match action {
+        RosterAction::Set {
+            contact,
+            tag,
+            amount,
+            token,
+        } => {
+            roles::require(Capability::Prepare)?;
+            let asset = resolve_asset(token.as_deref())?;
+            let contacts = load_contacts()?;
+            let names: Vec<String> = match (contact, tag) {
+                (Some(name), _) => {
+                    if !contacts.iter().any(|c| &c.name == name) {
+                        return Err(anyhow!("Contact '{}' not found", name));
+                    }
+                    vec![name.clone()]
+                }
+                (None, Some(tag)) => contacts
+                    .iter()
+                    .filter(|c| c.tags.iter().any(|t| t == tag))
+                    .map(|c| c.name.clone())
+                    .collect(),
+                (None, None) => unreachable!("clap requires --contact or --tag"),
+            };
+            if names.is_empty() {
+                return Err(anyhow!("No contacts match the given tag"));
+            }
+
+            let mut payroll = Payroll::load()?;
+            for name in &names {
+                payroll.set_salary(RosterEntry::new(name, amount, asset.clone())?);
+            }
+            payroll.save()?;
+            println!(
+                "{}: {} on the roster at {} {} per month",
+                "Success".green().bold(),
+                names.join(", "),
+                amount,
+                asset.symbol()
+            );
+        }
+        RosterAction::List => {
+            let payroll = Payroll::load()?;
+            if payroll.roster.is_empty() {
+                println!("{}: The payroll roster is empty", "Info".yellow().bold());
+                return Ok(());
+            }
+            let contacts = load_contacts()?;
+            let mut table = TableBuilder::new();
+            table.add_header(&["Contact", "Address", "Amount", "Asset"]);
+            for entry in &payroll.roster {
+                let address = contacts
+                    .iter()
+                    .find(|c| c.name == entry.contact)
+                    .map(|c| format!("0x{:x}", c.address))
+                    .unwrap_or_else(|| "(contact missing)".to_string());
+                table.add_row(&[
+                    &entry.contact,
+                    &address,
+                    &entry.amount,
+                    entry.asset.symbol(),
+                ]);
+            }
+            table.print();
+        }
+        RosterAction::Remove { contact } => {
+            roles::require(Capability::Prepare)?;
+            let mut payroll = Payroll::load()?;
+            payroll.remove_from_roster(contact)?;
+            payroll.save()?;
+            println!(
+                "{}: Removed {} from the roster",
+                "Success".green().bold(),
+                contact
+            );
+        }
+    }
+    Ok(())
+}
+
+fn generate(month: &str) -> Result<()> {
+    roles::require(Capability::Prepare)?;
+    let wallet_data = WalletData::load()?;
+    let wallet = wallet_data
+        .get_current_wallet()
+        .ok_or_else(|| anyhow!("No default wallet selected. Use `wallet switch` first."))?;
+
+    let mut payroll = Payroll::load()?;
+    let batch = payroll.generate(
+        month,
+        format!("0x{:x}", wallet.address),
+        &load_contacts()?,
+        roles::current_session()?.map(|s| s.name),
+    )?;
+    for payout in &batch.payouts {
+        ensure_verified_recipient(&payout.address)?;
+        // Payouts carry no memo
+        ensure_memo(&payout.address, None)?;
+    }
+    print_batch(batch);
+    payroll.save()?;
+    println!(
+        "{}: Generated the {} payroll batch; approve it with `payroll approve --month {}`",
+        "Success".green().bold(),
+        month,
+        month
+    );
+    Ok(())
+}
+
+fn list() -> Result<()> {
+    let payroll = Payroll::load()?;
+    if payroll.batches.is_empty() {
+        println!("{}: No payroll batches", "Info".yellow().bold());
+        return Ok(());
+    }
+    let mut table = TableBuilder::new();
+    table.add_header(&["Month", "Payouts", "Total", "Status", "Approved by"]);
+    for batch in &payroll.batches {
+        table.add_row(&[
+            &batch.month,
+            &batch.payouts.len().to_string(),
+            &format_totals(batch),
+            &format!("{:?}", batch.status),
+            batch.approved_by.as_deref().unwrap_or("-"),
+        ]);
+    }
+    table.print();
+    Ok(())
+}
+
+fn format_totals(batch: &PayrollBatch) -> String {
+    batch
+        .totals()
+        .iter()
+        .map(|(asset, total)| {
+            format!(
+                "{} {}",
+                format_units(*total, asset.decimals()).unwrap_or_else(|_| total.to_string()),
+                asset.symbol()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn print_batch(batch: &PayrollBatch) {
+    println!(
+        "\n{}",
+        format!("Payroll {}", batch.month).bold().underline()
+    );
+    println!("• From wallet: {}", batch.wallet);
+    println!("• Status: {:?}", batch.status);
+    if let Some(created_by) = &batch.created_by {
+        println!("• Generated by: {}", created_by);
+    }
+    if let Some(approved_by) = &batch.approved_by {
+        println!("• Approved by: {}", approved_by);
+    }
+    if let Some(error) = &batch.error {
+        println!("• Last error: {}", error.red());
+    }
+
+    let mut table = TableBuilder::new();
+    table.add_header(&["Contact", "Address", "Amount", "Asset", "Tx"]);
+    for payout in &batch.payouts {
+        table.add_row(&[
+            &payout.contact,
+            &format!("0x{:x}", payout.address),
+            &payout.formatted_amount(),
+            payout.asset.symbol(),
+            &match (payout.tx_hash, payout.pending_hash) {
+                (Some(hash), _) => format!("0x{:x}", hash),
+                (None, Some(hash)) => format!("0x{:x} (pending)", hash),
+                (None, None) => "-".to_string(),
+            },
+        ]);
+    }
+    table.print();
+    println!("• Total: {}", format_totals(batch));
+}
+
+fn approve(month: &str) -> Result<()> {
+    roles::require(Capability::SignQueued)?;
+    let session = roles::current_session()?;
+    let mut payroll = Payroll::load()?;
+    let batch = payroll.batch_mut(month)?;
+    if batch.status != BatchStatus::Draft {
+        return Err(anyhow!(
+            "The {} batch is {:?} and cannot be approved again",
+            month,
+            batch.status
+        ));
+    }
+    if let (Some(session), Some(created_by)) = (&session, &batch.created_by)
+        && &session.name == created_by
+    {
+        return Err(anyhow!(
+            "The {} batch was generated by '{}' and must be approved by another profile",
+            month,
+            created_by
+        ));
+    }
+
+    print_batch(batch);
+    if !inquire::Confirm::new("Approve this payroll batch for payment?")
+        .with_default(false)
+        .prompt()?
+    {
+        println!("{}: Batch {} not approved", "Info".yellow().bold(), month);
+        return Ok(());
+    }
+    batch.approved_by = Some(
+        session
+            .map(|s| s.name)
+            .unwrap_or_else(|| "owner".to_string()),
+    );
+    batch.status = BatchStatus::Approved;
+    payroll.save()?;
+    println!(
+        "{}: Approved the {} payroll batch",
+        "Success".green().bold(),
+        month
+    );
+    Ok(())
+}
+
+/// Pay every unpaid payout of the batch, saving progress after each
+/// transaction so a failed run can be resumed without paying anyone twice
+async fn execute_batch(month: &str) -> Result<()> {
+    roles::require(Capability::SignQueued)?;
+    let mut payroll = Payroll::load()?;
+    let batch = payroll.batch(month)?.clone();
+    batch.ensure_payable()?;
+    for payout in batch.unpaid() {
+        // The policy may have been enabled after the batch was generated
+        ensure_verified_recipient(&payout.address)?;
+        ensure_memo(&payout.address, None)?;
+    }
+
+    print_batch(&batch);
+    if !inquire::Confirm::new("Pay this batch now?")
+        .with_default(false)
+        .prompt()?
+    {
+        println!("{}: Batch {} not paid", "Info".yellow().bold(), month);
+        return Ok(());
+    }
+
+    let wallet_data = WalletData::load()?;
+    let wallet = wallet_data
+        .wallets
+        .get(&batch.wallet)
+        .ok_or_else(|| anyhow!("Wallet {} that pays this batch was not found", batch.wallet))?;
+    let private_key = unlock_wallet(
+        wallet,
+        0,
+        &format!("Enter password for wallet '{}': ", wallet.name),
+    )?;
+    let signer = PrivateKeySigner::from_str(&private_key)
+        .map_err(|e| anyhow!("Failed to create PrivateKeySigner: {}", e))?;
+
+    let config = ConfigManager::new()?.load()?;
+    let client_config = HelperConfig {
+        network: config.default_network.get_config(),
+        wallet: WalletConfig {
+            current_wallet_address: None,
+            private_key: Some(private_key),
+            mnemonic: None,
+        },
+    };
+    let eth_client = EthClient::new(&client_config, None).await?;
+
+    for (asset, _) in batch.totals() {
+        let result = pay_asset(
+            &eth_client,
+            &mut payroll,
+            month,
+            &asset,
+            config.multisend_contract,
+        )
+        .await;
+        if let Err(e) = result {
+            let batch = payroll.batch_mut(month)?;
+            batch.status = BatchStatus::Failed;
+            batch.error = Some(e.to_string());
+            payroll.save()?;
+            return Err(e.context(format!(
+                "Payroll {} is partly paid; run `payroll execute --month {}` again to pay the rest",
+                month, month
+            )));
+        }
+    }
+
+    let batch = payroll.batch_mut(month)?;
+    let report = batch.report_json()?;
+    let signature = signer
+        .sign_message_sync(report.as_bytes())
+        .map_err(|e| anyhow!("Failed to sign the payroll report: {}", e))?;
+    batch.report = Some(SignedReport {
+        report,
+        signer: signer.address(),
+        signature: format!("0x{}", hex::encode(signature.as_bytes())),
+    });
+    batch.status = BatchStatus::Executed;
+    batch.error = None;
+    payroll.save()?;
+    println!(
+        "{}: Paid the {} payroll; export the signed report with `payroll report --month {}`",
+        "Success".green().bold(),
+        month,
+        month
+    );
+    Ok(())
+}
+
+/// Pay the batch's unpaid payouts in one asset, through the multisend
+/// contract when one is configured. Each hash is saved as pending as soon
+/// as it is broadcast, so a run that fails while waiting for the receipt is
+/// resumed from that transaction instead of paying again.
+async fn pay_asset(
+    eth_client: &EthClient,
+    payroll: &mut Payroll,
+    month: &str,
+    asset: &AssetFilter,
+    multisend: Option<Address>,
+) -> Result<()> {
+    settle_pending(eth_client, payroll, month, asset).await?;
+
+    let unpaid: Vec<usize> = payroll
+        .batch(month)?
+        .payouts
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.tx_hash.is_none() && &p.asset == asset)
+        .map(|(index, _)| index)
+        .collect();
+    if unpaid.is_empty() {
+        return Ok(());
+    }
+
+    match multisend {
+        Some(contract) => {
+            let payouts = &payroll.batch(month)?.payouts;
+            let recipients = unpaid.iter().map(|&i| payouts[i].address).collect();
+            let values = unpaid.iter().map(|&i| payouts[i].value).collect();
+            let tx_hash = eth_client
+                .multisend(contract, asset.token(), recipients, values)
+                .await?;
+            let batch = payroll.batch_mut(month)?;
+            for &i in &unpaid {
+                batch.payouts[i].pending_hash = Some(tx_hash);
+            }
+            payroll.save()?;
+            if !eth_client.wait_for_receipt(tx_hash).await?.status() {
+                clear_pending(payroll, month, tx_hash)?;
+                return Err(anyhow!("Multisend transaction 0x{:x} reverted", tx_hash));
+            }
+            confirm_pending(payroll, month, tx_hash)?;
+            println!(
+                "{}: Paid {} {} payouts in 0x{:x}",
+                "Success".green().bold(),
+                unpaid.len(),
+                asset.symbol(),
+                tx_hash
+            );
+        }
+        None => {
+            for i in unpaid {
+                let payout = payroll.batch(month)?.payouts[i].clone();
+                let tx_hash = eth_client
+                    .send_transaction(payout.address, payout.value, asset.token())
+                    .await?;
+                payroll.batch_mut(month)?.payouts[i].pending_hash = Some(tx_hash);
+                payroll.save()?;
+                if !eth_client.wait_for_receipt(tx_hash).await?.status() {
+                    clear_pending(payroll, month, tx_hash)?;
+                    return Err(anyhow!(
+                        "Payout to {} (0x{:x}) reverted",
+                        payout.contact,
+                        tx_hash
+                    ));
+                }
+                confirm_pending(payroll, month, tx_hash)?;
+                println!(
+                    "{}: Paid {} {} to {}: 0x{:x}",
+                    "Success".green().bold(),
+                    payout.formatted_amount(),
+                    asset.symbol(),
+                    payout.contact,
+                    tx_hash
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Resolve the transactions a previous run broadcast but never saw mined.
+/// Mined ones mark their payouts paid, reverted or dropped ones are cleared
+/// so the payouts are sent again, and ones still waiting stop the run.
+async fn settle_pending(
+    eth_client: &EthClient,
+    payroll: &mut Payroll,
+    month: &str,
+    asset: &AssetFilter,
+) -> Result<()> {
+    for tx_hash in payroll.batch(month)?.pending_hashes(asset) {
+        match eth_client.wait_for_receipt(tx_hash).await {
+            Ok(receipt) if receipt.status() => {
+                confirm_pending(payroll, month, tx_hash)?;
+                println!(
+                    "{}: Earlier {} payroll transaction 0x{:x} was mined",
+                    "Info".yellow().bold(),
+                    asset.symbol(),
+                    tx_hash
+                );
+            }
+            Ok(_) => {
+                clear_pending(payroll, month, tx_hash)?;
+                println!(
+                    "{}: Earlier {} payroll transaction 0x{:x} reverted; sending it again",
+                    "Warning".yellow().bold(),
+                    asset.symbol(),
+                    tx_hash
+                );
+            }
+            Err(e) => {
+                let known = eth_client
+                    .provider()
+                    .get_transaction_by_hash(tx_hash)
+                    .await
+                    .map_err(|e| anyhow!("Failed to look up transaction 0x{:x}: {}", tx_hash, e))?
+                    .is_some();
+                if known {
+                    return Err(e.context(format!(
+                        "Payroll transaction 0x{:x} is still pending",
+                        tx_hash
+                    )));
+                }
+                clear_pending(payroll, month, tx_hash)?;
+                println!(
+                    "{}: Earlier {} payroll transaction 0x{:x} was dropped; sending it again",
+                    "Warning".yellow().bold(),
+                    asset.symbol(),
+                    tx_hash
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Mark the payouts waiting on `tx_hash` paid and save the batch
+fn confirm_pending(payroll: &mut Payroll, month: &str, tx_hash: B256) -> Result<()> {
+    payroll.batch_mut(month)?.confirm_pending(tx_hash);
+    payroll.save()
+}
+
+/// Clear `tx_hash` from the payouts waiting on it and save the batch
+fn clear_pending(payroll: &mut Payroll, month: &str, tx_hash: B256) -> Result<()> {
+    payroll.batch_mut(month)?.clear_pending(tx_hash);
+    payroll.save()
+}
+
+fn export_report(month: &str, output: &Option<PathBuf>) -> Result<()> {
+    let payroll = Payroll::load()?;
+    let report = payroll
+        .batch(month)?
+        .report
+        .as_ref()
+        .ok_or_else(|| anyhow!("The {} batch has not been paid yet", month))?;
+    let path = output
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("payroll-{}.json", month)));
+    std::fs::write(&path, serde_json::to_string_pretty(report)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    println!(
+        "{}: Signed payroll report written to {}",
+        "Success".green().bold(),
+        path.display()
+    );
+    Ok(())
+}
+
+fn verify_report(file: &PathBuf) -> Result<()> {
+    let content = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let report: SignedReport =
+        serde_json::from_str(&content).context("Failed to parse the payroll report")?;
+    report.verify()?;
+    println!(
+        "{}: Report signed by 0x{:x}",
+        "Verified".green().bold(),
+        report.signer
+    );
+    println!("{}", report.report);
+    Ok(())
+}
+
+fn set_multisend(address: Option<&str>) -> Result<()> {
+    roles::require(Capability::ManageWallets)?;
+    let contract = address
+        .map(|a| Address::from_str(a).map_err(|_| anyhow!("Invalid contract address: {}", a)))
+        .transpose()?;
+    let config_manager = ConfigManager::new()?;
+    let mut config = config_manager.load()?;
+    config.multisend_contract = contract;
+    config_manager.save(&config)?;
+    match contract {
+        Some(contract) => println!(
+            "{}: Payroll batches will be paid through 0x{:x}",
+            "Success".green().bold(),
+            contract
+        ),
+        None => println!(
+            "{}: Payroll payouts will be sent one by one",
+            "Success".green().bold()
+        ),
+    }
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use crate::commands::transfer::parse_gas_price;
+use crate::config::ConfigManager;
+use crate::types::wallet::WalletData;
+use crate::utils::eth::EthClient;
+use crate::utils::helper::{Config as HelperConfig, WalletConfig, unlock_wallet};
+use crate::utils::output;
+use crate::utils::pending::{PendingStore, PendingTx, min_replacement_gas_price};
+use crate::utils::roles::{self, Capability};
+use crate::utils::signer::WalletSigner;
+use crate::utils::table::TableBuilder;
+use alloy::primitives::{Address, B256, U256, utils::format_units};
+use anyhow::{Result, anyhow};
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use std::str::FromStr;
+
+/// Speed up or cancel transactions that are stuck pending
+#[derive(Parser, Debug)]
+pub struct PendingCommand {
+    #[command(subcommand)]
+    pub action: PendingAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PendingAction {
+    /// List sent transactions that are not mined yet
+    List,
+    /// Re-send a pending transaction with the same nonce and a higher gas price
+    SpeedUp {
+        /// Hash of the pending transaction
+        hash: String,
+        /// Gas price in gwei (defaults to the lowest price nodes accept)
+        #[arg(long)]
+        gas_price: Option<String>,
+    },
+    /// Replace a pending transaction with an empty transfer to yourself
+    Cancel {
+        /// Hash of the pending transaction
+        hash: String,
+        /// Gas price in gwei (defaults to the lowest price nodes accept)
+        #[arg(long)]
+        gas_price: Option<String>,
+    },
+}
+
+impl PendingCommand {
+    pub async fn execute(&self) -> Result<()> {
+        match &self.action {
+            PendingAction::List => list().await,
+            PendingAction::SpeedUp { hash, gas_price } => {
+                replace_by_hash(hash, gas_price.as_deref(), false).await
+            }
+            PendingAction::Cancel { hash, gas_price } => {
+                replace_by_hash(hash, gas_price.as_deref(), true).await
+            }
+        }
+    }
+}
+
+/// Client for the default network, without a signer
+pub(crate) async fn read_client() -> Result<EthClient> {
+    let config = ConfigManager::new()?.load()?;
+    let client_config = HelperConfig {
+        network: config.default_network.get_config(),
+        wallet: WalletConfig {
+            current_wallet_address: None,
+            private_key: None,
+            mnemonic: None,
+        },
+    };
+    EthClient::new(&client_config, None).await
+}
+
+/// Client that signs as `from`, which must be a wallet or derived account
+/// in this wallet file
+pub(crate) async fn signing_client(from: Address) -> Result<EthClient> {
+    let wallet_data = WalletData::load()?;
+    let (wallet, account) = wallet_data
+        .find_account(from)
+        .ok_or_else(|| anyhow!("0x{:x} is not one of your wallets", from))?;
+
+    let config = ConfigManager::new()?.load()?;
+    match &wallet.hardware {
+        Some(hardware) => {
+            let signer =
+                WalletSigner::trezor(&hardware.derivation_path, config.default_network.chain_id())
+                    .await?;
+            println!("Confirm the transaction on your {}...", hardware.device);
+            Ok(read_client().await?.with_signer(signer))
+        }
+        None => {
+            let private_key = unlock_wallet(
+                wallet,
+                account,
+                &format!("Enter password for {}: ", wallet.name),
+            )?;
+            let client_config = HelperConfig {
+                network: config.default_network.get_config(),
+                wallet: WalletConfig {
+                    current_wallet_address: None,
+                    private_key: Some(private_key),
+                    mnemonic: None,
+                },
+            };
+            EthClient::new(&client_config, None).await
+        }
+    }
+}
+
+/// Transactions on the default network that are still waiting to be mined
+pub async fn pending_transactions() -> Result<Vec<PendingTx>> {
+    let client = read_client().await?;
+    PendingStore::load()?.refresh(&client).await
+}
+
+/// Lowest gas price a replacement may use, or the network price when that
+/// is higher
+pub async fn suggested_gas_price(pending: &PendingTx) -> Result<u128> {
+    let network = read_client().await?.suggest_gas_price().await?.gas_price;
+    Ok(min_replacement_gas_price(pending.gas_price).max(network))
+}
+
+/// Speed up (or, with `cancel`, cancel) a pending transaction. Returns the
+/// hash of the replacement.
+pub async fn replace(pending: &PendingTx, gas_price: Option<u128>, cancel: bool) -> Result<B256> {
+    roles::require(Capability::Transfer)?;
+    let minimum = min_replacement_gas_price(pending.gas_price);
+    let gas_price = match gas_price {
+        Some(price) if price < minimum => {
+            return Err(anyhow!(
+                "The replacement needs a gas price of at least {} gwei",
+                format_gwei(minimum)
+            ));
+        }
+        Some(price) => price,
+        None => suggested_gas_price(pending).await?,
+    };
+
+    let client = signing_client(pending.from).await?;
+    client.replace_transaction(pending, gas_price, cancel).await
+}
+
+pub fn format_gwei(wei: u128) -> String {
+    format_units(U256::from(wei), "gwei").unwrap_or_else(|_| wei.to_string())
+}
+
+/// What a pending transaction does
+fn summary(pending: &PendingTx) -> String {
+    if pending.is_cancellation() {
+        "Cancellation".to_string()
+    } else if !pending.input.is_empty() {
+        format!("Contract call to 0x{:x}", pending.to)
+    } else {
+        format!(
+            "{} RBTC to 0x{:x}",
+            format_units(pending.value, 18).unwrap_or_else(|_| pending.value.to_string()),
+            pending.to
+        )
+    }
+}
+
+/// One-line description of a pending transaction
+pub fn describe(pending: &PendingTx) -> String {
+    format!(
+        "Nonce {}: {} at {} gwei, sent {}",
+        pending.nonce,
+        summary(pending),
+        format_gwei(pending.gas_price),
+        pending.submitted_at.format("%Y-%m-%d %H:%M")
+    )
+}
+
+async fn list() -> Result<()> {
+    let pending = pending_transactions().await?;
+    if pending.is_empty() {
+        println!("{}: No pending transactions", "Info".yellow().bold());
+        return output::emit(&pending);
+    }
+
+    let mut table = TableBuilder::new();
+    table.add_header(&["Hash", "From", "Nonce", "Transaction", "Gas Price (gwei)"]);
+    for tx in &pending {
+        table.add_row(&[
+            &format!("0x{:x}", tx.hash),
+            &format!("0x{:x}", tx.from),
+            &tx.nonce.to_string(),
+            &summary(tx),
+            &format_gwei(tx.gas_price),
+        ]);
+    }
+    table.print();
+    output::emit(&pending)
+}
+
+async fn replace_by_hash(hash: &str, gas_price: Option<&str>, cancel: bool) -> Result<()> {
+    let hash = B256::from_str(hash).map_err(|_| anyhow!("Invalid transaction hash: {}", hash))?;
+    let pending = pending_transactions()
+        .await?
+        .into_iter()
+        .find(|tx| tx.hash == hash)
+        .ok_or_else(|| anyhow!("Transaction 0x{:x} is not pending", hash))?;
+    let gas_price = gas_price.map(parse_gas_price).transpose()?;
+
+    let replacement = replace(&pending, gas_price, cancel).await?;
+    println!(
+        "{}: {} 0x{:x} with 0x{:x} (nonce {})",
+        "Success".green().bold(),
+        if cancel { "Cancelling" } else { "Sped up" },
+        pending.hash,
+        replacement,
+        pending.nonce
+    );
+    Ok(())
+}
@@ -0,0 +1,383 @@
+use crate::api::prices::{Currency, Prices, format_fiat};
+use crate::commands::tokens::TokenRegistry;
+use crate::config::ConfigManager;
+use crate::types::wallet::{Wallet, WalletData};
+use crate::utils::eth::EthClient;
+use crate::utils::helper::Helper;
+use crate::utils::output;
+use crate::utils::table::TableBuilder;
+use alloy::primitives::{Address, U256, utils::format_units};
+use anyhow::{Result, anyhow};
+use clap::Parser;
+use colored::Colorize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+
+/// Total value of RBTC and the registry tokens across every wallet
+#[derive(Parser, Debug, Clone)]
+pub struct PortfolioCommand {
+    /// Currency to value holdings in (usd | eur). Defaults to the display
+    /// currency, or USD when none is set
+    #[arg(long, value_parser = parse_currency)]
+    pub currency: Option<Currency>,
+}
+
+/// An asset held by the wallets: RBTC or a registry token
+#[derive(Debug, Clone)]
+struct Asset {
+    symbol: String,
+    /// `None` for RBTC
+    token: Option<Address>,
+    decimals: u8,
+}
+
+/// Balances of one asset across the wallets
+#[derive(Debug, Clone)]
+struct AssetHoldings {
+    asset: Asset,
+    by_wallet: Vec<(String, U256)>,
+}
+
+impl AssetHoldings {
+    fn total(&self) -> U256 {
+        self.by_wallet
+            .iter()
+            .fold(U256::ZERO, |sum, (_, balance)| sum.saturating_add(*balance))
+    }
+}
+
+impl PortfolioCommand {
+    pub async fn execute(&self) -> Result<()> {
+        let config = ConfigManager::new()?.load()?;
+        let network = config.default_network;
+        let currency = self
+            .currency
+            .or(config.display_currency)
+            .unwrap_or(Currency::Usd);
+
+        let wallet_data = WalletData::load()?;
+        if wallet_data.wallets.is_empty() {
+            return Err(anyhow!(
+                "No wallets found. Please create or import a wallet first."
+            ));
+        }
+        let mut wallets: Vec<Wallet> = wallet_data.wallets.into_values().collect();
+        wallets.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut assets = vec![Asset {
+            symbol: "RBTC".to_string(),
+            token: None,
+            decimals: 18,
+        }];
+        let mut tokens = TokenRegistry::load()
+            .unwrap_or_default()
+            .list_tokens(Some(network.chain_name()));
+        tokens.sort_by(|a, b| a.0.cmp(&b.0));
+        for (symbol, info) in tokens {
+            if let Ok(token) = Address::from_str(&info.address) {
+                assets.push(Asset {
+                    symbol,
+                    token: Some(token),
+                    decimals: info.decimals,
+                });
+            }
+        }
+
+        let (_, client) = Helper::init_eth_client(network.chain_name()).await?;
+        let (holdings, failed) = fetch_holdings(Arc::new(client), &wallets, &assets).await;
+        let token_addresses: Vec<Address> = assets.iter().filter_map(|a| a.token).collect();
+        let prices = Prices::fetch_in(currency, network, &token_addresses).await;
+
+        println!(
+            "\n{} on {} ({} wallet(s))",
+            "Portfolio".bold(),
+            network,
+            wallets.len()
+        );
+        print_holdings(&holdings, prices.as_ref());
+        print_assets(&holdings, prices.as_ref());
+
+        match &prices {
+            Some(prices) => {
+                let (total, change) = totals(&holdings, prices);
+                let change = match change {
+                    Some(change) if total > 0.0 => format!(
+                        " ({}, {} over 24h)",
+                        percent(change / (total - change) * 100.0),
+                        format_fiat(change, currency)
+                    ),
+                    _ => String::new(),
+                };
+                println!(
+                    "\n{} {}{}",
+                    "Total:".bold(),
+                    format_fiat(total, currency).green().bold(),
+                    change
+                );
+            }
+            None => println!(
+                "\n{}",
+                "Prices are only available on mainnet; balances are not valued.".dimmed()
+            ),
+        }
+        if failed > 0 {
+            println!(
+                "{}",
+                format!(
+                    "⚠️  {} balance lookup(s) failed and are counted as zero",
+                    failed
+                )
+                .yellow()
+            );
+        }
+        output::emit(&portfolio_json(
+            &network.to_string(),
+            currency,
+            &holdings,
+            prices.as_ref(),
+            failed,
+        ))
+    }
+}
+
+/// The portfolio for `--json`: balances in base units by asset and wallet,
+/// and the total value when prices are known
+fn portfolio_json(
+    network: &str,
+    currency: Currency,
+    holdings: &[AssetHoldings],
+    prices: Option<&Prices>,
+    failed: usize,
+) -> serde_json::Value {
+    let assets: Vec<_> = holdings
+        .iter()
+        .map(|holding| {
+            let wallets: serde_json::Map<String, serde_json::Value> = holding
+                .by_wallet
+                .iter()
+                .map(|(wallet, balance)| (wallet.clone(), balance.to_string().into()))
+                .collect();
+            serde_json::json!({
+                "symbol": holding.asset.symbol,
+                "token": holding.asset.token.map(|token| format!("{:#x}", token)),
+                "decimals": holding.asset.decimals,
+                "total": holding.total().to_string(),
+                "wallets": wallets,
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "network": network,
+        "currency": currency,
+        "assets": assets,
+        "total_value": prices.map(|prices| totals(holdings, prices).0),
+        "failed_lookups": failed,
+    })
+}
+
+/// Balances of every asset for every wallet, looked up concurrently, and the
+/// number of lookups that failed
+async fn fetch_holdings(
+    client: Arc<EthClient>,
+    wallets: &[Wallet],
+    assets: &[Asset],
+) -> (Vec<AssetHoldings>, usize) {
+    let mut lookups = JoinSet::new();
+    for (asset_index, asset) in assets.iter().enumerate() {
+        for (wallet_index, wallet) in wallets.iter().enumerate() {
+            let client = client.clone();
+            let (address, token) = (wallet.address, asset.token);
+            lookups.spawn(async move {
+                let balance = client.get_balance(&address, &token).await;
+                (asset_index, wallet_index, balance)
+            });
+        }
+    }
+
+    let mut balances = vec![vec![U256::ZERO; wallets.len()]; assets.len()];
+    let mut failed = 0;
+    while let Some(result) = lookups.join_next().await {
+        match result {
+            Ok((asset, wallet, Ok(balance))) => balances[asset][wallet] = balance,
+            Ok((asset, wallet, Err(e))) => {
+                log::warn!(
+                    "Failed to fetch the {} balance of {}: {}",
+                    assets[asset].symbol,
+                    wallets[wallet].name,
+                    e
+                );
+                failed += 1;
+            }
+            Err(e) => {
+                log::warn!("Balance lookup did not finish: {}", e);
+                failed += 1;
+            }
+        }
+    }
+
+    let holdings = assets
+        .iter()
+        .zip(balances)
+        .map(|(asset, balances)| AssetHoldings {
+            asset: asset.clone(),
+            by_wallet: wallets
+                .iter()
+                .map(|wallet| wallet.name.clone())
+                .zip(balances)
+                .collect(),
+        })
+        .collect();
+    (holdings, failed)
+}
+
+/// Each wallet's non-zero balances
+fn print_holdings(holdings: &[AssetHoldings], prices: Option<&Prices>) {
+    let mut rows: BTreeMap<(String, String), Vec<String>> = BTreeMap::new();
+    for holding in holdings {
+        for (wallet, balance) in &holding.by_wallet {
+            if balance.is_zero() {
+                continue;
+            }
+            let amount = amount(*balance, holding.asset.decimals);
+            let mut row = vec![
+                wallet.clone(),
+                holding.asset.symbol.clone(),
+                format_units(*balance, holding.asset.decimals).unwrap_or_default(),
+            ];
+            row.extend(prices.map(|prices| {
+                prices
+                    .value(holding.asset.token, amount)
+                    .unwrap_or_else(|| "-".to_string())
+            }));
+            rows.insert((wallet.clone(), holding.asset.symbol.clone()), row);
+        }
+    }
+    if rows.is_empty() {
+        println!("No balances found.");
+        return;
+    }
+
+    let mut table = TableBuilder::new();
+    let mut header = vec!["Wallet", "Asset", "Balance"];
+    header.extend(prices.map(|_| "Value"));
+    table.add_header(&header);
+    for row in rows.values() {
+        table.add_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+    table.print();
+}
+
+/// Totals per asset, with prices and their change over the last day
+fn print_assets(holdings: &[AssetHoldings], prices: Option<&Prices>) {
+    let held: Vec<&AssetHoldings> = holdings.iter().filter(|h| !h.total().is_zero()).collect();
+    if held.is_empty() {
+        return;
+    }
+    let total_value = prices.map(|prices| totals(holdings, prices).0);
+
+    println!("\n{}", "By asset".bold());
+    let mut table = TableBuilder::new();
+    let mut header = vec!["Asset", "Balance"];
+    if prices.is_some() {
+        header.extend(["Price", "24h", "Value", "Share"]);
+    }
+    table.add_header(&header);
+    for holding in held {
+        let token = holding.asset.token;
+        let balance = holding.total();
+        let mut row = vec![
+            holding.asset.symbol.clone(),
+            format_units(balance, holding.asset.decimals).unwrap_or_default(),
+        ];
+        if let Some(prices) = prices {
+            let value = prices
+                .price(token)
+                .map(|price| price * amount(balance, holding.asset.decimals));
+            row.push(
+                prices
+                    .price(token)
+                    .map_or("-".to_string(), |price| format_fiat(price, prices.currency)),
+            );
+            row.push(prices.change_24h(token).map_or("-".to_string(), percent));
+            row.push(value.map_or("-".to_string(), |value| format_fiat(value, prices.currency)));
+            row.push(match (value, total_value) {
+                (Some(value), Some(total)) if total > 0.0 => {
+                    format!("{:.1}%", value / total * 100.0)
+                }
+                _ => "-".to_string(),
+            });
+        }
+        table.add_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+    table.print();
+}
+
+/// Total value of the holdings, and how much of it was gained or lost over
+/// the last day for the assets whose change is known
+fn totals(holdings: &[AssetHoldings], prices: &Prices) -> (f64, Option<f64>) {
+    let mut total = 0.0;
+    let mut change = None;
+    for holding in holdings {
+        let token = holding.asset.token;
+        let Some(price) = prices.price(token) else {
+            continue;
+        };
+        let value = price * amount(holding.total(), holding.asset.decimals);
+        total += value;
+        if let Some(percent) = prices.change_24h(token) {
+            // Value a day ago was value / (1 + change)
+            let gained = value - value / (1.0 + percent / 100.0);
+            change = Some(change.unwrap_or(0.0) + gained);
+        }
+    }
+    (total, change)
+}
+
+fn amount(balance: U256, decimals: u8) -> f64 {
+    format_units(balance, decimals)
+        .ok()
+        .and_then(|amount| amount.parse().ok())
+        .unwrap_or_default()
+}
+
+fn percent(change: f64) -> String {
+    format!("{:+.2}%", change)
+}
+
+fn parse_currency(value: &str) -> Result<Currency, String> {
+    Currency::from_str(value).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_holdings_total_and_amount() {
+        let holding = AssetHoldings {
+            asset: Asset {
+                symbol: "DOC".to_string(),
+                token: Some(Address::repeat_byte(7)),
+                decimals: 18,
+            },
+            by_wallet: vec![
+                ("alice".to_string(), U256::from(10u64.pow(18))),
+                ("bob".to_string(), U256::from(5 * 10u64.pow(17))),
+            ],
+        };
+        assert_eq!(holding.total(), U256::from(15 * 10u64.pow(17)));
+        assert_eq!(amount(holding.total(), 18), 1.5);
+        assert_eq!(amount(U256::from(1_234_567u64), 6), 1.234567);
+        assert_eq!(percent(-0.5), "-0.50%");
+        assert_eq!(percent(2.0), "+2.00%");
+
+        let json = portfolio_json("testnet", Currency::Usd, &[holding], None, 1);
+        assert_eq!(json["currency"], "usd");
+        assert_eq!(json["assets"][0]["total"], "1500000000000000000");
+        assert_eq!(json["assets"][0]["wallets"]["bob"], "500000000000000000");
+        assert_eq!(json["total_value"], serde_json::Value::Null);
+        assert_eq!(json["failed_lookups"], 1);
+    }
+}
@@ -0,0 +1,76 @@
+use crate::config::profile::{self, DEFAULT};
+use crate::utils::table::TableBuilder;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+
+/// Manage configuration profiles, each with its own network, API keys,
+/// wallets, contacts and token registry
+#[derive(Parser, Debug)]
+pub struct ProfileCommand {
+    #[command(subcommand)]
+    pub action: ProfileAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ProfileAction {
+    /// List profiles
+    List,
+    /// Create an empty profile, e.g. `work` or `testing`
+    Create { name: String },
+    /// Delete a profile with its configuration, wallets and contacts
+    Delete {
+        name: String,
+        /// Delete without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Show the profile in use
+    Current,
+}
+
+impl ProfileCommand {
+    pub async fn execute(&self) -> Result<()> {
+        match &self.action {
+            ProfileAction::List => {
+                let mut table = TableBuilder::new();
+                table.add_header(&["Profile", "In use"]);
+                for name in profile::list()? {
+                    let in_use = if name == profile::active() { "✅" } else { "" };
+                    table.add_row(&[&name, in_use]);
+                }
+                table.print();
+            }
+            ProfileAction::Create { name } => {
+                profile::create(name)?;
+                println!(
+                    "{}: Created profile '{}'. Use it with `--profile {}`; it is set up on first use.",
+                    "Success".green().bold(),
+                    name,
+                    name
+                );
+            }
+            ProfileAction::Delete { name, yes } => {
+                if !profile::exists(name)? || name == DEFAULT {
+                    // Let `delete` explain why
+                    return profile::delete(name);
+                }
+                if !*yes
+                    && !inquire::Confirm::new(&format!(
+                        "Delete profile '{}' with its wallets and contacts? Back up any keys you still need first.",
+                        name
+                    ))
+                    .with_default(false)
+                    .prompt()?
+                {
+                    println!("Profile kept");
+                    return Ok(());
+                }
+                profile::delete(name)?;
+                println!("{}: Deleted profile '{}'", "Success".green().bold(), name);
+            }
+            ProfileAction::Current => println!("{}", profile::active()),
+        }
+        Ok(())
+    }
+}
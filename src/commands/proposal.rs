@@ -0,0 +1,203 @@
+use crate::commands::transfer::TransferCommand;
+use crate::config::ConfigManager;
+use crate::interactive::transfer_preview;
+use crate::types::network::Network;
+use crate::utils::roles::{self, Capability};
+use alloy::primitives::Address;
+use anyhow::{Context, Result, anyhow};
+use clap::Parser;
+use colored::Colorize;
+use console::style;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::str::FromStr;
+
+/// A transaction proposed by an external tool for human approval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionProposal {
+    /// Recipient address
+    pub to: String,
+    /// Amount in RBTC or token units (e.g. "0.5")
+    pub value: String,
+    /// Token contract address for ERC20 transfers
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Network the proposal was built for (mainnet/testnet)
+    #[serde(default)]
+    pub network: Option<String>,
+    /// Free-form description shown to the approver
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Name of the tool or person that created the proposal
+    #[serde(default)]
+    pub proposer: Option<String>,
+}
+
+impl TransactionProposal {
+    /// Read a proposal from a file path, or from stdin when the path is `-`
+    pub fn load(source: &str) -> Result<Self> {
+        let content = if source == "-" {
+            let mut buf = String::new();
+            std::io::stdin()
+                .read_to_string(&mut buf)
+                .context("Failed to read proposal from stdin")?;
+            buf
+        } else {
+            std::fs::read_to_string(source)
+                .with_context(|| format!("Failed to read proposal file {}", source))?
+        };
+        let proposal: Self =
+            serde_json::from_str(&content).context("Failed to parse transaction proposal")?;
+        proposal.validate()?;
+        Ok(proposal)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        Address::from_str(&self.to)
+            .map_err(|_| anyhow!("Invalid recipient address: {}", self.to))?;
+        if let Some(token) = &self.token {
+            Address::from_str(token).map_err(|_| anyhow!("Invalid token address: {}", token))?;
+        }
+        let value = self
+            .value
+            .parse::<f64>()
+            .map_err(|_| anyhow!("Invalid amount: {}", self.value))?;
+        if value.is_nan() || value <= 0.0 {
+            return Err(anyhow!("Amount must be greater than zero"));
+        }
+        Ok(())
+    }
+
+    /// Ensure the proposal targets the network the wallet is configured for
+    pub fn check_network(&self, network: Network) -> Result<()> {
+        if let Some(proposed) = &self.network {
+            let proposed = Network::from_str(proposed)
+                .ok_or_else(|| anyhow!("Unknown network in proposal: {}", proposed))?;
+            if proposed.chain_id() != network.chain_id() {
+                return Err(anyhow!(
+                    "Proposal is for {} but the wallet is configured for {}",
+                    proposed,
+                    network
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Review, then sign and broadcast, a transaction proposal
+#[derive(Parser, Debug)]
+pub struct ApproveCommand {
+    /// Proposal JSON file, or `-` to read from stdin
+    pub source: String,
+}
+
+impl ApproveCommand {
+    pub async fn execute(&self) -> Result<()> {
+        roles::require(Capability::SignQueued)?;
+        let proposal = TransactionProposal::load(&self.source)?;
+        let config = ConfigManager::new()?.load()?;
+        proposal.check_network(config.default_network)?;
+
+        println!("\n{}", style("📨 Transaction Proposal").bold());
+        println!("{}", "=".repeat(30));
+        if let Some(proposer) = &proposal.proposer {
+            println!("• Proposed by: {}", style(proposer).cyan());
+        }
+        if let Some(description) = &proposal.description {
+            println!("• Description: {}", description);
+        }
+
+        // Parsed once; the preview and the transfer use these exact units
+        let units: alloy::primitives::U256 =
+            alloy::primitives::utils::parse_units(&proposal.value, 18)
+                .map_err(|e| anyhow!("Invalid amount: {}", e))?
+                .into();
+        let token = proposal
+            .token
+            .as_deref()
+            .map(Address::from_str)
+            .transpose()
+            .map_err(|_| anyhow!("Invalid token address"))?;
+        if let Some(token) = &proposal.token {
+            println!("• Token: {}", style(token).cyan());
+        }
+        let gas_price = transfer_preview::show_transaction_preview(
+            &proposal.to,
+            &units.to_string(),
+            token,
+            None,
+            config.default_network,
+        )
+        .await?;
+        let Some(gas_price) = gas_price else {
+            println!("{}", "Proposal rejected".yellow());
+            return Ok(());
+        };
+
+        let cmd = TransferCommand {
+            address: proposal.to.clone(),
+            // Only displayed; the amount sent is `units`
+            value: proposal.value.parse::<f64>()?,
+            token: proposal.token.clone(),
+            gas_limit: None,
+            // Pay the price quoted in the preview
+            gas_price: Some(alloy::primitives::utils::format_units(gas_price, "gwei")?),
+            nonce: None,
+            account: None,
+            memo: None,
+            cost_center: None,
+            units: Some(units),
+        };
+        let result = cmd.send().await?;
+
+        println!(
+            "\n{}: Proposal executed. Tx Hash: 0x{:x}",
+            "Success".green().bold(),
+            result.tx_hash
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_and_check_proposal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("proposal.json");
+        std::fs::write(
+            &path,
+            r#"{"to": "0x70997970c51812dc3a010c7d01b50e0d17dc79c8", "value": "0.5",
+                "network": "testnet", "proposer": "payouts-bot"}"#,
+        )
+        .unwrap();
+        let proposal = TransactionProposal::load(path.to_str().unwrap()).unwrap();
+        assert_eq!(proposal.value, "0.5");
+        assert_eq!(proposal.proposer.as_deref(), Some("payouts-bot"));
+        assert!(proposal.check_network(Network::Testnet).is_ok());
+        assert!(proposal.check_network(Network::Mainnet).is_err());
+
+        let build = |to: &str, value: &str, token: Option<&str>| TransactionProposal {
+            to: to.to_string(),
+            value: value.to_string(),
+            token: token.map(str::to_string),
+            network: None,
+            description: None,
+            proposer: None,
+        };
+        let to = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+        assert!(build(to, "0.5", None).validate().is_ok());
+        assert!(build("bob", "0.5", None).validate().is_err());
+        assert!(build(to, "0", None).validate().is_err());
+        assert!(build(to, "-1", None).validate().is_err());
+        assert!(build(to, "NaN", None).validate().is_err());
+        assert!(build(to, "0.5", Some("rif")).validate().is_err());
+
+        std::fs::write(&path, r#"{"to": "bob", "value": "1"}"#).unwrap();
+        assert!(TransactionProposal::load(path.to_str().unwrap()).is_err());
+        assert!(TransactionProposal::load("/nonexistent/proposal.json").is_err());
+    }
+}
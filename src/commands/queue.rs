@@ -0,0 +1,569 @@
+use crate::commands::payroll::resolve_asset;
+use crate::config::ConfigManager;
+use crate::types::wallet::WalletData;
+use crate::utils::categories::AssetFilter;
+use crate::utils::confirmations::ConfirmationPolicy;
+use crate::utils::eth::EthClient;
+use crate::utils::helper::{Config as HelperConfig, WalletConfig, unlock_wallet};
+use crate::utils::notifications;
+use crate::utils::roles::{self, Capability, RoleStore};
+use crate::utils::storage::Storage;
+use crate::utils::table::TableBuilder;
+use alloy::primitives::{Address, B256, U256, utils::format_units};
+use alloy::providers::Provider;
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeZone};
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// When a queued transfer becomes eligible for execution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Schedule {
+    /// Execute at or after a specific local time
+    At(DateTime<Local>),
+    /// Execute once the chain reaches the given block
+    AfterBlock(u64),
+}
+
+impl Schedule {
+    /// Parse a schedule from user input.
+    ///
+    /// Accepts `HH:MM` (next occurrence of that time), `YYYY-MM-DD HH:MM`,
+    /// an RFC 3339 timestamp, or `block:N`.
+    pub fn parse(input: &str, now: DateTime<Local>) -> Result<Self> {
+        let input = input.trim();
+        if let Some(block) = input.strip_prefix("block:") {
+            let block = block
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| anyhow!("Invalid block number: {}", block))?;
+            return Ok(Schedule::AfterBlock(block));
+        }
+        if let Ok(time) = NaiveTime::parse_from_str(input, "%H:%M") {
+            let today = now.date_naive().and_time(time);
+            let mut at = Local
+                .from_local_datetime(&today)
+                .earliest()
+                .ok_or_else(|| anyhow!("Invalid local time: {}", input))?;
+            if at <= now {
+                at += chrono::Duration::days(1);
+            }
+            return Ok(Schedule::At(at));
+        }
+        if let Ok(dt) = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M") {
+            let at = Local
+                .from_local_datetime(&dt)
+                .earliest()
+                .ok_or_else(|| anyhow!("Invalid local time: {}", input))?;
+            return Ok(Schedule::At(at));
+        }
+        if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+            return Ok(Schedule::At(dt.with_timezone(&Local)));
+        }
+        Err(anyhow!(
+            "Invalid schedule '{}'. Use HH:MM, YYYY-MM-DD HH:MM or block:N",
+            input
+        ))
+    }
+
+    pub fn is_due(&self, now: DateTime<Local>, block: u64) -> bool {
+        match self {
+            Schedule::At(at) => now >= *at,
+            Schedule::AfterBlock(n) => block >= *n,
+        }
+    }
+}
+
+impl std::fmt::Display for Schedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Schedule::At(at) => write!(f, "at {}", at.format("%Y-%m-%d %H:%M")),
+            Schedule::AfterBlock(n) => write!(f, "after block {}", n),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueStatus {
+    Pending,
+    Executed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedTransfer {
+    pub id: u64,
+    /// Wallet (address key) that will sign the transfer
+    pub wallet: String,
+    pub to: Address,
+    pub value: U256,
+    pub token: Option<Address>,
+    /// Decimals of the token, or of RBTC; `value` is in its smallest unit
+    #[serde(default = "default_decimals")]
+    pub decimals: u8,
+    pub schedule: Schedule,
+    pub status: QueueStatus,
+    pub created_at: DateTime<Local>,
+    #[serde(default)]
+    pub tx_hash: Option<B256>,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Profile that queued the transfer, when role profiles are in use
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queued_by: Option<String>,
+    /// Approver that reviewed the transfer; required before it is signed
+    /// while role profiles are in use
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_by: Option<String>,
+}
+
+/// Transfers queued before decimals were stored were all parsed with 18
+fn default_decimals() -> u8 {
+    18
+}
+
+impl QueuedTransfer {
+    /// The amount in whole tokens or RBTC
+    pub fn formatted_amount(&self) -> String {
+        format_units(self.value, self.decimals).unwrap_or_else(|_| self.value.to_string())
+    }
+
+    /// Symbol-less description of the asset
+    pub fn asset(&self) -> String {
+        self.token
+            .map(|a| format!("token 0x{:x}", a))
+            .unwrap_or_else(|| "RBTC".to_string())
+    }
+}
+
+/// Persistent queue of scheduled outgoing transfers
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TransferQueue {
+    #[serde(default)]
+    pub transfers: Vec<QueuedTransfer>,
+}
+
+impl TransferQueue {
+    pub fn load() -> Result<Self> {
+        Storage::open()?.load_queue()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        Storage::open()?.save_queue(self)
+    }
+
+    pub fn add(
+        &mut self,
+        wallet: String,
+        to: Address,
+        value: U256,
+        asset: &AssetFilter,
+        schedule: Schedule,
+        queued_by: Option<String>,
+    ) -> u64 {
+        let id = self.transfers.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+        self.transfers.push(QueuedTransfer {
+            id,
+            wallet,
+            to,
+            value,
+            token: asset.token(),
+            decimals: asset.decimals(),
+            schedule,
+            status: QueueStatus::Pending,
+            created_at: Local::now(),
+            tx_hash: None,
+            error: None,
+            queued_by,
+            approved_by: None,
+        });
+        id
+    }
+
+    pub fn remove(&mut self, id: u64) -> Result<()> {
+        let index = self
+            .transfers
+            .iter()
+            .position(|t| t.id == id)
+            .ok_or_else(|| anyhow!("Queued transfer #{} not found", id))?;
+        self.transfers.remove(index);
+        Ok(())
+    }
+
+    pub fn pending(&self) -> impl Iterator<Item = &QueuedTransfer> {
+        self.transfers
+            .iter()
+            .filter(|t| t.status == QueueStatus::Pending)
+    }
+}
+
+#[derive(Parser, Debug)]
+pub struct QueueCommand {
+    #[command(subcommand)]
+    pub action: QueueAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum QueueAction {
+    /// Queue a transfer from the current wallet
+    Add {
+        /// Recipient address
+        #[arg(long)]
+        address: String,
+        /// Amount in RBTC or token units
+        #[arg(long)]
+        value: String,
+        /// Token symbol or address from the token registry (for ERC20 transfers)
+        #[arg(long)]
+        token: Option<String>,
+        /// HH:MM, "YYYY-MM-DD HH:MM" or block:N
+        #[arg(long)]
+        when: String,
+    },
+    /// List queued transfers
+    List,
+    /// Remove a queued transfer
+    Remove { id: u64 },
+    /// Review a queued transfer and approve it for signing
+    Approve { id: u64 },
+    /// Watch the queue and execute transfers as they become due
+    Run {
+        /// Seconds between checks (defaults to the configured queue interval)
+        #[arg(long)]
+        interval: Option<u64>,
+    },
+}
+
+impl QueueCommand {
+    pub async fn execute(&self) -> Result<()> {
+        match &self.action {
+            QueueAction::Add {
+                address,
+                value,
+                token,
+                when,
+            } => self.add(address, value, token.as_deref(), when),
+            QueueAction::List => self.list(),
+            QueueAction::Remove { id } => {
+                roles::require(Capability::Prepare)?;
+                let mut queue = TransferQueue::load()?;
+                queue.remove(*id)?;
+                queue.save()?;
+                println!(
+                    "{}: Removed queued transfer #{}",
+                    "Success".green().bold(),
+                    id
+                );
+                Ok(())
+            }
+            QueueAction::Approve { id } => self.approve(*id),
+            QueueAction::Run { interval } => self.run(*interval).await,
+        }
+    }
+
+    fn approve(&self, id: u64) -> Result<()> {
+        roles::require(Capability::SignQueued)?;
+        let session = roles::current_session()?;
+        let mut queue = TransferQueue::load()?;
+        let transfer = queue
+            .transfers
+            .iter_mut()
+            .find(|t| t.id == id && t.status == QueueStatus::Pending)
+            .ok_or_else(|| anyhow!("No pending transfer #{}", id))?;
+        if let (Some(session), Some(queued_by)) = (&session, &transfer.queued_by)
+            && &session.name == queued_by
+        {
+            return Err(anyhow!(
+                "Transfer #{} was queued by '{}' and must be approved by another profile",
+                id,
+                queued_by
+            ));
+        }
+
+        println!("\n{}", "Queued Transfer".bold().underline());
+        println!("• From wallet: {}", transfer.wallet);
+        println!("• To: 0x{:x}", transfer.to);
+        println!(
+            "• Amount: {} ({})",
+            transfer.formatted_amount(),
+            transfer.asset()
+        );
+        println!("• Schedule: {}", transfer.schedule);
+        if let Some(queued_by) = &transfer.queued_by {
+            println!("• Queued by: {}", queued_by);
+        }
+        if !inquire::Confirm::new("Approve this transfer for signing?")
+            .with_default(false)
+            .prompt()?
+        {
+            println!("{}: Transfer #{} not approved", "Info".yellow().bold(), id);
+            return Ok(());
+        }
+
+        transfer.approved_by = Some(
+            session
+                .map(|s| s.name)
+                .unwrap_or_else(|| "owner".to_string()),
+        );
+        queue.save()?;
+        println!(
+            "{}: Approved queued transfer #{}",
+            "Success".green().bold(),
+            id
+        );
+        Ok(())
+    }
+
+    fn add(&self, address: &str, value: &str, token: Option<&str>, when: &str) -> Result<()> {
+        roles::require(Capability::Prepare)?;
+        let wallet_data = load_wallet_data()?;
+        let wallet = wallet_data
+            .get_current_wallet()
+            .ok_or_else(|| anyhow!("No default wallet selected. Use `wallet switch` first."))?;
+
+        let to = Address::from_str(address)
+            .map_err(|_| anyhow!("Invalid recipient address: {}", address))?;
+        crate::commands::contacts::ensure_verified_recipient(&to)?;
+        // Queued transfers carry no memo
+        crate::commands::contacts::ensure_memo(&to, None)?;
+        let asset = resolve_asset(token)?;
+        let value: U256 = alloy::primitives::utils::parse_units(value, asset.decimals())
+            .map_err(|e| anyhow!("Invalid amount: {}", e))?
+            .into();
+        let schedule = Schedule::parse(when, Local::now())?;
+
+        let mut queue = TransferQueue::load()?;
+        let id = queue.add(
+            format!("0x{:x}", wallet.address),
+            to,
+            value,
+            &asset,
+            schedule.clone(),
+            roles::current_session()?.map(|s| s.name),
+        );
+        queue.save()?;
+
+        println!(
+            "{}: Queued transfer #{} to 0x{:x} {}",
+            "Success".green().bold(),
+            id,
+            to,
+            schedule
+        );
+        Ok(())
+    }
+
+    fn list(&self) -> Result<()> {
+        let queue = TransferQueue::load()?;
+        if queue.transfers.is_empty() {
+            println!("{}: No queued transfers", "Info".yellow().bold());
+            return Ok(());
+        }
+
+        let mut table = TableBuilder::new();
+        table.add_header(&["ID", "To", "Amount", "Token", "Schedule", "Status", "Tx"]);
+        for t in &queue.transfers {
+            table.add_row(&[
+                &t.id.to_string(),
+                &format!("0x{:x}", t.to),
+                &t.formatted_amount(),
+                &t.token
+                    .map(|a| format!("0x{:x}", a))
+                    .unwrap_or_else(|| "RBTC".to_string()),
+                &t.schedule.to_string(),
+                &match &t.approved_by {
+                    Some(approver) if t.status == QueueStatus::Pending => {
+                        format!("Pending (approved by {})", approver)
+                    }
+                    _ => format!("{:?}", t.status),
+                },
+                &t.tx_hash
+                    .map(|h| format!("0x{:x}", h))
+                    .or_else(|| t.error.clone())
+                    .unwrap_or_else(|| "-".to_string()),
+            ]);
+        }
+        table.print();
+        Ok(())
+    }
+
+    /// Unlock the current wallet once, then execute due transfers until interrupted
+    async fn run(&self, interval: Option<u64>) -> Result<()> {
+        roles::require(Capability::SignQueued)?;
+        // With role profiles, only transfers reviewed by an approver are signed
+        let needs_approval = RoleStore::load()?.is_enabled();
+        let wallet_data = load_wallet_data()?;
+        let wallet = wallet_data
+            .get_current_wallet()
+            .ok_or_else(|| anyhow!("No default wallet selected. Use `wallet switch` first."))?;
+        let wallet_key = format!("0x{:x}", wallet.address);
+
+        let private_key = unlock_wallet(wallet, 0, "Enter password for the default wallet: ")?;
+
+        let config = ConfigManager::new()?.load()?;
+        let interval = interval.unwrap_or(config.polling.queue_poll_interval_secs);
+        let client_config = HelperConfig {
+            network: config.default_network.get_config(),
+            wallet: WalletConfig {
+                current_wallet_address: None,
+                private_key: Some(private_key),
+                mnemonic: None,
+            },
+        };
+        let eth_client = EthClient::new(&client_config, None).await?;
+        let policy = ConfirmationPolicy::current();
+
+        println!(
+            "{}: Watching transfer queue for {} (Ctrl+C to stop)",
+            "Info".blue().bold(),
+            wallet.name
+        );
+
+        loop {
+            let block = eth_client.provider().get_block_number().await.unwrap_or(0);
+            let now = Local::now();
+
+            let mut queue = TransferQueue::load()?;
+            let due: Vec<u64> = queue
+                .pending()
+                .filter(|t| t.wallet == wallet_key && t.schedule.is_due(now, block))
+                .filter(|t| !needs_approval || t.approved_by.is_some())
+                .map(|t| t.id)
+                .collect();
+
+            for id in due {
+                let Some(transfer) = queue.transfers.iter_mut().find(|t| t.id == id) else {
+                    continue;
+                };
+                let result = async {
+                    // The policy may have been enabled after the transfer was queued
+                    crate::commands::contacts::ensure_verified_recipient(&transfer.to)?;
+                    crate::commands::contacts::ensure_memo(&transfer.to, None)?;
+                    let tx_hash = eth_client
+                        .send_transaction(transfer.to, transfer.value, transfer.token)
+                        .await?;
+                    let receipt = eth_client.wait_for_receipt(tx_hash).await?;
+                    if !receipt.status() {
+                        return Err(anyhow!("Transaction 0x{:x} reverted", tx_hash));
+                    }
+                    // Only report the transfer once it is final under the policy
+                    let required = policy.required(transfer.token, transfer.value);
+                    if let Some(included) = receipt.block_number {
+                        eth_client
+                            .wait_for_confirmations(included, required)
+                            .await?;
+                    }
+                    Ok((tx_hash, required))
+                }
+                .await;
+
+                match result {
+                    Ok((tx_hash, confirmations)) => {
+                        transfer.status = QueueStatus::Executed;
+                        transfer.tx_hash = Some(tx_hash);
+                        println!(
+                            "🔔 {}: Queued transfer #{} executed: 0x{:x} ({} confirmations)",
+                            "Executed".green().bold(),
+                            id,
+                            tx_hash,
+                            confirmations
+                        );
+                    }
+                    Err(e) => {
+                        transfer.status = QueueStatus::Failed;
+                        transfer.error = Some(e.to_string());
+                        println!(
+                            "🔔 {}: Queued transfer #{} failed: {}",
+                            "Failed".red().bold(),
+                            id,
+                            e
+                        );
+                        notifications::queued_transfer_failed(id, &e.to_string());
+                    }
+                }
+                queue.save()?;
+            }
+
+            if queue.pending().all(|t| t.wallet != wallet_key) {
+                println!("{}: No pending transfers left", "Info".blue().bold());
+                return Ok(());
+            }
+
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+        }
+    }
+}
+
+fn load_wallet_data() -> Result<WalletData> {
+    let wallet_data = WalletData::load()?;
+    if wallet_data.wallets.is_empty() {
+        return Err(anyhow!(
+            "No wallets found. Please create or import a wallet first."
+        ));
+    }
+    Ok(wallet_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_schedule() {
+        let now = Local.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        assert_eq!(
+            Schedule::parse("block:1500", now).unwrap(),
+            Schedule::AfterBlock(1500)
+        );
+
+        // A time earlier than now rolls over to the next day
+        match Schedule::parse("02:00", now).unwrap() {
+            Schedule::At(at) => {
+                assert_eq!(at, Local.with_ymd_and_hms(2024, 1, 2, 2, 0, 0).unwrap())
+            }
+            other => panic!("unexpected schedule {:?}", other),
+        }
+
+        assert!(Schedule::parse("tomorrow", now).is_err());
+    }
+
+    #[test]
+    fn test_schedule_is_due() {
+        let now = Local.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        assert!(Schedule::AfterBlock(10).is_due(now, 10));
+        assert!(!Schedule::AfterBlock(11).is_due(now, 10));
+        assert!(Schedule::At(now).is_due(now, 0));
+    }
+
+    #[test]
+    fn test_queued_amounts_use_token_decimals() {
+        let usdt = AssetFilter::Token {
+            symbol: "USDT".to_string(),
+            address: Address::repeat_byte(7),
+            decimals: 6,
+        };
+        let mut queue = TransferQueue::default();
+        let id = queue.add(
+            "0xwallet".to_string(),
+            Address::repeat_byte(1),
+            U256::from(1_500_000u64),
+            &usdt,
+            Schedule::AfterBlock(1),
+            None,
+        );
+        assert_eq!(queue.transfers[0].formatted_amount(), "1.500000");
+
+        // Transfers queued before decimals were stored used 18
+        let mut stored = serde_json::to_value(&queue.transfers[0]).unwrap();
+        stored.as_object_mut().unwrap().remove("decimals");
+        let old: QueuedTransfer = serde_json::from_value(stored).unwrap();
+        assert_eq!(old.id, id);
+        assert_eq!(old.decimals, 18);
+    }
+}
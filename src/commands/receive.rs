@@ -0,0 +1,103 @@
+use crate::commands::tokens::TokenRegistry;
+use crate::config::ConfigManager;
+use crate::qr::{self, PaymentRequest};
+use crate::types::wallet::WalletData;
+use alloy::primitives::Address;
+use anyhow::{Result, anyhow};
+use clap::Parser;
+use colored::Colorize;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Show a payment request QR code for the current wallet
+#[derive(Parser, Debug)]
+pub struct ReceiveCommand {
+    /// Amount to request (in tokens or RBTC); any amount if omitted
+    #[arg(long)]
+    pub value: Option<f64>,
+
+    /// Token address to request instead of RBTC
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Account of the current wallet to receive to (see `wallet add-account`)
+    #[arg(long)]
+    pub account: Option<u32>,
+
+    /// Also save the QR code as a PNG image
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+}
+
+impl ReceiveCommand {
+    pub async fn execute(&self) -> Result<()> {
+        let wallet_data = WalletData::load()?;
+        let wallet = wallet_data.get_current_wallet().ok_or_else(|| {
+            anyhow!(
+                "No default wallet selected. Please use 'wallet switch' to select a default wallet."
+            )
+        })?;
+        let account = self.account.unwrap_or(0);
+        let recipient = wallet
+            .account_address(account)
+            .ok_or_else(|| anyhow!("'{}' has no account #{}", wallet.name, account))?;
+
+        let config = ConfigManager::new()?.load()?;
+        let network = config.default_network.chain_name();
+        let token = match self.token.as_deref() {
+            None | Some("0x0000000000000000000000000000000000000000") => None,
+            Some(token) => Some(
+                Address::from_str(token)
+                    .map_err(|_| anyhow!("Invalid token address: {}", token))?,
+            ),
+        };
+
+        // Amounts are requested in base units, using the registry's decimals
+        let (symbol, decimals) = match token {
+            None => ("RBTC".to_string(), 18),
+            Some(token) => TokenRegistry::load()
+                .unwrap_or_default()
+                .list_tokens(Some(network))
+                .into_iter()
+                .find(|(_, info)| Address::from_str(&info.address).ok() == Some(token))
+                .map(|(_, info)| (info.symbol, info.decimals))
+                .unwrap_or_else(|| (format!("0x{:x}", token), 18)),
+        };
+        let value = self
+            .value
+            .map(|value| {
+                alloy::primitives::utils::parse_units(&value.to_string(), decimals)
+                    .map(Into::into)
+                    .map_err(|e| anyhow!("Invalid amount: {}", e))
+            })
+            .transpose()?;
+
+        let request = PaymentRequest {
+            recipient,
+            chain_id: config.default_network.chain_id(),
+            value,
+            token,
+        };
+        let uri = request.uri();
+
+        println!("\n{}", qr::render_terminal(&uri)?);
+        println!("Address: 0x{:x}", recipient);
+        match self.value {
+            Some(value) => println!("Amount:  {} {}", value, symbol),
+            None => println!("Amount:  any ({})", symbol),
+        }
+        println!("Network: {}", config.default_network);
+        println!("URI:     {}", uri);
+
+        if let Some(output) = &self.output {
+            qr::generate_qr_code(&uri, &output.to_string_lossy())
+                .map_err(|e| anyhow!("Failed to save the QR code: {}", e))?;
+            println!(
+                "{}: QR code saved to {}",
+                "Success".green().bold(),
+                output.display()
+            );
+        }
+        Ok(())
+    }
+}
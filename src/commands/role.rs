@@ -0,0 +1,82 @@
+use crate::utils::roles::{self, Capability, Role, RoleStore};
+use crate::utils::table::TableBuilder;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+
+/// Manage role profiles for separation of duties
+#[derive(Parser, Debug)]
+pub struct RoleCommand {
+    #[command(subcommand)]
+    pub action: RoleAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RoleAction {
+    /// Add a profile; the first one must be an owner
+    Add {
+        name: String,
+        #[arg(long, value_enum)]
+        role: Role,
+    },
+    /// List profiles
+    List,
+    /// Remove a profile
+    Remove { name: String },
+    /// Show the signed-in profile
+    Whoami,
+}
+
+impl RoleCommand {
+    pub async fn execute(&self) -> Result<()> {
+        match &self.action {
+            RoleAction::Add { name, role } => {
+                let mut store = RoleStore::load()?;
+                if store.is_enabled() {
+                    roles::require(Capability::ManageRoles)?;
+                }
+                let passphrase = inquire::Password::new(&format!("Passphrase for '{}':", name))
+                    .with_display_mode(inquire::PasswordDisplayMode::Masked)
+                    .with_custom_confirmation_message("Confirm passphrase:")
+                    .with_custom_confirmation_error_message("The passphrases don't match.")
+                    .prompt()?;
+                store.add(name, *role, &passphrase)?;
+                store.save()?;
+                println!(
+                    "{}: Added {} profile '{}'",
+                    "Success".green().bold(),
+                    role,
+                    name
+                );
+            }
+            RoleAction::List => {
+                let store = RoleStore::load()?;
+                if !store.is_enabled() {
+                    println!(
+                        "{}: No profiles; every action is allowed",
+                        "Info".yellow().bold()
+                    );
+                    return Ok(());
+                }
+                let mut table = TableBuilder::new();
+                table.add_header(&["Profile", "Role"]);
+                for profile in &store.profiles {
+                    table.add_row(&[&profile.name, &profile.role.to_string()]);
+                }
+                table.print();
+            }
+            RoleAction::Remove { name } => {
+                roles::require(Capability::ManageRoles)?;
+                let mut store = RoleStore::load()?;
+                store.remove(name)?;
+                store.save()?;
+                println!("{}: Removed profile '{}'", "Success".green().bold(), name);
+            }
+            RoleAction::Whoami => match roles::current_session()? {
+                Some(session) => println!("{} ({})", session.name, session.role),
+                None => println!("No profiles are set up; every action is allowed"),
+            },
+        }
+        Ok(())
+    }
+}
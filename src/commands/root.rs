@@ -1,61 +1,136 @@
+use crate::commands::address::AddressCommand;
 use crate::commands::api::SetApiKeyCommand;
-use crate::commands::contacts::ContactsCommand;
+use crate::commands::audit_export::AuditExportCommand;
+use crate::commands::balance::BalanceCommand;
+use crate::commands::canary::CanaryCommand;
+use crate::commands::config::ConfigCommand;
+use crate::commands::contacts::{ContactsAction, ContactsCommand};
+use crate::commands::contract::ContractCommand;
+use crate::commands::dashboard::DashboardCommand;
+use crate::commands::history::HistoryCommand;
+use crate::commands::payroll::PayrollCommand;
+use crate::commands::pending::{PendingAction, PendingCommand};
+use crate::commands::portfolio::PortfolioCommand;
+use crate::commands::profile::ProfileCommand;
+use crate::commands::proposal::ApproveCommand;
+use crate::commands::queue::QueueCommand;
+use crate::commands::receive::ReceiveCommand;
+use crate::commands::role::RoleCommand;
+use crate::commands::sign::SignCommand;
+use crate::commands::stats::StatsCommand;
+use crate::commands::sync::SyncCommand;
 use crate::commands::tokens::{TokenAddCommand, TokenListCommand, TokenRemoveCommand};
-use crate::commands::wallet::WalletCommand;
-use clap::Parser;
+use crate::commands::transfer::TransferCommand;
+use crate::commands::tui::TuiCommand;
+use crate::commands::tx::TxCommand;
+use crate::commands::update::UpdateCommand;
+use crate::commands::validate::ValidateCommand;
+use crate::commands::wallet::{WalletAction, WalletCommand};
+use crate::commands::watch::WatchCommand;
+use crate::commands::webhook::WebhookCommand;
+use crate::utils::output;
+use anyhow::{Result, anyhow};
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use std::path::PathBuf;
 
+/// Rootstock wallet. Runs the interactive menus when no command is given.
 #[derive(Parser, Debug)]
+#[command(name = "rootstock-wallet", version)]
+pub struct Cli {
+    /// Mask addresses and amounts in the output, e.g. while screen sharing
+    #[arg(long, global = true)]
+    pub redact: bool,
+
+    /// Configuration profile to use (see `profile list`); also read from
+    /// ROOTSTOCK_WALLET_PROFILE
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    /// Write the result of the command as JSON to stdout, and everything
+    /// else it prints to stderr
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Run the commands in FILE one after another with a single unlock: one
+    /// command line per line, or a YAML playbook; `-` reads stdin
+    #[arg(long, value_name = "FILE")]
+    pub batch: Option<PathBuf>,
+
+    /// Run the rest of a batch after a command fails
+    #[arg(long, requires = "batch")]
+    pub continue_on_error: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+#[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum Commands {
     /// Manage wallets
     Wallet(WalletCommand),
     /// Manage contacts
     Contacts(ContactsCommand),
     /// Show transaction history
-    History {
-        #[arg(short, long, default_value = "10")]
-        limit: usize,
-        #[arg(short, long)]
-        address: Option<String>,
-        #[arg(short, long)]
-        token: Option<String>,
-        #[arg(short, long)]
-        status: Option<String>,
-        #[arg(short, long)]
-        incoming: bool,
-        #[arg(short, long)]
-        outgoing: bool,
-        /// Alchemy API key (optional, saved in wallet after first use)
-        #[arg(long)]
-        api_key: Option<String>,
-        #[arg(long, default_value = "mainnet")]
-        network: String,
-    },
+    History(HistoryCommand),
+    /// Show activity statistics from the local history cache
+    Stats(StatsCommand),
+    /// Build the local history cache from the node's logs; `--full` scans
+    /// the whole chain, resuming between runs
+    Sync(SyncCommand),
     /// Check balance of an address
-    Balance {
-        /// Network to use (mainnet/testnet)
-        #[arg(long, default_value = "mainnet")]
-        network: String,
-        /// Token symbol to check balance for (e.g., RBTC, RIF, DoC)
-        #[arg(long)]
-        token: Option<String>,
-        /// Address to check balance for (optional if using default wallet)
-        #[arg(long)]
-        address: Option<String>,
-    },
-    /// Transfer RBTC or tokens
-    Transfer {
-        /// Address to send to
-        #[arg(long, required = true)]
-        address: String,
-        /// Amount to send (in RBTC or token units)
-        #[arg(long, required = true)]
-        value: f64,
-        /// Token address (for ERC20 transfers)
-        #[arg(long)]
-        token: Option<String>,
-        #[arg(short, long, default_value = "mainnet")]
-        network: String,
-    },
+    Balance(BalanceCommand),
+    /// Value RBTC and the registry tokens across every wallet
+    Portfolio(PortfolioCommand),
+    /// Transfer RBTC or tokens from the current wallet
+    Transfer(TransferCommand),
+    /// Show a payment request QR code for the current wallet
+    Receive(ReceiveCommand),
+    /// Send a large transfer in two steps, starting with a tiny canary
+    Canary(CanaryCommand),
+    /// Check the status of a transaction, or build, sign and broadcast one
+    /// across an air gap
+    Tx(TxCommand),
+    /// Speed up or cancel transactions that are stuck pending
+    Pending(PendingCommand),
+    /// Review, sign and broadcast a transaction proposal
+    Approve(ApproveCommand),
+    /// Sign a message with the current wallet to prove address ownership
+    Sign(SignCommand),
+
+    /// Export a read-only HTML dashboard
+    Dashboard(DashboardCommand),
+    /// Full-screen dashboard with balances, transactions, pending
+    /// operations and network status
+    Tui(TuiCommand),
+    /// Schedule transfers for later execution
+    Queue(QueueCommand),
+    /// Manage owner, operator and approver profiles
+    Role(RoleCommand),
+    /// Pay a roster of contacts in approved monthly batches
+    Payroll(PayrollCommand),
+    /// Export signed transactions of a period, encrypted for an auditor
+    AuditExport(AuditExportCommand),
+
+    /// Manage Alchemy Notify webhooks and the webhooks wallet activity is sent to
+    Webhook(WebhookCommand),
+    /// Stream decoded contract events or incoming payments live
+    Watch(WatchCommand),
+    /// Call or send any contract method through its ABI
+    Contract(ContractCommand),
+
+    /// Convert an address to lowercase, checksummed and bytes32 forms
+    Address(AddressCommand),
+    /// Check a list of addresses, e.g. before loading a payout CSV
+    Validate(ValidateCommand),
+
+    /// Check for a newer, signed release
+    Update(UpdateCommand),
+    /// Show, change or export the configuration
+    Config(ConfigCommand),
+    /// Manage configuration profiles (e.g. work, personal, testing)
+    Profile(ProfileCommand),
 
     SetApiKey(SetApiKeyCommand),
 
@@ -68,3 +143,127 @@ pub enum Commands {
     /// List tokens in the registry
     TokenList(TokenListCommand),
 }
+
+impl Commands {
+    pub async fn execute(&self) -> Result<()> {
+        if output::is_json() && !self.supports_json() {
+            return Err(anyhow!("This command does not support --json output"));
+        }
+        match self {
+            Commands::Wallet(cmd) => cmd.execute().await,
+            Commands::Contacts(cmd) => cmd.execute().await,
+            Commands::History(cmd) => cmd.execute().await,
+            Commands::Stats(cmd) => cmd.execute().await,
+            Commands::Sync(cmd) => cmd.execute().await,
+            Commands::Balance(cmd) => cmd.execute().await,
+            Commands::Portfolio(cmd) => cmd.execute().await,
+            Commands::Transfer(cmd) => {
+                let result = cmd.execute().await?;
+                println!(
+                    "{}: Transaction sent. Tx Hash: 0x{:x}",
+                    "Success".green().bold(),
+                    result.tx_hash
+                );
+                output::emit(&result.to_json())
+            }
+            Commands::Receive(cmd) => cmd.execute().await,
+            Commands::Canary(cmd) => cmd.execute().await,
+            Commands::Tx(cmd) if cmd.follow => {
+                let outcome = cmd.follow().await?;
+                output::emit(&serde_json::json!({
+                    "hash": cmd.hash(),
+                    "outcome": outcome.as_str(),
+                }))?;
+                std::process::exit(outcome.exit_code());
+            }
+            Commands::Tx(cmd) => cmd.execute().await,
+            Commands::Pending(cmd) => cmd.execute().await,
+            Commands::Approve(cmd) => cmd.execute().await,
+            Commands::Sign(cmd) => cmd.execute().await,
+            Commands::Dashboard(cmd) => cmd.execute().await,
+            Commands::Tui(cmd) => cmd.execute().await,
+            Commands::Queue(cmd) => cmd.execute().await,
+            Commands::Role(cmd) => cmd.execute().await,
+            Commands::Payroll(cmd) => cmd.execute().await,
+            Commands::AuditExport(cmd) => cmd.execute().await,
+            Commands::Webhook(cmd) => cmd.execute().await,
+            Commands::Watch(cmd) => cmd.execute().await,
+            Commands::Contract(cmd) => cmd.execute().await,
+            Commands::Address(cmd) => cmd.execute().await,
+            Commands::Validate(cmd) => cmd.execute().await,
+            Commands::Update(cmd) => cmd.execute().await,
+            Commands::Config(cmd) => cmd.execute().await,
+            Commands::Profile(cmd) => cmd.execute().await,
+            Commands::SetApiKey(cmd) => cmd.execute().await,
+            Commands::TokenAdd(cmd) => cmd.execute().await,
+            Commands::TokenRemove(cmd) => cmd.execute().await,
+            Commands::TokenList(cmd) => cmd.execute().await,
+        }
+    }
+
+    /// Whether the command writes its result with `output::emit`; the others
+    /// only print for people and are refused in JSON mode
+    fn supports_json(&self) -> bool {
+        match self {
+            Commands::Wallet(cmd) => matches!(cmd.action, WalletAction::List),
+            Commands::Contacts(cmd) => matches!(
+                cmd.action,
+                ContactsAction::List | ContactsAction::Get { .. } | ContactsAction::Search { .. }
+            ),
+            Commands::Tx(cmd) => cmd.action.is_none(),
+            Commands::Pending(cmd) => matches!(cmd.action, PendingAction::List),
+            Commands::History(_)
+            | Commands::Stats(_)
+            | Commands::Balance(_)
+            | Commands::Portfolio(_)
+            | Commands::Transfer(_)
+            | Commands::Sign(_)
+            | Commands::Watch(_)
+            | Commands::Address(_)
+            | Commands::Validate(_)
+            | Commands::TokenList(_) => true,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(std::iter::once("rootstock-wallet").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn test_dispatch_parsing() {
+        Cli::command().debug_assert();
+        assert!(parse(&[]).unwrap().command.is_none());
+
+        let cli = parse(&["--json", "wallet", "list"]).unwrap();
+        assert!(cli.json);
+        let command = cli.command.unwrap();
+        assert!(matches!(
+            &command,
+            Commands::Wallet(cmd) if matches!(cmd.action, WalletAction::List)
+        ));
+        assert!(command.supports_json());
+
+        let cli = parse(&["token-add", "-s", "RIF", "-a", "0x2acc", "-n", "testnet"]).unwrap();
+        match cli.command.unwrap() {
+            Commands::TokenAdd(cmd) => {
+                assert_eq!((cmd.symbol.as_str(), cmd.network.as_str()), ("RIF", "testnet"));
+                assert_eq!(cmd.decimals, 18);
+            }
+            other => panic!("unexpected command {:?}", other),
+        }
+
+        let command = parse(&["wallet", "create", "main", "secret"]).unwrap().command.unwrap();
+        assert!(!command.supports_json());
+
+        assert!(parse(&["--continue-on-error"]).is_err());
+        assert!(parse(&["token-add", "-s", "RIF"]).is_err());
+        assert!(parse(&["no-such-command"]).is_err());
+    }
+}
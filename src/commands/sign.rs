@@ -0,0 +1,163 @@
+use crate::config::ConfigManager;
+use crate::types::wallet::WalletData;
+use crate::utils::audit;
+use crate::utils::helper::unlock_wallet;
+use crate::utils::output;
+use crate::utils::roles::{self, Capability};
+use alloy::primitives::{Address, PrimitiveSignature};
+use alloy::signers::trezor::{HDPath, TrezorSigner};
+use alloy::signers::{Signer, SignerSync, local::PrivateKeySigner};
+use anyhow::{Result, anyhow};
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Sign data with the current wallet
+#[derive(Parser, Debug)]
+pub struct SignCommand {
+    #[command(subcommand)]
+    pub action: SignAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SignAction {
+    /// Sign a text message with EIP-191 personal_sign, e.g. to prove to a
+    /// dApp or exchange that you control the address
+    Message {
+        /// Text to sign
+        #[arg(required_unless_present = "file")]
+        message: Option<String>,
+        /// Sign the contents of a file instead
+        #[arg(long, conflicts_with = "message")]
+        file: Option<PathBuf>,
+        /// Account of the current wallet to sign with (see `wallet add-account`)
+        #[arg(long)]
+        account: Option<u32>,
+    },
+}
+
+/// A personal_sign signature and the address that made it
+#[derive(Debug, Clone)]
+pub struct SignedMessage {
+    pub address: Address,
+    pub signature: PrimitiveSignature,
+}
+
+impl SignedMessage {
+    /// 65-byte `r || s || v` signature in hex, as dApps and exchanges expect
+    pub fn signature_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.signature.as_bytes()))
+    }
+}
+
+impl SignCommand {
+    pub async fn execute(&self) -> Result<()> {
+        match &self.action {
+            SignAction::Message {
+                message,
+                file,
+                account,
+            } => {
+                let message = match (message, file) {
+                    (_, Some(file)) => std::fs::read_to_string(file).map_err(|e| {
+                        anyhow!("Failed to read {}: {}", file.display(), e)
+                    })?,
+                    (Some(message), None) => message.clone(),
+                    (None, None) => return Err(anyhow!("Give a message or --file to sign")),
+                };
+                let signed = sign_message(&message, account.unwrap_or(0)).await?;
+                // `--json` prints the address, message and signature together
+                if output::is_json() {
+                    output::emit(&serde_json::json!({
+                        "address": format!("{:#x}", signed.address),
+                        "message": message,
+                        "signature": signed.signature_hex(),
+                    }))?;
+                } else {
+                    println!("{}: 0x{:x}", "Address".bold(), signed.address);
+                    println!("{}: {}", "Signature".bold(), signed.signature_hex());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Sign `message` with EIP-191 personal_sign using `account` of the current
+/// wallet. Hardware wallets show the message on the device for confirmation.
+pub async fn sign_message(message: &str, account: u32) -> Result<SignedMessage> {
+    roles::require(Capability::Transfer)?;
+    let wallet_data = WalletData::load()?;
+    let wallet = wallet_data.get_current_wallet().ok_or_else(|| {
+        anyhow!("No default wallet selected. Please use 'wallet switch' to select a default wallet.")
+    })?;
+    let address = wallet
+        .account_address(account)
+        .ok_or_else(|| anyhow!("'{}' has no account #{}", wallet.name, account))?;
+
+    let signature = match &wallet.hardware {
+        Some(hardware) => {
+            let chain_id = ConfigManager::new()?.load()?.default_network.chain_id();
+            let trezor = TrezorSigner::new(
+                HDPath::Other(hardware.derivation_path.clone()),
+                Some(chain_id),
+            )
+            .await
+            .map_err(|e| anyhow!("Failed to connect to Trezor: {}", e))?;
+            if trezor.address() != address {
+                return Err(anyhow!(
+                    "The connected {} has address 0x{:x}, not 0x{:x}",
+                    hardware.device,
+                    trezor.address(),
+                    address
+                ));
+            }
+            println!("Confirm the message on your {}...", hardware.device);
+            trezor
+                .sign_message(message.as_bytes())
+                .await
+                .map_err(|e| anyhow!("Failed to sign the message: {}", e))?
+        }
+        None => {
+            let private_key = unlock_wallet(
+                wallet,
+                account,
+                &format!("Enter password for wallet '{}': ", wallet.name),
+            )?;
+            PrivateKeySigner::from_str(&private_key)
+                .map_err(|e| anyhow!("Failed to create PrivateKeySigner: {}", e))?
+                .sign_message_sync(message.as_bytes())
+                .map_err(|e| anyhow!("Failed to sign the message: {}", e))?
+        }
+    };
+
+    audit::record_or_warn(
+        "message_signed",
+        Some(&wallet.name),
+        format!("{} bytes from 0x{:x}", message.len(), address),
+    );
+    Ok(SignedMessage { address, signature })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_hex_recovers_signer() {
+        let signer = PrivateKeySigner::random();
+        let signed = SignedMessage {
+            address: signer.address(),
+            signature: signer.sign_message_sync(b"I own this address").unwrap(),
+        };
+        let hex = signed.signature_hex();
+        assert_eq!(hex.len(), 2 + 130);
+
+        let signature = PrimitiveSignature::from_str(&hex).unwrap();
+        assert_eq!(
+            signature.recover_address_from_msg("I own this address").unwrap(),
+            signed.address
+        );
+    }
+}
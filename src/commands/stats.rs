@@ -0,0 +1,360 @@
+use crate::config::ConfigManager;
+use crate::types::transaction::RskTransaction;
+use crate::types::wallet::WalletData;
+use crate::utils::categories;
+use crate::utils::cost_centers;
+use crate::utils::history_cache::HistoryCache;
+use crate::utils::output;
+use crate::utils::table::TableBuilder;
+use alloy::primitives::{Address, U256};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Datelike, Local, Timelike};
+use clap::Parser;
+use colored::Colorize;
+use console::style;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Show activity statistics computed from the local history cache
+#[derive(Parser, Debug, Clone)]
+pub struct StatsCommand {
+    /// Address to show statistics for (defaults to the current wallet)
+    #[arg(short, long)]
+    pub address: Option<String>,
+
+    /// Number of counterparties to list
+    #[arg(long, default_value = "5")]
+    pub top: usize,
+}
+
+/// Aggregated usage statistics for a single address
+#[derive(Debug, Default)]
+pub struct ActivityStats {
+    /// Transaction counts indexed by [weekday][hour] in local time
+    pub heatmap: [[u32; 24]; 7],
+    /// Counterparty address with number of transactions and volume
+    pub counterparties: Vec<(Address, u64, U256)>,
+    pub total: usize,
+    pub outgoing: usize,
+    /// Outgoing transactions whose fee is known from their receipt
+    pub priced: usize,
+    pub total_fees: U256,
+}
+
+impl ActivityStats {
+    pub fn compute(address: Address, txs: &[RskTransaction]) -> Self {
+        let mut stats = Self {
+            total: txs.len(),
+            ..Default::default()
+        };
+        let mut counterparties: HashMap<Address, (u64, U256)> = HashMap::new();
+
+        for tx in txs {
+            let time: DateTime<Local> = tx.timestamp.into();
+            let day = time.weekday().num_days_from_monday() as usize;
+            stats.heatmap[day][time.hour() as usize] += 1;
+
+            let counterparty = if tx.from == address { tx.to } else { Some(tx.from) };
+            if let Some(counterparty) = counterparty {
+                let entry = counterparties.entry(counterparty).or_default();
+                entry.0 += 1;
+                entry.1 = entry.1.saturating_add(tx.value);
+            }
+
+            // Only the sender pays fees
+            if tx.from == address {
+                stats.outgoing += 1;
+                if let Some(fee) = tx.fee() {
+                    stats.priced += 1;
+                    stats.total_fees = stats.total_fees.saturating_add(fee);
+                }
+            }
+        }
+
+        stats.counterparties = counterparties
+            .into_iter()
+            .map(|(addr, (count, volume))| (addr, count, volume))
+            .collect();
+        stats
+            .counterparties
+            .sort_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)));
+        stats
+    }
+
+    /// The statistics for `--json`, amounts in wei and the heatmap as seven
+    /// rows (Monday first) of 24 hourly counts
+    pub fn to_json(&self, address: Address, top: usize) -> serde_json::Value {
+        serde_json::json!({
+            "address": format!("{:#x}", address),
+            "transactions": self.total,
+            "outgoing": self.outgoing,
+            "average_fee": self.average_fee().to_string(),
+            "heatmap": self.heatmap,
+            "counterparties": self
+                .counterparties
+                .iter()
+                .take(top)
+                .map(|(address, count, volume)| {
+                    serde_json::json!({
+                        "address": format!("{:#x}", address),
+                        "transactions": count,
+                        "volume": volume.to_string(),
+                    })
+                })
+                .collect::<Vec<_>>(),
+            "categories": [],
+            "cost_centers": [],
+        })
+    }
+
+    /// Average fee of the outgoing transactions whose fee is known
+    pub fn average_fee(&self) -> U256 {
+        if self.priced == 0 {
+            U256::ZERO
+        } else {
+            self.total_fees / U256::from(self.priced)
+        }
+    }
+}
+
+impl StatsCommand {
+    pub async fn execute(&self) -> Result<()> {
+        let address = if let Some(addr) = &self.address {
+            Address::from_str(addr).map_err(|_| anyhow!("Invalid address format: {}", addr))?
+        } else {
+            let wallet_data = WalletData::load()?;
+            if wallet_data.wallets.is_empty() {
+                return Err(anyhow!(
+                    "No wallets found. Please create or import a wallet first."
+                ));
+            }
+            wallet_data
+                .get_current_wallet()
+                .ok_or_else(|| anyhow!("No default wallet selected. Use `wallet switch` first."))?
+                .address
+        };
+
+        let config = ConfigManager::new()?.load()?;
+        let cache = HistoryCache::load()?;
+        let txs = cache.get(config.default_network.chain_name(), &address);
+        if txs.is_empty() {
+            println!(
+                "{}",
+                "⚠️  No cached transactions. Open Transaction History first to populate the cache."
+                    .yellow()
+            );
+            return output::emit(&ActivityStats::default().to_json(address, self.top));
+        }
+
+        let stats = ActivityStats::compute(address, &txs);
+
+        println!("\n{}", style("📊 Wallet Activity").bold().underlined());
+        println!("• Transactions: {}", style(stats.total).cyan());
+        println!("• Outgoing: {}", style(stats.outgoing).cyan());
+        println!(
+            "• Average Fee: {} RBTC",
+            style(
+                alloy::primitives::utils::format_units(stats.average_fee(), 18)
+                    .unwrap_or_else(|_| "N/A".to_string())
+            )
+            .yellow()
+        );
+
+        // Heatmap: one row per weekday, one column per hour
+        println!("\n{}", style("Activity by weekday/hour").bold());
+        println!("     {}", (0..24).map(|h| format!("{:<2}", h % 10)).collect::<String>());
+        let max = stats
+            .heatmap
+            .iter()
+            .flat_map(|row| row.iter())
+            .copied()
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        for (day, row) in stats.heatmap.iter().enumerate() {
+            let cells: String = row
+                .iter()
+                .map(|&count| {
+                    let shade = match count * 4 / max {
+                        0 if count == 0 => "· ",
+                        0 | 1 => "░░",
+                        2 => "▒▒",
+                        3 => "▓▓",
+                        _ => "██",
+                    };
+                    shade.to_string()
+                })
+                .collect();
+            let total: u32 = row.iter().sum();
+            println!("{}  {} {}", WEEKDAYS[day], cells, style(total).dim());
+        }
+
+        // Busiest counterparties
+        let mut table = TableBuilder::new();
+        table.add_header(&["Counterparty", "Transactions", "Volume (RBTC)"]);
+        for (addr, count, volume) in stats.counterparties.iter().take(self.top) {
+            table.add_row(&[
+                &format!("{:#x}", addr),
+                &count.to_string(),
+                &alloy::primitives::utils::format_units(*volume, 18)
+                    .unwrap_or_else(|_| "N/A".to_string()),
+            ]);
+        }
+        println!("\n{}", style("Busiest counterparties").bold());
+        table.print();
+
+        // Totals per category when the user has defined rules
+        let rules = config.category_rules;
+        let mut by_category: Vec<(String, u64, U256)> = Vec::new();
+        if !rules.is_empty() {
+            for tx in &txs {
+                let category = categories::categorize(&rules, tx).unwrap_or("Uncategorized");
+                match by_category.iter_mut().find(|(c, _, _)| c == category) {
+                    Some(entry) => {
+                        entry.1 += 1;
+                        entry.2 = entry.2.saturating_add(tx.value);
+                    }
+                    None => by_category.push((category.to_string(), 1, tx.value)),
+                }
+            }
+            by_category.sort_by_key(|entry| std::cmp::Reverse(entry.1));
+
+            let mut table = TableBuilder::new();
+            table.add_header(&["Category", "Transactions", "Volume"]);
+            for (category, count, volume) in &by_category {
+                table.add_row(&[
+                    category,
+                    &count.to_string(),
+                    &alloy::primitives::utils::format_units(*volume, 18)
+                        .unwrap_or_else(|_| "N/A".to_string()),
+                ]);
+            }
+            println!("\n{}", style("By category").bold());
+            table.print();
+        }
+
+        // Spend per cost center, for sends that were allocated to one
+        let allocations = cost_centers::load().unwrap_or_default();
+        let mut by_cost_center: Vec<(String, u64, U256, U256)> = Vec::new();
+        for tx in txs.iter().filter(|tx| tx.from == address) {
+            let Some(cost_center) = allocations.get(&tx.hash) else {
+                continue;
+            };
+            let fee = tx.fee().unwrap_or_default();
+            match by_cost_center.iter_mut().find(|(c, ..)| c == cost_center) {
+                Some(entry) => {
+                    entry.1 += 1;
+                    entry.2 = entry.2.saturating_add(tx.value);
+                    entry.3 = entry.3.saturating_add(fee);
+                }
+                None => by_cost_center.push((cost_center.clone(), 1, tx.value, fee)),
+            }
+        }
+        if !by_cost_center.is_empty() {
+            by_cost_center.sort_by_key(|entry| std::cmp::Reverse(entry.2));
+            let mut table = TableBuilder::new();
+            table.add_header(&["Cost Center", "Transactions", "Volume", "Fees (RBTC)"]);
+            for (cost_center, count, volume, fees) in &by_cost_center {
+                table.add_row(&[
+                    cost_center,
+                    &count.to_string(),
+                    &alloy::primitives::utils::format_units(*volume, 18)
+                        .unwrap_or_else(|_| "N/A".to_string()),
+                    &alloy::primitives::utils::format_units(*fees, 18)
+                        .unwrap_or_else(|_| "N/A".to_string()),
+                ]);
+            }
+            println!("\n{}", style("By cost center").bold());
+            table.print();
+        }
+
+        let mut json = stats.to_json(address, self.top);
+        json["categories"] = totals_json(&by_category);
+        json["cost_centers"] = by_cost_center
+            .iter()
+            .map(|(cost_center, count, volume, fees)| {
+                serde_json::json!({
+                    "name": cost_center,
+                    "transactions": count,
+                    "volume": volume.to_string(),
+                    "fees": fees.to_string(),
+                })
+            })
+            .collect();
+        output::emit(&json)
+    }
+}
+
+/// Transactions and volume per name for `--json`, amounts in wei
+fn totals_json(totals: &[(String, u64, U256)]) -> serde_json::Value {
+    totals
+        .iter()
+        .map(|(name, count, volume)| {
+            serde_json::json!({
+                "name": name,
+                "transactions": count,
+                "volume": volume.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::transaction::TransactionStatus;
+    use alloy::primitives::B256;
+    use std::time::SystemTime;
+
+    fn tx(from: Address, to: Address, value: u64) -> RskTransaction {
+        RskTransaction {
+            hash: B256::ZERO,
+            from,
+            to: Some(to),
+            value: U256::from(value),
+            gas_price: U256::from(10),
+            gas: U256::from(21_000),
+            nonce: U256::ZERO,
+            input: None,
+            block_number: None,
+            transaction_index: None,
+            timestamp: SystemTime::now(),
+            status: TransactionStatus::Success,
+            token_address: None,
+            confirms: None,
+            cumulative_gas_used: None,
+            gas_used: Some(U256::from(21_000)),
+            logs: None,
+        }
+    }
+
+    #[test]
+    fn test_compute_stats() {
+        let me = Address::repeat_byte(1);
+        let alice = Address::repeat_byte(2);
+        let bob = Address::repeat_byte(3);
+        // The gas limit is not what is paid; a send without a receipt is
+        // left out of the average
+        let mut limited = tx(me, alice, 5);
+        limited.gas = U256::from(100_000);
+        let mut unmined = tx(me, alice, 5);
+        unmined.gas_used = None;
+        let txs = vec![limited, unmined, tx(bob, me, 1)];
+
+        let stats = ActivityStats::compute(me, &txs);
+
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.outgoing, 2);
+        assert_eq!(stats.counterparties[0], (alice, 2, U256::from(10)));
+        assert_eq!(stats.average_fee(), U256::from(210_000));
+        let cells: u32 = stats.heatmap.iter().flat_map(|r| r.iter()).sum();
+        assert_eq!(cells, 3);
+
+        let json = stats.to_json(me, 1);
+        assert_eq!(json["average_fee"], "210000");
+        assert_eq!(json["counterparties"].as_array().unwrap().len(), 1);
+        assert_eq!(json["counterparties"][0]["volume"], "10");
+    }
+}
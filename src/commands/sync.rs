@@ -0,0 +1,165 @@
+use crate::config::ConfigManager;
+use crate::types::wallet::WalletData;
+use crate::utils::helper::Helper;
+use crate::utils::history_cache::HistoryCache;
+use crate::utils::transfer_logs;
+use alloy::primitives::Address;
+use alloy::providers::Provider;
+use anyhow::{Result, anyhow};
+use clap::Parser;
+use colored::Colorize;
+use std::io::Write;
+use std::str::FromStr;
+
+/// Blocks scanned between saves of the scan progress
+const BATCH_BLOCKS: u64 = 10_000;
+
+/// Fill the local history cache from the node's token transfer logs
+#[derive(Parser, Debug, Clone)]
+pub struct SyncCommand {
+    /// Address to sync (defaults to the current wallet)
+    #[arg(short, long)]
+    pub address: Option<String>,
+
+    /// Account of the current wallet to sync (see `wallet add-account`)
+    #[arg(long)]
+    pub account: Option<u32>,
+
+    /// Scan the whole chain from block zero, resuming where the last full
+    /// scan stopped. Without it only recent blocks, or those after the last
+    /// full scan, are read.
+    #[arg(long)]
+    pub full: bool,
+
+    /// Stop after scanning this many blocks, to spread a full scan over
+    /// several sessions
+    #[arg(long, requires = "full")]
+    pub max_blocks: Option<u64>,
+
+    /// Forget the full scan progress and start again from block zero
+    #[arg(long, requires = "full")]
+    pub restart: bool,
+}
+
+impl SyncCommand {
+    pub async fn execute(&self) -> Result<()> {
+        let config = ConfigManager::new()?.load()?;
+        let network = config.default_network;
+        let address = self.address()?;
+        let (_, client) = Helper::init_eth_client(network.chain_name()).await?;
+        let head = client
+            .provider()
+            .get_block_number()
+            .await
+            .map_err(|e| anyhow!("Failed to get block number: {}", e))?;
+
+        let mut cache = HistoryCache::load().unwrap_or_default();
+        if self.restart {
+            cache.reset_scan(network.chain_name(), &address);
+        }
+        let scanned = cache.scanned_block(network.chain_name(), &address);
+        let start = match scanned {
+            Some(block) => block + 1,
+            None if self.full => 0,
+            None => head.saturating_sub(transfer_logs::SCAN_BLOCKS),
+        };
+        if start > head {
+            println!(
+                "{} History is already synced to block {}",
+                "✓".green(),
+                head
+            );
+            return Ok(());
+        }
+        let end = match self.max_blocks {
+            Some(max) => head.min(start.saturating_add(max.max(1) - 1)),
+            None => head,
+        };
+        // Progress only counts when the scan is contiguous from block zero
+        let track_progress = scanned.is_some() || self.full;
+
+        println!(
+            "Scanning blocks {} to {} on {} for token transfers of {:#x}",
+            start, end, network, address
+        );
+        let mut found = 0;
+        let mut from_block = start;
+        while from_block <= end {
+            let to_block = from_block.saturating_add(BATCH_BLOCKS - 1).min(end);
+            let txs =
+                transfer_logs::transfers_between(&client, address, from_block, to_block).await?;
+            found += txs.len();
+            cache.merge(network.chain_name(), &address, &txs);
+            if track_progress {
+                cache.set_scanned_block(network.chain_name(), &address, to_block);
+            }
+            cache.save()?;
+
+            print!(
+                "\r  {:>5.1}%  block {} of {}, {} transfer(s) found",
+                progress(start, to_block, end),
+                to_block,
+                end,
+                found
+            );
+            std::io::stdout().flush().ok();
+            from_block = to_block + 1;
+        }
+        println!();
+
+        if end < head {
+            println!(
+                "{} Scanned up to block {}; run `sync --full` again to continue",
+                "⏸".yellow(),
+                end
+            );
+        } else {
+            println!(
+                "{} History cache synced to block {} ({} transfer(s) found)",
+                "✓".green(),
+                head,
+                found
+            );
+        }
+        println!(
+            "{}",
+            "Logs only show token transfers; RBTC payments come from the history providers."
+                .dimmed()
+        );
+        Ok(())
+    }
+
+    fn address(&self) -> Result<Address> {
+        if let Some(address) = &self.address {
+            return Address::from_str(address)
+                .map_err(|_| anyhow!("Invalid address format: {}", address));
+        }
+        let wallet_data = WalletData::load()?;
+        let wallet = wallet_data.get_current_wallet().ok_or_else(|| {
+            anyhow!(
+                "No default wallet selected. Please use 'wallet switch' to select a default wallet."
+            )
+        })?;
+        let account = self.account.unwrap_or(0);
+        wallet
+            .account_address(account)
+            .ok_or_else(|| anyhow!("'{}' has no account #{}", wallet.name, account))
+    }
+}
+
+/// Percentage of the blocks from `start` to `end` scanned once `done` is
+fn progress(start: u64, done: u64, end: u64) -> f64 {
+    let total = end - start + 1;
+    (done - start + 1) as f64 * 100.0 / total as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress() {
+        assert_eq!(progress(0, 9_999, 19_999), 50.0);
+        assert_eq!(progress(100, 100, 100), 100.0);
+    }
+}
@@ -1,9 +1,11 @@
+use crate::utils::output;
+use crate::utils::profiles::{self, ProfileMatch};
+use crate::utils::store;
+use crate::utils::table::TableBuilder;
+use alloy::primitives::Address;
 use clap::Parser;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 use std::collections::HashMap;
-use std::fs;
-use std::path::Path;
 
 #[derive(Parser, Debug)]
 pub struct TokenAddCommand {
@@ -26,7 +28,8 @@ pub struct TokenAddCommand {
 
 #[derive(Parser, Debug)]
 pub struct TokenRemoveCommand {
-    /// Token symbol to remove
+    /// Symbol of the token to remove, or its address when several tokens
+    /// share the symbol
     #[arg(short, long)]
     pub symbol: String,
 
@@ -42,13 +45,59 @@ pub struct TokenListCommand {
     pub network: Option<String>,
 }
 
+impl TokenAddCommand {
+    pub async fn execute(&self) -> anyhow::Result<()> {
+        add_token(&self.network, &self.symbol, &self.address, self.decimals)
+            .map_err(|e| anyhow::anyhow!("{}", e))
+    }
+}
+
+impl TokenRemoveCommand {
+    pub async fn execute(&self) -> anyhow::Result<()> {
+        remove_token(&self.network, &self.symbol).map_err(|e| anyhow::anyhow!("{}", e))
+    }
+}
+
+impl TokenListCommand {
+    pub async fn execute(&self) -> anyhow::Result<()> {
+        let tokens = list_tokens(self.network.as_deref()).map_err(|e| anyhow::anyhow!("{}", e))?;
+        if !tokens.is_empty() {
+            let mut table = TableBuilder::new();
+            table.add_header(&["Symbol", "Address", "Decimals"]);
+            for (symbol, info) in &tokens {
+                table.add_row(&[symbol, &info.address, &info.decimals.to_string()]);
+            }
+            table.print();
+        }
+        let tokens: Vec<_> = tokens
+            .iter()
+            .map(|(label, info)| {
+                serde_json::json!({
+                    "label": label,
+                    "symbol": info.symbol,
+                    "address": info.address,
+                    "decimals": info.decimals,
+                })
+            })
+            .collect();
+        output::emit(&tokens)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TokenInfo {
+    /// Empty in registries written before tokens were keyed by address,
+    /// where the symbol was the key
+    #[serde(default)]
+    pub symbol: String,
     pub address: String,
     pub decimals: u8,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+/// Registered tokens of each network, keyed by lowercase contract address so
+/// tokens sharing a symbol can all be registered. Such tokens are told apart
+/// by their address wherever they are listed.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct TokenRegistry {
     pub mainnet: HashMap<String, TokenInfo>,
     pub testnet: HashMap<String, TokenInfo>,
@@ -56,29 +105,50 @@ pub struct TokenRegistry {
 
 impl TokenRegistry {
     pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
-        let path = "tokens.json";
-        if !Path::new(path).exists() {
-            // Create a new empty registry if file doesn't exist
-            let registry = TokenRegistry {
-                mainnet: HashMap::new(),
-                testnet: HashMap::new(),
-            };
-            let json = serde_json::to_string_pretty(&json!(&registry))?;
-            fs::write(path, json)?;
-            return Ok(registry);
+        Ok(store::open()?.load_tokens()?)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(store::open()?.save_tokens(self)?)
+    }
+
+    fn network(&self, network: &str) -> Option<&HashMap<String, TokenInfo>> {
+        match network.to_lowercase().as_str() {
+            "mainnet" => Some(&self.mainnet),
+            "testnet" => Some(&self.testnet),
+            _ => None,
         }
+    }
 
-        let content = fs::read_to_string(path)?;
-        let registry: TokenRegistry = serde_json::from_str(&content)?;
-        Ok(registry)
+    fn network_mut(&mut self, network: &str) -> Result<&mut HashMap<String, TokenInfo>, String> {
+        match network.to_lowercase().as_str() {
+            "mainnet" => Ok(&mut self.mainnet),
+            "testnet" => Ok(&mut self.testnet),
+            _ => Err("Invalid network. Use 'mainnet' or 'testnet'.".to_string()),
+        }
     }
 
-    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let json = serde_json::to_string_pretty(&self)?;
-        fs::write("tokens.json", json)?;
-        Ok(())
+    /// Re-key a registry written before tokens were keyed by address
+    pub fn normalized(self) -> Self {
+        let rekey = |tokens: HashMap<String, TokenInfo>| {
+            tokens
+                .into_iter()
+                .map(|(key, mut info)| {
+                    if info.symbol.is_empty() {
+                        info.symbol = key.to_uppercase();
+                    }
+                    (info.address.to_lowercase(), info)
+                })
+                .collect()
+        };
+        Self {
+            mainnet: rekey(self.mainnet),
+            testnet: rekey(self.testnet),
+        }
     }
 
+    /// Add a token to `network`. A symbol already used by another token is
+    /// accepted; see [`Self::collisions`].
     pub fn add_token(
         &mut self,
         network: &str,
@@ -86,130 +156,169 @@ impl TokenRegistry {
         address: &str,
         decimals: u8,
     ) -> Result<(), String> {
-        let network_lower = network.to_lowercase();
-        let symbol_upper = symbol.to_uppercase();
-        let address_lower = address.to_lowercase();
-
-        // Check if symbol already exists in any network
-        if self.mainnet.contains_key(&symbol_upper) || self.testnet.contains_key(&symbol_upper) {
+        let tokens = self.network_mut(network)?;
+        let key = address.to_lowercase();
+        if let Some(existing) = tokens.get(&key) {
             return Err(format!(
-                "Token symbol '{}' already exists in the registry",
-                symbol_upper
+                "Token address '{}' is already registered as {}",
+                address, existing.symbol
             ));
         }
+        tokens.insert(
+            key,
+            TokenInfo {
+                symbol: symbol.to_uppercase(),
+                address: address.to_string(),
+                decimals,
+            },
+        );
+        Ok(())
+    }
 
-        // Check if address already exists in any network
-        let all_tokens = self.mainnet.iter().chain(self.testnet.iter());
-        for (_, token) in all_tokens {
-            if token.address.to_lowercase() == address_lower {
-                return Err(format!("Token address '{}' is already registered", address));
-            }
-        }
-
-        let token = TokenInfo {
-            address: address.to_string(),
-            decimals,
-        };
+    /// Other tokens on `network` with the same symbol as the token at
+    /// `address`
+    pub fn collisions(&self, network: &str, symbol: &str, address: &str) -> Vec<&TokenInfo> {
+        let mut others: Vec<&TokenInfo> = self
+            .network(network)
+            .into_iter()
+            .flat_map(|tokens| tokens.values())
+            .filter(|info| {
+                info.symbol.eq_ignore_ascii_case(symbol)
+                    && !info.address.eq_ignore_ascii_case(address)
+            })
+            .collect();
+        others.sort_by(|a, b| a.address.cmp(&b.address));
+        others
+    }
 
-        match network_lower.as_str() {
-            "mainnet" => {
-                self.mainnet.insert(symbol_upper, token);
-            }
-            "testnet" => {
-                self.testnet.insert(symbol_upper, token);
+    /// The token on `network` with the contract address `query`, or the only
+    /// one with that symbol
+    pub fn resolve(&self, network: &str, query: &str) -> Result<&TokenInfo, String> {
+        let tokens = self
+            .network(network)
+            .ok_or_else(|| "Invalid network. Use 'mainnet' or 'testnet'.".to_string())?;
+        if let Some(info) = tokens.get(&query.to_lowercase()) {
+            return Ok(info);
+        }
+        let mut matches: Vec<&TokenInfo> = tokens
+            .values()
+            .filter(|info| info.symbol.eq_ignore_ascii_case(query))
+            .collect();
+        match matches.len() {
+            0 => Err(format!("Token '{}' is not in the {} registry", query, network)),
+            1 => Ok(matches.remove(0)),
+            _ => {
+                matches.sort_by(|a, b| a.address.cmp(&b.address));
+                Err(format!(
+                    "Several {} tokens use the symbol {}; give the address of one of {}",
+                    network,
+                    query.to_uppercase(),
+                    matches
+                        .iter()
+                        .map(|info| info.address.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
             }
-            _ => return Err("Invalid network. Use 'mainnet' or 'testnet'.".to_string()),
         }
-        Ok(())
     }
 
-    pub fn remove_token(&mut self, network: &str, symbol: &str) -> Result<(), &'static str> {
-        match network.to_lowercase().as_str() {
-            "mainnet" => {
-                self.mainnet.remove(&symbol.to_uppercase());
-            }
-            "testnet" => {
-                self.testnet.remove(&symbol.to_uppercase());
-            }
-            _ => return Err("Invalid network. Use 'mainnet' or 'testnet'."),
-        }
-        Ok(())
+    /// Remove the token with the contract address `query`, or the only one
+    /// with that symbol
+    pub fn remove_token(&mut self, network: &str, query: &str) -> Result<TokenInfo, String> {
+        let key = self.resolve(network, query)?.address.to_lowercase();
+        self.network_mut(network)?
+            .remove(&key)
+            .ok_or_else(|| format!("Token '{}' is not in the {} registry", query, network))
     }
 
+    /// Tokens of `network` (or of every network, labelled `mainnet/…` and
+    /// `testnet/…`) sorted by label. The label is the symbol, followed by
+    /// the start of the address when other tokens share it, e.g.
+    /// `USDT (0x1234…)`.
     pub fn list_tokens(&self, network: Option<&str>) -> Vec<(String, TokenInfo)> {
+        let networks = match network {
+            Some(net) => vec![(None, net)],
+            None => vec![(Some("mainnet"), "mainnet"), (Some("testnet"), "testnet")],
+        };
         let mut result = Vec::new();
-
-        match network {
-            Some(net) => match net.to_lowercase().as_str() {
-                "mainnet" => {
-                    for (symbol, info) in &self.mainnet {
-                        result.push((
-                            symbol.clone(),
-                            TokenInfo {
-                                address: info.address.clone(),
-                                decimals: info.decimals,
-                            },
-                        ));
-                    }
-                }
-                "testnet" => {
-                    for (symbol, info) in &self.testnet {
-                        result.push((
-                            symbol.clone(),
-                            TokenInfo {
-                                address: info.address.clone(),
-                                decimals: info.decimals,
-                            },
-                        ));
-                    }
-                }
-                _ => {}
-            },
-            None => {
-                for (symbol, info) in &self.mainnet {
-                    result.push((
-                        format!("mainnet/{}", symbol),
-                        TokenInfo {
-                            address: info.address.clone(),
-                            decimals: info.decimals,
-                        },
-                    ));
-                }
-                for (symbol, info) in &self.testnet {
-                    result.push((
-                        format!("testnet/{}", symbol),
-                        TokenInfo {
-                            address: info.address.clone(),
-                            decimals: info.decimals,
-                        },
-                    ));
+        for (prefix, net) in networks {
+            for info in self.network(net).into_iter().flat_map(|tokens| tokens.values()) {
+                let mut label = if self.collisions(net, &info.symbol, &info.address).is_empty() {
+                    info.symbol.clone()
+                } else {
+                    format!("{} ({})", info.symbol, short_address(&info.address))
+                };
+                if let Some(prefix) = prefix {
+                    label = format!("{}/{}", prefix, label);
                 }
+                result.push((label, info.clone()));
             }
         }
+        result.sort_by(|a, b| a.0.cmp(&b.0));
         result
     }
 }
 
+/// `0x1234…` from a token address
+fn short_address(address: &str) -> String {
+    format!("{}…", address.get(..6).unwrap_or(address))
+}
+
 pub fn add_token(
     network: &str,
     symbol: &str,
     address: &str,
     decimals: u8,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let parsed: Address = address
+        .parse()
+        .map_err(|_| format!("Invalid token address: {}", address))?;
+    match profiles::check_address(network, &parsed) {
+        ProfileMatch::LookAlike(profile) => {
+            return Err(format!(
+                "Address {} looks like {} ({}) at {:#x} but does not match it",
+                address, profile.name, profile.protocol, profile.address
+            )
+            .into());
+        }
+        ProfileMatch::Verified(profile) => {
+            println!("✓ Verified contract: {} ({})", profile.name, profile.protocol);
+        }
+        ProfileMatch::Unknown => {}
+    }
+
     let mut registry = TokenRegistry::load()?;
     if let Err(e) = registry.add_token(network, symbol, address, decimals) {
         return Err(e.into());
     }
     registry.save()?;
     println!("Added token {} to {} network", symbol, network);
+    let collisions = registry.collisions(network, symbol, address);
+    if !collisions.is_empty() {
+        println!(
+            "⚠️  {} other {} token(s) use the symbol {} ({}); they are listed with their address",
+            collisions.len(),
+            network,
+            symbol.to_uppercase(),
+            collisions
+                .iter()
+                .map(|info| info.address.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
     Ok(())
 }
 
-pub fn remove_token(network: &str, symbol: &str) -> Result<(), Box<dyn std::error::Error>> {
+pub fn remove_token(network: &str, token: &str) -> Result<(), Box<dyn std::error::Error>> {
     let mut registry = TokenRegistry::load()?;
-    registry.remove_token(network, symbol)?;
+    let removed = registry.remove_token(network, token)?;
     registry.save()?;
-    println!("Removed token {} from {} network", symbol, network);
+    println!(
+        "Removed token {} ({}) from {} network",
+        removed.symbol, removed.address, network
+    );
     Ok(())
 }
 
@@ -228,3 +337,51 @@ pub fn list_tokens(
 
     Ok(tokens)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_disambiguates_shared_symbols() {
+        let first = "0x1111111111111111111111111111111111111111";
+        let second = "0x2222222222222222222222222222222222222222";
+        let mut registry = TokenRegistry::default();
+        registry.add_token("mainnet", "usdt", first, 6).unwrap();
+        registry.add_token("mainnet", "USDT", second, 18).unwrap();
+        registry.add_token("testnet", "rif", first, 18).unwrap();
+        assert!(registry.add_token("mainnet", "other", first, 18).is_err());
+
+        assert_eq!(registry.collisions("mainnet", "USDT", first).len(), 1);
+        assert!(registry.collisions("testnet", "RIF", first).is_empty());
+        let labels: Vec<String> = registry
+            .list_tokens(Some("mainnet"))
+            .into_iter()
+            .map(|(label, _)| label)
+            .collect();
+        assert_eq!(labels, ["USDT (0x1111…)", "USDT (0x2222…)"]);
+        assert_eq!(registry.list_tokens(None)[2].0, "testnet/RIF");
+
+        assert!(registry.resolve("mainnet", "usdt").is_err());
+        assert_eq!(registry.resolve("mainnet", second).unwrap().decimals, 18);
+        assert_eq!(registry.resolve("testnet", "rif").unwrap().symbol, "RIF");
+
+        let removed = registry.remove_token("mainnet", first).unwrap();
+        assert_eq!(removed.decimals, 6);
+        assert_eq!(registry.resolve("mainnet", "usdt").unwrap().address, second);
+
+        let legacy = TokenRegistry {
+            mainnet: HashMap::from([(
+                "doc".to_string(),
+                TokenInfo {
+                    symbol: String::new(),
+                    address: first.to_uppercase().replace("0X", "0x"),
+                    decimals: 18,
+                },
+            )]),
+            testnet: HashMap::new(),
+        }
+        .normalized();
+        assert_eq!(legacy.mainnet[first].symbol, "DOC");
+    }
+}
@@ -1,15 +1,23 @@
+use crate::api::prices::{Currency, Prices};
 use crate::config::ConfigManager;
+use crate::types::transaction::{RskTransaction, TransactionStatus};
 use crate::types::wallet::WalletData;
-use crate::utils::constants;
-use crate::utils::eth::EthClient;
-use crate::utils::helper::Config as HelperConfig;
+use crate::utils::cost_centers;
+use crate::utils::eth::{EthClient, GasOverrides};
+use crate::utils::gas_analytics::{FeeRecord, GasAnalytics};
+use crate::utils::helper::{Config as HelperConfig, unlock_wallet};
+use crate::utils::profiles;
+use crate::utils::redact;
+use crate::utils::rns;
+use crate::utils::roles::{self, Capability};
+use crate::utils::signer::WalletSigner;
+use crate::utils::transfer_fee;
 use anyhow::{Result, anyhow};
 use clap::Parser;
 use colored::Colorize;
 use alloy::primitives::{Address, B256, U64, U256};
+use alloy::providers::Provider;
 use alloy::signers::local::PrivateKeySigner;
-use rpassword::prompt_password;
-use std::fs;
 use std::str::FromStr;
 
 /// Result of a transfer operation
@@ -26,9 +34,66 @@ pub struct TransferResult {
     pub token_symbol: Option<String>,
 }
 
+impl TransferResult {
+    /// Whether the transaction was mined and succeeded
+    pub fn succeeded(&self) -> bool {
+        self.status == U64::from(1)
+    }
+
+    /// The result as printed by `--json`. The value is in the smallest unit
+    /// of the asset; a transfer without a receipt yet is `pending`.
+    pub fn to_json(&self) -> serde_json::Value {
+        let status = if self.succeeded() {
+            "success"
+        } else if self.gas_used.is_zero() {
+            "pending"
+        } else {
+            "failed"
+        };
+        serde_json::json!({
+            "tx_hash": format!("{:#x}", self.tx_hash),
+            "from": format!("{:#x}", self.from),
+            "to": format!("{:#x}", self.to),
+            "value": self.value.to_string(),
+            "token_address": self.token_address.map(|token| format!("{:#x}", token)),
+            "token_symbol": self.token_symbol.as_deref().unwrap_or("RBTC"),
+            "gas_used": self.gas_used.to_string(),
+            "gas_price": self.gas_price.to_string(),
+            "status": status,
+        })
+    }
+
+    /// The transfer as a history entry, as of now
+    pub fn transaction(&self) -> RskTransaction {
+        RskTransaction {
+            hash: self.tx_hash,
+            from: self.from,
+            to: Some(self.to),
+            value: self.value,
+            gas_price: self.gas_price,
+            gas: self.gas_used,
+            nonce: U256::ZERO,
+            input: None,
+            block_number: None,
+            transaction_index: None,
+            timestamp: std::time::SystemTime::now(),
+            status: if self.succeeded() {
+                TransactionStatus::Success
+            } else {
+                TransactionStatus::Failed
+            },
+            token_address: self.token_address,
+            confirms: None,
+            cumulative_gas_used: None,
+            gas_used: Some(self.gas_used),
+            logs: None,
+        }
+    }
+}
+
 #[derive(Parser, Debug)]
 pub struct TransferCommand {
-    /// Address to send to
+    /// Address or RNS name (e.g. alice.rsk) to send to
     #[arg(long, required = true)]
     pub address: String,
 
@@ -39,50 +104,234 @@ pub struct TransferCommand {
     /// Token address (for ERC20 transfers)
     #[arg(long)]
     pub token: Option<String>,
+
+    /// Gas limit (defaults to the node's estimate)
+    #[arg(long)]
+    pub gas_limit: Option<u64>,
+
+    /// Gas price in gwei (defaults to the node's gas price)
+    #[arg(long)]
+    pub gas_price: Option<String>,
+
+    /// Nonce override, e.g. to replace a stuck transaction
+    #[arg(long)]
+    pub nonce: Option<u64>,
+
+    /// Account of the current wallet to send from (see `wallet add-account`)
+    #[arg(long)]
+    pub account: Option<u32>,
+
+    /// Memo or destination tag, required by contacts marked "memo required"
+    #[arg(long)]
+    pub memo: Option<String>,
+
+    /// Cost center to allocate the transfer to (one of the configured ones)
+    #[arg(long)]
+    pub cost_center: Option<String>,
+
+    /// Exact amount in the smallest unit, sent instead of `value` by callers
+    /// that already parsed the amount; `value` is then only displayed
+    #[arg(skip)]
+    pub units: Option<U256>,
+}
+
+/// A gas price given in gwei, in wei
+pub fn parse_gas_price(gwei: &str) -> Result<u128> {
+    alloy::primitives::utils::parse_units(gwei.trim(), "gwei")
+        .map_err(|e| anyhow!("Invalid gas price: {}", e))
+        .and_then(|v| {
+            if v.is_negative() {
+                return Err(anyhow!("Gas price cannot be negative"));
+            }
+            u128::try_from(v.get_absolute()).map_err(|_| anyhow!("Gas price out of range"))
+        })
+}
+
+/// Warnings for gas overrides that deviate far from the node's values
+pub fn gas_override_warnings(
+    overrides: &GasOverrides,
+    estimated_gas: Option<u64>,
+    network_gas_price: Option<u128>,
+    pending_nonce: Option<u64>,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    if let (Some(limit), Some(estimate)) = (overrides.gas_limit, estimated_gas) {
+        if limit < estimate {
+            warnings.push(format!(
+                "Gas limit {} is below the estimate of {}; the transaction will likely run out of gas",
+                limit, estimate
+            ));
+        } else if limit > estimate.saturating_mul(3) {
+            warnings.push(format!(
+                "Gas limit {} is more than 3x the estimate of {}",
+                limit, estimate
+            ));
+        }
+    }
+
+    if let (Some(price), Some(network)) = (overrides.gas_price, network_gas_price) {
+        if price < network / 2 {
+            warnings.push(format!(
+                "Gas price {} wei is less than half the network price of {} wei; the transaction may never be mined",
+                price, network
+            ));
+        } else if price > network.saturating_mul(3) {
+            warnings.push(format!(
+                "Gas price {} wei is more than 3x the network price of {} wei",
+                price, network
+            ));
+        }
+    }
+
+    if let (Some(nonce), Some(pending)) = (overrides.nonce, pending_nonce) {
+        if nonce < pending {
+            warnings.push(format!(
+                "Nonce {} is already used (next is {}); the transaction will be rejected unless it replaces a pending one",
+                nonce, pending
+            ));
+        } else if nonce > pending {
+            warnings.push(format!(
+                "Nonce {} leaves a gap (next is {}); the transaction will wait until earlier nonces are used",
+                nonce, pending
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Check gas overrides for a transfer from `from` against the node's gas
+/// estimate, gas price and pending nonce. Only the overridden values are
+/// looked up, and values the node cannot provide are not checked.
+pub async fn check_gas_overrides(
+    client: &EthClient,
+    from: Address,
+    to: Address,
+    amount: U256,
+    token: Option<Address>,
+    overrides: &GasOverrides,
+) -> Vec<String> {
+    let estimated_gas = match overrides.gas_limit {
+        Some(_) => client
+            .estimate_gas(from, to, amount, token)
+            .await
+            .ok()
+            .and_then(|gas| u64::try_from(gas).ok()),
+        None => None,
+    };
+    let network_gas_price = match overrides.gas_price {
+        Some(_) => client.suggest_gas_price().await.ok().map(|fee| fee.gas_price),
+        None => None,
+    };
+    let pending_nonce = match overrides.nonce {
+        Some(_) => client.provider().get_transaction_count(from).await.ok(),
+        None => None,
+    };
+    gas_override_warnings(overrides, estimated_gas, network_gas_price, pending_nonce)
 }
 
 impl TransferCommand {
     /// Execute the transfer command and return the transfer result
     pub async fn execute(&self) -> Result<TransferResult> {
+        roles::require(Capability::Transfer)?;
+        self.send().await
+    }
+
+    /// Sign and send the transfer without checking the signed-in role; used
+    /// for transfers that were approved through another path
+    pub(crate) async fn send(&self) -> Result<TransferResult> {
         // Load wallet file and get current wallet
-        let wallet_file = constants::wallet_file_path();
-        if !wallet_file.exists() {
+        let wallet_data = WalletData::load()?;
+        if wallet_data.wallets.is_empty() {
             return Err(anyhow!(
                 "No wallets found. Please create or import a wallet first."
             ));
         }
-        let data = fs::read_to_string(&wallet_file)?;
-        let wallet_data: WalletData = serde_json::from_str(&data)?;
         let default_wallet = wallet_data.get_current_wallet().ok_or_else(|| {
             anyhow!(
                 "No default wallet selected. Please use 'wallet switch' to select a default wallet."
             )
         })?;
 
-        // Prompt for password and decrypt private key
-        let password = prompt_password("Enter password for the default wallet: ")?;
-        let private_key = default_wallet.decrypt_private_key(&password)?;
-        let _local_wallet = PrivateKeySigner::from_str(&private_key)
-            .map_err(|e| anyhow!("Failed to create PrivateKeySigner: {}", e))?;
+        let account = self.account.unwrap_or(0);
+        let from = default_wallet.account_address(account).ok_or_else(|| {
+            anyhow!(
+                "'{}' has no account #{}. Use 'wallet add-account' to derive it.",
+                default_wallet.name,
+                account
+            )
+        })?;
 
         // Get the network from config
         let config = ConfigManager::new()?.load()?;
 
-        // Create a new helper config with the private key
-        let client_config = HelperConfig {
-            network: config.default_network.get_config(),
-            wallet: crate::utils::helper::WalletConfig {
-                current_wallet_address: None,
-                private_key: Some(private_key.clone()),
-                mnemonic: None,
-            },
-        };
+        let eth_client = match &default_wallet.hardware {
+            Some(hardware) => {
+                // The key stays on the device, so there is no password to ask for
+                let signer = WalletSigner::trezor(
+                    &hardware.derivation_path,
+                    config.default_network.chain_id(),
+                )
+                .await?;
+                if signer.address() != default_wallet.address() {
+                    return Err(anyhow!(
+                        "The connected {} has address 0x{:x}, not 0x{:x}",
+                        hardware.device,
+                        signer.address(),
+                        default_wallet.address()
+                    ));
+                }
+                let client_config = HelperConfig {
+                    network: config.default_network.get_config(),
+                    wallet: crate::utils::helper::WalletConfig {
+                        current_wallet_address: None,
+                        private_key: None,
+                        mnemonic: None,
+                    },
+                };
+                EthClient::new(&client_config, None)
+                    .await?
+                    .with_signer(signer)
+            }
+            None => {
+                // Unlock with the saved or prompted password
+                let private_key = unlock_wallet(
+                    default_wallet,
+                    account,
+                    "Enter password for the default wallet: ",
+                )?;
+                let _local_wallet = PrivateKeySigner::from_str(&private_key)
+                    .map_err(|e| anyhow!("Failed to create PrivateKeySigner: {}", e))?;
 
-        let eth_client = EthClient::new(&client_config, None).await?;
+                // Create a new helper config with the private key
+                let client_config = HelperConfig {
+                    network: config.default_network.get_config(),
+                    wallet: crate::utils::helper::WalletConfig {
+                        current_wallet_address: None,
+                        private_key: Some(private_key.clone()),
+                        mnemonic: None,
+                    },
+                };
+                EthClient::new(&client_config, None).await?
+            }
+        };
 
-        // Parse recipient address
-        let to = Address::from_str(&self.address)
-            .map_err(|_| anyhow!("Invalid recipient address: {}", &self.address))?;
+        // Parse the recipient address, or resolve it when given as an RNS name
+        let to = rns::resolve_recipient(eth_client.provider(), &self.address, eth_client.chain_id()).await?;
+        let network_name = if config.default_network.is_testnet() {
+            "testnet"
+        } else {
+            "mainnet"
+        };
+        profiles::ensure_not_look_alike(network_name, &to)?;
+        crate::commands::contacts::ensure_verified_recipient(&to)?;
+        crate::commands::contacts::ensure_memo(&to, self.memo.as_deref())?;
+        let cost_center = self
+            .cost_center
+            .as_deref()
+            .map(|name| cost_centers::resolve(&config.cost_centers, name))
+            .transpose()?;
 
         // Parse optional token address
         let (token_address, token_symbol) = if let Some(token_addr) = &self.token {
@@ -93,6 +342,7 @@ impl TransferCommand {
                 // Parse token address
                 let addr = Address::from_str(token_addr)
                     .map_err(|_| anyhow!("Invalid token address: {}", token_addr))?;
+                profiles::ensure_not_look_alike(network_name, &addr)?;
 
                 // Try to get token info, but don't fail if we can't
                 let symbol = match eth_client.get_token_info(addr).await {
@@ -110,35 +360,101 @@ impl TransferCommand {
         // Parse amount (convert f64 to wei or token units)
         // Both RBTC and tokens use 18 decimals
         let decimals = 18;
-        let amount = alloy::primitives::utils::parse_units(&self.value.to_string(), decimals)
-            .map_err(|e| anyhow!("Invalid amount: {}", e))?;
+        let amount: U256 = match self.units {
+            Some(units) => units,
+            None => alloy::primitives::utils::parse_units(&self.value.to_string(), decimals)
+                .map_err(|e| anyhow!("Invalid amount: {}", e))?
+                .into(),
+        };
+
+        // Estimate the fee up front so it can be compared after confirmation
+        let estimated_fee = match (
+            eth_client.suggest_gas_price().await,
+            eth_client
+                .estimate_gas(from, to, amount, token_address)
+                .await,
+        ) {
+            (Ok(fee), Ok(gas)) => Some(U256::from(fee.gas_price).saturating_mul(gas)),
+            _ => None,
+        };
+
+        // Tokens that keep part of each transfer credit the recipient less
+        if let Some(token) = token_address
+            && let Some(fee) =
+                transfer_fee::check(eth_client.provider(), token, from, to, amount).await
+        {
+            println!(
+                "{}: {}",
+                "Warning".yellow().bold(),
+                fee.warning(token_symbol.as_deref().unwrap_or("Token"), decimals)
+            );
+        }
+
+        let overrides = GasOverrides {
+            gas_limit: self.gas_limit,
+            gas_price: self.gas_price.as_deref().map(parse_gas_price).transpose()?,
+            nonce: self.nonce,
+        };
+        for warning in
+            check_gas_overrides(&eth_client, from, to, amount, token_address, &overrides).await
+        {
+            println!("{}: {}", "Warning".yellow().bold(), warning);
+        }
+
+        if let Some(hardware) = &default_wallet.hardware {
+            println!("Confirm the transaction on your {}...", hardware.device);
+        }
 
         // Send transaction
         let tx_hash = eth_client
-            .send_transaction(to, amount.into(), token_address)
+            .send_transaction_with_overrides(
+                to,
+                amount,
+                token_address,
+                overrides,
+                self.memo.as_deref().map(str::as_bytes),
+            )
             .await?;
+        if let Some(cost_center) = &cost_center
+            && let Err(e) = cost_centers::record(tx_hash, cost_center)
+        {
+            log::warn!("Failed to record the cost center: {}", e);
+        }
 
         println!(
-            "{}: Transaction sent: 0x{:x} for {} {}",
+            "{}: Transaction sent: {} for {} {}",
             "Success".green().bold(),
-            tx_hash,
-            self.value,
+            redact::address(format!("0x{:x}", tx_hash)),
+            redact::amount(self.value),
             token_symbol.clone().unwrap_or("RBTC".to_string())
         );
 
+        // Status 0 without gas used stands for pending until the receipt
+        let sent = TransferResult {
+            tx_hash,
+            from,
+            to,
+            value: amount,
+            gas_used: U256::ZERO,
+            gas_price: U256::ZERO,
+            status: U64::from(0),
+            token_address,
+            token_symbol: token_symbol.clone(),
+        };
         println!(
             "\n{}: Transaction submitted. Waiting for confirmation... (This may take a moment)",
             "Info".blue().bold()
         );
 
         // Try to get receipt with retries
-        let mut retries = 5;
+        let polling = &config.polling;
+        let mut retries = polling.receipt_poll_attempts;
         let receipt = loop {
             match eth_client.get_transaction_receipt(tx_hash).await {
                 Ok(receipt) => break receipt,
                 Err(_e) if retries > 0 => {
                     retries -= 1;
-                    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+                    tokio::time::sleep(polling.receipt_poll_interval()).await;
                 }
                 Err(_e) => {
                     println!(
@@ -151,17 +467,7 @@ impl TransferCommand {
                     );
 
                     // Return with minimal receipt info since we couldn't get the full receipt
-                    return Ok(TransferResult {
-                        tx_hash,
-                        from: default_wallet.address(),
-                        to,
-                        value: amount.into(),
-                        gas_used: U256::ZERO,
-                        gas_price: U256::ZERO,
-                        status: U64::from(0), // 0 indicates unknown/pending status
-                        token_address,
-                        token_symbol,
-                    });
+                    return Ok(sent);
                 }
             }
         };
@@ -182,16 +488,154 @@ impl TransferCommand {
             status_str
         );
 
-        Ok(TransferResult {
+        let gas_used = U256::from(receipt.gas_used);
+        let effective_gas_price = U256::from(receipt.effective_gas_price);
+        let fee = gas_used.saturating_mul(effective_gas_price);
+        let prices = Prices::fetch_in(
+            config.display_currency.unwrap_or(Currency::Usd),
+            config.default_network,
+            &[],
+        )
+        .await;
+        print_fee_summary(gas_used, effective_gas_price, fee, estimated_fee, prices.as_ref());
+
+        if let Err(e) = GasAnalytics::record(FeeRecord {
             tx_hash,
-            from: default_wallet.address(),
-            to,
-            value: amount.into(),
-            gas_used: U256::from(receipt.gas_used),
-            gas_price: U256::ZERO, // Gas price not available in receipt
+            network: network_name.to_string(),
+            timestamp: chrono::Utc::now(),
+            gas_used,
+            effective_gas_price,
+            fee,
+            estimated_fee,
+        }) {
+            log::warn!("Failed to record fee analytics: {}", e);
+        }
+
+        Ok(TransferResult {
+            gas_used,
+            gas_price: effective_gas_price,
             status,
-            token_address,
-            token_symbol,
+            ..sent
         })
     }
 }
+
+/// Prints the fee actually paid and how it compares with the pre-send
+/// estimate, in RBTC and in fiat when a price is available
+fn print_fee_summary(
+    gas_used: U256,
+    effective_gas_price: U256,
+    fee: U256,
+    estimated_fee: Option<U256>,
+    prices: Option<&Prices>,
+) {
+    let format_rbtc = |wei: U256| {
+        alloy::primitives::utils::format_units(wei, 18).unwrap_or_else(|_| wei.to_string())
+    };
+    let fiat = |wei: U256| {
+        let rbtc: f64 = format_rbtc(wei).parse().unwrap_or(0.0);
+        prices
+            .and_then(|prices| prices.value(None, rbtc))
+            .map_or_else(|| "no price available".to_string(), |value| format!("≈ {}", value))
+    };
+    let gwei = alloy::primitives::utils::format_units(effective_gas_price, 9)
+        .unwrap_or_else(|_| effective_gas_price.to_string());
+
+    println!("\n{}", "Fee Summary".bold().underline());
+    println!("• Gas Used: {}", gas_used);
+    println!("• Effective Gas Price: {} Gwei", gwei);
+    println!(
+        "• Fee Paid: {} RBTC ({})",
+        format_rbtc(fee).yellow(),
+        fiat(fee).dimmed()
+    );
+
+    if let Some(estimate) = estimated_fee {
+        let diff = if fee >= estimate {
+            format!("+{}", format_rbtc(fee - estimate))
+        } else {
+            format!("-{}", format_rbtc(estimate - fee))
+        };
+        println!(
+            "• Estimated Fee: {} RBTC ({}; {} RBTC vs estimate)",
+            format_rbtc(estimate),
+            fiat(estimate),
+            diff
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gas_override_warnings() {
+        let none = GasOverrides::default();
+        assert!(gas_override_warnings(&none, Some(21_000), Some(60), Some(5)).is_empty());
+
+        let sane = GasOverrides {
+            gas_limit: Some(30_000),
+            gas_price: Some(70),
+            nonce: Some(5),
+        };
+        assert!(gas_override_warnings(&sane, Some(21_000), Some(60), Some(5)).is_empty());
+
+        let off = GasOverrides {
+            gas_limit: Some(20_000),
+            gas_price: Some(1_000),
+            nonce: Some(3),
+        };
+        assert_eq!(
+            gas_override_warnings(&off, Some(21_000), Some(60), Some(5)).len(),
+            3
+        );
+        assert_eq!(parse_gas_price("0.06").unwrap(), 60_000_000);
+        assert!(parse_gas_price("fast").is_err());
+        assert!(parse_gas_price("-5").is_err());
+        assert!(parse_gas_price(" 1 ").is_ok());
+    }
+
+    #[test]
+    fn test_transfer_result_as_contact_history() {
+        let result = TransferResult {
+            tx_hash: B256::repeat_byte(9),
+            from: Address::repeat_byte(1),
+            to: Address::repeat_byte(2),
+            value: U256::from(500),
+            gas_used: U256::from(21_000),
+            gas_price: U256::from(60),
+            status: U64::from(1),
+            token_address: None,
+            token_symbol: None,
+        };
+        let mut contact = crate::types::contacts::Contact::new(
+            "bob".to_string(),
+            result.to,
+            None,
+            vec![],
+        );
+        contact.update_transaction_stats(&result.transaction(), false);
+        assert_eq!(contact.get_total_transactions(), 1);
+        assert_eq!(contact.get_total_volume(), U256::from(500));
+        assert_eq!(contact.recent_transactions, vec![result.tx_hash]);
+
+        let json = result.to_json();
+        assert_eq!(json["status"], "success");
+        assert_eq!(json["value"], "500");
+        assert_eq!(json["token_symbol"], "RBTC");
+        assert_eq!(json["to"], format!("{:#x}", Address::repeat_byte(2)));
+
+        let failed = TransferResult {
+            status: U64::from(0),
+            ..result
+        };
+        assert!(!failed.succeeded());
+        assert_eq!(failed.to_json()["status"], "failed");
+        let pending = TransferResult {
+            gas_used: U256::ZERO,
+            ..failed
+        };
+        assert_eq!(pending.to_json()["status"], "pending");
+    }
+}
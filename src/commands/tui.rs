@@ -0,0 +1,57 @@
+use crate::types::wallet::{Wallet, WalletData};
+use crate::utils::output;
+use anyhow::{Result, anyhow};
+use clap::Parser;
+use std::io::IsTerminal;
+use std::time::Duration;
+
+/// Full-screen dashboard with balances, recent transactions, pending
+/// operations and network status, refreshed live
+#[derive(Parser, Debug, Clone)]
+pub struct TuiCommand {
+    /// Wallet to show first (defaults to the current wallet); `w` switches
+    /// between wallets
+    #[arg(long)]
+    pub wallet: Option<String>,
+
+    /// Seconds between refreshes when no WebSocket endpoint gives live
+    /// updates
+    #[arg(long, default_value = "15", value_parser = clap::value_parser!(u64).range(1..))]
+    pub interval: u64,
+}
+
+impl TuiCommand {
+    pub async fn execute(&self) -> Result<()> {
+        if output::is_json() || !std::io::stdout().is_terminal() {
+            return Err(anyhow!(
+                "The dashboard needs a terminal; use `balance`, `history` or `pending list` in scripts"
+            ));
+        }
+
+        let wallet_data = WalletData::load()?;
+        let mut wallets: Vec<Wallet> = wallet_data.wallets.values().cloned().collect();
+        if wallets.is_empty() {
+            return Err(anyhow!(
+                "No wallets found. Please create or import a wallet first."
+            ));
+        }
+        wallets.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let selected = match &self.wallet {
+            Some(name) => wallets
+                .iter()
+                .position(|wallet| wallet.name == *name)
+                .ok_or_else(|| anyhow!("Wallet '{}' not found", name))?,
+            None => wallet_data
+                .get_current_wallet()
+                .and_then(|current| {
+                    wallets
+                        .iter()
+                        .position(|wallet| wallet.address == current.address)
+                })
+                .unwrap_or(0),
+        };
+
+        crate::tui::run(wallets, selected, Duration::from_secs(self.interval)).await
+    }
+}
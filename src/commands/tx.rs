@@ -1,16 +1,27 @@
 use anyhow::Context;
-use clap::Parser;
-use console::style;
+use clap::{Parser, Subcommand};
+use console::{Term, style};
 use serde_json::Value;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-use crate::{api::ApiProvider, config::ConfigManager, types::network::Network};
+use crate::commands::offline::{self, BuildArgs};
+use crate::{
+    api::ApiProvider, config::ConfigManager, types::network::Network,
+    utils::{alchemy, confirmations::ConfirmationPolicy, http, live::Ticker, output},
+};
+use alloy::primitives::{Address, B256, U256};
 
 /// Command to check transaction status
 #[derive(Debug, Parser)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct TxCommand {
+    #[command(subcommand)]
+    pub action: Option<TxAction>,
+
     /// Transaction hash to check
-    #[arg(short, long)]
-    pub tx_hash: String,
+    #[arg(short, long, required = true)]
+    pub tx_hash: Option<String>,
 
     /// Use testnet
     #[arg(long)]
@@ -19,11 +30,242 @@ pub struct TxCommand {
     /// Alchemy API key (optional, will use saved key if not provided)
     #[arg(long)]
     pub api_key: Option<String>,
+
+    /// Keep polling until the transaction is confirmed, fails or times out
+    #[arg(long)]
+    pub follow: bool,
+
+    /// Confirmations required before a followed transaction counts as final
+    /// (defaults to the configured confirmation policy for its asset and amount)
+    #[arg(long)]
+    pub confirmations: Option<u64>,
+
+    /// Seconds to follow a transaction before giving up
+    #[arg(long, default_value = "600")]
+    pub timeout: u64,
+}
+
+/// Air-gapped signing: build on an online machine, sign on an offline one,
+/// broadcast back on the online one
+#[derive(Debug, Subcommand)]
+pub enum TxAction {
+    /// Build a transfer from the current wallet and write it out unsigned
+    Build(BuildArgs),
+    /// Sign a transaction written by `tx build --unsigned`; needs no network
+    Sign {
+        /// The unsigned transaction file, or images of all its QR frames
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+        /// File to write the signed transaction to
+        #[arg(long, default_value = "signed-tx.json")]
+        output: PathBuf,
+        /// Also show the signed transaction as a QR code
+        #[arg(long)]
+        qr: bool,
+    },
+    /// Broadcast a transaction signed with `tx sign`
+    BroadcastSigned {
+        /// Signed transaction file, QR code image, or 0x-prefixed raw hex
+        input: String,
+    },
+}
+
+impl TxAction {
+    pub async fn execute(&self) -> anyhow::Result<()> {
+        match self {
+            TxAction::Build(args) => offline::build(args).await,
+            TxAction::Sign { inputs, output, qr } => offline::sign(inputs, output, *qr).await,
+            TxAction::BroadcastSigned { input } => offline::broadcast_signed(input).await,
+        }
+    }
+}
+
+/// How a followed transaction ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowOutcome {
+    Confirmed,
+    Failed,
+    TimedOut,
+}
+
+impl FollowOutcome {
+    /// Process exit code for scripts: 0 confirmed, 1 failed, 2 timed out
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            FollowOutcome::Confirmed => 0,
+            FollowOutcome::Failed => 1,
+            FollowOutcome::TimedOut => 2,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FollowOutcome::Confirmed => "confirmed",
+            FollowOutcome::Failed => "failed",
+            FollowOutcome::TimedOut => "timed_out",
+        }
+    }
 }
 
 impl TxCommand {
     pub async fn execute(&self) -> anyhow::Result<()> {
-        let client = reqwest::Client::new();
+        if let Some(action) = &self.action {
+            return action.execute().await;
+        }
+        let (client, url, api_key) = self.rpc_client()?;
+
+        // Get receipt first as it contains the status
+        let receipt = self
+            .get_transaction_receipt(&client, &url, &api_key, self.hash())
+            .await?;
+
+        // Get transaction details for additional info
+        let tx_details = self
+            .get_transaction_details(&client, &url, &api_key, self.hash())
+            .await?;
+
+        // Display the information
+        self.display_transaction_info(&tx_details, &receipt)?;
+
+        output::emit(&status_json(self.hash(), &tx_details, &receipt))
+    }
+
+    /// Poll the transaction and keep a single status line up to date as it
+    /// moves from pending to included to the requested confirmations. With a
+    /// WebSocket endpoint configured, the line is refreshed on every new block
+    /// instead of at the poll interval.
+    pub async fn follow(&self) -> anyhow::Result<FollowOutcome> {
+        let (client, url, api_key) = self.rpc_client()?;
+        let polling = crate::config::PollingConfig::current();
+        let deadline = Instant::now() + Duration::from_secs(self.timeout);
+        let term = Term::stdout();
+        let network = if self.testnet {
+            Network::Testnet
+        } else {
+            Network::Mainnet
+        };
+        let config = ConfigManager::new()?.load()?;
+        let mut ticker = Ticker::connect(
+            config.ws_url_for(&network.get_config().name),
+            polling.receipt_poll_interval(),
+            self.hash().parse::<B256>().ok(),
+        )
+        .await;
+
+        println!("{}", style(format!("Following {}", self.hash())).bold());
+        let mut required = self.confirmations;
+        loop {
+            let receipt = self
+                .rpc_result(&client, &url, &api_key, "eth_getTransactionReceipt")
+                .await?;
+            let status_line = if receipt.is_null() {
+                let tx = self
+                    .rpc_result(&client, &url, &api_key, "eth_getTransactionByHash")
+                    .await?;
+                if tx.is_null() {
+                    "🔎 Not seen by the node yet".to_string()
+                } else {
+                    "⏳ Pending in mempool".to_string()
+                }
+            } else {
+                let included = hex_quantity(&receipt["blockNumber"]).unwrap_or(0);
+                if matches!(receipt["status"].as_str(), Some("0x0") | Some("0x00")) {
+                    term.clear_line()?;
+                    println!(
+                        "{}",
+                        style(format!("✗ Failed in block {}", included))
+                            .red()
+                            .bold()
+                    );
+                    return Ok(FollowOutcome::Failed);
+                }
+
+                let head_value = self
+                    .rpc_call(
+                        &client,
+                        &url,
+                        &api_key,
+                        "eth_blockNumber",
+                        serde_json::json!([]),
+                    )
+                    .await?;
+                let head = hex_quantity(&head_value).unwrap_or(included);
+                let confirmations = head.saturating_sub(included) + 1;
+                let needed = match required {
+                    Some(needed) => needed,
+                    None => {
+                        let needed = self.policy_confirmations(&client, &url, &api_key).await?;
+                        required = Some(needed);
+                        needed
+                    }
+                };
+                if confirmations >= needed {
+                    term.clear_line()?;
+                    println!(
+                        "{}",
+                        style(format!(
+                            "✓ Confirmed in block {} ({} confirmations)",
+                            included, confirmations
+                        ))
+                        .green()
+                        .bold()
+                    );
+                    return Ok(FollowOutcome::Confirmed);
+                }
+                format!(
+                    "📦 Included in block {} ({}/{} confirmations)",
+                    included, confirmations, needed
+                )
+            };
+
+            term.clear_line()?;
+            term.write_str(&status_line)?;
+
+            if Instant::now() >= deadline {
+                println!();
+                println!(
+                    "{}",
+                    style(format!("⌛ Timed out after {}s", self.timeout))
+                        .yellow()
+                        .bold()
+                );
+                return Ok(FollowOutcome::TimedOut);
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let _ = tokio::time::timeout(remaining, ticker.tick()).await;
+        }
+    }
+
+    /// Confirmations the configured policy requires for this transaction
+    async fn policy_confirmations(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        api_key: &str,
+    ) -> anyhow::Result<u64> {
+        let tx = self
+            .rpc_result(client, url, api_key, "eth_getTransactionByHash")
+            .await?;
+        let to = tx["to"].as_str().and_then(|to| to.parse::<Address>().ok());
+        let value = tx["value"]
+            .as_str()
+            .and_then(|value| U256::from_str_radix(value.trim_start_matches("0x"), 16).ok())
+            .unwrap_or_default();
+        let input = tx["input"]
+            .as_str()
+            .and_then(|input| hex::decode(input.trim_start_matches("0x")).ok())
+            .unwrap_or_default();
+        Ok(ConfirmationPolicy::current().required_for_call(to, value, &input))
+    }
+
+    /// The hash to check; clap requires it whenever there is no subcommand
+    pub(crate) fn hash(&self) -> &str {
+        self.tx_hash.as_deref().unwrap_or_default()
+    }
+
+    /// HTTP client, endpoint and API key for the selected network
+    fn rpc_client(&self) -> anyhow::Result<(reqwest::Client, String, String)> {
+        let client = http::secure_client()?;
         let network = if self.testnet {
             Network::RootStockTestnet
         } else {
@@ -48,28 +290,59 @@ impl TxCommand {
                 .to_string()
         };
 
-        let base_url = if self.testnet {
-            "https://rootstock-testnet.g.alchemy.com/v2"
-        } else {
-            "https://rootstock-mainnet.g.alchemy.com/v2"
-        };
+        Ok((client, alchemy::base_url(self.testnet), api_key))
+    }
 
-        let url = base_url.to_string();
+    /// Call a method taking the transaction hash; a null result (unknown or
+    /// pending transaction) is returned as `Value::Null`
+    async fn rpc_result(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        api_key: &str,
+        method: &str,
+    ) -> anyhow::Result<Value> {
+        self.rpc_call(
+            client,
+            url,
+            api_key,
+            method,
+            serde_json::json!([self.hash()]),
+        )
+        .await
+    }
 
-        // Get receipt first as it contains the status
-        let receipt = self
-            .get_transaction_receipt(&client, &url, &api_key, &self.tx_hash)
-            .await?;
+    async fn rpc_call(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        api_key: &str,
+        method: &str,
+        params: Value,
+    ) -> anyhow::Result<Value> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params
+        });
 
-        // Get transaction details for additional info
-        let tx_details = self
-            .get_transaction_details(&client, &url, &api_key, &self.tx_hash)
-            .await?;
+        let mut response = client
+            .post(url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Request failed: {}", e))?
+            .json::<Value>()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to parse response: {}", e))?;
 
-        // Display the information
-        self.display_transaction_info(&tx_details, &receipt)?;
+        if let Some(error) = response.get("error") {
+            anyhow::bail!("Alchemy API error: {}", error);
+        }
 
-        Ok(())
+        Ok(response["result"].take())
     }
 
     async fn get_transaction_receipt(
@@ -204,7 +477,7 @@ impl TxCommand {
         println!("\n{}\n", style("Transaction Details").bold().underlined());
         println!("{}", "-".repeat(60));
 
-        println!("{}", style(format!("  Hash: {}", self.tx_hash)).dim());
+        println!("{}", style(format!("  Hash: {}", self.hash())).dim());
         println!("{}", style(format!("  Block: {}", block_number)).dim());
         println!("{}", style(format!("  From: {}", from)).dim());
         println!("{}", style(format!("  To: {}", to)).dim());
@@ -245,12 +518,12 @@ impl TxCommand {
         let explorer_url = if self.testnet {
             format!(
                 "https://explorer.testnet.rsk.co/tx/{}",
-                self.tx_hash.trim_start_matches("0x")
+                self.hash().trim_start_matches("0x")
             )
         } else {
             format!(
                 "https://explorer.rsk.co/tx/{}",
-                self.tx_hash.trim_start_matches("0x")
+                self.hash().trim_start_matches("0x")
             )
         };
 
@@ -268,3 +541,97 @@ impl TxCommand {
         Ok(())
     }
 }
+
+/// Parse a hex quantity such as a block number ("0x1b4")
+fn hex_quantity(value: &Value) -> Option<u64> {
+    u64::from_str_radix(value.as_str()?.trim_start_matches("0x"), 16).ok()
+}
+
+/// Status of a transaction for `--json`: amounts in wei, the block and gas
+/// as numbers, and `null` for what is not known yet
+fn status_json(hash: &str, tx: &Value, receipt: &Value) -> Value {
+    let amount = |value: &Value| {
+        value
+            .as_str()
+            .and_then(|v| U256::from_str_radix(v.trim_start_matches("0x"), 16).ok())
+            .map(|v| v.to_string())
+    };
+    let status = match receipt["status"].as_str() {
+        Some("0x1") | Some("0x01") => "success",
+        Some("0x0") | Some("0x00") => "failed",
+        _ => "pending",
+    };
+    serde_json::json!({
+        "hash": hash,
+        "status": status,
+        "block": hex_quantity(&receipt["blockNumber"]),
+        "from": tx["from"].as_str(),
+        "to": tx["to"].as_str(),
+        "value": amount(&tx["value"]),
+        "gas_price": amount(&tx["gasPrice"]),
+        "gas_used": hex_quantity(&receipt["gasUsed"]),
+        "contract_address": receipt["contractAddress"].as_str(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_json() {
+        let tx = serde_json::json!({
+            "from": "0x01",
+            "to": null,
+            "value": "0xde0b6b3a7640000",
+            "gasPrice": "0x3b9aca00",
+        });
+        let receipt = serde_json::json!({
+            "status": "0x0",
+            "blockNumber": "0x10",
+            "gasUsed": "0x5208",
+            "contractAddress": "0x02",
+        });
+        let json = status_json("0xabc", &tx, &receipt);
+        assert_eq!(json["status"], "failed");
+        assert_eq!(json["block"], 16);
+        assert_eq!(json["to"], Value::Null);
+        assert_eq!(json["value"], "1000000000000000000");
+        assert_eq!(json["gas_used"], 21_000);
+        assert_eq!(json["contract_address"], "0x02");
+
+        // Not mined yet: no receipt
+        let json = status_json("0xabc", &tx, &Value::Null);
+        assert_eq!(json["status"], "pending");
+        assert_eq!(json["block"], Value::Null);
+    }
+
+    #[test]
+    fn test_follow_and_action_parsing() {
+        let cmd = TxCommand::try_parse_from([
+            "tx", "--tx-hash", "0xabc", "--follow", "--confirmations", "3",
+        ])
+        .unwrap();
+        assert!(cmd.follow && cmd.action.is_none());
+        assert_eq!((cmd.hash(), cmd.confirmations, cmd.timeout), ("0xabc", Some(3), 600));
+
+        // A subcommand needs no hash, and cannot be combined with one
+        let cmd = TxCommand::try_parse_from(["tx", "sign", "unsigned.json", "--qr"]).unwrap();
+        match cmd.action {
+            Some(TxAction::Sign { inputs, output, qr }) => {
+                assert_eq!(inputs, vec![PathBuf::from("unsigned.json")]);
+                assert_eq!(output, PathBuf::from("signed-tx.json"));
+                assert!(qr);
+            }
+            other => panic!("unexpected action {:?}", other),
+        }
+        assert!(TxCommand::try_parse_from(["tx"]).is_err());
+        assert!(TxCommand::try_parse_from(["tx", "sign"]).is_err());
+        let both = ["tx", "--tx-hash", "0xabc", "sign", "a.json"];
+        assert!(TxCommand::try_parse_from(both).is_err());
+
+        let codes = [FollowOutcome::Confirmed, FollowOutcome::Failed, FollowOutcome::TimedOut]
+            .map(|outcome| (outcome.exit_code(), outcome.as_str()));
+        assert_eq!(codes, [(0, "confirmed"), (1, "failed"), (2, "timed_out")]);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::security::updates;
+use anyhow::{Result, anyhow};
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+/// Check for new releases of the wallet
+#[derive(Parser, Debug)]
+pub struct UpdateCommand {
+    #[command(subcommand)]
+    pub action: UpdateAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum UpdateAction {
+    /// Check the release feed for a newer release and verify its signature
+    Check {
+        /// Download the verified binary for this platform into this directory
+        #[arg(long)]
+        download: Option<PathBuf>,
+    },
+}
+
+impl UpdateCommand {
+    pub async fn execute(&self) -> Result<()> {
+        match &self.action {
+            UpdateAction::Check { download } => check(download.as_deref()).await,
+        }
+    }
+}
+
+/// Report whether a newer release exists, without ever installing it
+async fn check(download: Option<&Path>) -> Result<()> {
+    let current = env!("CARGO_PKG_VERSION");
+    let client = updates::http_client()?;
+    let release = updates::latest_release(&client).await?;
+
+    if !release.is_newer_than(current) {
+        println!(
+            "{}: You are running the latest version ({})",
+            "Success".green().bold(),
+            current
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{}: Version {} is available (you have {})",
+        "Update".cyan().bold(),
+        release.version(),
+        current
+    );
+    if release.is_security_release() {
+        println!(
+            "{}",
+            "⚠️  This release contains security fixes. Update as soon as possible."
+                .red()
+                .bold()
+        );
+    }
+    println!("Release notes: {}", release.html_url);
+
+    let verified = match updates::verify_release(&client, release).await {
+        Ok(verified) => {
+            println!(
+                "{}: Checksums signed by the release key 0x{:x}",
+                "Verified".green().bold(),
+                verified.signer
+            );
+            verified
+        }
+        Err(e) => {
+            println!(
+                "{}: Could not verify this release: {}",
+                "Warning".yellow().bold(),
+                e
+            );
+            if download.is_some() {
+                return Err(anyhow!("Refusing to download an unverified release"));
+            }
+            return Ok(());
+        }
+    };
+
+    match download {
+        Some(dir) => {
+            let asset = verified.release.asset_for_platform().ok_or_else(|| {
+                anyhow!(
+                    "Release {} has no binary for {}-{}",
+                    verified.release.tag_name,
+                    std::env::consts::OS,
+                    std::env::consts::ARCH
+                )
+            })?;
+            let path = verified.download(&client, asset, dir).await?;
+            println!(
+                "{}: Downloaded {} and checked it against the signed checksums",
+                "Success".green().bold(),
+                path.display()
+            );
+            println!("Replace your current binary with it to install the update.");
+        }
+        None => println!(
+            "Run `rootstock-wallet update check --download <dir>` to fetch the verified binary."
+        ),
+    }
+    Ok(())
+}
@@ -0,0 +1,254 @@
+use crate::commands::pending::read_client;
+use crate::config::ConfigManager;
+use crate::utils::address_validation::{self, AddressReport, Checksum};
+use crate::utils::output;
+use crate::utils::profiles::ProfileMatch;
+use crate::utils::table::TableBuilder;
+use alloy::primitives::utils::format_units;
+use alloy::providers::Provider;
+use anyhow::{Context, Result, anyhow};
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Check lists of addresses before using them
+#[derive(Parser, Debug)]
+pub struct ValidateCommand {
+    #[command(subcommand)]
+    pub action: ValidateAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ValidateAction {
+    /// Check every address in a file: checksum, contract or EOA, prior
+    /// activity and blocklist membership
+    Addresses {
+        /// One address per line, or a CSV with an address column
+        file: PathBuf,
+        /// File of blocked addresses, one per line
+        #[arg(long)]
+        blocklist: Option<PathBuf>,
+        /// Only run the checks that need no network
+        #[arg(long)]
+        offline: bool,
+        /// Also write the report as CSV
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+}
+
+impl ValidateCommand {
+    pub async fn execute(&self) -> Result<()> {
+        match &self.action {
+            ValidateAction::Addresses {
+                file,
+                blocklist,
+                offline,
+                output,
+            } => validate_addresses(file, blocklist.as_deref(), *offline, output.as_deref()).await,
+        }
+    }
+}
+
+async fn validate_addresses(
+    file: &Path,
+    blocklist: Option<&Path>,
+    offline: bool,
+    output: Option<&Path>,
+) -> Result<()> {
+    let content = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let entries = address_validation::read_entries(&content);
+    if entries.is_empty() {
+        return Err(anyhow!("No addresses found in {}", file.display()));
+    }
+    let blocklist = match blocklist {
+        Some(path) => address_validation::load_blocklist(path)?,
+        None => HashSet::new(),
+    };
+
+    let config = ConfigManager::new()?.load()?;
+    let network = if config.default_network.is_testnet() {
+        "testnet"
+    } else {
+        "mainnet"
+    };
+    let mut reports = AddressReport::check_all(
+        entries,
+        config.default_network.chain_id(),
+        network,
+        &blocklist,
+    );
+
+    if !offline {
+        println!(
+            "Checking {} addresses on {}...",
+            reports.len(),
+            config.default_network
+        );
+        let client = read_client().await?;
+        let provider = client.provider();
+        for report in reports.iter_mut() {
+            let Some(address) = report.address else {
+                continue;
+            };
+            if report.duplicate_of.is_some() {
+                continue;
+            }
+            let code = provider
+                .get_code_at(address)
+                .await
+                .map_err(|e| anyhow!("Failed to check 0x{:x}: {}", address, e))?;
+            report.is_contract = Some(!code.is_empty());
+            report.tx_count = Some(
+                provider
+                    .get_transaction_count(address)
+                    .await
+                    .map_err(|e| anyhow!("Failed to check 0x{:x}: {}", address, e))?,
+            );
+            report.balance = Some(
+                provider
+                    .get_balance(address)
+                    .await
+                    .map_err(|e| anyhow!("Failed to check 0x{:x}: {}", address, e))?,
+            );
+        }
+    }
+
+    let mut table = TableBuilder::new();
+    table.add_header(&[
+        "Line", "Address", "Checksum", "Kind", "Txs", "Balance", "Result",
+    ]);
+    for report in &reports {
+        let cells = Cells::of(report);
+        table.add_row(&[
+            &report.entry.line.to_string(),
+            &cells.address,
+            &cells.checksum,
+            &cells.kind,
+            &cells.tx_count,
+            &cells.balance,
+            &cells.result,
+        ]);
+    }
+    table.print();
+
+    if let Some(output) = output {
+        let mut writer = csv::Writer::from_path(output)
+            .with_context(|| format!("Failed to write {}", output.display()))?;
+        writer.write_record([
+            "line", "input", "address", "checksum", "kind", "tx_count", "balance", "result",
+        ])?;
+        for report in &reports {
+            let cells = Cells::of(report);
+            writer.write_record([
+                &report.entry.line.to_string(),
+                &report.entry.input,
+                &cells.address,
+                &cells.checksum,
+                &cells.kind,
+                &cells.tx_count,
+                &cells.balance,
+                &cells.result,
+            ])?;
+        }
+        writer.flush()?;
+        println!("Report written to {}", output.display());
+    }
+
+    let failed = reports.iter().filter(|r| !r.problems().is_empty()).count();
+    let flagged = reports
+        .iter()
+        .filter(|r| r.problems().is_empty() && !r.warnings().is_empty())
+        .count();
+    println!(
+        "{} addresses: {} ok, {} to review, {} failed",
+        reports.len(),
+        reports.len() - failed - flagged,
+        flagged,
+        failed
+    );
+    output::emit(&serde_json::json!({
+        "ok": reports.len() - failed - flagged,
+        "review": flagged,
+        "failed": failed,
+        "addresses": reports.iter().map(report_json).collect::<Vec<_>>(),
+    }))?;
+    if failed > 0 {
+        return Err(anyhow!("{} addresses failed validation", failed));
+    }
+    println!("{}: No blocking problems found", "Success".green().bold());
+    Ok(())
+}
+
+/// One entry of the report for `--json`, with the balance in wei
+fn report_json(report: &AddressReport) -> serde_json::Value {
+    serde_json::json!({
+        "line": report.entry.line,
+        "input": report.entry.input,
+        "address": report.address.map(|a| a.to_checksum(None)),
+        "checksum": match &report.checksum {
+            Some(Checksum::Valid) => "valid",
+            Some(Checksum::Missing) => "none",
+            None => "invalid",
+        },
+        "contract": report.is_contract,
+        "tx_count": report.tx_count,
+        "balance": report.balance.map(|b| b.to_string()),
+        "problems": report.problems(),
+        "warnings": report.warnings(),
+    })
+}
+
+/// Report columns as text, shared by the table and the CSV
+struct Cells {
+    address: String,
+    checksum: String,
+    kind: String,
+    tx_count: String,
+    balance: String,
+    result: String,
+}
+
+impl Cells {
+    fn of(report: &AddressReport) -> Self {
+        let unknown = || "-".to_string();
+        let kind = match (&report.profile, report.is_contract) {
+            (Some(ProfileMatch::Verified(profile)), _) => format!("Contract ({})", profile.name),
+            (_, Some(true)) => "Contract".to_string(),
+            (_, Some(false)) => "EOA".to_string(),
+            (_, None) => unknown(),
+        };
+        let problems = report.problems();
+        let warnings = report.warnings();
+        let result = if !problems.is_empty() {
+            format!("FAIL: {}", problems.join("; "))
+        } else if !warnings.is_empty() {
+            format!("Review: {}", warnings.join("; "))
+        } else {
+            "OK".to_string()
+        };
+        Self {
+            address: report
+                .address
+                .map(|a| a.to_checksum(None))
+                .unwrap_or_else(|| report.entry.input.clone()),
+            checksum: match &report.checksum {
+                Some(Checksum::Valid) => "Valid".to_string(),
+                Some(Checksum::Missing) => "None".to_string(),
+                None => "Invalid".to_string(),
+            },
+            kind,
+            tx_count: report
+                .tx_count
+                .map(|n| n.to_string())
+                .unwrap_or_else(unknown),
+            balance: report
+                .balance
+                .map(|b| format!("{} RBTC", format_units(b, 18).unwrap_or_default()))
+                .unwrap_or_else(unknown),
+            result,
+        }
+    }
+}
@@ -1,11 +1,27 @@
-use crate::types::wallet::{Wallet, WalletData};
-use crate::utils::{constants, helper::Config, table::TableBuilder};
-use anyhow::{Result, anyhow};
+use crate::commands::contacts::{ContactsAction, ContactsCommand};
+use crate::commands::tokens::TokenRegistry;
+use crate::config::ConfigManager;
+use crate::security::secrets;
+use crate::types::network::NetworkConfig;
+use crate::types::wallet::{HardwareAccount, HardwareDevice, Wallet, WalletData};
+use crate::utils::eth::EthClient;
+use crate::utils::helper::WalletConfig;
+use crate::utils::mnemonic::{self, MnemonicLanguage};
+use crate::utils::output;
+use crate::utils::roles::{self, Capability};
+use crate::utils::signer;
+use crate::utils::sponsor;
+use crate::utils::storage::Storage;
+use crate::utils::store;
+use crate::utils::{helper::Config, table::TableBuilder};
+use anyhow::{Context, Result, anyhow};
 use clap::Parser;
 use colored::Colorize;
+use alloy::primitives::{Address, U256};
 use alloy::signers::local::PrivateKeySigner;
 
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
@@ -26,6 +42,23 @@ pub enum WalletAction {
         name: String,
         password: String,
     },
+    /// Create a wallet from a newly generated recovery phrase
+    CreateWithMnemonic {
+        name: String,
+        password: String,
+        #[arg(long, default_value = "english")]
+        language: MnemonicLanguage,
+        #[arg(long, default_value = "12")]
+        words: usize,
+    },
+    /// Import a wallet from a recovery phrase, detecting its language if not given
+    ImportMnemonic {
+        phrase: String,
+        name: String,
+        password: String,
+        #[arg(long)]
+        language: Option<MnemonicLanguage>,
+    },
     List,
     Switch {
         name: String,
@@ -41,11 +74,65 @@ pub enum WalletAction {
     Delete {
         name: String,
     },
+    /// Move all funds to a freshly generated key and archive the old wallet.
+    /// Naming an existing wallet resumes an unfinished rotation into it.
+    Rotate {
+        name: String,
+        password: String,
+        new_name: String,
+        new_password: String,
+    },
+    /// Add a Trezor account after confirming its address on the device
+    AddTrezor {
+        name: String,
+        #[arg(long, default_value = "0")]
+        account: u32,
+    },
+    /// Show a hardware wallet's address on the device and check it matches
+    VerifyAddress {
+        name: String,
+    },
+    /// Derive the next account from a wallet's recovery phrase
+    AddAccount {
+        name: String,
+        password: String,
+        /// Name for the new account
+        #[arg(long)]
+        label: Option<String>,
+    },
+    /// Label an address, e.g. "cold storage"; without a label its label is
+    /// removed
+    Label {
+        address: String,
+        label: Option<String>,
+    },
+    /// Write wallets, contacts, the history cache, the transfer queue and
+    /// address labels out as JSON files
+    ExportJson {
+        dir: PathBuf,
+    },
+    /// Write the key of the wallet database to a file. Keep it safe and
+    /// apart from the data: without it the database cannot be read once the
+    /// OS keyring is lost or on another machine.
+    BackupKey {
+        path: PathBuf,
+    },
+    /// Put a key written by `backup-key` into the OS keyring, e.g. after
+    /// restoring the data directory on another machine
+    RestoreKey {
+        path: PathBuf,
+    },
 }
 
 impl WalletCommand {
     pub async fn execute(&self) -> Result<()> {
         let config = Config::default(); // Use default config
+        if !matches!(
+            self.action,
+            WalletAction::List | WalletAction::Switch { .. } | WalletAction::VerifyAddress { .. }
+        ) {
+            roles::require(Capability::ManageWallets)?;
+        }
         match &self.action {
             WalletAction::Create { name, password } => {
                 self.create_wallet(&config, name, password).await?
@@ -58,6 +145,20 @@ impl WalletCommand {
                 self.import_wallet(&config, private_key, name, password)
                     .await?
             }
+            WalletAction::CreateWithMnemonic {
+                name,
+                password,
+                language,
+                words,
+            } => {
+                self.create_wallet_with_mnemonic(name, password, *language, *words)?;
+            }
+            WalletAction::ImportMnemonic {
+                phrase,
+                name,
+                password,
+                language,
+            } => self.import_mnemonic(phrase, name, password, *language)?,
             WalletAction::List => self.list_wallets(&config)?,
             WalletAction::Switch { name } => self.switch_wallet(name)?,
             WalletAction::Rename { old_name, new_name } => {
@@ -65,32 +166,61 @@ impl WalletCommand {
             }
             WalletAction::Backup { name, path } => self.backup_wallet(&config, name, path)?,
             WalletAction::Delete { name } => self.delete_wallet(&config, name)?,
+            WalletAction::Rotate {
+                name,
+                password,
+                new_name,
+                new_password,
+            } => {
+                self.rotate_wallet(name, password, new_name, new_password)
+                    .await?
+            }
+            WalletAction::AddTrezor { name, account } => self.add_trezor(name, *account)?,
+            WalletAction::VerifyAddress { name } => self.verify_address(name)?,
+            WalletAction::AddAccount {
+                name,
+                password,
+                label,
+            } => self.add_account(name, password, label.as_deref())?,
+            WalletAction::Label { address, label } => {
+                self.label_address(address, label.as_deref())?
+            }
+            WalletAction::ExportJson { dir } => self.export_json(dir)?,
+            WalletAction::BackupKey { path } => self.backup_key(path)?,
+            WalletAction::RestoreKey { path } => self.restore_key(path)?,
+        }
+        if let WalletAction::Create { name, .. }
+        | WalletAction::Import { name, .. }
+        | WalletAction::CreateWithMnemonic { name, .. }
+        | WalletAction::ImportMnemonic { name, .. } = &self.action
+        {
+            self.sponsor(name).await?;
+        }
+        Ok(())
+    }
+
+    /// Top up a newly created or imported wallet from the sponsor wallet,
+    /// if one is configured
+    pub async fn sponsor(&self, name: &str) -> Result<()> {
+        let wallet_data = WalletData::load()?;
+        if let Some(wallet) = wallet_data.get_wallet_by_name(name) {
+            sponsor::sponsor_new_wallet(wallet.address).await;
         }
         Ok(())
     }
 
     async fn create_wallet(&self, _config: &Config, name: &str, password: &str) -> Result<()> {
-        let wallet_file = constants::wallet_file_path();
-        if wallet_file.exists() {
-            let data = fs::read_to_string(&wallet_file)?;
-            let wallet_data = serde_json::from_str::<WalletData>(&data)?;
-            if wallet_data.get_wallet_by_name(name).is_some() {
-                return Err(anyhow!("Wallet with name '{}' already exists", name));
-            }
+        let mut wallet_data = WalletData::load()?;
+        if wallet_data.get_wallet_by_name(name).is_some() {
+            return Err(anyhow!("Wallet with name '{}' already exists", name));
         }
         let wallet = PrivateKeySigner::random();
         let wallet = Wallet::new(wallet, name, password)?;
-        let mut wallet_data = if wallet_file.exists() {
-            let data = fs::read_to_string(&wallet_file)?;
-            serde_json::from_str::<WalletData>(&data)?
-        } else {
-            WalletData::new()
-        };
         let _ = wallet_data.add_wallet(wallet.clone());
-        fs::write(&wallet_file, serde_json::to_string_pretty(&wallet_data)?)?;
+        wallet_data.save()?;
         println!("{}", "🎉 Wallet created successfully".green());
         println!("Address: {:?}", wallet.address());
-        println!("Wallet saved at: {}", wallet_file.display());
+        println!("Wallet saved at: {}", Storage::path()?.display());
         Ok(())
     }
 
@@ -103,70 +233,327 @@ impl WalletCommand {
     ) -> Result<()> {
         let wallet = PrivateKeySigner::from_str(private_key)?;
         let wallet = Wallet::new(wallet, name, password)?;
-        let wallet_file = constants::wallet_file_path();
-        let mut wallet_data = if wallet_file.exists() {
-            let data = fs::read_to_string(&wallet_file)?;
-            serde_json::from_str::<WalletData>(&data)?
-        } else {
-            WalletData::new()
-        };
+        let mut wallet_data = WalletData::load()?;
         let _ = wallet_data.add_wallet(wallet);
-        fs::write(&wallet_file, serde_json::to_string_pretty(&wallet_data)?)?;
+        wallet_data.save()?;
+        println!("{}", "✅ Wallet imported successfully".green());
+        println!("Wallet saved at: {}", Storage::path()?.display());
+        Ok(())
+    }
+
+    /// Generate a recovery phrase, save the derived wallet and return the phrase
+    pub fn create_wallet_with_mnemonic(
+        &self,
+        name: &str,
+        password: &str,
+        language: MnemonicLanguage,
+        words: usize,
+    ) -> Result<String> {
+        let phrase = mnemonic::generate(language, words)?;
+        let signer = mnemonic::signer_from_phrase(&phrase, language)?;
+        let wallet = self.save_new_wallet(signer, name, password, Some((&phrase, language)))?;
+
+        println!("{}", "🎉 Wallet created successfully".green());
+        println!("Address: {:?}", wallet.address());
+        println!(
+            "\n{} ({} wordlist):\n\n  {}\n",
+            "Recovery phrase".bold(),
+            language,
+            phrase
+        );
+        println!(
+            "{}",
+            "Write these words down in order and keep them offline. Only an encrypted copy is kept with the wallet."
+                .yellow()
+        );
+        if let Some(warning) = language.compatibility_warning() {
+            println!("{} {}", "⚠️".yellow(), warning.yellow());
+        }
+        Ok(phrase)
+    }
+
+    fn import_mnemonic(
+        &self,
+        phrase: &str,
+        name: &str,
+        password: &str,
+        language: Option<MnemonicLanguage>,
+    ) -> Result<()> {
+        let language = match language {
+            Some(language) => language,
+            None => {
+                let detected = mnemonic::detect_language(phrase)?;
+                println!("Detected {} wordlist", detected);
+                detected
+            }
+        };
+        if let Some(warning) = language.compatibility_warning() {
+            println!("{} {}", "⚠️".yellow(), warning.yellow());
+        }
+
+        let signer = mnemonic::signer_from_phrase(phrase, language)?;
+        let wallet = self.save_new_wallet(signer, name, password, Some((phrase, language)))?;
         println!("{}", "✅ Wallet imported successfully".green());
-        println!("Wallet saved at: {}", wallet_file.display());
+        println!("Address: {:?}", wallet.address());
+        Ok(())
+    }
+
+    fn save_new_wallet(
+        &self,
+        signer: PrivateKeySigner,
+        name: &str,
+        password: &str,
+        seed: Option<(&str, MnemonicLanguage)>,
+    ) -> Result<Wallet> {
+        let mut wallet_data = WalletData::load()?;
+        if wallet_data.get_wallet_by_name(name).is_some() {
+            return Err(anyhow!("Wallet with name '{}' already exists", name));
+        }
+        let mut wallet = Wallet::new(signer, name, password)?;
+        if let Some((phrase, language)) = seed {
+            wallet = wallet.with_seed(phrase, language, password)?;
+        }
+        wallet_data.add_wallet(wallet.clone())?;
+        wallet_data.save()?;
+        Ok(wallet)
+    }
+
+    fn add_trezor(&self, name: &str, account: u32) -> Result<()> {
+        let mut wallet_data = WalletData::load()?;
+        if wallet_data.get_wallet_by_name(name).is_some() {
+            return Err(anyhow!("Wallet with name '{}' already exists", name));
+        }
+
+        let derivation_path = signer::rsk_derivation_path(account);
+        println!(
+            "Confirm the address shown on your Trezor ({})...",
+            derivation_path
+        );
+        let address = signer::display_trezor_address(&derivation_path)?;
+        let wallet = Wallet::new_hardware(
+            address,
+            name,
+            HardwareAccount {
+                device: HardwareDevice::Trezor,
+                derivation_path,
+                address_verified_at: Some(chrono::Utc::now()),
+            },
+        );
+        wallet_data.add_wallet(wallet)?;
+        wallet_data.save()?;
+        println!("{}", "🎉 Trezor wallet added".green());
+        println!("Address: 0x{:x}", address);
+        Ok(())
+    }
+
+    /// Re-display the address on the device; do this before sharing it to receive funds
+    fn verify_address(&self, name: &str) -> Result<()> {
+        let mut wallet_data = WalletData::load()?;
+        let wallet = wallet_data
+            .get_wallet_by_name_mut(name)
+            .ok_or_else(|| anyhow!("Wallet '{}' not found", name))?;
+        let hardware = wallet
+            .hardware
+            .as_mut()
+            .ok_or_else(|| anyhow!("'{}' is not a hardware wallet", name))?;
+
+        println!(
+            "Check that your {} shows 0x{:x} and confirm it on the device...",
+            hardware.device, wallet.address
+        );
+        let address = signer::display_trezor_address(&hardware.derivation_path)?;
+        if address != wallet.address {
+            return Err(anyhow!(
+                "The device shows 0x{:x} but '{}' is stored as 0x{:x}; do not receive funds to it",
+                address,
+                name,
+                wallet.address
+            ));
+        }
+        hardware.address_verified_at = Some(chrono::Utc::now());
+        wallet_data.save()?;
+        println!("{}", "✅ Address verified on the device".green());
+        Ok(())
+    }
+
+    fn add_account(&self, name: &str, password: &str, label: Option<&str>) -> Result<()> {
+        let mut wallet_data = WalletData::load()?;
+        let wallet = wallet_data
+            .get_wallet_by_name_mut(name)
+            .ok_or_else(|| anyhow!("Wallet '{}' not found", name))?;
+        if wallet.seed.is_none() {
+            return Err(anyhow!(
+                "'{}' was not created from a recovery phrase, so no accounts can be derived",
+                name
+            ));
+        }
+        let label = label
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("Account {}", wallet.accounts.len() + 2));
+        let account = wallet.add_account(password, &label)?;
+        wallet_data.save()?;
+        println!(
+            "{}",
+            format!("🎉 Added account #{} '{}'", account.index, account.name).green()
+        );
+        println!("Address: 0x{:x}", account.address);
+        println!("Path: {}", signer::rsk_derivation_path(account.index));
         Ok(())
     }
 
     fn list_wallets(&self, _config: &Config) -> Result<()> {
-        let wallet_file = constants::wallet_file_path();
-        if !wallet_file.exists() {
+        let wallet_data = WalletData::load()?;
+        if wallet_data.wallets.is_empty() {
             println!("No wallets found");
-            return Ok(());
+            return output::emit(&[] as &[serde_json::Value]);
         }
-        let data = fs::read_to_string(&wallet_file)?;
-        let wallet_data = serde_json::from_str::<WalletData>(&data)?;
         let wallets = wallet_data.list_wallets();
+        let labels = store::open()?.load_labels()?;
         let mut table = TableBuilder::new();
-        table.add_row(&["Name", "Address", "Created At", "Current"]);
+        let mut json = Vec::new();
+        table.add_row(&["Name", "Address", "Label", "Created At", "Current"]);
         for wallet in wallets {
             let is_current = if let Some(current) = wallet_data.get_current_wallet() {
                 current.address == wallet.address
             } else {
                 false
             };
+            json.push(serde_json::json!({
+                "name": wallet.name,
+                "address": format!("{:#x}", wallet.address),
+                "label": labels.get(&wallet.address),
+                "created_at": wallet.created_at,
+                "current": is_current,
+                "watch_only": wallet.watch_only,
+                "hardware": wallet.hardware.as_ref().map(|hardware| hardware.device.to_string()),
+                "accounts": wallet.accounts.iter().map(|account| serde_json::json!({
+                    "index": account.index,
+                    "name": account.name,
+                    "address": format!("{:#x}", account.address),
+                    "label": labels.get(&account.address),
+                })).collect::<Vec<_>>(),
+            }));
+            let name = if wallet.watch_only {
+                format!("{} (watch-only)", wallet.name)
+            } else if let Some(hardware) = &wallet.hardware {
+                format!("{} ({})", wallet.name, hardware.device)
+            } else {
+                wallet.name.clone()
+            };
             table.add_row(&[
-                &wallet.name,
+                &name,
                 &format!("0x{:x}", wallet.address),
+                labels.get(&wallet.address).map_or("", String::as_str),
                 &wallet.created_at,
                 if is_current { "✓" } else { "" },
             ]);
+            for account in &wallet.accounts {
+                table.add_row(&[
+                    &format!("  └ #{} {}", account.index, account.name),
+                    &format!("0x{:x}", account.address),
+                    labels.get(&account.address).map_or("", String::as_str),
+                    "",
+                    "",
+                ]);
+            }
         }
         table.print();
+        output::emit(&json)
+    }
+
+    fn label_address(&self, address: &str, label: Option<&str>) -> Result<()> {
+        let address =
+            Address::from_str(address).map_err(|_| anyhow!("Invalid address: {}", address))?;
+        let mut store = store::open()?;
+        let mut labels = store.load_labels()?;
+        match label.map(str::trim).filter(|label| !label.is_empty()) {
+            Some(label) => {
+                labels.insert(address, label.to_string());
+                store.save_labels(&labels)?;
+                println!(
+                    "{}: Labelled 0x{:x} as '{}'",
+                    "Success".green().bold(),
+                    address,
+                    label
+                );
+            }
+            None => {
+                if labels.remove(&address).is_none() {
+                    return Err(anyhow!("0x{:x} has no label", address));
+                }
+                store.save_labels(&labels)?;
+                println!("{}: Removed the label of 0x{:x}", "Success".green().bold(), address);
+            }
+        }
+        Ok(())
+    }
+
+    fn export_json(&self, dir: &Path) -> Result<()> {
+        let written = Storage::open()?.export_json(dir)?;
+        println!(
+            "{}: Exported the wallet database to JSON",
+            "Success".green().bold()
+        );
+        for path in written {
+            println!("  {}", path.display());
+        }
+        Ok(())
+    }
+
+    fn backup_key(&self, path: &Path) -> Result<()> {
+        let mut storage = Storage::open()?;
+        let key = storage.key()?;
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options
+            .open(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        writeln!(file, "{}", key)?;
+        file.sync_all()?;
+        storage.confirm_key_backup()?;
+
+        println!(
+            "{}: Database key written to {}",
+            "Success".green().bold(),
+            path.display()
+        );
+        println!(
+            "Keep it offline and apart from the wallet data; restore it with `wallet restore-key`."
+        );
+        Ok(())
+    }
+
+    fn restore_key(&self, path: &Path) -> Result<()> {
+        let key = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Storage::restore_key(key.trim())?;
+        println!(
+            "{}: The database key is in the OS keyring",
+            "Success".green().bold()
+        );
         Ok(())
     }
 
     fn switch_wallet(&self, name: &str) -> Result<()> {
-        let wallet_file = constants::wallet_file_path();
-        let data = fs::read_to_string(&wallet_file)?;
-        let mut wallet_data = serde_json::from_str::<WalletData>(&data)?;
+        let mut wallet_data = WalletData::load()?;
         let wallet_address = wallet_data
             .get_wallet_by_name(name)
             .ok_or_else(|| anyhow!("Wallet '{}' not found", name))?
             .address;
         let _ = wallet_data.switch_wallet(&format!("0x{:x}", wallet_address));
-        fs::write(&wallet_file, serde_json::to_string_pretty(&wallet_data)?)?;
+        wallet_data.save()?;
         println!("{}", format!("✅ Switched to wallet: {}", name).green());
         println!("Address: 0x{:x}", wallet_address);
         Ok(())
     }
 
     fn rename_wallet(&self, _config: &Config, old_name: &str, new_name: &str) -> Result<()> {
-        let wallet_file = constants::wallet_file_path();
-        if !wallet_file.exists() {
+        let mut wallet_data = WalletData::load()?;
+        if wallet_data.wallets.is_empty() {
             return Err(anyhow!("No wallets found"));
         }
-        let data = fs::read_to_string(&wallet_file)?;
-        let mut wallet_data = serde_json::from_str::<WalletData>(&data)?;
         let wallet = wallet_data
             .get_wallet_by_name(old_name)
             .ok_or_else(|| anyhow!("Wallet '{}' not found", old_name))?;
@@ -182,7 +569,7 @@ impl WalletCommand {
         } else {
             return Err(anyhow!("Failed to rename wallet '{}'", old_name));
         }
-        fs::write(&wallet_file, serde_json::to_string_pretty(&wallet_data)?)?;
+        wallet_data.save()?;
         println!(
             "{}",
             format!("✅ Wallet renamed from '{}' to '{}'", old_name, new_name).green()
@@ -192,12 +579,10 @@ impl WalletCommand {
     }
 
     fn backup_wallet(&self, _config: &Config, name: &str, path: &Path) -> Result<()> {
-        let wallet_file = constants::wallet_file_path();
-        if !wallet_file.exists() {
+        let wallet_data = WalletData::load()?;
+        if wallet_data.wallets.is_empty() {
             return Err(anyhow!("No wallets found"));
         }
-        let data = fs::read_to_string(&wallet_file)?;
-        let wallet_data = serde_json::from_str::<WalletData>(&data)?;
         if name.ends_with(".json") {
             return Err(anyhow!(
                 "Invalid wallet name '{}'. Use --name for the wallet name and --path for the filename.",
@@ -225,9 +610,7 @@ impl WalletCommand {
     }
 
     fn delete_wallet(&self, _config: &Config, name: &str) -> Result<()> {
-        let wallet_file = constants::wallet_file_path();
-        let data = fs::read_to_string(&wallet_file)?;
-        let mut wallet_data = serde_json::from_str::<WalletData>(&data)?;
+        let mut wallet_data = WalletData::load()?;
         let wallet = wallet_data
             .get_wallet_by_name(name)
             .ok_or_else(|| anyhow!("Wallet '{}' not found", name))?;
@@ -237,10 +620,211 @@ impl WalletCommand {
                 "Cannot delete currently selected wallet. Please switch to a different wallet first."
             ));
         }
+        let wallet_address = wallet.address;
         let _ = wallet_data.remove_wallet(&address);
-        fs::write(&wallet_file, serde_json::to_string_pretty(&wallet_data)?)?;
+        wallet_data.save()?;
+        secrets::forget_wallet_password(&wallet_address)?;
         println!("{}", format!("✅ Deleted wallet: {}", name).green());
         println!("Address: {}", address);
         Ok(())
     }
+
+    async fn rotate_wallet(
+        &self,
+        name: &str,
+        password: &str,
+        new_name: &str,
+        new_password: &str,
+    ) -> Result<()> {
+        let config = ConfigManager::new()?.load()?;
+        let network = if config.default_network.is_testnet() {
+            "testnet"
+        } else {
+            "mainnet"
+        };
+        rotate_wallet(
+            &config.default_network.get_config(),
+            network,
+            name,
+            password,
+            new_name,
+            new_password,
+        )
+        .await
+    }
+}
+
+/// Move all funds of wallet `name` to a freshly generated key saved as
+/// `new_name`, through the node of `network_config` and with the tokens
+/// registered for `network`. Only when every transfer went through do
+/// contacts and the label of the old address move to the new one, the new
+/// wallet become the current one and the old one get archived as
+/// watch-only; otherwise everything is left as it was. When `new_name`
+/// already exists, unlocked by `new_password`, an earlier rotation that did
+/// not complete is resumed into it.
+pub async fn rotate_wallet(
+    network_config: &NetworkConfig,
+    network: &str,
+    name: &str,
+    password: &str,
+    new_name: &str,
+    new_password: &str,
+) -> Result<()> {
+    let mut wallet_data = WalletData::load()?;
+    if wallet_data.wallets.is_empty() {
+        return Err(anyhow!("No wallets found"));
+    }
+    let old_wallet = wallet_data
+        .get_wallet_by_name(name)
+        .ok_or_else(|| anyhow!("Wallet '{}' not found", name))?
+        .clone();
+    let private_key = old_wallet.decrypt_private_key(password)?;
+
+    let new_address = match wallet_data.get_wallet_by_name(new_name) {
+        Some(existing) => {
+            if existing.address == old_wallet.address {
+                return Err(anyhow!("Cannot rotate '{}' into itself", name));
+            }
+            // The password proves the wallet is the one the rotation created
+            existing.decrypt_private_key(new_password)?;
+            println!(
+                "{}: Resuming the rotation into the existing wallet '{}'",
+                "Info".yellow().bold(),
+                new_name
+            );
+            existing.address
+        }
+        None => {
+            // Persist the new key before moving any funds so it can never be
+            // lost. Adding a wallet makes it current; that waits until the
+            // funds moved.
+            let previous_current = wallet_data.current_wallet.clone();
+            let new_wallet = Wallet::new(PrivateKeySigner::random(), new_name, new_password)?;
+            let new_address = new_wallet.address();
+            wallet_data.add_wallet(new_wallet)?;
+            wallet_data.current_wallet = previous_current;
+            wallet_data.save()?;
+            println!("{}", "🎉 New wallet created".green());
+            println!("Address: 0x{:x}", new_address);
+            new_address
+        }
+    };
+
+    let client_config = Config {
+        network: network_config.clone(),
+        wallet: WalletConfig {
+            current_wallet_address: None,
+            private_key: Some(private_key),
+            mnemonic: None,
+        },
+    };
+    let eth_client = EthClient::new(&client_config, None).await?;
+
+    // Sweep tokens first so the old wallet keeps RBTC to pay for their gas
+    let registry = TokenRegistry::load().unwrap_or_default();
+    let mut failures = 0;
+    for (symbol, info) in registry.list_tokens(Some(network)) {
+        let token = match Address::from_str(&info.address) {
+            Ok(token) => token,
+            Err(_) => continue,
+        };
+        let balance = eth_client
+            .get_balance(&old_wallet.address, &Some(token))
+            .await
+            .unwrap_or(U256::ZERO);
+        if balance.is_zero() {
+            continue;
+        }
+        print!(
+            "Moving {} {}... ",
+            alloy::primitives::utils::format_units(balance, info.decimals)?,
+            symbol
+        );
+        match eth_client
+            .send_transaction(new_address, balance, Some(token))
+            .await
+        {
+            Ok(tx_hash) => match eth_client.wait_for_receipt(tx_hash).await {
+                Ok(receipt) if receipt.status() => println!("✅ 0x{:x}", tx_hash),
+                _ => {
+                    println!("❌ not confirmed (0x{:x})", tx_hash);
+                    failures += 1;
+                }
+            },
+            Err(e) => {
+                println!("❌ {}", e);
+                failures += 1;
+            }
+        }
+    }
+
+    print!("Moving remaining RBTC... ");
+    match eth_client.sweep_rbtc(new_address).await {
+        Ok(Some((tx_hash, amount))) => {
+            match eth_client.wait_for_receipt(tx_hash).await {
+                Ok(receipt) if receipt.status() => println!(
+                    "✅ {} RBTC (0x{:x})",
+                    alloy::primitives::utils::format_units(amount, 18)?,
+                    tx_hash
+                ),
+                _ => {
+                    println!("❌ not confirmed (0x{:x})", tx_hash);
+                    failures += 1;
+                }
+            }
+        }
+        Ok(None) => println!("nothing to move"),
+        Err(e) => {
+            println!("❌ {}", e);
+            failures += 1;
+        }
+    }
+
+    if failures > 0 {
+        return Err(anyhow!(
+            "{} transfer(s) did not complete. The current wallet, contacts and labels were not changed and '{}' was not archived; run the same rotation again to move the rest into '{}', or move it manually.",
+            failures,
+            name,
+            new_name
+        ));
+    }
+
+    // Point contacts and the label of the old address at the new one
+    let contacts_cmd = ContactsCommand {
+        action: ContactsAction::List,
+    };
+    let mut contacts = contacts_cmd.load_contacts()?;
+    let mut updated = 0;
+    for contact in contacts.iter_mut().filter(|c| c.address == old_wallet.address) {
+        contact.address = new_address;
+        updated += 1;
+    }
+    if updated > 0 {
+        contacts_cmd.save_contacts(&contacts)?;
+        println!("Updated {} contact(s) to the new address", updated);
+    }
+    let mut store = store::open()?;
+    let mut labels = store.load_labels()?;
+    if let Some(label) = labels.remove(&old_wallet.address) {
+        println!("Moved the label '{}' to the new address", label);
+        labels.insert(new_address, label);
+        store.save_labels(&labels)?;
+    }
+
+    // Archive the old wallet as watch-only
+    let old_key = format!("0x{:x}", old_wallet.address);
+    if let Some(wallet) = wallet_data.wallets.get_mut(&old_key) {
+        wallet.watch_only = true;
+    }
+    wallet_data.current_wallet = format!("0x{:x}", new_address);
+    wallet_data.save()?;
+    println!(
+        "{}",
+        format!(
+            "✅ Key rotated: '{}' archived as watch-only, '{}' is now the current wallet",
+            name, new_name
+        )
+        .green()
+    );
+    Ok(())
 }
@@ -0,0 +1,357 @@
+use crate::config::{ConfigManager, PollingConfig};
+use crate::types::wallet::WalletData;
+use crate::utils::eth::EthClient;
+use crate::utils::event_webhooks::{self, WalletEvent};
+use crate::utils::events::{self, DecodedLog};
+use crate::utils::helper::{Config as HelperConfig, WalletConfig};
+use crate::utils::history_export::{self, ExportRow};
+use crate::utils::live::Ticker;
+use crate::utils::{http, incoming, notifications, output, redact};
+use alloy::json_abi::Event;
+use alloy::primitives::Address;
+use alloy::providers::Provider;
+use alloy::rpc::types::Filter;
+use anyhow::{Result, anyhow};
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+/// Most blocks requested in a single log query
+const MAX_BLOCK_RANGE: u64 = 1000;
+
+/// Watch contracts and wallets for on-chain activity
+#[derive(Parser, Debug)]
+pub struct WatchCommand {
+    #[command(subcommand)]
+    pub action: WatchAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WatchAction {
+    /// Stream decoded events of a contract as they are emitted
+    Events {
+        /// Contract address
+        #[arg(long)]
+        address: String,
+        /// ABI JSON file, or `erc20` for the bundled token ABI
+        #[arg(long, default_value = "erc20")]
+        abi: String,
+        /// Event name or signature to watch; repeat for several (default: all)
+        #[arg(long = "event")]
+        events: Vec<String>,
+        /// Start from this block instead of the chain head
+        #[arg(long)]
+        from_block: Option<u64>,
+        /// POST each decoded event as JSON to this URL
+        #[arg(long)]
+        webhook: Option<String>,
+    },
+    /// Report RBTC and ERC-20 payments to the wallets of this profile, their
+    /// accounts and watch-only wallets, as they are mined
+    Incoming {
+        /// Also watch this address; repeat for several
+        #[arg(long = "address")]
+        addresses: Vec<String>,
+        /// Watch only the `--address` addresses, not the wallets
+        #[arg(long, requires = "addresses")]
+        only: bool,
+        /// Start from this block instead of the chain head
+        #[arg(long)]
+        from_block: Option<u64>,
+        /// POST each payment as JSON to this URL
+        #[arg(long)]
+        webhook: Option<String>,
+    },
+}
+
+impl WatchCommand {
+    pub async fn execute(&self) -> Result<()> {
+        match &self.action {
+            WatchAction::Events {
+                address,
+                abi,
+                events: names,
+                from_block,
+                webhook,
+            } => {
+                let contract = Address::from_str(address)
+                    .map_err(|_| anyhow!("Invalid contract address: {}", address))?;
+                let abi = events::load_abi(abi)?;
+                let watched = events::select_events(&abi, names)?;
+                watch_events(contract, &watched, *from_block, webhook.as_deref()).await
+            }
+            WatchAction::Incoming {
+                addresses,
+                only,
+                from_block,
+                webhook,
+            } => {
+                let mut watched = if *only {
+                    HashMap::new()
+                } else {
+                    wallet_addresses()?
+                };
+                for address in addresses {
+                    let address = Address::from_str(address)
+                        .map_err(|_| anyhow!("Invalid address: {}", address))?;
+                    watched
+                        .entry(address)
+                        .or_insert_with(|| format!("0x{:x}", address));
+                }
+                if watched.is_empty() {
+                    return Err(anyhow!(
+                        "No wallets to watch; create or import one, or give --address"
+                    ));
+                }
+                watch_incoming(&watched, *from_block, webhook.as_deref()).await
+            }
+        }
+    }
+}
+
+/// Poll the chain for logs of `events` emitted by `contract` and print each
+/// one as it arrives, until Ctrl+C. Decoded events are also POSTed to
+/// `webhook` when one is given; delivery failures are reported but do not
+/// stop the watcher.
+pub async fn watch_events(
+    contract: Address,
+    events: &[Event],
+    from_block: Option<u64>,
+    webhook: Option<&str>,
+) -> Result<()> {
+    let config = ConfigManager::new()?.load()?;
+    let client_config = HelperConfig {
+        network: config.default_network.get_config(),
+        wallet: WalletConfig {
+            current_wallet_address: None,
+            private_key: None,
+            mnemonic: None,
+        },
+    };
+    let client = EthClient::new(&client_config, None).await?;
+    let http = http::client()?;
+    let polling = PollingConfig::current();
+
+    let head = client.provider().get_block_number().await?;
+    let mut next_block = from_block.unwrap_or(head + 1);
+    let signatures: Vec<_> = events.iter().map(|e| e.selector()).collect();
+
+    println!(
+        "{}: Watching {} on 0x{:x} from block {} (Ctrl+C to stop)",
+        "Info".blue().bold(),
+        events
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+        contract,
+        next_block
+    );
+    if let Some(url) = webhook {
+        println!("{}: Forwarding events to {}", "Info".blue().bold(), url);
+    }
+
+    loop {
+        let head = client.provider().get_block_number().await?;
+        while next_block <= head {
+            let to_block = head.min(next_block + MAX_BLOCK_RANGE - 1);
+            let filter = Filter::new()
+                .address(contract)
+                .event_signature(signatures.clone())
+                .from_block(next_block)
+                .to_block(to_block);
+            let logs = client
+                .provider()
+                .get_logs(&filter)
+                .await
+                .map_err(|e| anyhow!("Failed to fetch logs: {}", e))?;
+
+            for log in &logs {
+                let Some(decoded) = DecodedLog::decode(events, log) else {
+                    log::warn!("Could not decode log {:?}", log.transaction_hash);
+                    continue;
+                };
+                print_event(&decoded);
+                output::emit(&decoded.to_json())?;
+                if let Some(url) = webhook
+                    && let Err(e) = forward(&http, url, &decoded.to_json()).await
+                {
+                    println!("  {}: {}", "Webhook failed".yellow().bold(), e);
+                }
+            }
+            next_block = to_block + 1;
+        }
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                println!("\n{}: Stopped watching", "Info".blue().bold());
+                return Ok(());
+            }
+            _ = tokio::time::sleep(polling.receipt_poll_interval()) => {}
+        }
+    }
+}
+
+fn print_event(event: &DecodedLog) {
+    println!(
+        "\n{} {} {}",
+        format!("#{}", event.block.unwrap_or_default()).dimmed(),
+        event.event.cyan().bold(),
+        event
+            .tx_hash
+            .map(|h| format!("0x{:x}", h))
+            .unwrap_or_default()
+            .dimmed()
+    );
+    for (name, value) in &event.args {
+        println!("  {}: {}", name, value);
+    }
+}
+
+/// Every address of the wallets in the profile, with the name to show for
+/// it: the wallet name, with the account for derived accounts
+fn wallet_addresses() -> Result<HashMap<Address, String>> {
+    let mut addresses = HashMap::new();
+    for wallet in WalletData::load()?.list_wallets() {
+        addresses.insert(wallet.address, wallet.name.clone());
+        for account in &wallet.accounts {
+            addresses.insert(
+                account.address,
+                format!("{} #{} {}", wallet.name, account.index, account.name),
+            );
+        }
+    }
+    Ok(addresses)
+}
+
+/// Report payments to the `watched` addresses, named by the map, from the
+/// next block (or `from_block`) on, until Ctrl+C. New blocks are picked up
+/// over the WebSocket endpoint when one is configured and polled otherwise.
+/// Failed lookups are reported and retried on the next block, so the
+/// watcher can run unattended.
+pub async fn watch_incoming(
+    watched: &HashMap<Address, String>,
+    from_block: Option<u64>,
+    webhook: Option<&str>,
+) -> Result<()> {
+    let config = ConfigManager::new()?.load()?;
+    let client_config = HelperConfig {
+        network: config.default_network.get_config(),
+        wallet: WalletConfig {
+            current_wallet_address: None,
+            private_key: None,
+            mnemonic: None,
+        },
+    };
+    let client = EthClient::new(&client_config, None).await?;
+    let http = http::client()?;
+    let polling = PollingConfig::current();
+    let addresses: HashSet<Address> = watched.keys().copied().collect();
+    // Symbols and decimals of the tokens seen so far
+    let mut tokens: HashMap<Address, (String, u8)> = HashMap::new();
+
+    let head = client.provider().get_block_number().await?;
+    let mut next_block = from_block.unwrap_or(head + 1);
+    let mut ticker = Ticker::connect(client.ws_url(), polling.receipt_poll_interval(), None).await;
+
+    println!(
+        "{}: Watching {} address{} for incoming payments from block {} ({}, Ctrl+C to stop)",
+        "Info".blue().bold(),
+        watched.len(),
+        if watched.len() == 1 { "" } else { "es" },
+        next_block,
+        if ticker.is_live() { "live" } else { "polling" }
+    );
+    if let Some(url) = webhook {
+        println!("{}: Forwarding payments to {}", "Info".blue().bold(), url);
+    }
+
+    loop {
+        match client.provider().get_block_number().await {
+            Ok(head) => {
+                while next_block <= head {
+                    let to_block = head.min(next_block + incoming::MAX_BLOCKS - 1);
+                    let payments =
+                        match incoming::scan(&client, &addresses, next_block, to_block).await {
+                            Ok(payments) => payments,
+                            Err(e) => {
+                                println!("{}: {}", "Warning".yellow().bold(), e);
+                                break;
+                            }
+                        };
+                    for payment in &payments {
+                        if let Some(token) = payment.token_address
+                            && !tokens.contains_key(&token)
+                        {
+                            let info = client
+                                .get_token_info(token)
+                                .await
+                                .map(|(decimals, symbol)| (symbol, decimals))
+                                .unwrap_or_else(|_| (format!("0x{:x}", token), 18));
+                            tokens.insert(token, info);
+                        }
+                        let Some(to) = payment.to else { continue };
+                        let row = history_export::rows(
+                            std::slice::from_ref(payment),
+                            to,
+                            &tokens,
+                            &HashMap::new(),
+                        )
+                        .remove(0);
+                        print_payment(&row, &watched[&to]);
+                        notifications::payment_received(&row, &watched[&to]);
+                        let mut details = serde_json::to_value(&row)?;
+                        details["wallet"] = serde_json::json!(&watched[&to]);
+                        event_webhooks::deliver(
+                            WalletEvent::Received,
+                            &format!(
+                                "{} {} to {} from {} (tx {})",
+                                row.amount,
+                                row.token.as_deref().unwrap_or("tokens"),
+                                watched[&to],
+                                row.from,
+                                row.hash
+                            ),
+                            details,
+                        );
+                        output::emit(&row)?;
+                        if let Some(url) = webhook
+                            && let Err(e) = forward(&http, url, &serde_json::to_value(&row)?).await
+                        {
+                            println!("  {}: {}", "Webhook failed".yellow().bold(), e);
+                        }
+                    }
+                    next_block = to_block + 1;
+                }
+            }
+            Err(e) => println!("{}: {}", "Warning".yellow().bold(), e),
+        }
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                println!("\n{}: Stopped watching", "Info".blue().bold());
+                return Ok(());
+            }
+            _ = ticker.tick() => {}
+        }
+    }
+}
+
+fn print_payment(payment: &ExportRow, name: &str) {
+    println!(
+        "\n{} {} {} {} to {}",
+        format!("#{}", payment.block_number.unwrap_or_default()).dimmed(),
+        "Received".green().bold(),
+        redact::amount(&payment.amount),
+        payment.token.as_deref().unwrap_or("tokens"),
+        name.cyan()
+    );
+    println!("  From: {}", redact::address(&payment.from));
+    println!("  Tx:   {}", redact::address(&payment.hash).dimmed());
+}
+
+async fn forward(http: &reqwest::Client, url: &str, body: &serde_json::Value) -> Result<()> {
+    http.post(url).json(body).send().await?.error_for_status()?;
+    Ok(())
+}
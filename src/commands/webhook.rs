@@ -0,0 +1,478 @@
+use crate::config::{ConfigManager, WebhookSettings};
+use crate::types::transaction::RskTransaction;
+use crate::types::wallet::WalletData;
+use crate::utils::alchemy::AlchemyClient;
+use crate::utils::eth::endpoint_host;
+use crate::utils::event_webhooks::{EventWebhook, WalletEvent, WebhookFormat};
+use crate::utils::history_cache::HistoryCache;
+use crate::utils::table::TableBuilder;
+use alloy::primitives::{Address, utils::format_units};
+use anyhow::{Context, Result, anyhow};
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
+use std::str::FromStr;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Largest webhook body accepted by the listener
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+#[derive(Parser, Debug)]
+pub struct WebhookCommand {
+    #[command(subcommand)]
+    pub action: WebhookAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WebhookAction {
+    /// Register an Alchemy Notify webhook for all wallet addresses
+    Register {
+        /// Public URL Alchemy should deliver events to
+        #[arg(long)]
+        url: String,
+        /// Alchemy Notify auth token (from the dashboard)
+        #[arg(long)]
+        auth_token: String,
+        #[arg(long, default_value = "mainnet")]
+        network: String,
+    },
+    /// Add newly created wallets to the registered webhook
+    Sync {
+        #[arg(long)]
+        auth_token: String,
+    },
+    /// Listen for webhook deliveries and update the history cache
+    Listen {
+        #[arg(long, default_value = "8645")]
+        port: u16,
+    },
+    /// Send wallet activity (sent, confirmed, failed and received transfers)
+    /// to a URL, e.g. a Slack or Discord webhook
+    Add {
+        url: String,
+        /// Event to send; repeat for several (default: all)
+        #[arg(long = "event", value_enum)]
+        events: Vec<WalletEvent>,
+        /// Body format
+        #[arg(long, value_enum, default_value_t)]
+        format: WebhookFormat,
+        /// Sign each body with HMAC-SHA256 using this secret
+        #[arg(long)]
+        secret: Option<String>,
+    },
+    /// List the URLs wallet activity is sent to
+    List,
+    /// Stop sending wallet activity to a URL
+    Remove { url: String },
+    /// Send a test message to every activity webhook
+    Test,
+}
+
+impl WebhookCommand {
+    pub async fn execute(&self) -> Result<()> {
+        match &self.action {
+            WebhookAction::Register {
+                url,
+                auth_token,
+                network,
+            } => self.register(url, auth_token, network).await,
+            WebhookAction::Sync { auth_token } => self.sync(auth_token).await,
+            WebhookAction::Listen { port } => listen(*port).await,
+            WebhookAction::Add {
+                url,
+                events,
+                format,
+                secret,
+            } => add_event_webhook(EventWebhook {
+                url: url.clone(),
+                events: events.clone(),
+                format: *format,
+                secret: secret.clone(),
+            }),
+            WebhookAction::List => list_event_webhooks(),
+            WebhookAction::Remove { url } => remove_event_webhook(url),
+            WebhookAction::Test => test_event_webhooks().await,
+        }
+    }
+
+    async fn register(&self, url: &str, auth_token: &str, network: &str) -> Result<()> {
+        let is_testnet = match network.to_lowercase().as_str() {
+            "mainnet" => false,
+            "testnet" => true,
+            _ => anyhow::bail!("Invalid network: use 'mainnet' or 'testnet'"),
+        };
+
+        let config_manager = ConfigManager::new()?;
+        let mut config = config_manager.load()?;
+        let addresses = wallet_addresses()?;
+
+        let client = AlchemyClient::new(
+            config.get_alchemy_key().unwrap_or_default().to_string(),
+            is_testnet,
+        )?;
+        let data = client
+            .create_address_webhook(auth_token, url, &addresses)
+            .await?;
+
+        let id = data["id"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Webhook id missing from Alchemy response"))?;
+        let signing_key = data["signing_key"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Signing key missing from Alchemy response"))?;
+
+        config.webhook = Some(WebhookSettings {
+            id: id.to_string(),
+            url: url.to_string(),
+            signing_key: signing_key.to_string(),
+            network: network.to_lowercase(),
+            addresses: addresses.clone(),
+        });
+        config_manager.save(&config)?;
+
+        println!(
+            "{}: Webhook {} registered for {} address(es)",
+            "Success".green().bold(),
+            id,
+            addresses.len()
+        );
+        Ok(())
+    }
+
+    async fn sync(&self, auth_token: &str) -> Result<()> {
+        let config_manager = ConfigManager::new()?;
+        let mut config = config_manager.load()?;
+        let mut settings = config
+            .webhook
+            .clone()
+            .ok_or_else(|| anyhow!("No webhook registered. Register one first."))?;
+
+        let current = wallet_addresses()?;
+        let add: Vec<String> = current
+            .iter()
+            .filter(|a| !settings.addresses.contains(a))
+            .cloned()
+            .collect();
+        let remove: Vec<String> = settings
+            .addresses
+            .iter()
+            .filter(|a| !current.contains(a))
+            .cloned()
+            .collect();
+
+        if add.is_empty() && remove.is_empty() {
+            println!("{}: Webhook is up to date", "Info".blue().bold());
+            return Ok(());
+        }
+
+        let client = AlchemyClient::new(
+            config.get_alchemy_key().unwrap_or_default().to_string(),
+            settings.network == "testnet",
+        )?;
+        client
+            .update_webhook_addresses(auth_token, &settings.id, &add, &remove)
+            .await?;
+
+        settings.addresses = current;
+        config.webhook = Some(settings);
+        config_manager.save(&config)?;
+
+        println!(
+            "{}: Added {} and removed {} address(es)",
+            "Success".green().bold(),
+            add.len(),
+            remove.len()
+        );
+        Ok(())
+    }
+}
+
+fn wallet_addresses() -> Result<Vec<String>> {
+    let wallet_data = WalletData::load()?;
+    if wallet_data.wallets.is_empty() {
+        return Err(anyhow!("No wallets found"));
+    }
+    let mut addresses: Vec<String> = wallet_data.wallets.keys().cloned().collect();
+    addresses.sort();
+    Ok(addresses)
+}
+
+/// Accept webhook deliveries until interrupted
+pub async fn listen(port: u16) -> Result<()> {
+    let settings = ConfigManager::new()?
+        .load()?
+        .webhook
+        .ok_or_else(|| anyhow!("No webhook registered. Register one first."))?;
+
+    let listener = TcpListener::bind(("0.0.0.0", port))
+        .await
+        .with_context(|| format!("Failed to bind port {}", port))?;
+    println!(
+        "{}: Listening for Alchemy webhooks on port {} (Ctrl+C to stop)",
+        "Info".blue().bold(),
+        port
+    );
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let settings = settings.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, &settings).await {
+                log::warn!("Webhook delivery from {} rejected: {}", peer, e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, settings: &WebhookSettings) -> Result<()> {
+    let (body, signature) = match read_request(&mut stream).await {
+        Ok(request) => request,
+        Err(e) => {
+            respond(&mut stream, "400 Bad Request").await?;
+            return Err(e);
+        }
+    };
+
+    if !verify_signature(&settings.signing_key, &body, signature.as_deref()) {
+        respond(&mut stream, "401 Unauthorized").await?;
+        return Err(anyhow!("invalid signature"));
+    }
+
+    // Acknowledge before processing so Alchemy does not retry slow deliveries
+    respond(&mut stream, "200 OK").await?;
+    let payload: Value = serde_json::from_slice(&body)?;
+    ingest_payload(&payload)?;
+    Ok(())
+}
+
+/// Read a single HTTP request, returning its body and X-Alchemy-Signature header
+async fn read_request(stream: &mut TcpStream) -> Result<(Vec<u8>, Option<String>)> {
+    let mut reader = BufReader::new(stream);
+    let mut content_length = 0usize;
+    let mut signature = None;
+
+    let mut line = String::new();
+    reader.read_line(&mut line).await?;
+    if !line.starts_with("POST ") {
+        return Err(anyhow!("unexpected request line: {}", line.trim()));
+    }
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Err(anyhow!("connection closed before headers ended"));
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            match name.trim().to_lowercase().as_str() {
+                "content-length" => content_length = value.trim().parse()?,
+                "x-alchemy-signature" => signature = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    if content_length > MAX_BODY_SIZE {
+        return Err(anyhow!("body too large ({} bytes)", content_length));
+    }
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).await?;
+    Ok((body, signature))
+}
+
+async fn respond(stream: &mut TcpStream, status: &str) -> Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        status
+    );
+    stream.write_all(response.as_bytes()).await?;
+    Ok(())
+}
+
+/// Add an activity webhook, replacing one with the same URL
+fn add_event_webhook(hook: EventWebhook) -> Result<()> {
+    url::Url::parse(&hook.url).map_err(|e| anyhow!("Invalid URL {}: {}", hook.url, e))?;
+    let config_manager = ConfigManager::new()?;
+    let mut config = config_manager.load()?;
+    config.event_webhooks.retain(|other| other.url != hook.url);
+    println!(
+        "{}: Sending {} to {} as {}",
+        "Success".green().bold(),
+        hook.describe_events(),
+        endpoint_host(&hook.url),
+        hook.format
+    );
+    config.event_webhooks.push(hook);
+    config_manager.save(&config)
+}
+
+fn list_event_webhooks() -> Result<()> {
+    let config = ConfigManager::new()?.load()?;
+    if config.event_webhooks.is_empty() {
+        println!("No activity webhooks; add one with `webhook add <url>`");
+        return Ok(());
+    }
+    let mut table = TableBuilder::new();
+    table.add_header(&["URL", "Events", "Format", "Signed"]);
+    for hook in &config.event_webhooks {
+        table.add_row(&[
+            &hook.url,
+            &hook.describe_events(),
+            &hook.format.to_string(),
+            if hook.secret.is_some() { "yes" } else { "no" },
+        ]);
+    }
+    table.print();
+    Ok(())
+}
+
+fn remove_event_webhook(url: &str) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let mut config = config_manager.load()?;
+    let before = config.event_webhooks.len();
+    config.event_webhooks.retain(|hook| hook.url != url);
+    if config.event_webhooks.len() == before {
+        return Err(anyhow!("No activity webhook for {}", url));
+    }
+    config_manager.save(&config)?;
+    println!(
+        "{}: Removed the webhook to {}",
+        "Success".green().bold(),
+        endpoint_host(url)
+    );
+    Ok(())
+}
+
+/// POST a sample `sent` event to every activity webhook, whatever events it
+/// takes, and report each outcome
+async fn test_event_webhooks() -> Result<()> {
+    let config = ConfigManager::new()?.load()?;
+    if config.event_webhooks.is_empty() {
+        return Err(anyhow!(
+            "No activity webhooks; add one with `webhook add <url>`"
+        ));
+    }
+    let network = config.default_network.to_string();
+    let details = serde_json::json!({ "test": true });
+    for hook in &config.event_webhooks {
+        let host = endpoint_host(&hook.url);
+        match hook
+            .post(
+                WalletEvent::Sent,
+                &network,
+                "Test message from rootstock-wallet",
+                &details,
+            )
+            .await
+        {
+            Ok(()) => println!("{}: {}", "Delivered".green().bold(), host),
+            Err(e) => println!("{}: {}: {}", "Failed".red().bold(), host, e),
+        }
+    }
+    Ok(())
+}
+
+/// Check the hex HMAC-SHA256 signature Alchemy attaches to each delivery
+pub fn verify_signature(signing_key: &str, body: &[u8], signature: Option<&str>) -> bool {
+    let Some(signature) = signature.and_then(|s| hex::decode(s).ok()) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(signing_key.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Merge the activity of an address-activity payload into the history cache
+/// and print a notification for each transfer touching one of our wallets.
+/// Returns the number of transfers recorded.
+pub fn ingest_payload(payload: &Value) -> Result<usize> {
+    if payload["type"].as_str() != Some("ADDRESS_ACTIVITY") {
+        return Ok(0);
+    }
+    let activity = payload["event"]["activity"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Webhook payload has no activity"))?;
+
+    let wallets: Vec<Address> = wallet_addresses()?
+        .iter()
+        .filter_map(|a| Address::from_str(a).ok())
+        .collect();
+
+    // Alchemy reports the network as e.g. "ROOTSTOCK_TESTNET"
+    let network = match payload["event"]["network"].as_str() {
+        Some(name) if name.to_uppercase().contains("TESTNET") => "testnet",
+        _ => "mainnet",
+    };
+
+    let mut cache = HistoryCache::load().unwrap_or_default();
+    let mut recorded = 0;
+    for entry in activity {
+        let tx = match RskTransaction::from_webhook_activity(entry) {
+            Ok(tx) => tx,
+            Err(e) => {
+                log::warn!("Skipping webhook activity: {}", e);
+                continue;
+            }
+        };
+
+        for wallet in wallets
+            .iter()
+            .filter(|w| tx.from == **w || tx.to == Some(**w))
+        {
+            cache.merge(network, wallet, std::slice::from_ref(&tx));
+            recorded += 1;
+
+            let direction = if tx.from == *wallet {
+                "Sent"
+            } else {
+                "Received"
+            };
+            let asset = entry["asset"].as_str().unwrap_or("RBTC");
+            let decimals = entry["rawContract"]["decimals"]
+                .as_u64()
+                .unwrap_or(18)
+                .min(u8::MAX as u64) as u8;
+            println!(
+                "\x07🔔 {} {} {} — 0x{:x} (tx 0x{:x})",
+                direction.green().bold(),
+                format_units(tx.value, decimals).unwrap_or_else(|_| tx.value.to_string()),
+                asset,
+                wallet,
+                tx.hash
+            );
+        }
+    }
+
+    cache.save()?;
+    Ok(recorded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_signature() {
+        let body = br#"{"type":"ADDRESS_ACTIVITY"}"#;
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"whsec_test").unwrap();
+        mac.update(body);
+        let signature = hex::encode(mac.finalize().into_bytes());
+
+        assert!(verify_signature("whsec_test", body, Some(&signature)));
+        assert!(!verify_signature("whsec_other", body, Some(&signature)));
+        assert!(!verify_signature(
+            "whsec_test",
+            b"tampered",
+            Some(&signature)
+        ));
+        assert!(!verify_signature("whsec_test", body, None));
+    }
+}
@@ -1,4 +1,5 @@
 use std::fs;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
@@ -7,11 +8,23 @@ use serde::{Deserialize, Serialize};
 
 // Re-export the API types for easier access
 pub use crate::api::{ApiConfig, ApiKey, ApiProvider};
-use crate::types::network::Network;
+use crate::api::prices::Currency;
+use crate::types::network::{self, CustomChain, Network};
+use crate::utils::accounting::AccountMapping;
+use crate::utils::canary::CanaryPolicy;
+use crate::utils::categories::CategoryRule;
+use crate::utils::confirmations::ConfirmationPolicy;
+use crate::utils::event_webhooks::EventWebhook;
+use crate::utils::retry::Backoff;
+use crate::utils::sponsor::SponsorPolicy;
+use alloy::primitives::Address;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
     pub default_network: Network,
+    /// User-defined EVM chains, selectable like the built-in networks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_chains: Vec<CustomChain>,
     #[serde(default)]
     pub api: ApiConfig,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -20,6 +33,173 @@ pub struct Config {
     pub alchemy_testnet_key: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default_wallet: Option<String>,
+    /// Only allow sending to contacts that have completed a signed handshake
+    #[serde(default)]
+    pub require_verified_contacts: bool,
+    /// Registered Alchemy Notify webhook, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<WebhookSettings>,
+    /// URLs that receive wallet activity (sent, confirmed, failed and
+    /// received transfers)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub event_webhooks: Vec<EventWebhook>,
+    /// Rules used to categorize history entries, evaluated in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub category_rules: Vec<CategoryRule>,
+    /// Cost centers (e.g. departments) a send can be allocated to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cost_centers: Vec<String>,
+    /// Accounts used when exporting history to beancount or ledger
+    #[serde(default)]
+    pub accounting: AccountMapping,
+    #[serde(default)]
+    pub polling: PollingConfig,
+    /// Confirmations required before transfers are reported as final
+    #[serde(default)]
+    pub confirmations: ConfirmationPolicy,
+    /// Submit signed transactions to every known RPC endpoint in parallel
+    #[serde(default)]
+    pub multi_broadcast: bool,
+    /// Additional RPC endpoints, used when the others fail and when
+    /// broadcasting to multiple endpoints
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_rpc_urls: Vec<String>,
+    /// WebSocket endpoints by network ("mainnet", "testnet", "regtest" or a
+    /// custom chain name), subscribed to for live confirmation tracking
+    /// instead of polling
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub ws_urls: BTreeMap<String, String>,
+    /// Proxy every HTTP request is sent through, e.g. `http://proxy:3128`,
+    /// or `socks5h://127.0.0.1:9050` for Tor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Keep wallet passwords and the Alchemy API key in the OS keyring
+    #[serde(default)]
+    pub use_keyring: bool,
+    /// Mask addresses and amounts in on-screen output, e.g. for screen shares
+    #[serde(default)]
+    pub redact_output: bool,
+    /// Show a desktop notification when a sent transaction confirms or a
+    /// watched wallet receives a payment
+    #[serde(default = "default_desktop_notifications")]
+    pub desktop_notifications: bool,
+    /// Disperse-style contract that pays a payroll batch in one transaction
+    /// per asset; payouts are sent one by one when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multisend_contract: Option<Address>,
+    /// Minutes a wallet stays unlocked after its password is entered; zero
+    /// asks for the password every time
+    #[serde(default = "default_session_timeout")]
+    pub session_timeout_minutes: u64,
+    /// Two-step sends for large transfers
+    #[serde(default)]
+    pub canary: CanaryPolicy,
+    /// Amounts offered in the interactive send prompt, by token symbol
+    /// ("RBTC", "DOC", ...)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub quick_amounts: BTreeMap<String, Vec<String>>,
+    /// Currency balances, previews and history show fiat equivalents in;
+    /// none are shown when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_currency: Option<Currency>,
+    /// Wallet that tops up new wallets with a gas allowance
+    #[serde(default)]
+    pub sponsor: SponsorPolicy,
+}
+
+fn default_session_timeout() -> u64 {
+    5
+}
+
+fn default_desktop_notifications() -> bool {
+    true
+}
+
+/// Polling and request pacing, tunable for slow or metered endpoints
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct PollingConfig {
+    /// Seconds between transaction receipt lookups
+    pub receipt_poll_interval_secs: u64,
+    /// Receipt lookups before a transaction is reported as still pending
+    pub receipt_poll_attempts: u32,
+    /// Seconds between checks of the scheduled transfer queue
+    pub queue_poll_interval_secs: u64,
+    /// Pause between batches of a bulk transfer
+    pub bulk_transfer_delay_ms: u64,
+    /// Transfers of a bulk transfer submitted at once, each with its own
+    /// nonce, before their receipts are awaited
+    pub bulk_transfer_concurrency: usize,
+    /// Times a bulk transfer row that failed for a transient reason (e.g. an
+    /// underpriced gas price or a nonce race) is retried
+    pub bulk_transfer_max_retries: u32,
+    /// Pause between consecutive RPC lookups in history and dashboard scans
+    pub request_delay_ms: u64,
+    /// Times an RPC or API request that was rate limited, hit a server error
+    /// or lost its connection is retried
+    pub rpc_max_retries: u32,
+    /// Pause before the first such retry; it doubles with every retry, with
+    /// random jitter, up to `rpc_retry_max_delay_ms`
+    pub rpc_retry_base_delay_ms: u64,
+    pub rpc_retry_max_delay_ms: u64,
+}
+
+impl Default for PollingConfig {
+    fn default() -> Self {
+        Self {
+            receipt_poll_interval_secs: 2,
+            receipt_poll_attempts: 15,
+            queue_poll_interval_secs: 30,
+            bulk_transfer_delay_ms: 1000,
+            bulk_transfer_concurrency: 5,
+            bulk_transfer_max_retries: 3,
+            request_delay_ms: 0,
+            rpc_max_retries: 3,
+            rpc_retry_base_delay_ms: 500,
+            rpc_retry_max_delay_ms: 8000,
+        }
+    }
+}
+
+impl PollingConfig {
+    /// Settings from the saved config, or the defaults if it cannot be read
+    pub fn current() -> Self {
+        ConfigManager::new()
+            .and_then(|manager| manager.load())
+            .map(|config| config.polling)
+            .unwrap_or_default()
+    }
+
+    pub fn receipt_poll_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.receipt_poll_interval_secs)
+    }
+
+    /// Retry policy for RPC and API requests that failed for a passing reason
+    pub fn rpc_backoff(&self) -> Backoff {
+        Backoff {
+            max_retries: self.rpc_max_retries,
+            base_delay: std::time::Duration::from_millis(self.rpc_retry_base_delay_ms),
+            max_delay: std::time::Duration::from_millis(self.rpc_retry_max_delay_ms),
+        }
+    }
+
+    /// Sleep for the configured request delay, if any
+    pub async fn pace_requests(&self) {
+        if self.request_delay_ms > 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(self.request_delay_ms)).await;
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WebhookSettings {
+    pub id: String,
+    pub url: String,
+    /// Key used by Alchemy to sign payloads (X-Alchemy-Signature)
+    pub signing_key: String,
+    pub network: String,
+    #[serde(default)]
+    pub addresses: Vec<String>,
 }
 
 impl Config {
@@ -30,7 +210,8 @@ impl Config {
             Network::Testnet
             | Network::AlchemyTestnet
             | Network::RootStockTestnet
-            | Network::Regtest => "testnet",
+            | Network::Regtest
+            | Network::Custom(_) => "testnet",
         };
 
         // First try to get from the new API config
@@ -61,6 +242,51 @@ impl Config {
         self.get_api_key(&ApiProvider::Alchemy)
     }
 
+    /// RPC endpoint of the default network, keyed when an RSK RPC or
+    /// Alchemy key is set
+    pub fn rpc_url(&self) -> String {
+        self.default_network
+            .get_rpc_url_with_key(self.get_rsk_rpc_key(), self.get_alchemy_key())
+    }
+
+    /// WebSocket endpoint for live updates on the network whose
+    /// configuration is named `network_name` (e.g. "RSK Testnet"). None
+    /// behind a proxy, which WebSocket connections would bypass.
+    pub fn ws_url_for(&self, network_name: &str) -> Option<&str> {
+        if self.proxy.is_some() {
+            return None;
+        }
+        Network::all()
+            .into_iter()
+            .find(|network| network.get_config().name == network_name)
+            .and_then(|network| self.ws_urls.get(network.chain_name()))
+            .map(String::as_str)
+    }
+
+    /// RPC endpoints for the default network that a transaction can be
+    /// broadcast to: keyed RSK RPC and Alchemy endpoints, the public node,
+    /// then any extra endpoints, without duplicates
+    pub fn broadcast_endpoints(&self) -> Vec<String> {
+        let network = self.default_network;
+        let mut endpoints = Vec::new();
+        if let Some(key) = self.get_rsk_rpc_key() {
+            endpoints.push(network.get_rpc_url_with_key(Some(key), None));
+        }
+        if let Some(key) = self.get_alchemy_key() {
+            endpoints.push(network.get_rpc_url_with_key(None, Some(key)));
+        }
+        endpoints.push(network.get_rpc_url_with_key(None, None));
+        endpoints.extend(self.extra_rpc_urls.iter().cloned());
+
+        let mut unique = Vec::new();
+        for endpoint in endpoints {
+            if !unique.contains(&endpoint) {
+                unique.push(endpoint);
+            }
+        }
+        unique
+    }
+
     /// Add or update an API key
     pub fn set_api_key(
         &mut self,
@@ -104,10 +330,32 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             default_network: Network::Testnet,
+            custom_chains: Vec::new(),
             api: ApiConfig::default(),
             alchemy_mainnet_key: None,
             alchemy_testnet_key: None,
             default_wallet: None,
+            require_verified_contacts: false,
+            webhook: None,
+            event_webhooks: Vec::new(),
+            category_rules: Vec::new(),
+            cost_centers: Vec::new(),
+            accounting: AccountMapping::default(),
+            polling: PollingConfig::default(),
+            confirmations: ConfirmationPolicy::default(),
+            multi_broadcast: false,
+            extra_rpc_urls: Vec::new(),
+            ws_urls: BTreeMap::new(),
+            proxy: None,
+            use_keyring: false,
+            redact_output: false,
+            desktop_notifications: default_desktop_notifications(),
+            multisend_contract: None,
+            session_timeout_minutes: default_session_timeout(),
+            canary: CanaryPolicy::default(),
+            quick_amounts: BTreeMap::new(),
+            display_currency: None,
+            sponsor: SponsorPolicy::default(),
         }
     }
 }
@@ -118,9 +366,7 @@ pub struct ConfigManager {
 
 impl ConfigManager {
     pub fn new() -> Result<Self> {
-        let config_dir = dirs::config_dir()
-            .context("Could not find config directory")?
-            .join("rootstock-wallet");
+        let config_dir = super::profile::config_dir()?;
 
         std::fs::create_dir_all(&config_dir)?;
 
@@ -137,7 +383,16 @@ impl ConfigManager {
         let content =
             fs::read_to_string(&self.config_path).context("Failed to read config file")?;
 
-        serde_json::from_str(&content).context("Failed to parse config file")
+        let mut config: Config =
+            serde_json::from_str(&content).context("Failed to parse config file")?;
+        network::register_custom_chains(&config.custom_chains);
+        // A custom chain that was removed by hand leaves nothing to connect to
+        if let Network::Custom(chain_id) = config.default_network
+            && !config.custom_chains.iter().any(|c| c.chain_id == chain_id)
+        {
+            config.default_network = Network::Testnet;
+        }
+        Ok(config)
     }
 
     pub fn save(&self, config: &Config) -> Result<()> {
@@ -228,3 +483,41 @@ impl ConfigManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_broadcast_endpoints() {
+        let mut config = Config {
+            default_network: Network::Testnet,
+            ..Config::default()
+        };
+        assert_eq!(
+            config.broadcast_endpoints(),
+            vec!["https://public-node.testnet.rsk.co".to_string()]
+        );
+
+        config.api.keys.push(ApiKey {
+            key: "rsk-key".to_string(),
+            network: "testnet".to_string(),
+            provider: ApiProvider::RskRpc,
+            name: None,
+        });
+        config.alchemy_testnet_key = Some("alchemy-key".to_string());
+        config.extra_rpc_urls = vec![
+            "https://public-node.testnet.rsk.co".to_string(),
+            "https://rpc.example.org".to_string(),
+        ];
+        assert_eq!(
+            config.broadcast_endpoints(),
+            vec![
+                "https://rpc.testnet.rootstock.io/rsk-key".to_string(),
+                "https://rootstock-testnet.g.alchemy.com/v2/alchemy-key".to_string(),
+                "https://public-node.testnet.rsk.co".to_string(),
+                "https://rpc.example.org".to_string(),
+            ]
+        );
+    }
+}
@@ -3,6 +3,7 @@ use console::style;
 
 use crate::config::{Config, ConfigManager};
 use crate::types::network::Network;
+use crate::utils::eth::endpoint_host;
 
 pub fn run_doctor() -> Result<()> {
     println!("\n{}", style("🩺 Running diagnostics...").bold().cyan());
@@ -24,6 +25,15 @@ pub fn run_doctor() -> Result<()> {
     // Check network configuration
     println!("\n{}", style("🌐 Network Configuration:").bold());
     println!("  Default network: {}", config.default_network);
+    for chain in &config.custom_chains {
+        println!(
+            "  Custom chain: {} (chain {}, {}) at {}",
+            chain.name,
+            chain.chain_id,
+            chain.symbol,
+            endpoint_host(&chain.rpc_url)
+        );
+    }
 
     // Check API keys
     println!("\n{}", style("🔑 API Keys:").bold());
@@ -61,7 +71,8 @@ fn check_api_key(config: &Config, network: Network) {
         Network::Testnet
         | Network::AlchemyTestnet
         | Network::RootStockTestnet
-        | Network::Regtest => &config.alchemy_testnet_key,
+        | Network::Regtest
+        | Network::Custom(_) => &config.alchemy_testnet_key,
     };
 
     let status = match key {
@@ -79,6 +90,7 @@ fn check_api_key(config: &Config, network: Network) {
             Network::AlchemyTestnet => "Alchemy Testnet",
             Network::RootStockMainnet => "Rootstock Mainnet",
             Network::RootStockTestnet => "Rootstock Testnet",
+            Network::Custom(_) => "Custom chain",
         },
         status
     );
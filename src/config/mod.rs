@@ -1,9 +1,11 @@
+#[allow(clippy::module_inception)]
 mod config;
 mod doctor;
+pub mod profile;
 mod setup;
 
 // Re-export types from the config module
-pub use config::{Config, ConfigManager};
+pub use config::{Config, ConfigManager, PollingConfig, WebhookSettings};
 
 // Re-export Network from the types module
 pub use crate::types::network::Network;
@@ -0,0 +1,174 @@
+//! Configuration profiles, e.g. to keep work, personal and testing setups
+//! apart.
+//!
+//! A profile has its own configuration file (default network, API keys,
+//! policies) and its own wallet database (wallets, contacts, token
+//! registry). The `default` profile uses the directories of earlier
+//! versions; other profiles live in a `profiles/<name>` directory under
+//! both the config and the data directory. The profile is chosen once per
+//! process, with `--profile` or at the start of the interactive menus.
+
+use anyhow::{Context, Result, anyhow};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Profile used when none is chosen, with the pre-profile file locations
+pub const DEFAULT: &str = "default";
+/// Environment variable naming the profile when `--profile` is not given
+pub const PROFILE_ENV: &str = "ROOTSTOCK_WALLET_PROFILE";
+
+static ACTIVE: OnceLock<String> = OnceLock::new();
+
+/// Name of the profile in use
+pub fn active() -> &'static str {
+    ACTIVE.get().map_or(DEFAULT, String::as_str)
+}
+
+/// Use profile `name` for the rest of the process. Fails for a profile that
+/// does not exist, or when another profile is already in use.
+pub fn set_active(name: &str) -> Result<()> {
+    if !exists(name)? {
+        return Err(anyhow!(
+            "Profile '{}' does not exist; create it with `profile create {}`",
+            name,
+            name
+        ));
+    }
+    if ACTIVE.get_or_init(|| name.to_string()) != name {
+        return Err(anyhow!("Profile '{}' is already in use", active()));
+    }
+    Ok(())
+}
+
+/// Profile names are used as directory names: letters, digits, `-` and `_`
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > 32 {
+        return Err(anyhow!("Profile names have 1 to 32 characters"));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow!(
+            "Profile names may only contain letters, digits, '-' and '_'"
+        ));
+    }
+    Ok(())
+}
+
+fn config_root() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .context("Could not find config directory")?
+        .join("rootstock-wallet"))
+}
+
+fn data_root() -> Result<PathBuf> {
+    Ok(dirs::data_local_dir()
+        .context("Failed to get data directory")?
+        .join("rootstock-wallet"))
+}
+
+/// `root` for the default profile, `root/profiles/<name>` for the others
+fn profile_dir(root: PathBuf, name: &str) -> PathBuf {
+    if name == DEFAULT {
+        root
+    } else {
+        root.join("profiles").join(name)
+    }
+}
+
+/// Configuration directory of the active profile
+pub fn config_dir() -> Result<PathBuf> {
+    Ok(profile_dir(config_root()?, active()))
+}
+
+/// Data directory (wallet database, bulk jobs) of the active profile
+pub fn data_dir() -> Result<PathBuf> {
+    Ok(profile_dir(data_root()?, active()))
+}
+
+pub fn exists(name: &str) -> Result<bool> {
+    if name == DEFAULT {
+        return Ok(true);
+    }
+    validate_name(name)?;
+    Ok(profile_dir(config_root()?, name).is_dir())
+}
+
+/// Every profile, the default one first
+pub fn list() -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    let dir = config_root()?.join("profiles");
+    if dir.is_dir() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            if entry.path().is_dir()
+                && let Some(name) = entry.file_name().to_str()
+                && validate_name(name).is_ok()
+            {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names.sort();
+    names.insert(0, DEFAULT.to_string());
+    Ok(names)
+}
+
+/// Create an empty profile; it is set up like a new installation when first
+/// used
+pub fn create(name: &str) -> Result<()> {
+    if exists(name)? {
+        return Err(anyhow!("Profile '{}' already exists", name));
+    }
+    fs::create_dir_all(profile_dir(config_root()?, name))?;
+    fs::create_dir_all(profile_dir(data_root()?, name))?;
+    Ok(())
+}
+
+/// Delete a profile with its configuration, wallets and contacts
+pub fn delete(name: &str) -> Result<()> {
+    if name == DEFAULT {
+        return Err(anyhow!("The default profile cannot be deleted"));
+    }
+    if name == active() {
+        return Err(anyhow!("Profile '{}' is in use", name));
+    }
+    if !exists(name)? {
+        return Err(anyhow!("Profile '{}' does not exist", name));
+    }
+    for dir in [
+        profile_dir(config_root()?, name),
+        profile_dir(data_root()?, name),
+    ] {
+        if dir.exists() {
+            fs::remove_dir_all(&dir)
+                .with_context(|| format!("Failed to remove {}", dir.display()))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_names_and_dirs() {
+        assert!(validate_name("work").is_ok());
+        assert!(validate_name("test_net-2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../work").is_err());
+        assert!(validate_name("my profile").is_err());
+        assert!(validate_name(&"a".repeat(33)).is_err());
+
+        let root = PathBuf::from("/config/rootstock-wallet");
+        assert_eq!(profile_dir(root.clone(), DEFAULT), root);
+        assert_eq!(
+            profile_dir(root.clone(), "work"),
+            root.join("profiles").join("work")
+        );
+        assert!(delete(DEFAULT).is_err());
+    }
+}
@@ -69,7 +69,8 @@ fn setup_api_keys(config: &mut Config, network: Network) -> Result<()> {
         Network::Testnet
         | Network::AlchemyTestnet
         | Network::RootStockTestnet
-        | Network::Regtest => "testnet",
+        | Network::Regtest
+        | Network::Custom(_) => "testnet",
     };
 
     println!(
@@ -165,7 +166,8 @@ fn setup_api_keys(config: &mut Config, network: Network) -> Result<()> {
         Network::Testnet
         | Network::AlchemyTestnet
         | Network::RootStockTestnet
-        | Network::Regtest => {
+        | Network::Regtest
+        | Network::Custom(_) => {
             println!("\nWould you like to set up mainnet API keys as well?");
             Network::Mainnet
         }
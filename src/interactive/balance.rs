@@ -1,9 +1,20 @@
 use crate::commands::balance::BalanceCommand;
+use crate::commands::portfolio::PortfolioCommand;
 use crate::commands::tokens::TokenRegistry;
 use crate::config::ConfigManager;
 use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
 use console::style;
-use inquire::Select;
+use inquire::{CustomType, Select};
+
+const ALL_TOKENS: &str = "All tokens held (via Blockscout)";
+
+/// Value of every wallet's RBTC and registry tokens
+pub async fn show_portfolio() -> Result<()> {
+    println!("\n{}", style("📊 Portfolio").bold());
+    println!("{}", "=".repeat(30));
+    PortfolioCommand { currency: None }.execute().await
+}
 
 /// Displays the balance checking interface
 pub async fn show_balance() -> Result<()> {
@@ -12,8 +23,8 @@ pub async fn show_balance() -> Result<()> {
 
     // Get the current network from config
     let config = ConfigManager::new()?.load()?;
-    let network = config.default_network.to_string().to_lowercase();
-    println!("Using network: {}", network);
+    let network = config.default_network.chain_name();
+    println!("Using network: {}", config.default_network);
 
     // Load token registry
     let registry = TokenRegistry::load()
@@ -24,14 +35,16 @@ pub async fn show_balance() -> Result<()> {
         .unwrap_or_default();
 
     // Get tokens for the current network
-    let mut tokens = registry.list_tokens(Some(&network));
+    let mut tokens = registry.list_tokens(Some(network));
 
-    // Add RBTC as the first option
+    // Add the native coin as the first option
+    let native = config.default_network.native_symbol();
     tokens.insert(
         0,
         (
-            "RBTC (Native)".to_string(),
+            format!("{} (Native)", native),
             crate::commands::tokens::TokenInfo {
+                symbol: native.to_string(),
                 address: "0x0000000000000000000000000000000000000000".to_string(),
                 decimals: 18,
             },
@@ -49,18 +62,32 @@ pub async fn show_balance() -> Result<()> {
             // Only include tokens that match the current network or are RBTC
             info.address == "0x0000000000000000000000000000000000000000"
                 || registry
-                    .list_tokens(Some(&network))
+                    .list_tokens(Some(network))
                     .iter()
                     .any(|(_, token_info)| token_info.address == info.address)
         })
         .collect();
 
     // Get just the display names for the selection menu
-    let token_display_names: Vec<String> =
+    let mut token_display_names: Vec<String> =
         token_choices.iter().map(|(name, _)| name.clone()).collect();
+    token_display_names.push(ALL_TOKENS.to_string());
 
     // Let the user select which token to check
     let selection = Select::new("Select token to check balance:", token_display_names).prompt()?;
+    if selection == ALL_TOKENS {
+        let account = super::wallet::select_account()?;
+        return BalanceCommand {
+            address: None,
+            token: None,
+            account,
+            all: true,
+            at_block: None,
+            at_date: None,
+        }
+        .execute()
+        .await;
+    }
 
     // Find the selected token info
     let (_, token_info) = token_choices
@@ -72,8 +99,14 @@ pub async fn show_balance() -> Result<()> {
     let token_address = token_info.address; // This is a String which is Clone
 
     // Execute the balance command
+    let account = super::wallet::select_account()?;
+    let (at_block, at_date) = select_snapshot()?;
     let cmd = BalanceCommand {
         address: None, // Will use default wallet
+        account,
+        all: false,
+        at_block,
+        at_date,
         token: if token_address == "0x0000000000000000000000000000000000000000" {
             None
         } else {
@@ -83,3 +116,23 @@ pub async fn show_balance() -> Result<()> {
 
     cmd.execute().await
 }
+
+/// Asks whether to show the current balance or the balance at a past block
+/// or day
+fn select_snapshot() -> Result<(Option<u64>, Option<NaiveDate>)> {
+    let options = vec!["Now", "At a past block", "At the end of a past day (UTC)"];
+    let selection = Select::new("Balance as of:", options.clone())
+        .with_help_message("Past balances need an archive node, such as Alchemy")
+        .prompt()?;
+    if selection == options[1] {
+        let block = CustomType::<u64>::new("Block number:").prompt()?;
+        Ok((Some(block), None))
+    } else if selection == options[2] {
+        let date = CustomType::<NaiveDate>::new("Day (YYYY-MM-DD):")
+            .with_error_message("Please enter a date as YYYY-MM-DD")
+            .prompt()?;
+        Ok((None, Some(date)))
+    } else {
+        Ok((None, None))
+    }
+}
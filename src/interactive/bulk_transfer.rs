@@ -1,24 +1,34 @@
 use crate::{
-    config::ConfigManager,
-    types::{network::Network, wallet::WalletData},
-    utils::constants,
+    commands::{
+        contacts::{self, ContactsAction, ContactsCommand},
+        payroll::resolve_asset,
+    },
+    config::{ConfigManager, PollingConfig},
+    types::{
+        contacts::{group_members, groups},
+        network::Network,
+        wallet::WalletData,
+    },
+    utils::bulk_csv::{self, BulkTransfer, RowError},
+    utils::bulk_engine,
+    utils::bulk_job::{BulkJob, RowState},
+    utils::categories::AssetFilter,
+    utils::eth::EthClient,
+    utils::helper::{Config as HelperConfig, WalletConfig},
+    utils::retry::FailureKind,
+    utils::rns,
+    utils::roles::{self, Capability},
+    utils::table::TableBuilder,
 };
 use anyhow::{Result, anyhow};
-use dialoguer::{Confirm, Input};
-use alloy::{
-    primitives::{Address, U256},
-    providers::{Provider, ProviderBuilder},
-    signers::local::PrivateKeySigner,
-    network::TransactionBuilder,
+use dialoguer::{Confirm, Input, Select};
+use alloy::primitives::{
+    Address, U256,
+    utils::{format_units, parse_units},
 };
 use serde::Deserialize;
-use std::{fs, sync::Arc};
-
-#[derive(Debug, Clone)]
-struct Transfer {
-    to: Address,
-    value: U256,
-}
+use std::collections::HashMap;
+use std::sync::Arc;
 
 #[derive(Debug, Deserialize)]
 struct TransferInput {
@@ -26,19 +36,98 @@ struct TransferInput {
     value: String,
 }
 
+/// A row entered by hand or read from a JSON file, which only carry RBTC
+fn rbtc_transfer(line: usize, to: Address, value: U256) -> BulkTransfer {
+    BulkTransfer {
+        line,
+        to,
+        asset: AssetFilter::Rbtc,
+        value,
+        memo: None,
+        warnings: Vec::new(),
+    }
+}
+
+/// Rows paying every member of a contact group the same amount, with a memo
+/// asked for each member that requires one
+fn group_transfers() -> Result<Vec<BulkTransfer>> {
+    let contacts = ContactsCommand {
+        action: ContactsAction::List,
+    }
+    .load_contacts()?;
+    let groups = groups(&contacts);
+    if groups.is_empty() {
+        return Err(anyhow!(
+            "No contact groups found; create one under Contacts → Manage groups"
+        ));
+    }
+    let labels: Vec<String> = groups
+        .iter()
+        .map(|(group, members)| format!("{} ({} members)", group, members))
+        .collect();
+    let index = Select::new()
+        .with_prompt("Which group would you like to pay?")
+        .items(&labels)
+        .default(0)
+        .interact()?;
+    let group = &groups[index].0;
+
+    let token: String = Input::new()
+        .with_prompt("Token symbol or address (empty for RBTC)")
+        .allow_empty(true)
+        .interact_text()?;
+    let asset = resolve_asset(Some(token.trim()).filter(|token| !token.is_empty()))?;
+    let amount: String = Input::new()
+        .with_prompt(format!("Amount of {} for each member", asset.symbol()))
+        .interact_text()?;
+    let value: U256 = parse_units(amount.trim(), asset.decimals())
+        .map_err(|e| anyhow!("Invalid amount '{}': {}", amount.trim(), e))?
+        .into();
+    if value.is_zero() {
+        return Err(anyhow!("The amount must be positive"));
+    }
+
+    let mut transfers = Vec::new();
+    for (i, contact) in group_members(&contacts, group).into_iter().enumerate() {
+        // Exchange deposit addresses only credit transfers that carry their memo
+        let memo = if contact.memo_required {
+            let memo: String = Input::new()
+                .with_prompt(format!("Memo for {} (required)", contact.name))
+                .validate_with(|input: &String| {
+                    if input.trim().is_empty() {
+                        Err("This contact requires a memo")
+                    } else {
+                        Ok(())
+                    }
+                })
+                .interact_text()?;
+            Some(memo.trim().to_string())
+        } else {
+            None
+        };
+        transfers.push(BulkTransfer {
+            line: i + 1,
+            to: contact.address,
+            asset: asset.clone(),
+            value,
+            memo,
+            warnings: Vec::new(),
+        });
+    }
+    Ok(transfers)
+}
+
 /// Interactive menu for bulk token transfers
 pub async fn bulk_transfer() -> Result<()> {
     println!("\n💸 Bulk Token Transfer");
     println!("=====================");
+    roles::require(Capability::Transfer)?;
 
     // Load wallet data
-    let wallet_file = constants::wallet_file_path();
-    let wallet_data = if wallet_file.exists() {
-        let data = fs::read_to_string(&wallet_file)?;
-        serde_json::from_str::<WalletData>(&data)?
-    } else {
+    let wallet_data = WalletData::load()?;
+    if wallet_data.wallets.is_empty() {
         return Err(anyhow!("No wallet found. Please create a wallet first."));
-    };
+    }
 
     // Get current wallet
     let current_wallet = wallet_data
@@ -52,145 +141,254 @@ pub async fn bulk_transfer() -> Result<()> {
     // Get the network configuration
     let network_config = config.default_network.get_config();
 
-    // Get the chain ID based on the network
-    let chain_id = match config.default_network {
-        Network::RootStockMainnet => 30,
-        Network::RootStockTestnet => 31,
-        Network::Mainnet => 30,
-        Network::Testnet => 31,
-        Network::Regtest => 1337,
-        _ => return Err(anyhow!("Unsupported network for bulk transfers")),
-    };
-
-    // Prompt for password to decrypt the private key
-    let password = rpassword::prompt_password("Enter password for the wallet: ")?;
-
-    // Decrypt the private key
-    let private_key = current_wallet.decrypt_private_key(&password)?;
-
-    // Create a wallet
-    let wallet = private_key
-        .parse::<PrivateKeySigner>()
-        .map_err(|e| anyhow!("Failed to parse private key: {}", e))?;
-
-    // Create a provider with the network RPC URL
-    let provider = ProviderBuilder::new()
-        .on_http(network_config.rpc_url.parse()?);
-
-    let client = Arc::new(provider);
+    // Only networks with a known chain ID are supported
+    if !matches!(
+        config.default_network,
+        Network::RootStockMainnet
+            | Network::RootStockTestnet
+            | Network::Mainnet
+            | Network::Testnet
+            | Network::Regtest
+            | Network::Custom(_)
+    ) {
+        return Err(anyhow!("Unsupported network for bulk transfers"));
+    }
 
-    // Ask if user wants to use a file or manual input
-    let use_file = Confirm::new()
-        .with_prompt("Do you want to load recipients from a JSON file?")
-        .default(false)
+    // Decrypt the private key, prompting unless the wallet is unlocked
+    let private_key =
+        crate::utils::helper::unlock_wallet(current_wallet, 0, "Enter password for the wallet: ")?;
+
+    // Transfers are signed locally, so each gets its own nonce and retries
+    // can refresh the gas price; the client is shared by concurrent sends
+    let client = Arc::new(EthClient::new(
+        &HelperConfig {
+            network: network_config,
+            wallet: WalletConfig {
+                current_wallet_address: None,
+                private_key: Some(private_key),
+                mnemonic: None,
+            },
+        },
+        None,
+    )
+    .await?);
+
+    // Recipients come from a CSV or JSON file, or are entered one by one;
+    // runs that did not finish can be picked up again
+    let from = current_wallet.address;
+    let network = config.default_network;
+    let mut unfinished = BulkJob::unfinished(network, from)?;
+    let mut sources = vec![
+        "Enter recipients manually",
+        "Load a CSV file (address, amount, token, memo)",
+        "Load a JSON file",
+        "Send to all members of a contact group",
+    ];
+    if !unfinished.is_empty() {
+        sources.push("🔁 Resume bulk job");
+    }
+    let source = Select::new()
+        .with_prompt("How would you like to add recipients?")
+        .items(&sources)
+        .default(0)
         .interact()?;
 
-    let transfers = if use_file {
-        // Load transfers from file
-        let file_path: String = Input::new()
-            .with_prompt("Enter path to JSON file with transfer details")
-            .interact_text()?;
-
-        let file_content = std::fs::read_to_string(&file_path)
-            .map_err(|e| anyhow!("Failed to read file: {}", e))?;
-
-        let transfer_inputs: Vec<TransferInput> = serde_json::from_str(&file_content)
-            .map_err(|e| anyhow!("Failed to parse JSON: {}", e))?;
-
-        transfer_inputs
-            .into_iter()
-            .map(|input| {
-                let to_addr = input
-                    .to
-                    .parse::<Address>()
-                    .map_err(|e| anyhow!("Invalid address {}: {}", input.to, e))?;
-                let value_wei = parse_amount(&input.value)?;
-                Ok(Transfer {
-                    to: to_addr,
-                    value: value_wei,
-                })
-            })
-            .collect::<Result<Vec<_>>>()?
+    let resumed = source == 4;
+    let mut job = if resumed {
+        let labels: Vec<String> = unfinished.iter().map(BulkJob::describe).collect();
+        let index = Select::new()
+            .with_prompt("Which job would you like to resume?")
+            .items(&labels)
+            .default(0)
+            .interact()?;
+        let mut job = unfinished.swap_remove(index);
+        bulk_engine::reconcile_sent(&client, &mut job).await?;
+        job.save()?;
+        job
     } else {
-        // Manual input
-        let count_str: String = Input::new()
-            .with_prompt("How many recipients?")
-            .validate_with(|input: &String| {
-                if input.parse::<usize>().is_ok() {
-                    Ok(())
-                } else {
-                    Err("Please enter a valid number".to_string())
+        let transfers = match source {
+            1 => {
+                let file_path: String = Input::new()
+                    .with_prompt("Enter path to the CSV file")
+                    .interact_text()?;
+                let content = std::fs::read_to_string(file_path.trim())
+                    .map_err(|e| anyhow!("Failed to read file: {}", e))?;
+                let (transfers, mut errors) =
+                    bulk_csv::parse(&content, client.chain_id(), resolve_asset)?;
+                // Exchange deposit addresses only credit transfers that carry their memo
+                for transfer in &transfers {
+                    if transfer.memo.is_none() && contacts::memo_required(&transfer.to)? {
+                        errors.push(RowError {
+                            line: transfer.line,
+                            message: "this contact requires a memo".to_string(),
+                        });
+                    }
                 }
-            })
-            .interact_text()?;
-
-        let count = count_str
-            .parse::<usize>()
-            .map_err(|_| anyhow!("Failed to parse number of recipients"))?;
-
-        let mut transfers = Vec::with_capacity(count);
-        for i in 0..count {
-            println!("\nRecipient #{}:", i + 1);
-
-            let to: String = Input::new()
-                .with_prompt("Recipient address (0x...)")
-                .validate_with(|input: &String| {
-                    if input.starts_with("0x") && input.len() == 42 {
-                        Ok(())
-                    } else {
-                        Err("Please enter a valid rBTC address starting with 0x".to_string())
+                if !errors.is_empty() {
+                    errors.sort_by_key(|error| error.line);
+                    let mut table = TableBuilder::new();
+                    table.add_header(&["Line", "Problem"]);
+                    for error in &errors {
+                        table.add_row(&[&error.line.to_string(), &error.message]);
                     }
-                })
-                .interact()?;
-
-            let to = to
-                .parse::<Address>()
-                .map_err(|e| anyhow!("Invalid address: {}", e))?;
-
-            let amount: String = Input::new()
-                .with_prompt("Amount to send (e.g., 1.0)")
-                .interact()?;
-
-            let value = parse_amount(&amount)?;
-
-            transfers.push(Transfer { to, value });
-        }
-        transfers
+                    table.print();
+                    return Err(anyhow!(
+                        "{} row(s) of the file cannot be sent; fix them and load the file again",
+                        errors.len()
+                    ));
+                }
+                transfers
+            }
+            2 => {
+                let file_path: String = Input::new()
+                    .with_prompt("Enter path to JSON file with transfer details")
+                    .interact_text()?;
+
+                let file_content = std::fs::read_to_string(&file_path)
+                    .map_err(|e| anyhow!("Failed to read file: {}", e))?;
+
+                let transfer_inputs: Vec<TransferInput> = serde_json::from_str(&file_content)
+                    .map_err(|e| anyhow!("Failed to parse JSON: {}", e))?;
+
+                let mut transfers = Vec::with_capacity(transfer_inputs.len());
+                for (i, input) in transfer_inputs.into_iter().enumerate() {
+                    let to_addr =
+                        rns::resolve_recipient(client.provider(), &input.to, client.chain_id()).await?;
+                    let value_wei = parse_amount(&input.value)?;
+                    transfers.push(rbtc_transfer(i + 1, to_addr, value_wei));
+                }
+                transfers
+            }
+            3 => group_transfers()?,
+            _ => {
+                // Manual input
+                let count_str: String = Input::new()
+                    .with_prompt("How many recipients?")
+                    .validate_with(|input: &String| {
+                        if input.parse::<usize>().is_ok() {
+                            Ok(())
+                        } else {
+                            Err("Please enter a valid number".to_string())
+                        }
+                    })
+                    .interact_text()?;
+
+                let count = count_str
+                    .parse::<usize>()
+                    .map_err(|_| anyhow!("Failed to parse number of recipients"))?;
+
+                let mut transfers = Vec::with_capacity(count);
+                for i in 0..count {
+                    println!("\nRecipient #{}:", i + 1);
+
+                    let to: String = Input::new()
+                        .with_prompt("Recipient address (0x...) or RNS name")
+                        .validate_with(|input: &String| {
+                            if (input.starts_with("0x") && input.len() == 42) || rns::is_name(input) {
+                                Ok(())
+                            } else {
+                                Err("Please enter a valid rBTC address starting with 0x or an RNS name"
+                                    .to_string())
+                            }
+                        })
+                        .interact()?;
+
+                    let to = rns::resolve_recipient(client.provider(), &to, client.chain_id()).await?;
+
+                    let amount: String = Input::new()
+                        .with_prompt("Amount to send (e.g., 1.0)")
+                        .interact()?;
+
+                    let value = parse_amount(&amount)?;
+
+                    transfers.push(rbtc_transfer(i + 1, to, value));
+                }
+                transfers
+            }
+        };
+        BulkJob::new(network, from, transfers)
     };
 
+    let transfers: Vec<BulkTransfer> = job
+        .rows
+        .iter()
+        .filter(|row| row.state.needs_sending())
+        .map(|row| row.transfer.clone())
+        .collect();
+    if transfers.is_empty() {
+        println!("Nothing is left to send in job {}", job.id);
+        print_results(&job, &config.polling)?;
+        return Ok(());
+    }
+    if resumed {
+        println!(
+            "\n{} of {} transfer(s) are confirmed; {} are left to send",
+            job.count(|state| matches!(state, RowState::Confirmed { .. })),
+            job.rows.len(),
+            transfers.len()
+        );
+    }
+
     // Show summary
     println!("\n📋 Transaction Summary:");
     println!("====================");
-    let total = transfers.iter().fold(U256::ZERO, |acc, t| acc + t.value);
-
-    for (i, transfer) in transfers.iter().enumerate() {
+    let chain_id = client.chain_id();
+    let mut table = TableBuilder::new();
+    table.add_header(&["#", "To", "Amount", "Asset", "Memo", "Notes"]);
+    for transfer in &transfers {
+        table.add_row(&[
+            &transfer.line.to_string(),
+            &transfer.to.to_checksum(Some(chain_id)),
+            &transfer.formatted_amount(),
+            transfer.asset.symbol(),
+            transfer.memo.as_deref().unwrap_or("-"),
+            &transfer.warnings.join("; "),
+        ]);
+    }
+    table.print();
+    for (asset, total) in bulk_csv::totals(&transfers) {
         println!(
-            "{:2}. To: {} - Amount: {} rBTC",
-            i + 1,
-            transfer.to,
-            format_eth(transfer.value)
+            "Total to send: {} {}",
+            format_units(total, asset.decimals()).unwrap_or_else(|_| total.to_string()),
+            asset.symbol()
         );
     }
 
-    println!("\nTotal to send: {} rBTC", format_eth(total));
-
     // Get current gas price
-    let gas_price = client.get_gas_price().await?;
-    println!("Current gas price: {} Gwei", format_gwei(U256::from(gas_price)));
-
-    // Estimate gas cost (21,000 gas per basic transfer)
-    let gas_per_tx = U256::from(21000u64);
-    let total_gas = gas_per_tx
-        .checked_mul(U256::from(transfers.len()))
-        .unwrap_or_default();
-    let total_gas_cost = total_gas.checked_mul(U256::from(gas_price)).unwrap_or_default();
-
-    println!("Estimated gas cost: {} rBTC", format_eth(total_gas_cost));
+    let fee = client.suggest_gas_price().await?;
+    let gas_price = fee.gas_price;
     println!(
-        "Total cost (amount + gas): {} rBTC",
-        format_eth(total + total_gas_cost)
+        "Current gas price: {} Gwei ({})",
+        format_gwei(U256::from(gas_price)),
+        fee.source
     );
 
+    let gas: u64 = transfers.iter().map(BulkTransfer::gas).sum();
+    let total_gas_cost = U256::from(gas).saturating_mul(U256::from(gas_price));
+    println!("Estimated gas cost: {} rBTC", format_eth(total_gas_cost));
+
+    // Every asset, and RBTC for gas, must cover the whole run
+    let mut balances = HashMap::new();
+    let tokens = bulk_csv::totals(&transfers)
+        .into_iter()
+        .filter_map(|(asset, _)| asset.token());
+    for token in std::iter::once(None).chain(tokens.map(Some)) {
+        balances.insert(token, client.get_balance(&from, &token).await?);
+    }
+    let shortfalls = bulk_csv::shortfalls(&transfers, gas_price, |token| {
+        balances.get(&token).copied().unwrap_or_default()
+    });
+    if !shortfalls.is_empty() {
+        return Err(anyhow!(
+            "Insufficient balance for these transfers: the wallet {}",
+            shortfalls.join("; ")
+        ));
+    }
+    let warned = transfers.iter().filter(|t| !t.warnings.is_empty()).count();
+    if warned > 0 {
+        println!("⚠️  {} row(s) have notes worth checking before sending", warned);
+    }
+
     // Confirm before sending
     let confirm = Confirm::new()
         .with_prompt("\nDo you want to send these transactions?")
@@ -202,61 +400,59 @@ pub async fn bulk_transfer() -> Result<()> {
         return Ok(());
     }
 
-    // Send transactions
-    println!("\n🚀 Sending transactions...");
-
-    let mut successful = 0;
-    let mut failed = 0;
-
-    for (i, transfer) in transfers.clone().into_iter().enumerate() {
-        print!("Sending {}/{}... ", i + 1, transfers.clone().len());
-
-        use alloy::rpc::types::TransactionRequest;
-        let tx = TransactionRequest::default()
-            .with_to(transfer.to)
-            .with_value(transfer.value)
-            .with_gas_limit(gas_per_tx.try_into().unwrap_or(0u64))
-            .with_gas_price(gas_price.try_into().unwrap_or(0u128));
-
-        match client.send_transaction(tx).await {
-            Ok(pending_tx) => {
-                let tx_hash = pending_tx.tx_hash();
-                match client.get_transaction_receipt(*tx_hash).await {
-                    Ok(Some(receipt)) => {
-                        if receipt.status() {
-                            println!("✅ Success! Tx: {:?}", receipt.transaction_hash);
-                            successful += 1;
-                        } else {
-                            println!("❌ Failed! Tx: {:?}", receipt.transaction_hash);
-                            failed += 1;
-                        }
-                    }
-                    Ok(None) => {
-                        println!("❌ Transaction was dropped from the mempool");
-                        failed += 1;
-                    }
-                    Err(e) => {
-                        println!("❌ Error: {}", e);
-                        failed += 1;
-                    }
-                }
-            }
-            Err(e) => {
-                println!("❌ Failed to send transaction: {}", e);
-                failed += 1;
-            }
-        }
+    // The job is on disk before the first transfer goes out, and saved as
+    // each row changes, so a run that dies can be resumed
+    job.save()?;
+    println!("\n🚀 Sending transactions (job {})...", job.id);
 
-        // Small delay between transactions
-        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-    }
+    let polling = &config.polling;
+    bulk_engine::run(client, &mut job, polling).await?;
 
+    print_results(&job, polling)
+}
+
+/// Print every row of `job` with its outcome, and how to pick up what is
+/// left
+fn print_results(job: &BulkJob, polling: &PollingConfig) -> Result<()> {
     println!("\n📊 Transaction Summary:");
     println!("====================");
-    println!("Total transactions: {}", successful + failed);
-    println!("✅ Successful: {}", successful);
-    println!("❌ Failed: {}", failed);
+    let mut table = TableBuilder::new();
+    table.add_header(&["#", "To", "Amount", "Result", "Attempts", "Details"]);
+    for row in &job.rows {
+        let transfer = &row.transfer;
+        table.add_row(&[
+            &transfer.line.to_string(),
+            &format!("{:#x}", transfer.to),
+            &format!("{} {}", transfer.formatted_amount(), transfer.asset.symbol()),
+            row.state.label(),
+            &row.attempts.to_string(),
+            &row.state.details(),
+        ]);
+    }
+    table.print();
 
+    println!("Total transactions: {}", job.rows.len());
+    println!(
+        "✅ Confirmed: {}",
+        job.count(|s| matches!(s, RowState::Confirmed { .. }))
+    );
+    println!(
+        "⏳ Still pending: {}",
+        job.count(|s| matches!(s, RowState::Sent { .. }))
+    );
+    println!(
+        "❌ Failed (permanent): {}",
+        job.count(|s| s.failure_kind() == Some(FailureKind::Permanent))
+    );
+    println!(
+        "❌ Failed after {} retries (transient): {}",
+        polling.bulk_transfer_max_retries,
+        job.count(|s| s.failure_kind() == Some(FailureKind::Transient))
+    );
+    println!("Job file: {}", job.path()?.display());
+    if !job.is_finished() {
+        println!("Resume it from Bulk Transfer → 🔁 Resume bulk job to send or confirm the rest");
+    }
     Ok(())
 }
 
@@ -1,11 +1,23 @@
 use anyhow::Result;
 use console::style;
-use dialoguer::{Confirm, Input, Select, theme::ColorfulTheme};
+use dialoguer::{Confirm, Input, MultiSelect, Select, theme::ColorfulTheme};
 
 // Import config and API types
 use crate::api::ApiProvider;
-use crate::config::ConfigManager;
-use crate::types::network::Network;
+use crate::api::prices::Currency;
+use crate::commands::contacts::{ContactsAction, ContactsCommand};
+use crate::config::{ConfigManager, profile};
+use crate::security::{secrets, session};
+use crate::types::network::{CustomChain, Network};
+use crate::types::wallet::WalletData;
+use crate::utils::accounting;
+use crate::utils::categories::{AssetFilter, CategoryRule};
+use crate::utils::confirmations::ConfirmationRule;
+use crate::utils::eth::endpoint_host;
+use crate::utils::event_webhooks::{EventWebhook, WalletEvent, WebhookFormat};
+use crate::utils::{http, quick_amounts, redact, sponsor};
+use alloy::primitives::{Address, utils::format_units};
+use std::str::FromStr;
 
 // This module provides configuration management functionality
 
@@ -26,6 +38,7 @@ pub async fn show_config_menu() -> Result<()> {
 
         // Show current settings
         println!("  {}", style("Current Settings:").bold());
+        println!("  • Profile: {}", style(profile::active()).cyan());
         println!("  • Network: {}", style(config.default_network).cyan());
 
         // Show current API key status
@@ -50,9 +63,86 @@ pub async fn show_config_menu() -> Result<()> {
             println!("  • Default Wallet: {}", style(wallet).dim());
         }
 
+        println!(
+            "  • Send only to verified contacts: {}",
+            if config.require_verified_contacts {
+                style("on").green()
+            } else {
+                style("off").dim()
+            }
+        );
+        println!(
+            "  • Stay unlocked after entering a password: {}",
+            if config.session_timeout_minutes > 0 {
+                style(format!("{} min", config.session_timeout_minutes)).green()
+            } else {
+                style("off".to_string()).dim()
+            }
+        );
+        println!(
+            "  • Keep passwords and API keys in the OS keyring: {}",
+            if config.use_keyring {
+                style("on").green()
+            } else {
+                style("off").dim()
+            }
+        );
+        println!(
+            "  • Redact addresses and amounts on screen: {}",
+            if redact::is_active() {
+                style("on").green()
+            } else {
+                style("off").dim()
+            }
+        );
+
+        println!(
+            "  • Desktop notifications: {}",
+            if config.desktop_notifications {
+                style("on").green()
+            } else {
+                style("off").dim()
+            }
+        );
+        println!(
+            "  • Proxy: {}",
+            match &config.proxy {
+                Some(proxy) => style(endpoint_host(proxy)).green(),
+                None => style("off".to_string()).dim(),
+            }
+        );
+        println!(
+            "  • Fiat values: {}",
+            match config.display_currency {
+                Some(currency) => style(currency.to_string()).green(),
+                None => style("off".to_string()).dim(),
+            }
+        );
+
         let options = vec![
             format!("{}  Change Network", style("🌐").bold().blue()),
             format!("{}  Manage API Keys", style("🔑").bold().green()),
+            format!(
+                "{}  Toggle Verified-Contacts Policy",
+                style("🔐").bold().yellow()
+            ),
+            format!("{}  Toggle OS Keyring", style("🗝️").bold().yellow()),
+            format!("{}  Toggle Redact Output", style("🙈").bold().yellow()),
+            format!("{}  Toggle Desktop Notifications", style("🔔").bold().yellow()),
+            format!("{}  Unlock Session Timeout", style("🔒").bold().yellow()),
+            format!("{}  Category Rules", style("🏷️").bold().cyan()),
+            format!("{}  Polling & Rate Limits", style("⏱️").bold().cyan()),
+            format!("{}  Broadcast Endpoints", style("📡").bold().cyan()),
+            format!("{}  Accounting Export", style("📒").bold().cyan()),
+            format!("{}  Confirmation Policy", style("✅").bold().cyan()),
+            format!("{}  Canary Transfers", style("🐤").bold().cyan()),
+            format!("{}  Cost Centers", style("💼").bold().cyan()),
+            format!("{}  Quick Amounts", style("⚡").bold().cyan()),
+            format!("{}  Display Currency", style("💱").bold().cyan()),
+            format!("{}  Gas Sponsorship", style("⛽").bold().cyan()),
+            format!("{}  Custom Chains", style("🧩").bold().cyan()),
+            format!("{}  Proxy", style("🧅").bold().cyan()),
+            format!("{}  Event Webhooks", style("🪝").bold().cyan()),
             format!("{}  Clear Cache & Reset", style("🧹").bold().red()),
             format!("{}  Back to Main Menu", style("⬅️").bold().blue()),
         ];
@@ -67,6 +157,62 @@ pub async fn show_config_menu() -> Result<()> {
             0 => change_network(&config_manager).await?,
             1 => manage_api_keys(&config_manager).await?,
             2 => {
+                let mut config = config;
+                config.require_verified_contacts = !config.require_verified_contacts;
+                config_manager.save(&config)?;
+            }
+            3 => toggle_keyring(&config_manager)?,
+            4 => {
+                let mut config = config;
+                config.redact_output = !redact::is_active();
+                config_manager.save(&config)?;
+                redact::set_active(config.redact_output);
+            }
+            5 => {
+                let mut config = config;
+                config.desktop_notifications = !config.desktop_notifications;
+                config_manager.save(&config)?;
+            }
+            6 => {
+                let mut config = config;
+                config.session_timeout_minutes = Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Minutes a wallet stays unlocked (0 to always ask)")
+                    .default(config.session_timeout_minutes)
+                    .interact_text()?;
+                config_manager.save(&config)?;
+                if config.session_timeout_minutes == 0 {
+                    session::lock_all();
+                }
+            }
+            7 => manage_category_rules(&config_manager).await?,
+            8 => edit_polling(&config_manager)?,
+            9 => manage_broadcast(&config_manager)?,
+            10 => manage_accounting(&config_manager)?,
+            11 => manage_confirmation_policy(&config_manager)?,
+            12 => edit_canary(&config_manager)?,
+            13 => manage_cost_centers(&config_manager)?,
+            14 => manage_quick_amounts(&config_manager)?,
+            15 => {
+                let mut config = config;
+                let mut options = vec!["Off".to_string()];
+                options.extend(Currency::ALL.iter().map(Currency::to_string));
+                let current = config
+                    .display_currency
+                    .and_then(|c| Currency::ALL.iter().position(|&other| other == c))
+                    .map_or(0, |index| index + 1);
+                let index = Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Show fiat values (mainnet only) in")
+                    .items(&options)
+                    .default(current)
+                    .interact()?;
+                config.display_currency = index.checked_sub(1).map(|i| Currency::ALL[i]);
+                config_manager.save(&config)?;
+            }
+            16 => edit_sponsor(&config_manager)?,
+            17 => manage_custom_chains(&config_manager)?,
+            18 => edit_proxy(&config_manager)?,
+            19 => manage_event_webhooks(&config_manager)?,
+            20 => {
                 let confirm = Confirm::new()
                     .with_prompt("⚠️  WARNING: This will delete ALL wallet data and cannot be undone! Continue?")
                     .default(false)
@@ -81,7 +227,7 @@ pub async fn show_config_menu() -> Result<()> {
                     println!("\nOperation cancelled. No data was deleted.");
                 }
             }
-            3 => break,
+            21 => break,
             _ => {}
         }
     }
@@ -89,6 +235,1096 @@ pub async fn show_config_menu() -> Result<()> {
     Ok(())
 }
 
+/// Ask which configuration profile to use when profiles other than the
+/// default one exist; the profile stays in use until the wallet exits
+pub fn select_profile() -> Result<()> {
+    let profiles = profile::list()?;
+    if profiles.len() < 2 {
+        return Ok(());
+    }
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Profile")
+        .items(&profiles)
+        .default(0)
+        .interact()?;
+    profile::set_active(&profiles[selection])
+}
+
+/// Switch secret storage between the OS keyring and the wallet database,
+/// moving the saved Alchemy API key across
+fn toggle_keyring(config_manager: &ConfigManager) -> Result<()> {
+    let mut config = config_manager.load()?;
+    let mut wallet_data = WalletData::load()?;
+
+    if config.use_keyring {
+        // Read the key while the keyring is still enabled
+        wallet_data.api_key = wallet_data.stored_api_key()?;
+        secrets::forget_alchemy_api_key()?;
+        for wallet in wallet_data.wallets.values() {
+            secrets::forget_wallet_password(&wallet.address)?;
+        }
+        config.use_keyring = false;
+        config_manager.save(&config)?;
+        wallet_data.save()?;
+        println!(
+            "\n✅ Secrets are no longer kept in the OS keyring; saved passwords were removed."
+        );
+    } else {
+        config.use_keyring = true;
+        config_manager.save(&config)?;
+        if let Some(key) = wallet_data.api_key.take()
+            && let Err(e) = wallet_data.set_api_key(key)
+        {
+            config.use_keyring = false;
+            config_manager.save(&config)?;
+            return Err(e);
+        }
+        println!(
+            "\n✅ Wallet passwords will be remembered in the OS keyring after the next unlock."
+        );
+    }
+
+    println!("\n{}", style("Press Enter to continue...").dim());
+    let _ = std::io::stdin().read_line(&mut String::new());
+    Ok(())
+}
+
+/// Edit how many confirmations transfers need before they are reported as final
+fn manage_confirmation_policy(config_manager: &ConfigManager) -> Result<()> {
+    loop {
+        let mut config = config_manager.load()?;
+        clearscreen::clear().ok();
+
+        println!(
+            "\n{}",
+            style("✅ Confirmation Policy").bold().blue().underlined()
+        );
+        println!("{}\n", "-".repeat(40));
+        println!(
+            "  {}",
+            style("Rules are checked in order; the first match sets the confirmations.").dim()
+        );
+        for (i, rule) in config.confirmations.rules.iter().enumerate() {
+            println!("  {}. {}", i + 1, rule);
+        }
+        println!(
+            "  Otherwise: {} confirmations",
+            config.confirmations.default_confirmations
+        );
+
+        let options = vec![
+            format!("{}  Set Default", style("⚙️").bold().cyan()),
+            format!("{}  Add Rule", style("+").bold().green()),
+            format!("{}  Remove Rule", style("-").bold().red()),
+            format!("{}  Back to Configuration", style("⬅️").bold().blue()),
+        ];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("\nWhat would you like to do?")
+            .items(&options)
+            .default(0)
+            .interact()?;
+
+        match selection {
+            0 => {
+                config.confirmations.default_confirmations =
+                    Input::with_theme(&ColorfulTheme::default())
+                        .with_prompt("Confirmations when no rule matches")
+                        .default(config.confirmations.default_confirmations)
+                        .interact_text()?;
+            }
+            1 => {
+                let asset = prompt_rule_asset(config.default_network)?;
+                let min_amount = prompt_rule_amount("Minimum amount (inclusive, optional)")?;
+                let confirmations: u64 = Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Confirmations required")
+                    .interact_text()?;
+                config.confirmations.rules.push(ConfirmationRule {
+                    asset,
+                    min_amount,
+                    confirmations,
+                });
+            }
+            2 => {
+                if config.confirmations.rules.is_empty() {
+                    continue;
+                }
+                let rules: Vec<String> = config
+                    .confirmations
+                    .rules
+                    .iter()
+                    .map(|r| r.to_string())
+                    .collect();
+                let index = Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Select rule to remove")
+                    .items(&rules)
+                    .interact()?;
+                config.confirmations.rules.remove(index);
+            }
+            _ => break,
+        }
+        config_manager.save(&config)?;
+    }
+
+    Ok(())
+}
+
+async fn manage_category_rules(config_manager: &ConfigManager) -> Result<()> {
+    loop {
+        let config = config_manager.load()?;
+        clearscreen::clear().ok();
+
+        println!(
+            "\n{}",
+            style("🏷️  Category Rules").bold().blue().underlined()
+        );
+        println!("{}\n", "-".repeat(40));
+        println!(
+            "  {}",
+            style("Rules are checked in order; the first match sets the category.").dim()
+        );
+
+        if config.category_rules.is_empty() {
+            println!("  No rules configured");
+        } else {
+            for (i, rule) in config.category_rules.iter().enumerate() {
+                println!("  {}. {}", i + 1, rule);
+            }
+        }
+
+        let options = vec![
+            format!("{}  Add Rule", style("+").bold().green()),
+            format!("{}  Remove Rule", style("-").bold().red()),
+            format!("{}  Back to Configuration", style("⬅️").bold().blue()),
+        ];
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("\nWhat would you like to do?")
+            .items(&options)
+            .default(0)
+            .interact()?;
+
+        match selection {
+            0 => {
+                if let Err(e) = add_category_rule(config_manager) {
+                    println!("\n{}", style(format!("Error: {}", e)).red());
+                    println!("\n{}", style("Press Enter to continue...").dim());
+                    let _ = std::io::stdin().read_line(&mut String::new());
+                }
+            }
+            1 => {
+                let mut config = config;
+                if config.category_rules.is_empty() {
+                    continue;
+                }
+                let rules: Vec<String> = config
+                    .category_rules
+                    .iter()
+                    .map(|r| r.to_string())
+                    .collect();
+                let index = Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Select rule to remove")
+                    .items(&rules)
+                    .interact()?;
+                config.category_rules.remove(index);
+                config_manager.save(&config)?;
+            }
+            _ => break,
+        }
+    }
+
+    Ok(())
+}
+
+fn add_category_rule(config_manager: &ConfigManager) -> Result<()> {
+    let mut config = config_manager.load()?;
+
+    let category: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Category (e.g. off-ramp, expenses)")
+        .interact_text()?;
+
+    let to = prompt_rule_address("Recipient address or contact name (optional)")?;
+    let from = prompt_rule_address("Sender address or contact name (optional)")?;
+
+    let asset = prompt_rule_asset(config.default_network)?;
+
+    let min_amount = prompt_rule_amount("Minimum amount (inclusive, optional)")?;
+    let max_amount = prompt_rule_amount("Maximum amount (exclusive, optional)")?;
+
+    let rule = CategoryRule {
+        category: category.trim().to_string(),
+        to,
+        from,
+        asset,
+        min_amount,
+        max_amount,
+    };
+    println!("\n  {}", style(&rule).cyan());
+    config.category_rules.push(rule);
+    config_manager.save(&config)?;
+    Ok(())
+}
+
+/// Pick RBTC or a registered token, or `None` for any asset
+fn prompt_rule_asset(network: Network) -> Result<Option<AssetFilter>> {
+    let network = network.chain_name();
+    let tokens = crate::commands::tokens::TokenRegistry::load()
+        .map(|registry| registry.list_tokens(Some(network)))
+        .unwrap_or_default();
+    let mut asset_options = vec!["Any asset".to_string(), "RBTC".to_string()];
+    asset_options.extend(tokens.iter().map(|(symbol, _)| symbol.clone()));
+    Ok(
+        match Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Asset")
+            .items(&asset_options)
+            .default(0)
+            .interact()?
+        {
+            0 => None,
+            1 => Some(AssetFilter::Rbtc),
+            i => {
+                let (_, info) = &tokens[i - 2];
+                Some(AssetFilter::Token {
+                    symbol: info.symbol.clone(),
+                    address: info.address.parse()?,
+                    decimals: info.decimals,
+                })
+            }
+        },
+    )
+}
+
+/// Read an optional address, resolving contact names
+fn prompt_rule_address(prompt: &str) -> Result<Option<Address>> {
+    let input: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .allow_empty(true)
+        .interact_text()?;
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    if let Ok(address) = Address::from_str(input) {
+        return Ok(Some(address));
+    }
+
+    let contacts = ContactsCommand {
+        action: ContactsAction::List,
+    }
+    .load_contacts()?;
+    contacts
+        .iter()
+        .find(|c| c.name.eq_ignore_ascii_case(input))
+        .map(|c| Some(c.address))
+        .ok_or_else(|| anyhow::anyhow!("'{}' is neither an address nor a contact", input))
+}
+
+fn prompt_rule_amount(prompt: &str) -> Result<Option<f64>> {
+    let input: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .allow_empty(true)
+        .validate_with(|input: &String| -> Result<(), &str> {
+            if input.trim().is_empty() || input.trim().parse::<f64>().is_ok() {
+                Ok(())
+            } else {
+                Err("Please enter a number")
+            }
+        })
+        .interact_text()?;
+    Ok(input.trim().parse().ok())
+}
+
+/// Edit polling intervals and request pacing
+fn edit_polling(config_manager: &ConfigManager) -> Result<()> {
+    let mut config = config_manager.load()?;
+    let polling = &mut config.polling;
+
+    println!(
+        "\n  {}",
+        style("Raise these values on slow or metered connections.").dim()
+    );
+
+    polling.receipt_poll_interval_secs = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Seconds between receipt checks")
+        .default(polling.receipt_poll_interval_secs)
+        .interact_text()?;
+    polling.receipt_poll_attempts = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Receipt checks before reporting a transaction as pending")
+        .default(polling.receipt_poll_attempts)
+        .interact_text()?;
+    polling.queue_poll_interval_secs = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Seconds between scheduled transfer queue checks")
+        .default(polling.queue_poll_interval_secs)
+        .interact_text()?;
+    polling.bulk_transfer_delay_ms = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Milliseconds between batches of bulk transfers")
+        .default(polling.bulk_transfer_delay_ms)
+        .interact_text()?;
+    polling.bulk_transfer_concurrency = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Bulk transfers submitted at once (1 sends them one by one)")
+        .default(polling.bulk_transfer_concurrency)
+        .validate_with(|value: &usize| -> Result<(), &str> {
+            if *value > 0 {
+                Ok(())
+            } else {
+                Err("Send at least one transfer at a time")
+            }
+        })
+        .interact_text()?;
+    polling.bulk_transfer_max_retries = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Retries for bulk transfers that fail on gas price or nonce")
+        .default(polling.bulk_transfer_max_retries)
+        .interact_text()?;
+    polling.request_delay_ms = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Milliseconds between RPC lookups in history and dashboard scans")
+        .default(polling.request_delay_ms)
+        .interact_text()?;
+    polling.rpc_max_retries = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Retries for requests that are rate limited or hit a server error")
+        .default(polling.rpc_max_retries)
+        .interact_text()?;
+    polling.rpc_retry_base_delay_ms = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Milliseconds before the first retry (doubles with each retry)")
+        .default(polling.rpc_retry_base_delay_ms)
+        .interact_text()?;
+    polling.rpc_retry_max_delay_ms = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Longest pause between retries, in milliseconds")
+        .default(polling.rpc_retry_max_delay_ms)
+        .interact_text()?;
+
+    println!(
+        "\n  {}",
+        style("Client-side rate limits per provider; 0 turns a limit off.").dim()
+    );
+    let limits = &mut config.api.rate_limits;
+    limits.alchemy_compute_units_per_sec = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Alchemy compute units per second")
+        .default(limits.alchemy_compute_units_per_sec)
+        .interact_text()?;
+    limits.rsk_rpc_requests_per_sec = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("RSK RPC API requests per second")
+        .default(limits.rsk_rpc_requests_per_sec)
+        .interact_text()?;
+    limits.blockscout_requests_per_sec = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Blockscout requests per second")
+        .default(limits.blockscout_requests_per_sec)
+        .interact_text()?;
+    limits.explorer_requests_per_sec = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Rootstock explorer requests per second")
+        .default(limits.explorer_requests_per_sec)
+        .interact_text()?;
+
+    config_manager.save(&config)?;
+    println!("\n{}", style("✅ Polling settings saved").green().bold());
+    Ok(())
+}
+
+fn edit_canary(config_manager: &ConfigManager) -> Result<()> {
+    let mut config = config_manager.load()?;
+    let canary = &mut config.canary;
+
+    println!(
+        "\n  {}",
+        style("Transfers at or above the threshold offer to send a tiny canary first.").dim()
+    );
+
+    let threshold: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Threshold in asset units (empty to never offer a canary)")
+        .default(canary.threshold.map(|t| t.to_string()).unwrap_or_default())
+        .allow_empty(true)
+        .validate_with(|input: &String| -> Result<(), &str> {
+            if input.trim().is_empty() || input.trim().parse::<f64>().is_ok_and(|v| v > 0.0) {
+                Ok(())
+            } else {
+                Err("Enter a positive number")
+            }
+        })
+        .interact_text()?;
+    canary.threshold = threshold.trim().parse().ok();
+    canary.amount = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Canary amount in asset units")
+        .default(canary.amount)
+        .validate_with(|amount: &f64| -> Result<(), &str> {
+            if *amount > 0.0 {
+                Ok(())
+            } else {
+                Err("The canary amount must be positive")
+            }
+        })
+        .interact_text()?;
+
+    config_manager.save(&config)?;
+    println!("\n{}", style("✅ Canary settings saved").green().bold());
+    Ok(())
+}
+
+fn edit_sponsor(config_manager: &ConfigManager) -> Result<()> {
+    let mut config = config_manager.load()?;
+    let network = config.default_network;
+    let grants = sponsor::grants(network.chain_name())?;
+
+    println!(
+        "\n  {}",
+        style("New and imported wallets are topped up with RBTC for gas from the sponsor wallet.")
+            .dim()
+    );
+    println!(
+        "  Sponsored on {}: {} wallet(s), {} RBTC",
+        network,
+        grants.len(),
+        format_units(sponsor::total(&grants), 18)?
+    );
+
+    let wallet_data = WalletData::load()?;
+    let mut names: Vec<String> = wallet_data
+        .wallets
+        .values()
+        .filter(|wallet| !wallet.watch_only && wallet.hardware.is_none())
+        .map(|wallet| wallet.name.clone())
+        .collect();
+    names.sort();
+    let mut options = vec!["Off".to_string()];
+    options.extend(names.iter().cloned());
+    let current = config
+        .sponsor
+        .wallet
+        .as_ref()
+        .and_then(|name| names.iter().position(|other| other == name))
+        .map_or(0, |index| index + 1);
+    let index = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Sponsor wallet")
+        .items(&options)
+        .default(current)
+        .interact()?;
+    let sponsor = &mut config.sponsor;
+    sponsor.wallet = index.checked_sub(1).map(|i| names[i].clone());
+
+    if sponsor.wallet.is_some() {
+        let positive = |amount: &f64| -> Result<(), &str> {
+            if *amount > 0.0 {
+                Ok(())
+            } else {
+                Err("Enter a positive amount")
+            }
+        };
+        sponsor.amount = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("RBTC to top each new wallet up to")
+            .default(sponsor.amount)
+            .validate_with(positive)
+            .interact_text()?;
+        sponsor.cap = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Most RBTC the sponsor hands out per network")
+            .default(sponsor.cap)
+            .validate_with(positive)
+            .interact_text()?;
+    }
+
+    config_manager.save(&config)?;
+    println!("\n{}", style("✅ Gas sponsorship saved").green().bold());
+    Ok(())
+}
+
+fn manage_broadcast(config_manager: &ConfigManager) -> Result<()> {
+    loop {
+        let config = config_manager.load()?;
+        clearscreen::clear().ok();
+
+        println!(
+            "\n{}",
+            style("📡 Broadcast Endpoints").bold().blue().underlined()
+        );
+        println!("{}\n", "-".repeat(40));
+        println!(
+            "  {}",
+            style("When enabled, transactions are signed locally and sent to every endpoint.")
+                .dim()
+        );
+        println!(
+            "  {}",
+            style("Requests fail over to the next endpoint when one is down or rate limited.")
+                .dim()
+        );
+        println!(
+            "  • Broadcast to all endpoints: {}",
+            if config.multi_broadcast {
+                style("on").green()
+            } else {
+                style("off").dim()
+            }
+        );
+        println!("  {}", style("Endpoints:").bold());
+        for endpoint in config.broadcast_endpoints() {
+            println!("    • {}", endpoint_host(&endpoint));
+        }
+        println!(
+            "  • Live updates (WebSocket): {}",
+            match config.ws_urls.get(config.default_network.chain_name()) {
+                Some(url) => style(endpoint_host(url)).green(),
+                None => style("off, polling".to_string()).dim(),
+            }
+        );
+
+        let options = vec![
+            format!("{}  Toggle Broadcasting", style("🔁").bold().yellow()),
+            format!("{}  Add Endpoint", style("+").bold().green()),
+            format!("{}  Remove Endpoint", style("-").bold().red()),
+            format!("{}  WebSocket Endpoint", style("⚡").bold().cyan()),
+            format!("{}  Back to Configuration", style("⬅️").bold().blue()),
+        ];
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("\nWhat would you like to do?")
+            .items(&options)
+            .default(0)
+            .interact()?;
+
+        let mut config = config;
+        match selection {
+            0 => config.multi_broadcast = !config.multi_broadcast,
+            1 => {
+                let endpoint: String = Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("RPC endpoint URL")
+                    .validate_with(|input: &String| -> Result<(), &str> {
+                        match url::Url::parse(input) {
+                            Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(()),
+                            _ => Err("Please enter an http(s) URL"),
+                        }
+                    })
+                    .interact_text()?;
+                if !config.extra_rpc_urls.contains(&endpoint) {
+                    config.extra_rpc_urls.push(endpoint);
+                }
+            }
+            2 => {
+                if config.extra_rpc_urls.is_empty() {
+                    continue;
+                }
+                let index = Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Select endpoint to remove")
+                    .items(&config.extra_rpc_urls)
+                    .interact()?;
+                config.extra_rpc_urls.remove(index);
+            }
+            3 => {
+                let endpoint: String = Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt(format!(
+                        "WebSocket URL for {} (empty to poll instead)",
+                        config.default_network
+                    ))
+                    .allow_empty(true)
+                    .validate_with(|input: &String| -> Result<(), &str> {
+                        if input.is_empty() {
+                            return Ok(());
+                        }
+                        match url::Url::parse(input) {
+                            Ok(url) if matches!(url.scheme(), "ws" | "wss") => Ok(()),
+                            _ => Err("Please enter a ws(s) URL"),
+                        }
+                    })
+                    .interact_text()?;
+                let network = config.default_network.chain_name().to_string();
+                if endpoint.is_empty() {
+                    config.ws_urls.remove(&network);
+                } else {
+                    config.ws_urls.insert(network, endpoint);
+                }
+            }
+            _ => break,
+        }
+        config_manager.save(&config)?;
+    }
+
+    Ok(())
+}
+
+/// Choose the proxy requests go through. Clients are built once per run, so
+/// a change takes effect when the wallet is started again.
+fn edit_proxy(config_manager: &ConfigManager) -> Result<()> {
+    let mut config = config_manager.load()?;
+    let options = [
+        "Off (connect directly)",
+        "Tor (socks5h://127.0.0.1:9050)",
+        "Custom proxy URL",
+    ];
+    let current = match config.proxy.as_deref() {
+        None => 0,
+        Some(http::TOR_PROXY) => 1,
+        Some(_) => 2,
+    };
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Send requests through")
+        .items(&options)
+        .default(current)
+        .interact()?;
+    config.proxy = match selection {
+        0 => None,
+        1 => Some(http::TOR_PROXY.to_string()),
+        _ => Some(
+            Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Proxy URL (http://, https://, socks5:// or socks5h://)")
+                .with_initial_text(config.proxy.clone().unwrap_or_default())
+                .validate_with(|input: &String| -> Result<(), String> {
+                    http::parse_proxy(input).map(|_| ()).map_err(|e| e.to_string())
+                })
+                .interact_text()?,
+        ),
+    };
+    config_manager.save(&config)?;
+
+    println!(
+        "\n{} Restart the wallet for the proxy setting to take effect. Live updates over WebSocket are off while a proxy is set.",
+        style("ℹ").blue().bold()
+    );
+    println!("\n{}", style("Press Enter to continue...").dim());
+    let _ = std::io::stdin().read_line(&mut String::new());
+    Ok(())
+}
+
+fn manage_custom_chains(config_manager: &ConfigManager) -> Result<()> {
+    loop {
+        let config = config_manager.load()?;
+        clearscreen::clear().ok();
+
+        println!("\n{}", style("🧩 Custom Chains").bold().blue().underlined());
+        println!("{}\n", "-".repeat(40));
+        println!(
+            "  {}",
+            style("EVM chains, e.g. private Rootstock networks, selectable under Change Network.")
+                .dim()
+        );
+        if config.custom_chains.is_empty() {
+            println!("  No custom chains defined yet.");
+        }
+        for chain in &config.custom_chains {
+            println!(
+                "    • {} (chain {}, {}) at {}",
+                style(&chain.name).cyan(),
+                chain.chain_id,
+                chain.symbol,
+                endpoint_host(&chain.rpc_url)
+            );
+        }
+
+        let options = vec![
+            format!("{}  Add Chain", style("+").bold().green()),
+            format!("{}  Remove Chain", style("-").bold().red()),
+            format!("{}  Back to Configuration", style("⬅️").bold().blue()),
+        ];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("\nWhat would you like to do?")
+            .items(&options)
+            .default(0)
+            .interact()?;
+
+        let mut config = config;
+        match selection {
+            0 => {
+                let chain = CustomChain {
+                    name: Input::with_theme(&ColorfulTheme::default())
+                        .with_prompt("Name, also used with --network (e.g. devnet)")
+                        .interact_text()?,
+                    chain_id: Input::with_theme(&ColorfulTheme::default())
+                        .with_prompt("Chain ID")
+                        .interact_text()?,
+                    rpc_url: Input::with_theme(&ColorfulTheme::default())
+                        .with_prompt("RPC URL")
+                        .interact_text()?,
+                    explorer_url: Input::with_theme(&ColorfulTheme::default())
+                        .with_prompt("Explorer URL (optional)")
+                        .allow_empty(true)
+                        .interact_text()?,
+                    symbol: Input::with_theme(&ColorfulTheme::default())
+                        .with_prompt("Native coin symbol")
+                        .default("RBTC".to_string())
+                        .interact_text()?,
+                };
+                if let Err(e) = chain.validate(&config.custom_chains) {
+                    println!("\n{}: {}", style("Error").red().bold(), e);
+                    println!("\n{}", style("Press Enter to continue...").dim());
+                    let _ = std::io::stdin().read_line(&mut String::new());
+                    continue;
+                }
+                config.custom_chains.push(chain);
+            }
+            1 => {
+                if config.custom_chains.is_empty() {
+                    continue;
+                }
+                let names: Vec<&str> = config
+                    .custom_chains
+                    .iter()
+                    .map(|chain| chain.name.as_str())
+                    .collect();
+                let index = Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Select chain to remove")
+                    .items(&names)
+                    .interact()?;
+                if config.default_network == Network::Custom(config.custom_chains[index].chain_id)
+                {
+                    println!(
+                        "\n{}: {} is the current network; change networks first.",
+                        style("Error").red().bold(),
+                        names[index]
+                    );
+                    println!("\n{}", style("Press Enter to continue...").dim());
+                    let _ = std::io::stdin().read_line(&mut String::new());
+                    continue;
+                }
+                config.custom_chains.remove(index);
+            }
+            _ => break,
+        }
+        config_manager.save(&config)?;
+    }
+    Ok(())
+}
+
+fn manage_cost_centers(config_manager: &ConfigManager) -> Result<()> {
+    loop {
+        let config = config_manager.load()?;
+        clearscreen::clear().ok();
+
+        println!("\n{}", style("💼 Cost Centers").bold().blue().underlined());
+        println!("{}\n", "-".repeat(40));
+        println!(
+            "  {}",
+            style("Sends can be allocated to one of these; exports and stats show the allocation.")
+                .dim()
+        );
+        if config.cost_centers.is_empty() {
+            println!("  No cost centers defined yet.");
+        }
+        for cost_center in &config.cost_centers {
+            println!("    • {}", cost_center);
+        }
+
+        let options = vec![
+            format!("{}  Add Cost Center", style("+").bold().green()),
+            format!("{}  Remove Cost Center", style("-").bold().red()),
+            format!("{}  Back to Configuration", style("⬅️").bold().blue()),
+        ];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("\nWhat would you like to do?")
+            .items(&options)
+            .default(0)
+            .interact()?;
+
+        let mut config = config;
+        match selection {
+            0 => {
+                let name: String = Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Cost center name (e.g. Marketing)")
+                    .interact_text()?;
+                let name = name.trim().to_string();
+                if !name.is_empty()
+                    && !config
+                        .cost_centers
+                        .iter()
+                        .any(|c| c.eq_ignore_ascii_case(&name))
+                {
+                    config.cost_centers.push(name);
+                }
+            }
+            1 => {
+                if config.cost_centers.is_empty() {
+                    continue;
+                }
+                let index = Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Select cost center to remove")
+                    .items(&config.cost_centers)
+                    .interact()?;
+                config.cost_centers.remove(index);
+            }
+            _ => break,
+        }
+        config_manager.save(&config)?;
+    }
+    Ok(())
+}
+
+fn manage_event_webhooks(config_manager: &ConfigManager) -> Result<()> {
+    loop {
+        let config = config_manager.load()?;
+        clearscreen::clear().ok();
+
+        println!("\n{}", style("🪝 Event Webhooks").bold().blue().underlined());
+        println!("{}\n", "-".repeat(40));
+        println!(
+            "  {}",
+            style("Sent, confirmed, failed and received transfers are posted to these URLs.")
+                .dim()
+        );
+        if config.event_webhooks.is_empty() {
+            println!("  No event webhooks defined yet.");
+        }
+        for hook in &config.event_webhooks {
+            println!(
+                "    • {} ({}, {})",
+                endpoint_host(&hook.url),
+                hook.describe_events(),
+                hook.format
+            );
+        }
+
+        let options = vec![
+            format!("{}  Add Webhook", style("+").bold().green()),
+            format!("{}  Remove Webhook", style("-").bold().red()),
+            format!("{}  Back to Configuration", style("⬅️").bold().blue()),
+        ];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("\nWhat would you like to do?")
+            .items(&options)
+            .default(0)
+            .interact()?;
+
+        let mut config = config;
+        match selection {
+            0 => {
+                let url: String = Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Webhook URL")
+                    .validate_with(|input: &String| {
+                        url::Url::parse(input.trim())
+                            .map(|_| ())
+                            .map_err(|e| e.to_string())
+                    })
+                    .interact_text()?;
+                let formats = [WebhookFormat::Json, WebhookFormat::Slack, WebhookFormat::Discord];
+                let format = Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Format")
+                    .items(&formats)
+                    .default(0)
+                    .interact()?;
+                let chosen = MultiSelect::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Events (none for all)")
+                    .items(&WalletEvent::ALL)
+                    .interact()?;
+                let secret: String = Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Signing secret (empty for none)")
+                    .allow_empty(true)
+                    .interact_text()?;
+                let url = url.trim().to_string();
+                config.event_webhooks.retain(|hook| hook.url != url);
+                config.event_webhooks.push(EventWebhook {
+                    url,
+                    events: chosen.into_iter().map(|i| WalletEvent::ALL[i]).collect(),
+                    format: formats[format],
+                    secret: Some(secret).filter(|secret| !secret.is_empty()),
+                });
+            }
+            1 => {
+                if config.event_webhooks.is_empty() {
+                    continue;
+                }
+                let hosts: Vec<String> = config
+                    .event_webhooks
+                    .iter()
+                    .map(|hook| format!("{} ({})", endpoint_host(&hook.url), hook.format))
+                    .collect();
+                let index = Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Select webhook to remove")
+                    .items(&hosts)
+                    .interact()?;
+                config.event_webhooks.remove(index);
+            }
+            _ => break,
+        }
+        config_manager.save(&config)?;
+    }
+    Ok(())
+}
+
+fn manage_quick_amounts(config_manager: &ConfigManager) -> Result<()> {
+    loop {
+        let config = config_manager.load()?;
+        clearscreen::clear().ok();
+
+        println!("\n{}", style("⚡ Quick Amounts").bold().blue().underlined());
+        println!("{}\n", "-".repeat(40));
+        println!(
+            "  {}",
+            style("Offered in Send Funds next to the last amount sent to the recipient.").dim()
+        );
+        if config.quick_amounts.is_empty() {
+            println!("  No quick amounts defined yet.");
+        }
+        for (symbol, amounts) in &config.quick_amounts {
+            println!("    • {}: {}", symbol, amounts.join(", "));
+        }
+
+        let options = vec![
+            format!("{}  Set Amounts for a Token", style("+").bold().green()),
+            format!("{}  Back to Configuration", style("⬅️").bold().blue()),
+        ];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("\nWhat would you like to do?")
+            .items(&options)
+            .default(0)
+            .interact()?;
+        if selection != 0 {
+            break;
+        }
+
+        let mut symbols = vec!["RBTC".to_string()];
+        for (_, info) in crate::commands::tokens::TokenRegistry::load()
+            .map(|registry| registry.list_tokens(Some(config.default_network.chain_name())))
+            .unwrap_or_default()
+        {
+            // Presets are per symbol, so tokens sharing one are listed once
+            if !symbols.contains(&info.symbol) {
+                symbols.push(info.symbol);
+            }
+        }
+        let index = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Token")
+            .items(&symbols)
+            .default(0)
+            .interact()?;
+        let symbol = symbols[index].to_uppercase();
+
+        let mut config = config;
+        let current = config
+            .quick_amounts
+            .get(&symbol)
+            .map(|amounts| amounts.join(", "))
+            .unwrap_or_default();
+        let input: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Amounts, separated by commas (empty to clear)")
+            .with_initial_text(current)
+            .allow_empty(true)
+            .validate_with(|input: &String| {
+                quick_amounts::parse(input)
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            })
+            .interact_text()?;
+        let amounts = quick_amounts::parse(&input)?;
+        if amounts.is_empty() {
+            config.quick_amounts.remove(&symbol);
+        } else {
+            config.quick_amounts.insert(symbol, amounts);
+        }
+        config_manager.save(&config)?;
+    }
+    Ok(())
+}
+
+fn manage_accounting(config_manager: &ConfigManager) -> Result<()> {
+    loop {
+        let config = config_manager.load()?;
+        clearscreen::clear().ok();
+
+        println!(
+            "\n{}",
+            style("📒 Accounting Export").bold().blue().underlined()
+        );
+        println!("{}\n", "-".repeat(40));
+        println!(
+            "  {}",
+            style("Accounts used when exporting history to beancount or ledger.").dim()
+        );
+        let accounts = &config.accounting;
+        println!("  • Wallet: {}", accounts.wallet_account);
+        println!("  • Fees: {}", accounts.fee_account);
+        println!(
+            "  • Income (uncategorized): {}:<Category>",
+            accounts.income_root
+        );
+        println!(
+            "  • Expenses (uncategorized): {}:<Category>",
+            accounts.expense_root
+        );
+        for (category, account) in &accounts.categories {
+            println!("  • {} → {}", category, account);
+        }
+
+        let options = vec![
+            format!("{}  Wallet Account", style("🏦").bold().cyan()),
+            format!("{}  Fee Account", style("⛽").bold().cyan()),
+            format!("{}  Income Parent Account", style("📥").bold().cyan()),
+            format!("{}  Expense Parent Account", style("📤").bold().cyan()),
+            format!("{}  Map Category to Account", style("+").bold().green()),
+            format!("{}  Remove Category Mapping", style("-").bold().red()),
+            format!("{}  Back to Configuration", style("⬅️").bold().blue()),
+        ];
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("\nWhat would you like to do?")
+            .items(&options)
+            .default(0)
+            .interact()?;
+
+        let mut config = config;
+        let accounts = &mut config.accounting;
+        match selection {
+            0 => {
+                accounts.wallet_account =
+                    prompt_account("Wallet account", &accounts.wallet_account)?
+            }
+            1 => accounts.fee_account = prompt_account("Fee account", &accounts.fee_account)?,
+            2 => {
+                accounts.income_root =
+                    prompt_account("Income parent account", &accounts.income_root)?
+            }
+            3 => {
+                accounts.expense_root =
+                    prompt_account("Expense parent account", &accounts.expense_root)?
+            }
+            4 => {
+                if config.category_rules.is_empty() {
+                    println!("\nNo category rules defined yet. Add them under Category Rules.");
+                    std::thread::sleep(std::time::Duration::from_secs(2));
+                    continue;
+                }
+                let mut categories: Vec<String> = config
+                    .category_rules
+                    .iter()
+                    .map(|rule| rule.category.clone())
+                    .collect();
+                categories.sort();
+                categories.dedup();
+                let index = Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Category")
+                    .items(&categories)
+                    .interact()?;
+                let current = config
+                    .accounting
+                    .counter_account(Some(&categories[index]), false);
+                let account = prompt_account("Account", &current)?;
+                config
+                    .accounting
+                    .categories
+                    .insert(categories[index].clone(), account);
+            }
+            5 => {
+                let mapped: Vec<String> = accounts.categories.keys().cloned().collect();
+                if mapped.is_empty() {
+                    continue;
+                }
+                let index = Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Select mapping to remove")
+                    .items(&mapped)
+                    .interact()?;
+                accounts.categories.remove(&mapped[index]);
+            }
+            _ => break,
+        }
+        config_manager.save(&config)?;
+    }
+
+    Ok(())
+}
+
+fn prompt_account(prompt: &str, current: &str) -> Result<String> {
+    Ok(Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .with_initial_text(current)
+        .validate_with(|input: &String| -> Result<(), String> {
+            accounting::validate_account(input.trim()).map_err(|e| e.to_string())
+        })
+        .interact_text()?
+        .trim()
+        .to_string())
+}
+
 async fn manage_api_keys(config_manager: &ConfigManager) -> Result<()> {
     loop {
         let config = config_manager.load()?;
@@ -117,10 +1353,19 @@ async fn manage_api_keys(config_manager: &ConfigManager) -> Result<()> {
                 );
             }
         }
+        println!(
+            "\n  {} {}",
+            style("History provider:").bold(),
+            match &config.api.default_provider {
+                Some(provider) => provider.to_string(),
+                None => "Alchemy when a key is set, otherwise Blockscout".to_string(),
+            }
+        );
 
         let options = vec![
             format!("{}  Add API Key", style("+").bold().green()),
             format!("{}  Remove API Key", style("-").bold().red()),
+            format!("{}  History Provider", style("📜").bold().cyan()),
             format!("{}  Back to Configuration", style("⬅️").bold().blue()),
         ];
 
@@ -133,7 +1378,8 @@ async fn manage_api_keys(config_manager: &ConfigManager) -> Result<()> {
         match selection {
             0 => add_api_key(config_manager).await?,
             1 => remove_api_key(config_manager).await?,
-            2 => break,
+            2 => select_history_provider(config_manager)?,
+            3 => break,
             _ => {}
         }
     }
@@ -141,6 +1387,40 @@ async fn manage_api_keys(config_manager: &ConfigManager) -> Result<()> {
     Ok(())
 }
 
+/// Choose where transaction history is fetched from
+fn select_history_provider(config_manager: &ConfigManager) -> Result<()> {
+    let mut config = config_manager.load()?;
+    let choices = [
+        (
+            None,
+            "Automatic (Alchemy when a key is set, otherwise Blockscout)",
+        ),
+        (Some(ApiProvider::Alchemy), "Alchemy (requires an API key)"),
+        (
+            Some(ApiProvider::Blockscout),
+            "Blockscout (public explorer, no key needed)",
+        ),
+        (
+            Some(ApiProvider::Explorer),
+            "Rootstock Explorer (official explorer, no key needed)",
+        ),
+    ];
+    let names: Vec<_> = choices.iter().map(|(_, name)| *name).collect();
+    let current = choices
+        .iter()
+        .position(|(provider, _)| *provider == config.api.default_provider)
+        .unwrap_or(0);
+
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Fetch transaction history from (the others are tried if it fails)")
+        .items(&names)
+        .default(current)
+        .interact()?;
+    config.api.default_provider = choices[selection].0.clone();
+    config_manager.save(&config)?;
+    Ok(())
+}
+
 async fn add_api_key(config_manager: &ConfigManager) -> Result<()> {
     let mut config = config_manager.load()?;
 
@@ -232,7 +1512,7 @@ async fn remove_api_key(config_manager: &ConfigManager) -> Result<()> {
     Ok(())
 }
 
-async fn change_network(config_manager: &ConfigManager) -> Result<()> {
+pub(super) async fn change_network(config_manager: &ConfigManager) -> Result<()> {
     let mut config = config_manager.load()?;
 
     // Define all available networks with their display names
@@ -246,7 +1526,7 @@ async fn change_network(config_manager: &ConfigManager) -> Result<()> {
         Network::RootStockTestnet,
     ];
 
-    let network_descriptions = [
+    let mut network_descriptions = [
         "Mainnet (Production, real RSK)",
         "Testnet (Test network, free test tokens)",
         "Regtest (Local development)",
@@ -254,7 +1534,19 @@ async fn change_network(config_manager: &ConfigManager) -> Result<()> {
         "Alchemy Testnet (Test network, Alchemy RPC)",
         "Rootstock Mainnet (Production, Rootstock RPC)",
         "Rootstock Testnet (Test network, Rootstock RPC)",
-    ];
+    ]
+    .map(String::from)
+    .to_vec();
+    let mut networks = networks.to_vec();
+    for chain in &config.custom_chains {
+        networks.push(Network::Custom(chain.chain_id));
+        network_descriptions.push(format!(
+            "{} (Custom chain {}, {})",
+            chain.name,
+            chain.chain_id,
+            endpoint_host(&chain.rpc_url)
+        ));
+    }
 
     let current_network = config.default_network;
 
@@ -1,10 +1,17 @@
 use crate::{
-    commands::contacts::{ContactsAction, ContactsCommand},
-    utils::table::TableBuilder,
+    commands::contacts::{ContactsAction, ContactsCommand, GroupAction},
+    commands::transfer::TransferResult,
+    config::ConfigManager,
+    interactive::scan::scan_payment_request,
+    types::{
+        contacts::{Contact, groups},
+        wallet::WalletData,
+    },
+    utils::{http, rns, table::TableBuilder},
 };
 use anyhow::Result;
 use console::style;
-use inquire::{Confirm, Text, validator::Validation};
+use inquire::{Confirm, MultiSelect, Text, validator::Validation};
 /// Interacive contacts manage
 pub async fn manage_contacts() -> Result<()> {
     loop {
@@ -17,6 +24,8 @@ pub async fn manage_contacts() -> Result<()> {
             "✏️  Update contact",
             "❌ Remove contact",
             "🔍 Search contacts",
+            "🔐 Verify contact",
+            "🗂️  Manage groups",
             "🏠 Back to main menu",
         ];
 
@@ -28,6 +37,8 @@ pub async fn manage_contacts() -> Result<()> {
             "✏️  Update contact" => update_contact().await?,
             "❌ Remove contact" => remove_contact().await?,
             "🔍 Search contacts" => search_contacts().await?,
+            "🔐 Verify contact" => verify_contact().await?,
+            "🗂️  Manage groups" => manage_groups()?,
             "🏠 Back to main menu" => break,
             _ => unreachable!(),
         }
@@ -61,6 +72,15 @@ pub async fn list_contacts() -> Result<()> {
         return Ok(());
     }
 
+    let config = ConfigManager::new()?.load()?;
+    let names = match http::provider(&config.rpc_url()) {
+        Ok(provider) => {
+            let addresses = contacts.iter().map(|contact| contact.address);
+            rns::reverse_names(&provider, config.default_network.chain_id(), addresses).await
+        }
+        Err(_) => Default::default(),
+    };
+
     let mut table = TableBuilder::new();
     table.add_header(&["Name", "Address", "Transactions", "Last Tx"]);
 
@@ -83,8 +103,8 @@ pub async fn list_contacts() -> Result<()> {
             .unwrap_or_else(|| "Never".to_string());
 
         table.add_row(&[
-            &contact.name,
-            &format!("0x{:x}", contact.address),
+            &format!("{}{}", contact.name, contact.badge()),
+            &rns::with_name(format!("0x{:x}", contact.address), &contact.address, &names),
             &tx_info,
             &last_tx,
         ]);
@@ -102,34 +122,40 @@ pub async fn add_contact() -> Result<()> {
         .with_help_message("Enter a name for this contact")
         .prompt()?;
 
-    let address = Text::new("Ethereum address (0x...):")
-        .with_help_message("Enter the contact's Ethereum address")
-        .with_validator(|input: &str| {
-            if input.starts_with("0x") && input.len() == 42 {
-                Ok(Validation::Valid)
-            } else {
-                Ok(Validation::Invalid(
-                    "Please enter a valid Ethereum address (0x...)".into(),
-                ))
-            }
-        })
-        .prompt()?;
+    let entry = inquire::Select::new(
+        "How would you like to enter the address?",
+        vec!["📝 Type or paste it", "🖼️ Scan QR code from image"],
+    )
+    .raw_prompt()?;
+    let address = if entry.index == 1 {
+        let chain_id = ConfigManager::new()?.load()?.default_network.chain_id();
+        format!("0x{:x}", scan_payment_request(chain_id)?.recipient)
+    } else {
+        Text::new("Ethereum address (0x...) or RNS name:")
+            .with_help_message("Enter the contact's address or RNS name (e.g. alice.rsk)")
+            .with_validator(|input: &str| {
+                if (input.starts_with("0x") && input.len() == 42) || rns::is_name(input) {
+                    Ok(Validation::Valid)
+                } else {
+                    Ok(Validation::Invalid(
+                        "Please enter a valid address (0x...) or RNS name (name.rsk)".into(),
+                    ))
+                }
+            })
+            .prompt()?
+    };
 
     let notes = Text::new("Notes (optional):")
         .with_help_message("Add any notes about this contact")
         .prompt_skippable()?
         .filter(|s| !s.trim().is_empty());
 
-    let tags = Text::new("Tags (comma-separated, optional):")
-        .with_help_message("e.g., friend,team,client")
-        .prompt_skippable()?
-        .map(|s| {
-            s.split(',')
-                .map(|t| t.trim().to_string())
-                .filter(|t| !t.is_empty())
-                .collect()
-        })
-        .unwrap_or_default();
+    let tags = prompt_tags()?;
+
+    let memo_required = Confirm::new("Require a memo when sending to this contact?")
+        .with_help_message("Exchange deposit addresses often need a destination tag or memo")
+        .with_default(false)
+        .prompt()?;
 
     let cmd = ContactsCommand {
         action: ContactsAction::Add {
@@ -137,6 +163,8 @@ pub async fn add_contact() -> Result<()> {
             address,
             notes,
             tags,
+            memo_required,
+            groups: Vec::new(),
         },
     };
 
@@ -145,6 +173,70 @@ pub async fn add_contact() -> Result<()> {
     Ok(())
 }
 
+/// After a confirmed send to an address that is neither a contact nor one
+/// of your wallets, offer to save it, with the transfer as its first
+/// transaction
+pub(super) fn offer_to_save_recipient(result: &TransferResult) -> Result<()> {
+    if !result.succeeded() {
+        return Ok(());
+    }
+    let cmd = ContactsCommand {
+        action: ContactsAction::List,
+    };
+    let mut contacts = cmd.load_contacts()?;
+    if contacts.iter().any(|c| c.address == result.to)
+        || WalletData::load()?.find_account(result.to).is_some()
+    {
+        return Ok(());
+    }
+    if !Confirm::new(&format!(
+        "0x{:x} is not in your contacts. Save it as a contact?",
+        result.to
+    ))
+    .with_default(true)
+    .prompt()?
+    {
+        return Ok(());
+    }
+
+    let taken: Vec<String> = contacts.iter().map(|c| c.name.to_lowercase()).collect();
+    let name = Text::new("Contact name:")
+        .with_validator(move |input: &str| {
+            if input.trim().is_empty() {
+                Ok(Validation::Invalid("Please enter a name".into()))
+            } else if taken.contains(&input.trim().to_lowercase()) {
+                Ok(Validation::Invalid(
+                    "A contact with this name already exists".into(),
+                ))
+            } else {
+                Ok(Validation::Valid)
+            }
+        })
+        .prompt()?;
+    let tags = prompt_tags()?;
+
+    let mut contact = Contact::new(name.trim().to_string(), result.to, None, tags);
+    contact.update_transaction_stats(&result.transaction(), false);
+    contact.validate()?;
+    contacts.push(contact);
+    cmd.save_contacts(&contacts)?;
+    println!("✅ Contact saved with this transfer in its history.");
+    Ok(())
+}
+
+fn prompt_tags() -> Result<Vec<String>> {
+    Ok(Text::new("Tags (comma-separated, optional):")
+        .with_help_message("e.g., friend,team,client")
+        .prompt_skippable()?
+        .map(|s| {
+            s.split(',')
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
 /// Update an existing contact
 pub async fn update_contact() -> Result<()> {
     let contacts = ContactsCommand {
@@ -165,6 +257,10 @@ pub async fn update_contact() -> Result<()> {
     let selection = inquire::Select::new("Select contact to update:", contact_names).prompt()?;
 
     let contact_name = selection.split('(').next().unwrap_or("").trim();
+    let memo_was_required = contacts
+        .iter()
+        .find(|c| c.name == contact_name)
+        .is_some_and(|c| c.memo_required);
 
     let new_name = Text::new("New name (press Enter to keep current):")
         .with_help_message("Enter new name or press Enter to skip")
@@ -182,6 +278,10 @@ pub async fn update_contact() -> Result<()> {
         .with_help_message("e.g., friend,team,client")
         .prompt_skippable()?;
 
+    let memo_required = Confirm::new("Require a memo when sending to this contact?")
+        .with_default(memo_was_required)
+        .prompt_skippable()?;
+
     let cmd = ContactsCommand {
         action: ContactsAction::Update {
             identifier: contact_name.to_string(),
@@ -194,6 +294,7 @@ pub async fn update_contact() -> Result<()> {
                     .filter(|t| !t.is_empty())
                     .collect()
             }),
+            memo_required,
         },
     };
 
@@ -287,3 +388,179 @@ pub async fn search_contacts() -> Result<()> {
 
     Ok(())
 }
+
+/// Create contact groups and change their members, names or existence
+pub fn manage_groups() -> Result<()> {
+    let cmd = ContactsCommand {
+        action: ContactsAction::List,
+    };
+    loop {
+        let mut contacts = cmd.load_contacts()?;
+        if contacts.is_empty() {
+            println!("No contacts found. Add contacts before grouping them.");
+            return Ok(());
+        }
+        let existing = groups(&contacts);
+
+        println!("\n{}", style("🗂️  Contact Groups").bold());
+        if existing.is_empty() {
+            println!("No groups yet.");
+        } else {
+            let mut table = TableBuilder::new();
+            table.add_header(&["Group", "Members"]);
+            for (group, members) in &existing {
+                table.add_row(&[group, &members.to_string()]);
+            }
+            table.print();
+        }
+
+        let mut options = vec!["➕ Create group"];
+        if !existing.is_empty() {
+            options.extend(["👥 Edit members", "✏️  Rename group", "❌ Delete group"]);
+        }
+        options.push("🔙 Back");
+        let selection = inquire::Select::new("What would you like to do?", options).prompt()?;
+        let names: Vec<String> = existing.into_iter().map(|(group, _)| group).collect();
+
+        let (group, new_group) = match selection {
+            "➕ Create group" => {
+                let taken = names.clone();
+                let group = Text::new("Group name:")
+                    .with_validator(move |input: &str| {
+                        if input.trim().is_empty() {
+                            Ok(Validation::Invalid("Please enter a name".into()))
+                        } else if taken.iter().any(|g| g.eq_ignore_ascii_case(input.trim())) {
+                            Ok(Validation::Invalid("This group already exists".into()))
+                        } else {
+                            Ok(Validation::Valid)
+                        }
+                    })
+                    .prompt()?;
+                (group.trim().to_string(), true)
+            }
+            "👥 Edit members" => (
+                inquire::Select::new("Select group:", names).prompt()?,
+                false,
+            ),
+            "✏️  Rename group" => {
+                let group = inquire::Select::new("Select group:", names).prompt()?;
+                let new_name = Text::new("New name:").prompt()?;
+                cmd.manage_group(&GroupAction::Rename {
+                    group,
+                    new_name: new_name.trim().to_string(),
+                })?;
+                continue;
+            }
+            "❌ Delete group" => {
+                let group = inquire::Select::new("Select group:", names).prompt()?;
+                if Confirm::new(&format!(
+                    "Delete '{}'? Its members stay in your contacts.",
+                    group
+                ))
+                .with_default(false)
+                .prompt()?
+                {
+                    cmd.manage_group(&GroupAction::Delete { group })?;
+                }
+                continue;
+            }
+            _ => return Ok(()),
+        };
+
+        let labels: Vec<String> = contacts
+            .iter()
+            .map(|c| format!("{}{} (0x{:x})", c.name, c.badge(), c.address))
+            .collect();
+        let members: Vec<usize> = contacts
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.in_group(&group))
+            .map(|(index, _)| index)
+            .collect();
+        let chosen = MultiSelect::new(&format!("Members of '{}':", group), labels)
+            .with_default(&members)
+            .with_help_message("Space to select, Enter to confirm")
+            .raw_prompt()?;
+        if new_group && chosen.is_empty() {
+            println!("A group needs at least one member; nothing was created.");
+            continue;
+        }
+        for (index, contact) in contacts.iter_mut().enumerate() {
+            if chosen.iter().any(|option| option.index == index) {
+                contact.join_group(&group);
+            } else {
+                contact.leave_group(&group);
+            }
+        }
+        cmd.save_contacts(&contacts)?;
+        println!("✅ '{}' has {} member(s)", group, chosen.len());
+    }
+}
+
+/// Verify a contact by having them sign a challenge with their key
+pub async fn verify_contact() -> Result<()> {
+    let cmd = ContactsCommand {
+        action: ContactsAction::List,
+    };
+    let contacts = cmd.load_contacts()?;
+
+    if contacts.is_empty() {
+        println!("No contacts found to verify.");
+        return Ok(());
+    }
+
+    let contact_names: Vec<String> = contacts
+        .iter()
+        .map(|c| format!("{}{} ({})", c.name, c.badge(), c.address))
+        .collect();
+
+    let selection = inquire::Select::new("Select contact to verify:", contact_names).prompt()?;
+    let index = contacts
+        .iter()
+        .position(|c| selection.ends_with(&format!("({})", c.address)))
+        .unwrap_or(0);
+    let contact = &contacts[index];
+
+    if contact.is_verified()
+        && !Confirm::new(&format!(
+            "{} is already verified. Start a new handshake?",
+            contact.name
+        ))
+        .with_default(false)
+        .prompt()?
+    {
+        return Ok(());
+    }
+
+    let challenge = match &contact.pending_challenge {
+        Some(challenge)
+            if Confirm::new("A challenge was already issued. Reuse it?")
+                .with_default(true)
+                .prompt()? =>
+        {
+            println!("\n{}\n", challenge);
+            challenge.clone()
+        }
+        _ => cmd.issue_challenge(&contact.name).await?,
+    };
+
+    println!(
+        "{}",
+        style("Send the message above to the contact. They can sign it with any wallet that supports personal_sign.").dim()
+    );
+
+    let Some(signature) = Text::new("Signature (0x...):")
+        .with_help_message("Press Esc to finish later; the challenge stays pending")
+        .prompt_skippable()?
+        .filter(|s| !s.trim().is_empty())
+    else {
+        println!("Verification pending. Challenge:\n{}", challenge);
+        return Ok(());
+    };
+
+    if let Err(e) = cmd.verify_contact(&contact.name, &signature).await {
+        println!("{}: {}", style("❌ Verification failed").red().bold(), e);
+    }
+
+    Ok(())
+}
@@ -1,154 +1,278 @@
-use crate::{
-    config::ConfigManager,
-    types::network::Network,
-    wallet::load_wallet,
-};
-use anyhow::{anyhow, Result};
-use dialoguer::{Confirm, Input, Select};
-use alloy::{
-    primitives::{Address, U256},
-    providers::{Provider, ProviderBuilder, RootProvider},
-    signers::local::PrivateKeySigner,
-    transports::http::{Client, Http},
-    sol,
-};
-use std::sync::Arc;
-use std::str::FromStr;
+use crate::commands::address::parse_address;
+use crate::commands::contract::format_value;
+use crate::commands::pending::read_client;
+use crate::config::ConfigManager;
+use crate::types::network::NetworkService;
+use crate::types::wallet::WalletData;
+use crate::utils::abi_registry::{self, AbiRegistry, RegisteredContract};
+use crate::utils::eth::{EthClient, get_explorer_url};
+use crate::utils::helper::{Config as HelperConfig, WalletConfig, unlock_wallet};
+use crate::utils::roles::{self, Capability};
+use alloy::dyn_abi::{DynSolType, FunctionExt, JsonAbiExt};
+use alloy::json_abi::{Function, JsonAbi, StateMutability};
+use alloy::network::TransactionBuilder;
+use alloy::primitives::utils::parse_ether;
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionRequest;
+use anyhow::{Result, anyhow};
+use console::style;
+use std::path::PathBuf;
 
-/// Interactive menu for interacting with smart contracts
-pub async fn interact_with_contract() -> Result<()> {
-    println!("\n📝 Smart Contract Interaction");
-    println!("========================");
+const OTHER: &str = "🔍 Other Contract (address and ABI file)";
+const REGISTER: &str = "➕ Register Contract";
+const REMOVE: &str = "🗑️ Remove Registered Contract";
+const BACK: &str = "🏠 Back to Main Menu";
 
-    // Load wallet
-    let wallet_data = match load_wallet()? {
-        Some(w) => w,
-        None => return Err(anyhow!("No wallet found. Please create a wallet first.")),
-    };
-    
-    // Load config
-    let config_manager = ConfigManager::new()?;
-    let config = config_manager.load()?;
-    
-    // Get the network configuration
-    let network_config = config.default_network.get_config();
-    
-    // Get the chain ID based on the network
-    let chain_id = match config.default_network {
-        Network::RootStockMainnet => 30,
-        Network::RootStockTestnet => 31,
-        Network::Mainnet => 1,
-        Network::Testnet => 5, // Goerli
-        Network::Regtest => 1337,
-        _ => return Err(anyhow!("Unsupported network for contract interaction")),
-    };
-    
-    // Create a wallet with the chain ID
-    let private_key = wallet_data.private_key
-        .as_ref()
-        .ok_or_else(|| anyhow!("No private key found in wallet"))?;
-    
-    let wallet = private_key
-        .parse::<PrivateKeySigner>()
-        .map_err(|e| anyhow!("Failed to parse private key: {}", e))?;
-    
-    // Create provider with signer
-    let provider = ProviderBuilder::new()
-        .with_recommended_fillers()
-        .wallet(wallet)
-        .on_http(network_config.rpc_url.parse()?)
-        .map_err(|e| anyhow!("Failed to connect to RPC: {}", e))?;
-    
-    // Get contract address
-    let contract_address: String = Input::new()
-        .with_prompt("Enter contract address (0x...)")
-        .validate_with(|input: &String| {
-            if input.starts_with("0x") && input.len() == 42 {
-                Ok(())
-            } else {
-                Err("Please enter a valid contract address starting with 0x".to_string())
+/// Interactive menu for calling smart contracts, registered ones by name
+pub async fn contract_menu() -> Result<()> {
+    loop {
+        let config = ConfigManager::new()?.load()?;
+        let network = network_name(&config.default_network);
+        let registry = AbiRegistry::load()?;
+
+        let mut options: Vec<String> = registry
+            .list(network)
+            .iter()
+            .map(|contract| format!("📝 {} (0x{:x})", contract.name, contract.address))
+            .collect();
+        options.extend([OTHER, REGISTER, REMOVE, BACK].map(String::from));
+
+        let selection = inquire::Select::new(
+            &format!("Smart Contracts ({})", config.default_network),
+            options,
+        )
+        .prompt()
+        .map_err(|_| anyhow!("Failed to get selection"))?;
+
+        let result = match selection.as_str() {
+            OTHER => match prompt_contract(config.default_network.chain_id()) {
+                Ok((address, abi)) => interact(address, &abi).await,
+                Err(e) => Err(e),
+            },
+            REGISTER => register_contract(network, config.default_network.chain_id()),
+            REMOVE => remove_contract(network),
+            BACK => break,
+            _ => {
+                // "📝 <name> (0x...)"
+                let name = selection
+                    .trim_start_matches("📝 ")
+                    .rsplit_once(" (")
+                    .map_or(selection.as_str(), |(name, _)| name);
+                match registry.get(network, name) {
+                    Some(contract) => interact(contract.address, &contract.abi).await,
+                    None => Err(anyhow!("Unknown contract '{}'", name)),
+                }
             }
-        })
-        .interact()?;
-    
-    let contract_address = contract_address.parse::<Address>()
-        .map_err(|e| anyhow!("Invalid contract address: {}", e))?;
-    
-    // Get ABI file path
-    let abi_path: String = Input::new()
-        .with_prompt("Enter path to ABI JSON file")
-        .interact()?;
-    
-    // Read and parse ABI
-    let abi_content = std::fs::read_to_string(&abi_path)
-        .map_err(|e| anyhow!("Failed to read ABI file: {}", e))?;
-    
-    let abi: Abi = serde_json::from_str(&abi_content)
-        .map_err(|e| anyhow!("Failed to parse ABI: {}", e))?;
-    
-    println!("\n📋 Available functions:");
-    for (i, function) in abi.functions().enumerate() {
-        println!("{:2}. {}", i + 1, function.signature());
+        };
+        if let Err(e) = result {
+            eprintln!("\n{} {}", style("❌").red(), style(e).bold());
+        }
     }
-    
-    // Select function
-    let function_index: usize = Input::new()
-        .with_prompt("Select function to call")
-        .default(0)
-        .interact()?;
-    
-    let selected_function = abi.functions().nth(function_index)
-        .ok_or_else(|| anyhow!("Invalid function index"))?;
-    
-    println!("\n🔧 Function: {}", selected_function.signature());
-    
-    // TODO: Add parameter input and function call logic
-    
     Ok(())
 }
 
-// Helper function to load wallet
-fn load_wallet() -> Result<PrivateKeySigner> {
-    // TODO: Implement wallet loading logic
-    // This is a placeholder - replace with actual wallet loading logic
-    let private_key = "0x...".to_string();
-    
-    private_key.parse::<PrivateKeySigner>()
-        .map_err(|e| anyhow!("Failed to parse private key: {}", e))
+/// Registry key for the network: contracts on mainnet or on a test network
+fn network_name(network: &crate::types::network::Network) -> &'static str {
+    if network.is_testnet() {
+        "testnet"
+    } else {
+        "mainnet"
+    }
 }
 
-// Helper function to load config
-fn load_config() -> Result<Config> {
-    // TODO: Implement config loading logic
-    // This is a placeholder - replace with actual config loading logic
-    Ok(Config::default())
+fn prompt_contract(chain_id: u64) -> Result<(Address, JsonAbi)> {
+    let address = inquire::Text::new("Contract address (0x...):").prompt()?;
+    let address = parse_address(address.trim(), chain_id)?;
+    let path = inquire::Text::new("Path to the ABI JSON file:")
+        .with_help_message("The ABI itself, or a build artifact with an 'abi' field")
+        .prompt()?;
+    let abi = abi_registry::read_abi_file(&PathBuf::from(path.trim()))?;
+    Ok((address, abi))
 }
 
-#[derive(Default)]
-struct Config {
-    default_network: Network,
-}
+fn register_contract(network: &str, chain_id: u64) -> Result<()> {
+    println!("\n{}", style("➕ Register Contract").bold());
+    println!("{}", "=".repeat(30));
+
+    let name = inquire::Text::new("Name:")
+        .with_help_message("Used to pick the contract in this menu")
+        .prompt()?;
+    let (address, abi) = prompt_contract(chain_id)?;
+    let functions = abi.functions().count();
 
-#[derive(Default)]
-enum Network {
-    #[default]
-    Mainnet,
-    Testnet,
+    let mut registry = AbiRegistry::load()?;
+    registry.add(
+        network,
+        RegisteredContract {
+            name: name.clone(),
+            address,
+            abi,
+        },
+    )?;
+    registry.save()?;
+    println!(
+        "\n{} Registered {} on {} ({} functions)",
+        style("✅").green(),
+        style(name.trim()).bold(),
+        network,
+        functions
+    );
+    Ok(())
 }
 
-impl Network {
-    fn get_config(&self) -> NetworkConfig {
-        match self {
-            Network::Mainnet => NetworkConfig {
-                rpc_url: "https://public-node.rsk.co".to_string(),
-            },
-            Network::Testnet => NetworkConfig {
-                rpc_url: "https://public-node.testnet.rsk.co".to_string(),
-            },
-        }
+fn remove_contract(network: &str) -> Result<()> {
+    let mut registry = AbiRegistry::load()?;
+    let names: Vec<String> = registry
+        .list(network)
+        .iter()
+        .map(|contract| contract.name.clone())
+        .collect();
+    if names.is_empty() {
+        println!("\nNo contracts are registered on {}.", network);
+        return Ok(());
+    }
+    let name = inquire::Select::new("Contract to remove:", names).prompt()?;
+    if !inquire::Confirm::new(&format!("Remove {} from the registry?", name))
+        .with_default(false)
+        .prompt()?
+    {
+        return Ok(());
     }
+    registry.remove(network, &name)?;
+    registry.save()?;
+    println!("\n{} Removed {}", style("✅").green(), name);
+    Ok(())
 }
 
-struct NetworkConfig {
-    rpc_url: String,
+/// Pick a function of the contract, ask for its arguments and call it: view
+/// and pure functions are read with `eth_call`, others are sent as a
+/// transaction from the current wallet
+async fn interact(address: Address, abi: &JsonAbi) -> Result<()> {
+    let functions: Vec<&Function> = abi.functions().collect();
+    if functions.is_empty() {
+        return Err(anyhow!("The ABI has no functions"));
+    }
+    let labels: Vec<String> = functions
+        .iter()
+        .map(|function| {
+            let kind = match function.state_mutability {
+                StateMutability::View | StateMutability::Pure => "read",
+                StateMutability::Payable => "write, payable",
+                StateMutability::NonPayable => "write",
+            };
+            format!("{} [{}]", function.signature(), kind)
+        })
+        .collect();
+    let selected = inquire::Select::new("Function to call:", labels)
+        .raw_prompt()?
+        .index;
+    let function = functions[selected];
+
+    let mut args = Vec::with_capacity(function.inputs.len());
+    for param in &function.inputs {
+        let ty = DynSolType::parse(&param.selector_type())?;
+        let name = if param.name.is_empty() {
+            format!("Argument {}", args.len() + 1)
+        } else {
+            param.name.clone()
+        };
+        let input = inquire::Text::new(&format!("{} ({}):", name, param.selector_type()))
+            .with_help_message("Arrays and tuples as [a, b] and (a, b)")
+            .prompt()?;
+        let value = ty
+            .coerce_str(input.trim())
+            .map_err(|e| anyhow!("Invalid {}: {}", name, e))?;
+        args.push(value);
+    }
+    let input = function
+        .abi_encode_input(&args)
+        .map_err(|e| anyhow!("Failed to encode the call: {}", e))?;
+
+    match function.state_mutability {
+        StateMutability::View | StateMutability::Pure => {
+            let client = read_client().await?;
+            let tx = TransactionRequest::default()
+                .with_to(address)
+                .with_input(input);
+            let output = client
+                .provider()
+                .call(&tx)
+                .await
+                .map_err(|e| anyhow!("Call failed: {}", e))?;
+            let values = function
+                .abi_decode_output(&output, true)
+                .map_err(|e| anyhow!("Failed to decode the result: {}", e))?;
+            println!("\n{}", style("📋 Result").bold());
+            if values.is_empty() {
+                println!("  (no return value)");
+            }
+            for (param, value) in function.outputs.iter().zip(&values) {
+                let name = if param.name.is_empty() {
+                    param.selector_type().to_string()
+                } else {
+                    format!("{} ({})", param.name, param.selector_type())
+                };
+                println!("  {}: {}", name, format_value(value));
+            }
+            Ok(())
+        }
+        StateMutability::Payable | StateMutability::NonPayable => {
+            roles::require(Capability::Transfer)?;
+            let value = if function.state_mutability == StateMutability::Payable {
+                let amount = inquire::Text::new("RBTC to send with the call:")
+                    .with_default("0")
+                    .prompt()?;
+                parse_ether(amount.trim()).map_err(|e| anyhow!("Invalid amount: {}", e))?
+            } else {
+                U256::ZERO
+            };
+            if !inquire::Confirm::new(&format!(
+                "Send {} to 0x{:x}?",
+                function.signature(),
+                address
+            ))
+            .with_default(false)
+            .prompt()?
+            {
+                println!("Call cancelled.");
+                return Ok(());
+            }
+
+            let config = ConfigManager::new()?.load()?;
+            let wallet_data = WalletData::load()?;
+            let wallet = wallet_data
+                .get_current_wallet()
+                .ok_or_else(|| anyhow!("No active wallet found. Please select a wallet first."))?;
+            let private_key = unlock_wallet(wallet, 0, "Enter password for the wallet: ")?;
+            let client = EthClient::new(
+                &HelperConfig {
+                    network: config.default_network.get_config(),
+                    wallet: WalletConfig {
+                        current_wallet_address: None,
+                        private_key: Some(private_key),
+                        mnemonic: None,
+                    },
+                },
+                None,
+            )
+            .await?;
+            let tx_hash = client.send_call(address, value, input.into()).await?;
+            println!(
+                "\n{} Transaction sent: 0x{:x}",
+                style("✅").green(),
+                tx_hash
+            );
+            if config.default_network.supports(NetworkService::Explorer) {
+                println!(
+                    "{}",
+                    get_explorer_url(
+                        &format!("0x{:x}", tx_hash),
+                        config.default_network.is_testnet()
+                    )
+                );
+            }
+            Ok(())
+        }
+    }
 }
@@ -0,0 +1,106 @@
+use crate::{
+    commands::{tokens::TokenRegistry, watch},
+    config::ConfigManager,
+    utils::{events, profiles},
+};
+use alloy::json_abi::JsonAbi;
+use alloy::primitives::Address;
+use anyhow::{Result, anyhow};
+use console::style;
+use inquire::{CustomType, MultiSelect, Select, Text, validator::Validation};
+use std::str::FromStr;
+
+const ENTER_ADDRESS: &str = "📝 Enter a contract address";
+
+/// Pick a contract and some of its events, then stream them live
+pub async fn watch_contract_events() -> Result<()> {
+    println!("\n{}", style("👀 Watch Contract Events").bold());
+    println!("{}", "=".repeat(30));
+
+    let config = ConfigManager::new()?.load()?;
+    let network = config.default_network.chain_name();
+
+    // Known contracts: curated protocol profiles and registered tokens
+    let mut contracts: Vec<(String, Address, JsonAbi)> = Vec::new();
+    for profile in profiles::profiles_for_network(network) {
+        if let Some(abi) = profile
+            .abi_json()
+            .and_then(|json| events::parse_abi(json).ok())
+        {
+            contracts.push((
+                format!("{} ({})", profile.name, profile.protocol),
+                profile.address,
+                abi,
+            ));
+        }
+    }
+    let registry = TokenRegistry::load().unwrap_or_default();
+    for (symbol, info) in registry.list_tokens(Some(network)) {
+        if let Ok(address) = Address::from_str(&info.address)
+            && !contracts.iter().any(|(_, a, _)| *a == address)
+        {
+            contracts.push((symbol, address, events::parse_abi(events::ERC20_ABI)?));
+        }
+    }
+    // Contracts whose ABI declares no events cannot be watched
+    contracts.retain(|(_, _, abi)| abi.events().next().is_some());
+
+    let mut options: Vec<String> = contracts
+        .iter()
+        .map(|(name, address, _)| format!("{} 0x{:x}", name, address))
+        .collect();
+    options.push(ENTER_ADDRESS.to_string());
+    let selection = Select::new("Contract to watch:", options.clone()).prompt()?;
+
+    let (address, abi) = match options.iter().position(|o| o == &selection) {
+        Some(i) if i < contracts.len() => {
+            let (_, address, abi) = contracts.swap_remove(i);
+            (address, abi)
+        }
+        _ => {
+            let address = Text::new("Contract address (0x...):")
+                .with_validator(|input: &str| {
+                    if Address::from_str(input.trim()).is_ok() {
+                        Ok(Validation::Valid)
+                    } else {
+                        Ok(Validation::Invalid("Invalid address".into()))
+                    }
+                })
+                .prompt()?;
+            let source = Text::new("ABI JSON file:")
+                .with_help_message("Type erc20 to use the standard token ABI")
+                .prompt()?;
+            (
+                Address::from_str(address.trim())?,
+                events::load_abi(source.trim())?,
+            )
+        }
+    };
+
+    let all_events = events::select_events(&abi, &[])?;
+    let signatures: Vec<String> = all_events.iter().map(|e| e.signature()).collect();
+    let chosen = MultiSelect::new("Events to watch:", signatures)
+        .with_help_message("Space to select, Enter to confirm; none selected watches all")
+        .prompt()?;
+    let watched = if chosen.is_empty() {
+        all_events
+    } else {
+        events::select_events(&abi, &chosen)?
+    };
+
+    let from_block = CustomType::<u64>::new("Start from block:")
+        .with_help_message("Leave empty to watch new blocks only")
+        .prompt_skippable()?;
+    let webhook = Text::new("Forward events to webhook URL:")
+        .with_help_message("Leave empty to only show events here")
+        .prompt_skippable()?
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty());
+    if let Some(url) = &webhook
+        && url::Url::parse(url).is_err()
+    {
+        return Err(anyhow!("Invalid webhook URL: {}", url));
+    }
+
+    watch::watch_events(address, &watched, from_block, webhook.as_deref()).await
+}
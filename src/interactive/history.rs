@@ -1,40 +1,20 @@
+use crate::api::ApiProvider;
+use crate::commands::dashboard::DashboardCommand;
 use crate::commands::history::HistoryCommand;
+use crate::commands::stats::StatsCommand;
 use crate::commands::tokens::{TokenRegistry, list_tokens};
-use crate::config::ConfigManager;
+use crate::config::{Config, ConfigManager};
+use crate::utils::history_export::ExportFormat;
 use anyhow::{Context, Result};
 use console::style;
 use inquire::{Confirm, Select, Text, validator::Validation};
 
-/// Shows the transaction history in an interactive way
-pub async fn show_history() -> Result<()> {
-    println!("\n{}", style("📜 Transaction History").bold());
-    println!("{}", "=".repeat(30));
-
-    // Load config and get current network
-    let config_manager = ConfigManager::new()?;
-    let config = config_manager.load()?;
-
-    // Network selection
-    let network_options = vec!["mainnet", "testnet"];
-    let network_selection = Select::new("Select network:", network_options)
-        .with_starting_cursor(
-            if config
-                .default_network
-                .to_string()
-                .to_lowercase()
-                .contains("testnet")
-            {
-                1
-            } else {
-                0
-            },
-        )
-        .prompt()?;
-
-    // Default values for the history command
-    let mut command = HistoryCommand {
+/// History of the selected account on `network`, without filters
+fn default_command(config: &Config, network: &str, account: Option<u32>) -> HistoryCommand {
+    HistoryCommand {
         address: None,
         contact: None,
+        account,
         limit: 10,
         detailed: false,
         status: None,
@@ -46,20 +26,82 @@ pub async fn show_history() -> Result<()> {
         incoming: false,
         outgoing: false,
         export_csv: None,
-        api_key: match network_selection {
+        export_beancount: None,
+        export_ledger: None,
+        export: None,
+        output: None,
+        api_key: match network {
             "mainnet" => config.alchemy_mainnet_key.clone(),
             "testnet" => config.alchemy_testnet_key.clone(),
             _ => None,
         },
-        network: network_selection.to_string(),
+        provider: config.api.default_provider.clone(),
+        network: network.to_string(),
+        resync: false,
+    }
+}
+
+/// Ask for a format and file name and export the history `command` selects
+async fn export(command: &HistoryCommand) -> Result<()> {
+    let format = Select::new("Export format:", vec!["csv", "json"]).prompt()?;
+    let filename = Text::new("Enter filename to save:")
+        .with_default(&format!("transactions.{}", format))
+        .with_help_message(
+            "Includes token symbols, decimal amounts, USD values (when available) and status",
+        )
+        .prompt()?;
+
+    let mut export_cmd = command.clone();
+    export_cmd.export = ExportFormat::from_name(format);
+    export_cmd.output = Some(filename);
+
+    if let Err(e) = export_cmd.execute().await {
+        eprintln!("Error exporting transactions: {}", e);
+    }
+    Ok(())
+}
+
+/// Export the history of the default network straight away, without
+/// showing it first
+pub async fn export_history() -> Result<()> {
+    let config = ConfigManager::new()?.load()?;
+    let network = if config.default_network.is_testnet() {
+        "testnet"
+    } else {
+        "mainnet"
     };
+    let account = super::wallet::select_account()?;
+    let mut command = default_command(&config, network, account);
+    // The export covers more than the first page shown in the history view
+    command.limit = 1000;
+    export(&command).await
+}
+
+/// Shows the transaction history in an interactive way
+pub async fn show_history() -> Result<()> {
+    println!("\n{}", style("📜 Transaction History").bold());
+    println!("{}", "=".repeat(30));
+
+    // Load config and get current network
+    let config_manager = ConfigManager::new()?;
+    let config = config_manager.load()?;
+
+    // Network selection
+    let network_options = vec!["mainnet", "testnet"];
+    let network_selection = Select::new("Select network:", network_options)
+        .with_starting_cursor(if config.default_network.is_testnet() { 1 } else { 0 })
+        .prompt()?;
+
+    // Default values for the history command
+    let account = super::wallet::select_account()?;
+    let mut command = default_command(&config, network_selection, account);
 
     // Load available tokens for the selected network
     let registry = TokenRegistry::load()
         .map_err(|e| anyhow::anyhow!("Failed to load token registry: {}", e))?;
-    let tokens = registry.list_tokens(Some(network_selection));
+    let mut tokens = registry.list_tokens(Some(network_selection));
     let mut token_options = vec!["RBTC (Native)".to_string()];
-    token_options.extend(tokens.into_iter().map(|(symbol, _info)| symbol));
+    token_options.extend(tokens.iter().map(|(label, _info)| label.clone()));
 
     // Main history menu loop
     loop {
@@ -86,15 +128,13 @@ pub async fn show_history() -> Result<()> {
         println!("Limit: {} transactions", command.limit);
         println!("{}", "-".repeat(40));
 
-        // Check if we have an API key, prompt if not
-        if command.api_key.is_none() {
-            println!(
-                "\n{}",
-                style("⚠️  Alchemy API Key Required").yellow().bold()
-            );
-            println!("Transaction history requires an Alchemy API key.");
+        // Offer to add an Alchemy key unless another provider was chosen;
+        // without one, history comes from the public Blockscout API
+        if command.api_key.is_none() && command.provider.is_none() {
+            println!("\n{}", style("ℹ️  No Alchemy API key").yellow().bold());
+            println!("History can come from Alchemy or from the public Blockscout API.");
 
-            let should_add_key = Confirm::new("Would you like to add an API key now?")
+            let should_add_key = Confirm::new("Would you like to add an Alchemy API key now?")
                 .with_default(true)
                 .prompt()
                 .unwrap_or(false);
@@ -116,21 +156,13 @@ pub async fn show_history() -> Result<()> {
 
                     println!("\n{}", style("✅ API key saved successfully!").green());
                     command.api_key = Some(api_key.trim().to_string());
-                } else {
-                    println!(
-                        "\n{}",
-                        style("❌ No API key provided. Cannot fetch transaction history.").red()
-                    );
-                    println!("You can add an API key later from the Configuration menu.");
-                    return Ok(());
                 }
-            } else {
+            }
+            if command.api_key.is_none() {
                 println!(
-                    "\n{}",
-                    style("⚠️  Transaction history requires an API key.").yellow()
+                    "Using Blockscout. You can add an API key later from the Configuration menu."
                 );
-                println!("You can add an API key later from the Configuration menu.");
-                return Ok(());
+                command.provider = Some(ApiProvider::Blockscout);
             }
         }
 
@@ -154,7 +186,8 @@ pub async fn show_history() -> Result<()> {
 
         // Show options for further actions
         let options = vec![
-            "Export to CSV",
+            "Export (CSV/JSON)",
+            "Export to beancount/ledger",
             "Change network",
             "Change token",
             "Change limit",
@@ -163,6 +196,8 @@ pub async fn show_history() -> Result<()> {
             "Toggle detailed view",
             "Clear all filters",
             "Filter by date range",
+            "Activity statistics",
+            "Export public dashboard (HTML)",
             "Back to main menu",
         ];
 
@@ -178,10 +213,11 @@ pub async fn show_history() -> Result<()> {
                     command.network = network.to_string();
                     // Reload tokens for the new network
                     match list_tokens(Some(&command.network)) {
-                        Ok(tokens) => {
+                        Ok(network_tokens) => {
                             token_options = std::iter::once("RBTC (Native)".to_string())
-                                .chain(tokens.into_iter().map(|(symbol, _info)| symbol))
+                                .chain(network_tokens.iter().map(|(label, _info)| label.clone()))
                                 .collect();
+                            tokens = network_tokens;
                         }
                         Err(e) => {
                             eprintln!(
@@ -189,17 +225,18 @@ pub async fn show_history() -> Result<()> {
                                 e
                             );
                             token_options = vec!["RBTC (Native)".to_string()];
+                            tokens.clear();
                         }
                     }
                 }
             }
             "Change token" => {
                 let token = Select::new("Select token:", token_options.clone()).prompt()?;
-                command.token = if token == "RBTC (Native)" {
-                    None
-                } else {
-                    Some(token.split_whitespace().next().unwrap_or("").to_string())
-                };
+                // The filter is by address, since several tokens may share a symbol
+                command.token = tokens
+                    .iter()
+                    .find(|(label, _info)| *label == token)
+                    .map(|(_, info)| info.address.clone());
             }
             "Change limit" => {
                 let limit = Text::new("Enter number of transactions to show (1-100):")
@@ -240,24 +277,32 @@ pub async fn show_history() -> Result<()> {
                     }
                 }
             }
-            "Export to CSV" => {
-                let filename = Text::new("Enter filename to save (e.g., transactions.csv):")
-                    .with_default("transactions.csv")
-                    .with_validator(|input: &str| {
-                        if input.ends_with(".csv") {
-                            Ok(Validation::Valid)
-                        } else {
-                            Ok(Validation::Invalid("Filename must end with .csv".into()))
-                        }
-                    })
+            "Export (CSV/JSON)" => {
+                export(&command).await?;
+                continue;
+            }
+            "Export to beancount/ledger" => {
+                let formats = vec!["beancount", "ledger"];
+                let format = Select::new("Journal format:", formats).prompt()?;
+                let default_name = if format == "beancount" {
+                    "transactions.beancount"
+                } else {
+                    "transactions.ledger"
+                };
+                let filename = Text::new("Enter filename to save:")
+                    .with_default(default_name)
+                    .with_help_message("Accounts can be changed under Configuration → Accounting")
                     .prompt()?;
 
                 let mut export_cmd = command.clone();
-                export_cmd.export_csv = Some(filename);
+                if format == "beancount" {
+                    export_cmd.export_beancount = Some(filename);
+                } else {
+                    export_cmd.export_ledger = Some(filename);
+                }
 
-                match export_cmd.execute().await {
-                    Ok(_) => {}
-                    Err(e) => eprintln!("Error exporting to CSV: {}", e),
+                if let Err(e) = export_cmd.execute().await {
+                    eprintln!("Error exporting journal: {}", e);
                 }
 
                 continue;
@@ -288,6 +333,39 @@ pub async fn show_history() -> Result<()> {
                 command.from = from.and_then(|s| if s.is_empty() { None } else { Some(s) });
                 command.to = to.and_then(|s| if s.is_empty() { None } else { Some(s) });
             }
+            "Activity statistics" => {
+                let stats_cmd = StatsCommand {
+                    address: command.address.clone(),
+                    top: 5,
+                };
+                if let Err(e) = stats_cmd.execute().await {
+                    eprintln!("Error computing statistics: {}", e);
+                }
+            }
+            "Export public dashboard (HTML)" => {
+                let wallets = Text::new("Wallet names (comma-separated, leave empty for all):")
+                    .prompt_skippable()?
+                    .map(|s| {
+                        s.split(',')
+                            .map(|w| w.trim().to_string())
+                            .filter(|w| !w.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let output = Text::new("Output file:")
+                    .with_default("dashboard.html")
+                    .prompt()?;
+                let dashboard_cmd = DashboardCommand {
+                    wallets,
+                    output: output.into(),
+                    limit: command.limit as usize,
+                    network: command.network.clone(),
+                    interval: None,
+                };
+                if let Err(e) = dashboard_cmd.execute().await {
+                    eprintln!("Error exporting dashboard: {}", e);
+                }
+            }
             "Back to main menu" => break,
             _ => {}
         }
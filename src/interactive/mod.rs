@@ -4,25 +4,32 @@ mod balance;
 mod bulk_transfer;
 mod config;
 mod contacts;
+mod contract;
+mod events;
 mod history;
+mod palette;
+mod queue;
+mod recovery_drill;
+mod scan;
 mod system;
 mod tokens;
 mod transfer;
-mod transfer_preview;
+pub(crate) mod transfer_preview;
 mod tx;
 mod wallet;
 
-use crate::utils::constants;
+use crate::security::session;
+use crate::utils::{roles, watchdog};
 use anyhow::Result;
 use console::style;
-use dialoguer::{Select, theme::ColorfulTheme};
+use dialoguer::{FuzzySelect, theme::ColorfulTheme};
 
 // Re-export public functions
 pub use self::{
-    balance::show_balance, bulk_transfer::bulk_transfer, config::show_config_menu,
-    contacts::manage_contacts, history::show_history, system::system_menu, tokens::token_menu,
-    transfer::send_funds, tx::check_transaction_status, wallet::create_wallet_with_name,
-    wallet::wallet_menu,
+    balance::show_balance, balance::show_portfolio, bulk_transfer::bulk_transfer, config::select_profile, config::show_config_menu,
+    contacts::manage_contacts, contract::contract_menu, history::show_history, queue::queue_menu, system::system_menu,
+    tokens::token_menu, transfer::send_funds, tx::transaction_menu,
+    wallet::create_wallet_with_name, wallet::wallet_menu,
 };
 
 // Import for network status display
@@ -35,15 +42,14 @@ use crate::types::network::Network;
 pub use crate::types::network::Network as ConfigNetwork;
 
 // Helper function to get styled network status
-fn get_network_status(network: Network) -> console::StyledObject<&'static str> {
+fn get_network_status(network: Network) -> console::StyledObject<String> {
+    let label = format!("🔗 {}", network);
     match network {
-        Network::Mainnet => style("🔗 Mainnet").cyan(),
-        Network::Testnet => style("🔗 Testnet").yellow(),
-        Network::Regtest => style("🔗 Regtest").magenta(),
-        Network::AlchemyMainnet => style("🔗 Alchemy Mainnet").blue(),
-        Network::AlchemyTestnet => style("🔗 Alchemy Testnet").blue(),
-        Network::RootStockMainnet => style("🔗 Rootstock Mainnet").green(),
-        Network::RootStockTestnet => style("🔗 Rootstock Testnet").green(),
+        Network::Mainnet => style(label).cyan(),
+        Network::Testnet => style(label).yellow(),
+        Network::Regtest | Network::Custom(_) => style(label).magenta(),
+        Network::AlchemyMainnet | Network::AlchemyTestnet => style(label).blue(),
+        Network::RootStockMainnet | Network::RootStockTestnet => style(label).green(),
     }
 }
 
@@ -69,22 +75,23 @@ pub async fn start() -> Result<()> {
 
     println!("  {}", style("🟢 Online").green());
     println!("  {}", get_network_status(config.default_network));
+    if crate::config::profile::active() != crate::config::profile::DEFAULT {
+        println!(
+            "  {}",
+            style(format!("🗂️  Profile: {}", crate::config::profile::active())).cyan()
+        );
+    }
+    if let Some(session) = roles::current_session()? {
+        println!(
+            "  {}",
+            style(format!("👤 {} ({})", session.name, session.role)).cyan()
+        );
+    }
 
     // Check if wallet data file exists and count wallets
-    let wallet_file = constants::wallet_file_path();
-    let wallet_count = if wallet_file.exists() {
-        match std::fs::read_to_string(&wallet_file) {
-            Ok(contents) => {
-                match serde_json::from_str::<crate::types::wallet::WalletData>(&contents) {
-                    Ok(wallet_data) => wallet_data.wallets.len(),
-                    Err(_) => 0,
-                }
-            }
-            Err(_) => 0,
-        }
-    } else {
-        0
-    };
+    let wallet_count = crate::types::wallet::WalletData::load()
+        .map(|wallet_data| wallet_data.wallets.len())
+        .unwrap_or(0);
 
     let wallet_text = match wallet_count {
         0 => "💼 No wallets loaded".to_string(),
@@ -93,39 +100,81 @@ pub async fn start() -> Result<()> {
     };
     println!("  {}\n", style(wallet_text).dim());
 
+    // Saves ask before overwriting wallets or contacts changed elsewhere
+    if let Err(e) = watchdog::arm() {
+        log::warn!("Failed to watch the wallet database for outside changes: {}", e);
+    }
+
     loop {
+        match watchdog::reload() {
+            Ok(true) => println!(
+                "\n{}",
+                style("ℹ️  Wallets or contacts were changed by another program; showing the latest data.")
+                    .yellow()
+            ),
+            Ok(false) => {}
+            Err(e) => log::warn!("Failed to check the wallet database for outside changes: {}", e),
+        }
+
+        // Plain labels: the fuzzy search matches and highlights them as typed,
+        // and typing `/` jumps to the command palette
         let options = vec![
-            format!("{}  Check Balance", style("💰").bold().green()),
-            format!("{}  Send Funds", style("💸").bold().yellow()),
-            format!("{}  Bulk Transfer", style("📤").bold().yellow()),
-            format!("{}  Check Transaction Status", style("🔍").bold().cyan()),
-            format!("{}  Transaction History", style("📜").bold().cyan()),
-            format!("{}  Wallet Management", style("🔑").bold().blue()),
-            format!("{}  Token Management", style("🪙").bold().magenta()),
-            format!("{}  Contact Management", style("📇").bold().cyan()),
-            format!("{}  Configuration", style("⚙️").bold().white()),
-            format!("{}  System", style("💻").bold().cyan()),
-            format!("{}  Exit", style("🚪").bold().red()),
+            palette::MENU_LABEL,
+            "💰  Check Balance",
+            "📊  Portfolio",
+            "💸  Send Funds",
+            "📤  Bulk Transfer",
+            "⏰  Scheduled Transfers",
+            "🔍  Transactions",
+            "📜  Transaction History",
+            "🔑  Wallet Management",
+            "🪙  Token Management",
+            "📇  Contact Management",
+            "📝  Smart Contracts",
+            "⚙️  Configuration",
+            "💻  System",
+            "🔒  Lock Now",
+            "🚪  Exit",
         ];
 
-        let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("\nWhat would you like to do?")
+        let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("\nWhat would you like to do? (type to search, / for all actions)")
             .items(&options)
-            .default(0)
+            .default(1)
             .interact()?;
 
         match selection {
-            0 => show_balance().await?,
-            1 => send_funds().await?,
-            2 => bulk_transfer().await?,
-            3 => check_transaction_status().await?,
-            4 => show_history().await?,
-            5 => wallet_menu().await?,
-            6 => token_menu().await?,
-            7 => manage_contacts().await?,
-            8 => show_config_menu().await?,
-            9 => system_menu().await?,
-            10 => {
+            0 => {
+                if let Err(e) = palette::command_palette().await {
+                    eprintln!("Error: {}", e);
+                }
+            }
+            1 => show_balance().await?,
+            2 => show_portfolio().await?,
+            3 => send_funds().await?,
+            4 => bulk_transfer().await?,
+            5 => queue_menu().await?,
+            6 => transaction_menu().await?,
+            7 => show_history().await?,
+            8 => wallet_menu().await?,
+            9 => token_menu().await?,
+            10 => manage_contacts().await?,
+            11 => contract_menu().await?,
+            12 => show_config_menu().await?,
+            13 => system_menu().await?,
+            14 => {
+                let locked = session::lock_all();
+                println!(
+                    "\n🔒 {}",
+                    match locked {
+                        0 => "No wallets were unlocked.".to_string(),
+                        1 => "Locked 1 wallet; its password will be asked again.".to_string(),
+                        n => format!("Locked {} wallets; their passwords will be asked again.", n),
+                    }
+                );
+            }
+            15 => {
+                session::lock_all();
                 println!("\n👋 Goodbye!");
                 break;
             }
@@ -0,0 +1,169 @@
+//! Command palette: every action from the menus in one fuzzy-searchable
+//! list, so frequent actions don't need several menus to reach.
+
+use crate::config::ConfigManager;
+use crate::security::session;
+use anyhow::Result;
+use dialoguer::{FuzzySelect, theme::ColorfulTheme};
+
+/// Label of the palette in the main menu; typing `/` there selects it
+pub const MENU_LABEL: &str = "/  Command Palette";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    CheckBalance,
+    Portfolio,
+    SendFunds,
+    BulkTransfer,
+    ScheduledTransfers,
+    ReceiveFunds,
+    SignMessage,
+    TransactionStatus,
+    PendingTransactions,
+    ShowHistory,
+    ExportHistory,
+    CreateWallet,
+    ImportWallet,
+    SwitchWallet,
+    BackupWallet,
+    AddAccount,
+    RecoveryDrill,
+    ListContacts,
+    AddContact,
+    SearchContacts,
+    VerifyContact,
+    ContactGroups,
+    AddToken,
+    ListTokens,
+    TokenApprovals,
+    Nfts,
+    SmartContracts,
+    WatchEvents,
+    ChangeNetwork,
+    Configuration,
+    NetworkStatus,
+    LockNow,
+}
+
+/// Actions with the menu they are found in, in the order they are listed
+const ACTIONS: &[(Action, &str, &str)] = &[
+    (Action::CheckBalance, "Check balance", "Main"),
+    (Action::Portfolio, "Portfolio (all wallets)", "Main"),
+    (Action::SendFunds, "Send funds", "Main"),
+    (Action::BulkTransfer, "Bulk transfer", "Main"),
+    (Action::ScheduledTransfers, "Scheduled transfers", "Main"),
+    (Action::ReceiveFunds, "Receive funds", "Wallet"),
+    (Action::SignMessage, "Sign message", "Wallet"),
+    (Action::TransactionStatus, "Check transaction status", "Transactions"),
+    (Action::PendingTransactions, "Manage pending transactions", "Transactions"),
+    (Action::ShowHistory, "Transaction history", "Main"),
+    (Action::ExportHistory, "Export history (CSV/JSON)", "History"),
+    (Action::CreateWallet, "Create wallet", "Wallet"),
+    (Action::ImportWallet, "Import wallet", "Wallet"),
+    (Action::SwitchWallet, "Switch wallet", "Wallet"),
+    (Action::BackupWallet, "Backup wallet", "Wallet"),
+    (Action::AddAccount, "Add account", "Wallet"),
+    (Action::RecoveryDrill, "Recovery drill", "Wallet"),
+    (Action::ListContacts, "List contacts", "Contacts"),
+    (Action::AddContact, "Add contact", "Contacts"),
+    (Action::SearchContacts, "Search contacts", "Contacts"),
+    (Action::VerifyContact, "Verify contact", "Contacts"),
+    (Action::ContactGroups, "Manage contact groups", "Contacts"),
+    (Action::AddToken, "Add token", "Tokens"),
+    (Action::ListTokens, "List tokens", "Tokens"),
+    (Action::TokenApprovals, "Token approvals (revoke allowances)", "Tokens"),
+    (Action::Nfts, "NFTs (list and send)", "Tokens"),
+    (Action::SmartContracts, "Smart contracts", "Main"),
+    (Action::WatchEvents, "Watch contract events", "System"),
+    (Action::ChangeNetwork, "Change network", "Configuration"),
+    (Action::Configuration, "Configuration", "Main"),
+    (Action::NetworkStatus, "Network status", "System"),
+    (Action::LockNow, "Lock now", "Main"),
+];
+
+/// Search every action by name and run the chosen one
+pub async fn command_palette() -> Result<()> {
+    let Some(selection) = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Type to search actions (Esc to go back)")
+        .items(&items())
+        .default(0)
+        .interact_opt()?
+    else {
+        return Ok(());
+    };
+
+    run(ACTIONS[selection].0).await
+}
+
+/// Palette entries, each action followed by the menu it is found in
+fn items() -> Vec<String> {
+    ACTIONS
+        .iter()
+        .map(|(_, label, menu)| format!("{}  ({})", label, menu))
+        .collect()
+}
+
+async fn run(action: Action) -> Result<()> {
+    match action {
+        Action::CheckBalance => super::show_balance().await,
+        Action::Portfolio => super::show_portfolio().await,
+        Action::SendFunds => super::send_funds().await,
+        Action::BulkTransfer => super::bulk_transfer().await,
+        Action::ScheduledTransfers => super::queue_menu().await,
+        Action::ReceiveFunds => super::wallet::receive_funds().await,
+        Action::SignMessage => super::wallet::sign_message().await,
+        Action::TransactionStatus => super::tx::check_transaction_status().await,
+        Action::PendingTransactions => super::tx::manage_pending_transactions().await,
+        Action::ShowHistory => super::show_history().await,
+        Action::ExportHistory => super::history::export_history().await,
+        Action::CreateWallet => super::wallet::create_wallet().await,
+        Action::ImportWallet => super::wallet::import_wallet().await,
+        Action::SwitchWallet => super::wallet::switch_wallet().await,
+        Action::BackupWallet => super::wallet::backup_wallet().await,
+        Action::AddAccount => super::wallet::add_account().await,
+        Action::RecoveryDrill => super::recovery_drill::recovery_drill().await,
+        Action::ListContacts => super::contacts::list_contacts().await,
+        Action::AddContact => super::contacts::add_contact().await,
+        Action::SearchContacts => super::contacts::search_contacts().await,
+        Action::VerifyContact => super::contacts::verify_contact().await,
+        Action::ContactGroups => super::contacts::manage_groups(),
+        Action::AddToken => super::tokens::add_token().await,
+        Action::ListTokens => super::tokens::list_tokens().await,
+        Action::TokenApprovals => super::tokens::token_approvals().await,
+        Action::Nfts => super::tokens::nfts().await,
+        Action::SmartContracts => super::contract_menu().await,
+        Action::WatchEvents => super::events::watch_contract_events().await,
+        Action::ChangeNetwork => super::config::change_network(&ConfigManager::new()?).await,
+        Action::Configuration => super::show_config_menu().await,
+        Action::NetworkStatus => super::system::show_system_info().await,
+        Action::LockNow => {
+            let locked = session::lock_all();
+            println!("\n🔒 Locked {} wallet(s).", locked);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palette_items() {
+        for (i, (action, label, _)) in ACTIONS.iter().enumerate() {
+            assert!(
+                ACTIONS[..i]
+                    .iter()
+                    .all(|(other, other_label, _)| other != action && other_label != label),
+                "{:?} is listed twice",
+                action
+            );
+        }
+
+        let items = items();
+        assert_eq!(items.len(), ACTIONS.len());
+        assert_eq!(items[0], "Check balance  (Main)");
+        assert!(items.contains(&"Export history (CSV/JSON)  (History)".to_string()));
+        assert!(MENU_LABEL.starts_with('/'));
+    }
+}
@@ -0,0 +1,84 @@
+use crate::commands::queue::{QueueAction, QueueCommand, Schedule, TransferQueue};
+use anyhow::Result;
+use chrono::Local;
+use console::style;
+use inquire::{Confirm, CustomType, Text, validator::Validation};
+
+/// Interactive menu for scheduled transfers
+pub async fn queue_menu() -> Result<()> {
+    loop {
+        println!("\n{}", style("⏰ Scheduled Transfers").bold());
+        println!("{}", "=".repeat(30));
+
+        let options = vec![
+            "📋 List queued transfers",
+            "➕ Queue a transfer",
+            "❌ Cancel a queued transfer",
+            "✅ Approve a queued transfer",
+            "▶️  Run queue now (watch mode)",
+            "🏠 Back to main menu",
+        ];
+
+        let selection = inquire::Select::new("What would you like to do?", options).prompt()?;
+
+        let action = match selection {
+            "📋 List queued transfers" => QueueAction::List,
+            "➕ Queue a transfer" => {
+                let address = Text::new("Recipient address:")
+                    .with_validator(|input: &str| {
+                        if input.starts_with("0x") && input.len() == 42 {
+                            Ok(Validation::Valid)
+                        } else {
+                            Ok(Validation::Invalid("Invalid address format".into()))
+                        }
+                    })
+                    .prompt()?;
+                let value = Text::new("Amount:").prompt()?;
+                let token = Text::new("Token address (leave empty for RBTC):").prompt()?;
+                let when = Text::new("When to send (HH:MM, YYYY-MM-DD HH:MM or block:N):")
+                    .with_validator(|input: &str| match Schedule::parse(input, Local::now()) {
+                        Ok(_) => Ok(Validation::Valid),
+                        Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+                    })
+                    .prompt()?;
+                QueueAction::Add {
+                    address,
+                    value,
+                    token: (!token.trim().is_empty()).then(|| token.trim().to_string()),
+                    when,
+                }
+            }
+            "❌ Cancel a queued transfer" => {
+                if TransferQueue::load()?.pending().next().is_none() {
+                    println!("No pending transfers.");
+                    continue;
+                }
+                let id = CustomType::<u64>::new("ID of the transfer to cancel:").prompt()?;
+                if !Confirm::new(&format!("Cancel queued transfer #{}?", id))
+                    .with_default(false)
+                    .prompt()?
+                {
+                    continue;
+                }
+                QueueAction::Remove { id }
+            }
+            "✅ Approve a queued transfer" => {
+                if TransferQueue::load()?.pending().next().is_none() {
+                    println!("No pending transfers.");
+                    continue;
+                }
+                let id = CustomType::<u64>::new("ID of the transfer to approve:").prompt()?;
+                QueueAction::Approve { id }
+            }
+            "▶️  Run queue now (watch mode)" => QueueAction::Run { interval: None },
+            "🏠 Back to main menu" => break,
+            _ => unreachable!(),
+        };
+
+        if let Err(e) = (QueueCommand { action }).execute().await {
+            println!("{}: {}", style("Error").red().bold(), e);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,237 @@
+use crate::types::network::Network;
+use crate::types::wallet::{Wallet, WalletData};
+use crate::utils::eth::EthClient;
+use crate::utils::helper::{Config as HelperConfig, WalletConfig};
+use crate::utils::recovery_drill::DrillReport;
+use crate::utils::{audit, mnemonic};
+use alloy::primitives::U256;
+use alloy::signers::{SignerSync, local::PrivateKeySigner};
+use anyhow::{Result, anyhow};
+use console::style;
+use std::fs;
+use std::time::Instant;
+
+/// Attempts allowed for the password or phrase before the drill counts as failed
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Guided rehearsal of restoring a backup or recovery phrase.
+///
+/// The key is restored into a temporary profile that is deleted afterwards,
+/// and only testnet is used, so installed wallets are never touched.
+pub async fn recovery_drill() -> Result<()> {
+    println!("\n{}", style("🧯 Recovery Drill").bold());
+    println!("{}", "=".repeat(30));
+    println!(
+        "{}",
+        style(
+            "Practise restoring a wallet before you need to. The key is restored into a \
+             temporary profile and only testnet is used; your wallets are not changed."
+        )
+        .dim()
+    );
+
+    let sources = vec!["Backup file (from 💾 Backup Wallet)", "Recovery phrase"];
+    let source = inquire::Select::new("What do you want to restore from?", sources).prompt()?;
+
+    let started = Instant::now();
+    let mut report = DrillReport::default();
+
+    let backup = if source == "Recovery phrase" {
+        None
+    } else {
+        let path = inquire::Text::new("Path to the backup file:").prompt()?;
+        let data = fs::read_to_string(path.trim())
+            .map_err(|e| anyhow!("Failed to read backup file: {}", e))?;
+        Some(
+            serde_json::from_str::<Wallet>(&data)
+                .map_err(|e| anyhow!("Not a wallet backup file: {}", e))?,
+        )
+    };
+
+    let mut signer = None;
+    while signer.is_none() && report.attempts < MAX_ATTEMPTS {
+        report.attempts += 1;
+        let restored = match &backup {
+            Some(wallet) => restore_backup(wallet),
+            None => restore_phrase(),
+        };
+        match restored {
+            Ok(restored) => signer = Some(restored),
+            Err(e) => println!("{}", style(format!("❌ {}", e)).red()),
+        }
+    }
+
+    if let Some(signer) = signer {
+        report.restored = true;
+        println!(
+            "\n{} Restored {}",
+            style("✓").green().bold(),
+            style(format!("0x{:x}", signer.address())).cyan()
+        );
+        run_checks(&signer, &mut report).await?;
+    }
+
+    report.duration = started.elapsed();
+    print_report(&report);
+    audit::record_or_warn(
+        "recovery_drill",
+        None,
+        format!("Recovery drill scored {}/100", report.score()),
+    );
+    Ok(())
+}
+
+fn restore_backup(wallet: &Wallet) -> Result<PrivateKeySigner> {
+    let password = inquire::Password::new("Backup password:")
+        .with_display_mode(inquire::PasswordDisplayMode::Masked)
+        .without_confirmation()
+        .prompt()?;
+    let private_key = wallet
+        .decrypt_backup(&password)?
+        .ok_or_else(|| anyhow!("Incorrect password"))?;
+    private_key
+        .parse::<PrivateKeySigner>()
+        .map_err(|e| anyhow!("Invalid private key in backup: {}", e))
+}
+
+fn restore_phrase() -> Result<PrivateKeySigner> {
+    let phrase = inquire::Password::new("Recovery phrase:")
+        .with_display_toggle_enabled()
+        .with_display_mode(inquire::PasswordDisplayMode::Masked)
+        .without_confirmation()
+        .prompt()?;
+    let language = mnemonic::detect_language(&phrase)?;
+    mnemonic::signer_from_phrase(&phrase, language)
+}
+
+/// Restore into a temporary profile, then prove control of the key
+async fn run_checks(signer: &PrivateKeySigner, report: &mut DrillReport) -> Result<()> {
+    // Write the restored key to a throwaway profile and unlock it from there,
+    // as a real restore onto a new machine would
+    let profile = tempfile::tempdir()?;
+    let session_password = hex::encode(rand::random::<[u8; 16]>());
+    let mut profile_data = WalletData::new();
+    profile_data.add_wallet(Wallet::new(
+        signer.clone(),
+        "recovery-drill",
+        &session_password,
+    )?)?;
+    let profile_file = profile.path().join("wallets.json");
+    fs::write(&profile_file, serde_json::to_string_pretty(&profile_data)?)?;
+    let restored: WalletData = serde_json::from_str(&fs::read_to_string(&profile_file)?)?;
+    let private_key = restored
+        .get_current_wallet()
+        .ok_or_else(|| anyhow!("The drill profile has no wallet"))?
+        .decrypt_backup(&session_password)?
+        .ok_or_else(|| anyhow!("Failed to unlock the drill profile"))?;
+
+    report.matched_wallet = installed_wallet_name(signer);
+    match &report.matched_wallet {
+        Some(name) => println!(
+            "{} Matches your wallet '{}'",
+            style("✓").green().bold(),
+            name
+        ),
+        None => println!(
+            "{}",
+            style("⚠️  The recovered address does not match any installed wallet").yellow()
+        ),
+    }
+
+    let message = format!(
+        "Rootstock wallet recovery drill {}",
+        chrono::Utc::now().to_rfc3339()
+    );
+    report.signed = signer
+        .sign_message_sync(message.as_bytes())
+        .ok()
+        .and_then(|signature| signature.recover_address_from_msg(message.as_bytes()).ok())
+        == Some(signer.address());
+    if report.signed {
+        println!("{} Signed a test message", style("✓").green().bold());
+    }
+
+    let client_config = HelperConfig {
+        network: Network::Testnet.get_config(),
+        wallet: WalletConfig {
+            current_wallet_address: None,
+            private_key: Some(private_key),
+            mnemonic: None,
+        },
+    };
+    let eth_client = EthClient::new(&client_config, None).await?;
+    let balance = eth_client
+        .get_balance(&signer.address(), &None)
+        .await
+        .unwrap_or(U256::ZERO);
+    if balance.is_zero() {
+        println!(
+            "{}",
+            style("ℹ️  No testnet RBTC at this address, skipping the on-chain check").dim()
+        );
+        return Ok(());
+    }
+
+    let send = inquire::Confirm::new("Send a 0 RBTC testnet transaction to yourself?")
+        .with_help_message("Proves the restored key can move funds; only the fee is spent")
+        .with_default(true)
+        .prompt()?;
+    if send {
+        let tx_hash = match eth_client
+            .send_transaction(signer.address(), U256::ZERO, None)
+            .await
+        {
+            Ok(tx_hash) => tx_hash,
+            Err(e) => {
+                println!("{}", style(format!("❌ {}", e)).red());
+                return Ok(());
+            }
+        };
+        println!("Sent 0x{:x}, waiting for confirmation...", tx_hash);
+        match eth_client.wait_for_receipt(tx_hash).await {
+            Ok(receipt) if receipt.status() => {
+                report.funds_moved = true;
+                println!(
+                    "{} Testnet transaction confirmed",
+                    style("✓").green().bold()
+                );
+            }
+            Ok(_) => println!("{}", style("❌ Testnet transaction failed").red()),
+            Err(e) => println!("{}", style(format!("⚠️  No receipt yet: {}", e)).yellow()),
+        }
+    }
+    Ok(())
+}
+
+/// Name of the installed wallet with the signer's address, if any
+fn installed_wallet_name(signer: &PrivateKeySigner) -> Option<String> {
+    let wallet_data = WalletData::load().ok()?;
+    wallet_data
+        .list_wallets()
+        .into_iter()
+        .find(|wallet| wallet.address == signer.address())
+        .map(|wallet| wallet.name.clone())
+}
+
+fn print_report(report: &DrillReport) {
+    let score = report.score();
+    let score_text = format!("{}/100 ({})", score, report.grade());
+    let score_text = match score {
+        90.. => style(score_text).green().bold(),
+        40..=89 => style(score_text).yellow().bold(),
+        _ => style(score_text).red().bold(),
+    };
+
+    println!("\n{}", style("📋 Drill Result").bold());
+    println!("{}", "=".repeat(30));
+    println!("Score: {}", score_text);
+    println!(
+        "Time: {}m {}s, attempts: {}",
+        report.duration.as_secs() / 60,
+        report.duration.as_secs() % 60,
+        report.attempts
+    );
+    for tip in report.tips() {
+        println!("  • {}", tip);
+    }
+}
@@ -0,0 +1,22 @@
+use crate::qr::{self, PaymentRequest};
+use anyhow::{Result, anyhow};
+use inquire::Text;
+use std::path::Path;
+
+/// Read an address or payment request from a screenshot of its QR code
+pub fn scan_payment_request(chain_id: u64) -> Result<PaymentRequest> {
+    let path = Text::new("Path to the QR code image:")
+        .with_help_message("A PNG or JPEG screenshot of the address or payment request")
+        .prompt()?;
+    let content = qr::scan_image(Path::new(path.trim()))?;
+    let request = PaymentRequest::parse(&content, chain_id)?;
+    if request.chain_id != chain_id {
+        return Err(anyhow!(
+            "The QR code is for chain {}, but the wallet is using chain {}",
+            request.chain_id,
+            chain_id
+        ));
+    }
+    println!("Scanned address: 0x{:x}", request.recipient);
+    Ok(request)
+}
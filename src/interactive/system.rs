@@ -1,14 +1,18 @@
+use crate::commands::audit_export;
+use crate::commands::webhook::{WebhookAction, WebhookCommand};
 use crate::config::ConfigManager;
 use crate::types::network::Network;
 use crate::utils::eth::EthClient;
 use crate::utils::helper::Config;
+use crate::utils::audit;
 use crate::utils::terminal::{self, show_version};
 use anyhow::Result;
 use console::style;
-use dialoguer::{Select, theme::ColorfulTheme};
-use alloy::primitives::U256;
+use dialoguer::{Input, Select, theme::ColorfulTheme};
 use alloy::providers::Provider;
+use chrono::{NaiveDate, Utc};
 use std::io;
+use std::path::Path;
 use std::time::Duration;
 
 /// Helper function to get styled network status
@@ -39,15 +43,6 @@ async fn get_block_number(eth_client: &EthClient) -> Result<u64> {
     Ok(block_number)
 }
 
-/// Get current gas price from the network
-async fn get_gas_price(eth_client: &EthClient) -> Result<u128> {
-    eth_client
-        .provider()
-        .get_gas_price()
-        .await
-        .map_err(|_| anyhow::anyhow!("Failed to get gas price"))
-}
-
 /// Check network health by measuring block time
 async fn check_network_health(eth_client: &EthClient) -> Result<String> {
     let start_block = get_block_number(eth_client).await?;
@@ -64,7 +59,7 @@ async fn check_network_health(eth_client: &EthClient) -> Result<String> {
 }
 
 /// Display system information including network status and API key configuration
-async fn show_system_info() -> Result<()> {
+pub(super) async fn show_system_info() -> Result<()> {
     let config_manager = ConfigManager::new()?;
     let config = config_manager.load()?;
 
@@ -78,14 +73,14 @@ async fn show_system_info() -> Result<()> {
             let has_key = config
                 .alchemy_mainnet_key
                 .as_ref()
-                .map_or(false, |k| !k.is_empty());
+                .is_some_and(|k| !k.is_empty());
             println!("• Service Configuration: {}", get_config_status(has_key));
         }
         Network::Testnet => {
             let has_key = config
                 .alchemy_testnet_key
                 .as_ref()
-                .map_or(false, |k| !k.is_empty());
+                .is_some_and(|k| !k.is_empty());
             println!("• Service Configuration: {}", get_config_status(has_key));
         }
         _ => {}
@@ -109,12 +104,13 @@ async fn show_system_info() -> Result<()> {
             }
 
             // Get gas price
-            match get_gas_price(&eth_client).await {
-                Ok(gas_price) => {
-                    let gwei = gas_price as f64 / 1_000_000_000.0;
+            match eth_client.suggest_gas_price().await {
+                Ok(fee) => {
+                    let gwei = fee.gas_price as f64 / 1_000_000_000.0;
                     println!(
-                        "• Current Gas Price: {} Gwei",
-                        style(format!("{:.2}", gwei)).yellow()
+                        "• Current Gas Price: {} Gwei ({})",
+                        style(format!("{:.2}", gwei)).yellow(),
+                        fee.source
                     );
                 }
                 Err(_) => println!("• Current Gas Price: {}", style("Unavailable").red().bold()),
@@ -143,6 +139,10 @@ pub async fn system_menu() -> Result<()> {
             format!("{}  Clear Screen", style("🧹").bold().cyan()),
             format!("{}  Show Version", style("ℹ️").bold().blue()),
             format!("{}  Network Status", style("🌐").bold().green()),
+            format!("{}  Alchemy Webhooks", style("🪝").bold().magenta()),
+            format!("{}  Watch Contract Events", style("👀").bold().magenta()),
+            format!("{}  Audit Log", style("📒").bold().yellow()),
+            format!("{}  Export Audit Package", style("🔏").bold().yellow()),
             format!("{}  Back to Main Menu", style("⬅️").bold().white()),
         ];
 
@@ -162,7 +162,11 @@ pub async fn system_menu() -> Result<()> {
                 Ok(())
             }
             2 => show_system_info().await,
-            3 => break,
+            3 => webhook_menu().await,
+            4 => super::events::watch_contract_events().await,
+            5 => show_audit_log(),
+            6 => export_audit_package(),
+            7 => break,
             _ => Ok(()),
         };
 
@@ -171,7 +175,7 @@ pub async fn system_menu() -> Result<()> {
             continue;
         }
 
-        if selection < 3 {
+        if selection < 7 {
             // Don't pause after "Back"
             println!("\nPress Enter to continue...");
             let _ = io::stdin().read_line(&mut String::new())?;
@@ -180,3 +184,95 @@ pub async fn system_menu() -> Result<()> {
 
     Ok(())
 }
+
+/// Show the most recent security events
+fn show_audit_log() -> Result<()> {
+    let entries = audit::load()?;
+    println!("\n{}", style("📒 Audit Log").bold());
+    if entries.is_empty() {
+        println!("  No events recorded");
+        return Ok(());
+    }
+    for entry in entries.iter().rev().take(20) {
+        println!(
+            "  {}  {:<18} {}",
+            style(entry.timestamp.format("%Y-%m-%d %H:%M:%S")).dim(),
+            entry.event,
+            entry.detail
+        );
+    }
+    println!("\n  Full log: {}", audit::path()?.display());
+    Ok(())
+}
+
+/// Export the signed transactions of a period, encrypted to an auditor's key
+fn export_audit_package() -> Result<()> {
+    println!("\n{}", style("🔏 Export Audit Package").bold());
+    let from: NaiveDate = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("First day (YYYY-MM-DD, UTC)")
+        .interact_text()?;
+    let to: NaiveDate = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Last day, included (YYYY-MM-DD, UTC)")
+        .default(Utc::now().date_naive())
+        .interact_text()?;
+    let recipient: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Auditor's public key (hex)")
+        .interact_text()?;
+    let output: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Save package as")
+        .default(audit_export::default_output(from, to).display().to_string())
+        .interact_text()?;
+    audit_export::export(from, to, &recipient, Path::new(&output))
+}
+
+/// Register Alchemy Notify webhooks and run the listener
+async fn webhook_menu() -> Result<()> {
+    let config = ConfigManager::new()?.load()?;
+    match &config.webhook {
+        Some(webhook) => println!(
+            "\n  Webhook {} → {} ({} addresses)",
+            style(&webhook.id).cyan(),
+            webhook.url,
+            webhook.addresses.len()
+        ),
+        None => println!("\n  {}", style("No webhook registered").dim()),
+    }
+
+    let options = vec![
+        "Register webhook",
+        "Sync wallet addresses",
+        "Start listener",
+        "Back",
+    ];
+    let action = match inquire::Select::new("Webhooks:", options).prompt()? {
+        "Register webhook" => {
+            let url = inquire::Text::new("Public URL for deliveries:")
+                .with_help_message("Must reach this machine's listener, e.g. https://host/alchemy")
+                .prompt()?;
+            let auth_token = inquire::Password::new("Alchemy Notify auth token:")
+                .without_confirmation()
+                .prompt()?;
+            let network = inquire::Select::new("Network:", vec!["mainnet", "testnet"]).prompt()?;
+            WebhookAction::Register {
+                url,
+                auth_token,
+                network: network.to_string(),
+            }
+        }
+        "Sync wallet addresses" => {
+            let auth_token = inquire::Password::new("Alchemy Notify auth token:")
+                .without_confirmation()
+                .prompt()?;
+            WebhookAction::Sync { auth_token }
+        }
+        "Start listener" => {
+            let port = inquire::CustomType::<u16>::new("Port:")
+                .with_default(8645)
+                .prompt()?;
+            WebhookAction::Listen { port }
+        }
+        _ => return Ok(()),
+    };
+
+    WebhookCommand { action }.execute().await
+}
@@ -1,7 +1,23 @@
-use crate::commands::tokens;
-use anyhow::Result;
+use crate::commands::pending::signing_client;
+use crate::commands::tokens::{self, TokenRegistry};
+use crate::config::ConfigManager;
+use crate::types::network::NetworkService;
+use crate::types::wallet::WalletData;
+use crate::utils::alchemy::AlchemyClient;
+use crate::utils::allowances::{self, Allowance};
+use crate::utils::audit;
+use crate::utils::eth::{EthClient, get_explorer_url};
+use crate::utils::helper::Helper;
+use crate::utils::nft::{self, Nft};
+use crate::utils::rns;
+use crate::utils::roles::{self, Capability};
+use crate::utils::table::TableBuilder;
+use alloy::primitives::utils::{format_units, parse_units};
+use alloy::primitives::{Address, U256};
+use anyhow::{Result, anyhow};
 use console::style;
 use inquire::validator::Validation;
+use std::str::FromStr;
 
 /// Displays the token management menu
 pub async fn token_menu() -> Result<()> {
@@ -10,6 +26,9 @@ pub async fn token_menu() -> Result<()> {
             String::from("➕ Add Token"),
             String::from("🗑️ Remove Token"),
             String::from("📋 List Tokens"),
+            String::from("🔎 Token Details"),
+            String::from("🛡️ Token Approvals"),
+            String::from("🖼️ NFTs"),
             String::from("🏠 Back to Main Menu"),
         ];
 
@@ -21,13 +40,40 @@ pub async fn token_menu() -> Result<()> {
             "➕ Add Token" => add_token().await?,
             "🗑️ Remove Token" => remove_token().await?,
             "📋 List Tokens" => list_tokens().await?,
+            "🔎 Token Details" => {
+                if let Err(e) = token_details().await {
+                    eprintln!(
+                        "\n{} {}",
+                        style("❌ Failed to load token details:").red(),
+                        style(e).bold()
+                    );
+                }
+            }
+            "🛡️ Token Approvals" => {
+                if let Err(e) = token_approvals().await {
+                    eprintln!(
+                        "\n{} {}",
+                        style("❌ Failed to manage token approvals:").red(),
+                        style(e).bold()
+                    );
+                }
+            }
+            "🖼️ NFTs" => {
+                if let Err(e) = nfts().await {
+                    eprintln!(
+                        "\n{} {}",
+                        style("❌ Failed to manage NFTs:").red(),
+                        style(e).bold()
+                    );
+                }
+            }
             _ => break,
         }
     }
     Ok(())
 }
 
-async fn add_token() -> Result<()> {
+pub(super) async fn add_token() -> Result<()> {
     println!("\n{}", style("➕ Add Token").bold());
     println!("{}", "=".repeat(30));
 
@@ -99,9 +145,9 @@ async fn remove_token() -> Result<()> {
     .prompt()?
     .to_string();
 
-    // Get token symbol to remove
-    let symbol = inquire::Text::new("Token symbol to remove (e.g., USDT):")
-        .with_help_message("Enter the token's ticker symbol to remove")
+    // Get token symbol or address to remove
+    let symbol = inquire::Text::new("Token symbol or address to remove (e.g., USDT):")
+        .with_help_message("Use the contract address when several tokens share the symbol")
         .prompt()?;
 
     // Remove the token
@@ -125,7 +171,7 @@ async fn remove_token() -> Result<()> {
     Ok(())
 }
 
-async fn list_tokens() -> Result<()> {
+pub(super) async fn list_tokens() -> Result<()> {
     println!("\n{}", style("📋 Your Tokens").bold());
     println!("{}", "=".repeat(30));
 
@@ -171,3 +217,471 @@ async fn list_tokens() -> Result<()> {
 
     Ok(())
 }
+
+/// Shows a detail page for a token from the registry
+async fn token_details() -> Result<()> {
+    println!("\n{}", style("🔎 Token Details").bold());
+    println!("{}", "=".repeat(30));
+
+    let config = ConfigManager::new()?.load()?;
+    let network = config.default_network.chain_name();
+
+    let registry = TokenRegistry::load().map_err(|e| anyhow!("{}", e))?;
+    let tokens = registry.list_tokens(Some(network));
+    if tokens.is_empty() {
+        println!("\nNo tokens found in {} network", network);
+        return Ok(());
+    }
+
+    let symbols: Vec<String> = tokens.iter().map(|(symbol, _)| symbol.clone()).collect();
+    let selection = inquire::Select::new("Select token:", symbols).prompt()?;
+    let (symbol, info) = tokens
+        .into_iter()
+        .find(|(symbol, _)| symbol == &selection)
+        .ok_or_else(|| anyhow!("Selected token not found"))?;
+
+    let token_address = Address::from_str(&info.address)
+        .map_err(|_| anyhow!("Invalid token address in registry: {}", info.address))?;
+
+    let (net_config, eth_client) = Helper::init_eth_client(network).await?;
+
+    println!("\n{}", style(format!("{} ({})", symbol, network)).bold().underlined());
+    println!("• Contract: {}", style(token_address.to_checksum(None)).cyan());
+    println!("• Decimals: {}", info.decimals);
+
+    match eth_client.get_token_total_supply(token_address).await {
+        Ok(supply) => println!(
+            "• Total Supply: {} {}",
+            style(format_units(supply, info.decimals).unwrap_or_else(|_| supply.to_string()))
+                .green(),
+            symbol
+        ),
+        Err(_) => println!("• Total Supply: {}", style("Unavailable").red()),
+    }
+
+    // Price data is not provided by any configured API yet
+    println!("• Price: {}", style("Not available").dim());
+
+    // Balances across all local wallets
+    let wallet_data = WalletData::load()?;
+
+    println!("\n{}", style("Your Balances").bold().underlined());
+    if wallet_data.wallets.is_empty() {
+        println!("  No wallets found");
+    }
+    for wallet in wallet_data.list_wallets() {
+        let balance = match eth_client
+            .get_balance(&wallet.address, &Some(token_address))
+            .await
+        {
+            Ok(balance) => format_units(balance, info.decimals)
+                .unwrap_or_else(|_| balance.to_string()),
+            Err(_) => "Unavailable".to_string(),
+        };
+        println!(
+            "  {:<20} {} {} {}",
+            wallet.name,
+            Helper::format_address(&wallet.address),
+            balance,
+            symbol
+        );
+    }
+
+    // Recent transfers involving the current wallet (requires Alchemy)
+    println!("\n{}", style("Recent Transfers").bold().underlined());
+    match (config.get_alchemy_key(), wallet_data.get_current_wallet()) {
+        (Some(api_key), Some(current)) => {
+            let client = AlchemyClient::new(api_key.to_string(), network == "testnet")?;
+            let address = format!("{:#x}", current.address);
+            let contract = format!("{:#x}", token_address);
+
+            let mut transfers = Vec::new();
+            for incoming in [false, true] {
+                match client
+                    .get_token_transfers(&address, &contract, 5, incoming)
+                    .await
+                {
+                    Ok(response) => {
+                        if let Some(items) = response["result"]["transfers"].as_array() {
+                            transfers.extend(items.iter().cloned());
+                        }
+                    }
+                    Err(e) => println!("  {}", style(format!("Error: {}", e)).dim()),
+                }
+            }
+
+            transfers.sort_by(|a, b| {
+                let block = |t: &serde_json::Value| {
+                    t["blockNum"]
+                        .as_str()
+                        .and_then(|s| u64::from_str_radix(s.trim_start_matches("0x"), 16).ok())
+                        .unwrap_or(0)
+                };
+                block(b).cmp(&block(a))
+            });
+
+            if transfers.is_empty() {
+                println!("  No transfers found");
+            }
+            for transfer in transfers.iter().take(5) {
+                let direction = if transfer["to"].as_str() == Some(address.as_str()) {
+                    style("IN ").green()
+                } else {
+                    style("OUT").yellow()
+                };
+                let counterparty = if transfer["to"].as_str() == Some(address.as_str()) {
+                    transfer["from"].as_str().unwrap_or("unknown")
+                } else {
+                    transfer["to"].as_str().unwrap_or("unknown")
+                };
+                println!(
+                    "  {} {} {} {}  {}",
+                    direction,
+                    transfer["value"]
+                        .as_f64()
+                        .map(|v| v.to_string())
+                        .unwrap_or_else(|| "?".to_string()),
+                    symbol,
+                    counterparty,
+                    style(transfer["hash"].as_str().unwrap_or("")).dim()
+                );
+            }
+        }
+        (None, _) => println!(
+            "  {}",
+            style("Add an Alchemy API key in Configuration to see transfers").dim()
+        ),
+        (_, None) => println!("  {}", style("No active wallet selected").dim()),
+    }
+
+    if !net_config.network.explorer_url.is_empty() {
+        println!(
+            "\n🔗 View on Explorer: {}",
+            style(format!(
+                "{}/address/{:#x}",
+                net_config.network.explorer_url, token_address
+            ))
+            .blue()
+            .underlined()
+        );
+    }
+
+    Ok(())
+}
+
+/// Lists the token allowances the current wallet has granted and lets the
+/// user revoke or reduce them
+pub(super) async fn token_approvals() -> Result<()> {
+    println!("\n{}", style("🛡️ Token Approvals").bold());
+    println!("{}", "=".repeat(30));
+
+    let config = ConfigManager::new()?.load()?;
+    let network = config.default_network.chain_name();
+    let wallet_data = WalletData::load()?;
+    let owner = wallet_data
+        .get_current_wallet()
+        .ok_or_else(|| anyhow!("No active wallet found. Please select a wallet first."))?
+        .address;
+    let (_, eth_client) = Helper::init_eth_client(network).await?;
+
+    println!(
+        "Scanning approvals from the last {} blocks...",
+        allowances::SCAN_BLOCKS
+    );
+    let granted = allowances::granted(&eth_client, owner).await?;
+
+    let registry = TokenRegistry::load().map_err(|e| anyhow!("{}", e))?;
+    let known = registry.list_tokens(Some(network));
+    let mut tokens = Vec::new();
+    for allowance in &granted {
+        tokens.push(token_label(&eth_client, &known, allowance.token).await);
+    }
+
+    if granted.is_empty() {
+        println!("\nNo open approvals found.");
+    } else {
+        let mut table = TableBuilder::new();
+        table.add_header(&["Token", "Spender", "Allowance"]);
+        for (allowance, (symbol, decimals)) in granted.iter().zip(&tokens) {
+            table.add_row(&[
+                symbol,
+                &Helper::format_address(&allowance.spender),
+                &allowance_text(allowance, *decimals),
+            ]);
+        }
+        table.print();
+    }
+
+    let mut options: Vec<String> = granted
+        .iter()
+        .zip(&tokens)
+        .map(|(allowance, (symbol, decimals))| {
+            format!(
+                "{} → 0x{:x} ({})",
+                symbol,
+                allowance.spender,
+                allowance_text(allowance, *decimals)
+            )
+        })
+        .collect();
+    options.push(String::from("🔎 Check a token and spender"));
+    options.push(String::from("🔙 Back"));
+
+    let selection = inquire::Select::new("Select an approval to change:", options.clone())
+        .prompt()?;
+    let index = options
+        .iter()
+        .position(|option| option == &selection)
+        .unwrap_or_default();
+    let (allowance, (symbol, decimals)) = if index < granted.len() {
+        (granted[index].clone(), tokens[index].clone())
+    } else if selection.starts_with("🔎") {
+        let token = prompt_address("Token contract address (0x...):")?;
+        let spender = prompt_address("Spender address (0x...):")?;
+        let amount = eth_client.get_allowance(token, owner, spender).await?;
+        let label = token_label(&eth_client, &known, token).await;
+        let allowance = Allowance {
+            token,
+            spender,
+            amount,
+        };
+        println!(
+            "\nAllowance: {} {}",
+            allowance_text(&allowance, label.1),
+            label.0
+        );
+        if amount.is_zero() {
+            return Ok(());
+        }
+        (allowance, label)
+    } else {
+        return Ok(());
+    };
+
+    let action = inquire::Select::new(
+        "What would you like to do?",
+        vec!["Revoke (set to 0)", "Reduce", "Back"],
+    )
+    .prompt()?;
+    let amount = match action {
+        "Revoke (set to 0)" => U256::ZERO,
+        "Reduce" => {
+            let input = inquire::Text::new(&format!("New allowance in {}:", symbol))
+                .prompt()?;
+            let amount: U256 = parse_units(input.trim(), decimals)
+                .map_err(|e| anyhow!("Invalid amount: {}", e))?
+                .into();
+            if amount >= allowance.amount {
+                return Err(anyhow!(
+                    "The new allowance must be below the current {} {}",
+                    allowance_text(&allowance, decimals),
+                    symbol
+                ));
+            }
+            amount
+        }
+        _ => return Ok(()),
+    };
+
+    roles::require(Capability::Transfer)?;
+    if !inquire::Confirm::new(&format!(
+        "Set the {} allowance of 0x{:x} to {}?",
+        symbol,
+        allowance.spender,
+        format_units(amount, decimals).unwrap_or_else(|_| amount.to_string())
+    ))
+    .with_default(false)
+    .prompt()?
+    {
+        println!("Approval unchanged.");
+        return Ok(());
+    }
+
+    let client = signing_client(owner).await?;
+    let tx_hash = client
+        .approve(allowance.token, allowance.spender, amount)
+        .await?;
+    audit::record_or_warn(
+        "allowance_changed",
+        wallet_data.get_current_wallet().map(|w| w.name.as_str()),
+        format!(
+            "{} 0x{:x} for 0x{:x} set to {}",
+            symbol, allowance.token, allowance.spender, amount
+        ),
+    );
+    println!(
+        "\n{} Transaction sent: 0x{:x}",
+        style("✅").green(),
+        tx_hash
+    );
+    if config.default_network.supports(NetworkService::Explorer) {
+        println!(
+            "{}",
+            get_explorer_url(
+                &format!("0x{:x}", tx_hash),
+                config.default_network.is_testnet()
+            )
+        );
+    }
+    Ok(())
+}
+
+/// Lists the ERC-721 tokens the current wallet owns and sends one on request
+pub(super) async fn nfts() -> Result<()> {
+    println!("\n{}", style("🖼️ NFTs").bold());
+    println!("{}", "=".repeat(30));
+
+    let config = ConfigManager::new()?.load()?;
+    let wallet_data = WalletData::load()?;
+    let wallet = wallet_data
+        .get_current_wallet()
+        .ok_or_else(|| anyhow!("No active wallet found. Please select a wallet first."))?;
+    let owner = wallet.address;
+    let (_, eth_client) = Helper::init_eth_client(config.default_network.chain_name()).await?;
+
+    println!("Looking up NFTs...");
+    let owned = nft::owned(&eth_client, config.default_network, owner).await?;
+    if owned.is_empty() {
+        println!("\nNo NFTs found.");
+        return Ok(());
+    }
+
+    let mut table = TableBuilder::new();
+    table.add_header(&["Collection", "Token ID", "Name"]);
+    for token in &owned {
+        table.add_row(&[
+            &token.collection,
+            &token.token_id.to_string(),
+            token.name.as_deref().unwrap_or("-"),
+        ]);
+    }
+    table.print();
+
+    let mut options: Vec<String> = owned.iter().map(nft_label).collect();
+    options.push(String::from("🔙 Back"));
+    let selection = inquire::Select::new("Select an NFT:", options.clone()).prompt()?;
+    let Some(index) = options.iter().position(|option| option == &selection) else {
+        return Ok(());
+    };
+    let Some(token) = owned.get(index) else {
+        return Ok(());
+    };
+
+    let (name, image) = match (&token.name, &token.image) {
+        (None, None) => nft::metadata(&eth_client, token.contract, token.token_id)
+            .await
+            .unwrap_or_default(),
+        (name, image) => (name.clone(), image.clone()),
+    };
+    println!("\n{}", style(nft_label(token)).bold().underlined());
+    println!("• Contract: {}", style(token.contract.to_checksum(None)).cyan());
+    println!("• Token ID: {}", token.token_id);
+    println!("• Name: {}", name.as_deref().unwrap_or("Unknown"));
+    println!("• Image: {}", image.as_deref().unwrap_or("None"));
+
+    let action = inquire::Select::new("What would you like to do?", vec!["📤 Send", "Back"])
+        .prompt()?;
+    if action != "📤 Send" {
+        return Ok(());
+    }
+
+    roles::require(Capability::Transfer)?;
+    let input = inquire::Text::new("Recipient address or RNS name:")
+        .with_validator(|input: &str| {
+            if rns::is_name(input) || Address::from_str(input.trim()).is_ok() {
+                Ok(Validation::Valid)
+            } else {
+                Ok(Validation::Invalid(
+                    "Please enter a valid address (0x...) or RNS name".into(),
+                ))
+            }
+        })
+        .prompt()?;
+    let to = rns::resolve_recipient(eth_client.provider(), &input, eth_client.chain_id()).await?;
+    if to == owner {
+        return Err(anyhow!("The NFT is already in this wallet"));
+    }
+    if !inquire::Confirm::new(&format!(
+        "Send {} to 0x{:x}?",
+        nft_label(token),
+        to
+    ))
+    .with_default(false)
+    .prompt()?
+    {
+        println!("Transfer cancelled.");
+        return Ok(());
+    }
+
+    let client = signing_client(owner).await?;
+    let tx_hash = nft::safe_transfer(&client, token.contract, owner, to, token.token_id).await?;
+    audit::record_or_warn(
+        "nft_sent",
+        Some(&wallet.name),
+        format!(
+            "#{} of 0x{:x} to 0x{:x}",
+            token.token_id, token.contract, to
+        ),
+    );
+    println!(
+        "\n{} Transaction sent: 0x{:x}",
+        style("✅").green(),
+        tx_hash
+    );
+    if config.default_network.supports(NetworkService::Explorer) {
+        println!(
+            "{}",
+            get_explorer_url(
+                &format!("0x{:x}", tx_hash),
+                config.default_network.is_testnet()
+            )
+        );
+    }
+    Ok(())
+}
+
+fn nft_label(token: &Nft) -> String {
+    match &token.name {
+        Some(name) => format!("{} #{} ({})", token.collection, token.token_id, name),
+        None => format!("{} #{}", token.collection, token.token_id),
+    }
+}
+
+/// Symbol and decimals of `token`, from the registry or else the contract
+async fn token_label(
+    eth_client: &EthClient,
+    known: &[(String, tokens::TokenInfo)],
+    token: Address,
+) -> (String, u8) {
+    let registered = known.iter().find(|(_, info)| {
+        Address::from_str(&info.address).is_ok_and(|address| address == token)
+    });
+    match registered {
+        Some((symbol, info)) => (symbol.clone(), info.decimals),
+        None => match eth_client.get_token_info(token).await {
+            Ok((decimals, symbol)) => (symbol, decimals),
+            Err(_) => (format!("0x{:x}", token), 18),
+        },
+    }
+}
+
+fn allowance_text(allowance: &Allowance, decimals: u8) -> String {
+    if allowance.is_unlimited() {
+        String::from("Unlimited")
+    } else {
+        format_units(allowance.amount, decimals).unwrap_or_else(|_| allowance.amount.to_string())
+    }
+}
+
+fn prompt_address(message: &str) -> Result<Address> {
+    let input = inquire::Text::new(message)
+        .with_validator(|input: &str| {
+            if Address::from_str(input.trim()).is_ok() {
+                Ok(Validation::Valid)
+            } else {
+                Ok(Validation::Invalid("Please enter a valid address (0x...)".into()))
+            }
+        })
+        .prompt()?;
+    Ok(Address::from_str(input.trim())?)
+}
@@ -1,11 +1,21 @@
 use crate::{
     commands::{
+        address::checksum_warning,
+        canary,
         contacts::{ContactsAction, ContactsCommand},
-        tokens::TokenRegistry,
-        transfer::TransferCommand,
+        tokens::{TokenInfo, TokenRegistry},
+        transfer::{self, TransferCommand, parse_gas_price},
     },
     config::ConfigManager,
-    interactive::transfer_preview,
+    interactive::{scan, transfer_preview},
+    qr::PaymentRequest,
+    utils::canary::{CanaryPolicy, CanaryTransfers},
+    types::wallet::WalletData,
+    utils::{
+        eth::{EthClient, GasOverrides},
+        helper::{Config as HelperConfig, WalletConfig},
+        http, quick_amounts, rns, transfer_fee,
+    },
 };
 use anyhow::{Context, Result, anyhow};
 use colored::*;
@@ -17,18 +27,34 @@ pub async fn send_funds() -> Result<()> {
     println!("\n{}", style("💸 Send Funds").bold());
     println!("{}", "=".repeat(30));
 
+    if let Some(id) = select_awaiting_canary()? {
+        return follow_up_canary(id).await;
+    }
+
     // Get the current network from config
     let config = ConfigManager::new()?.load()?;
-    let network = config.default_network.to_string().to_lowercase();
-    println!("Using network: {}", network);
+    let network = config.default_network.chain_name();
+    println!("Using network: {}", config.default_network);
+
+    let account = super::wallet::select_account()?;
 
     // Ask user if they want to select from contacts or enter address manually
-    let send_options = vec!["📝 Enter address manually", "👥 Select from contacts"];
+    let send_options = vec![
+        "📝 Enter address manually",
+        "👥 Select from contacts",
+        "🖼️ Scan QR code from image",
+    ];
 
     let send_choice =
         Select::new("How would you like to specify the recipient?", send_options).prompt()?;
 
-    let to = if send_choice == "👥 Select from contacts" {
+    let mut request = None;
+    let to = if send_choice == "🖼️ Scan QR code from image" {
+        let scanned = scan::scan_payment_request(config.default_network.chain_id())?;
+        let to = format!("0x{:x}", scanned.recipient);
+        request = Some(scanned);
+        to
+    } else if send_choice == "👥 Select from contacts" {
         // Load contacts
         let cmd = ContactsCommand {
             action: ContactsAction::List,
@@ -44,8 +70,9 @@ pub async fn send_funds() -> Result<()> {
                 .iter()
                 .map(|c| {
                     format!(
-                        "{} (0x{:x}) - {}",
+                        "{}{} (0x{:x}) - {}",
                         c.name,
+                        c.badge(),
                         c.address,
                         c.notes.as_deref().unwrap_or("No notes")
                     )
@@ -65,6 +92,50 @@ pub async fn send_funds() -> Result<()> {
         get_recipient_address()?
     };
 
+    // RNS names are resolved once here so the preview and the transfer use
+    // the same address, shown with the checksum for the network
+    let chain_id = config.default_network.chain_id();
+    let provider = http::provider(&config.rpc_url())?;
+    let address = rns::resolve_recipient(&provider, &to, chain_id).await?;
+    if checksum_warning(&to, chain_id).is_some()
+        && !inquire::Confirm::new("Send to this address anyway?")
+            .with_default(false)
+            .prompt()?
+    {
+        println!("Transaction cancelled");
+        return Ok(());
+    }
+    let to = address.to_checksum(Some(chain_id));
+
+    // Exchange deposit addresses only credit transfers that carry their memo
+    let recipient: alloy::primitives::Address = to
+        .parse()
+        .map_err(|_| anyhow!("Invalid recipient address: {}", to))?;
+    let memo = if crate::commands::contacts::memo_required(&recipient)? {
+        println!(
+            "{}: This recipient requires a memo (destination tag) to credit the deposit.",
+            "Note".yellow().bold()
+        );
+        Some(
+            Text::new("Memo:")
+                .with_help_message("The destination tag or deposit memo the recipient gave you")
+                .with_validator(|input: &str| {
+                    if input.trim().is_empty() {
+                        Ok(Validation::Invalid(
+                            "A memo is required for this recipient".into(),
+                        ))
+                    } else {
+                        Ok(Validation::Valid)
+                    }
+                })
+                .prompt()?
+                .trim()
+                .to_string(),
+        )
+    } else {
+        None
+    };
+
     // Load token registry
     let registry = TokenRegistry::load()
         .map_err(|e| {
@@ -74,7 +145,7 @@ pub async fn send_funds() -> Result<()> {
         .unwrap_or_default();
 
     // Get tokens for the current network
-    let mut tokens = registry.list_tokens(Some(&network));
+    let mut tokens = registry.list_tokens(Some(network));
 
     // Add RBTC as the first option
     tokens.insert(
@@ -82,6 +153,7 @@ pub async fn send_funds() -> Result<()> {
         (
             "RBTC (Native)".to_string(),
             crate::commands::tokens::TokenInfo {
+                symbol: "RBTC".to_string(),
                 address: "0x0000000000000000000000000000000000000000".to_string(),
                 decimals: 18,
             },
@@ -99,7 +171,7 @@ pub async fn send_funds() -> Result<()> {
             // Only include tokens that match the current network or are RBTC
             info.address == "0x0000000000000000000000000000000000000000"
                 || registry
-                    .list_tokens(Some(&network))
+                    .list_tokens(Some(network))
                     .iter()
                     .any(|(_, token_info)| token_info.address == info.address)
         })
@@ -109,48 +181,97 @@ pub async fn send_funds() -> Result<()> {
     let token_display_names: Vec<String> =
         token_choices.iter().map(|(name, _)| name.clone()).collect();
 
-    // Let the user select which token to send
-    let selection = Select::new("Select token to send:", token_display_names).prompt()?;
+    // A scanned payment request names its token; otherwise the user picks one
+    let requested_token = match &request {
+        Some(PaymentRequest {
+            token: Some(token), ..
+        }) => Some(format!("{:#x}", token)),
+        Some(PaymentRequest {
+            token: None,
+            value: Some(_),
+            ..
+        }) => Some("0x0000000000000000000000000000000000000000".to_string()),
+        _ => None,
+    };
+    let selection = match requested_token {
+        Some(address) => token_choices
+            .iter()
+            .find(|(_, info)| info.address.eq_ignore_ascii_case(&address))
+            .map(|(name, _)| name.clone())
+            .ok_or_else(|| {
+                anyhow!(
+                    "The requested token {} is not in your token list; add it under Token Management first",
+                    address
+                )
+            })?,
+        None => Select::new("Select token to send:", token_display_names).prompt()?,
+    };
 
     // Find the selected token info
-    let (display_name, token_info) = token_choices
+    let (_, token_info) = token_choices
         .into_iter()
         .find(|(name, _)| name == &selection)
         .ok_or_else(|| anyhow!("Selected token not found"))?;
 
-    // Extract the token symbol (remove the (Native) suffix if present)
-    let token_symbol = display_name
-        .split_whitespace()
-        .next()
-        .unwrap_or(&display_name)
-        .to_string();
-
-    let amount = loop {
-        let input = inquire::Text::new(&format!("Amount of {} to send:", token_symbol))
-            .with_help_message("Enter the amount to send")
-            .with_validator(|input: &str| {
-                if input.parse::<f64>().is_ok() {
-                    Ok(Validation::Valid)
-                } else {
-                    Ok(Validation::Invalid("Please enter a valid number".into()))
+    let token_symbol = token_info.symbol.clone();
+
+    // Amounts are sent with 18 decimals, so a requested amount is shown the same way
+    let requested_amount = request
+        .as_ref()
+        .and_then(|r| r.value)
+        .map(|value| alloy::primitives::utils::format_units(value, 18))
+        .transpose()?;
+
+    // Routine payments can reuse the last amount or a configured preset
+    let token_key: alloy::primitives::Address = token_info.address.parse()?;
+    let last_amount = quick_amounts::last(network, &recipient, &token_key).unwrap_or_else(|e| {
+        log::warn!("Failed to load the last amount sent: {}", e);
+        None
+    });
+    let presets = quick_amounts::presets(&config, &token_symbol);
+
+    let (amount, tier_gas_price) = loop {
+        let quick = if requested_amount.is_none() {
+            select_quick_amount(&token_symbol, last_amount.as_deref(), presets)?
+        } else {
+            None
+        };
+        let input = match quick {
+            Some(amount) => amount,
+            None => {
+                let message = format!("Amount of {} to send:", token_symbol);
+                let mut prompt = inquire::Text::new(&message)
+                    .with_help_message("Enter the amount to send")
+                    .with_validator(|input: &str| {
+                        if input.parse::<f64>().is_ok() {
+                            Ok(Validation::Valid)
+                        } else {
+                            Ok(Validation::Invalid("Please enter a valid number".into()))
+                        }
+                    });
+                if let Some(amount) = &requested_amount {
+                    prompt = prompt.with_initial_value(amount);
                 }
-            })
-            .prompt()?;
+                prompt.prompt()?
+            }
+        };
 
         // Convert RBTC to wei for preview
         let rbtc: f64 = input.parse().unwrap_or(0.0);
         let wei = (rbtc * 1e18) as u128;
 
         // Show preview and ask for confirmation
-        let confirmed = transfer_preview::show_transaction_preview(
+        let chosen_gas_price = transfer_preview::show_transaction_preview(
             &to,
             &wei.to_string(),
+            Some(token_key).filter(|token| !token.is_zero()),
+            account,
             config.default_network,
         )
         .await?;
 
-        if confirmed {
-            break input;
+        if let Some(gas_price) = chosen_gas_price {
+            break (input, gas_price);
         } else {
             println!("Transaction cancelled. Please enter a new amount or press Ctrl+C to exit.");
         }
@@ -164,14 +285,77 @@ pub async fn send_funds() -> Result<()> {
         Some(token_address.clone())
     };
 
+    // Finance can split spend by the configured cost centers
+    let cost_center = if config.cost_centers.is_empty() {
+        None
+    } else {
+        let mut options = config.cost_centers.clone();
+        options.push("(none)".to_string());
+        Some(
+            Select::new("Allocate to cost center:", options)
+                .with_help_message("Shown in history exports, journals and stats")
+                .prompt()?,
+        )
+        .filter(|center| center != "(none)")
+    };
+
     // Show transaction summary
     println!("\n{}", style("📝 Transaction Summary").bold());
     println!("{}", "=".repeat(30));
     println!("To: {}", to);
     println!("Token: {}", token_symbol);
     println!("Amount: {} {}", amount, token_symbol);
+    if let Some(fee) = simulate_token_fee(&config, account, &to, &token_info, &amount).await {
+        println!(
+            "{}: {}",
+            "Warning".yellow().bold(),
+            fee.warning(&token_symbol, token_info.decimals)
+        );
+    }
+    if let Some(memo) = &memo {
+        println!("Memo: {}", memo);
+    }
+    if let Some(cost_center) = &cost_center {
+        println!("Cost center: {}", cost_center);
+    }
     println!("Network: {}", network);
 
+    let advanced = inquire::Confirm::new("Advanced options (gas limit, gas price, nonce)?")
+        .with_default(false)
+        .prompt()?;
+    let (gas_limit, gas_price, nonce) = if advanced {
+        loop {
+            let (gas_limit, gas_price, nonce) = prompt_gas_overrides()?;
+            let overrides = GasOverrides {
+                gas_limit,
+                gas_price: gas_price.as_deref().map(parse_gas_price).transpose()?,
+                nonce,
+            };
+            let warnings =
+                check_gas_overrides(&config, account, &to, &token_info, &amount, &overrides)
+                    .await;
+            if warnings.is_empty() {
+                break (gas_limit, gas_price, nonce);
+            }
+            for warning in &warnings {
+                println!("{}: {}", "Warning".yellow().bold(), warning);
+            }
+            if inquire::Confirm::new("Use these values anyway?")
+                .with_default(false)
+                .prompt()?
+            {
+                break (gas_limit, gas_price, nonce);
+            }
+        }
+    } else {
+        (None, None, None)
+    };
+    // Without an explicit override, pay the price of the tier picked in the preview
+    let gas_price = match gas_price {
+        Some(gas_price) => Some(gas_price),
+        None => Some(alloy::primitives::utils::format_units(tier_gas_price, "gwei")?),
+    };
+
     // Confirm transaction
     let confirm = inquire::Confirm::new("Confirm transaction?")
         .with_default(false)
@@ -193,29 +377,242 @@ pub async fn send_funds() -> Result<()> {
         } else {
             Some(token_address)
         },
+        gas_limit,
+        gas_price,
+        nonce,
+        account,
+        memo,
+        cost_center,
+        units: None,
     };
 
+    // Large transfers can go out in two steps, starting with a tiny canary
+    let canary = alloy::primitives::utils::parse_units(&cmd.value.to_string(), 18)
+        .ok()
+        .and_then(|value| CanaryPolicy::current().canary_for(value.into()));
+    if let Some(canary) = canary
+        && inquire::Confirm::new(&format!(
+            "This is a large transfer. Send a canary of {} {} first?",
+            alloy::primitives::utils::format_units(canary, 18)?,
+            token_symbol
+        ))
+        .with_help_message("The rest is sent once the recipient confirms the canary arrived")
+        .with_default(true)
+        .prompt()?
+    {
+        let id = canary::send_canary(&cmd, canary).await?;
+        return follow_up_canary(id).await;
+    }
+
     let result = cmd.execute().await?;
+    if let Err(e) = quick_amounts::record(network, &recipient, &token_key, &amount) {
+        log::warn!("Failed to remember the amount sent: {}", e);
+    }
 
     println!(
         "\n{}: Transaction confirmed! Tx Hash: {}",
         "Success".green().bold(),
         result.tx_hash
     );
+    super::contacts::offer_to_save_recipient(&result)?;
+
+    Ok(())
+}
 
+/// Offers the amount last sent to the recipient and the presets of the
+/// token; `None` when there are none or another amount is to be typed
+fn select_quick_amount(
+    symbol: &str,
+    last: Option<&str>,
+    presets: &[String],
+) -> Result<Option<String>> {
+    if last.is_none() && presets.is_empty() {
+        return Ok(None);
+    }
+    let mut amounts = Vec::new();
+    let mut options = Vec::new();
+    if let Some(last) = last {
+        options.push(format!("↩️  Repeat last amount: {} {}", last, symbol));
+        amounts.push(last);
+    }
+    for preset in presets {
+        options.push(format!("⚡ {} {}", preset, symbol));
+        amounts.push(preset);
+    }
+    options.push("✏️  Enter another amount".to_string());
+
+    let selection = Select::new(&format!("Amount of {} to send:", symbol), options.clone())
+        .prompt()?;
+    Ok(options
+        .iter()
+        .position(|option| option == &selection)
+        .and_then(|i| amounts.get(i))
+        .map(|amount| amount.to_string()))
+}
+
+/// Offers to continue a transfer whose canary is waiting for confirmation
+fn select_awaiting_canary() -> Result<Option<u64>> {
+    let canaries = CanaryTransfers::load()?;
+    let awaiting: Vec<_> = canaries.awaiting().collect();
+    if awaiting.is_empty() {
+        return Ok(None);
+    }
+
+    let mut options: Vec<String> = awaiting
+        .iter()
+        .map(|t| format!("🐤 Continue {}", canary::describe(t)))
+        .collect();
+    options.push("➕ New transfer".to_string());
+    let selection = Select::new(
+        "Canary transfers are waiting for confirmation:",
+        options.clone(),
+    )
+    .prompt()?;
+    Ok(options
+        .iter()
+        .position(|o| o == &selection)
+        .and_then(|i| awaiting.get(i))
+        .map(|t| t.id))
+}
+
+/// Asks whether the canary arrived, then sends the rest, keeps waiting or
+/// cancels the remainder
+async fn follow_up_canary(id: u64) -> Result<()> {
+    let options = vec![
+        "✅ It arrived, send the rest",
+        "⏳ Not yet, ask me later",
+        "❌ Cancel the rest of the transfer",
+    ];
+    let choice = Select::new(
+        "Has the recipient confirmed the canary arrived?",
+        options.clone(),
+    )
+    .prompt()?;
+
+    if choice == options[0] {
+        let result = canary::send_remainder(id).await?;
+        println!(
+            "\n{}: Transaction confirmed! Tx Hash: {}",
+            "Success".green().bold(),
+            result.tx_hash
+        );
+        super::contacts::offer_to_save_recipient(&result)?;
+    } else if choice == options[1] {
+        println!(
+            "Canary #{} is saved. Open Send Funds again to finish it once the recipient confirms.",
+            id
+        );
+    } else {
+        canary::cancel(id)?;
+    }
     Ok(())
 }
 
+/// Prompts for optional gas limit, gas price (gwei) and nonce overrides
+fn prompt_gas_overrides() -> Result<(Option<u64>, Option<String>, Option<u64>)> {
+    let gas_limit = inquire::CustomType::<u64>::new("Gas limit:")
+        .with_help_message("Leave empty to use the estimate")
+        .prompt_skippable()?;
+    let gas_price = Text::new("Gas price (gwei):")
+        .with_help_message("Leave empty to use the network gas price")
+        .with_validator(|input: &str| {
+            if input.trim().is_empty() || input.trim().parse::<f64>().is_ok() {
+                Ok(Validation::Valid)
+            } else {
+                Ok(Validation::Invalid("Please enter a valid number".into()))
+            }
+        })
+        .prompt_skippable()?
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    let nonce = inquire::CustomType::<u64>::new("Nonce:")
+        .with_help_message("Leave empty to use the next pending nonce")
+        .prompt_skippable()?;
+
+    if gas_limit.is_some() || gas_price.is_some() || nonce.is_some() {
+        println!("\n{}", style("⚙️  Gas Overrides").bold());
+        if let Some(limit) = gas_limit {
+            println!("Gas limit: {}", limit);
+        }
+        if let Some(price) = &gas_price {
+            println!("Gas price: {} gwei", price);
+        }
+        if let Some(nonce) = nonce {
+            println!("Nonce: {}", nonce);
+        }
+    }
+
+    Ok((gas_limit, gas_price, nonce))
+}
+
+/// Warnings for advanced gas values that deviate far from the node's gas
+/// estimate, gas price and pending nonce for a transfer from the selected
+/// account; values that cannot be checked are left out
+async fn check_gas_overrides(
+    config: &crate::config::Config,
+    account: Option<u32>,
+    to: &str,
+    token: &TokenInfo,
+    amount: &str,
+    overrides: &GasOverrides,
+) -> Vec<String> {
+    let client_config = HelperConfig {
+        network: config.default_network.get_config(),
+        wallet: WalletConfig::default(),
+    };
+    let from = WalletData::load()
+        .ok()
+        .and_then(|data| data.get_current_wallet()?.account_address(account.unwrap_or(0)));
+    let (Ok(client), Ok(to), Some(from)) =
+        (EthClient::new(&client_config, None).await, to.parse(), from)
+    else {
+        return Vec::new();
+    };
+    let token_address = token
+        .address
+        .parse::<alloy::primitives::Address>()
+        .ok()
+        .filter(|token| !token.is_zero());
+    let amount = alloy::primitives::utils::parse_units(amount, token.decimals)
+        .map(Into::into)
+        .unwrap_or_default();
+    transfer::check_gas_overrides(&client, from, to, amount, token_address, overrides).await
+}
+
+/// Simulate a token transfer from the selected account to spot tokens that
+/// take a fee on transfer. Native RBTC and anything that cannot be simulated
+/// are not checked.
+async fn simulate_token_fee(
+    config: &crate::config::Config,
+    account: Option<u32>,
+    to: &str,
+    token: &TokenInfo,
+    amount: &str,
+) -> Option<transfer_fee::TransferFee> {
+    let token_address: alloy::primitives::Address = token.address.parse().ok()?;
+    if token_address.is_zero() {
+        return None;
+    }
+    let from = WalletData::load()
+        .ok()?
+        .get_current_wallet()?
+        .account_address(account.unwrap_or(0))?;
+    let to = to.parse().ok()?;
+    let amount = alloy::primitives::utils::parse_units(amount, token.decimals).ok()?.into();
+    let provider = http::provider(&config.rpc_url()).ok()?;
+    transfer_fee::check(&provider, token_address, from, to, amount).await
+}
+
 /// Helper function to get recipient address with validation
 fn get_recipient_address() -> Result<String> {
-    Text::new("Recipient address (0x...):")
-        .with_help_message("Enter the Ethereum address to send to")
+    Text::new("Recipient address (0x...) or RNS name:")
+        .with_help_message("Enter the address or RNS name (e.g. alice.rsk) to send to")
         .with_validator(|input: &str| {
-            if input.starts_with("0x") && input.len() == 42 {
+            if (input.starts_with("0x") && input.len() == 42) || rns::is_name(input) {
                 Ok(Validation::Valid)
             } else {
                 Ok(Validation::Invalid(
-                    "Please enter a valid Ethereum address (0x...)".into(),
+                    "Please enter a valid address (0x...) or RNS name (name.rsk)".into(),
                 ))
             }
         })
@@ -1,16 +1,21 @@
 use crate::{
+    api::prices::Prices,
     config::ConfigManager,
-    types::network::{Network, NetworkConfig},
+    types::{
+        network::{Network, NetworkConfig},
+        wallet::WalletData,
+    },
     utils::{
         eth::EthClient,
         helper::{Config as HelperConfig, WalletConfig},
+        simulation,
     },
 };
+use alloy::providers::Provider;
 use anyhow::{Result, anyhow};
 use console::style;
-use dialoguer::Confirm;
+use dialoguer::{Confirm, Select};
 use alloy::primitives::{Address, U256};
-use alloy::providers::Provider;
 use std::str::FromStr;
 
 /// Helper function to convert wei to RBTC
@@ -20,8 +25,18 @@ fn convert_wei_to_rbtc(wei: U256) -> f64 {
     wei_f64 / 1_000_000_000_000_000_000.0
 }
 
-/// Displays transaction details and asks for confirmation
-pub async fn show_transaction_preview(to: &str, amount: &str, network: Network) -> Result<bool> {
+/// Displays transaction details, simulates the transfer of `amount` (of
+/// `token`, or RBTC) from `account` of the current wallet, offers the slow,
+/// standard and fast gas prices and asks for confirmation. Returns the chosen
+/// gas price in wei, or `None` when the transaction would fail or was not
+/// confirmed.
+pub async fn show_transaction_preview(
+    to: &str,
+    amount: &str,
+    token: Option<Address>,
+    account: Option<u32>,
+    network: Network,
+) -> Result<Option<u128>> {
     println!("\n{}", style("Transaction Preview").bold().underlined());
     println!("• To: {}", style(to).cyan());
 
@@ -39,11 +54,16 @@ pub async fn show_transaction_preview(to: &str, amount: &str, network: Network)
 
     // Get current config and initialize EthClient
     let config = ConfigManager::new()?.load()?;
+    let prices = Prices::fetch(&config, &[]).await;
+    if let Some(value) = prices.as_ref().and_then(|p| p.value(None, amount_rbtc)) {
+        println!("  ≈ {}", style(value).dim());
+    }
     let helper_config = HelperConfig {
         network: NetworkConfig {
             name: config.default_network.to_string(),
             rpc_url: config.default_network.get_config().rpc_url,
             explorer_url: config.default_network.get_config().explorer_url,
+            fallback_rpc_urls: Vec::new(),
         },
         wallet: WalletConfig {
             current_wallet_address: None,
@@ -53,32 +73,76 @@ pub async fn show_transaction_preview(to: &str, amount: &str, network: Network)
     };
     let eth_client = EthClient::new(&helper_config, None).await?;
 
-    // Fetch current gas price from the network
-    let gas_price = eth_client
-        .provider()
-        .get_gas_price()
-        .await
-        .map_err(|e| anyhow!("Failed to get gas price: {}", e))?;
+    // Slow, standard and fast prices sampled from the network
+    let oracle = eth_client.gas_oracle().await?;
 
-    // Estimate gas for the transaction
     let to_address: Address = to
         .parse()
         .map_err(|_| anyhow!("Invalid recipient address"))?;
-    let estimated_gas = eth_client
-        .estimate_gas(
-            to_address, amount_wei, None, // No token address for native transfers
-        )
-        .await?;
-    let gas_cost = U256::from(gas_price).checked_mul(estimated_gas).unwrap_or_default();
-    let gas_cost_rbtc = convert_wei_to_rbtc(gas_cost);
+    let from = WalletData::load()
+        .ok()
+        .and_then(|data| data.get_current_wallet()?.account_address(account.unwrap_or(0)));
+    let call = simulation::transfer_call(from, to_address, amount_wei, token);
+
+    // Run the transfer before it is signed, so a revert costs no gas
+    if from.is_some() {
+        if let Some(reason) = simulation::revert_reason(eth_client.provider(), &call).await {
+            println!("• Simulation: {}", style(format!("would fail ({})", reason)).red());
+            return Ok(None);
+        }
+        println!("• Simulation: {}", style("succeeds").green());
+    }
+
+    // Estimate gas for the transaction
+    let estimated_gas = U256::from(
+        eth_client
+            .provider()
+            .estimate_gas(&call)
+            .await
+            .map_err(|e| anyhow!("Failed to estimate gas: {}", e))?,
+    );
+    let fee_for = |gas_price: u128| {
+        U256::from(gas_price)
+            .checked_mul(estimated_gas)
+            .unwrap_or_default()
+    };
 
     println!("• Network: {}", style(network).cyan());
+    println!("• Estimated Gas: {}", style(estimated_gas).yellow());
+    println!("• Gas Price ({}):", oracle.source);
+    let options: Vec<String> = oracle
+        .tiers
+        .iter()
+        .map(|tier| {
+            format!(
+                "{:<8}  {} Gwei  fee {} RBTC  {}",
+                tier.tier,
+                convert_wei_to_gwei(U256::from(tier.gas_price)),
+                convert_wei_to_rbtc(fee_for(tier.gas_price)),
+                oracle.wait(tier)
+            )
+        })
+        .collect();
+    let index = Select::new()
+        .with_prompt("Gas price")
+        .items(&options)
+        .default(1)
+        .interact()?;
+    let tier = oracle.tiers[index];
+    let gas_price = tier.gas_price;
+    let gas_cost = fee_for(gas_price);
+    let gas_cost_rbtc = convert_wei_to_rbtc(gas_cost);
+
     println!(
-        "• Gas Price: {} Gwei",
-        style(convert_wei_to_gwei(U256::from(gas_price))).yellow()
+        "• Gas Price: {} Gwei ({}, {})",
+        style(convert_wei_to_gwei(U256::from(gas_price))).yellow(),
+        tier.tier,
+        oracle.wait(&tier)
     );
-    println!("• Estimated Gas: {}", style(estimated_gas).yellow());
     println!("• Estimated Fee: {} RBTC", style(gas_cost_rbtc).red());
+    if let Some(value) = prices.as_ref().and_then(|p| p.value(None, gas_cost_rbtc)) {
+        println!("  ≈ {}", style(value).dim());
+    }
 
     let total_amount = amount_wei.checked_add(gas_cost).unwrap_or(amount_wei);
     let total_rbtc = convert_wei_to_rbtc(total_amount);
@@ -86,6 +150,9 @@ pub async fn show_transaction_preview(to: &str, amount: &str, network: Network)
         "• Total (Amount + Fee): {} RBTC",
         style(total_rbtc).green().bold()
     );
+    if let Some(value) = prices.as_ref().and_then(|p| p.value(None, total_rbtc)) {
+        println!("  ≈ {}", style(value).dim());
+    }
 
     // Ask for confirmation
     let confirm = Confirm::new()
@@ -93,7 +160,7 @@ pub async fn show_transaction_preview(to: &str, amount: &str, network: Network)
         .default(false)
         .interact()?;
 
-    Ok(confirm)
+    Ok(confirm.then_some(gas_price))
 }
 
 /// Helper function to convert wei to Gwei
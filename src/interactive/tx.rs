@@ -1,8 +1,144 @@
 use anyhow::Result;
 use console::style;
 use dialoguer::Input;
+use std::path::{Path, PathBuf};
 
-use crate::{commands::tx::TxCommand, config::ConfigManager, types::network::Network};
+use crate::{
+    commands::{offline, pending, transfer::parse_gas_price, tx::TxCommand},
+    config::ConfigManager,
+    types::network::NetworkService,
+};
+
+/// Transaction status and pending transaction management
+pub async fn transaction_menu() -> Result<()> {
+    loop {
+        println!("\n{}", style("🔍 Transactions").bold());
+        println!("{}", "=".repeat(30));
+
+        let options = vec![
+            "🔍 Check transaction status",
+            "⏳ Manage pending transactions",
+            "✍️ Sign an offline transaction",
+            "📡 Broadcast a signed transaction",
+            "🏠 Back to main menu",
+        ];
+        match inquire::Select::new("What would you like to do?", options).prompt()? {
+            "🔍 Check transaction status" => check_transaction_status().await?,
+            "⏳ Manage pending transactions" => {
+                if let Err(e) = manage_pending_transactions().await {
+                    println!("\n{}: {}", style("Error").red().bold(), e);
+                }
+            }
+            "✍️ Sign an offline transaction" => {
+                if let Err(e) = sign_offline_transaction().await {
+                    println!("\n{}: {}", style("Error").red().bold(), e);
+                }
+            }
+            "📡 Broadcast a signed transaction" => {
+                let input = inquire::Text::new("Signed transaction file, QR image or raw hex:")
+                    .with_default("signed-tx.json")
+                    .prompt()?;
+                if let Err(e) = offline::broadcast_signed(&input).await {
+                    println!("\n{}: {}", style("Error").red().bold(), e);
+                }
+            }
+            _ => return Ok(()),
+        }
+    }
+}
+
+/// Signs a transaction built with `tx build --unsigned` on another machine
+async fn sign_offline_transaction() -> Result<()> {
+    let inputs = inquire::Text::new("Unsigned transaction file, or QR frame images:")
+        .with_default("unsigned-tx.json")
+        .with_help_message("Separate several QR frame images with spaces")
+        .prompt()?;
+    let inputs: Vec<PathBuf> = inputs.split_whitespace().map(PathBuf::from).collect();
+    let output = inquire::Text::new("Write the signed transaction to:")
+        .with_default("signed-tx.json")
+        .prompt()?;
+    let show_qr = inquire::Confirm::new("Also show it as a QR code?")
+        .with_default(false)
+        .prompt()?;
+    offline::sign(&inputs, Path::new(output.trim()), show_qr).await
+}
+
+/// Lists sent transactions that are not mined yet and offers to speed them
+/// up or cancel them by replacing them with the same nonce
+pub(super) async fn manage_pending_transactions() -> Result<()> {
+    println!("\n{}", style("⏳ Fetching pending transactions...").dim());
+    let pending = pending::pending_transactions().await?;
+    if pending.is_empty() {
+        println!("No pending transactions.");
+        return Ok(());
+    }
+
+    let mut options: Vec<String> = pending.iter().map(pending::describe).collect();
+    options.push("🏠 Back".to_string());
+    let selection =
+        inquire::Select::new("Select a pending transaction:", options.clone()).prompt()?;
+    let Some(tx) = options
+        .iter()
+        .position(|o| o == &selection)
+        .and_then(|i| pending.get(i))
+    else {
+        return Ok(());
+    };
+    println!("Hash: 0x{:x}", tx.hash);
+
+    let actions = vec![
+        "🚀 Speed up (same transaction, higher gas price)",
+        "🛑 Cancel (empty transfer to yourself, same nonce)",
+        "🏠 Back",
+    ];
+    let action = inquire::Select::new("What would you like to do?", actions.clone()).prompt()?;
+    let cancel = match action {
+        a if a == actions[0] => false,
+        a if a == actions[1] => true,
+        _ => return Ok(()),
+    };
+
+    let suggested = pending::suggested_gas_price(tx).await?;
+    let gas_price: String = Input::new()
+        .with_prompt(format!(
+            "Gas price in gwei (current {} gwei)",
+            pending::format_gwei(tx.gas_price)
+        ))
+        .default(pending::format_gwei(suggested))
+        .validate_with(|input: &String| -> Result<(), &str> {
+            if parse_gas_price(input).is_ok() {
+                Ok(())
+            } else {
+                Err("Please enter a valid, non-negative gas price")
+            }
+        })
+        .interact_text()?;
+    let gas_price = parse_gas_price(&gas_price)?;
+
+    if !inquire::Confirm::new(if cancel {
+        "Replace this transaction with a cancellation?"
+    } else {
+        "Re-send this transaction with the new gas price?"
+    })
+    .with_default(false)
+    .prompt()?
+    {
+        println!("No changes made.");
+        return Ok(());
+    }
+
+    let replacement = pending::replace(tx, Some(gas_price), cancel).await?;
+    println!(
+        "\n{}: Replacement sent: 0x{:x}",
+        style("Success").green().bold(),
+        replacement
+    );
+    println!(
+        "{}",
+        style("Whichever transaction with this nonce is mined first wins.").dim()
+    );
+    Ok(())
+}
 
 /// Interactive transaction status checker
 pub async fn check_transaction_status() -> Result<()> {
@@ -12,11 +148,10 @@ pub async fn check_transaction_status() -> Result<()> {
 
         // Get the current network from config
         let config = ConfigManager::new()?.load()?;
-        let (_, is_testnet) = match config.default_network {
-            Network::RootStockMainnet => ("mainnet", false),
-            Network::RootStockTestnet => ("testnet", true),
-            _ => ("testnet", true), // Default to testnet if not specified
-        };
+        config
+            .default_network
+            .require(NetworkService::Alchemy, "Checking transaction status")?;
+        let is_testnet = config.default_network.is_testnet();
 
         // Get transaction hash from user
         let input = Input::new()
@@ -41,9 +176,13 @@ pub async fn check_transaction_status() -> Result<()> {
 
         // Create and execute the transaction status command
         let cmd = TxCommand {
-            tx_hash: tx_hash.clone(),
+            action: None,
+            tx_hash: Some(tx_hash.clone()),
             testnet: is_testnet,
             api_key: None, // Will use the configured API key
+            follow: false,
+            confirmations: None,
+            timeout: 600,
         };
 
         println!("\n{}", style("⏳ Fetching transaction status...").dim());
@@ -1,4 +1,8 @@
+use crate::commands::receive::ReceiveCommand;
+use crate::commands::tokens::TokenRegistry;
 use crate::commands::wallet::{WalletAction, WalletCommand};
+use crate::types::wallet::WalletData;
+use crate::utils::mnemonic::{self, MnemonicLanguage};
 use anyhow::Result;
 use console::style;
 
@@ -13,6 +17,13 @@ pub async fn wallet_menu() -> Result<()> {
             String::from("✏️ Rename Wallet"),
             String::from("💾 Backup Wallet"),
             String::from("🗑️ Delete Wallet"),
+            String::from("🔁 Rotate Key"),
+            String::from("🧯 Recovery Drill"),
+            String::from("🔐 Add Trezor Wallet"),
+            String::from("🔍 Verify Address on Device"),
+            String::from("➕ Add Account"),
+            String::from("📥 Receive Funds"),
+            String::from("✍️ Sign Message"),
             String::from("🏠 Back to Main Menu"),
         ];
 
@@ -28,6 +39,13 @@ pub async fn wallet_menu() -> Result<()> {
             "✏️ Rename Wallet" => rename_wallet().await,
             "💾 Backup Wallet" => backup_wallet().await,
             "🗑️ Delete Wallet" => delete_wallet().await,
+            "🔁 Rotate Key" => rotate_key().await,
+            "🧯 Recovery Drill" => super::recovery_drill::recovery_drill().await,
+            "🔐 Add Trezor Wallet" => add_trezor().await,
+            "🔍 Verify Address on Device" => verify_address().await,
+            "➕ Add Account" => add_account().await,
+            "📥 Receive Funds" => receive_funds().await,
+            "✍️ Sign Message" => sign_message().await,
             _ => break,
         };
 
@@ -39,7 +57,7 @@ pub async fn wallet_menu() -> Result<()> {
 }
 
 /// Creates a new wallet with the given name and prompts for a password
-async fn create_wallet() -> Result<()> {
+pub(super) async fn create_wallet() -> Result<()> {
     println!("\n{}", style("🆕 Create New Wallet").bold());
     println!("{}", "=".repeat(30));
 
@@ -82,26 +100,129 @@ pub async fn create_wallet_with_name(name: &str) -> Result<()> {
         .with_formatter(&|_| String::from("✓ Password set"))
         .prompt()?;
 
+    let kinds = vec![
+        "🧩 Create from new mnemonic (recommended)",
+        "🔑 Private key only",
+    ];
+    let kind = inquire::Select::new("How should the key be backed up?", kinds).prompt()?;
+
+    if kind == "🔑 Private key only" {
+        println!(
+            "\n{}",
+            style("⏳ Creating your wallet. This may take a few seconds...").dim()
+        );
+
+        let cmd = WalletCommand {
+            action: WalletAction::Create {
+                name: name.to_string(),
+                password: password.clone(),
+            },
+        };
+
+        cmd.execute().await?;
+        return Ok(());
+    }
+
+    let language =
+        inquire::Select::new("Recovery phrase language:", MnemonicLanguage::ALL.to_vec())
+            .with_help_message("English phrases are accepted by almost every wallet")
+            .prompt()?;
+    if let Some(warning) = language.compatibility_warning() {
+        println!("\n{} {}", style("⚠️").yellow(), style(warning).yellow());
+        if !inquire::Confirm::new("Use this wordlist anyway?")
+            .with_default(false)
+            .prompt()?
+        {
+            return Ok(());
+        }
+    }
+    let words = inquire::Select::new("Number of words:", vec![12, 24]).prompt()?;
+
     println!(
         "\n{}",
         style("⏳ Creating your wallet. This may take a few seconds...").dim()
     );
 
     let cmd = WalletCommand {
-        action: WalletAction::Create {
+        action: WalletAction::CreateWithMnemonic {
             name: name.to_string(),
             password: password.clone(),
+            language,
+            words,
         },
     };
+    let phrase = cmd.create_wallet_with_mnemonic(name, &password, language, words)?;
 
-    cmd.execute().await?;
-    Ok(())
+    while !inquire::Confirm::new("Have you written down your recovery phrase?")
+        .with_default(false)
+        .prompt()?
+    {
+        println!(
+            "{}",
+            style(
+                "Without the phrase you cannot recover this wallet if the password or file is lost."
+            )
+            .dim()
+        );
+    }
+
+    // The phrase is shown once; clear it before checking it was written down
+    console::Term::stdout().clear_screen()?;
+    loop {
+        if phrase_quiz(&phrase)? {
+            println!("{}", style("✅ Recovery phrase confirmed").green());
+            break;
+        }
+        if !inquire::Confirm::new("Try again?")
+            .with_default(true)
+            .prompt()?
+        {
+            println!(
+                "{}",
+                style(
+                    "⚠️  The phrase was not confirmed. Check your copy before funding this wallet."
+                )
+                .yellow()
+            );
+            break;
+        }
+    }
+    // Top up only once the phrase is no longer on screen
+    cmd.sponsor(name).await
+}
+
+/// Ask for a few words of the phrase by position
+fn phrase_quiz(phrase: &str) -> Result<bool> {
+    let word_count = phrase.split_whitespace().count();
+    println!(
+        "\n{}",
+        style("Enter the requested words from your written copy.").dim()
+    );
+    let mut correct = true;
+    for position in mnemonic::quiz_positions(word_count, 3) {
+        let answer = inquire::Text::new(&format!("Word #{}:", position)).prompt()?;
+        if !mnemonic::quiz_answer_matches(phrase, position, &answer) {
+            println!(
+                "{}",
+                style(format!("❌ Word #{} is not correct", position)).red()
+            );
+            correct = false;
+        }
+    }
+    Ok(correct)
 }
 
-async fn import_wallet() -> Result<()> {
+pub(super) async fn import_wallet() -> Result<()> {
     println!("\n{}", style("📤 Import Wallet").bold().blue());
     println!("{}", "-".repeat(30));
 
+    let sources = vec!["🔑 Private key", "🧩 Recovery phrase"];
+    if inquire::Select::new("What would you like to import?", sources).prompt()?
+        == "🧩 Recovery phrase"
+    {
+        return import_mnemonic().await;
+    }
+
     println!(
         "\n{}",
         style("Please enter the private key of the wallet you want to import.").dim()
@@ -163,7 +284,7 @@ async fn list_wallets() -> Result<()> {
     cmd.execute().await
 }
 
-async fn switch_wallet() -> Result<()> {
+pub(super) async fn switch_wallet() -> Result<()> {
     println!("\n{}", style("🔄 Switch Wallet").bold());
     println!("{}", "=".repeat(30));
 
@@ -226,7 +347,7 @@ async fn rename_wallet() -> Result<()> {
     Ok(())
 }
 
-async fn backup_wallet() -> Result<()> {
+pub(super) async fn backup_wallet() -> Result<()> {
     use std::path::PathBuf;
 
     println!("\n{}", style("💾 Backup Wallet").bold());
@@ -312,3 +433,284 @@ async fn delete_wallet() -> Result<()> {
 
     Ok(())
 }
+
+async fn rotate_key() -> Result<()> {
+    println!("\n{}", style("🔁 Rotate Key").bold());
+    println!("{}", "=".repeat(30));
+    println!(
+        "{}",
+        style("A new wallet will be created and all RBTC and tracked tokens moved to it.").dim()
+    );
+    println!(
+        "{}",
+        style("The old wallet is kept as watch-only once the transfers complete.").dim()
+    );
+
+    let list_cmd = WalletCommand {
+        action: WalletAction::List,
+    };
+    list_cmd.execute().await?;
+
+    let name = inquire::Text::new("Enter the name of the wallet to rotate:")
+        .with_help_message("Enter the exact name of the wallet to rotate")
+        .prompt()?;
+
+    let password = inquire::Password::new("Password for the old wallet:")
+        .with_display_mode(inquire::PasswordDisplayMode::Masked)
+        .without_confirmation()
+        .prompt()?;
+
+    let new_name = inquire::Text::new("Name for the new wallet:")
+        .with_default(&format!("{} (rotated)", name))
+        .with_help_message("Use the name of an unfinished rotation's wallet to resume it")
+        .prompt()?;
+
+    let new_password = inquire::Password::new("Password for the new wallet:")
+        .with_display_toggle_enabled()
+        .with_display_mode(inquire::PasswordDisplayMode::Masked)
+        .with_custom_confirmation_error_message("The passwords don't match.")
+        .with_custom_confirmation_message("Please confirm your password:")
+        .with_formatter(&|_| String::from("✓ Password set"))
+        .prompt()?;
+
+    let confirmed = inquire::Confirm::new(&format!(
+        "⚠️ Move all funds from '{}' to a new wallet '{}'?",
+        name, new_name
+    ))
+    .with_default(false)
+    .prompt()?;
+
+    if !confirmed {
+        println!("\n{}", style("❌ Rotation cancelled").yellow());
+        return Ok(());
+    }
+
+    let cmd = WalletCommand {
+        action: WalletAction::Rotate {
+            name,
+            password,
+            new_name,
+            new_password,
+        },
+    };
+
+    cmd.execute().await
+}
+
+async fn add_trezor() -> Result<()> {
+    println!("\n{}", style("🔐 Add Trezor Wallet").bold());
+    println!("{}", "=".repeat(30));
+    println!(
+        "{}",
+        style("Connect and unlock your Trezor. The key never leaves the device.").dim()
+    );
+
+    let name = inquire::Text::new("Wallet name:").prompt()?;
+    let account = inquire::CustomType::<u32>::new("Account index:")
+        .with_default(0)
+        .with_help_message("Uses the path m/44'/137'/0'/0/<index>")
+        .prompt()?;
+
+    let cmd = WalletCommand {
+        action: WalletAction::AddTrezor { name, account },
+    };
+    cmd.execute().await
+}
+
+async fn verify_address() -> Result<()> {
+    println!("\n{}", style("🔍 Verify Address on Device").bold());
+    println!("{}", "=".repeat(30));
+    println!(
+        "{}",
+        style("Check the address on the device before sharing it to receive funds.").dim()
+    );
+
+    let list_cmd = WalletCommand {
+        action: WalletAction::List,
+    };
+    list_cmd.execute().await?;
+
+    let name = inquire::Text::new("Enter the name of the hardware wallet:").prompt()?;
+    let cmd = WalletCommand {
+        action: WalletAction::VerifyAddress { name },
+    };
+    cmd.execute().await
+}
+
+pub(super) async fn add_account() -> Result<()> {
+    println!("\n{}", style("➕ Add Account").bold());
+    println!("{}", "=".repeat(30));
+    println!(
+        "{}",
+        style("Derives the next address from a wallet's recovery phrase (m/44'/137'/0'/0/N).")
+            .dim()
+    );
+
+    let list_cmd = WalletCommand {
+        action: WalletAction::List,
+    };
+    list_cmd.execute().await?;
+
+    let name = inquire::Text::new("Enter the name of the wallet:").prompt()?;
+    let label = inquire::Text::new("Name for the new account (optional):").prompt()?;
+    let password = inquire::Password::new("Wallet password:")
+        .with_display_mode(inquire::PasswordDisplayMode::Masked)
+        .without_confirmation()
+        .prompt()?;
+
+    let cmd = WalletCommand {
+        action: WalletAction::AddAccount {
+            name,
+            password,
+            label: Some(label.trim().to_string()).filter(|label| !label.is_empty()),
+        },
+    };
+    cmd.execute().await
+}
+
+/// Show a payment request QR code that a mobile wallet can scan to prefill
+/// the amount and token
+pub(super) async fn receive_funds() -> Result<()> {
+    let account = select_account()?;
+
+    let network = crate::config::ConfigManager::new()?
+        .load()?
+        .default_network
+        .to_string()
+        .to_lowercase();
+    let mut tokens = TokenRegistry::load()
+        .unwrap_or_default()
+        .list_tokens(Some(&network));
+    tokens.insert(
+        0,
+        (
+            "RBTC (Native)".to_string(),
+            crate::commands::tokens::TokenInfo {
+                symbol: "RBTC".to_string(),
+                address: "0x0000000000000000000000000000000000000000".to_string(),
+                decimals: 18,
+            },
+        ),
+    );
+    let names: Vec<String> = tokens.iter().map(|(name, _)| name.clone()).collect();
+    let choice = inquire::Select::new("Token to receive:", names).raw_prompt()?;
+    let (symbol, token) = &tokens[choice.index];
+
+    let value = inquire::CustomType::<f64>::new(&format!("Amount of {} to request:", symbol))
+        .with_help_message("Leave empty to let the sender choose the amount")
+        .prompt_skippable()?;
+    let output = inquire::Text::new("Save the QR code as PNG (optional):")
+        .with_help_message("e.g. receive.png; leave empty to only show it here")
+        .prompt_skippable()?
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .map(std::path::PathBuf::from);
+
+    ReceiveCommand {
+        value,
+        token: (choice.index > 0).then(|| token.address.clone()),
+        account,
+        output,
+    }
+    .execute()
+    .await
+}
+
+/// Sign a text message with personal_sign to prove ownership of an address
+pub(super) async fn sign_message() -> Result<()> {
+    let account = select_account()?;
+    let message = inquire::Text::new("Message to sign:")
+        .with_help_message(
+            "Paste the exact text you were asked to sign; use `sign message --file` for multi-line text",
+        )
+        .prompt()?;
+    if message.is_empty() {
+        return Err(anyhow::anyhow!("Nothing to sign"));
+    }
+
+    println!("\n{}\n{}\n", style("Message:").bold(), message);
+    let signed = crate::commands::sign::sign_message(&message, account.unwrap_or(0)).await?;
+    println!("{}: 0x{:x}", style("Address").bold(), signed.address);
+    println!("{}: {}", style("Signature").bold(), signed.signature_hex());
+    Ok(())
+}
+
+/// Let the user pick an account when the current wallet has derived ones.
+/// Returns `None` for the wallet's own address.
+pub fn select_account() -> Result<Option<u32>> {
+    let wallet_data = WalletData::load()?;
+    let Some(wallet) = wallet_data.get_current_wallet() else {
+        return Ok(None);
+    };
+    if wallet.accounts.is_empty() {
+        return Ok(None);
+    }
+
+    let mut options = vec![format!("#0 {} (0x{:x})", wallet.name, wallet.address)];
+    options.extend(wallet.accounts.iter().map(|account| {
+        format!(
+            "#{} {} (0x{:x})",
+            account.index, account.name, account.address
+        )
+    }));
+    let choice = inquire::Select::new("Account:", options).raw_prompt()?;
+    Ok(match choice.index {
+        0 => None,
+        index => Some(wallet.accounts[index - 1].index),
+    })
+}
+
+async fn import_mnemonic() -> Result<()> {
+    let phrase = inquire::Password::new("Recovery phrase:")
+        .with_display_toggle_enabled()
+        .with_display_mode(inquire::PasswordDisplayMode::Masked)
+        .with_help_message("Words separated by spaces; the language is detected automatically")
+        .without_confirmation()
+        .prompt()?;
+
+    let language = match mnemonic::detect_languages(&phrase).as_slice() {
+        [] => {
+            println!(
+                "\n{}",
+                style("❌ This is not a valid recovery phrase in any supported wordlist.").red()
+            );
+            println!(
+                "{}",
+                style("Check the spelling, word order and number of words.").dim()
+            );
+            return Ok(());
+        }
+        [language] => {
+            println!("\nDetected {} wordlist", style(language).cyan());
+            *language
+        }
+        languages => inquire::Select::new(
+            "The phrase is valid in several wordlists. Which one was it created with?",
+            languages.to_vec(),
+        )
+        .prompt()?,
+    };
+
+    let name = inquire::Text::new("Wallet name:")
+        .with_help_message("A name to identify this wallet in the app")
+        .prompt()?;
+
+    let password = inquire::Password::new("Enter password:")
+        .with_display_toggle_enabled()
+        .with_display_mode(inquire::PasswordDisplayMode::Masked)
+        .with_custom_confirmation_error_message("The passwords don't match.")
+        .with_custom_confirmation_message("Please confirm your password:")
+        .with_formatter(&|_| String::from("✓ Password set"))
+        .prompt()?;
+
+    let cmd = WalletCommand {
+        action: WalletAction::ImportMnemonic {
+            phrase,
+            name,
+            password,
+            language: Some(language),
+        },
+    };
+    cmd.execute().await?;
+    Ok(())
+}
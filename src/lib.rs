@@ -7,5 +7,9 @@ pub mod commands;
 pub mod config;
 pub mod interactive;
 pub mod qr;
+pub mod security;
+#[cfg(feature = "test-harness")]
+pub mod testing;
+pub mod tui;
 pub mod types;
 pub mod utils;
@@ -1,39 +1,102 @@
 #![allow(warnings)]
 use anyhow::{Result, anyhow};
+use clap::Parser;
 use dotenv::dotenv;
-use std::env;
 
 mod api;
 mod commands;
 mod config;
 mod interactive;
+mod qr;
+mod security;
 mod setup;
+#[cfg(feature = "test-harness")]
+mod testing;
+mod tui;
 mod types;
 mod utils;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Check if any command line arguments were provided
-    if env::args().count() > 1 {
-        eprintln!("This program only runs in interactive mode. Please run without any arguments.");
-        eprintln!("Usage: cargo run");
-        std::process::exit(1);
-    }
-
     // Initialize logging
     env_logger::init();
 
     // Load environment variables from .env file if it exists
     dotenv().ok();
 
+    // Run a single command when one is given, otherwise start the menus
+    let cli = commands::root::Cli::parse();
+    if cli.redact {
+        utils::redact::set_active(true);
+    }
+    let profile = cli
+        .profile
+        .clone()
+        .or_else(|| std::env::var(config::profile::PROFILE_ENV).ok())
+        .filter(|name| !name.is_empty());
+    if let Some(name) = &profile
+        && let Err(e) = config::profile::set_active(name)
+    {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    if cli.json
+        && (cli.command.is_some() || cli.batch.is_some())
+        && let Err(e) = utils::output::enable_json()
+    {
+        eprintln!("Error: Failed to set up JSON output: {}", e);
+        std::process::exit(1);
+    }
+    if let Some(path) = &cli.batch {
+        // A batch keeps its wallets unlocked until it ends
+        security::session::lock_on_interrupt();
+        let result = match cli.command {
+            Some(_) => Err(anyhow!("Give either a command or --batch, not both")),
+            None => match commands::batch::load(path) {
+                Ok(batch) => commands::batch::run(&batch, cli.continue_on_error).await,
+                Err(e) => Err(e),
+            },
+        };
+        utils::event_webhooks::flush().await;
+        security::session::lock_all();
+        if let Err(e) = result {
+            eprintln!("Error: {}", e);
+            let _ = utils::output::emit(&serde_json::json!({ "error": e.to_string() }));
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if let Some(command) = cli.command {
+        let result = command.execute().await;
+        utils::event_webhooks::flush().await;
+        security::session::lock_all();
+        if let Err(e) = result {
+            eprintln!("Error: {}", e);
+            let _ = utils::output::emit(&serde_json::json!({ "error": e.to_string() }));
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Ask which profile to use when there is more than one
+    if profile.is_none()
+        && let Err(e) = interactive::select_profile()
+    {
+        eprintln!("Failed to select a profile: {}", e);
+        std::process::exit(1);
+    }
+
     // Ensure wallet is configured
     if let Err(e) = setup::ensure_configured().await {
         eprintln!("Failed to configure wallet: {}", e);
         std::process::exit(1);
     }
 
-    // Start the interactive interface
-    interactive::start().await?;
-
-    Ok(())
+    // Start the interactive interface. Ctrl-C at a prompt comes back as an
+    // error rather than a signal, so the keys are also locked on the way out
+    security::session::lock_on_interrupt();
+    let result = interactive::start().await;
+    utils::event_webhooks::flush().await;
+    security::session::lock_all();
+    result
 }
@@ -1,5 +1,157 @@
+//! QR codes for addresses and EIP-681 payment requests.
+
+use alloy::primitives::{Address, U256};
+use anyhow::{Context, anyhow};
 use image::{ImageBuffer, Luma};
+use qrcode::render::unicode::Dense1x2;
 use qrcode::{EcLevel, QrCode};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// How long each frame of an animated QR code stays on screen
+const FRAME_INTERVAL: Duration = Duration::from_millis(800);
+
+/// An EIP-681 payment request: who to pay on which chain and, optionally,
+/// how much of which token. Amounts are in base units (wei).
+#[derive(Debug, Clone)]
+pub struct PaymentRequest {
+    pub recipient: Address,
+    pub chain_id: u64,
+    pub value: Option<U256>,
+    /// ERC-20 contract; `None` requests RBTC
+    pub token: Option<Address>,
+}
+
+impl PaymentRequest {
+    /// The `ethereum:` URI. RBTC requests use `value`; token requests call
+    /// the token's `transfer` function, the form mobile wallets prefill.
+    pub fn uri(&self) -> String {
+        let recipient = self.recipient.to_checksum(None);
+        match self.token {
+            None => {
+                let mut uri = format!("ethereum:{}@{}", recipient, self.chain_id);
+                if let Some(value) = self.value {
+                    uri.push_str(&format!("?value={}", value));
+                }
+                uri
+            }
+            Some(token) => {
+                let mut uri = format!(
+                    "ethereum:{}@{}/transfer?address={}",
+                    token.to_checksum(None),
+                    self.chain_id,
+                    recipient
+                );
+                if let Some(value) = self.value {
+                    uri.push_str(&format!("&uint256={}", value));
+                }
+                uri
+            }
+        }
+    }
+
+    /// Parse a scanned `ethereum:` URI, or a bare address. Requests that do
+    /// not name a chain are taken to be for `default_chain_id`.
+    pub fn parse(text: &str, default_chain_id: u64) -> anyhow::Result<Self> {
+        let text = text.trim();
+        let Some(rest) = text.strip_prefix("ethereum:") else {
+            let recipient = Address::from_str(text)
+                .map_err(|_| anyhow!("Not an address or payment request: {}", text))?;
+            return Ok(Self {
+                recipient,
+                chain_id: default_chain_id,
+                value: None,
+                token: None,
+            });
+        };
+        let rest = rest.strip_prefix("pay-").unwrap_or(rest);
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let (target, function) = match path.split_once('/') {
+            Some((target, function)) => (target, Some(function)),
+            None => (path, None),
+        };
+        let (target, chain_id) = match target.split_once('@') {
+            Some((target, chain)) => (
+                target,
+                chain
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid chain id in payment request: {}", chain))?,
+            ),
+            None => (target, default_chain_id),
+        };
+        let target = Address::from_str(target)
+            .map_err(|_| anyhow!("Invalid address in payment request: {}", target))?;
+        let param = |name: &str| {
+            query
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value)
+        };
+
+        match function {
+            None => Ok(Self {
+                recipient: target,
+                chain_id,
+                value: param("value").map(parse_number).transpose()?,
+                token: None,
+            }),
+            Some("transfer") => {
+                let recipient = param("address")
+                    .ok_or_else(|| anyhow!("The token payment request has no recipient"))?;
+                Ok(Self {
+                    recipient: Address::from_str(recipient)
+                        .map_err(|_| anyhow!("Invalid recipient address: {}", recipient))?,
+                    chain_id,
+                    value: param("uint256").map(parse_number).transpose()?,
+                    token: Some(target),
+                })
+            }
+            Some(function) => Err(anyhow!(
+                "Unsupported payment request function: {}",
+                function
+            )),
+        }
+    }
+}
+
+/// An EIP-681 number: an integer, or a decimal with an exponent such as
+/// `2.014e18`
+fn parse_number(value: &str) -> anyhow::Result<U256> {
+    let parsed = match value.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => exponent
+            .parse::<u8>()
+            .ok()
+            .and_then(|exponent| alloy::primitives::utils::parse_units(mantissa, exponent).ok())
+            .map(Into::into),
+        None => U256::from_str_radix(value, 10).ok(),
+    };
+    parsed.ok_or_else(|| anyhow!("Invalid amount in payment request: {}", value))
+}
+
+/// Decode the first QR code found in a PNG or JPEG image
+pub fn scan_image(path: &Path) -> anyhow::Result<String> {
+    let image = image::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?
+        .to_luma8();
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        image.width() as usize,
+        image.height() as usize,
+        |x, y| image.get_pixel(x as u32, y as u32).0[0],
+    );
+    let grid = prepared
+        .detect_grids()
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("No QR code found in {}", path.display()))?;
+    let (_, content) = grid
+        .decode()
+        .map_err(|e| anyhow!("Failed to read the QR code: {}", e))?;
+    Ok(content)
+}
 
 pub fn generate_qr_code(data: &str, path: &str) -> Result<(), Box<dyn std::error::Error>> {
     let code = QrCode::with_error_correction_level(data.as_bytes(), EcLevel::M)?;
@@ -8,3 +160,116 @@ pub fn generate_qr_code(data: &str, path: &str) -> Result<(), Box<dyn std::error
     image.save(path)?;
     Ok(())
 }
+
+/// Render `data` as a QR code of unicode blocks for the terminal. Colours
+/// are inverted so the code scans on a dark background.
+pub fn render_terminal(data: &str) -> anyhow::Result<String> {
+    let code = QrCode::with_error_correction_level(data.as_bytes(), EcLevel::M)?;
+    Ok(code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build())
+}
+
+/// Show `frames` as QR codes in turn, looping until Enter is pressed, for
+/// payloads too large for a single code. A single frame is shown still.
+pub fn show_animated(frames: &[String]) -> anyhow::Result<()> {
+    let rendered = frames
+        .iter()
+        .map(|frame| render_terminal(frame))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    if rendered.len() == 1 {
+        println!("\n{}", rendered[0]);
+        return Ok(());
+    }
+
+    let done = Arc::new(AtomicBool::new(false));
+    let stop = done.clone();
+    std::thread::spawn(move || {
+        let _ = std::io::stdin().read_line(&mut String::new());
+        stop.store(true, Ordering::Relaxed);
+    });
+
+    let term = console::Term::stdout();
+    for (i, code) in rendered.iter().enumerate().cycle() {
+        if done.load(Ordering::Relaxed) {
+            break;
+        }
+        term.clear_screen()?;
+        println!("{}", code);
+        println!(
+            "Frame {}/{}. Press Enter once every frame has been scanned.",
+            i + 1,
+            rendered.len()
+        );
+        std::thread::sleep(FRAME_INTERVAL);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payment_request_uri() {
+        let recipient = Address::repeat_byte(0xab);
+        let mut request = PaymentRequest {
+            recipient,
+            chain_id: 30,
+            value: None,
+            token: None,
+        };
+        assert_eq!(
+            request.uri(),
+            format!("ethereum:{}@30", recipient.to_checksum(None))
+        );
+
+        request.value = Some(U256::from(1_500_000_000_000_000_000u128));
+        assert!(request.uri().ends_with("@30?value=1500000000000000000"));
+
+        request.token = Some(Address::repeat_byte(0x01));
+        assert_eq!(
+            request.uri(),
+            format!(
+                "ethereum:{}@30/transfer?address={}&uint256=1500000000000000000",
+                Address::repeat_byte(0x01).to_checksum(None),
+                recipient.to_checksum(None)
+            )
+        );
+        assert!(render_terminal(&request.uri()).is_ok());
+    }
+
+    #[test]
+    fn test_parse_scanned_request() {
+        let recipient = Address::repeat_byte(0xab);
+        let request = PaymentRequest {
+            recipient,
+            chain_id: 31,
+            value: Some(U256::from(25)),
+            token: Some(Address::repeat_byte(0x01)),
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("request.png");
+        generate_qr_code(&request.uri(), path.to_str().unwrap()).unwrap();
+        let scanned = PaymentRequest::parse(&scan_image(&path).unwrap(), 30).unwrap();
+        assert_eq!(scanned.recipient, recipient);
+        assert_eq!(scanned.chain_id, 31);
+        assert_eq!(scanned.token, request.token);
+        assert_eq!(scanned.value, request.value);
+
+        let scanned =
+            PaymentRequest::parse(&format!("ethereum:pay-{:#x}?value=2.014e18", recipient), 30)
+                .unwrap();
+        assert_eq!(scanned.chain_id, 30);
+        assert_eq!(
+            scanned.value,
+            Some(U256::from(2_014_000_000_000_000_000u128))
+        );
+
+        let bare = PaymentRequest::parse(&format!("{:#x}", recipient), 30).unwrap();
+        assert_eq!((bare.recipient, bare.value), (recipient, None));
+        assert!(PaymentRequest::parse("ethereum:0x12", 30).is_err());
+    }
+}
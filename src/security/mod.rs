@@ -0,0 +1,6 @@
+//! Secret storage in the platform keyring, unlock sessions and verification
+//! of releases
+
+pub mod secrets;
+pub mod session;
+pub mod updates;
@@ -0,0 +1,100 @@
+//! Wallet passwords and API keys kept in the platform keyring (Keychain on
+//! macOS, the Secret Service on Linux, the Credential Manager on Windows).
+//!
+//! Used only when `use_keyring` is enabled in the config; every lookup
+//! returns `None` otherwise, so callers fall back to prompting or to the
+//! wallet database. The key of the wallet database itself is the exception:
+//! it is always kept here, since the database cannot hold its own key.
+
+use crate::config::ConfigManager;
+use alloy::primitives::Address;
+use anyhow::{Result, anyhow};
+use keyring::Entry;
+
+const SERVICE: &str = "rootstock-wallet";
+const ALCHEMY_ACCOUNT: &str = "api-key:alchemy";
+const DATABASE_ACCOUNT: &str = "database-key";
+
+/// Whether the keyring is enabled in the saved config
+pub fn is_enabled() -> bool {
+    ConfigManager::new()
+        .and_then(|manager| manager.load())
+        .map(|config| config.use_keyring)
+        .unwrap_or(false)
+}
+
+fn entry(account: &str) -> Result<Entry> {
+    Entry::new(SERVICE, account).map_err(|e| anyhow!("Keyring unavailable: {}", e))
+}
+
+fn wallet_account(address: &Address) -> String {
+    format!("wallet:{:#x}", address)
+}
+
+fn get(account: &str) -> Result<Option<String>> {
+    if !is_enabled() {
+        return Ok(None);
+    }
+    read(account)
+}
+
+fn read(account: &str) -> Result<Option<String>> {
+    match entry(account)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(anyhow!("Failed to read from the keyring: {}", e)),
+    }
+}
+
+fn set(account: &str, secret: &str) -> Result<()> {
+    entry(account)?
+        .set_password(secret)
+        .map_err(|e| anyhow!("Failed to write to the keyring: {}", e))
+}
+
+fn delete(account: &str) -> Result<()> {
+    if !is_enabled() {
+        return Ok(());
+    }
+    match entry(account)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(anyhow!("Failed to remove from the keyring: {}", e)),
+    }
+}
+
+pub fn wallet_password(address: &Address) -> Result<Option<String>> {
+    get(&wallet_account(address))
+}
+
+/// Remember a password that just unlocked the wallet, if the keyring is enabled
+pub fn remember_wallet_password(address: &Address, password: &str) -> Result<()> {
+    if !is_enabled() {
+        return Ok(());
+    }
+    set(&wallet_account(address), password)
+}
+
+pub fn forget_wallet_password(address: &Address) -> Result<()> {
+    delete(&wallet_account(address))
+}
+
+pub fn alchemy_api_key() -> Result<Option<String>> {
+    get(ALCHEMY_ACCOUNT)
+}
+
+pub fn set_alchemy_api_key(key: &str) -> Result<()> {
+    set(ALCHEMY_ACCOUNT, key)
+}
+
+pub fn forget_alchemy_api_key() -> Result<()> {
+    delete(ALCHEMY_ACCOUNT)
+}
+
+/// Key of the wallet database, whether or not `use_keyring` is enabled
+pub fn database_key() -> Result<Option<String>> {
+    read(DATABASE_ACCOUNT)
+}
+
+pub fn set_database_key(key: &str) -> Result<()> {
+    set(DATABASE_ACCOUNT, key)
+}
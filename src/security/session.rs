@@ -0,0 +1,168 @@
+//! Unlock sessions that spare re-entering the wallet password.
+//!
+//! After a wallet is unlocked, the keys derived from its password are kept
+//! in memory for the configured number of minutes, counted from that first
+//! unlock. The keys are zeroized when the session expires, when the wallet
+//! is locked from the menu, and by `lock_all` when the menus are left or
+//! the process is interrupted with Ctrl-C. The sessions live in a static
+//! that is never dropped, so a path that ends the process without calling
+//! `lock_all` leaves clearing the memory to the OS. Sessions only last as
+//! long as the process, so they mostly help in the interactive menus and in
+//! batches, where one unlock lasts for the whole batch.
+
+use crate::config::ConfigManager;
+use alloy::primitives::Address;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
+
+static SESSIONS: Mutex<Option<HashMap<Address, Session>>> = Mutex::new(None);
+/// Set while a batch runs, so its commands share one unlock
+static UNTIL_EXIT: AtomicBool = AtomicBool::new(false);
+/// Session length that outlives any process
+const PROCESS_LIFETIME: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// Keys derived from a wallet password with scrypt
+pub struct DerivedKeys {
+    /// Decrypts the private key
+    pub key: Zeroizing<[u8; 32]>,
+    /// Decrypts the recovery phrase, for wallets with derived accounts
+    pub seed_key: Option<Zeroizing<[u8; 32]>>,
+}
+
+struct Session {
+    keys: DerivedKeys,
+    expires_at: Instant,
+}
+
+/// How long a wallet stays unlocked; zero disables sessions
+pub fn timeout() -> Duration {
+    if UNTIL_EXIT.load(Ordering::Relaxed) {
+        return PROCESS_LIFETIME;
+    }
+    let minutes = ConfigManager::new()
+        .and_then(|manager| manager.load())
+        .map(|config| config.session_timeout_minutes)
+        .unwrap_or(0);
+    Duration::from_secs(minutes * 60)
+}
+
+fn with_sessions<T>(f: impl FnOnce(&mut HashMap<Address, Session>) -> T) -> T {
+    let mut sessions = SESSIONS.lock().unwrap_or_else(|e| e.into_inner());
+    let sessions = sessions.get_or_insert_with(HashMap::new);
+    let now = Instant::now();
+    sessions.retain(|_, session| session.expires_at > now);
+    f(sessions)
+}
+
+/// Keep the keys of a freshly unlocked wallet, if sessions are enabled
+pub fn start(address: Address, keys: DerivedKeys) {
+    start_for(address, keys, timeout());
+}
+
+fn start_for(address: Address, keys: DerivedKeys, timeout: Duration) {
+    if timeout.is_zero() {
+        return;
+    }
+    with_sessions(|sessions| {
+        sessions.insert(
+            address,
+            Session {
+                keys,
+                expires_at: Instant::now() + timeout,
+            },
+        );
+    });
+}
+
+/// Run `f` with the keys of an unlocked wallet, or return `None` when the
+/// wallet has no live session
+pub fn with_keys<T>(address: &Address, f: impl FnOnce(&DerivedKeys) -> T) -> Option<T> {
+    with_sessions(|sessions| sessions.get(address).map(|session| f(&session.keys)))
+}
+
+/// Time left before the wallet locks again
+pub fn remaining(address: &Address) -> Option<Duration> {
+    with_sessions(|sessions| {
+        sessions
+            .get(address)
+            .map(|session| session.expires_at.saturating_duration_since(Instant::now()))
+    })
+}
+
+/// Keep wallets unlocked until the process exits, whatever the configured
+/// timeout, e.g. while a batch of commands runs
+pub fn keep_until_exit() {
+    UNTIL_EXIT.store(true, Ordering::Relaxed);
+}
+
+/// End the session of one wallet
+pub fn lock(address: &Address) {
+    with_sessions(|sessions| {
+        sessions.remove(address);
+    });
+}
+
+/// End every session, returning how many wallets were unlocked
+pub fn lock_all() -> usize {
+    with_sessions(|sessions| {
+        let count = sessions.len();
+        sessions.clear();
+        count
+    })
+}
+
+/// Lock every wallet and exit when Ctrl-C is pressed, instead of letting
+/// the signal end the process with the keys still in memory
+pub fn lock_on_interrupt() {
+    tokio::spawn(async {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            lock_all();
+            std::process::exit(130);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(byte: u8) -> DerivedKeys {
+        DerivedKeys {
+            key: Zeroizing::new([byte; 32]),
+            seed_key: None,
+        }
+    }
+
+    // One test, since the sessions are shared by the whole process
+    #[test]
+    fn test_unlock_timeout_and_lock() {
+        let first = Address::repeat_byte(0xa1);
+        let second = Address::repeat_byte(0xa2);
+        let expiring = Address::repeat_byte(0xa3);
+        let disabled = Address::repeat_byte(0xa4);
+
+        start_for(first, keys(1), Duration::from_secs(60));
+        start_for(second, keys(2), Duration::from_secs(60));
+        start_for(expiring, keys(3), Duration::from_millis(20));
+        start_for(disabled, keys(4), Duration::ZERO);
+
+        assert_eq!(with_keys(&first, |keys| keys.key[0]), Some(1));
+        assert!(remaining(&first).unwrap() <= Duration::from_secs(60));
+        assert_eq!(with_keys(&expiring, |keys| keys.key[0]), Some(3));
+        assert!(with_keys(&disabled, |_| ()).is_none());
+
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(with_keys(&expiring, |_| ()).is_none());
+        assert!(remaining(&expiring).is_none());
+
+        lock(&first);
+        assert!(with_keys(&first, |_| ()).is_none());
+        assert_eq!(with_keys(&second, |keys| keys.key[0]), Some(2));
+
+        assert!(lock_all() >= 1);
+        assert!(with_keys(&second, |_| ()).is_none());
+    }
+}
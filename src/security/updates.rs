@@ -0,0 +1,261 @@
+//! Release feed checks and verification of downloaded releases.
+//!
+//! Every release publishes a `SHA256SUMS` file listing the checksum of each
+//! binary, and `SHA256SUMS.sig`, a personal_sign signature over that file by
+//! the release key. A release is only reported as verified when the
+//! signature recovers to the release signer built into this binary, and
+//! downloads are only kept when their checksum matches the signed list.
+
+use alloy::primitives::{Address, PrimitiveSignature};
+use anyhow::{Context, Result, anyhow};
+use reqwest::Client;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const RELEASES_URL: &str =
+    "https://api.github.com/repos/cosmasken/rootstock-wallet/releases/latest";
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+const SIGNATURE_ASSET: &str = "SHA256SUMS.sig";
+
+/// Address of the release key, set by the release build
+const RELEASE_SIGNER: Option<&str> = option_env!("ROOTSTOCK_WALLET_RELEASE_SIGNER");
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    pub html_url: String,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+impl Release {
+    pub fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+
+    /// Whether this release is newer than the running version
+    pub fn is_newer_than(&self, current: &str) -> bool {
+        match (parse_version(self.version()), parse_version(current)) {
+            (Some(latest), Some(current)) => latest > current,
+            _ => false,
+        }
+    }
+
+    /// Releases that fix vulnerabilities say so in their title or notes
+    pub fn is_security_release(&self) -> bool {
+        [self.name.as_deref(), self.body.as_deref()]
+            .into_iter()
+            .flatten()
+            .any(|text| {
+                let text = text.to_lowercase();
+                text.contains("security") || text.contains("cve-")
+            })
+    }
+
+    fn asset(&self, name: &str) -> Option<&ReleaseAsset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+
+    /// The binary built for this operating system and architecture
+    pub fn asset_for_platform(&self) -> Option<&ReleaseAsset> {
+        self.assets.iter().find(|asset| {
+            asset.name.contains(std::env::consts::OS)
+                && asset.name.contains(std::env::consts::ARCH)
+                && asset.name != CHECKSUMS_ASSET
+                && asset.name != SIGNATURE_ASSET
+        })
+    }
+}
+
+/// A release whose checksum list was signed by the release key
+#[derive(Debug, Clone)]
+pub struct VerifiedRelease {
+    pub release: Release,
+    pub signer: Address,
+    checksums: HashMap<String, String>,
+}
+
+pub fn release_signer() -> Result<Option<Address>> {
+    RELEASE_SIGNER
+        .map(|signer| {
+            Address::from_str(signer).map_err(|e| anyhow!("Invalid release signer: {}", e))
+        })
+        .transpose()
+}
+
+/// Version as (major, minor, patch), ignoring any pre-release suffix
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    Some((major, minor, patch))
+}
+
+/// Parse `sha256sum` output into file name → lowercase hex checksum
+fn parse_checksums(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (checksum, name) = line.trim().split_once(char::is_whitespace)?;
+            let name = name.trim_start().trim_start_matches('*');
+            (checksum.len() == 64 && !name.is_empty())
+                .then(|| (name.to_string(), checksum.to_lowercase()))
+        })
+        .collect()
+}
+
+/// Check the personal_sign signature over the checksum list
+fn verify_checksums(content: &[u8], signature: &str, signer: Address) -> Result<()> {
+    let signature = PrimitiveSignature::from_str(signature.trim())
+        .map_err(|e| anyhow!("Invalid release signature: {}", e))?;
+    let recovered = signature
+        .recover_address_from_msg(content)
+        .map_err(|e| anyhow!("Failed to recover release signer: {}", e))?;
+    if recovered != signer {
+        return Err(anyhow!(
+            "Release checksums were signed by 0x{:x}, not by the release key 0x{:x}",
+            recovered,
+            signer
+        ));
+    }
+    Ok(())
+}
+
+pub fn http_client() -> Result<Client> {
+    crate::utils::http::with_proxy(Client::builder())?
+        .user_agent(concat!("rootstock-wallet/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .context("Failed to create HTTP client")
+}
+
+async fn fetch_bytes(client: &Client, url: &str) -> Result<Vec<u8>> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch {}", url))?
+        .error_for_status()?;
+    Ok(response.bytes().await?.to_vec())
+}
+
+/// Latest published release from the project's release feed
+pub async fn latest_release(client: &Client) -> Result<Release> {
+    client
+        .get(RELEASES_URL)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .context("Failed to reach the release feed")?
+        .error_for_status()
+        .context("The release feed returned an error")?
+        .json()
+        .await
+        .context("Failed to parse the release feed")
+}
+
+/// Download the release's checksum list and check it was signed by the
+/// release key
+pub async fn verify_release(client: &Client, release: Release) -> Result<VerifiedRelease> {
+    let signer = release_signer()?.ok_or_else(|| {
+        anyhow!("This build has no release signing key, so releases cannot be verified")
+    })?;
+    let checksums_asset = release
+        .asset(CHECKSUMS_ASSET)
+        .ok_or_else(|| anyhow!("Release {} has no {}", release.tag_name, CHECKSUMS_ASSET))?;
+    let signature_asset = release
+        .asset(SIGNATURE_ASSET)
+        .ok_or_else(|| anyhow!("Release {} is not signed", release.tag_name))?;
+
+    let content = fetch_bytes(client, &checksums_asset.browser_download_url).await?;
+    let signature = fetch_bytes(client, &signature_asset.browser_download_url).await?;
+    verify_checksums(&content, &String::from_utf8_lossy(&signature), signer)?;
+
+    let checksums = parse_checksums(&String::from_utf8_lossy(&content));
+    Ok(VerifiedRelease {
+        release,
+        signer,
+        checksums,
+    })
+}
+
+impl VerifiedRelease {
+    /// Download an asset into `dir`, keeping it only if its checksum matches
+    /// the signed list
+    pub async fn download(
+        &self,
+        client: &Client,
+        asset: &ReleaseAsset,
+        dir: &Path,
+    ) -> Result<PathBuf> {
+        let expected = self
+            .checksums
+            .get(&asset.name)
+            .ok_or_else(|| anyhow!("{} is not listed in the signed checksums", asset.name))?;
+        let bytes = fetch_bytes(client, &asset.browser_download_url).await?;
+        let actual = hex::encode(Sha256::digest(&bytes));
+        if &actual != expected {
+            return Err(anyhow!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                asset.name,
+                expected,
+                actual
+            ));
+        }
+
+        fs::create_dir_all(dir)?;
+        let path = dir.join(&asset.name);
+        fs::write(&path, bytes).with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::signers::{SignerSync, local::PrivateKeySigner};
+
+    #[test]
+    fn test_versions_and_checksums() {
+        assert!(parse_version("0.2.0") > parse_version("0.1.9"));
+        assert_eq!(parse_version("1.4"), Some((1, 4, 0)));
+        assert_eq!(parse_version("1.0.0-rc.1"), Some((1, 0, 0)));
+        assert_eq!(parse_version("latest"), None);
+
+        let checksum = "ab".repeat(32);
+        let checksums = parse_checksums(&format!(
+            "{}  rootstock-wallet-linux-x86_64\n{} *rootstock-wallet-windows-x86_64.exe\nbad line\n",
+            checksum,
+            checksum.to_uppercase()
+        ));
+        assert_eq!(checksums.len(), 2);
+        assert_eq!(checksums["rootstock-wallet-windows-x86_64.exe"], checksum);
+    }
+
+    #[test]
+    fn test_verify_checksums() {
+        let release_key = PrivateKeySigner::random();
+        let content = b"abcd  rootstock-wallet-linux-x86_64\n";
+        let signature = release_key.sign_message_sync(content).unwrap();
+        let signature = format!("0x{}", hex::encode(signature.as_bytes()));
+
+        verify_checksums(content, &signature, release_key.address()).unwrap();
+        assert!(verify_checksums(content, &signature, Address::repeat_byte(1)).is_err());
+        assert!(verify_checksums(b"tampered", &signature, release_key.address()).is_err());
+    }
+}
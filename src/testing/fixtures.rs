@@ -0,0 +1,119 @@
+//! Deterministic fixtures shared by the mock RPC server and tests.
+
+use alloy::primitives::{Address, B256, address, b256};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+/// Well-known development key (never use it with real funds)
+pub const PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+pub const WALLET_ADDRESS: Address = address!("f39fd6e51aad88f6f4ce6ab8827279cfffb92266");
+pub const RECIPIENT_ADDRESS: Address = address!("70997970c51812dc3a010c7d01b50e0d17dc79c8");
+pub const TX_HASH: B256 =
+    b256!("0293c59578303f3dc88daeda6c8564fd39b612dd85d7a1e025a37e611dc5b900");
+pub const BLOCK_HASH: B256 =
+    b256!("1111111111111111111111111111111111111111111111111111111111111111");
+pub const CHAIN_ID: u64 = 31;
+/// Timestamp of every block the mock server returns
+pub const BLOCK_TIMESTAMP: u64 = 0x65929c40;
+
+/// Canned `alchemy_getAssetTransfers` result with one outgoing RBTC transfer
+pub fn asset_transfers() -> Value {
+    json!({
+        "transfers": [{
+            "blockNum": "0x10",
+            "uniqueId": format!("{:#x}:external", TX_HASH),
+            "hash": format!("{:#x}", TX_HASH),
+            "from": format!("{:#x}", WALLET_ADDRESS),
+            "to": format!("{:#x}", RECIPIENT_ADDRESS),
+            "value": 1,
+            "asset": "RBTC",
+            "category": "external",
+            "rawContract": { "value": "0xde0b6b3a7640000", "address": null, "decimal": "0x12" },
+            "metadata": { "blockTimestamp": "2024-01-01T12:00:00.000Z" }
+        }]
+    })
+}
+
+pub fn transaction_receipt() -> Value {
+    json!({
+        "transactionHash": format!("{:#x}", TX_HASH),
+        "transactionIndex": "0x0",
+        "blockHash": format!("{:#x}", BLOCK_HASH),
+        "blockNumber": "0x10",
+        "from": format!("{:#x}", WALLET_ADDRESS),
+        "to": format!("{:#x}", RECIPIENT_ADDRESS),
+        "cumulativeGasUsed": "0x5208",
+        "gasUsed": "0x5208",
+        "effectiveGasPrice": "0x3b9aca00",
+        "contractAddress": null,
+        "logs": [],
+        "logsBloom": format!("0x{}", "0".repeat(512)),
+        "type": "0x0",
+        "status": "0x1"
+    })
+}
+
+pub fn transaction() -> Value {
+    json!({
+        "hash": format!("{:#x}", TX_HASH),
+        "nonce": "0x0",
+        "blockHash": format!("{:#x}", BLOCK_HASH),
+        "blockNumber": "0x10",
+        "transactionIndex": "0x0",
+        "from": format!("{:#x}", WALLET_ADDRESS),
+        "to": format!("{:#x}", RECIPIENT_ADDRESS),
+        "value": "0xde0b6b3a7640000",
+        "gas": "0x5208",
+        "gasPrice": "0x3b9aca00",
+        "input": "0x",
+        "v": "0x62",
+        "r": "0x1",
+        "s": "0x1",
+        "type": "0x0",
+        "chainId": format!("{:#x}", CHAIN_ID)
+    })
+}
+
+pub fn block() -> Value {
+    json!({
+        "number": "0x10",
+        "hash": format!("{:#x}", BLOCK_HASH),
+        "parentHash": format!("{:#x}", B256::ZERO),
+        "sha3Uncles": format!("{:#x}", B256::ZERO),
+        "miner": format!("{:#x}", Address::ZERO),
+        "stateRoot": format!("{:#x}", B256::ZERO),
+        "transactionsRoot": format!("{:#x}", B256::ZERO),
+        "receiptsRoot": format!("{:#x}", B256::ZERO),
+        "logsBloom": format!("0x{}", "0".repeat(512)),
+        "difficulty": "0x1",
+        "gasLimit": "0x67c280",
+        "gasUsed": "0x5208",
+        "extraData": "0x",
+        "mixHash": format!("{:#x}", B256::ZERO),
+        "nonce": "0x0000000000000000",
+        "timestamp": format!("{:#x}", BLOCK_TIMESTAMP),
+        "uncles": [],
+        "transactions": [format!("{:#x}", TX_HASH)]
+    })
+}
+
+/// Results for every JSON-RPC method the command paths use
+pub fn default_responses() -> HashMap<String, Value> {
+    HashMap::from([
+        ("alchemy_getAssetTransfers".to_string(), asset_transfers()),
+        ("eth_getTransactionReceipt".to_string(), transaction_receipt()),
+        ("eth_getTransactionByHash".to_string(), transaction()),
+        ("eth_getBlockByNumber".to_string(), block()),
+        ("eth_sendTransaction".to_string(), json!(format!("{:#x}", TX_HASH))),
+        ("eth_sendRawTransaction".to_string(), json!(format!("{:#x}", TX_HASH))),
+        ("eth_getTransactionCount".to_string(), json!("0x0")),
+        ("eth_gasPrice".to_string(), json!("0x3b9aca00")),
+        ("eth_getBalance".to_string(), json!("0xde0b6b3a7640000")),
+        ("eth_chainId".to_string(), json!(format!("{:#x}", CHAIN_ID))),
+        ("eth_estimateGas".to_string(), json!("0x5208")),
+        // One zero word: calls succeed, and token balances read as zero
+        ("eth_call".to_string(), json!(format!("0x{}", "0".repeat(64)))),
+        ("eth_blockNumber".to_string(), json!("0x10")),
+        ("net_version".to_string(), json!(CHAIN_ID.to_string())),
+    ])
+}
@@ -0,0 +1,163 @@
+//! Test harness for exercising command paths without network access.
+//!
+//! Enabled with the `test-harness` feature. [`MockRpcServer::shared`] starts a
+//! local JSON-RPC server answering with the canned responses in [`fixtures`],
+//! points the Alchemy client at it (with an HTTP client that may use plain
+//! HTTP), and redirects the wallet's data and config directories to a
+//! temporary location.
+
+pub mod fixtures;
+
+use crate::utils::{alchemy, http};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+
+static SHARED_SERVER: OnceLock<MockRpcServer> = OnceLock::new();
+
+/// A minimal HTTP JSON-RPC server answering each method with a canned result
+pub struct MockRpcServer {
+    pub url: String,
+    pub data_dir: PathBuf,
+    requests: Arc<Mutex<Vec<Value>>>,
+}
+
+impl MockRpcServer {
+    /// Start a server on an ephemeral local port in a background thread
+    pub fn start(responses: HashMap<String, Value>) -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let responses = Arc::new(responses);
+
+        let log = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let responses = Arc::clone(&responses);
+                let log = Arc::clone(&log);
+                std::thread::spawn(move || {
+                    let _ = handle_connection(stream, &responses, &log);
+                });
+            }
+        });
+
+        let data_dir = tempfile::tempdir()?.keep();
+        Ok(Self {
+            url,
+            data_dir,
+            requests,
+        })
+    }
+
+    /// The process-wide server loaded with [`fixtures::default_responses`].
+    ///
+    /// The first call also points the Alchemy client at the server and
+    /// isolates the data/config directories, so tests never touch a real
+    /// wallet.
+    pub fn shared() -> &'static MockRpcServer {
+        SHARED_SERVER.get_or_init(|| {
+            let server = Self::start(fixtures::default_responses())
+                .expect("Failed to start mock RPC server");
+            alchemy::set_base_url(server.url.clone());
+            // The server speaks plain HTTP, which hosted API clients refuse
+            http::set_secure_client(http::client().expect("Failed to build the HTTP client"))
+                .expect("The HTTP client was used before the mock server started");
+            isolate_dirs(&server.data_dir);
+            server
+        })
+    }
+
+    /// JSON-RPC requests received so far, in order
+    pub fn requests(&self) -> Vec<Value> {
+        self.requests
+            .lock()
+            .map(|reqs| reqs.clone())
+            .unwrap_or_default()
+    }
+
+    /// JSON-RPC methods received so far, in order
+    pub fn methods(&self) -> Vec<String> {
+        self.requests
+            .lock()
+            .map(|reqs| {
+                reqs.iter()
+                    .filter_map(|r| r["method"].as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn isolate_dirs(root: &std::path::Path) {
+    // SAFETY: called once while initialising the shared server, before any
+    // test code reads these variables.
+    unsafe {
+        std::env::set_var("HOME", root);
+        std::env::set_var("XDG_DATA_HOME", root.join("data"));
+        std::env::set_var("XDG_CONFIG_HOME", root.join("config"));
+        // Test machines have no OS keyring to keep the database key in
+        std::env::set_var(crate::utils::storage::KEY_ENV, "00".repeat(32));
+    }
+}
+
+fn handle_connection(
+    mut stream: TcpStream,
+    responses: &HashMap<String, Value>,
+    log: &Mutex<Vec<Value>>,
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut content_length = 0usize;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line == "\r\n" {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().unwrap_or(0);
+        }
+    }
+
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body)?;
+    let request: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+    let reply = match &request {
+        Value::Array(batch) => Value::Array(
+            batch
+                .iter()
+                .map(|r| answer(r, responses, log))
+                .collect(),
+        ),
+        single => answer(single, responses, log),
+    };
+
+    let payload = reply.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        payload.len(),
+        payload
+    )?;
+    stream.flush()
+}
+
+fn answer(request: &Value, responses: &HashMap<String, Value>, log: &Mutex<Vec<Value>>) -> Value {
+    if let Ok(mut log) = log.lock() {
+        log.push(request.clone());
+    }
+    let id = request["id"].clone();
+    match request["method"].as_str().and_then(|m| responses.get(m)) {
+        Some(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        None => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": -32601, "message": "Method not found" }
+        }),
+    }
+}
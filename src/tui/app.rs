@@ -0,0 +1,663 @@
+//! State of the dashboard, its keyboard handling and its layout.
+
+use crate::tui::snapshot::{PendingOperation, Snapshot};
+use crate::tui::transaction_list::{self, TransactionList};
+use crate::types::wallet::Wallet;
+use crate::utils::accounting::format_amount;
+use crate::utils::redact;
+use alloy::primitives::U256;
+use chrono::{DateTime, Local};
+use ratatui::Frame;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{
+    Block, BorderType, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table, TableState,
+    Wrap,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Panel {
+    Balances,
+    Transactions,
+    Pending,
+    Network,
+}
+
+impl Panel {
+    pub const ALL: [Panel; 4] = [
+        Self::Balances,
+        Self::Transactions,
+        Self::Pending,
+        Self::Network,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Balances => "Balances",
+            Self::Transactions => "Recent Transactions",
+            Self::Pending => "Pending Operations",
+            Self::Network => "Network",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|&panel| panel == self)
+            .unwrap_or(0)
+    }
+
+    fn next(self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    fn previous(self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// What the event loop should do after a key press
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    None,
+    /// Load the snapshot of the selected wallet now
+    Refresh,
+    Quit,
+}
+
+/// Window shown over the panels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Popup {
+    Help,
+    Details,
+}
+
+pub struct App {
+    wallets: Vec<Wallet>,
+    wallet: usize,
+    snapshot: Option<Snapshot>,
+    focus: Panel,
+    balances: TableState,
+    transactions: TableState,
+    pending: ListState,
+    popup: Option<Popup>,
+    /// Set while a snapshot is being loaded
+    loading: bool,
+}
+
+impl App {
+    /// Dashboard for `wallets`, starting with the one at `selected`
+    pub fn new(wallets: Vec<Wallet>, selected: usize) -> Self {
+        Self {
+            wallet: selected.min(wallets.len().saturating_sub(1)),
+            wallets,
+            snapshot: None,
+            focus: Panel::Balances,
+            balances: TableState::default(),
+            transactions: TableState::default(),
+            pending: ListState::default(),
+            popup: None,
+            loading: true,
+        }
+    }
+
+    pub fn wallet(&self) -> &Wallet {
+        &self.wallets[self.wallet]
+    }
+
+    /// Show a freshly loaded snapshot, keeping the selections where they
+    /// still fit. Snapshots of a wallet no longer selected are dropped.
+    pub fn update(&mut self, snapshot: Snapshot) {
+        if snapshot.address != self.wallet().address {
+            return;
+        }
+        clamp_table(&mut self.balances, snapshot.balances.len());
+        clamp_table(&mut self.transactions, snapshot.transactions.len());
+        clamp_list(&mut self.pending, snapshot.pending.len());
+        self.snapshot = Some(snapshot);
+        self.loading = false;
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+        if self.popup.is_some() {
+            if matches!(
+                key.code,
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q' | '?')
+            ) {
+                self.popup = None;
+            }
+            return Action::None;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => self.focus = self.focus.next(),
+            KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => {
+                self.focus = self.focus.previous()
+            }
+            KeyCode::Char(c @ '1'..='4') => self.focus = Panel::ALL[c as usize - '1' as usize],
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(10),
+            KeyCode::PageUp => self.move_selection(-10),
+            KeyCode::Home | KeyCode::Char('g') => self.move_selection(isize::MIN),
+            KeyCode::End | KeyCode::Char('G') => self.move_selection(isize::MAX),
+            KeyCode::Enter if self.selected_details().is_some() => {
+                self.popup = Some(Popup::Details)
+            }
+            KeyCode::Char('?') => self.popup = Some(Popup::Help),
+            KeyCode::Char('r') => {
+                self.loading = true;
+                return Action::Refresh;
+            }
+            KeyCode::Char('w') | KeyCode::Char('W') if self.wallets.len() > 1 => {
+                let count = self.wallets.len();
+                self.wallet = if key.code == KeyCode::Char('w') {
+                    (self.wallet + 1) % count
+                } else {
+                    (self.wallet + count - 1) % count
+                };
+                self.snapshot = None;
+                self.balances = TableState::default();
+                self.transactions = TableState::default();
+                self.pending = ListState::default();
+                self.loading = true;
+                return Action::Refresh;
+            }
+            _ => {}
+        }
+        Action::None
+    }
+
+    /// Move the selection of the focused panel by `delta` rows, clamped to
+    /// the first and last row
+    fn move_selection(&mut self, delta: isize) {
+        let Some(snapshot) = &self.snapshot else {
+            return;
+        };
+        let (len, selected) = match self.focus {
+            Panel::Balances => (snapshot.balances.len(), self.balances.selected()),
+            Panel::Transactions => (snapshot.transactions.len(), self.transactions.selected()),
+            Panel::Pending => (snapshot.pending.len(), self.pending.selected()),
+            Panel::Network => return,
+        };
+        if len == 0 {
+            return;
+        }
+        let current = selected.unwrap_or(0) as isize;
+        let index = current.saturating_add(delta).clamp(0, len as isize - 1) as usize;
+        match self.focus {
+            Panel::Balances => self.balances.select(Some(index)),
+            Panel::Transactions => self.transactions.select(Some(index)),
+            Panel::Pending => self.pending.select(Some(index)),
+            Panel::Network => {}
+        }
+    }
+
+    /// Title and fields of the selected transaction or pending operation
+    fn selected_details(&self) -> Option<(String, Vec<(String, String)>)> {
+        let snapshot = self.snapshot.as_ref()?;
+        match self.focus {
+            Panel::Transactions => {
+                let tx = snapshot.transactions.get(self.transactions.selected()?)?;
+                let (symbol, decimals) = transaction_list::asset(tx, &snapshot.tokens);
+                let mut fields = vec![
+                    ("Hash".to_string(), format!("0x{:x}", tx.hash)),
+                    ("From".to_string(), format!("0x{:x}", tx.from)),
+                    (
+                        "To".to_string(),
+                        tx.to.map_or("-".to_string(), |to| format!("0x{:x}", to)),
+                    ),
+                    (
+                        "Value".to_string(),
+                        format!("{} {}", format_amount(tx.value, decimals), symbol),
+                    ),
+                    ("Status".to_string(), tx.status.to_string()),
+                    (
+                        "Time".to_string(),
+                        DateTime::<Local>::from(tx.timestamp)
+                            .format("%Y-%m-%d %H:%M:%S")
+                            .to_string(),
+                    ),
+                ];
+                if let Some(block) = tx.block_number {
+                    fields.push(("Block".to_string(), block.to_string()));
+                }
+                if let Some(token) = tx.token_address {
+                    fields.push(("Token".to_string(), format!("0x{:x}", token)));
+                }
+                Some(("Transaction".to_string(), fields))
+            }
+            Panel::Pending => {
+                let operation = snapshot.pending.get(self.pending.selected()?)?;
+                let title = match operation.kind {
+                    "queued" => "Queued Transfer",
+                    _ => "Pending Transaction",
+                };
+                Some((title.to_string(), operation.details.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        let [header, top, bottom, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Percentage(55),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [balances, transactions] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Fill(1)]).areas(top);
+        let [pending, network] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Fill(1)]).areas(bottom);
+
+        frame.render_widget(self.header(), header);
+        self.render_balances(frame, balances);
+        self.render_transactions(frame, transactions);
+        self.render_pending(frame, pending);
+        frame.render_widget(self.network(), network);
+        frame.render_widget(self.footer(), footer);
+
+        match self.popup {
+            Some(Popup::Help) => render_popup(frame, "Keys", help_lines()),
+            Some(Popup::Details) => {
+                if let Some((title, fields)) = self.selected_details() {
+                    let lines = fields
+                        .into_iter()
+                        .map(|(name, value)| {
+                            Line::from(vec![
+                                Span::styled(format!("{:<16}", name), Style::default().bold()),
+                                Span::raw(redact::text(&value)),
+                            ])
+                        })
+                        .collect();
+                    render_popup(frame, &title, lines);
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn block(&self, panel: Panel) -> Block<'static> {
+        let focused = self.focus == panel;
+        let title = format!(" {} {} ", panel.index() + 1, panel.title());
+        let block = Block::bordered().title(title);
+        if focused {
+            block
+                .border_type(BorderType::Thick)
+                .border_style(Style::default().fg(Color::Cyan))
+        } else {
+            block.border_style(Style::default().fg(Color::DarkGray))
+        }
+    }
+
+    fn header(&self) -> Line<'static> {
+        let wallet = self.wallet();
+        let mut spans = vec![
+            Span::styled(" Rootstock Wallet ", Style::default().bold().reversed()),
+            Span::raw(" "),
+            Span::styled(wallet.name.clone(), Style::default().bold().cyan()),
+            Span::raw(format!(
+                " {}",
+                redact::address(format!("0x{:x}", wallet.address))
+            )),
+        ];
+        if self.wallets.len() > 1 {
+            spans.push(Span::styled(
+                format!("  ({} of {})", self.wallet + 1, self.wallets.len()),
+                Style::default().dark_gray(),
+            ));
+        }
+        if let Some(snapshot) = &self.snapshot {
+            spans.push(Span::raw(format!("  · {}", snapshot.network.name)));
+        }
+        Line::from(spans)
+    }
+
+    fn footer(&self) -> Line<'static> {
+        let status = match &self.snapshot {
+            _ if self.loading => Span::styled("Refreshing…", Style::default().yellow()),
+            Some(snapshot) if !snapshot.errors.is_empty() => Span::styled(
+                format!(
+                    "{} lookup(s) failed: {}",
+                    snapshot.errors.len(),
+                    redact::text(&snapshot.errors[0])
+                ),
+                Style::default().red(),
+            ),
+            Some(snapshot) => Span::styled(
+                format!("Updated {}", snapshot.updated_at.format("%H:%M:%S")),
+                Style::default().green(),
+            ),
+            None => Span::raw(""),
+        };
+        let mut keys = "q quit · Tab panel · ↑↓ select · Enter details · r refresh".to_string();
+        if self.wallets.len() > 1 {
+            keys.push_str(" · w wallet");
+        }
+        keys.push_str(" · ? help  ");
+        Line::from(vec![
+            Span::styled(keys, Style::default().dark_gray()),
+            status,
+        ])
+    }
+
+    fn render_balances(&mut self, frame: &mut Frame, area: Rect) {
+        let block = self.block(Panel::Balances);
+        let Some(snapshot) = &self.snapshot else {
+            frame.render_widget(placeholder("Loading…").block(block), area);
+            return;
+        };
+        let rows = snapshot.balances.iter().map(|balance| {
+            Row::new(vec![
+                Cell::from(balance.symbol.clone()).bold(),
+                Cell::from(Line::from(redact::amount(&balance.amount)).right_aligned()),
+            ])
+        });
+        let table = Table::new(rows, [Constraint::Length(10), Constraint::Fill(1)])
+            .block(block)
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, area, &mut self.balances);
+    }
+
+    fn render_transactions(&mut self, frame: &mut Frame, area: Rect) {
+        let block = self.block(Panel::Transactions);
+        let Some(snapshot) = &self.snapshot else {
+            frame.render_widget(placeholder("Loading…").block(block), area);
+            return;
+        };
+        if snapshot.transactions.is_empty() {
+            let text = "No transactions in the history cache; run `sync` or `history` to fill it";
+            frame.render_widget(placeholder(text).block(block), area);
+            return;
+        }
+        let list = TransactionList::new(&snapshot.transactions, snapshot.address, &snapshot.tokens)
+            .block(block);
+        frame.render_stateful_widget(list, area, &mut self.transactions);
+    }
+
+    fn render_pending(&mut self, frame: &mut Frame, area: Rect) {
+        let block = self.block(Panel::Pending);
+        let Some(snapshot) = &self.snapshot else {
+            frame.render_widget(placeholder("Loading…").block(block), area);
+            return;
+        };
+        if snapshot.pending.is_empty() {
+            let text = "Nothing pending or queued";
+            frame.render_widget(placeholder(text).block(block), area);
+            return;
+        }
+        let items: Vec<ListItem> = snapshot.pending.iter().map(pending_item).collect();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.pending);
+    }
+
+    fn network(&self) -> Paragraph<'static> {
+        let block = self.block(Panel::Network);
+        let Some(snapshot) = &self.snapshot else {
+            return placeholder("Loading…").block(block);
+        };
+        let status = &snapshot.network;
+        let unavailable = || Span::styled("unavailable", Style::default().red());
+        let field = |name: &str, value: Span<'static>| {
+            Line::from(vec![
+                Span::styled(format!("{:<11}", name), Style::default().bold()),
+                value,
+            ])
+        };
+        let lines = vec![
+            field("Network", Span::raw(status.name.clone())),
+            field("Chain ID", Span::raw(status.chain_id.to_string())),
+            field("Endpoint", Span::raw(status.endpoint.clone())),
+            field(
+                "Block",
+                status
+                    .block
+                    .map_or_else(unavailable, |block| Span::raw(block.to_string())),
+            ),
+            field(
+                "Gas price",
+                status
+                    .gas_price
+                    .as_ref()
+                    .map_or_else(unavailable, |(wei, source)| {
+                        Span::raw(format!(
+                            "{} gwei ({})",
+                            format_amount(U256::from(*wei), 9),
+                            source
+                        ))
+                    }),
+            ),
+            field(
+                "Latency",
+                status.latency.map_or_else(unavailable, |latency| {
+                    let ms = latency.as_millis();
+                    let style = match ms {
+                        0..500 => Style::default().green(),
+                        500..2000 => Style::default().yellow(),
+                        _ => Style::default().red(),
+                    };
+                    Span::styled(format!("{} ms", ms), style)
+                }),
+            ),
+            field(
+                "Updates",
+                Span::raw(if status.live {
+                    "live (new blocks)"
+                } else {
+                    "polling"
+                }),
+            ),
+        ];
+        Paragraph::new(lines).block(block)
+    }
+}
+
+fn pending_item(operation: &PendingOperation) -> ListItem<'static> {
+    let color = match operation.kind {
+        "queued" => Color::Cyan,
+        _ => Color::Yellow,
+    };
+    ListItem::new(Line::from(vec![
+        Span::styled(format!("{:<8}", operation.kind), Style::default().fg(color)),
+        Span::raw(redact::text(&operation.summary)),
+    ]))
+}
+
+fn placeholder(text: &str) -> Paragraph<'static> {
+    Paragraph::new(Line::from(text.to_string()).dark_gray()).wrap(Wrap { trim: true })
+}
+
+fn help_lines() -> Vec<Line<'static>> {
+    [
+        ("Tab, →, l", "Next panel"),
+        ("Shift+Tab, ←, h", "Previous panel"),
+        ("1-4", "Go to a panel"),
+        ("↑↓, j k", "Select a row"),
+        ("PgUp PgDn, g G", "Page, first and last row"),
+        ("Enter", "Details of the selected row"),
+        ("r", "Refresh now"),
+        ("w, W", "Next or previous wallet"),
+        ("q, Esc", "Quit"),
+    ]
+    .into_iter()
+    .map(|(keys, action)| {
+        Line::from(vec![
+            Span::styled(format!("{:<18}", keys), Style::default().bold().cyan()),
+            Span::raw(action),
+        ])
+    })
+    .collect()
+}
+
+/// Draw `lines` in a window centred over the panels
+fn render_popup(frame: &mut Frame, title: &str, lines: Vec<Line<'static>>) {
+    let width = lines
+        .iter()
+        .map(Line::width)
+        .max()
+        .unwrap_or(0)
+        .max(title.len()) as u16
+        + 4;
+    let height = lines.len() as u16 + 2;
+    let [area] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(frame.area());
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::bordered()
+                .border_type(BorderType::Rounded)
+                .title(format!(" {} ", title))
+                .title_bottom(Line::from(" Esc to close ").right_aligned()),
+        ),
+        area,
+    );
+}
+
+/// Keep the selection of a panel with `len` rows on a row, selecting the
+/// first one once there are rows
+fn clamp_table(state: &mut TableState, len: usize) {
+    state.select(match (state.selected(), len) {
+        (_, 0) => None,
+        (Some(index), _) => Some(index.min(len - 1)),
+        (None, _) => Some(0),
+    });
+}
+
+fn clamp_list(state: &mut ListState, len: usize) {
+    state.select(match (state.selected(), len) {
+        (_, 0) => None,
+        (Some(index), _) => Some(index.min(len - 1)),
+        (None, _) => Some(0),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::snapshot::{Balance, NetworkStatus, TokenSymbols};
+    use alloy::primitives::Address;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::KeyEventKind;
+
+    fn wallet(name: &str, byte: u8) -> Wallet {
+        Wallet {
+            address: Address::repeat_byte(byte),
+            balance: U256::ZERO,
+            network: "testnet".to_string(),
+            name: name.to_string(),
+            encrypted_private_key: String::new(),
+            salt: String::new(),
+            iv: String::new(),
+            created_at: String::new(),
+            watch_only: true,
+            hardware: None,
+            seed: None,
+            accounts: Vec::new(),
+        }
+    }
+
+    fn snapshot(wallet: &Wallet) -> Snapshot {
+        Snapshot {
+            wallet: wallet.name.clone(),
+            address: wallet.address,
+            balances: vec![
+                Balance {
+                    symbol: "RBTC".to_string(),
+                    amount: "0.5".to_string(),
+                },
+                Balance {
+                    symbol: "USDRIF".to_string(),
+                    amount: "25".to_string(),
+                },
+            ],
+            transactions: Vec::new(),
+            pending: vec![PendingOperation {
+                kind: "queued",
+                summary: "#1 1 RBTC to 0x02 after block 100".to_string(),
+                details: vec![("Queued transfer".to_string(), "#1".to_string())],
+            }],
+            network: NetworkStatus {
+                name: "RSK Testnet".to_string(),
+                chain_id: 31,
+                block: Some(6_000_000),
+                ..Default::default()
+            },
+            tokens: TokenSymbols::new(),
+            errors: Vec::new(),
+            updated_at: Local::now(),
+        }
+    }
+
+    fn press(app: &mut App, code: KeyCode) -> Action {
+        app.handle_key(KeyEvent {
+            code,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: ratatui::crossterm::event::KeyEventState::NONE,
+        })
+    }
+
+    #[test]
+    fn test_dashboard_navigation() {
+        let wallets = vec![wallet("savings", 1), wallet("spending", 2)];
+        let mut app = App::new(wallets.clone(), 0);
+        app.update(snapshot(&wallets[0]));
+        assert_eq!(app.balances.selected(), Some(0));
+
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.balances.selected(), Some(1));
+        assert_eq!(press(&mut app, KeyCode::BackTab), Action::None);
+        assert_eq!(app.focus, Panel::Network);
+        press(&mut app, KeyCode::Char('3'));
+        assert_eq!(app.focus, Panel::Pending);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.popup, Some(Popup::Details));
+        // Keys go to the popup until it is closed
+        assert_eq!(press(&mut app, KeyCode::Char('r')), Action::None);
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.popup, None);
+
+        assert_eq!(press(&mut app, KeyCode::Char('w')), Action::Refresh);
+        assert_eq!(app.wallet().name, "spending");
+        // A late snapshot of the previous wallet is not shown
+        app.update(snapshot(&wallets[0]));
+        assert!(app.snapshot.is_none());
+        assert_eq!(press(&mut app, KeyCode::Char('q')), Action::Quit);
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        app.update(snapshot(&wallets[1]));
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        for text in [
+            "spending",
+            "1 Balances",
+            "USDRIF",
+            "4 Network",
+            "6000000",
+            "after block 100",
+        ] {
+            assert!(screen.contains(text), "{} missing from the screen", text);
+        }
+    }
+}
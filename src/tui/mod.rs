@@ -0,0 +1,121 @@
+//! Full-screen terminal dashboard (`tui`).
+//!
+//! Four panels show the balances of a wallet, its recent transactions from
+//! the history cache, its sent transactions that are not mined yet and its
+//! queued transfers, and the status of the network. Snapshots are loaded in
+//! the background: on every new block when a WebSocket endpoint is
+//! configured, every refresh interval otherwise, and when `r` is pressed or
+//! another wallet is selected, so the keyboard stays responsive while the
+//! node is slow. Nothing is signed from the dashboard and no password is
+//! asked for.
+
+mod app;
+pub mod snapshot;
+pub mod transaction_list;
+
+use crate::commands::pending::read_client;
+use crate::config::ConfigManager;
+use crate::types::network::Network;
+use crate::types::wallet::Wallet;
+use crate::utils::eth::EthClient;
+use crate::utils::live::Ticker;
+use anyhow::Result;
+use app::{Action, App};
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use snapshot::Snapshot;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Longest wait for a key press before checking for a new snapshot
+const INPUT_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Run the dashboard for `wallets`, starting with the one at `selected`,
+/// until it is quit. Without live updates, snapshots are reloaded every
+/// `interval`.
+pub async fn run(wallets: Vec<Wallet>, selected: usize, interval: Duration) -> Result<()> {
+    let network = ConfigManager::new()?.load()?.default_network;
+    let client = read_client().await?;
+    let app = App::new(wallets, selected);
+
+    let (requests, wallet_requests) = mpsc::channel(8);
+    let (snapshot_sender, snapshots) = mpsc::channel(8);
+    let loader = tokio::spawn(load_snapshots(
+        client,
+        network,
+        app.wallet().clone(),
+        interval,
+        wallet_requests,
+        snapshot_sender,
+    ));
+
+    // Restores the terminal on panics too
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, app, requests, snapshots).await;
+    ratatui::restore();
+    loader.abort();
+    result
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    mut app: App,
+    requests: mpsc::Sender<Wallet>,
+    mut snapshots: mpsc::Receiver<Snapshot>,
+) -> Result<()> {
+    loop {
+        while let Ok(snapshot) = snapshots.try_recv() {
+            app.update(snapshot);
+        }
+        terminal.draw(|frame| app.render(frame))?;
+
+        // Waiting for input blocks, so the loader keeps the other workers
+        let event = tokio::task::block_in_place(|| {
+            if event::poll(INPUT_TIMEOUT)? {
+                event::read().map(Some)
+            } else {
+                Ok(None)
+            }
+        })?;
+        let Some(Event::Key(key)) = event else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match app.handle_key(key) {
+            Action::Quit => return Ok(()),
+            Action::Refresh => {
+                // A full queue already holds a refresh
+                let _ = requests.try_send(app.wallet().clone());
+            }
+            Action::None => {}
+        }
+    }
+}
+
+/// Load a snapshot of `wallet`, then again on every tick or request, until
+/// the dashboard is closed. A request switches to the wallet it names.
+async fn load_snapshots(
+    client: EthClient,
+    network: Network,
+    mut wallet: Wallet,
+    interval: Duration,
+    mut requests: mpsc::Receiver<Wallet>,
+    snapshots: mpsc::Sender<Snapshot>,
+) {
+    let mut ticker = Ticker::connect(client.ws_url(), interval, None).await;
+    loop {
+        let snapshot = Snapshot::load(&client, network, &wallet, ticker.is_live()).await;
+        if snapshots.send(snapshot).await.is_err() {
+            return;
+        }
+        tokio::select! {
+            _ = ticker.tick() => {}
+            request = requests.recv() => match request {
+                Some(requested) => wallet = requested,
+                None => return,
+            },
+        }
+    }
+}
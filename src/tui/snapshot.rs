@@ -0,0 +1,270 @@
+//! Everything the dashboard shows for one wallet, loaded in one go.
+//!
+//! A lookup that fails leaves its part of the snapshot empty and adds a
+//! message to `errors`, so a flaky node blanks a panel instead of ending the
+//! dashboard.
+
+use crate::commands::queue::TransferQueue;
+use crate::commands::tokens::TokenRegistry;
+use crate::config::PollingConfig;
+use crate::types::network::Network;
+use crate::types::transaction::RskTransaction;
+use crate::types::wallet::Wallet;
+use crate::utils::accounting::format_amount;
+use crate::utils::eth::{EthClient, endpoint_host};
+use crate::utils::history_cache::HistoryCache;
+use crate::utils::pending::PendingStore;
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Transactions kept from the history cache
+pub const RECENT_TRANSACTIONS: usize = 50;
+
+/// Symbols and decimals of the registry tokens, by address
+pub type TokenSymbols = HashMap<Address, (String, u8)>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Balance {
+    pub symbol: String,
+    pub amount: String,
+}
+
+/// A transfer that is sent but not mined, or queued but not sent
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingOperation {
+    /// `pending` or `queued`
+    pub kind: &'static str,
+    pub summary: String,
+    /// Details shown when the operation is opened
+    pub details: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetworkStatus {
+    pub name: String,
+    pub chain_id: u64,
+    pub endpoint: String,
+    pub block: Option<u64>,
+    /// Gas price in wei, and where the suggestion came from
+    pub gas_price: Option<(u128, String)>,
+    /// Round trip of the block number lookup
+    pub latency: Option<Duration>,
+    /// Whether updates arrive over a WebSocket subscription
+    pub live: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub wallet: String,
+    pub address: Address,
+    pub balances: Vec<Balance>,
+    /// Newest first
+    pub transactions: Vec<RskTransaction>,
+    pub pending: Vec<PendingOperation>,
+    pub network: NetworkStatus,
+    pub tokens: TokenSymbols,
+    pub errors: Vec<String>,
+    pub updated_at: DateTime<Local>,
+}
+
+impl Snapshot {
+    pub async fn load(client: &EthClient, network: Network, wallet: &Wallet, live: bool) -> Self {
+        let mut errors = Vec::new();
+        let tokens = token_symbols(network);
+
+        let network_status = network_status(client, network, live, &mut errors).await;
+        let balances = balances(client, &wallet.address, &tokens, &mut errors).await;
+
+        let mut transactions = HistoryCache::load()
+            .unwrap_or_default()
+            .get(network.chain_name(), &wallet.address);
+        transactions.sort_by_key(|tx| std::cmp::Reverse(tx.timestamp));
+        transactions.truncate(RECENT_TRANSACTIONS);
+
+        let pending = pending_operations(client, &wallet.address, &tokens, &mut errors).await;
+
+        Self {
+            wallet: wallet.name.clone(),
+            address: wallet.address,
+            balances,
+            transactions,
+            pending,
+            network: network_status,
+            tokens,
+            errors,
+            updated_at: Local::now(),
+        }
+    }
+}
+
+fn token_symbols(network: Network) -> TokenSymbols {
+    TokenRegistry::load()
+        .unwrap_or_default()
+        .list_tokens(Some(network.chain_name()))
+        .into_iter()
+        .filter_map(|(symbol, info)| {
+            let address = Address::from_str(&info.address).ok()?;
+            Some((address, (symbol, info.decimals)))
+        })
+        .collect()
+}
+
+async fn network_status(
+    client: &EthClient,
+    network: Network,
+    live: bool,
+    errors: &mut Vec<String>,
+) -> NetworkStatus {
+    let config = network.get_config();
+    let started = Instant::now();
+    let block = match client.provider().get_block_number().await {
+        Ok(block) => Some(block),
+        Err(e) => {
+            errors.push(format!("Block number: {}", e));
+            None
+        }
+    };
+    let latency = block.map(|_| started.elapsed());
+    let gas_price = match client.suggest_gas_price().await {
+        Ok(fee) => Some((fee.gas_price, fee.source.to_string())),
+        Err(e) => {
+            errors.push(format!("Gas price: {}", e));
+            None
+        }
+    };
+    NetworkStatus {
+        name: config.name,
+        chain_id: network.chain_id(),
+        endpoint: endpoint_host(&config.rpc_url),
+        block,
+        gas_price,
+        latency,
+        live,
+    }
+}
+
+/// RBTC and the registry tokens the wallet holds
+async fn balances(
+    client: &EthClient,
+    address: &Address,
+    tokens: &TokenSymbols,
+    errors: &mut Vec<String>,
+) -> Vec<Balance> {
+    let mut balances = Vec::new();
+    match client.get_balance(address, &None).await {
+        Ok(balance) => balances.push(Balance {
+            symbol: "RBTC".to_string(),
+            amount: format_amount(balance, 18),
+        }),
+        Err(e) => errors.push(format!("RBTC balance: {}", e)),
+    }
+
+    let polling = PollingConfig::current();
+    let mut held = Vec::new();
+    for (token, (symbol, decimals)) in tokens {
+        polling.pace_requests().await;
+        match client.get_balance(address, &Some(*token)).await {
+            Ok(balance) if !balance.is_zero() => held.push(Balance {
+                symbol: symbol.clone(),
+                amount: format_amount(balance, *decimals),
+            }),
+            Ok(_) => {}
+            Err(e) => errors.push(format!("{} balance: {}", symbol, e)),
+        }
+    }
+    held.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    balances.extend(held);
+    balances
+}
+
+/// Sent transactions that are not mined yet, then queued transfers, of the
+/// wallet
+async fn pending_operations(
+    client: &EthClient,
+    address: &Address,
+    tokens: &TokenSymbols,
+    errors: &mut Vec<String>,
+) -> Vec<PendingOperation> {
+    let mut operations = Vec::new();
+    let sent = match PendingStore::load() {
+        Ok(mut store) => store.refresh(client).await,
+        Err(e) => Err(e),
+    };
+    match sent {
+        Ok(sent) => {
+            for tx in sent.iter().filter(|tx| tx.from == *address) {
+                let summary = if tx.is_cancellation() {
+                    format!("Cancellation (nonce {})", tx.nonce)
+                } else {
+                    format!(
+                        "{} RBTC to 0x{:x} (nonce {})",
+                        format_amount(tx.value, 18),
+                        tx.to,
+                        tx.nonce
+                    )
+                };
+                operations.push(PendingOperation {
+                    kind: "pending",
+                    summary,
+                    details: vec![
+                        ("Hash".to_string(), format!("0x{:x}", tx.hash)),
+                        ("To".to_string(), format!("0x{:x}", tx.to)),
+                        (
+                            "Value".to_string(),
+                            format!("{} RBTC", format_amount(tx.value, 18)),
+                        ),
+                        ("Nonce".to_string(), tx.nonce.to_string()),
+                        (
+                            "Gas price".to_string(),
+                            format!("{} gwei", format_amount(U256::from(tx.gas_price), 9)),
+                        ),
+                        (
+                            "Submitted".to_string(),
+                            tx.submitted_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                        ),
+                    ],
+                });
+            }
+        }
+        Err(e) => errors.push(format!("Pending transactions: {}", e)),
+    }
+
+    let wallet_key = format!("0x{:x}", address);
+    match TransferQueue::load() {
+        Ok(queue) => {
+            for transfer in queue.pending().filter(|t| t.wallet == wallet_key) {
+                let (symbol, decimals) = match transfer.token {
+                    Some(token) => tokens
+                        .get(&token)
+                        .cloned()
+                        .unwrap_or_else(|| (format!("0x{:x}", token), 18)),
+                    None => ("RBTC".to_string(), 18),
+                };
+                let amount = format_amount(transfer.value, decimals);
+                operations.push(PendingOperation {
+                    kind: "queued",
+                    summary: format!(
+                        "#{} {} {} to 0x{:x} {}",
+                        transfer.id, amount, symbol, transfer.to, transfer.schedule
+                    ),
+                    details: vec![
+                        ("Queued transfer".to_string(), format!("#{}", transfer.id)),
+                        ("To".to_string(), format!("0x{:x}", transfer.to)),
+                        ("Value".to_string(), format!("{} {}", amount, symbol)),
+                        ("Runs".to_string(), transfer.schedule.to_string()),
+                        (
+                            "Approved by".to_string(),
+                            transfer.approved_by.clone().unwrap_or("-".to_string()),
+                        ),
+                    ],
+                });
+            }
+        }
+        Err(e) => errors.push(format!("Transfer queue: {}", e)),
+    }
+    operations
+}
@@ -0,0 +1,175 @@
+//! Table of a wallet's transactions, newest first, for the dashboard.
+//!
+//! Each row shows when the transaction was mined, whether it was sent or
+//! received, the other party, the amount with its token and the status.
+//! Addresses and amounts follow redact mode.
+
+use crate::tui::snapshot::TokenSymbols;
+use crate::types::transaction::{RskTransaction, TransactionStatus};
+use crate::utils::accounting::format_amount;
+use crate::utils::redact;
+use alloy::primitives::Address;
+use chrono::{DateTime, Local};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Row, StatefulWidget, Table, TableState};
+
+const HEADER: [&str; 5] = ["Time", "", "Counterparty", "Amount", "Status"];
+
+pub struct TransactionList<'a> {
+    transactions: &'a [RskTransaction],
+    /// Address the direction is relative to
+    owner: Address,
+    tokens: &'a TokenSymbols,
+    block: Option<Block<'a>>,
+}
+
+impl<'a> TransactionList<'a> {
+    pub fn new(
+        transactions: &'a [RskTransaction],
+        owner: Address,
+        tokens: &'a TokenSymbols,
+    ) -> Self {
+        Self {
+            transactions,
+            owner,
+            tokens,
+            block: None,
+        }
+    }
+
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+impl StatefulWidget for TransactionList<'_> {
+    type State = TableState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut TableState) {
+        let rows = self.transactions.iter().map(|tx| {
+            let cells = cells(tx, self.owner, self.tokens);
+            let color = match tx.status {
+                TransactionStatus::Failed => Color::Red,
+                TransactionStatus::Pending => Color::Yellow,
+                _ if tx.from == self.owner => Color::Reset,
+                _ => Color::Green,
+            };
+            Row::new(cells).style(Style::default().fg(color))
+        });
+        let widths = [
+            Constraint::Length(16),
+            Constraint::Length(3),
+            Constraint::Min(14),
+            Constraint::Min(12),
+            Constraint::Length(8),
+        ];
+        let mut table = Table::new(rows, widths)
+            .header(Row::new(HEADER).style(Style::default().add_modifier(Modifier::BOLD)))
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("› ");
+        if let Some(block) = self.block {
+            table = table.block(block);
+        }
+        StatefulWidget::render(table, area, buf, state);
+    }
+}
+
+/// Cells of the row for `tx`, as seen from `owner`
+pub fn cells(tx: &RskTransaction, owner: Address, tokens: &TokenSymbols) -> [String; 5] {
+    let time = DateTime::<Local>::from(tx.timestamp)
+        .format("%Y-%m-%d %H:%M")
+        .to_string();
+    let received = tx.to == Some(owner);
+    let (direction, counterparty) = if tx.from == owner && received {
+        ("⇄", Some(owner))
+    } else if received {
+        ("↓", Some(tx.from))
+    } else {
+        ("↑", tx.to)
+    };
+    let counterparty = counterparty.map_or("contract creation".to_string(), |address| {
+        redact::address(format!("0x{:x}", address))
+    });
+    let (symbol, decimals) = asset(tx, tokens);
+    let amount = format!(
+        "{} {}",
+        redact::amount(format_amount(tx.value, decimals)),
+        symbol
+    );
+    [
+        time,
+        direction.to_string(),
+        counterparty,
+        amount,
+        tx.status.to_string(),
+    ]
+}
+
+/// Symbol and decimals of what `tx` transfers: RBTC, a registry token, or
+/// a token that is not in the registry
+pub fn asset(tx: &RskTransaction, tokens: &TokenSymbols) -> (String, u8) {
+    match tx.token_address {
+        Some(token) => tokens
+            .get(&token)
+            .cloned()
+            .unwrap_or_else(|| ("tokens".to_string(), 18)),
+        None => ("RBTC".to_string(), 18),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{B256, U256};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn transaction(from: Address, to: Address, value: u64) -> RskTransaction {
+        RskTransaction {
+            hash: B256::repeat_byte(0xaa),
+            from,
+            to: Some(to),
+            value: U256::from(value),
+            gas_price: U256::ZERO,
+            gas: U256::ZERO,
+            nonce: U256::ZERO,
+            input: None,
+            block_number: None,
+            transaction_index: None,
+            timestamp: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            status: TransactionStatus::Success,
+            token_address: None,
+            confirms: None,
+            cumulative_gas_used: None,
+            gas_used: None,
+            logs: None,
+        }
+    }
+
+    #[test]
+    fn test_transaction_cells() {
+        let owner = Address::repeat_byte(1);
+        let other = Address::repeat_byte(2);
+        let token = Address::repeat_byte(3);
+        let tokens = TokenSymbols::from([(token, ("USDRIF".to_string(), 6))]);
+
+        let sent = transaction(owner, other, 500_000_000_000_000_000);
+        let [_, direction, counterparty, amount, status] = cells(&sent, owner, &tokens);
+        assert_eq!(direction, "↑");
+        assert_eq!(counterparty, format!("0x{:x}", other));
+        assert_eq!(amount, "0.5 RBTC");
+        assert_eq!(status, TransactionStatus::Success.to_string());
+
+        let mut received = transaction(other, owner, 25_000_000);
+        received.token_address = Some(token);
+        let [_, direction, counterparty, amount, _] = cells(&received, owner, &tokens);
+        assert_eq!(direction, "↓");
+        assert_eq!(counterparty, format!("0x{:x}", other));
+        assert_eq!(amount, "25 USDRIF");
+
+        let to_self = transaction(owner, owner, 1);
+        assert_eq!(cells(&to_self, owner, &tokens)[1], "⇄");
+    }
+}
@@ -1,9 +1,10 @@
 use crate::types::transaction::RskTransaction;
 use anyhow::Result;
 use colored::Colorize;
-use alloy::primitives::{Address, B256, U256};
+use alloy::primitives::{Address, B256, PrimitiveSignature, U256};
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContactTransactionStats {
@@ -12,6 +13,14 @@ pub struct ContactTransactionStats {
     pub last_transaction: Option<chrono::DateTime<chrono::Local>>,
 }
 
+/// Proof that a contact controls their address
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactVerification {
+    pub challenge: String,
+    pub signature: String,
+    pub verified_at: chrono::DateTime<chrono::Local>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Contact {
     pub name: String,
@@ -23,6 +32,20 @@ pub struct Contact {
     pub transaction_stats: Option<ContactTransactionStats>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub recent_transactions: Vec<B256>, // Transaction hashes
+    /// Challenge handed to the contact that has not been signed back yet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_challenge: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<ContactVerification>,
+    /// Deposits need a memo (destination tag) to be credited, as with most
+    /// exchange addresses; sending without one is refused
+    #[serde(default)]
+    pub memo_required: bool,
+    /// Groups the contact belongs to (e.g. "payroll", "suppliers"); unlike
+    /// tags, a group is managed as a whole and can be paid in one bulk
+    /// transfer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
 }
 
 impl Contact {
@@ -39,9 +62,84 @@ impl Contact {
                 last_transaction: None,
             }),
             recent_transactions: Vec::new(),
+            pending_challenge: None,
+            verification: None,
+            memo_required: false,
+            groups: Vec::new(),
         }
     }
 
+    pub fn in_group(&self, group: &str) -> bool {
+        self.groups.iter().any(|g| g.eq_ignore_ascii_case(group))
+    }
+
+    /// Add the contact to `group`; false when it is already a member
+    pub fn join_group(&mut self, group: &str) -> bool {
+        if self.in_group(group) {
+            return false;
+        }
+        self.groups.push(group.trim().to_string());
+        true
+    }
+
+    /// Take the contact out of `group`; false when it was not a member
+    pub fn leave_group(&mut self, group: &str) -> bool {
+        let before = self.groups.len();
+        self.groups.retain(|g| !g.eq_ignore_ascii_case(group));
+        self.groups.len() != before
+    }
+
+    /// Generate a fresh challenge for the contact to sign with their key
+    pub fn issue_challenge(&mut self) -> String {
+        let nonce: [u8; 16] = rand::random();
+        let challenge = format!(
+            "Rootstock Wallet contact verification\nAddress: 0x{:x}\nNonce: {}\nIssued: {}",
+            self.address,
+            hex::encode(nonce),
+            chrono::Local::now().to_rfc3339()
+        );
+        self.pending_challenge = Some(challenge.clone());
+        challenge
+    }
+
+    /// Check a personal_sign signature over the pending challenge and mark the contact verified
+    pub fn verify_challenge(&mut self, signature: &str) -> Result<()> {
+        let challenge = self
+            .pending_challenge
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("No pending challenge for {}", self.name))?;
+        let sig = PrimitiveSignature::from_str(signature.trim())
+            .map_err(|e| anyhow::anyhow!("Invalid signature: {}", e))?;
+        let signer = sig
+            .recover_address_from_msg(challenge.as_bytes())
+            .map_err(|e| anyhow::anyhow!("Failed to recover signer: {}", e))?;
+
+        if signer != self.address {
+            return Err(anyhow::anyhow!(
+                "Signature was made by 0x{:x}, not by the contact address 0x{:x}",
+                signer,
+                self.address
+            ));
+        }
+
+        self.verification = Some(ContactVerification {
+            challenge,
+            signature: signature.trim().to_string(),
+            verified_at: chrono::Local::now(),
+        });
+        self.pending_challenge = None;
+        Ok(())
+    }
+
+    pub fn is_verified(&self) -> bool {
+        self.verification.is_some()
+    }
+
+    /// Badge shown next to the contact name in pickers and lists
+    pub fn badge(&self) -> &'static str {
+        if self.is_verified() { " ✅" } else { "" }
+    }
+
     pub fn update_transaction_stats(&mut self, tx: &RskTransaction, _is_incoming: bool) {
         // Ensure transaction_stats is initialized
         if self.transaction_stats.is_none() {
@@ -202,6 +300,9 @@ impl Contact {
         if self.tags.len() > 5 {
             return Err(anyhow::anyhow!("A contact can have a maximum of 5 tags"));
         }
+        if self.groups.iter().any(|group| group.trim().is_empty()) {
+            return Err(anyhow::anyhow!("Group names cannot be empty"));
+        }
         if self.created_at.timestamp() > chrono::Local::now().timestamp() {
             return Err(anyhow::anyhow!(
                 "Created at timestamp cannot be in the future"
@@ -264,8 +365,9 @@ impl fmt::Display for Contact {
         // Format the main contact info
         write!(
             f,
-            "{}{}{}{}{}",
+            "{}{}{}{}{}{}",
             self.name.bold().green(),
+            self.badge(),
             tx_info,
             last_tx,
             notes_display,
@@ -277,6 +379,14 @@ impl fmt::Display for Contact {
             write!(f, "{}", tags_display.blue())?;
         }
 
+        if !self.groups.is_empty() {
+            write!(f, "\n  Groups: {}", self.groups.join(", ").cyan())?;
+        }
+
+        if self.memo_required {
+            write!(f, "\n  {}", "Memo required".yellow())?;
+        }
+
         // Add address at the end
         write!(
             f,
@@ -289,3 +399,82 @@ impl fmt::Display for Contact {
         Ok(())
     }
 }
+
+/// Every group in `contacts` with its number of members, by name. Names
+/// differing only in case are the same group, shown as first seen.
+pub fn groups(contacts: &[Contact]) -> Vec<(String, usize)> {
+    let mut groups: Vec<(String, usize)> = Vec::new();
+    for group in contacts.iter().flat_map(|contact| &contact.groups) {
+        match groups
+            .iter_mut()
+            .find(|(name, _)| name.eq_ignore_ascii_case(group))
+        {
+            Some((_, members)) => *members += 1,
+            None => groups.push((group.clone(), 1)),
+        }
+    }
+    groups.sort_by_key(|(name, _)| name.to_lowercase());
+    groups
+}
+
+pub fn group_members<'a>(contacts: &'a [Contact], group: &str) -> Vec<&'a Contact> {
+    contacts
+        .iter()
+        .filter(|contact| contact.in_group(group))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::signers::{SignerSync, local::PrivateKeySigner};
+
+    #[test]
+    fn test_verify_challenge() {
+        let signer = PrivateKeySigner::random();
+        let mut contact = Contact::new("alice".into(), signer.address(), None, vec![]);
+
+        let challenge = contact.issue_challenge();
+        let signature = signer.sign_message_sync(challenge.as_bytes()).unwrap();
+        contact
+            .verify_challenge(&format!("0x{}", hex::encode(signature.as_bytes())))
+            .unwrap();
+        assert!(contact.is_verified());
+        assert!(contact.pending_challenge.is_none());
+
+        // A signature from another key must be rejected
+        let mut impostor = Contact::new("bob".into(), Address::repeat_byte(1), None, vec![]);
+        let challenge = impostor.issue_challenge();
+        let signature = signer.sign_message_sync(challenge.as_bytes()).unwrap();
+        assert!(
+            impostor
+                .verify_challenge(&format!("0x{}", hex::encode(signature.as_bytes())))
+                .is_err()
+        );
+        assert!(!impostor.is_verified());
+    }
+
+    #[test]
+    fn test_contact_groups() {
+        let mut alice = Contact::new("alice".into(), Address::repeat_byte(1), None, vec![]);
+        let mut bob = Contact::new("bob".into(), Address::repeat_byte(2), None, vec![]);
+        assert!(alice.join_group("Payroll"));
+        assert!(!alice.join_group("payroll"));
+        assert!(alice.join_group("suppliers"));
+        assert!(bob.join_group(" payroll "));
+        assert_eq!(bob.groups, vec!["payroll"]);
+
+        let contacts = vec![alice.clone(), bob.clone()];
+        assert_eq!(
+            groups(&contacts),
+            vec![("Payroll".to_string(), 2), ("suppliers".to_string(), 1)]
+        );
+        assert_eq!(group_members(&contacts, "PAYROLL").len(), 2);
+
+        assert!(alice.leave_group("SUPPLIERS"));
+        assert!(!alice.leave_group("suppliers"));
+        assert_eq!(alice.groups, vec!["Payroll"]);
+        alice.groups.push(" ".to_string());
+        assert!(alice.validate().is_err());
+    }
+}
@@ -5,9 +5,148 @@ pub struct NetworkConfig {
     pub name: String,
     pub rpc_url: String,
     pub explorer_url: String,
+    /// Endpoints of the same network tried in order when `rpc_url` times
+    /// out, is rate limited or has a server error
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallback_rpc_urls: Vec<String>,
 }
 
+impl NetworkConfig {
+    /// Every RPC endpoint in order of preference, without duplicates
+    pub fn rpc_urls(&self) -> Vec<String> {
+        let mut urls = vec![self.rpc_url.clone()];
+        for url in &self.fallback_rpc_urls {
+            if !urls.contains(url) {
+                urls.push(url.clone());
+            }
+        }
+        urls
+    }
+}
+
+use anyhow::{Result, anyhow};
 use std::fmt;
+use std::sync::RwLock;
+
+/// An EVM chain registered by the user, e.g. a private Rootstock-compatible
+/// network. Its name doubles as the `--network` value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomChain {
+    pub name: String,
+    pub chain_id: u64,
+    pub rpc_url: String,
+    #[serde(default)]
+    pub explorer_url: String,
+    /// Symbol of the native coin, e.g. "RBTC"
+    pub symbol: String,
+}
+
+impl CustomChain {
+    /// Check a new definition against the built-in networks and the chains
+    /// registered so far
+    pub fn validate(&self, existing: &[CustomChain]) -> Result<()> {
+        if self.name.is_empty()
+            || !self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(anyhow!(
+                "Chain names may only contain letters, digits, '-' and '_'"
+            ));
+        }
+        if Network::built_in(&self.name).is_some() {
+            return Err(anyhow!("'{}' is a built-in network", self.name));
+        }
+        if self.chain_id == 0 {
+            return Err(anyhow!("The chain id must be greater than zero"));
+        }
+        if let Some(network) = BUILT_IN
+            .iter()
+            .find(|network| network.chain_id() == self.chain_id)
+        {
+            return Err(anyhow!(
+                "Chain {} is the built-in {} network",
+                self.chain_id,
+                network.chain_name()
+            ));
+        }
+        match url::Url::parse(&self.rpc_url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            _ => return Err(anyhow!("The RPC URL must be an http(s) URL")),
+        }
+        if self.symbol.is_empty() || self.symbol.len() > 11 {
+            return Err(anyhow!("The symbol must have 1 to 11 characters"));
+        }
+        for other in existing {
+            if other.name.eq_ignore_ascii_case(&self.name) {
+                return Err(anyhow!("A chain named '{}' already exists", other.name));
+            }
+            if other.chain_id == self.chain_id {
+                return Err(anyhow!(
+                    "Chain {} is already registered as '{}'",
+                    self.chain_id,
+                    other.name
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Custom chains of the loaded configuration. Entries are leaked so that
+/// `Network::chain_name` can hand out `&'static str` for them too; a
+/// definition is only leaked the first time it is seen.
+static CUSTOM_CHAINS: RwLock<Vec<&'static CustomChain>> = RwLock::new(Vec::new());
+
+/// Make `chains` the custom chains `Network::Custom` refers to
+pub fn register_custom_chains(chains: &[CustomChain]) {
+    let mut registered = CUSTOM_CHAINS.write().unwrap_or_else(|e| e.into_inner());
+    let kept = chains
+        .iter()
+        .map(|chain| {
+            registered
+                .iter()
+                .find(|known| **known == chain)
+                .copied()
+                .unwrap_or_else(|| Box::leak(Box::new(chain.clone())))
+        })
+        .collect();
+    *registered = kept;
+}
+
+fn custom_chain(chain_id: u64) -> Option<&'static CustomChain> {
+    CUSTOM_CHAINS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|chain| chain.chain_id == chain_id)
+        .copied()
+}
+
+/// Networks with their own chain, one per chain id
+const BUILT_IN: [Network; 3] = [Network::Mainnet, Network::Testnet, Network::Regtest];
+
+/// Hosted services some features rely on besides the JSON-RPC node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkService {
+    /// Alchemy's enhanced API: transfer history, transaction lookups, webhooks
+    Alchemy,
+    /// Blockscout indexer: transfer history and token balances
+    Blockscout,
+    /// Public block explorer pages
+    Explorer,
+}
+
+impl fmt::Display for NetworkService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkService::Alchemy => write!(f, "the Alchemy API"),
+            NetworkService::Blockscout => write!(f, "the Blockscout API"),
+            NetworkService::Explorer => write!(f, "a block explorer"),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Network {
@@ -18,6 +157,8 @@ pub enum Network {
     AlchemyTestnet,
     RootStockMainnet,
     RootStockTestnet,
+    /// A user-defined chain from the configuration, by chain id
+    Custom(u64),
 }
 
 impl fmt::Display for Network {
@@ -30,6 +171,7 @@ impl fmt::Display for Network {
             Network::AlchemyTestnet => write!(f, "Alchemy Testnet"),
             Network::RootStockMainnet => write!(f, "Rootstock Mainnet"),
             Network::RootStockTestnet => write!(f, "Rootstock Testnet"),
+            Network::Custom(_) => write!(f, "{}", self.chain_name()),
         }
     }
 }
@@ -41,37 +183,59 @@ impl Network {
                 name: "RSK Mainnet".to_string(),
                 rpc_url: "https://public-node.rsk.co".to_string(),
                 explorer_url: "https://explorer.rsk.co".to_string(),
+                fallback_rpc_urls: Vec::new(),
             },
             Network::Testnet => NetworkConfig {
                 name: "RSK Testnet".to_string(),
                 rpc_url: "https://public-node.testnet.rsk.co".to_string(),
                 explorer_url: "https://explorer.testnet.rsk.co".to_string(),
+                fallback_rpc_urls: Vec::new(),
             },
             Network::Regtest => NetworkConfig {
                 name: "RSK Regtest".to_string(),
                 rpc_url: "http://localhost:4444".to_string(),
                 explorer_url: "".to_string(),
+                fallback_rpc_urls: Vec::new(),
             },
             // Legacy network types - use public nodes by default
             Network::AlchemyMainnet => NetworkConfig {
                 name: "RSK Mainnet".to_string(),
                 rpc_url: "https://public-node.rsk.co".to_string(),
                 explorer_url: "https://explorer.rsk.co".to_string(),
+                fallback_rpc_urls: Vec::new(),
             },
             Network::AlchemyTestnet => NetworkConfig {
                 name: "RSK Testnet".to_string(),
                 rpc_url: "https://public-node.testnet.rsk.co".to_string(),
                 explorer_url: "https://explorer.testnet.rsk.co".to_string(),
+                fallback_rpc_urls: Vec::new(),
             },
             Network::RootStockMainnet => NetworkConfig {
                 name: "RSK Mainnet".to_string(),
                 rpc_url: "https://public-node.rsk.co".to_string(),
                 explorer_url: "https://explorer.rsk.co".to_string(),
+                fallback_rpc_urls: Vec::new(),
             },
             Network::RootStockTestnet => NetworkConfig {
                 name: "RSK Testnet".to_string(),
                 rpc_url: "https://public-node.testnet.rsk.co".to_string(),
                 explorer_url: "https://explorer.testnet.rsk.co".to_string(),
+                fallback_rpc_urls: Vec::new(),
+            },
+            Network::Custom(chain_id) => match custom_chain(*chain_id) {
+                Some(chain) => NetworkConfig {
+                    name: chain.name.clone(),
+                    rpc_url: chain.rpc_url.clone(),
+                    explorer_url: chain.explorer_url.clone(),
+                    fallback_rpc_urls: Vec::new(),
+                },
+                // Removed from the configuration; there is nothing to connect to
+                None => NetworkConfig {
+                    name: self.chain_name().to_string(),
+                    rpc_url: String::new(),
+                    explorer_url: String::new(),
+                    fallback_rpc_urls: Vec::new(),
+                },
             },
         }
     }
@@ -112,11 +276,133 @@ impl Network {
                 }
             }
             Network::Regtest => "http://localhost:4444".to_string(),
+            Network::Custom(_) => self.get_config().rpc_url,
         }
     }
 
+    /// EIP-155 chain id, also used for EIP-1191 address checksums
+    pub fn chain_id(&self) -> u64 {
+        match self {
+            Network::Mainnet | Network::AlchemyMainnet | Network::RootStockMainnet => 30,
+            Network::Testnet | Network::AlchemyTestnet | Network::RootStockTestnet => 31,
+            Network::Regtest => 33,
+            Network::Custom(chain_id) => *chain_id,
+        }
+    }
+
+    /// Whether this network uses test funds (Regtest and custom chains count
+    /// as test networks)
+    pub fn is_testnet(&self) -> bool {
+        matches!(
+            self,
+            Network::Testnet
+                | Network::AlchemyTestnet
+                | Network::RootStockTestnet
+                | Network::Regtest
+                | Network::Custom(_)
+        )
+    }
+
+    /// Name of the chain this network connects to, as used by the token
+    /// registry, the hosted APIs and `--network` options
+    pub fn chain_name(&self) -> &'static str {
+        match self {
+            Network::Mainnet | Network::AlchemyMainnet | Network::RootStockMainnet => "mainnet",
+            Network::Testnet | Network::AlchemyTestnet | Network::RootStockTestnet => "testnet",
+            Network::Regtest => "regtest",
+            Network::Custom(chain_id) => {
+                custom_chain(*chain_id).map_or("custom", |chain| chain.name.as_str())
+            }
+        }
+    }
+
+    /// Symbol of the native coin
+    pub fn native_symbol(&self) -> &'static str {
+        match self {
+            Network::Custom(chain_id) => {
+                custom_chain(*chain_id).map_or("RBTC", |chain| chain.symbol.as_str())
+            }
+            _ => "RBTC",
+        }
+    }
+
+    /// The built-in networks, one per chain, then the registered custom
+    /// chains
+    pub fn all() -> Vec<Network> {
+        let mut networks = BUILT_IN.to_vec();
+        networks.extend(
+            CUSTOM_CHAINS
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .iter()
+                .map(|chain| Network::Custom(chain.chain_id)),
+        );
+        networks
+    }
+
+    /// The hosted services available on this network. A local Regtest node
+    /// or a custom chain is only reachable over JSON-RPC.
+    pub fn services(&self) -> &'static [NetworkService] {
+        match self {
+            Network::Regtest | Network::Custom(_) => &[],
+            _ => &[
+                NetworkService::Alchemy,
+                NetworkService::Blockscout,
+                NetworkService::Explorer,
+            ],
+        }
+    }
+
+    pub fn supports(&self, service: NetworkService) -> bool {
+        self.services().contains(&service)
+    }
+
+    /// The closest network that offers `service`: this one, or else the
+    /// public testnet, which holds test funds like Regtest
+    pub fn nearest_with(&self, service: NetworkService) -> Network {
+        if self.supports(service) {
+            *self
+        } else if self.is_testnet() {
+            Network::Testnet
+        } else {
+            Network::Mainnet
+        }
+    }
+
+    /// Fail with a pointer to the nearest network that can run `feature`
+    /// when this one lacks the service it needs
+    pub fn require(&self, service: NetworkService, feature: &str) -> anyhow::Result<()> {
+        if self.supports(service) {
+            return Ok(());
+        }
+        let nearest = self.nearest_with(service);
+        Err(anyhow!(
+            "{} needs {}, which is not available on {}. Use {} instead: switch networks \
+             under Configuration, or pass --network {} where the command accepts it.",
+            feature,
+            service,
+            self,
+            nearest,
+            nearest.chain_name()
+        ))
+    }
+
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
-        match s.to_lowercase().as_str() {
+        Self::built_in(s).or_else(|| {
+            CUSTOM_CHAINS
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .iter()
+                .find(|chain| chain.name.eq_ignore_ascii_case(s))
+                .map(|chain| Network::Custom(chain.chain_id))
+        })
+    }
+
+    /// The network a built-in name stands for; custom chains cannot use
+    /// these names
+    fn built_in(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
             "mainnet" => Some(Network::Mainnet),
             "testnet" => Some(Network::Testnet),
             "regtest" => Some(Network::Regtest),
@@ -128,3 +414,108 @@ impl Network {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capability_matrix() {
+        for network in [
+            Network::Mainnet,
+            Network::AlchemyTestnet,
+            Network::RootStockMainnet,
+        ] {
+            assert!(network.supports(NetworkService::Blockscout));
+            assert!(network.require(NetworkService::Alchemy, "History").is_ok());
+            assert_eq!(
+                Network::from_str(network.chain_name()).unwrap().chain_id(),
+                network.chain_id()
+            );
+        }
+
+        assert!(Network::Regtest.services().is_empty());
+        assert_eq!(
+            Network::Regtest.nearest_with(NetworkService::Explorer),
+            Network::Testnet
+        );
+        let error = Network::Regtest
+            .require(NetworkService::Alchemy, "Transaction history")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("--network testnet"), "{}", error);
+    }
+
+    #[test]
+    fn test_custom_chains() {
+        let devnet = CustomChain {
+            name: "devnet".to_string(),
+            chain_id: 7771,
+            rpc_url: "http://10.0.0.5:4444".to_string(),
+            explorer_url: String::new(),
+            symbol: "dRBTC".to_string(),
+        };
+        assert!(devnet.validate(&[]).is_ok());
+        assert!(devnet.validate(std::slice::from_ref(&devnet)).is_err());
+        for invalid in [
+            CustomChain {
+                name: "Testnet".to_string(),
+                ..devnet.clone()
+            },
+            CustomChain {
+                name: "my chain".to_string(),
+                ..devnet.clone()
+            },
+            CustomChain {
+                rpc_url: "localhost:4444".to_string(),
+                ..devnet.clone()
+            },
+            CustomChain {
+                chain_id: 0,
+                ..devnet.clone()
+            },
+        ] {
+            assert!(invalid.validate(&[]).is_err(), "{:?}", invalid);
+        }
+
+        // Names of the hosted endpoints are taken as well
+        for name in [
+            "alchemy-mainnet",
+            "Alchemy-Testnet",
+            "rootstock-mainnet",
+            "rootstock-testnet",
+        ] {
+            let chain = CustomChain {
+                name: name.to_string(),
+                ..devnet.clone()
+            };
+            let error = chain.validate(&[]).unwrap_err().to_string();
+            assert!(error.contains("built-in network"), "{}", error);
+        }
+        // So are the chain ids of the built-in networks
+        for chain_id in [30, 31, 33] {
+            let chain = CustomChain {
+                chain_id,
+                ..devnet.clone()
+            };
+            let error = chain.validate(&[]).unwrap_err().to_string();
+            assert!(error.contains("is the built-in"), "{}", error);
+        }
+
+        register_custom_chains(std::slice::from_ref(&devnet));
+        let network = Network::from_str("DevNet").unwrap();
+        assert_eq!(network, Network::Custom(7771));
+        assert_eq!(network.chain_id(), 7771);
+        assert_eq!(network.chain_name(), "devnet");
+        assert_eq!(network.native_symbol(), "dRBTC");
+        assert_eq!(
+            network.get_rpc_url_with_key(Some("key"), None),
+            devnet.rpc_url
+        );
+        assert!(network.services().is_empty());
+        assert!(Network::all().contains(&network));
+
+        let json = serde_json::to_string(&network).unwrap();
+        assert_eq!(serde_json::from_str::<Network>(&json).unwrap(), network);
+    }
+}
@@ -1,9 +1,12 @@
 use crate::utils::alchemy::AlchemyClient;
+use crate::utils::blockscout;
+use crate::utils::http;
+use crate::utils::rsk_explorer;
 use anyhow::{Result, anyhow};
 use chrono::{DateTime, Utc};
 use alloy::primitives::{Address, Bytes, B256, U64, U256};
-use alloy::providers::{Provider, ProviderBuilder};
-use alloy::transports::http::{Client, Http};
+use alloy::providers::Provider;
+use alloy::rpc::types::Log;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::str::FromStr;
@@ -31,6 +34,10 @@ pub struct RskTransaction {
     // Additional metadata
     pub confirms: Option<U64>,
     pub cumulative_gas_used: Option<U256>,
+    /// Gas the receipt reports as used, once the transaction is mined;
+    /// `gas` may hold the limit instead
+    #[serde(default)]
+    pub gas_used: Option<U256>,
     pub logs: Option<Vec<alloy::rpc::types::Log>>,
 }
 
@@ -65,6 +72,13 @@ pub struct TransactionReceipt {
 }
 
 impl RskTransaction {
+    /// Fee paid for the transaction, from the gas its receipt reports as
+    /// used; `None` until that is known
+    pub fn fee(&self) -> Option<U256> {
+        self.gas_used
+            .map(|gas_used| gas_used.saturating_mul(self.gas_price))
+    }
+
     /// Converts the transaction to a CSV record
     pub fn to_csv_record(&self) -> csv::StringRecord {
         let timestamp = self
@@ -143,6 +157,7 @@ impl RskTransaction {
         // Get transaction receipt for status and gas used
         let rpc_url = alchemy_client.get_base_url();
         let receipt = Self::get_transaction_receipt(&hash, &rpc_url).await?;
+        let receipt_gas_used = receipt.as_ref().map(|r| r.gas_used);
         let (status, gas_used) = match receipt {
             Some(r) => (r.status, r.gas_used),
             None => (TransactionStatus::Pending, U256::ZERO),
@@ -223,15 +238,227 @@ impl RskTransaction {
             token_address,
             confirms: None, // Would need to be calculated from current block
             cumulative_gas_used: Some(gas_used), // From receipt if available
+            gas_used: receipt_gas_used,
             logs: None,     // Could be populated from receipt if needed
         })
     }
 
+    /// Build a transaction from an activity entry of an Alchemy Notify
+    /// address-activity webhook. Webhooks only report mined transfers, so the
+    /// status is taken as successful and gas details are left empty.
+    pub fn from_webhook_activity(activity: &Value) -> Result<Self> {
+        let hash = activity["hash"]
+            .as_str()
+            .and_then(|s| B256::from_str(s).ok())
+            .ok_or_else(|| anyhow!("Invalid or missing transaction hash in webhook activity"))?;
+
+        let from = activity["fromAddress"]
+            .as_str()
+            .and_then(|s| Address::from_str(s).ok())
+            .ok_or_else(|| anyhow!("Invalid 'fromAddress' in webhook activity"))?;
+
+        let to = activity["toAddress"]
+            .as_str()
+            .and_then(|s| Address::from_str(s).ok());
+
+        let value = activity["rawContract"]["rawValue"]
+            .as_str()
+            .and_then(|s| U256::from_str_radix(s.trim_start_matches("0x"), 16).ok())
+            .unwrap_or_default();
+
+        let block_number = activity["blockNum"]
+            .as_str()
+            .and_then(|s| u64::from_str_radix(s.trim_start_matches("0x"), 16).ok())
+            .map(U64::from);
+
+        let token_address = if activity["category"].as_str() == Some("token") {
+            activity["rawContract"]["address"]
+                .as_str()
+                .and_then(|s| Address::from_str(s).ok())
+        } else {
+            None
+        };
+
+        Ok(Self {
+            hash,
+            from,
+            to,
+            value,
+            gas_price: U256::ZERO,
+            gas: U256::ZERO,
+            nonce: U256::ZERO,
+            input: None,
+            block_number,
+            transaction_index: None,
+            timestamp: SystemTime::now(),
+            status: TransactionStatus::Success,
+            token_address,
+            confirms: None,
+            cumulative_gas_used: None,
+            gas_used: None,
+            logs: None,
+        })
+    }
+
+    /// Build a transaction from an item of the Blockscout
+    /// `/addresses/{address}/transactions` endpoint
+    pub fn from_blockscout_transaction(item: &Value) -> Result<Self> {
+        let hash = item["hash"]
+            .as_str()
+            .and_then(|s| B256::from_str(s).ok())
+            .ok_or_else(|| anyhow!("Invalid or missing transaction hash in Blockscout item"))?;
+        let from = blockscout::address(&item["from"])
+            .ok_or_else(|| anyhow!("Invalid 'from' address in Blockscout item"))?;
+
+        // `status` is null until the transaction is mined
+        let status = match item["status"].as_str() {
+            Some("ok") => TransactionStatus::Success,
+            Some("error") => TransactionStatus::Failed,
+            _ => TransactionStatus::Pending,
+        };
+        let receipt_gas_used = blockscout::number(&item["gas_used"]);
+        let gas_used = receipt_gas_used.unwrap_or_default();
+
+        Ok(Self {
+            hash,
+            from,
+            to: blockscout::address(&item["to"]),
+            value: blockscout::number(&item["value"]).unwrap_or_default(),
+            gas_price: blockscout::number(&item["gas_price"]).unwrap_or_default(),
+            gas: gas_used,
+            nonce: blockscout::number(&item["nonce"]).unwrap_or_default(),
+            input: None,
+            block_number: blockscout_block(item),
+            transaction_index: blockscout::number::<u64>(&item["position"]).map(U64::from),
+            timestamp: blockscout_timestamp(item),
+            status,
+            token_address: None,
+            confirms: blockscout::number::<u64>(&item["confirmations"]).map(U64::from),
+            cumulative_gas_used: Some(gas_used),
+            gas_used: receipt_gas_used,
+            logs: None,
+        })
+    }
+
+    /// Build a transaction from an item of the Blockscout
+    /// `/addresses/{address}/token-transfers` endpoint. Only mined transfers
+    /// are listed there, so the status is taken as successful.
+    pub fn from_blockscout_token_transfer(item: &Value) -> Result<Self> {
+        let hash = item["transaction_hash"]
+            .as_str()
+            .or_else(|| item["tx_hash"].as_str())
+            .and_then(|s| B256::from_str(s).ok())
+            .ok_or_else(|| anyhow!("Invalid or missing transaction hash in Blockscout transfer"))?;
+        let from = blockscout::address(&item["from"])
+            .ok_or_else(|| anyhow!("Invalid 'from' address in Blockscout transfer"))?;
+
+        Ok(Self {
+            hash,
+            from,
+            to: blockscout::address(&item["to"]),
+            value: blockscout::number(&item["total"]["value"]).unwrap_or_default(),
+            gas_price: U256::ZERO,
+            gas: U256::ZERO,
+            nonce: U256::ZERO,
+            input: None,
+            block_number: blockscout_block(item),
+            transaction_index: None,
+            timestamp: blockscout_timestamp(item),
+            status: TransactionStatus::Success,
+            token_address: blockscout::token_address(&item["token"]),
+            confirms: None,
+            cumulative_gas_used: None,
+            gas_used: None,
+            logs: None,
+        })
+    }
+
+    /// Build a transaction from an item of the Rootstock explorer
+    /// `/transactions/address/{address}` endpoint
+    pub fn from_explorer_transaction(item: &Value) -> Result<Self> {
+        let hash = item["hash"]
+            .as_str()
+            .and_then(|s| B256::from_str(s).ok())
+            .ok_or_else(|| anyhow!("Invalid or missing transaction hash in explorer item"))?;
+        let from = item["from"]
+            .as_str()
+            .and_then(|s| Address::from_str(s).ok())
+            .ok_or_else(|| anyhow!("Invalid 'from' address in explorer item"))?;
+
+        // The receipt is missing until the transaction is mined
+        let receipt = &item["receipt"];
+        let status = match rsk_explorer::number::<u64>(&receipt["status"]) {
+            Some(1) => TransactionStatus::Success,
+            Some(_) => TransactionStatus::Failed,
+            None => TransactionStatus::Pending,
+        };
+        let receipt_gas_used = rsk_explorer::number(&receipt["gasUsed"]);
+        let gas_used = receipt_gas_used.unwrap_or_default();
+
+        Ok(Self {
+            hash,
+            from,
+            to: item["to"].as_str().and_then(|s| Address::from_str(s).ok()),
+            value: rsk_explorer::number(&item["value"]).unwrap_or_default(),
+            gas_price: rsk_explorer::number(&item["gasPrice"]).unwrap_or_default(),
+            gas: gas_used,
+            nonce: rsk_explorer::number(&item["nonce"]).unwrap_or_default(),
+            input: None,
+            block_number: rsk_explorer::number::<u64>(&item["blockNumber"]).map(U64::from),
+            transaction_index: rsk_explorer::number::<u64>(&item["transactionIndex"])
+                .map(U64::from),
+            timestamp: rsk_explorer::number::<u64>(&item["timestamp"])
+                .map(|t| UNIX_EPOCH + std::time::Duration::from_secs(t))
+                .unwrap_or_else(SystemTime::now),
+            status,
+            token_address: None,
+            confirms: None,
+            cumulative_gas_used: rsk_explorer::number(&receipt["cumulativeGasUsed"]),
+            gas_used: receipt_gas_used,
+            logs: None,
+        })
+    }
+
+    /// Build a token transfer from an ERC-20 `Transfer` log read from the
+    /// node. Only successful transactions emit logs; the fee is not known.
+    pub fn from_transfer_log(log: &Log, timestamp: SystemTime) -> Result<Self> {
+        let hash = log
+            .transaction_hash
+            .ok_or_else(|| anyhow!("Transfer log has no transaction hash"))?;
+        let [_, from, to] = log.topics() else {
+            return Err(anyhow!("Log in {} is not an ERC-20 Transfer", hash));
+        };
+        let data = &log.data().data;
+
+        Ok(Self {
+            hash,
+            from: Address::from_word(*from),
+            to: Some(Address::from_word(*to)),
+            value: data
+                .get(..32)
+                .map(U256::from_be_slice)
+                .unwrap_or_default(),
+            gas_price: U256::ZERO,
+            gas: U256::ZERO,
+            nonce: U256::ZERO,
+            input: None,
+            block_number: log.block_number.map(U64::from),
+            transaction_index: log.transaction_index.map(U64::from),
+            timestamp,
+            status: TransactionStatus::Success,
+            token_address: Some(log.address()),
+            confirms: None,
+            cumulative_gas_used: None,
+            gas_used: None,
+            logs: None,
+        })
+    }
+
     async fn get_transaction_receipt(
         hash: &B256,
         rpc_url: &str,
     ) -> Result<Option<TransactionReceipt>> {
-        let provider = ProviderBuilder::new().on_http(rpc_url.parse()?);
+        let provider = http::provider(rpc_url)?;
         let receipt = provider.get_transaction_receipt(*hash).await?;
 
         Ok(receipt.map(|r| TransactionReceipt {
@@ -248,3 +475,18 @@ impl RskTransaction {
         }))
     }
 }
+
+/// Block number of a Blockscout item; older versions call it `block`
+fn blockscout_block(item: &Value) -> Option<U64> {
+    blockscout::number::<u64>(&item["block_number"])
+        .or_else(|| blockscout::number(&item["block"]))
+        .map(U64::from)
+}
+
+fn blockscout_timestamp(item: &Value) -> SystemTime {
+    item["timestamp"]
+        .as_str()
+        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+        .map(|dt| UNIX_EPOCH + std::time::Duration::from_secs(dt.timestamp().max(0) as u64))
+        .unwrap_or_else(SystemTime::now)
+}
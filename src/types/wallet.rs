@@ -1,4 +1,10 @@
+use crate::security::secrets;
+use crate::security::session::DerivedKeys;
 use crate::types::contacts::Contact;
+use crate::utils::audit;
+use crate::utils::lockout::{self, LockoutState};
+use crate::utils::mnemonic::{self, MnemonicLanguage};
+use crate::utils::storage::Storage;
 use aes::Aes256;
 use anyhow::Result;
 use anyhow::{Error, anyhow};
@@ -7,15 +13,16 @@ use base64::{self, Engine as _};
 use cbc::cipher::block_padding::Pkcs7;
 use cbc::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
 use cbc::{Decryptor, Encryptor};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use alloy::primitives::{Address, U256};
-use alloy::signers::{local::PrivateKeySigner, Signer};
+use alloy::signers::local::PrivateKeySigner;
 use generic_array::GenericArray;
 use rand::{RngCore, rngs::OsRng};
 use scrypt::{Params, scrypt};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
+use zeroize::Zeroizing;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Wallet {
@@ -27,6 +34,65 @@ pub struct Wallet {
     pub salt: String,
     pub iv: String,
     pub created_at: String,
+    /// Archived wallets are kept for reference but can no longer sign
+    #[serde(default)]
+    pub watch_only: bool,
+    /// Set for wallets whose key lives on a hardware device; the encrypted
+    /// key fields are then empty
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hardware: Option<HardwareAccount>,
+    /// Recovery phrase of wallets created from a mnemonic, encrypted with
+    /// the wallet password
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<EncryptedSeed>,
+    /// Further accounts derived from the recovery phrase; the wallet's own
+    /// address is account 0
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accounts: Vec<DerivedAccount>,
+}
+
+/// An account derived from the wallet's recovery phrase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DerivedAccount {
+    /// Index N in `m/44'/137'/0'/0/N`
+    pub index: u32,
+    pub address: Address,
+    pub name: String,
+}
+
+/// A recovery phrase encrypted like the private key, with its own salt and IV
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedSeed {
+    pub encrypted_phrase: String,
+    pub salt: String,
+    pub iv: String,
+    /// Wordlist code, see [`MnemonicLanguage::code`]
+    pub language: String,
+}
+
+/// Supported hardware wallets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HardwareDevice {
+    Trezor,
+}
+
+impl fmt::Display for HardwareDevice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HardwareDevice::Trezor => write!(f, "Trezor"),
+        }
+    }
+}
+
+/// The device and account backing a hardware wallet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardwareAccount {
+    pub device: HardwareDevice,
+    pub derivation_path: String,
+    /// When the address was last confirmed on the device's screen
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address_verified_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,9 +120,55 @@ impl Wallet {
             salt: STANDARD.encode(&salt),
             iv: STANDARD.encode(&iv),
             created_at: Utc::now().to_rfc3339(),
+            watch_only: false,
+            hardware: None,
+            seed: None,
+            accounts: Vec::new(),
         })
     }
 
+    /// Keep an encrypted copy of the recovery phrase the key was derived from
+    pub fn with_seed(
+        mut self,
+        phrase: &str,
+        language: MnemonicLanguage,
+        password: &str,
+    ) -> Result<Self, Error> {
+        let phrase = mnemonic::normalize_phrase(phrase);
+        if mnemonic::signer_from_phrase(&phrase, language)?.address() != self.address {
+            return Err(anyhow!(
+                "The recovery phrase does not belong to wallet '{}'",
+                self.name
+            ));
+        }
+        let (encrypted_phrase, iv, salt) = Self::encrypt_private_key(phrase.as_bytes(), password)?;
+        self.seed = Some(EncryptedSeed {
+            encrypted_phrase: STANDARD.encode(&encrypted_phrase),
+            salt: STANDARD.encode(&salt),
+            iv: STANDARD.encode(&iv),
+            language: language.code().to_string(),
+        });
+        Ok(self)
+    }
+
+    /// A wallet whose key stays on a hardware device
+    pub fn new_hardware(address: Address, name: &str, hardware: HardwareAccount) -> Self {
+        Self {
+            address,
+            balance: U256::ZERO,
+            network: String::new(),
+            name: name.to_string(),
+            encrypted_private_key: String::new(),
+            salt: String::new(),
+            iv: String::new(),
+            created_at: Utc::now().to_rfc3339(),
+            watch_only: false,
+            hardware: Some(hardware),
+            seed: None,
+            accounts: Vec::new(),
+        }
+    }
+
     pub fn encrypt_private_key(
         private_key: &[u8],
         password: &str,
@@ -77,22 +189,337 @@ impl Wallet {
         Ok((buffer, iv.to_vec(), salt.to_vec()))
     }
 
+    /// Decrypt the private key, throttling repeated wrong passwords.
+    ///
+    /// Failed attempts are counted per wallet; see [`crate::utils::lockout`]
+    /// for the lockout schedule. Failures and lockouts are written to the
+    /// audit log.
     pub fn decrypt_private_key(&self, password: &str) -> Result<String, anyhow::Error> {
-        // Decode Base64-encoded salt, IV, and encrypted key
+        self.unlock_throttled(|| self.decrypt_key_material(password))
+    }
+
+    /// Derive the keys that decrypt this wallet, for an unlock session.
+    ///
+    /// The password is checked like [`Wallet::decrypt_private_key`], so
+    /// wrong passwords count towards the lockout. The recovery phrase key is
+    /// only derived when the wallet has derived accounts that need it.
+    pub fn derive_keys(&self, password: &str) -> Result<DerivedKeys, anyhow::Error> {
+        self.unlock_throttled(|| {
+            self.ensure_local_key()?;
+            let key = Self::derive_secret_key(password, &self.salt)?;
+            if self.key_material_with(&key)?.is_none() {
+                return Ok(None);
+            }
+            let seed_key = match &self.seed {
+                Some(seed) if !self.accounts.is_empty() => {
+                    Some(Self::derive_secret_key(password, &seed.salt)?)
+                }
+                _ => None,
+            };
+            Ok(Some(DerivedKeys { key, seed_key }))
+        })
+    }
+
+    /// Decrypt the key of account `index` with keys from [`Wallet::derive_keys`]
+    pub fn decrypt_with_keys(&self, keys: &DerivedKeys, index: u32) -> Result<String, Error> {
+        let private_key = self
+            .key_material_with(&keys.key)?
+            .ok_or_else(|| anyhow!("The unlock session for '{}' is no longer valid", self.name))?;
+        if index == 0 {
+            return Ok(private_key);
+        }
+        let seed = self
+            .seed
+            .as_ref()
+            .ok_or_else(|| anyhow!("'{}' has no stored recovery phrase", self.name))?;
+        let seed_key = keys
+            .seed_key
+            .as_ref()
+            .ok_or_else(|| anyhow!("The unlock session for '{}' has no phrase key", self.name))?;
+        let decrypted = Self::decrypt_with_key(&seed.encrypted_phrase, &seed.iv, seed_key)?
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(|| anyhow!("Failed to decrypt the recovery phrase of '{}'", self.name))?;
+        self.account_key_from_phrase(&decrypted, seed.language.parse()?, index)
+    }
+
+    /// Run an unlock attempt, refusing while the wallet is locked out and
+    /// recording failures (`Ok(None)`) and successes towards the lockout
+    fn unlock_throttled<T>(
+        &self,
+        attempt: impl FnOnce() -> Result<Option<T>, anyhow::Error>,
+    ) -> Result<T, anyhow::Error> {
+        if self.watch_only {
+            return Err(anyhow!(
+                "Wallet '{}' is archived as watch-only and cannot sign transactions",
+                self.name
+            ));
+        }
+
+        let wallet_key = format!("0x{:x}", self.address);
+        let now = Utc::now();
+        let mut state = LockoutState::load().unwrap_or_else(|e| {
+            log::warn!("Failed to load lockout state: {}", e);
+            LockoutState::default()
+        });
+
+        if let Some(remaining) = state.remaining_lockout(&wallet_key, now) {
+            audit::record_or_warn(
+                "unlock_blocked",
+                Some(&wallet_key),
+                format!("'{}' is locked out", self.name),
+            );
+            return Err(anyhow!(
+                "Too many failed password attempts for '{}'. Try again in {}.",
+                self.name,
+                lockout::format_duration(remaining)
+            ));
+        }
+
+        match attempt()? {
+            Some(unlocked) => {
+                let failures = state.record_success(&wallet_key);
+                if failures > 0 {
+                    if let Err(e) = state.save() {
+                        log::warn!("Failed to save lockout state: {}", e);
+                    }
+                    audit::record_or_warn(
+                        "unlock_succeeded",
+                        Some(&wallet_key),
+                        format!(
+                            "'{}' unlocked after {} failed attempt(s)",
+                            self.name, failures
+                        ),
+                    );
+                }
+                Ok(unlocked)
+            }
+            None => {
+                let lockout_period = state.record_failure(&wallet_key, now);
+                if let Err(e) = state.save() {
+                    log::warn!("Failed to save lockout state: {}", e);
+                }
+                let failures = state.wallets.get(&wallet_key).map_or(0, |r| r.failures);
+                audit::record_or_warn(
+                    "password_failed",
+                    Some(&wallet_key),
+                    format!("Wrong password for '{}' (attempt {})", self.name, failures),
+                );
+
+                match lockout_period {
+                    Some(period) => {
+                        audit::record_or_warn(
+                            "wallet_locked",
+                            Some(&wallet_key),
+                            format!(
+                                "'{}' locked for {}",
+                                self.name,
+                                lockout::format_duration(period)
+                            ),
+                        );
+                        Err(anyhow!(
+                            "Incorrect password. '{}' is locked for {} after {} failed attempts.",
+                            self.name,
+                            lockout::format_duration(period),
+                            failures
+                        ))
+                    }
+                    None => Err(anyhow!(
+                        "Incorrect password ({} attempt(s) left before a temporary lockout)",
+                        lockout::FREE_ATTEMPTS - failures
+                    )),
+                }
+            }
+        }
+    }
+
+    /// Decrypt a wallet loaded from a backup file, returning `None` if the
+    /// password is wrong. Unlike [`Wallet::decrypt_private_key`] this does not
+    /// count towards the lockout of installed wallets, so recovery drills can
+    /// be rehearsed without locking the real wallet.
+    pub fn decrypt_backup(&self, password: &str) -> Result<Option<String>, anyhow::Error> {
+        self.decrypt_key_material(password)
+    }
+
+    /// Decrypt the stored key, returning `None` if the password is wrong
+    fn decrypt_key_material(&self, password: &str) -> Result<Option<String>, anyhow::Error> {
+        self.ensure_local_key()?;
+        let key = Self::derive_secret_key(password, &self.salt)?;
+        self.key_material_with(&key)
+    }
+
+    fn ensure_local_key(&self) -> Result<(), anyhow::Error> {
+        match &self.hardware {
+            Some(hardware) => Err(anyhow!(
+                "'{}' is a {} wallet; its key never leaves the device",
+                self.name,
+                hardware.device
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Decrypt the stored key with a derived key, returning `None` if the
+    /// key is wrong
+    fn key_material_with(&self, key: &[u8; 32]) -> Result<Option<String>, anyhow::Error> {
+        let Some(decrypted) = Self::decrypt_with_key(&self.encrypted_private_key, &self.iv, key)?
+        else {
+            return Ok(None);
+        };
+        // A wrong password yields garbage: bad padding, a key of the wrong
+        // length, or a key for a different address
+        if decrypted.len() != 32 {
+            return Ok(None);
+        }
+        match PrivateKeySigner::from_slice(&decrypted) {
+            Ok(signer) if signer.address() == self.address => {}
+            _ => return Ok(None),
+        }
+
+        // Return the decrypted private key as a 0x-prefixed hex string
+        Ok(Some(format!("0x{}", hex::encode(decrypted))))
+    }
+
+    /// Address of account `index`; account 0 is the wallet itself
+    pub fn account_address(&self, index: u32) -> Option<Address> {
+        if index == 0 {
+            return Some(self.address);
+        }
+        self.accounts
+            .iter()
+            .find(|account| account.index == index)
+            .map(|account| account.address)
+    }
+
+    /// Derive the next account from the stored recovery phrase and keep it
+    pub fn add_account(&mut self, password: &str, name: &str) -> Result<DerivedAccount, Error> {
+        // Unlock through the lockout-protected path before touching the seed
+        self.decrypt_private_key(password)?;
+        let (phrase, language) = self
+            .decrypt_seed(password)?
+            .ok_or_else(|| anyhow!("Failed to decrypt the recovery phrase of '{}'", self.name))?;
+        let index = self
+            .accounts
+            .iter()
+            .map(|account| account.index)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let account = DerivedAccount {
+            index,
+            address: mnemonic::signer_at(&phrase, language, index)?.address(),
+            name: name.to_string(),
+        };
+        self.accounts.push(account.clone());
+        Ok(account)
+    }
+
+    /// Decrypt the key of account `index` as a 0x-prefixed hex string
+    pub fn decrypt_account_key(&self, password: &str, index: u32) -> Result<String, Error> {
+        let private_key = self.decrypt_private_key(password)?;
+        if index == 0 {
+            return Ok(private_key);
+        }
+        let (phrase, language) = self
+            .decrypt_seed(password)?
+            .ok_or_else(|| anyhow!("Failed to decrypt the recovery phrase of '{}'", self.name))?;
+        self.account_key_from_phrase(&phrase, language, index)
+    }
+
+    /// Derive the key of account `index` from the recovery phrase, checking
+    /// it matches the stored account address
+    fn account_key_from_phrase(
+        &self,
+        phrase: &str,
+        language: MnemonicLanguage,
+        index: u32,
+    ) -> Result<String, Error> {
+        let address = self
+            .account_address(index)
+            .ok_or_else(|| anyhow!("'{}' has no account #{}", self.name, index))?;
+        let signer = mnemonic::signer_at(phrase, language, index)?;
+        if signer.address() != address {
+            return Err(anyhow!(
+                "Account #{} of '{}' does not match its recovery phrase",
+                index,
+                self.name
+            ));
+        }
+        Ok(format!("0x{}", hex::encode(signer.to_bytes())))
+    }
+
+    /// Decrypt the stored recovery phrase, returning `None` if the password
+    /// is wrong. Fails if the wallet was not created from a phrase.
+    pub fn decrypt_seed(
+        &self,
+        password: &str,
+    ) -> Result<Option<(String, MnemonicLanguage)>, anyhow::Error> {
+        let seed = self
+            .seed
+            .as_ref()
+            .ok_or_else(|| anyhow!("'{}' has no stored recovery phrase", self.name))?;
+        let language: MnemonicLanguage = seed.language.parse()?;
+        let Some(decrypted) =
+            Self::decrypt_secret(&seed.encrypted_phrase, &seed.salt, &seed.iv, password)?
+        else {
+            return Ok(None);
+        };
+        let Ok(phrase) = String::from_utf8(decrypted) else {
+            return Ok(None);
+        };
+        match mnemonic::signer_from_phrase(&phrase, language) {
+            Ok(signer) if signer.address() == self.address => Ok(Some((phrase, language))),
+            _ => Ok(None),
+        }
+    }
+
+    /// Decrypt Base64 fields written by [`Wallet::encrypt_private_key`],
+    /// returning `None` if the padding is invalid
+    fn decrypt_secret(
+        encrypted: &str,
+        salt: &str,
+        iv: &str,
+        password: &str,
+    ) -> Result<Option<Vec<u8>>, anyhow::Error> {
+        let key = Self::derive_secret_key(password, salt)?;
+        Self::decrypt_with_key(encrypted, iv, &key)
+    }
+
+    /// Derive the AES key for a Base64 salt using scrypt with parameters
+    /// matching encryption
+    fn derive_secret_key(password: &str, salt: &str) -> Result<Zeroizing<[u8; 32]>, anyhow::Error> {
         let salt = STANDARD
-            .decode(&self.salt)
+            .decode(salt)
             .map_err(|e| anyhow!("Failed to decode salt: {}", e))?;
+        if salt.len() != 16 {
+            return Err(anyhow!("Salt must be 16 bytes, got {} bytes", salt.len()));
+        }
+        let mut key = Zeroizing::new([0u8; 32]);
+        scrypt(
+            password.as_bytes(),
+            &salt,
+            &Params::recommended(),
+            &mut *key,
+        )
+        .map_err(|e| anyhow!("Key derivation failed: {}", e))?;
+        Ok(key)
+    }
+
+    /// Decrypt Base64 fields with a derived key, returning `None` if the
+    /// padding is invalid
+    fn decrypt_with_key(
+        encrypted: &str,
+        iv: &str,
+        key: &[u8; 32],
+    ) -> Result<Option<Vec<u8>>, anyhow::Error> {
+        // Decode Base64-encoded IV and encrypted key
         let iv = STANDARD
-            .decode(&self.iv)
+            .decode(iv)
             .map_err(|e| anyhow!("Failed to decode IV: {}", e))?;
         let encrypted_key = STANDARD
-            .decode(&self.encrypted_private_key)
+            .decode(encrypted)
             .map_err(|e| anyhow!("Failed to decode encrypted private key: {}", e))?;
 
         // Validate lengths
-        if salt.len() != 16 {
-            return Err(anyhow!("Salt must be 16 bytes, got {} bytes", salt.len()));
-        }
         if iv.len() != 16 {
             return Err(anyhow!("IV must be 16 bytes, got {} bytes", iv.len()));
         }
@@ -103,12 +530,6 @@ impl Wallet {
             ));
         }
 
-        // Derive the key using scrypt with parameters matching encryption
-        let mut key = [0u8; 32];
-        let params = Params::recommended(); // Ensure this matches your encryption params
-        scrypt(password.as_bytes(), &salt, &params, &mut key)
-            .map_err(|e| anyhow!("Key derivation failed: {}", e))?;
-
         // Convert key and IV to GenericArray for the cipher
         let key_array = GenericArray::from_slice(&key[..]); // returns &GenericArray<u8, U32>
         let iv_array = GenericArray::from_slice(&iv[..]); // returns &GenericArray<u8, U16>
@@ -118,20 +539,10 @@ impl Wallet {
 
         // Create a mutable buffer for decryption
         let mut buffer = encrypted_key.clone(); // Clone to make it mutable
-        let decrypted = cipher
-            .decrypt_padded_mut::<Pkcs7>(&mut buffer)
-            .map_err(|e| anyhow!("Decryption failed: {}", e))?;
-
-        // Ensure the decrypted key is exactly 32 bytes
-        if decrypted.len() != 32 {
-            return Err(anyhow!(
-                "Decrypted private key has invalid length: {} bytes (expected 32)",
-                decrypted.len()
-            ));
+        match cipher.decrypt_padded_mut::<Pkcs7>(&mut buffer) {
+            Ok(decrypted) => Ok(Some(decrypted.to_vec())),
+            Err(_) => Ok(None),
         }
-
-        // Return the decrypted private key as a 0x-prefixed hex string
-        Ok(format!("0x{}", hex::encode(decrypted)))
     }
 }
 
@@ -162,6 +573,33 @@ impl WalletData {
         }
     }
 
+    /// Loads all wallets from the wallet database
+    pub fn load() -> anyhow::Result<Self> {
+        Storage::open()?.load_wallets()
+    }
+
+    /// Writes all wallets to the wallet database in one transaction
+    pub fn save(&self) -> anyhow::Result<()> {
+        Storage::open()?.save_wallets(self)
+    }
+
+    /// The saved Alchemy API key, read from the keyring when it is enabled
+    pub fn stored_api_key(&self) -> anyhow::Result<Option<String>> {
+        Ok(secrets::alchemy_api_key()?.or_else(|| self.api_key.clone()))
+    }
+
+    /// Saves the Alchemy API key to the keyring when it is enabled, otherwise
+    /// to the wallet database
+    pub fn set_api_key(&mut self, key: String) -> anyhow::Result<()> {
+        if secrets::is_enabled() {
+            secrets::set_alchemy_api_key(&key)?;
+            self.api_key = None;
+        } else {
+            self.api_key = Some(key);
+        }
+        self.save()
+    }
+
     pub fn add_wallet(&mut self, wallet: Wallet) -> anyhow::Result<()> {
         let address = format!("0x{:x}", wallet.address);
         if self.wallets.contains_key(&address) {
@@ -176,6 +614,17 @@ impl WalletData {
         self.wallets.get(&self.current_wallet)
     }
 
+    /// The wallet and account index that `address` belongs to, whether it is
+    /// a wallet's own address or one of its derived accounts
+    pub fn find_account(&self, address: Address) -> Option<(&Wallet, u32)> {
+        self.wallets.values().find_map(|wallet| {
+            std::iter::once(0)
+                .chain(wallet.accounts.iter().map(|a| a.index))
+                .find(|index| wallet.account_address(*index) == Some(address))
+                .map(|index| (wallet, index))
+        })
+    }
+
     pub fn switch_wallet(&mut self, address: &str) -> anyhow::Result<()> {
         if !self.wallets.contains_key(address) {
             return Err(anyhow!("Wallet with address {} not found", address));
@@ -188,6 +637,10 @@ impl WalletData {
         self.wallets.values().find(|w| w.name == name)
     }
 
+    pub fn get_wallet_by_name_mut(&mut self, name: &str) -> Option<&mut Wallet> {
+        self.wallets.values_mut().find(|w| w.name == name)
+    }
+
     pub fn remove_wallet(&mut self, address: &str) -> anyhow::Result<()> {
         if !self.wallets.contains_key(address) {
             return Err(anyhow!("Wallet with address {} not found", address));
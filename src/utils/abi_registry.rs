@@ -0,0 +1,148 @@
+//! Contracts registered once by name, address and ABI, so the contract menu
+//! can call them without asking for the ABI file again. Like the token
+//! registry, entries are kept per network in the wallet database.
+
+use crate::utils::storage::Storage;
+use alloy::json_abi::JsonAbi;
+use alloy::primitives::Address;
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisteredContract {
+    pub name: String,
+    pub address: Address,
+    pub abi: JsonAbi,
+}
+
+/// Registered contracts by network, keyed by lowercase name
+#[derive(Debug, Default, Clone)]
+pub struct AbiRegistry {
+    pub mainnet: BTreeMap<String, RegisteredContract>,
+    pub testnet: BTreeMap<String, RegisteredContract>,
+}
+
+impl AbiRegistry {
+    pub fn load() -> Result<Self> {
+        Storage::open()?.load_contract_abis()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        Storage::open()?.save_contract_abis(self)
+    }
+
+    fn network(&self, network: &str) -> &BTreeMap<String, RegisteredContract> {
+        match network {
+            "mainnet" => &self.mainnet,
+            _ => &self.testnet,
+        }
+    }
+
+    fn network_mut(&mut self, network: &str) -> &mut BTreeMap<String, RegisteredContract> {
+        match network {
+            "mainnet" => &mut self.mainnet,
+            _ => &mut self.testnet,
+        }
+    }
+
+    pub fn add(&mut self, network: &str, contract: RegisteredContract) -> Result<()> {
+        let name = contract.name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("Contract name cannot be empty"));
+        }
+        let contracts = self.network(network);
+        if contracts.contains_key(&name.to_lowercase()) {
+            return Err(anyhow!(
+                "A contract named '{}' is already registered on {}",
+                name,
+                network
+            ));
+        }
+        if let Some(existing) = contracts.values().find(|c| c.address == contract.address) {
+            return Err(anyhow!(
+                "0x{:x} is already registered on {} as '{}'",
+                contract.address,
+                network,
+                existing.name
+            ));
+        }
+        let contract = RegisteredContract {
+            name: name.to_string(),
+            ..contract
+        };
+        self.network_mut(network)
+            .insert(contract.name.to_lowercase(), contract);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, network: &str, name: &str) -> Result<RegisteredContract> {
+        self.network_mut(network)
+            .remove(&name.trim().to_lowercase())
+            .ok_or_else(|| anyhow!("No contract named '{}' on {}", name, network))
+    }
+
+    /// The contract registered under `name`, ignoring case
+    pub fn get(&self, network: &str, name: &str) -> Option<&RegisteredContract> {
+        self.network(network).get(&name.trim().to_lowercase())
+    }
+
+    /// Registered contracts on `network`, by name
+    pub fn list(&self, network: &str) -> Vec<&RegisteredContract> {
+        self.network(network).values().collect()
+    }
+}
+
+/// Read an ABI from a JSON file holding either the ABI itself or a build
+/// artifact (Hardhat, Foundry, Truffle) with an `abi` field
+pub fn read_abi_file(path: &Path) -> Result<JsonAbi> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    parse_abi(&content).with_context(|| format!("Failed to parse the ABI in {}", path.display()))
+}
+
+fn parse_abi(content: &str) -> Result<JsonAbi> {
+    let value: serde_json::Value = serde_json::from_str(content)?;
+    let abi = match value {
+        serde_json::Value::Object(mut artifact) => artifact
+            .remove("abi")
+            .ok_or_else(|| anyhow!("Expected an ABI array or an object with an 'abi' field"))?,
+        abi => abi,
+    };
+    Ok(serde_json::from_value(abi)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABI: &str = r#"[{"type":"function","name":"totalSupply","inputs":[],"outputs":[{"name":"","type":"uint256"}],"stateMutability":"view"}]"#;
+
+    #[test]
+    fn test_register_and_look_up_by_name() {
+        let abi = parse_abi(ABI).unwrap();
+        let artifact = parse_abi(&format!(r#"{{"contractName":"Vault","abi":{}}}"#, ABI)).unwrap();
+        assert_eq!(artifact, abi);
+
+        let mut registry = AbiRegistry::default();
+        let vault = RegisteredContract {
+            name: " Vault ".to_string(),
+            address: Address::repeat_byte(0x11),
+            abi,
+        };
+        registry.add("testnet", vault.clone()).unwrap();
+        assert_eq!(registry.get("testnet", "vault").unwrap().name, "Vault");
+        assert!(registry.get("mainnet", "vault").is_none());
+        assert!(registry.add("testnet", vault.clone()).is_err());
+        let renamed = RegisteredContract {
+            name: "Other".to_string(),
+            ..vault
+        };
+        assert!(registry.add("testnet", renamed).is_err());
+
+        registry.remove("testnet", "VAULT").unwrap();
+        assert!(registry.list("testnet").is_empty());
+    }
+}
@@ -0,0 +1,416 @@
+//! Export of wallet history to plaintext accounting journals.
+//!
+//! Each transaction becomes one balanced entry between the wallet's asset
+//! account and a counter-account chosen from its category (see
+//! [`crate::utils::categories`]). Fees of outgoing transactions are booked
+//! to a separate expense account, in RBTC. Transactions allocated to a cost
+//! center (see [`crate::utils::cost_centers`]) carry it as metadata.
+
+use crate::types::transaction::{RskTransaction, TransactionStatus};
+use crate::utils::categories::{self, CategoryRule};
+use alloy::primitives::{Address, B256, U256, utils::format_units};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::time::UNIX_EPOCH;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalFormat {
+    Beancount,
+    Ledger,
+}
+
+/// Accounts used when exporting, editable from the configuration menu
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccountMapping {
+    /// Asset account holding the wallet's funds
+    pub wallet_account: String,
+    /// Expense account for network fees
+    pub fee_account: String,
+    /// Parent of derived accounts for incoming transfers, e.g. `Income:Crypto:Salary`
+    pub income_root: String,
+    /// Parent of derived accounts for outgoing transfers, e.g. `Expenses:Crypto:Rent`
+    pub expense_root: String,
+    /// Explicit counter-account per category, overriding the derived ones
+    pub categories: BTreeMap<String, String>,
+}
+
+impl Default for AccountMapping {
+    fn default() -> Self {
+        Self {
+            wallet_account: "Assets:Crypto:Rootstock".to_string(),
+            fee_account: "Expenses:Fees:Rootstock".to_string(),
+            income_root: "Income:Crypto".to_string(),
+            expense_root: "Expenses:Crypto".to_string(),
+            categories: BTreeMap::new(),
+        }
+    }
+}
+
+impl AccountMapping {
+    /// Counter-account for a transfer in the given category
+    pub fn counter_account(&self, category: Option<&str>, incoming: bool) -> String {
+        if let Some(account) = category.and_then(|c| self.categories.get(c)) {
+            return account.clone();
+        }
+        let root = if incoming {
+            &self.income_root
+        } else {
+            &self.expense_root
+        };
+        format!("{}:{}", root, account_component(category.unwrap_or("")))
+    }
+}
+
+/// Check an account name has the `Type:Component:...` shape both tools accept
+pub fn validate_account(account: &str) -> Result<()> {
+    let mut parts = account.split(':');
+    let root = parts.next().unwrap_or_default();
+    if !["Assets", "Liabilities", "Equity", "Income", "Expenses"].contains(&root) {
+        return Err(anyhow!(
+            "Account '{}' must start with Assets, Liabilities, Equity, Income or Expenses",
+            account
+        ));
+    }
+    for part in parts {
+        let valid = part
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        if !valid {
+            return Err(anyhow!(
+                "Invalid account component '{}' in '{}'; use letters, digits and '-', starting with a capital",
+                part,
+                account
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Turn a category name such as "office supplies" into `OfficeSupplies`
+fn account_component(category: &str) -> String {
+    let component: String = category
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase());
+            first.into_iter().chain(chars).collect::<String>()
+        })
+        .collect();
+    if component.is_empty() {
+        "Uncategorized".to_string()
+    } else {
+        component
+    }
+}
+
+/// Commodity name valid in both formats, e.g. `RBTC` or `USDT0`
+fn commodity(symbol: &str) -> String {
+    let mut name: String = symbol
+        .to_ascii_uppercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .take(24)
+        .collect();
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        name.insert(0, 'T');
+    }
+    name
+}
+
+/// Decimal amount without trailing zeros
+pub fn format_amount(value: U256, decimals: u8) -> String {
+    let formatted = format_units(value, decimals).unwrap_or_else(|_| value.to_string());
+    if formatted.contains('.') {
+        formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    } else {
+        formatted
+    }
+}
+
+struct Posting {
+    account: String,
+    amount: String,
+    commodity: String,
+}
+
+struct Entry {
+    date: String,
+    payee: String,
+    narration: String,
+    hash: String,
+    cost_center: Option<String>,
+    postings: Vec<Posting>,
+}
+
+/// Render the wallet's transactions as a journal.
+///
+/// `tokens` maps token contracts to their symbol and decimals; unknown
+/// tokens are named after their address. `cost_centers` maps transaction
+/// hashes to the cost center they were allocated to. Pending transactions
+/// are skipped and failed ones only book their fee.
+pub fn export(
+    format: JournalFormat,
+    txs: &[RskTransaction],
+    wallet: Address,
+    rules: &[CategoryRule],
+    mapping: &AccountMapping,
+    tokens: &HashMap<Address, (String, u8)>,
+    cost_centers: &HashMap<B256, String>,
+) -> String {
+    let mut txs: Vec<&RskTransaction> = txs
+        .iter()
+        .filter(|tx| tx.status != TransactionStatus::Pending)
+        .collect();
+    txs.sort_by_key(|tx| tx.timestamp);
+
+    let entries: Vec<Entry> = txs
+        .into_iter()
+        .filter_map(|tx| entry(tx, wallet, rules, mapping, tokens, cost_centers))
+        .collect();
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "; Rootstock wallet {:#x}, exported {}\n",
+        wallet,
+        Utc::now().format("%Y-%m-%d")
+    );
+
+    if format == JournalFormat::Beancount
+        && let Some(first) = entries.first()
+    {
+        // Beancount rejects postings to accounts that were never opened
+        let accounts: BTreeSet<&str> = entries
+            .iter()
+            .flat_map(|e| e.postings.iter().map(|p| p.account.as_str()))
+            .collect();
+        for account in accounts {
+            let _ = writeln!(out, "{} open {}", first.date, account);
+        }
+        out.push('\n');
+    }
+
+    for entry in &entries {
+        match format {
+            JournalFormat::Beancount => {
+                let _ = writeln!(
+                    out,
+                    "{} * \"{}\" \"{}\"",
+                    entry.date, entry.payee, entry.narration
+                );
+                let _ = writeln!(out, "  tx: \"{}\"", entry.hash);
+                if let Some(cost_center) = &entry.cost_center {
+                    let _ = writeln!(out, "  cost-center: \"{}\"", cost_center.replace('"', "'"));
+                }
+                for posting in &entry.postings {
+                    let _ = writeln!(
+                        out,
+                        "  {:<40} {} {}",
+                        posting.account, posting.amount, posting.commodity
+                    );
+                }
+            }
+            JournalFormat::Ledger => {
+                let _ = writeln!(out, "{} * {}", entry.date.replace('-', "/"), entry.payee);
+                let _ = writeln!(out, "    ; {}", entry.narration);
+                let _ = writeln!(out, "    ; tx: {}", entry.hash);
+                if let Some(cost_center) = &entry.cost_center {
+                    let _ = writeln!(out, "    ; cost-center: {}", cost_center);
+                }
+                for posting in &entry.postings {
+                    // Ledger needs quotes around commodities containing digits
+                    let commodity = if posting.commodity.chars().all(|c| c.is_ascii_alphabetic()) {
+                        posting.commodity.clone()
+                    } else {
+                        format!("\"{}\"", posting.commodity)
+                    };
+                    let _ = writeln!(
+                        out,
+                        "    {:<40}  {} {}",
+                        posting.account, posting.amount, commodity
+                    );
+                }
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn entry(
+    tx: &RskTransaction,
+    wallet: Address,
+    rules: &[CategoryRule],
+    mapping: &AccountMapping,
+    tokens: &HashMap<Address, (String, u8)>,
+    cost_centers: &HashMap<B256, String>,
+) -> Option<Entry> {
+    let outgoing = tx.from == wallet;
+    let incoming = tx.to == Some(wallet);
+    if !outgoing && !incoming {
+        return None;
+    }
+
+    let category = categories::categorize(rules, tx);
+    let (symbol, decimals) = match tx.token_address {
+        Some(token) => tokens.get(&token).cloned().unwrap_or_else(|| {
+            let hex = format!("{:x}", token);
+            (format!("TKN{}", &hex[..6]), 18)
+        }),
+        None => ("RBTC".to_string(), 18),
+    };
+    let commodity = commodity(&symbol);
+    let mut postings = Vec::new();
+
+    // A self-transfer or a failed transaction moves nothing but the fee
+    let moved = tx.status == TransactionStatus::Success && outgoing != incoming;
+    if moved && !tx.value.is_zero() {
+        let amount = format_amount(tx.value, decimals);
+        let counter = mapping.counter_account(category, incoming);
+        let (wallet_sign, counter_sign) = if incoming { ("", "-") } else { ("-", "") };
+        postings.push(Posting {
+            account: mapping.wallet_account.clone(),
+            amount: format!("{}{}", wallet_sign, amount),
+            commodity: commodity.clone(),
+        });
+        postings.push(Posting {
+            account: counter,
+            amount: format!("{}{}", counter_sign, amount),
+            commodity,
+        });
+    }
+
+    let fee = tx.gas_price.saturating_mul(tx.gas);
+    if outgoing && !fee.is_zero() {
+        let fee = format_amount(fee, 18);
+        postings.push(Posting {
+            account: mapping.fee_account.clone(),
+            amount: fee.clone(),
+            commodity: "RBTC".to_string(),
+        });
+        postings.push(Posting {
+            account: mapping.wallet_account.clone(),
+            amount: format!("-{}", fee),
+            commodity: "RBTC".to_string(),
+        });
+    }
+
+    if postings.is_empty() {
+        return None;
+    }
+
+    let counterparty = if incoming && !outgoing {
+        Some(tx.from)
+    } else {
+        tx.to
+    };
+    let timestamp = tx
+        .timestamp
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let narration = match (tx.status, category) {
+        (TransactionStatus::Failed, _) => "Failed transaction".to_string(),
+        (_, Some(category)) => category.to_string(),
+        _ if outgoing && incoming => "Self-transfer".to_string(),
+        _ if incoming => format!("Received {}", symbol),
+        _ => format!("Sent {}", symbol),
+    };
+    Some(Entry {
+        date: DateTime::from_timestamp(timestamp, 0)
+            .unwrap_or_default()
+            .format("%Y-%m-%d")
+            .to_string(),
+        payee: counterparty
+            .map(|a| format!("{:#x}", a))
+            .unwrap_or_else(|| "Contract creation".to_string()),
+        narration: narration.replace('"', "'"),
+        hash: format!("{:#x}", tx.hash),
+        cost_center: cost_centers.get(&tx.hash).cloned(),
+        postings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::B256;
+    use std::time::{Duration, SystemTime};
+
+    fn tx(from: Address, to: Address, value: u64, gas: u64) -> RskTransaction {
+        RskTransaction {
+            hash: B256::repeat_byte(0xab),
+            from,
+            to: Some(to),
+            value: U256::from(value),
+            gas_price: U256::from(60_000_000u64),
+            gas: U256::from(gas),
+            nonce: U256::ZERO,
+            input: None,
+            block_number: None,
+            transaction_index: None,
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            status: TransactionStatus::Success,
+            token_address: None,
+            confirms: None,
+            cumulative_gas_used: None,
+            gas_used: None,
+            logs: None,
+        }
+    }
+
+    #[test]
+    fn test_beancount_export() {
+        let wallet = Address::repeat_byte(1);
+        let shop = Address::repeat_byte(2);
+        let rules = vec![CategoryRule {
+            category: "office supplies".to_string(),
+            to: Some(shop),
+            from: None,
+            asset: None,
+            min_amount: None,
+            max_amount: None,
+        }];
+        let txs = vec![
+            tx(wallet, shop, 500_000_000_000_000_000, 21_000),
+            tx(shop, wallet, 2_000_000_000_000_000_000, 21_000),
+        ];
+        let journal = export(
+            JournalFormat::Beancount,
+            &txs,
+            wallet,
+            &rules,
+            &AccountMapping::default(),
+            &HashMap::new(),
+            &HashMap::from([(B256::repeat_byte(0xab), "Marketing".to_string())]),
+        );
+
+        assert!(journal.contains("2023-11-14 open Expenses:Crypto:OfficeSupplies"));
+        assert!(journal.contains("\"office supplies\""));
+        assert!(journal.contains("-0.5 RBTC"));
+        assert!(journal.contains("Expenses:Fees:Rootstock"));
+        // Only the outgoing transfer books a fee for the wallet
+        assert_eq!(journal.matches("0.00000126 RBTC").count(), 2);
+        assert!(journal.contains("Income:Crypto:Uncategorized"));
+        assert!(journal.contains("  cost-center: \"Marketing\""));
+    }
+
+    #[test]
+    fn test_account_names() {
+        assert!(validate_account("Assets:Crypto:Rootstock").is_ok());
+        assert!(validate_account("Crypto:Rootstock").is_err());
+        assert!(validate_account("Expenses:office").is_err());
+        assert_eq!(commodity("usdt0"), "USDT0");
+        assert_eq!(commodity("1inch"), "T1INCH");
+    }
+}
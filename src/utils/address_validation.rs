@@ -0,0 +1,220 @@
+//! Checks for lists of addresses, e.g. before loading a payout CSV.
+//!
+//! Offline checks (format, checksum, duplicates, blocklist, look-alikes of
+//! curated contracts) are done here; the on-chain ones (contract or EOA,
+//! prior activity) are filled in by the `validate addresses` command.
+
+use crate::commands::address::parse_address;
+use crate::utils::profiles::{self, ProfileMatch};
+use alloy::primitives::{Address, U256};
+use anyhow::{Context, Result, anyhow};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// An address as it appears in the input file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// 1-based line number
+    pub line: usize,
+    pub input: String,
+}
+
+/// Read the addresses from a file with one per line, or from a CSV whose
+/// rows contain one (the first address-like field is used). A header row is
+/// skipped, as are blank lines and `#` comments.
+pub fn read_entries(content: &str) -> Vec<Entry> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let field = line
+                .split([',', ';', '\t', ' '])
+                .map(|field| field.trim().trim_matches('"'))
+                .find(|field| looks_like_address(field));
+            match field {
+                Some(field) => Some(Entry {
+                    line: i + 1,
+                    input: field.to_string(),
+                }),
+                // Header row
+                None if i == 0 => None,
+                None => Some(Entry {
+                    line: i + 1,
+                    input: line.to_string(),
+                }),
+            }
+        })
+        .collect()
+}
+
+fn looks_like_address(field: &str) -> bool {
+    let hex = field
+        .strip_prefix("0x")
+        .or_else(|| field.strip_prefix("0X"));
+    match hex {
+        Some(_) => true,
+        None => matches!(field.len(), 40 | 64) && field.chars().all(|c| c.is_ascii_hexdigit()),
+    }
+}
+
+/// Addresses in a blocklist file (same format as the input list)
+pub fn load_blocklist(path: &Path) -> Result<HashSet<Address>> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    read_entries(&content)
+        .into_iter()
+        .map(|entry| {
+            parse_address(&entry.input, 30)
+                .or_else(|_| parse_address(&entry.input.to_lowercase(), 30))
+                .map_err(|e| anyhow!("{}:{}: {}", path.display(), entry.line, e))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Checksum {
+    /// Mixed case with a valid EIP-1191 or EIP-55 checksum
+    Valid,
+    /// All lowercase or uppercase, so typos cannot be detected
+    Missing,
+}
+
+/// Validation result for one entry
+#[derive(Debug, Clone)]
+pub struct AddressReport {
+    pub entry: Entry,
+    /// Why the entry is not a usable address
+    pub error: Option<String>,
+    pub address: Option<Address>,
+    pub checksum: Option<Checksum>,
+    /// Line of an earlier entry with the same address
+    pub duplicate_of: Option<usize>,
+    pub blocked: bool,
+    pub profile: Option<ProfileMatch>,
+    pub is_contract: Option<bool>,
+    /// Transactions sent from the address
+    pub tx_count: Option<u64>,
+    pub balance: Option<U256>,
+}
+
+impl AddressReport {
+    /// Run the offline checks for every entry
+    pub fn check_all(
+        entries: Vec<Entry>,
+        chain_id: u64,
+        network: &str,
+        blocklist: &HashSet<Address>,
+    ) -> Vec<Self> {
+        let mut seen: HashMap<Address, usize> = HashMap::new();
+        entries
+            .into_iter()
+            .map(|entry| {
+                let mut report = Self {
+                    error: None,
+                    address: None,
+                    checksum: None,
+                    duplicate_of: None,
+                    blocked: false,
+                    profile: None,
+                    is_contract: None,
+                    tx_count: None,
+                    balance: None,
+                    entry,
+                };
+                match parse_address(&report.entry.input, chain_id) {
+                    Ok(address) => {
+                        let hex = report.entry.input.trim_start_matches("0x");
+                        let mixed = hex.chars().any(|c| c.is_ascii_uppercase())
+                            && hex.chars().any(|c| c.is_ascii_lowercase());
+                        report.checksum = Some(if mixed {
+                            Checksum::Valid
+                        } else {
+                            Checksum::Missing
+                        });
+                        report.duplicate_of = seen.get(&address).copied();
+                        seen.entry(address).or_insert(report.entry.line);
+                        report.blocked = blocklist.contains(&address);
+                        report.profile = Some(profiles::check_address(network, &address));
+                        report.address = Some(address);
+                    }
+                    Err(e) => report.error = Some(e.to_string()),
+                }
+                report
+            })
+            .collect()
+    }
+
+    /// Problems that should stop the list from being used
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if let Some(error) = &self.error {
+            problems.push(error.clone());
+        }
+        if self.blocked {
+            problems.push("on the blocklist".to_string());
+        }
+        if let Some(ProfileMatch::LookAlike(profile)) = &self.profile {
+            problems.push(format!(
+                "looks like {} ({}) but is not it",
+                profile.name, profile.protocol
+            ));
+        }
+        problems
+    }
+
+    /// Findings worth a second look
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if let Some(line) = self.duplicate_of {
+            warnings.push(format!("duplicate of line {}", line));
+        }
+        if self.checksum == Some(Checksum::Missing) {
+            warnings.push("no checksum".to_string());
+        }
+        if self.is_contract == Some(true)
+            && !matches!(self.profile, Some(ProfileMatch::Verified(_)))
+        {
+            warnings.push("unknown contract".to_string());
+        }
+        if self.tx_count == Some(0) && self.balance.is_some_and(|b| b.is_zero()) {
+            warnings.push("no prior activity".to_string());
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payout_csv_checks() {
+        let a = Address::repeat_byte(0xab);
+        let b = Address::repeat_byte(0x01);
+        let csv = format!(
+            "name,address,amount\nalice,{},10\n# comment\n\nbob,\"{:#x}\",5\ncarol,0x1234,1\nalice again,{:#x},10\n",
+            a.to_checksum(Some(30)),
+            b,
+            a
+        );
+        let entries = read_entries(&csv);
+        assert_eq!(
+            entries.iter().map(|e| e.line).collect::<Vec<_>>(),
+            vec![2, 5, 6, 7]
+        );
+
+        let blocklist = HashSet::from([b]);
+        let reports = AddressReport::check_all(entries, 30, "mainnet", &blocklist);
+        assert_eq!(reports[0].checksum, Some(Checksum::Valid));
+        assert!(reports[0].problems().is_empty());
+        assert_eq!(reports[1].problems(), vec!["on the blocklist"]);
+        assert!(reports[2].address.is_none() && !reports[2].problems().is_empty());
+        assert_eq!(reports[3].duplicate_of, Some(2));
+        assert!(reports[3].warnings().contains(&"no checksum".to_string()));
+    }
+}
@@ -1,12 +1,36 @@
 // src/utils/alchemy.rs
+use crate::api::{ApiProvider, rate_limit};
+use crate::config::PollingConfig;
+use crate::utils::http;
+use crate::utils::retry::Backoff;
 use anyhow::{Result, anyhow};
 use reqwest::Client;
 use serde_json::Value;
+use std::sync::OnceLock;
+
+/// Endpoint used instead of the hosted Alchemy API, when one is set
+static BASE_URL: OnceLock<String> = OnceLock::new();
+
+/// Send every Alchemy request to `url` instead of the hosted API, e.g. to a
+/// local mock server. Only the first call has an effect.
+pub fn set_base_url(url: String) {
+    let _ = BASE_URL.set(url);
+}
+
+/// Alchemy JSON-RPC endpoint for Rootstock mainnet or testnet
+pub fn base_url(is_testnet: bool) -> String {
+    if let Some(url) = BASE_URL.get() {
+        return url.clone();
+    }
+    let network = if is_testnet { "testnet" } else { "mainnet" };
+    format!("https://rootstock-{}.g.alchemy.com/v2", network)
+}
 
 pub struct AlchemyClient {
     client: Client,
     api_key: String,
     is_testnet: bool,
+    backoff: Backoff,
 }
 
 impl AlchemyClient {
@@ -17,26 +41,49 @@ impl AlchemyClient {
     //         is_testnet,
     //     }
     // }
-    pub fn new(api_key: String, is_testnet: bool) -> Self {
-        let client = Client::builder()
-            .https_only(true) // Restrict to HTTPS
-            .use_rustls_tls() // Use rustls for TLS (more secure, avoids system-specific issues)
-            .build()
-            .expect("Failed to build reqwest client");
-        Self {
-            client,
+    pub fn new(api_key: String, is_testnet: bool) -> Result<Self> {
+        Ok(Self {
+            client: http::secure_client()?,
             api_key,
             is_testnet,
-        }
+            backoff: PollingConfig::current().rpc_backoff(),
+        })
     }
 
-    pub fn get_base_url(&self) -> String {
-        let network = if self.is_testnet {
-            "testnet"
-        } else {
-            "mainnet"
+    /// Post a JSON-RPC request, retrying with backoff when Alchemy rate
+    /// limits it, has a server error or cannot be reached
+    async fn post_rpc(&self, body: &Value) -> Result<Value> {
+        let url = self.get_base_url();
+        let method = body["method"].as_str().unwrap_or_default();
+        let send = || async {
+            rate_limit::acquire(
+                &ApiProvider::Alchemy,
+                rate_limit::alchemy_compute_units(method),
+            )
+            .await;
+            let response = self
+                .client
+                .post(&url)
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .json(body)
+                .send()
+                .await?;
+            if http::is_transient_status(response.status()) {
+                return response.error_for_status();
+            }
+            Ok(response)
         };
-        format!("https://rootstock-{}.g.alchemy.com/v2", network)
+        self.backoff
+            .run(send, http::is_transient)
+            .await
+            .map_err(|e| anyhow!("Request failed: {}", e))?
+            .json::<Value>()
+            .await
+            .map_err(|e| anyhow!("Failed to parse response: {}", e))
+    }
+
+    pub fn get_base_url(&self) -> String {
+        base_url(self.is_testnet)
     }
 
     pub async fn get_asset_transfers(
@@ -46,8 +93,6 @@ impl AlchemyClient {
         from_block: Option<&str>,
         to_block: Option<&str>,
     ) -> Result<Value> {
-        let url = self.get_base_url();
-
         let params = serde_json::json!([{
             "fromBlock": from_block.unwrap_or("0x0"),
             "toBlock": to_block.unwrap_or("latest"),
@@ -59,21 +104,52 @@ impl AlchemyClient {
         }]);
 
         let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .json(&serde_json::json!({
+            .post_rpc(&serde_json::json!({
                 "jsonrpc": "2.0",
                 "id": 1,
                 "method": "alchemy_getAssetTransfers",
                 "params": params
             }))
-            .send()
-            .await
-            .map_err(|e| anyhow!("Request failed: {}", e))?
-            .json::<Value>()
-            .await
-            .map_err(|e| anyhow!("Failed to parse response: {}", e))?;
+            .await?;
+
+        if let Some(error) = response.get("error") {
+            return Err(anyhow!("Alchemy API error: {}", error));
+        }
+
+        Ok(response)
+    }
+
+    /// Fetch ERC20 transfers of a single token contract sent from (or, when
+    /// `incoming` is set, received by) the given address.
+    pub async fn get_token_transfers(
+        &self,
+        address: &str,
+        contract_address: &str,
+        limit: u32,
+        incoming: bool,
+    ) -> Result<Value> {
+        let address_field = if incoming { "toAddress" } else { "fromAddress" };
+
+        let params = serde_json::json!([{
+            "fromBlock": "0x0",
+            "toBlock": "latest",
+            address_field: address,
+            "contractAddresses": [contract_address],
+            "category": ["erc20"],
+            "withMetadata": true,
+            "excludeZeroValue": false,
+            "order": "desc",
+            "maxCount": format!("0x{:x}", limit),
+        }]);
+
+        let response = self
+            .post_rpc(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "alchemy_getAssetTransfers",
+                "params": params
+            }))
+            .await?;
 
         if let Some(error) = response.get("error") {
             return Err(anyhow!("Alchemy API error: {}", error));
@@ -83,23 +159,15 @@ impl AlchemyClient {
     }
 
     pub async fn get_block_by_number(&self, block_number: u64) -> Result<Option<Value>> {
-        let url = self.get_base_url();
         let block_number_hex = format!("0x{:x}", block_number);
 
         let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .json(&serde_json::json!({
+            .post_rpc(&serde_json::json!({
                 "jsonrpc": "2.0",
                 "id": 1,
                 "method": "eth_getBlockByNumber",
                 "params": [block_number_hex, false]  // false to get transaction hashes only
             }))
-            .send()
-            .await
-            .map_err(|e| anyhow!("Request failed: {}", e))?
-            .json::<Value>()
             .await?;
 
         if let Some(error) = response.get("error") {
@@ -110,4 +178,78 @@ impl AlchemyClient {
             .get("result")
             .and_then(|r| if r.is_null() { None } else { Some(r.clone()) }))
     }
+
+    /// Register an Alchemy Notify "address activity" webhook for the given
+    /// addresses. `auth_token` is the Notify auth token from the Alchemy
+    /// dashboard, not the API key.
+    pub async fn create_address_webhook(
+        &self,
+        auth_token: &str,
+        webhook_url: &str,
+        addresses: &[String],
+    ) -> Result<Value> {
+        let network = if self.is_testnet {
+            "ROOTSTOCK_TESTNET"
+        } else {
+            "ROOTSTOCK_MAINNET"
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/create-webhook", NOTIFY_API_URL))
+            .header("X-Alchemy-Token", auth_token)
+            .json(&serde_json::json!({
+                "network": network,
+                "webhook_type": "ADDRESS_ACTIVITY",
+                "webhook_url": webhook_url,
+                "addresses": addresses,
+            }))
+            .send()
+            .await
+            .map_err(|e| anyhow!("Request failed: {}", e))?;
+
+        let status = response.status();
+        let body = response
+            .json::<Value>()
+            .await
+            .map_err(|e| anyhow!("Failed to parse response: {}", e))?;
+        if !status.is_success() {
+            return Err(anyhow!("Alchemy Notify API error ({}): {}", status, body));
+        }
+
+        body.get("data")
+            .cloned()
+            .ok_or_else(|| anyhow!("Invalid response format from Alchemy Notify"))
+    }
+
+    /// Add and remove addresses tracked by an existing webhook
+    pub async fn update_webhook_addresses(
+        &self,
+        auth_token: &str,
+        webhook_id: &str,
+        add: &[String],
+        remove: &[String],
+    ) -> Result<()> {
+        let response = self
+            .client
+            .patch(format!("{}/update-webhook-addresses", NOTIFY_API_URL))
+            .header("X-Alchemy-Token", auth_token)
+            .json(&serde_json::json!({
+                "webhook_id": webhook_id,
+                "addresses_to_add": add,
+                "addresses_to_remove": remove,
+            }))
+            .send()
+            .await
+            .map_err(|e| anyhow!("Request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Alchemy Notify API error ({}): {}", status, body));
+        }
+        Ok(())
+    }
 }
+
+const NOTIFY_API_URL: &str = "https://dashboard.alchemy.com/api";
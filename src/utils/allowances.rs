@@ -0,0 +1,161 @@
+//! ERC-20 allowances granted by a wallet.
+//!
+//! Spenders are found from the `Approval` logs the wallet emitted, read
+//! straight from the node, so no indexer or API key is needed. Nodes limit
+//! the range of log queries, so only the last [`SCAN_BLOCKS`] blocks are
+//! scanned; an older approval can still be checked by token and spender. An
+//! `Approval` log only shows what was approved at the time, so the amount
+//! left is read from the token with `allowance`.
+
+use crate::utils::eth::EthClient;
+use alloy::primitives::{Address, B256, U256, b256};
+use alloy::providers::Provider;
+use alloy::rpc::types::{Filter, Log};
+use anyhow::{Result, anyhow};
+
+/// `keccak256("Approval(address,address,uint256)")`
+pub const APPROVAL_TOPIC: B256 =
+    b256!("8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925");
+/// Blocks scanned back from the head, about a month of Rootstock blocks
+pub const SCAN_BLOCKS: u64 = 90_000;
+/// Blocks covered by a single log query
+const CHUNK_BLOCKS: u64 = 1_000;
+
+/// An allowance a wallet has granted and not used up or revoked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Allowance {
+    pub token: Address,
+    pub spender: Address,
+    pub amount: U256,
+}
+
+impl Allowance {
+    /// Whether this is an "infinite" approval; dApps approve `2^256 - 1`,
+    /// which some tokens decrease as it is spent
+    pub fn is_unlimited(&self) -> bool {
+        self.amount >= U256::MAX >> 1
+    }
+}
+
+/// Allowances `owner` has granted in the last [`SCAN_BLOCKS`] blocks that
+/// are still above zero, most recently approved first
+pub async fn granted(client: &EthClient, owner: Address) -> Result<Vec<Allowance>> {
+    let provider = client.provider();
+    let head = provider
+        .get_block_number()
+        .await
+        .map_err(|e| anyhow!("Failed to get block number: {}", e))?;
+    let oldest = head.saturating_sub(SCAN_BLOCKS);
+
+    let mut logs = Vec::new();
+    let mut to_block = head;
+    loop {
+        let from_block = to_block.saturating_sub(CHUNK_BLOCKS - 1).max(oldest);
+        let filter = Filter::new()
+            .event_signature(APPROVAL_TOPIC)
+            .topic1(owner.into_word())
+            .from_block(from_block)
+            .to_block(to_block);
+        logs.extend(
+            provider
+                .get_logs(&filter)
+                .await
+                .map_err(|e| anyhow!("Failed to fetch logs: {}", e))?,
+        );
+        if from_block == oldest {
+            break;
+        }
+        to_block = from_block - 1;
+    }
+
+    let mut allowances = Vec::new();
+    for (token, spender) in approved_pairs(logs) {
+        let amount = client.get_allowance(token, owner, spender).await?;
+        if !amount.is_zero() {
+            allowances.push(Allowance {
+                token,
+                spender,
+                amount,
+            });
+        }
+    }
+    Ok(allowances)
+}
+
+/// Distinct token and spender pairs among `logs`, newest first. ERC-721
+/// approvals (which index the token id as a fourth topic) are left out.
+fn approved_pairs(mut logs: Vec<Log>) -> Vec<(Address, Address)> {
+    logs.retain(|log| log.topics().len() == 3);
+    logs.sort_by_key(|log| std::cmp::Reverse((log.block_number, log.log_index)));
+    let mut pairs = Vec::new();
+    for log in logs {
+        let pair = (log.address(), Address::from_word(log.topics()[2]));
+        if !pairs.contains(&pair) {
+            pairs.push(pair);
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Bytes, keccak256};
+
+    #[test]
+    fn test_approved_pairs() {
+        assert_eq!(
+            APPROVAL_TOPIC,
+            keccak256("Approval(address,address,uint256)")
+        );
+
+        let owner = Address::repeat_byte(1);
+        let approval = |block: u64, token: u8, spender: u8, topics: usize| {
+            let mut all = vec![
+                APPROVAL_TOPIC,
+                owner.into_word(),
+                Address::repeat_byte(spender).into_word(),
+                B256::ZERO,
+            ];
+            all.truncate(topics);
+            Log {
+                inner: alloy::primitives::Log::new_unchecked(
+                    Address::repeat_byte(token),
+                    all,
+                    Bytes::from(U256::MAX.to_be_bytes::<32>().to_vec()),
+                ),
+                block_number: Some(block),
+                log_index: Some(0),
+                ..Default::default()
+            }
+        };
+
+        let pairs = approved_pairs(vec![
+            approval(10, 7, 2, 3),
+            approval(12, 7, 2, 3),
+            approval(11, 8, 2, 3),
+            approval(13, 9, 3, 4),
+        ]);
+        assert_eq!(
+            pairs,
+            vec![
+                (Address::repeat_byte(7), Address::repeat_byte(2)),
+                (Address::repeat_byte(8), Address::repeat_byte(2)),
+            ]
+        );
+
+        let unlimited = Allowance {
+            token: Address::repeat_byte(7),
+            spender: Address::repeat_byte(2),
+            amount: U256::MAX - U256::from(5),
+        };
+        assert!(unlimited.is_unlimited());
+        assert!(
+            !Allowance {
+                amount: U256::from(1000),
+                ..unlimited
+            }
+            .is_unlimited()
+        );
+    }
+}
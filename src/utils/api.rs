@@ -65,10 +65,8 @@ impl ApiKeys {
     }
 
     fn get_config_path() -> Result<PathBuf> {
-        let mut path = dirs::config_dir()
-            .context("Could not find config directory")?
-            .join("rootstock-wallet");
-        
+        let mut path = crate::config::profile::config_dir()?;
+
         std::fs::create_dir_all(&path)?;
         path.push(API_KEYS_FILE);
         Ok(path)
@@ -0,0 +1,63 @@
+//! Append-only audit log of security relevant events.
+//!
+//! Entries are written as JSON lines to `audit.log` in the data directory.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub event: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet: Option<String>,
+    pub detail: String,
+}
+
+pub fn path() -> Result<PathBuf> {
+    let dir = dirs::data_local_dir()
+        .context("Failed to get data directory")?
+        .join("rootstock-wallet");
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join("audit.log"))
+}
+
+/// Append an entry to the audit log
+pub fn record(event: &str, wallet: Option<&str>, detail: impl Into<String>) -> Result<()> {
+    let entry = AuditEntry {
+        timestamp: Utc::now(),
+        event: event.to_string(),
+        wallet: wallet.map(str::to_string),
+        detail: detail.into(),
+    };
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path()?)
+        .context("Failed to open audit log")?;
+    writeln!(file, "{}", serde_json::to_string(&entry)?).context("Failed to write audit log")
+}
+
+/// Like [`record`], but only logs a warning if the entry cannot be written
+pub fn record_or_warn(event: &str, wallet: Option<&str>, detail: impl Into<String>) {
+    if let Err(e) = record(event, wallet, detail) {
+        log::warn!("Failed to write audit log entry '{}': {}", event, e);
+    }
+}
+
+/// Read all entries, skipping lines that cannot be parsed
+pub fn load() -> Result<Vec<AuditEntry>> {
+    let path = path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path).context("Failed to read audit log")?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
@@ -0,0 +1,182 @@
+//! Encrypted packages of signing activity for auditors.
+//!
+//! A package holds every transaction signed in a period, with its raw
+//! payload and metadata, plus the audit-log entries of the same period. It
+//! is encrypted to the auditor's secp256k1 public key using the ECIES scheme
+//! of the `eccrypto` library (ECDH, SHA-512 KDF, AES-256-CBC, HMAC-SHA256),
+//! so it can be opened with standard tooling and the auditor's private key.
+
+use crate::utils::audit::{self, AuditEntry};
+use crate::utils::signing_log::{self, SignedTx};
+use aes::Aes256;
+use anyhow::{Context, Result, anyhow};
+use cbc::Encryptor;
+use cbc::cipher::block_padding::Pkcs7;
+use cbc::cipher::{BlockEncryptMut, KeyIvInit};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use hmac::{Hmac, Mac};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::{PublicKey, SecretKey, ecdh};
+use rand::{RngCore, rngs::OsRng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+
+/// Identifies the encryption scheme in the package envelope
+pub const FORMAT: &str = "ecies-secp256k1-sha512-aes256cbc-hmacsha256";
+
+/// Everything signed in a period, before encryption
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditPackage {
+    pub generated_at: DateTime<Utc>,
+    /// First day of the period (UTC), inclusive
+    pub period_start: NaiveDate,
+    /// Last day of the period (UTC), inclusive
+    pub period_end: NaiveDate,
+    pub transactions: Vec<SignedTx>,
+    pub audit_log: Vec<AuditEntry>,
+}
+
+impl AuditPackage {
+    /// Collect the transactions and audit entries from `start` to `end`,
+    /// both days included
+    pub fn build(start: NaiveDate, end: NaiveDate) -> Result<Self> {
+        if end < start {
+            return Err(anyhow!("The period ends before it starts"));
+        }
+        let from = start.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        let until = end
+            .checked_add_days(Days::new(1))
+            .ok_or_else(|| anyhow!("Invalid end date"))?
+            .and_hms_opt(0, 0, 0)
+            .unwrap_or_default()
+            .and_utc();
+
+        let audit_log = audit::load()?
+            .into_iter()
+            .filter(|entry| entry.timestamp >= from && entry.timestamp < until)
+            .collect();
+        Ok(Self {
+            generated_at: Utc::now(),
+            period_start: start,
+            period_end: end,
+            transactions: signing_log::between(from, until)?,
+            audit_log,
+        })
+    }
+
+    /// Encrypt the package as JSON to `recipient`
+    pub fn encrypt(&self, recipient: &PublicKey) -> Result<EncryptedPackage> {
+        let plaintext = serde_json::to_vec(self)?;
+        let mut package = encrypt(recipient, &plaintext)?;
+        package.sha256 = hex::encode(Sha256::digest(&plaintext));
+        Ok(package)
+    }
+}
+
+/// Encrypted package as written to disk. Binary fields are hex encoded and
+/// correspond to the `iv`, `ephemPublicKey`, `ciphertext` and `mac` buffers
+/// of `eccrypto.decrypt`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EncryptedPackage {
+    pub format: String,
+    /// Auditor public key the package is encrypted to (compressed, hex)
+    pub recipient: String,
+    pub iv: String,
+    pub ephem_public_key: String,
+    pub ciphertext: String,
+    pub mac: String,
+    /// SHA-256 of the decrypted JSON, to confirm the package opened intact
+    pub sha256: String,
+}
+
+/// Parse an auditor public key: hex SEC1 (compressed or uncompressed), or
+/// the 64-byte uncompressed form without its `04` prefix
+pub fn parse_public_key(input: &str) -> Result<PublicKey> {
+    let input = input.trim();
+    let mut bytes = hex::decode(input.strip_prefix("0x").unwrap_or(input))
+        .context("The public key is not valid hex")?;
+    if bytes.len() == 64 {
+        bytes.insert(0, 0x04);
+    }
+    PublicKey::from_sec1_bytes(&bytes).map_err(|_| anyhow!("Invalid secp256k1 public key"))
+}
+
+/// ECDH key derivation shared by encryption and decryption: the SHA-512 of
+/// the shared x coordinate, split into the AES key and the MAC key
+fn derive_keys(secret: &SecretKey, public: &PublicKey) -> ([u8; 32], [u8; 32]) {
+    let shared = ecdh::diffie_hellman(secret.to_nonzero_scalar(), public.as_affine());
+    let hash = Sha512::digest(shared.raw_secret_bytes());
+    let mut encryption_key = [0u8; 32];
+    let mut mac_key = [0u8; 32];
+    encryption_key.copy_from_slice(&hash[..32]);
+    mac_key.copy_from_slice(&hash[32..]);
+    (encryption_key, mac_key)
+}
+
+fn mac(key: &[u8; 32], iv: &[u8], ephem_public_key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key).map_err(|e| anyhow!("Invalid MAC key: {}", e))?;
+    mac.update(iv);
+    mac.update(ephem_public_key);
+    mac.update(ciphertext);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+fn encrypt(recipient: &PublicKey, plaintext: &[u8]) -> Result<EncryptedPackage> {
+    let ephemeral = SecretKey::random(&mut OsRng);
+    let ephem_public_key = ephemeral.public_key().to_encoded_point(false);
+    let (encryption_key, mac_key) = derive_keys(&ephemeral, recipient);
+
+    let mut iv = [0u8; 16];
+    OsRng.fill_bytes(&mut iv);
+    let mut buffer = plaintext.to_vec();
+    buffer.resize(plaintext.len() + 16 - plaintext.len() % 16, 0);
+    let ciphertext = Encryptor::<Aes256>::new(&encryption_key.into(), &iv.into())
+        .encrypt_padded_mut::<Pkcs7>(&mut buffer, plaintext.len())
+        .map_err(|e| anyhow!("Failed to encrypt the package: {}", e))?
+        .to_vec();
+    let tag = mac(&mac_key, &iv, ephem_public_key.as_bytes(), &ciphertext)?;
+
+    Ok(EncryptedPackage {
+        format: FORMAT.to_string(),
+        recipient: hex::encode(recipient.to_encoded_point(true).as_bytes()),
+        iv: hex::encode(iv),
+        ephem_public_key: hex::encode(ephem_public_key.as_bytes()),
+        ciphertext: hex::encode(ciphertext),
+        mac: hex::encode(tag),
+        sha256: String::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cbc::Decryptor;
+    use cbc::cipher::BlockDecryptMut;
+
+    #[test]
+    fn test_package_decrypts_with_auditor_key() {
+        let auditor = SecretKey::random(&mut OsRng);
+        let public = auditor.public_key().to_encoded_point(false);
+        // Bare 64-byte keys are accepted as well as SEC1
+        let recipient = parse_public_key(&hex::encode(&public.as_bytes()[1..])).unwrap();
+        assert!(parse_public_key("0x1234").is_err());
+
+        let plaintext = br#"{"transactions":[]}"#;
+        let package = encrypt(&recipient, plaintext).unwrap();
+
+        let ephem = hex::decode(&package.ephem_public_key).unwrap();
+        let iv: [u8; 16] = hex::decode(&package.iv).unwrap().try_into().unwrap();
+        let mut ciphertext = hex::decode(&package.ciphertext).unwrap();
+        let (encryption_key, mac_key) =
+            derive_keys(&auditor, &PublicKey::from_sec1_bytes(&ephem).unwrap());
+        assert_eq!(
+            hex::encode(mac(&mac_key, &iv, &ephem, &ciphertext).unwrap()),
+            package.mac
+        );
+        let decrypted = Decryptor::<Aes256>::new(&encryption_key.into(), &iv.into())
+            .decrypt_padded_mut::<Pkcs7>(&mut ciphertext)
+            .unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+}
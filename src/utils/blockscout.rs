@@ -0,0 +1,331 @@
+//! Client for the public Rootstock Blockscout explorer API (v2).
+//!
+//! Blockscout needs no API key and returns transactions with their status,
+//! gas and timestamp in one response, so history can be shown without an
+//! Alchemy key and without a receipt and block lookup per transaction.
+
+use crate::api::{ApiProvider, rate_limit};
+use crate::config::PollingConfig;
+use crate::types::transaction::RskTransaction;
+use crate::utils::http;
+use crate::utils::nft::Nft;
+use crate::utils::retry::Backoff;
+use alloy::json_abi::JsonAbi;
+use alloy::primitives::{Address, U256};
+use anyhow::{Result, anyhow};
+use reqwest::Client;
+use serde_json::Value;
+use std::str::FromStr;
+
+const MAINNET_URL: &str = "https://rootstock.blockscout.com/api/v2";
+const TESTNET_URL: &str = "https://rootstock-testnet.blockscout.com/api/v2";
+
+/// A token held by an address, as reported by Blockscout
+#[derive(Debug, Clone)]
+pub struct TokenBalance {
+    pub address: Address,
+    pub symbol: String,
+    pub name: String,
+    pub decimals: u8,
+    pub value: U256,
+}
+
+pub struct BlockscoutClient {
+    client: Client,
+    is_testnet: bool,
+    backoff: Backoff,
+}
+
+impl BlockscoutClient {
+    pub fn new(is_testnet: bool) -> Result<Self> {
+        Ok(Self {
+            client: http::secure_client()?,
+            is_testnet,
+            backoff: PollingConfig::current().rpc_backoff(),
+        })
+    }
+
+    pub fn get_base_url(&self) -> &'static str {
+        if self.is_testnet {
+            TESTNET_URL
+        } else {
+            MAINNET_URL
+        }
+    }
+
+    async fn get(&self, path: &str, query: &[(String, String)]) -> Result<Value> {
+        let url = format!("{}{}", self.get_base_url(), path);
+        let send = || async {
+            rate_limit::acquire(&ApiProvider::Blockscout, 1).await;
+            let response = self.client.get(&url).query(query).send().await?;
+            if http::is_transient_status(response.status()) {
+                return response.error_for_status();
+            }
+            Ok(response)
+        };
+        let response = self
+            .backoff
+            .run(send, http::is_transient)
+            .await
+            .map_err(|e| anyhow!("Request failed: {}", e))?;
+        let status = response.status();
+        let body = response
+            .json::<Value>()
+            .await
+            .map_err(|e| anyhow!("Failed to parse response: {}", e))?;
+        if !status.is_success() {
+            return Err(anyhow!("Blockscout API error ({}): {}", status, body));
+        }
+        Ok(body)
+    }
+
+    /// Follow `next_page_params` until `limit` items have been collected
+    async fn get_items(
+        &self,
+        path: &str,
+        mut query: Vec<(String, String)>,
+        limit: u32,
+    ) -> Result<Vec<Value>> {
+        let base_query = query.clone();
+        let mut items = Vec::new();
+        loop {
+            let page = self.get(path, &query).await?;
+            let page_items = page["items"]
+                .as_array()
+                .ok_or_else(|| anyhow!("Invalid response format from Blockscout"))?;
+            items.extend(page_items.iter().cloned());
+            if items.len() >= limit as usize {
+                items.truncate(limit as usize);
+                return Ok(items);
+            }
+            let Some(next) = page["next_page_params"].as_object() else {
+                return Ok(items);
+            };
+            query = base_query.clone();
+            query.extend(next.iter().map(|(key, value)| {
+                let value = value
+                    .as_str()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| value.to_string());
+                (key.clone(), value)
+            }));
+        }
+    }
+
+    /// Latest transactions sent or received by `address`, newest first
+    pub async fn get_transactions(
+        &self,
+        address: &Address,
+        limit: u32,
+    ) -> Result<Vec<RskTransaction>> {
+        let items = self
+            .get_items(
+                &format!("/addresses/{:#x}/transactions", address),
+                Vec::new(),
+                limit,
+            )
+            .await?;
+        items
+            .iter()
+            .map(RskTransaction::from_blockscout_transaction)
+            .collect()
+    }
+
+    /// Latest ERC-20 transfers sent or received by `address`, optionally of
+    /// a single token, newest first
+    pub async fn get_token_transfers(
+        &self,
+        address: &Address,
+        token: Option<&Address>,
+        limit: u32,
+    ) -> Result<Vec<RskTransaction>> {
+        let mut query = vec![("type".to_string(), "ERC-20".to_string())];
+        if let Some(token) = token {
+            query.push(("token".to_string(), format!("{:#x}", token)));
+        }
+        let items = self
+            .get_items(
+                &format!("/addresses/{:#x}/token-transfers", address),
+                query,
+                limit,
+            )
+            .await?;
+        items
+            .iter()
+            .map(RskTransaction::from_blockscout_token_transfer)
+            .collect()
+    }
+
+    /// Transactions and token transfers of `address`, newest first. A
+    /// contract call that moved tokens is listed as its token transfer.
+    pub async fn get_history(&self, address: &Address, limit: u32) -> Result<Vec<RskTransaction>> {
+        let transfers = self.get_token_transfers(address, None, limit).await?;
+        let mut txs: Vec<RskTransaction> = self
+            .get_transactions(address, limit)
+            .await?
+            .into_iter()
+            .filter(|tx| !(tx.value.is_zero() && transfers.iter().any(|t| t.hash == tx.hash)))
+            .collect();
+        txs.extend(transfers);
+        txs.sort_by_key(|tx| std::cmp::Reverse(tx.timestamp));
+        txs.truncate(limit as usize);
+        Ok(txs)
+    }
+
+    /// Every ERC-20 token `address` holds a balance of
+    pub async fn get_token_balances(&self, address: &Address) -> Result<Vec<TokenBalance>> {
+        let response = self
+            .get(&format!("/addresses/{:#x}/token-balances", address), &[])
+            .await?;
+        let items = response
+            .as_array()
+            .ok_or_else(|| anyhow!("Invalid response format from Blockscout"))?;
+        Ok(items
+            .iter()
+            .filter(|item| item["token"]["type"].as_str().unwrap_or("ERC-20") == "ERC-20")
+            .filter_map(parse_token_balance)
+            .collect())
+    }
+
+    /// ERC-721 tokens `address` holds, with metadata where the explorer has
+    /// fetched it
+    pub async fn get_nfts(&self, address: &Address, limit: u32) -> Result<Vec<Nft>> {
+        let items = self
+            .get_items(
+                &format!("/addresses/{:#x}/nft", address),
+                vec![("type".to_string(), "ERC-721".to_string())],
+                limit,
+            )
+            .await?;
+        Ok(items.iter().filter_map(Nft::from_blockscout).collect())
+    }
+
+    /// ABI of a contract whose source is verified on the explorer
+    pub async fn get_contract_abi(&self, address: &Address) -> Result<JsonAbi> {
+        let contract = self
+            .get(&format!("/smart-contracts/{:#x}", address), &[])
+            .await
+            .map_err(|e| anyhow!("Failed to fetch the ABI of {:#x}: {}", address, e))?;
+        match contract.get("abi") {
+            Some(abi) if !abi.is_null() => serde_json::from_value(abi.clone())
+                .map_err(|e| anyhow!("Failed to parse the explorer's ABI: {}", e)),
+            _ => Err(anyhow!(
+                "{:#x} is not verified on the explorer; give its ABI with --abi",
+                address
+            )),
+        }
+    }
+
+    /// Current USD price of RBTC, when the explorer reports one
+    pub async fn get_coin_price(&self) -> Result<Option<f64>> {
+        let stats = self.get("/stats", &[]).await?;
+        Ok(price(&stats["coin_price"]))
+    }
+
+    /// Current USD price of a token, when the explorer reports one
+    pub async fn get_token_price(&self, token: &Address) -> Result<Option<f64>> {
+        let info = self.get(&format!("/tokens/{:#x}", token), &[]).await?;
+        Ok(price(&info["exchange_rate"]))
+    }
+}
+
+/// A price that Blockscout sends as a decimal string, or null when unknown
+fn price(value: &Value) -> Option<f64> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        Value::Number(n) => n.as_f64(),
+        _ => None,
+    }
+    .filter(|price: &f64| price.is_finite() && *price > 0.0)
+}
+
+fn parse_token_balance(item: &Value) -> Option<TokenBalance> {
+    let token = &item["token"];
+    Some(TokenBalance {
+        address: token_address(token)?,
+        symbol: token["symbol"].as_str().unwrap_or("?").to_string(),
+        name: token["name"].as_str().unwrap_or_default().to_string(),
+        decimals: number(&token["decimals"]).unwrap_or(18),
+        value: number(&item["value"])?,
+    })
+}
+
+/// The contract address of a token object; newer Blockscout versions call
+/// the field `address_hash`
+pub fn token_address(token: &Value) -> Option<Address> {
+    token["address_hash"]
+        .as_str()
+        .or_else(|| token["address"].as_str())
+        .and_then(|s| Address::from_str(s).ok())
+}
+
+/// An address object (`{"hash": "0x..."}`), as used for senders and recipients
+pub fn address(value: &Value) -> Option<Address> {
+    value["hash"]
+        .as_str()
+        .and_then(|s| Address::from_str(s).ok())
+}
+
+/// A number that Blockscout sends either as a decimal string or as JSON
+pub fn number<T: TryFrom<U256>>(value: &Value) -> Option<T> {
+    let n = match value {
+        Value::String(s) => U256::from_str_radix(s, 10).ok()?,
+        Value::Number(n) => U256::from(n.as_u64()?),
+        _ => return None,
+    };
+    T::try_from(n).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::transaction::TransactionStatus;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_blockscout_items() {
+        let tx = RskTransaction::from_blockscout_transaction(&json!({
+            "hash": "0x5f3d0a4c1e1b5a2e8b7a8a0f9e6c1d2b3a4f5e6d7c8b9a0f1e2d3c4b5a697887",
+            "block_number": 6512345,
+            "timestamp": "2024-09-01T12:00:00.000000Z",
+            "from": {"hash": "0x1111111111111111111111111111111111111111"},
+            "to": {"hash": "0x2222222222222222222222222222222222222222"},
+            "value": "1000000000000000",
+            "gas_price": "65164000",
+            "gas_used": "21000",
+            "nonce": 4,
+            "status": "error"
+        }))
+        .unwrap();
+        assert_eq!(tx.value, U256::from(1_000_000_000_000_000u64));
+        assert_eq!(tx.status, TransactionStatus::Failed);
+        assert_eq!(tx.block_number.map(|n| n.to::<u64>()), Some(6512345));
+        assert_eq!(tx.nonce, U256::from(4));
+
+        // Older instances use `tx_hash`, `block` and `address`
+        let transfer = RskTransaction::from_blockscout_token_transfer(&json!({
+            "tx_hash": "0x5f3d0a4c1e1b5a2e8b7a8a0f9e6c1d2b3a4f5e6d7c8b9a0f1e2d3c4b5a697887",
+            "block": 6512345,
+            "timestamp": "2024-09-01T12:00:00.000000Z",
+            "from": {"hash": "0x1111111111111111111111111111111111111111"},
+            "to": {"hash": "0x2222222222222222222222222222222222222222"},
+            "token": {"address": "0x3333333333333333333333333333333333333333", "symbol": "USDT"},
+            "total": {"decimals": "18", "value": "2500"}
+        }))
+        .unwrap();
+        assert_eq!(transfer.value, U256::from(2500));
+        assert_eq!(transfer.token_address, Some(Address::repeat_byte(0x33)));
+        assert_eq!(transfer.status, TransactionStatus::Success);
+
+        let balance = parse_token_balance(&json!({
+            "token": {"address_hash": "0x3333333333333333333333333333333333333333",
+                      "symbol": "USDT", "name": "Tether", "decimals": "6"},
+            "value": "1500000"
+        }))
+        .unwrap();
+        assert_eq!(
+            (balance.decimals, balance.value),
+            (6, U256::from(1_500_000))
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! Bulk transfer CSV files.
+//!
+//! Each row is `address,amount[,token][,memo]`: the token is a registry
+//! symbol or contract address (RBTC when empty), and the memo is carried as
+//! in a single transfer. A header row and `#` comments are skipped. Every row
+//! is validated before anything is sent, so a file with a typo is fixed and
+//! loaded again rather than paid in part.
+
+use crate::commands::address::{checksum_warning, parse_address};
+use crate::utils::categories::AssetFilter;
+use alloy::primitives::{Address, U256, utils::format_units};
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Gas a transfer row is expected to use, for the balance check; token
+/// transfers and memos are estimated by the node when sent
+const RBTC_TRANSFER_GAS: u64 = 21_000;
+const TOKEN_TRANSFER_GAS: u64 = 65_000;
+
+/// A transfer of a bulk run, read from a file or entered by hand
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkTransfer {
+    /// 1-based line number in the file, or position when entered by hand
+    pub line: usize,
+    pub to: Address,
+    pub asset: AssetFilter,
+    /// Amount in the asset's smallest unit
+    pub value: U256,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    /// Findings worth a second look, e.g. a repeated recipient
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl BulkTransfer {
+    pub fn formatted_amount(&self) -> String {
+        format_units(self.value, self.asset.decimals()).unwrap_or_else(|_| self.value.to_string())
+    }
+
+    /// Gas the row is expected to use
+    pub fn gas(&self) -> u64 {
+        if self.asset.token().is_some() {
+            TOKEN_TRANSFER_GAS
+        } else {
+            RBTC_TRANSFER_GAS
+        }
+    }
+}
+
+/// A row that cannot be sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    pub line: usize,
+    pub message: String,
+}
+
+/// Read the transfers in `content`, resolving the token column with
+/// `resolve_token`. Rows that fail validation are returned as errors.
+pub fn parse(
+    content: &str,
+    chain_id: u64,
+    resolve_token: impl Fn(Option<&str>) -> Result<AssetFilter>,
+) -> Result<(Vec<BulkTransfer>, Vec<RowError>)> {
+    let mut transfers: Vec<BulkTransfer> = Vec::new();
+    let mut errors = Vec::new();
+    let mut seen: HashMap<(Address, Option<Address>), usize> = HashMap::new();
+    let mut first_row = true;
+    for (index, text) in content.lines().enumerate() {
+        let line = index + 1;
+        if text.trim().is_empty() || text.trim_start().starts_with('#') {
+            continue;
+        }
+        let record = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .trim(csv::Trim::All)
+            .from_reader(text.as_bytes())
+            .records()
+            .next()
+            .transpose()
+            .map_err(|e| anyhow!("Failed to read line {} of the CSV file: {}", line, e))?
+            .unwrap_or_default();
+        let field = |i: usize| record.get(i).filter(|value| !value.is_empty());
+        let header =
+            first_row && field(0).is_some_and(|value| value.eq_ignore_ascii_case("address"));
+        first_row = false;
+        if header {
+            continue;
+        }
+
+        let row = (|| {
+            let input = field(0).ok_or_else(|| anyhow!("missing address"))?;
+            let to = parse_address(input, chain_id)?;
+            let asset = resolve_token(field(2))?;
+            let amount = field(1).ok_or_else(|| anyhow!("missing amount"))?;
+            let value: U256 = alloy::primitives::utils::parse_units(amount, asset.decimals())
+                .map_err(|e| anyhow!("invalid amount '{}': {}", amount, e))?
+                .into();
+            if value.is_zero() {
+                return Err(anyhow!("the amount must be positive"));
+            }
+            let mut warnings: Vec<String> = checksum_warning(input, chain_id).into_iter().collect();
+            if let Some(first) = seen.get(&(to, asset.token())) {
+                warnings.push(format!("same recipient and asset as line {}", first));
+            }
+            Ok(BulkTransfer {
+                line,
+                to,
+                asset,
+                value,
+                memo: field(3).map(str::to_string),
+                warnings,
+            })
+        })();
+        match row {
+            Ok(transfer) => {
+                seen.entry((transfer.to, transfer.asset.token()))
+                    .or_insert(line);
+                transfers.push(transfer);
+            }
+            Err(e) => errors.push(RowError {
+                line,
+                message: e.to_string(),
+            }),
+        }
+    }
+    Ok((transfers, errors))
+}
+
+/// Total per asset, in the order the assets first appear
+pub fn totals(transfers: &[BulkTransfer]) -> Vec<(AssetFilter, U256)> {
+    let mut totals: Vec<(AssetFilter, U256)> = Vec::new();
+    for transfer in transfers {
+        match totals
+            .iter_mut()
+            .find(|(asset, _)| *asset == transfer.asset)
+        {
+            Some((_, total)) => *total = total.saturating_add(transfer.value),
+            None => totals.push((transfer.asset.clone(), transfer.value)),
+        }
+    }
+    totals
+}
+
+/// Assets the sender holds too little of: each needed total against
+/// `balance`, with the gas for every row at `gas_price` added to RBTC
+pub fn shortfalls(
+    transfers: &[BulkTransfer],
+    gas_price: u128,
+    balance: impl Fn(Option<Address>) -> U256,
+) -> Vec<String> {
+    let gas: u64 = transfers.iter().map(BulkTransfer::gas).sum();
+    let gas_cost = U256::from(gas_price).saturating_mul(U256::from(gas));
+    let mut needed = totals(transfers);
+    match needed
+        .iter_mut()
+        .find(|(asset, _)| *asset == AssetFilter::Rbtc)
+    {
+        Some((_, total)) => *total = total.saturating_add(gas_cost),
+        None => needed.push((AssetFilter::Rbtc, gas_cost)),
+    }
+    needed
+        .into_iter()
+        .filter_map(|(asset, total)| {
+            let held = balance(asset.token());
+            let amount = |value: U256| {
+                format_units(value, asset.decimals()).unwrap_or_else(|_| value.to_string())
+            };
+            (held < total).then(|| {
+                format!(
+                    "needs {} {}{} but holds {}",
+                    amount(total),
+                    asset.symbol(),
+                    if asset == AssetFilter::Rbtc {
+                        " including gas"
+                    } else {
+                        ""
+                    },
+                    amount(held)
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bulk_csv() {
+        let alice = Address::repeat_byte(0xab);
+        let doc = AssetFilter::Token {
+            symbol: "DOC".to_string(),
+            address: Address::repeat_byte(7),
+            decimals: 18,
+        };
+        let resolve = |token: Option<&str>| match token {
+            None => Ok(AssetFilter::Rbtc),
+            Some(token) if token.eq_ignore_ascii_case("doc") => Ok(doc.clone()),
+            Some(token) => Err(anyhow!("Token '{}' is not in the registry", token)),
+        };
+        let csv = format!(
+            "address,amount,token,memo\n{},0.5\n# bonus\n{:#x}, 10 ,DOC,invoice 42\n\n{:#x},0.25\n{:#x},1,XYZ\n0x1234,1\n{:#x},0\n",
+            alice.to_checksum(Some(30)),
+            alice,
+            alice,
+            alice,
+            alice
+        );
+        let (transfers, errors) = parse(&csv, 30, resolve).unwrap();
+
+        assert_eq!(
+            transfers.iter().map(|t| t.line).collect::<Vec<_>>(),
+            vec![2, 4, 6]
+        );
+        assert_eq!(transfers[1].asset, doc);
+        assert_eq!(transfers[1].formatted_amount(), "10.000000000000000000");
+        assert_eq!(transfers[1].memo.as_deref(), Some("invoice 42"));
+        assert!(transfers[1].warnings.is_empty());
+        assert_eq!(
+            transfers[2].warnings,
+            vec!["same recipient and asset as line 2"]
+        );
+        assert_eq!(
+            errors.iter().map(|e| e.line).collect::<Vec<_>>(),
+            vec![7, 8, 9]
+        );
+
+        let totals = totals(&transfers);
+        assert_eq!(totals[0].1, U256::from(75u64) * U256::from(10u64.pow(16)));
+        assert_eq!(totals[1].0, doc);
+
+        let rbtc = U256::from(10u64.pow(18));
+        let balances = |token: Option<Address>| match token {
+            None => rbtc,
+            Some(_) => U256::from(5u64) * rbtc,
+        };
+        assert_eq!(
+            shortfalls(&transfers, 60_000_000, balances),
+            vec!["needs 10.000000000000000000 DOC but holds 5.000000000000000000"]
+        );
+        assert!(shortfalls(&transfers, 60_000_000, |_| U256::MAX).is_empty());
+    }
+}
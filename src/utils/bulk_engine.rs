@@ -0,0 +1,319 @@
+//! Parallel execution of bulk transfer jobs.
+//!
+//! Sending a transfer and waiting for its receipt before the next makes a run
+//! of a few hundred rows take hours. Rows are sent in batches instead: every
+//! transfer of a batch is built and simulated, the ones that pass are given a
+//! run of sequential nonces in row order, all of them are submitted at once,
+//! and their receipts are awaited together. A row whose submission fails
+//! would leave a gap that holds back every later nonce, so the nonce is used
+//! up with an empty transfer to the sender and the row is retried in a later
+//! batch with a fresh one.
+
+use crate::config::PollingConfig;
+use crate::utils::bulk_csv::BulkTransfer;
+use crate::utils::bulk_job::{BulkJob, JobRow, RowState};
+use crate::utils::eth::{EthClient, GasOverrides};
+use crate::utils::nonce;
+use crate::utils::pending::min_replacement_gas_price;
+use crate::utils::retry::{self, FailureKind};
+use alloy::primitives::{B256, U256};
+use alloy::providers::Provider;
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+
+/// Gas of a plain RBTC transfer, also used for the transfers that fill a
+/// nonce gap
+const TRANSFER_GAS: u64 = 21_000;
+
+/// Send every row of `job` that needs sending, in batches of the configured
+/// concurrency, saving the job as each row changes. Rows that fail for a
+/// transient reason are retried in later batches up to the configured number
+/// of times.
+pub async fn run(client: Arc<EthClient>, job: &mut BulkJob, polling: &PollingConfig) -> Result<()> {
+    let batch_size = polling.bulk_transfer_concurrency.max(1);
+    let total = job.count(RowState::needs_sending);
+    // Tries in this run and the last gas price offered, by row
+    let mut tries: HashMap<usize, u32> = HashMap::new();
+    let mut gas_prices: HashMap<usize, u128> = HashMap::new();
+    let mut started = 0;
+    loop {
+        let batch: Vec<usize> = job
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(index, row)| {
+                row.state.needs_sending()
+                    && tries.get(index).copied().unwrap_or(0) <= polling.bulk_transfer_max_retries
+            })
+            .map(|(index, _)| index)
+            .take(batch_size)
+            .collect();
+        if batch.is_empty() {
+            return Ok(());
+        }
+        let fresh = batch
+            .iter()
+            .filter(|index| !tries.contains_key(index))
+            .count();
+        println!(
+            "Sending {} transfer(s) at once ({}/{})...",
+            batch.len(),
+            started + fresh,
+            total
+        );
+        started += fresh;
+        for &index in &batch {
+            *tries.entry(index).or_default() += 1;
+            job.rows[index].attempts += 1;
+        }
+
+        let setup = async {
+            let network = client.suggest_gas_price().await?.gas_price;
+            let base = nonce::reserve(client.provider(), job.from).await?;
+            Ok::<_, anyhow::Error>((network, base))
+        };
+        let (network, base) = match setup.await {
+            Ok(setup) => setup,
+            Err(e) => {
+                for &index in &batch {
+                    fail(&mut job.rows[index], e.to_string(), tries[&index], polling);
+                }
+                job.save()?;
+                tokio::time::sleep(polling.receipt_poll_interval()).await;
+                continue;
+            }
+        };
+
+        // Build and simulate every row, each retry outbidding its last price
+        let mut prepared = JoinSet::new();
+        for &index in &batch {
+            let gas_price = gas_prices.get(&index).map_or(network, |&last| {
+                network.max(min_replacement_gas_price(last))
+            });
+            gas_prices.insert(index, gas_price);
+            let transfer = job.rows[index].transfer.clone();
+            let client = client.clone();
+            prepared.spawn(async move {
+                let tx = client
+                    .prepare_transfer(
+                        transfer.to,
+                        transfer.value,
+                        transfer.asset.token(),
+                        overrides(&transfer, gas_price, base),
+                        transfer.memo.as_deref().map(str::as_bytes),
+                    )
+                    .await;
+                (index, tx)
+            });
+        }
+        let mut ready = Vec::new();
+        while let Some(result) = prepared.join_next().await {
+            match result? {
+                (index, Ok(tx)) => ready.push((index, tx)),
+                (index, Err(e)) => {
+                    fail(&mut job.rows[index], e.to_string(), tries[&index], polling)
+                }
+            }
+        }
+        job.save()?;
+
+        // Nonces follow the row order, leaving none for rows that failed to
+        // build
+        ready.sort_by_key(|(index, _)| *index);
+        let mut submitted = JoinSet::new();
+        for (offset, (index, tx)) in ready.into_iter().enumerate() {
+            let nonce = base + offset as u64;
+            let client = client.clone();
+            submitted.spawn(async move {
+                let result = client.send_prepared(tx.nonce(nonce)).await;
+                (index, nonce, result)
+            });
+        }
+        let mut unused = Vec::new();
+        let mut highest_sent = None;
+        while let Some(result) = submitted.join_next().await {
+            let (index, nonce, result) = result?;
+            match result {
+                Ok(tx_hash) => {
+                    job.rows[index].state = RowState::Sent { tx_hash };
+                    highest_sent = highest_sent.max(Some(nonce));
+                }
+                Err(e) => {
+                    fail(&mut job.rows[index], e.to_string(), tries[&index], polling);
+                    unused.push(nonce);
+                }
+            }
+            job.save()?;
+        }
+        for nonce in unused {
+            if highest_sent.is_some_and(|highest| nonce < highest) {
+                fill_gap(&client, job, nonce, network).await;
+            }
+        }
+
+        let mut receipts = JoinSet::new();
+        for &index in &batch {
+            if let RowState::Sent { tx_hash } = job.rows[index].state {
+                let client = client.clone();
+                let polling = polling.clone();
+                receipts
+                    .spawn(async move { (index, wait_for_row(&client, tx_hash, &polling).await) });
+            }
+        }
+        while let Some(result) = receipts.join_next().await {
+            let (index, state) = result?;
+            let row = &mut job.rows[index];
+            row.state = state;
+            println!(
+                "  #{} {} {}",
+                row.transfer.line,
+                row.state.label(),
+                row.state.details()
+            );
+            job.save()?;
+        }
+
+        // Small delay between batches
+        tokio::time::sleep(std::time::Duration::from_millis(
+            polling.bulk_transfer_delay_ms,
+        ))
+        .await;
+    }
+}
+
+/// Gas settings of a row: plain RBTC transfers use a fixed limit, token
+/// calls and memos are estimated by the node. Rows are built with the first
+/// nonce of their batch and given their own before they are signed.
+fn overrides(transfer: &BulkTransfer, gas_price: u128, nonce: u64) -> GasOverrides {
+    let plain = transfer.asset.token().is_none() && transfer.memo.is_none();
+    GasOverrides {
+        gas_limit: plain.then_some(TRANSFER_GAS),
+        gas_price: Some(gas_price),
+        nonce: Some(nonce),
+    }
+}
+
+/// Record the failed try of `row`, its `tried`th in this run, saying
+/// whether it will be tried again
+fn fail(row: &mut JobRow, error: String, tried: u32, polling: &PollingConfig) {
+    let kind = retry::classify(&error);
+    if kind == FailureKind::Transient && tried <= polling.bulk_transfer_max_retries {
+        println!(
+            "  ⚠️  #{} {} (retry {}/{} in a later batch with a fresh nonce and gas price)",
+            row.transfer.line, error, tried, polling.bulk_transfer_max_retries
+        );
+    } else {
+        println!("  ❌ #{} {}", row.transfer.line, error);
+    }
+    row.state = RowState::Failed {
+        tx_hash: None,
+        kind,
+        error,
+    };
+}
+
+/// Use up `nonce`, left free by a submission that failed, with an empty
+/// transfer to the sender so the transfers after it can be mined
+async fn fill_gap(client: &EthClient, job: &BulkJob, nonce: u64, gas_price: u128) {
+    let overrides = GasOverrides {
+        gas_limit: Some(TRANSFER_GAS),
+        gas_price: Some(gas_price),
+        nonce: Some(nonce),
+    };
+    if let Err(e) = client
+        .send_transaction_with_overrides(job.from, U256::ZERO, None, overrides, None)
+        .await
+    {
+        println!(
+            "  ⚠️  Nonce {} is unused ({}); the transfers after it wait until the wallet's next transaction uses it",
+            nonce, e
+        );
+    }
+}
+
+/// Settle the rows an earlier run sent but never saw mined. They are never
+/// sent again while the node knows them, so no one is paid twice; only a
+/// transaction the node has dropped is queued to be sent again.
+pub async fn reconcile_sent(client: &EthClient, job: &mut BulkJob) -> Result<()> {
+    for row in &mut job.rows {
+        let RowState::Sent { tx_hash } = row.state else {
+            continue;
+        };
+        row.state = match client.get_transaction_receipt(tx_hash).await {
+            Ok(receipt) => mined_state(tx_hash, receipt.status()),
+            Err(_) => match client.provider().get_transaction_by_hash(tx_hash).await {
+                Ok(None) => RowState::Failed {
+                    tx_hash: None,
+                    kind: FailureKind::Transient,
+                    error: format!("{:#x} was dropped before being mined", tx_hash),
+                },
+                Ok(Some(_)) => continue,
+                Err(e) => return Err(anyhow!("Failed to look up {:#x}: {}", tx_hash, e)),
+            },
+        };
+    }
+    Ok(())
+}
+
+/// State of a row whose transaction was mined; a reverted transaction fails
+/// the same way if sent again
+fn mined_state(tx_hash: B256, success: bool) -> RowState {
+    if success {
+        RowState::Confirmed { tx_hash }
+    } else {
+        RowState::Failed {
+            tx_hash: Some(tx_hash),
+            kind: FailureKind::Permanent,
+            error: "reverted".to_string(),
+        }
+    }
+}
+
+/// Poll for the receipt of a sent row; still `Sent` when it is not mined
+/// within the receipt polling window
+async fn wait_for_row(client: &EthClient, tx_hash: B256, polling: &PollingConfig) -> RowState {
+    for _ in 0..polling.receipt_poll_attempts {
+        if let Ok(receipt) = client.get_transaction_receipt(tx_hash).await {
+            return mined_state(tx_hash, receipt.status());
+        }
+        tokio::time::sleep(polling.receipt_poll_interval()).await;
+    }
+    RowState::Sent { tx_hash }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::categories::AssetFilter;
+    use alloy::primitives::Address;
+
+    #[test]
+    fn test_row_overrides() {
+        let mut transfer = BulkTransfer {
+            line: 1,
+            to: Address::repeat_byte(1),
+            asset: AssetFilter::Rbtc,
+            value: U256::from(1000u64),
+            memo: None,
+            warnings: Vec::new(),
+        };
+        let plain = overrides(&transfer, 60_000_000, 7);
+        assert_eq!(plain.gas_limit, Some(TRANSFER_GAS));
+        assert_eq!(plain.gas_price, Some(60_000_000));
+        assert_eq!(plain.nonce, Some(7));
+
+        transfer.memo = Some("invoice 42".to_string());
+        assert_eq!(overrides(&transfer, 60_000_000, 7).gas_limit, None);
+
+        let hash = B256::repeat_byte(3);
+        assert_eq!(
+            mined_state(hash, true),
+            RowState::Confirmed { tx_hash: hash }
+        );
+        let reverted = mined_state(hash, false);
+        assert_eq!(reverted.failure_kind(), Some(FailureKind::Permanent));
+        assert!(!reverted.needs_sending());
+    }
+}
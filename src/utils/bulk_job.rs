@@ -0,0 +1,263 @@
+//! Resumable bulk transfer jobs.
+//!
+//! A bulk run is saved as a job file in the data directory before the first
+//! transfer goes out, and each row's state is written as it changes: pending,
+//! sent (with its hash, before the receipt is awaited), confirmed or failed.
+//! A run that dies half way is resumed from the file: confirmed rows are
+//! skipped, sent rows are checked on chain instead of being paid again, and
+//! the rest is sent.
+
+use crate::types::network::Network;
+use crate::utils::bulk_csv::BulkTransfer;
+use crate::utils::retry::FailureKind;
+use crate::utils::storage::Storage;
+use alloy::primitives::{Address, B256};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Where a row of a job stands
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "lowercase")]
+pub enum RowState {
+    Pending,
+    /// Broadcast, but no receipt seen yet
+    Sent {
+        tx_hash: B256,
+    },
+    Confirmed {
+        tx_hash: B256,
+    },
+    /// Not sent, or mined but reverted (with the hash)
+    Failed {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tx_hash: Option<B256>,
+        kind: FailureKind,
+        error: String,
+    },
+}
+
+impl RowState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RowState::Pending => "⏸️ Not sent",
+            RowState::Confirmed { .. } => "✅ Confirmed",
+            RowState::Sent { .. } => "⏳ Pending",
+            RowState::Failed {
+                tx_hash: Some(_), ..
+            } => "❌ Reverted",
+            RowState::Failed {
+                kind: FailureKind::Transient,
+                ..
+            } => "❌ Failed (transient)",
+            RowState::Failed { .. } => "❌ Failed (permanent)",
+        }
+    }
+
+    pub fn details(&self) -> String {
+        match self {
+            RowState::Pending => String::new(),
+            RowState::Sent { tx_hash }
+            | RowState::Confirmed { tx_hash }
+            | RowState::Failed {
+                tx_hash: Some(tx_hash),
+                ..
+            } => format!("{:#x}", tx_hash),
+            RowState::Failed { error, .. } => error.clone(),
+        }
+    }
+
+    pub fn failure_kind(&self) -> Option<FailureKind> {
+        match self {
+            RowState::Failed { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+
+    /// Whether a resumed job sends the row (again): rows never sent, and
+    /// sends that failed for a passing reason
+    pub fn needs_sending(&self) -> bool {
+        matches!(
+            self,
+            RowState::Pending
+                | RowState::Failed {
+                    tx_hash: None,
+                    kind: FailureKind::Transient,
+                    ..
+                }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRow {
+    #[serde(flatten)]
+    pub transfer: BulkTransfer,
+    #[serde(flatten)]
+    pub state: RowState,
+    /// Sends tried for the row, over every run of the job
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkJob {
+    pub id: String,
+    pub network: Network,
+    /// Address the transfers are sent from
+    pub from: Address,
+    pub created_at: DateTime<Local>,
+    pub rows: Vec<JobRow>,
+}
+
+impl BulkJob {
+    pub fn new(network: Network, from: Address, transfers: Vec<BulkTransfer>) -> Self {
+        let created_at = Local::now();
+        Self {
+            id: created_at.format("%Y%m%d-%H%M%S").to_string(),
+            network,
+            from,
+            created_at,
+            rows: transfers
+                .into_iter()
+                .map(|transfer| JobRow {
+                    transfer,
+                    state: RowState::Pending,
+                    attempts: 0,
+                })
+                .collect(),
+        }
+    }
+
+    fn dir() -> Result<PathBuf> {
+        let dir = Storage::data_dir()?.join("bulk_jobs");
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    pub fn path(&self) -> Result<PathBuf> {
+        Ok(Self::dir()?.join(format!("{}.json", self.id)))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        let path = self.path()?;
+        // Written whole and renamed, so a crash never leaves half a file
+        let partial = path.with_extension("json.tmp");
+        fs::write(&partial, content).context("Failed to write the bulk job")?;
+        fs::rename(&partial, &path).context("Failed to write the bulk job")
+    }
+
+    /// Jobs of `from` on `network` with rows left to send or confirm, newest
+    /// first
+    pub fn unfinished(network: Network, from: Address) -> Result<Vec<Self>> {
+        let mut jobs = Vec::new();
+        for entry in fs::read_dir(Self::dir()?)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            match serde_json::from_str::<Self>(&content) {
+                Ok(job) if job.network == network && job.from == from && !job.is_finished() => {
+                    jobs.push(job)
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("Skipping unreadable bulk job {}: {}", path.display(), e),
+            }
+        }
+        jobs.sort_by_key(|job| std::cmp::Reverse(job.created_at));
+        Ok(jobs)
+    }
+
+    /// No row is waiting to be sent or for its receipt
+    pub fn is_finished(&self) -> bool {
+        self.rows
+            .iter()
+            .all(|row| !row.state.needs_sending() && !matches!(row.state, RowState::Sent { .. }))
+    }
+
+    pub fn count(&self, f: impl Fn(&RowState) -> bool) -> usize {
+        self.rows.iter().filter(|row| f(&row.state)).count()
+    }
+
+    /// Menu label, e.g. `20261016-142530  57/200 confirmed`
+    pub fn describe(&self) -> String {
+        format!(
+            "{}  {}/{} confirmed",
+            self.id,
+            self.count(|state| matches!(state, RowState::Confirmed { .. })),
+            self.rows.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::categories::AssetFilter;
+    use alloy::primitives::U256;
+
+    #[test]
+    fn test_bulk_job_rows() {
+        let transfer = |line| BulkTransfer {
+            line,
+            to: Address::repeat_byte(line as u8),
+            asset: AssetFilter::Rbtc,
+            value: U256::from(1000u64),
+            memo: None,
+            warnings: Vec::new(),
+        };
+        let mut job = BulkJob::new(
+            Network::Testnet,
+            Address::repeat_byte(9),
+            (1..=5).map(transfer).collect(),
+        );
+        job.rows[0].state = RowState::Confirmed {
+            tx_hash: B256::repeat_byte(1),
+        };
+        job.rows[1].state = RowState::Sent {
+            tx_hash: B256::repeat_byte(2),
+        };
+        job.rows[2].state = RowState::Failed {
+            tx_hash: None,
+            kind: FailureKind::Transient,
+            error: "nonce too low".to_string(),
+        };
+        job.rows[3].state = RowState::Failed {
+            tx_hash: Some(B256::repeat_byte(4)),
+            kind: FailureKind::Permanent,
+            error: "reverted".to_string(),
+        };
+
+        let resend: Vec<usize> = job
+            .rows
+            .iter()
+            .filter(|row| row.state.needs_sending())
+            .map(|row| row.transfer.line)
+            .collect();
+        assert_eq!(resend, vec![3, 5]);
+        assert!(!job.is_finished());
+        assert_eq!(job.describe(), format!("{}  1/5 confirmed", job.id));
+        assert_eq!(job.rows[3].state.label(), "❌ Reverted");
+
+        // The state survives the job file
+        let json = serde_json::to_string(&job).unwrap();
+        let read: BulkJob = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.rows[1].state, job.rows[1].state);
+        assert_eq!(read.rows[2].state, job.rows[2].state);
+        assert_eq!(read.rows[4].transfer.to, Address::repeat_byte(5));
+
+        for row in &mut job.rows {
+            if row.state.needs_sending() || matches!(row.state, RowState::Sent { .. }) {
+                row.state = RowState::Confirmed {
+                    tx_hash: B256::ZERO,
+                };
+            }
+        }
+        assert!(job.is_finished());
+    }
+}
@@ -0,0 +1,177 @@
+//! Canary transfers: large transfers sent in two steps.
+//!
+//! A tiny canary amount is sent first. Once the recipient (or the sender,
+//! checking an exchange deposit for example) confirms it arrived, the rest of
+//! the amount follows to the same address. Canaries waiting for that
+//! confirmation are stored, so the transfer can be finished in a later
+//! session.
+
+use crate::config::ConfigManager;
+use crate::utils::storage::Storage;
+use alloy::primitives::{Address, B256, U256, utils::format_units};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// When to offer a canary and how much it sends
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CanaryPolicy {
+    /// Transfers of at least this amount, in asset units, offer a canary;
+    /// canaries are never offered when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+    /// Amount of the canary, in asset units
+    pub amount: f64,
+}
+
+impl Default for CanaryPolicy {
+    fn default() -> Self {
+        Self {
+            threshold: None,
+            amount: 0.0001,
+        }
+    }
+}
+
+impl CanaryPolicy {
+    /// Policy from the saved config, or the default if it cannot be read
+    pub fn current() -> Self {
+        ConfigManager::new()
+            .and_then(|manager| manager.load())
+            .map(|config| config.canary)
+            .unwrap_or_default()
+    }
+
+    /// Canary value for a transfer of `value`, if the transfer is large
+    /// enough to offer one
+    pub fn canary_for(&self, value: U256) -> Option<U256> {
+        let threshold = self.threshold?;
+        let units = format_units(value, 18).ok()?.parse::<f64>().ok()?;
+        if units < threshold {
+            return None;
+        }
+        self.canary_value(value)
+    }
+
+    /// The canary value, unless it would not be smaller than the transfer
+    pub fn canary_value(&self, value: U256) -> Option<U256> {
+        let canary: U256 = alloy::primitives::utils::parse_units(&self.amount.to_string(), 18)
+            .ok()?
+            .into();
+        (!canary.is_zero() && canary < value).then_some(canary)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CanaryStatus {
+    /// The canary was sent; waiting for its receipt to be confirmed
+    AwaitingReceipt,
+    /// The remainder was sent
+    Completed,
+    /// The remainder will not be sent
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanaryTransfer {
+    pub id: u64,
+    /// Wallet (address key) that sends the transfer
+    pub wallet: String,
+    #[serde(default)]
+    pub account: u32,
+    pub to: Address,
+    pub token: Option<Address>,
+    pub symbol: String,
+    /// Full amount of the transfer, canary included
+    pub total: U256,
+    pub canary: U256,
+    pub canary_tx: B256,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remainder_tx: Option<B256>,
+    /// Memo sent with the canary, repeated on the rest of the transfer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    /// Cost center both parts of the transfer are allocated to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_center: Option<String>,
+    pub status: CanaryStatus,
+    pub created_at: DateTime<Local>,
+}
+
+impl CanaryTransfer {
+    /// The amount still to send once the canary is confirmed
+    pub fn remainder(&self) -> U256 {
+        self.total.saturating_sub(self.canary)
+    }
+
+    pub fn format(&self, value: U256) -> String {
+        format!(
+            "{} {}",
+            format_units(value, 18).unwrap_or_else(|_| value.to_string()),
+            self.symbol
+        )
+    }
+}
+
+/// Canary transfers, kept until they are completed or cancelled
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CanaryTransfers {
+    #[serde(default)]
+    pub transfers: Vec<CanaryTransfer>,
+}
+
+impl CanaryTransfers {
+    pub fn load() -> Result<Self> {
+        Storage::open()?.load_canaries()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        Storage::open()?.save_canaries(self)
+    }
+
+    /// Record a transfer whose canary was just sent, assigning its id
+    pub fn add(&mut self, mut transfer: CanaryTransfer) -> u64 {
+        transfer.id = self.transfers.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+        transfer.status = CanaryStatus::AwaitingReceipt;
+        let id = transfer.id;
+        self.transfers.push(transfer);
+        id
+    }
+
+    /// A canary transfer still waiting for its receipt to be confirmed
+    pub fn awaiting_mut(&mut self, id: u64) -> Result<&mut CanaryTransfer> {
+        self.transfers
+            .iter_mut()
+            .find(|t| t.id == id && t.status == CanaryStatus::AwaitingReceipt)
+            .ok_or_else(|| anyhow!("No canary transfer #{} is waiting for confirmation", id))
+    }
+
+    pub fn awaiting(&self) -> impl Iterator<Item = &CanaryTransfer> {
+        self.transfers
+            .iter()
+            .filter(|t| t.status == CanaryStatus::AwaitingReceipt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canary_for() {
+        let one: U256 = U256::from(10u64).pow(U256::from(18u64));
+        let mut policy = CanaryPolicy::default();
+        assert_eq!(policy.canary_for(one * U256::from(100u64)), None);
+
+        policy.threshold = Some(1.0);
+        policy.amount = 0.01;
+        assert_eq!(policy.canary_for(one / U256::from(2u64)), None);
+        assert_eq!(policy.canary_for(one), Some(one / U256::from(100u64)));
+
+        // A canary as large as the transfer makes no sense
+        policy.amount = 5.0;
+        assert_eq!(policy.canary_for(one * U256::from(2u64)), None);
+    }
+}
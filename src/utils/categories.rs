@@ -0,0 +1,203 @@
+//! User-defined rules that assign categories to history entries for reports.
+//!
+//! Rules are evaluated in order and the first matching rule wins.
+
+use crate::types::transaction::RskTransaction;
+use alloy::primitives::{Address, U256, utils::format_units};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Asset a rule applies to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetFilter {
+    Rbtc,
+    Token {
+        symbol: String,
+        address: Address,
+        decimals: u8,
+    },
+}
+
+impl AssetFilter {
+    pub fn symbol(&self) -> &str {
+        match self {
+            AssetFilter::Rbtc => "RBTC",
+            AssetFilter::Token { symbol, .. } => symbol,
+        }
+    }
+
+    /// Token contract, or `None` for RBTC
+    pub fn token(&self) -> Option<Address> {
+        match self {
+            AssetFilter::Rbtc => None,
+            AssetFilter::Token { address, .. } => Some(*address),
+        }
+    }
+
+    pub fn decimals(&self) -> u8 {
+        match self {
+            AssetFilter::Rbtc => 18,
+            AssetFilter::Token { decimals, .. } => *decimals,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryRule {
+    pub category: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset: Option<AssetFilter>,
+    /// Inclusive lower bound in asset units
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_amount: Option<f64>,
+    /// Exclusive upper bound in asset units
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_amount: Option<f64>,
+}
+
+impl CategoryRule {
+    pub fn matches(&self, tx: &RskTransaction) -> bool {
+        if self.to.is_some_and(|to| tx.to != Some(to)) {
+            return false;
+        }
+        if self.from.is_some_and(|from| tx.from != from) {
+            return false;
+        }
+
+        let decimals = match &self.asset {
+            Some(AssetFilter::Rbtc) if tx.token_address.is_some() => return false,
+            Some(AssetFilter::Token { address, .. }) if tx.token_address != Some(*address) => {
+                return false;
+            }
+            Some(AssetFilter::Token { decimals, .. }) => *decimals,
+            _ => 18,
+        };
+
+        if self.min_amount.is_none() && self.max_amount.is_none() {
+            return true;
+        }
+        let amount = amount_in_units(tx.value, decimals);
+        self.min_amount.is_none_or(|min| amount >= min)
+            && self.max_amount.is_none_or(|max| amount < max)
+    }
+}
+
+impl fmt::Display for CategoryRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut conditions = Vec::new();
+        if let Some(to) = self.to {
+            conditions.push(format!("to {:#x}", to));
+        }
+        if let Some(from) = self.from {
+            conditions.push(format!("from {:#x}", from));
+        }
+        match &self.asset {
+            Some(AssetFilter::Rbtc) => conditions.push("token RBTC".to_string()),
+            Some(AssetFilter::Token { symbol, .. }) => conditions.push(format!("token {}", symbol)),
+            None => {}
+        }
+        if let Some(min) = self.min_amount {
+            conditions.push(format!("amount >= {}", min));
+        }
+        if let Some(max) = self.max_amount {
+            conditions.push(format!("amount < {}", max));
+        }
+        if conditions.is_empty() {
+            conditions.push("any transaction".to_string());
+        }
+        write!(f, "{} → {}", conditions.join(" and "), self.category)
+    }
+}
+
+pub(crate) fn amount_in_units(value: U256, decimals: u8) -> f64 {
+    format_units(value, decimals)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(f64::MAX)
+}
+
+/// Category of the first rule matching the transaction
+pub fn categorize<'a>(rules: &'a [CategoryRule], tx: &RskTransaction) -> Option<&'a str> {
+    rules
+        .iter()
+        .find(|rule| rule.matches(tx))
+        .map(|rule| rule.category.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::transaction::TransactionStatus;
+    use alloy::primitives::B256;
+    use std::time::SystemTime;
+
+    fn tx(to: Address, value: U256, token: Option<Address>) -> RskTransaction {
+        RskTransaction {
+            hash: B256::ZERO,
+            from: Address::ZERO,
+            to: Some(to),
+            value,
+            gas_price: U256::ZERO,
+            gas: U256::ZERO,
+            nonce: U256::ZERO,
+            input: None,
+            block_number: None,
+            transaction_index: None,
+            timestamp: SystemTime::now(),
+            status: TransactionStatus::Success,
+            token_address: token,
+            confirms: None,
+            cumulative_gas_used: None,
+            gas_used: None,
+            logs: None,
+        }
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let exchange = Address::repeat_byte(0xee);
+        let doc = Address::repeat_byte(0xdc);
+        let rules = vec![
+            CategoryRule {
+                category: "off-ramp".into(),
+                to: Some(exchange),
+                from: None,
+                asset: None,
+                min_amount: None,
+                max_amount: None,
+            },
+            CategoryRule {
+                category: "expenses".into(),
+                to: None,
+                from: None,
+                asset: Some(AssetFilter::Token {
+                    symbol: "DOC".into(),
+                    address: doc,
+                    decimals: 18,
+                }),
+                min_amount: None,
+                max_amount: Some(50.0),
+            },
+        ];
+
+        let ten = U256::from(10u64) * U256::from(10u64).pow(U256::from(18u64));
+        let hundred = ten * U256::from(10u64);
+        let other = Address::repeat_byte(1);
+
+        assert_eq!(
+            categorize(&rules, &tx(exchange, hundred, Some(doc))),
+            Some("off-ramp")
+        );
+        assert_eq!(
+            categorize(&rules, &tx(other, ten, Some(doc))),
+            Some("expenses")
+        );
+        assert_eq!(categorize(&rules, &tx(other, hundred, Some(doc))), None);
+        assert_eq!(categorize(&rules, &tx(other, ten, None)), None);
+    }
+}
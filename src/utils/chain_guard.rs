@@ -0,0 +1,88 @@
+//! Chain-ID guard against sending on the wrong network.
+//!
+//! Transactions take their chain id from the RPC endpoint, so an RPC URL that
+//! points at another chain (a mainnet key pasted into the testnet slot, a
+//! local node left running) would have the wallet sign a valid transaction
+//! for that chain. Before anything is signed, the endpoint's `eth_chainId`
+//! is compared with the configured network, and signing is refused when
+//! they differ.
+
+use crate::utils::http::RpcProvider;
+use crate::types::network::Network;
+use crate::utils::eth::endpoint_host;
+use alloy::providers::Provider;
+use anyhow::{Result, anyhow};
+
+/// Ask the endpoint at `rpc_url` for its chain id and compare it with
+/// `network`
+pub async fn verify(
+    provider: &RpcProvider,
+    network: Network,
+    rpc_url: &str,
+) -> Result<()> {
+    let chain_id = provider.get_chain_id().await.map_err(|e| {
+        anyhow!(
+            "Could not confirm the chain of {} ({}); nothing was signed",
+            endpoint_host(rpc_url),
+            e
+        )
+    })?;
+    check(network, chain_id, rpc_url)
+}
+
+/// Refuse to sign when the endpoint at `rpc_url` reports `chain_id` but the
+/// wallet is set to `network`
+pub fn check(network: Network, chain_id: u64, rpc_url: &str) -> Result<()> {
+    if chain_id == network.chain_id() {
+        return Ok(());
+    }
+    let reported = Network::all()
+        .into_iter()
+        .find(|known| known.chain_id() == chain_id)
+        .map_or_else(
+            || "an unknown network".to_string(),
+            |known| known.to_string(),
+        );
+    Err(anyhow!(
+        "The RPC endpoint {} is on chain {} ({}), but the wallet is set to {} (chain {}). \
+         Nothing was signed; fix the RPC URL or switch networks first.",
+        endpoint_host(rpc_url),
+        chain_id,
+        reported,
+        network,
+        network.chain_id()
+    ))
+}
+
+/// Network whose configuration is named `name` (e.g. "RSK Testnet")
+pub fn network_named(name: &str) -> Option<Network> {
+    Network::all()
+        .into_iter()
+        .find(|network| network.get_config().name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_chain_id() {
+        let url = "https://rootstock-mainnet.g.alchemy.com/v2/secret";
+        assert!(check(Network::Mainnet, 30, url).is_ok());
+        assert!(check(Network::RootStockTestnet, 31, url).is_ok());
+
+        let error = check(Network::Testnet, 30, url).unwrap_err().to_string();
+        assert!(error.contains("chain 30"));
+        assert!(error.contains("chain 31"));
+        assert!(!error.contains("secret"));
+        assert!(
+            check(Network::Mainnet, 1, url)
+                .unwrap_err()
+                .to_string()
+                .contains("unknown")
+        );
+
+        assert_eq!(network_named("RSK Testnet"), Some(Network::Testnet));
+        assert_eq!(network_named("Ethereum"), None);
+    }
+}
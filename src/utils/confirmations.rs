@@ -0,0 +1,153 @@
+//! Confirmations a transfer needs before it is reported as final.
+//!
+//! Rules are evaluated in order and the first matching rule wins; transfers
+//! that match no rule need the default number of confirmations.
+
+use crate::config::ConfigManager;
+use crate::utils::categories::{AssetFilter, amount_in_units};
+use alloy::primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Selector of `transfer(address,uint256)`
+const ERC20_TRANSFER: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfirmationRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset: Option<AssetFilter>,
+    /// Inclusive lower bound in asset units
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_amount: Option<f64>,
+    pub confirmations: u64,
+}
+
+impl ConfirmationRule {
+    pub fn matches(&self, token: Option<Address>, value: U256) -> bool {
+        let decimals = match &self.asset {
+            Some(AssetFilter::Rbtc) if token.is_some() => return false,
+            Some(AssetFilter::Token { address, .. }) if token != Some(*address) => return false,
+            Some(AssetFilter::Token { decimals, .. }) => *decimals,
+            _ => 18,
+        };
+        self.min_amount
+            .is_none_or(|min| amount_in_units(value, decimals) >= min)
+    }
+}
+
+impl fmt::Display for ConfirmationRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut conditions = Vec::new();
+        match &self.asset {
+            Some(AssetFilter::Rbtc) => conditions.push("token RBTC".to_string()),
+            Some(AssetFilter::Token { symbol, .. }) => conditions.push(format!("token {}", symbol)),
+            None => {}
+        }
+        if let Some(min) = self.min_amount {
+            conditions.push(format!("amount >= {}", min));
+        }
+        if conditions.is_empty() {
+            conditions.push("any transfer".to_string());
+        }
+        write!(
+            f,
+            "{} → {} confirmations",
+            conditions.join(" and "),
+            self.confirmations
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfirmationPolicy {
+    /// Confirmations for transfers no rule matches
+    pub default_confirmations: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<ConfirmationRule>,
+}
+
+impl Default for ConfirmationPolicy {
+    fn default() -> Self {
+        Self {
+            default_confirmations: 12,
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl ConfirmationPolicy {
+    /// Policy from the saved config, or the default if it cannot be read
+    pub fn current() -> Self {
+        ConfigManager::new()
+            .and_then(|manager| manager.load())
+            .map(|config| config.confirmations)
+            .unwrap_or_default()
+    }
+
+    /// Confirmations required for a transfer of `value` of `token` (`None`
+    /// for RBTC)
+    pub fn required(&self, token: Option<Address>, value: U256) -> u64 {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(token, value))
+            .map(|rule| rule.confirmations)
+            .unwrap_or(self.default_confirmations)
+            .max(1)
+    }
+
+    /// Confirmations required for a transaction sending `value` to `to` with
+    /// `input`, treating ERC-20 `transfer` calls as transfers of the token
+    pub fn required_for_call(&self, to: Option<Address>, value: U256, input: &[u8]) -> u64 {
+        match to {
+            Some(token) if input.len() >= 68 && input[..4] == ERC20_TRANSFER => {
+                self.required(Some(token), U256::from_be_slice(&input[36..68]))
+            }
+            _ => self.required(None, value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::utils::parse_units;
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let usdt = Address::repeat_byte(7);
+        let policy = ConfirmationPolicy {
+            default_confirmations: 6,
+            rules: vec![
+                ConfirmationRule {
+                    asset: Some(AssetFilter::Rbtc),
+                    min_amount: Some(1.0),
+                    confirmations: 12,
+                },
+                ConfirmationRule {
+                    asset: Some(AssetFilter::Token {
+                        symbol: "USDT".to_string(),
+                        address: usdt,
+                        decimals: 6,
+                    }),
+                    min_amount: None,
+                    confirmations: 2,
+                },
+            ],
+        };
+
+        let rbtc = |amount: &str| -> U256 { parse_units(amount, 18).unwrap().into() };
+        assert_eq!(policy.required(None, rbtc("1.5")), 12);
+        assert_eq!(policy.required(None, rbtc("0.5")), 6);
+        assert_eq!(policy.required(Some(usdt), U256::from(25_000_000u64)), 2);
+        assert_eq!(
+            policy.required(Some(Address::repeat_byte(9)), U256::from(1u64)),
+            6
+        );
+
+        let mut input = ERC20_TRANSFER.to_vec();
+        input.extend_from_slice(&[0u8; 32]);
+        input.extend_from_slice(&U256::from(5u64).to_be_bytes::<32>());
+        assert_eq!(policy.required_for_call(Some(usdt), U256::ZERO, &input), 2);
+    }
+}
@@ -0,0 +1,330 @@
+//! Signed contact exports for sharing an address book with teammates.
+//!
+//! The exporting wallet signs the exported contacts (personal_sign), so an
+//! importer can check who made the file and that no address in it was
+//! changed afterwards, as is done to redirect invoice payments. Only the
+//! fields worth sharing are exported, sorted, so the same address book
+//! always gives the same signed bytes.
+//!
+//! Either kind of file can be encrypted with a passphrase for sharing over
+//! channels that should not see the payees: scrypt derives the keys from
+//! the passphrase, AES-256-CBC encrypts the file and HMAC-SHA256 over the IV
+//! and ciphertext detects a wrong passphrase or a changed file before
+//! anything is decrypted.
+
+use crate::types::contacts::Contact;
+use aes::Aes256;
+use alloy::primitives::{Address, PrimitiveSignature};
+use alloy::signers::SignerSync;
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{Context, Result, anyhow};
+use cbc::cipher::block_padding::Pkcs7;
+use cbc::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use cbc::{Decryptor, Encryptor};
+use hmac::{Hmac, Mac};
+use rand::{RngCore, rngs::OsRng};
+use scrypt::{Params, scrypt};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::str::FromStr;
+
+pub const FORMAT: &str = "rootstock-wallet-contacts/1";
+pub const ENCRYPTED_FORMAT: &str = "rootstock-wallet-contacts-encrypted/1";
+
+/// A contact as shared with others: local history and challenges stay behind
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharedContact {
+    pub name: String,
+    pub address: Address,
+    pub notes: Option<String>,
+    pub tags: Vec<String>,
+    pub memo_required: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+}
+
+impl SharedContact {
+    pub fn into_contact(self) -> Contact {
+        let mut contact = Contact::new(self.name, self.address, self.notes, self.tags);
+        contact.memo_required = self.memo_required;
+        contact.groups = self.groups;
+        contact
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedContacts {
+    pub format: String,
+    pub signer: Address,
+    /// The contacts as JSON; the signature covers these exact bytes
+    pub contacts: String,
+    /// personal_sign signature over `contacts`
+    pub signature: String,
+}
+
+impl SignedContacts {
+    pub fn sign(contacts: &[Contact], signer: &PrivateKeySigner) -> Result<Self> {
+        let mut shared: Vec<SharedContact> = contacts
+            .iter()
+            .map(|contact| SharedContact {
+                name: contact.name.clone(),
+                address: contact.address,
+                notes: contact.notes.clone(),
+                tags: contact.tags.clone(),
+                memo_required: contact.memo_required,
+                groups: contact.groups.clone(),
+            })
+            .collect();
+        shared.sort_by(|a, b| a.name.cmp(&b.name).then(a.address.cmp(&b.address)));
+        let contacts = serde_json::to_string(&shared)?;
+        let signature = signer
+            .sign_message_sync(contacts.as_bytes())
+            .map_err(|e| anyhow!("Failed to sign the contacts: {}", e))?;
+        Ok(Self {
+            format: FORMAT.to_string(),
+            signer: signer.address(),
+            contacts,
+            signature: format!("0x{}", hex::encode(signature.as_bytes())),
+        })
+    }
+
+    /// Check the signature was made by `signer` over the contacts and return
+    /// them
+    pub fn verify(&self) -> Result<Vec<SharedContact>> {
+        if self.format != FORMAT {
+            return Err(anyhow!(
+                "Unsupported contacts export format '{}'",
+                self.format
+            ));
+        }
+        let signature = PrimitiveSignature::from_str(&self.signature)
+            .map_err(|e| anyhow!("Invalid export signature: {}", e))?;
+        let recovered = signature
+            .recover_address_from_msg(self.contacts.as_bytes())
+            .map_err(|e| anyhow!("Failed to recover the export signer: {}", e))?;
+        if recovered != self.signer {
+            return Err(anyhow!(
+                "The export was modified after signing: it claims 0x{:x} signed it, \
+                 but the signature is from 0x{:x}",
+                self.signer,
+                recovered
+            ));
+        }
+        serde_json::from_str(&self.contacts)
+            .map_err(|e| anyhow!("Failed to parse the signed contacts: {}", e))
+    }
+}
+
+/// scrypt parameters of an encrypted file, kept with it so files stay
+/// readable if the defaults change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+}
+
+/// A contacts file encrypted with a passphrase. Binary fields are hex
+/// encoded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedContacts {
+    pub format: String,
+    pub kdf: KdfParams,
+    pub salt: String,
+    pub iv: String,
+    /// The signed or plain contacts file, encrypted
+    pub ciphertext: String,
+    /// HMAC-SHA256 over the IV and ciphertext
+    pub mac: String,
+}
+
+impl EncryptedContacts {
+    /// Encrypt `content`, a signed or plain contacts file, with `passphrase`
+    pub fn encrypt(content: &str, passphrase: &str) -> Result<Self> {
+        Self::encrypt_with(content, passphrase, Params::recommended())
+    }
+
+    fn encrypt_with(content: &str, passphrase: &str, params: Params) -> Result<Self> {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        let mut iv = [0u8; 16];
+        OsRng.fill_bytes(&mut iv);
+        let kdf = KdfParams {
+            log_n: params.log_n(),
+            r: params.r(),
+            p: params.p(),
+        };
+        let (encryption_key, mac_key) = derive_keys(passphrase, &salt, &kdf)?;
+
+        let plaintext = content.as_bytes();
+        let mut buffer = plaintext.to_vec();
+        buffer.resize(plaintext.len() + 16 - plaintext.len() % 16, 0);
+        let ciphertext = Encryptor::<Aes256>::new(&encryption_key.into(), &iv.into())
+            .encrypt_padded_mut::<Pkcs7>(&mut buffer, plaintext.len())
+            .map_err(|e| anyhow!("Failed to encrypt the contacts: {}", e))?
+            .to_vec();
+        let tag = mac(&mac_key, &iv, &ciphertext)?;
+
+        Ok(Self {
+            format: ENCRYPTED_FORMAT.to_string(),
+            kdf,
+            salt: hex::encode(salt),
+            iv: hex::encode(iv),
+            ciphertext: hex::encode(ciphertext),
+            mac: hex::encode(tag),
+        })
+    }
+
+    /// The contacts file inside, if `passphrase` is the one it was
+    /// encrypted with
+    pub fn decrypt(&self, passphrase: &str) -> Result<String> {
+        if self.format != ENCRYPTED_FORMAT {
+            return Err(anyhow!(
+                "Unsupported encrypted contacts format '{}'",
+                self.format
+            ));
+        }
+        let salt = hex::decode(&self.salt).context("Invalid salt in the encrypted file")?;
+        let iv: [u8; 16] = hex::decode(&self.iv)
+            .ok()
+            .and_then(|iv| iv.try_into().ok())
+            .ok_or_else(|| anyhow!("Invalid IV in the encrypted file"))?;
+        let mut ciphertext =
+            hex::decode(&self.ciphertext).context("Invalid ciphertext in the encrypted file")?;
+        let tag = hex::decode(&self.mac).context("Invalid MAC in the encrypted file")?;
+
+        let (encryption_key, mac_key) = derive_keys(passphrase, &salt, &self.kdf)?;
+        let mut check = Hmac::<Sha256>::new_from_slice(&mac_key)
+            .map_err(|e| anyhow!("Invalid MAC key: {}", e))?;
+        check.update(&iv);
+        check.update(&ciphertext);
+        check.verify_slice(&tag).map_err(|_| {
+            anyhow!("Wrong passphrase, or the file was changed after it was encrypted")
+        })?;
+
+        let plaintext = Decryptor::<Aes256>::new(&encryption_key.into(), &iv.into())
+            .decrypt_padded_mut::<Pkcs7>(&mut ciphertext)
+            .map_err(|e| anyhow!("Failed to decrypt the contacts: {}", e))?;
+        String::from_utf8(plaintext.to_vec()).context("The decrypted contacts are not text")
+    }
+}
+
+/// The AES key and the MAC key for `passphrase`
+fn derive_keys(passphrase: &str, salt: &[u8], kdf: &KdfParams) -> Result<([u8; 32], [u8; 32])> {
+    let params = Params::new(kdf.log_n, kdf.r, kdf.p, 64)
+        .map_err(|e| anyhow!("Invalid scrypt parameters in the encrypted file: {}", e))?;
+    let mut keys = [0u8; 64];
+    scrypt(passphrase.as_bytes(), salt, &params, &mut keys)
+        .map_err(|e| anyhow!("Failed to derive the file key: {}", e))?;
+    let mut encryption_key = [0u8; 32];
+    let mut mac_key = [0u8; 32];
+    encryption_key.copy_from_slice(&keys[..32]);
+    mac_key.copy_from_slice(&keys[32..]);
+    Ok((encryption_key, mac_key))
+}
+
+fn mac(key: &[u8; 32], iv: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key).map_err(|e| anyhow!("Invalid MAC key: {}", e))?;
+    mac.update(iv);
+    mac.update(ciphertext);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+/// A contacts file: signed, or a plain list as written by earlier versions,
+/// either of which may be encrypted
+pub enum ContactsFile {
+    Signed(SignedContacts),
+    Unsigned(Vec<Contact>),
+    Encrypted(EncryptedContacts),
+}
+
+pub fn parse(content: &str) -> Result<ContactsFile> {
+    if let Ok(encrypted) = serde_json::from_str::<EncryptedContacts>(content) {
+        return Ok(ContactsFile::Encrypted(encrypted));
+    }
+    if let Ok(signed) = serde_json::from_str::<SignedContacts>(content) {
+        return Ok(ContactsFile::Signed(signed));
+    }
+    serde_json::from_str(content)
+        .map(ContactsFile::Unsigned)
+        .map_err(|e| anyhow!("Not a contacts file: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signed_export_detects_tampering() {
+        let signer = PrivateKeySigner::random();
+        let alice = Contact::new(
+            "Alice".to_string(),
+            Address::repeat_byte(0x0a),
+            None,
+            vec![],
+        );
+        let bob = Contact::new(
+            "Bob".to_string(),
+            Address::repeat_byte(0x0b),
+            Some("supplier".to_string()),
+            vec!["invoices".to_string()],
+        );
+
+        let signed = SignedContacts::sign(&[bob.clone(), alice.clone()], &signer).unwrap();
+        let reordered = SignedContacts::sign(&[alice, bob], &signer).unwrap();
+        assert_eq!(signed.contacts, reordered.contacts);
+
+        let content = serde_json::to_string(&signed).unwrap();
+        let ContactsFile::Signed(read) = parse(&content).unwrap() else {
+            panic!("expected a signed export");
+        };
+        let contacts = read.verify().unwrap();
+        assert_eq!(contacts[0].name, "Alice");
+        assert_eq!(read.signer, signer.address());
+
+        let mut redirected = contacts.clone();
+        redirected[1].address = Address::repeat_byte(0x0c);
+        let tampered = SignedContacts {
+            contacts: serde_json::to_string(&redirected).unwrap(),
+            ..signed
+        };
+        assert!(tampered.verify().is_err());
+    }
+
+    #[test]
+    fn test_encrypted_contacts() {
+        let signer = PrivateKeySigner::random();
+        let alice = Contact::new(
+            "Alice".to_string(),
+            Address::repeat_byte(0x0a),
+            None,
+            vec![],
+        );
+        let signed = SignedContacts::sign(&[alice], &signer).unwrap();
+        let content = serde_json::to_string(&signed).unwrap();
+        // Cheap parameters; files are written with the recommended ones
+        let params = Params::new(4, 8, 1, 64).unwrap();
+        let encrypted = EncryptedContacts::encrypt_with(&content, "correct horse", params).unwrap();
+        let file = serde_json::to_string(&encrypted).unwrap();
+        assert!(!file.contains("Alice"));
+        assert!(!file.contains("0a0a0a0a"));
+
+        let ContactsFile::Encrypted(read) = parse(&file).unwrap() else {
+            panic!("expected an encrypted file");
+        };
+        assert!(read.decrypt("wrong horse").is_err());
+        let decrypted = read.decrypt("correct horse").unwrap();
+        let ContactsFile::Signed(inner) = parse(&decrypted).unwrap() else {
+            panic!("expected a signed export inside");
+        };
+        assert_eq!(inner.verify().unwrap()[0].name, "Alice");
+
+        let mut changed = encrypted.clone();
+        changed.ciphertext.replace_range(..2, "00");
+        if changed.ciphertext == encrypted.ciphertext {
+            changed.ciphertext.replace_range(..2, "ff");
+        }
+        assert!(changed.decrypt("correct horse").is_err());
+    }
+}
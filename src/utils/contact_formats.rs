@@ -0,0 +1,377 @@
+//! Contact lists in CSV and vCard, for moving an address book between
+//! machines or in from a spreadsheet or another address book.
+//!
+//! CSV files have the columns `name,address,notes,tags,memo_required,groups`,
+//! with tags and groups separated by `;`. vCards (3.0) carry the name in
+//! `FN`, the notes in `NOTE`, the tags in `CATEGORIES`, and the address, memo
+//! flag and groups in the `X-RSK-ADDRESS`, `X-RSK-MEMO-REQUIRED` and
+//! `X-RSK-GROUPS` extensions. Addresses are
+//! written in lowercase so a file works on every network, and checked on
+//! the way in like any other typed address.
+
+use crate::commands::address::parse_address;
+use crate::types::contacts::Contact;
+use crate::utils::bulk_csv::RowError;
+use crate::utils::contact_export::SharedContact;
+use anyhow::{Result, anyhow};
+use std::path::Path;
+
+const CSV_HEADER: [&str; 6] = [
+    "name",
+    "address",
+    "notes",
+    "tags",
+    "memo_required",
+    "groups",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ContactFormat {
+    Csv,
+    Vcard,
+}
+
+impl ContactFormat {
+    /// Format named by the extension of `path` (`.csv`, `.vcf` or `.vcard`)
+    pub fn of_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase);
+        match extension.as_deref() {
+            Some("csv") => Ok(ContactFormat::Csv),
+            Some("vcf" | "vcard") => Ok(ContactFormat::Vcard),
+            _ => Err(anyhow!(
+                "Cannot tell the format of {} from its extension; pass --format csv or --format vcard",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// The contacts written out in `format`
+pub fn write(contacts: &[Contact], format: ContactFormat) -> Result<String> {
+    match format {
+        ContactFormat::Csv => write_csv(contacts),
+        ContactFormat::Vcard => Ok(contacts.iter().map(vcard).collect()),
+    }
+}
+
+/// Contacts read from `content`, with the entries that cannot be imported
+/// (by line, or by card for vCards)
+pub fn read(
+    content: &str,
+    format: ContactFormat,
+    chain_id: u64,
+) -> Result<(Vec<SharedContact>, Vec<RowError>)> {
+    match format {
+        ContactFormat::Csv => read_csv(content, chain_id),
+        ContactFormat::Vcard => Ok(read_vcards(content, chain_id)),
+    }
+}
+
+fn write_csv(contacts: &[Contact]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(CSV_HEADER)?;
+    for contact in contacts {
+        writer.write_record([
+            contact.name.as_str(),
+            &format!("{:#x}", contact.address),
+            contact.notes.as_deref().unwrap_or_default(),
+            &contact.tags.join(";"),
+            if contact.memo_required {
+                "true"
+            } else {
+                "false"
+            },
+            &contact.groups.join(";"),
+        ])?;
+    }
+    String::from_utf8(writer.into_inner()?).map_err(|e| anyhow!("Failed to write CSV: {}", e))
+}
+
+fn read_csv(content: &str, chain_id: u64) -> Result<(Vec<SharedContact>, Vec<RowError>)> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+    let mut contacts = Vec::new();
+    let mut errors = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| anyhow!("Failed to read the CSV file: {}", e))?;
+        let line = record
+            .position()
+            .map_or(index + 1, |position| position.line() as usize);
+        let field = |i: usize| record.get(i).filter(|value| !value.is_empty());
+        if index == 0 && field(0).is_some_and(|value| value.eq_ignore_ascii_case("name")) {
+            continue;
+        }
+        let contact = (|| {
+            let name = field(0).ok_or_else(|| anyhow!("missing name"))?;
+            let address = field(1).ok_or_else(|| anyhow!("missing address"))?;
+            let memo_required = match field(4).map(str::to_lowercase).as_deref() {
+                None | Some("false" | "no" | "0") => false,
+                Some("true" | "yes" | "1") => true,
+                Some(other) => {
+                    return Err(anyhow!(
+                        "memo_required must be true or false, not '{}'",
+                        other
+                    ));
+                }
+            };
+            shared(
+                name,
+                address,
+                field(2),
+                field(3).map_or_else(Vec::new, |tags| split_tags(tags, ';')),
+                memo_required,
+                field(5).map_or_else(Vec::new, |groups| split_tags(groups, ';')),
+                chain_id,
+            )
+        })();
+        match contact {
+            Ok(contact) => contacts.push(contact),
+            Err(e) => errors.push(RowError {
+                line,
+                message: e.to_string(),
+            }),
+        }
+    }
+    Ok((contacts, errors))
+}
+
+/// A vCard for `contact`
+fn vcard(contact: &Contact) -> String {
+    let mut card = format!(
+        "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:{}\r\n",
+        escape(&contact.name)
+    );
+    if let Some(notes) = &contact.notes {
+        card.push_str(&format!("NOTE:{}\r\n", escape(notes)));
+    }
+    if !contact.tags.is_empty() {
+        let tags: Vec<String> = contact.tags.iter().map(|tag| escape(tag)).collect();
+        card.push_str(&format!("CATEGORIES:{}\r\n", tags.join(",")));
+    }
+    card.push_str(&format!("X-RSK-ADDRESS:{:#x}\r\n", contact.address));
+    if contact.memo_required {
+        card.push_str("X-RSK-MEMO-REQUIRED:TRUE\r\n");
+    }
+    if !contact.groups.is_empty() {
+        let groups: Vec<String> = contact.groups.iter().map(|group| escape(group)).collect();
+        card.push_str(&format!("X-RSK-GROUPS:{}\r\n", groups.join(",")));
+    }
+    card.push_str("END:VCARD\r\n");
+    card
+}
+
+fn read_vcards(content: &str, chain_id: u64) -> (Vec<SharedContact>, Vec<RowError>) {
+    // Long lines are folded onto continuation lines starting with a space
+    // or tab
+    let unfolded = content
+        .replace("\r\n", "\n")
+        .replace("\n ", "")
+        .replace("\n\t", "");
+    let mut contacts = Vec::new();
+    let mut errors = Vec::new();
+    let mut card: Option<Vec<(String, String)>> = None;
+    let mut number = 0;
+    for line in unfolded.lines() {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        // Drop any group prefix (`item1.`) and parameters (`;CHARSET=UTF-8`)
+        let name = name.split(';').next().unwrap_or_default();
+        let name = name.rsplit('.').next().unwrap_or_default().to_uppercase();
+        match (name.as_str(), card.as_mut()) {
+            ("BEGIN", _) if value.eq_ignore_ascii_case("VCARD") => {
+                number += 1;
+                card = Some(Vec::new());
+            }
+            ("END", Some(properties)) if value.eq_ignore_ascii_case("VCARD") => {
+                let property = |key: &str| {
+                    properties
+                        .iter()
+                        .find(|(name, _)| name == key)
+                        .map(|(_, value)| value.as_str())
+                        .filter(|value| !value.is_empty())
+                };
+                let contact = (|| {
+                    let name = property("FN").ok_or_else(|| anyhow!("missing FN (name)"))?;
+                    let address = property("X-RSK-ADDRESS")
+                        .ok_or_else(|| anyhow!("missing X-RSK-ADDRESS"))?;
+                    shared(
+                        &unescape(name),
+                        address,
+                        property("NOTE").map(unescape).as_deref(),
+                        property("CATEGORIES").map_or_else(Vec::new, unescape_list),
+                        property("X-RSK-MEMO-REQUIRED")
+                            .is_some_and(|value| value.eq_ignore_ascii_case("TRUE")),
+                        property("X-RSK-GROUPS").map_or_else(Vec::new, unescape_list),
+                        chain_id,
+                    )
+                })();
+                match contact {
+                    Ok(contact) => contacts.push(contact),
+                    Err(e) => errors.push(RowError {
+                        line: number,
+                        message: e.to_string(),
+                    }),
+                }
+                card = None;
+            }
+            (_, Some(properties)) => properties.push((name, value.to_string())),
+            _ => {}
+        }
+    }
+    (contacts, errors)
+}
+
+/// Build and check an imported contact
+fn shared(
+    name: &str,
+    address: &str,
+    notes: Option<&str>,
+    tags: Vec<String>,
+    memo_required: bool,
+    groups: Vec<String>,
+    chain_id: u64,
+) -> Result<SharedContact> {
+    let contact = SharedContact {
+        name: name.to_string(),
+        address: parse_address(address, chain_id)?,
+        notes: notes.map(str::to_string),
+        tags,
+        memo_required,
+        groups,
+    };
+    contact.clone().into_contact().validate()?;
+    Ok(contact)
+}
+
+/// Tags separated by `separator`, skipping empty ones; an escaped
+/// separator (`\,`) stays inside its tag
+fn split_tags(tags: &str, separator: char) -> Vec<String> {
+    let mut split = Vec::new();
+    let mut current = String::new();
+    let mut escaped = false;
+    for c in tags.chars() {
+        if c == separator && !escaped {
+            split.push(std::mem::take(&mut current));
+        } else {
+            current.push(c);
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    split.push(current);
+    split
+        .into_iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// Items of a comma-separated vCard list
+fn unescape_list(value: &str) -> Vec<String> {
+    split_tags(value, ',')
+        .iter()
+        .map(|item| unescape(item))
+        .collect()
+}
+
+/// Escape a vCard text value
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(';', "\\;")
+        .replace('\n', "\\n")
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(next) => unescaped.push(next),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Address;
+
+    #[test]
+    fn test_contact_formats_round_trip() {
+        let mut alice = Contact::new(
+            "Alice, Ltd.".to_string(),
+            Address::repeat_byte(0x0a),
+            Some("supplier; pays in DOC\nsecond line".to_string()),
+            vec!["invoices".to_string(), "team a".to_string()],
+        );
+        alice.memo_required = true;
+        alice.join_group("Payroll, EU");
+        alice.join_group("suppliers");
+        let bob = Contact::new("Bob".to_string(), Address::repeat_byte(0x0b), None, vec![]);
+        let contacts = [alice, bob];
+
+        for format in [ContactFormat::Csv, ContactFormat::Vcard] {
+            let content = write(&contacts, format).unwrap();
+            let (read, errors) = read(&content, format, 31).unwrap();
+            assert!(errors.is_empty(), "{:?}", errors);
+            assert_eq!(read.len(), 2);
+            assert_eq!(read[0].name, "Alice, Ltd.");
+            assert_eq!(read[0].address, Address::repeat_byte(0x0a));
+            assert_eq!(read[0].notes, contacts[0].notes);
+            assert_eq!(read[0].tags, contacts[0].tags);
+            assert!(read[0].memo_required);
+            assert_eq!(read[0].groups, contacts[0].groups);
+            assert_eq!(read[1].notes, None);
+            assert!(read[1].tags.is_empty());
+            assert!(!read[1].memo_required);
+        }
+
+        assert_eq!(
+            ContactFormat::of_path(Path::new("book.VCF")).unwrap(),
+            ContactFormat::Vcard
+        );
+        assert!(ContactFormat::of_path(Path::new("book.txt")).is_err());
+    }
+
+    #[test]
+    fn test_contact_import_errors() {
+        let alice = format!("{:#x}", Address::repeat_byte(0x0a));
+        let csv = format!(
+            "Alice,{},,friends;;work,yes\n,{}\nCarol,0x1234\nDan,{},,,maybe\n",
+            alice, alice, alice
+        );
+        let (contacts, errors) = read(&csv, ContactFormat::Csv, 31).unwrap();
+        assert_eq!(contacts.len(), 1);
+        assert_eq!(contacts[0].tags, vec!["friends", "work"]);
+        assert!(contacts[0].memo_required);
+        assert_eq!(
+            errors.iter().map(|e| e.line).collect::<Vec<_>>(),
+            vec![2, 3, 4]
+        );
+
+        // Folded lines, parameters and group prefixes from other address books
+        let cards = format!(
+            "BEGIN:VCARD\nVERSION:3.0\nFN;CHARSET=UTF-8:Al\n ice\nitem1.X-RSK-ADDRESS:{}\nEND:VCARD\nBEGIN:VCARD\nFN:No address\nEND:VCARD\n",
+            alice
+        );
+        let (contacts, errors) = read(&cards, ContactFormat::Vcard, 31).unwrap();
+        assert_eq!(contacts[0].name, "Alice");
+        assert_eq!(errors[0].line, 2);
+        assert!(errors[0].message.contains("X-RSK-ADDRESS"));
+    }
+}
@@ -0,0 +1,53 @@
+//! Cost centers (departments, projects) that sent transactions are allocated
+//! to, so reports and exports can split on-chain spend between them.
+//!
+//! The list of cost centers is kept in the configuration; the allocation of
+//! each transaction, by hash, is kept in the wallet database.
+
+use crate::utils::storage::Storage;
+use alloy::primitives::B256;
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+
+/// The configured cost center matching `name`, ignoring case
+pub fn resolve(configured: &[String], name: &str) -> Result<String> {
+    if configured.is_empty() {
+        return Err(anyhow!(
+            "No cost centers are configured. Add them under Configuration → Cost Centers."
+        ));
+    }
+    configured
+        .iter()
+        .find(|center| center.eq_ignore_ascii_case(name.trim()))
+        .cloned()
+        .ok_or_else(|| {
+            anyhow!(
+                "Unknown cost center '{}'. Configured: {}",
+                name,
+                configured.join(", ")
+            )
+        })
+}
+
+/// Allocate a sent transaction to a cost center
+pub fn record(hash: B256, cost_center: &str) -> Result<()> {
+    Storage::open()?.record_cost_allocation(hash, cost_center)
+}
+
+/// Cost center of every allocated transaction, by hash
+pub fn load() -> Result<HashMap<B256, String>> {
+    Storage::open()?.load_cost_allocations()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_against_configured_list() {
+        let configured = vec!["Marketing".to_string(), "R&D".to_string()];
+        assert_eq!(resolve(&configured, " marketing").unwrap(), "Marketing");
+        assert!(resolve(&configured, "Sales").is_err());
+        assert!(resolve(&[], "Marketing").is_err());
+    }
+}
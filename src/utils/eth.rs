@@ -1,14 +1,27 @@
+use crate::types::network::Network;
 use crate::types::wallet::WalletData;
-use crate::utils::constants;
+use crate::utils::audit;
+use crate::utils::chain_guard;
+use crate::utils::fees::{self, FeeSuggestion};
+use crate::utils::gas_oracle::{self, GasOracle};
 use crate::utils::helper::Config;
+use crate::utils::http::{self, RpcProvider};
+use crate::utils::live::{ChainEvent, Ticker};
+use crate::utils::nonce;
+use crate::utils::pending::{PendingStore, PendingTx};
+use crate::utils::signer::WalletSigner;
+use crate::utils::signing_log::{self, SignedTx};
+use crate::utils::simulation;
+use crate::utils::submissions;
 use anyhow::anyhow;
+use alloy::eips::{BlockId, BlockNumberOrTag};
 use alloy::primitives::{Address, B256, U256};
-use alloy::providers::{Provider, ProviderBuilder, RootProvider};
+use alloy::rpc::types::BlockTransactionsKind;
+use chrono::{DateTime, Utc};
+use alloy::providers::Provider;
 use alloy::signers::local::PrivateKeySigner;
-use alloy::transports::http::{Client, Http};
 use alloy::network::TransactionBuilder;
 use alloy::sol;
-use std::fs;
 use std::sync::Arc;
 
 // Define ERC20 interface using alloy's sol! macro
@@ -18,53 +31,222 @@ sol! {
     contract IERC20 {
         function balanceOf(address account) external view returns (uint256);
         function transfer(address recipient, uint256 amount) external returns (bool);
+        function approve(address spender, uint256 amount) external returns (bool);
+        function allowance(address owner, address spender) external view returns (uint256);
         function decimals() external view returns (uint8);
         function symbol() external view returns (string);
+        function totalSupply() external view returns (uint256);
     }
 }
 
+// Disperse-style contract paying many recipients in one transaction
+sol! {
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    contract IMultisend {
+        function disperseEther(address[] recipients, uint256[] values) external payable;
+        function disperseToken(address token, address[] recipients, uint256[] values) external;
+    }
+}
+
+/// Optional replacements for values normally fetched from the node.
+/// Rootstock uses legacy gas pricing, so the gas price is also the max fee.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GasOverrides {
+    pub gas_limit: Option<u64>,
+    pub gas_price: Option<u128>,
+    pub nonce: Option<u64>,
+}
+
 pub struct EthClient {
-    provider: Arc<RootProvider<Http<Client>>>,
-    wallet: Option<PrivateKeySigner>,
+    provider: Arc<RpcProvider>,
+    wallet: Option<WalletSigner>,
+    /// Endpoints signed transactions are broadcast to in parallel; empty
+    /// unless multi-endpoint broadcasting is enabled
+    broadcast_endpoints: Vec<String>,
+    /// WebSocket endpoint subscribed to while waiting on the chain, if set
+    ws_url: Option<String>,
+    /// Network the client was configured for, checked against the
+    /// endpoint's chain id before signing
+    network: Network,
+    rpc_url: String,
 }
 
 impl EthClient {
     pub async fn new(config: &Config, cli_api_key: Option<String>) -> Result<Self, anyhow::Error> {
         // Load or update API key
-        let wallet_file = constants::wallet_file_path();
-        let mut wallet_data = if wallet_file.exists() {
-            let data = fs::read_to_string(&wallet_file)?;
-            serde_json::from_str::<WalletData>(&data)?
-        } else {
-            WalletData::new()
-        };
+        let mut wallet_data = WalletData::load()?;
 
         let _api_key = if let Some(key) = cli_api_key {
-            wallet_data.api_key = Some(key.clone());
-            fs::write(&wallet_file, serde_json::to_string_pretty(&wallet_data)?)?;
+            wallet_data.set_api_key(key.clone())?;
             Some(key)
         } else {
-            wallet_data.api_key.clone()
+            wallet_data.stored_api_key()?
         };
 
-        // Use the RPC URL from config (which defaults to public nodes)
-        let provider = ProviderBuilder::new()
-            .on_http(config.network.rpc_url.parse()?);
         let wallet = config
             .wallet
             .private_key
             .as_ref()
             .map(|key| {
                 key.parse::<PrivateKeySigner>()
+                    .map(WalletSigner::local)
                     .map_err(|e| anyhow!("Invalid private key: {}", e))
             })
             .transpose()?;
+
+        // Only broadcast to the configured endpoints when they belong to the
+        // same network as this client
+        let app_config = crate::config::ConfigManager::new()
+            .and_then(|manager| manager.load())
+            .unwrap_or_default();
+        let mut broadcast_endpoints = Vec::new();
+        if app_config.multi_broadcast
+            && app_config.default_network.get_config().name == config.network.name
+        {
+            broadcast_endpoints.push(config.network.rpc_url.clone());
+            for endpoint in app_config.broadcast_endpoints() {
+                if !broadcast_endpoints.contains(&endpoint) {
+                    broadcast_endpoints.push(endpoint);
+                }
+            }
+        }
+
+        let ws_url = app_config
+            .ws_url_for(&config.network.name)
+            .map(str::to_string);
+        let network =
+            chain_guard::network_named(&config.network.name).unwrap_or(app_config.default_network);
+
+        // Use the RPC URL from config (which defaults to public nodes), failing
+        // over to the other endpoints known for the network
+        let mut rpc_urls = config.network.rpc_urls();
+        let fallbacks = if app_config.default_network.get_config().name == config.network.name {
+            app_config.broadcast_endpoints()
+        } else {
+            vec![network.get_rpc_url_with_key(None, None)]
+        };
+        for url in fallbacks {
+            if !rpc_urls.contains(&url) {
+                rpc_urls.push(url);
+            }
+        }
+        let provider = http::failover_provider(&rpc_urls)?;
+
         Ok(Self {
             provider: Arc::new(provider),
             wallet,
+            broadcast_endpoints,
+            ws_url,
+            network,
+            rpc_url: config.network.rpc_url.clone(),
         })
     }
 
+    /// Sign with the given signer instead of a private key from the config,
+    /// e.g. a hardware wallet
+    pub fn with_signer(mut self, signer: WalletSigner) -> Self {
+        self.wallet = Some(signer);
+        self
+    }
+
+    /// Submit a fully populated transaction. It is signed here (on the device
+    /// for hardware wallets) and sent raw, to every endpoint when
+    /// broadcasting. Submitted transactions are kept in the pending store so
+    /// they can be sped up or cancelled, and in the signing log with their
+    /// raw payload for audits. Nothing is signed unless the endpoint is on
+    /// the configured network. A transaction without a nonce gets one from
+    /// the nonce manager here, which is released again if sending fails.
+    async fn submit(
+        &self,
+        tx: alloy::rpc::types::TransactionRequest,
+        wallet: &WalletSigner,
+    ) -> Result<B256, anyhow::Error> {
+        chain_guard::verify(&self.provider, self.network, &self.rpc_url).await?;
+        let (tx, reserved) = match tx.nonce {
+            Some(_) => (tx, None),
+            None => {
+                let nonce = nonce::reserve(&self.provider, wallet.address()).await?;
+                (tx.with_nonce(nonce), Some(nonce))
+            }
+        };
+        let request = tx.clone();
+        let sent = match wallet.sign_transaction(tx).await {
+            Ok(raw) => self.send_raw(&raw).await.map(|tx_hash| (tx_hash, raw)),
+            Err(e) => Err(e),
+        };
+        let (tx_hash, raw) = match sent {
+            Ok(sent) => sent,
+            Err(e) => {
+                if let Some(nonce) = reserved {
+                    nonce::release(self.chain_id(), wallet.address(), nonce).await;
+                }
+                return Err(e);
+            }
+        };
+        record_submission(tx_hash, &request, raw, wallet.address());
+        Ok(tx_hash)
+    }
+
+    /// Send an already signed transaction, to every endpoint when
+    /// broadcasting
+    pub async fn send_raw(&self, raw: &[u8]) -> Result<B256, anyhow::Error> {
+        if self.broadcast_endpoints.len() > 1 {
+            broadcast_raw_transaction(&self.broadcast_endpoints, raw.to_vec()).await
+        } else {
+            Ok(*self.provider.send_raw_transaction(raw).await?.tx_hash())
+        }
+    }
+
+    /// Replace a pending transaction with one using the same nonce and
+    /// `gas_price`. With `cancel` the replacement is an empty transfer to the
+    /// sender, so the nonce is used up without the original taking effect.
+    pub async fn replace_transaction(
+        &self,
+        pending: &PendingTx,
+        gas_price: u128,
+        cancel: bool,
+    ) -> Result<B256, anyhow::Error> {
+        let wallet = self
+            .wallet
+            .as_ref()
+            .ok_or_else(|| anyhow!("No wallet configured"))?;
+        if wallet.address() != pending.from {
+            return Err(anyhow!(
+                "Transaction 0x{:x} was sent from 0x{:x}, not 0x{:x}",
+                pending.hash,
+                pending.from,
+                wallet.address()
+            ));
+        }
+
+        use alloy::rpc::types::TransactionRequest;
+        let tx = TransactionRequest::default()
+            .with_from(pending.from)
+            .with_nonce(pending.nonce)
+            .with_gas_price(gas_price)
+            .with_chain_id(pending.chain_id);
+        let tx = if cancel {
+            tx.with_to(pending.from)
+                .with_value(U256::ZERO)
+                .with_gas_limit(21_000)
+        } else {
+            tx.with_to(pending.to)
+                .with_value(pending.value)
+                .with_input(pending.input.clone())
+                .with_gas_limit(pending.gas_limit)
+        };
+
+        let tx_hash = self
+            .submit(tx, wallet)
+            .await
+            .map_err(|e| anyhow!("Failed to send replacement transaction: {}", e))?;
+        let mut store = PendingStore::load()?;
+        store.mark_replaced(pending.hash, tx_hash);
+        store.save()?;
+        Ok(tx_hash)
+    }
+
     pub async fn get_balance(
         &self,
         address: &Address,
@@ -88,32 +270,179 @@ impl EthClient {
         }
     }
 
+    /// Balance of `address` at a past `block`. Only archive nodes keep the
+    /// state of old blocks; other endpoints fail with an explanation.
+    pub async fn get_balance_at(
+        &self,
+        address: &Address,
+        token_address: &Option<Address>,
+        block: u64,
+    ) -> Result<U256, anyhow::Error> {
+        let block_id = BlockId::number(block);
+        let result = match token_address {
+            Some(token_addr) => IERC20::new(*token_addr, &self.provider)
+                .balanceOf(*address)
+                .block(block_id)
+                .call()
+                .await
+                .map(|balance| balance._0)
+                .map_err(|e| e.to_string()),
+            None => self
+                .provider
+                .get_balance(*address)
+                .block_id(block_id)
+                .await
+                .map_err(|e| e.to_string()),
+        };
+        result.map_err(|e| historical_state_error(block, &e))
+    }
+
+    /// Timestamp (seconds since the epoch) of a mined block
+    pub async fn get_block_timestamp(&self, block: u64) -> Result<u64, anyhow::Error> {
+        let header = self
+            .provider
+            .get_block_by_number(
+                BlockNumberOrTag::Number(block),
+                BlockTransactionsKind::Hashes,
+            )
+            .await
+            .map_err(|e| anyhow!("Failed to get block {}: {}", block, e))?
+            .ok_or_else(|| anyhow!("Block {} not found", block))?
+            .header;
+        Ok(header.timestamp)
+    }
+
+    /// The last block mined before `time`, found by bisecting block
+    /// timestamps
+    pub async fn block_before(&self, time: DateTime<Utc>) -> Result<u64, anyhow::Error> {
+        let target = u64::try_from(time.timestamp())
+            .map_err(|_| anyhow!("{} is before the chain started", time))?;
+        let head = self
+            .provider
+            .get_block_number()
+            .await
+            .map_err(|e| anyhow!("Failed to get block number: {}", e))?;
+        if self.get_block_timestamp(head).await? < target {
+            return Ok(head);
+        }
+        if self.get_block_timestamp(0).await? >= target {
+            return Err(anyhow!("No block was mined before {}", time));
+        }
+
+        // Invariant: block `low` is before `time`, block `high` is not
+        let (mut low, mut high) = (0, head);
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if self.get_block_timestamp(mid).await? < target {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(low)
+    }
+
     pub async fn send_transaction(
         &self,
         to: Address,
         amount: U256,
         token_address: Option<Address>,
+    ) -> Result<B256, anyhow::Error> {
+        self.send_transaction_with_overrides(
+            to,
+            amount,
+            token_address,
+            GasOverrides::default(),
+            None,
+        )
+        .await
+    }
+
+    /// Send a transaction, using any provided gas limit, gas price or nonce
+    /// instead of the values fetched from the node. A `memo` is carried as
+    /// the input of an RBTC transfer, or appended to the `transfer` call data
+    /// of a token transfer. A transaction that would revert is not sent.
+    pub async fn send_transaction_with_overrides(
+        &self,
+        to: Address,
+        amount: U256,
+        token_address: Option<Address>,
+        overrides: GasOverrides,
+        memo: Option<&[u8]>,
+    ) -> Result<B256, anyhow::Error> {
+        let tx = self
+            .prepare_transfer(to, amount, token_address, overrides, memo)
+            .await?;
+        let asset = if token_address.is_some() {
+            "token"
+        } else {
+            "RBTC"
+        };
+        self.send_prepared(tx)
+            .await
+            .map_err(|e| anyhow!("Failed to send {} transaction: {}", asset, e))
+    }
+
+    /// Build a transfer from the configured wallet as
+    /// `send_transaction_with_overrides` does and check it would not revert,
+    /// without signing it
+    pub async fn prepare_transfer(
+        &self,
+        to: Address,
+        amount: U256,
+        token_address: Option<Address>,
+        overrides: GasOverrides,
+        memo: Option<&[u8]>,
+    ) -> Result<alloy::rpc::types::TransactionRequest, anyhow::Error> {
+        let wallet = self
+            .wallet
+            .as_ref()
+            .ok_or_else(|| anyhow!("No wallet configured"))?;
+        let tx = self
+            .build_transfer(wallet.address(), to, amount, token_address, overrides, memo)
+            .await?;
+        if let Some(reason) = simulation::revert_reason(&self.provider, &tx).await {
+            return Err(anyhow!("The transaction would fail: {}", reason));
+        }
+        Ok(tx)
+    }
+
+    /// Sign and send a transaction from `prepare_transfer`
+    pub async fn send_prepared(
+        &self,
+        tx: alloy::rpc::types::TransactionRequest,
     ) -> Result<B256, anyhow::Error> {
         let wallet = self
             .wallet
             .as_ref()
             .ok_or_else(|| anyhow!("No wallet configured"))?;
-        let nonce = self
-            .provider
-            .get_transaction_count(wallet.address())
-            .await
-            .map_err(|e| anyhow!("Failed to get nonce: {}", e))?;
-        let gas_price = self
-            .provider
-            .get_gas_price()
-            .await
-            .map_err(|e| anyhow!("Failed to get gas price: {}", e))?;
+        self.submit(tx, wallet).await
+    }
+
+    /// Build an unsigned transfer from `from`, checking its balances. Gas
+    /// price and gas limit come from the node unless overridden. The nonce
+    /// is only set when overridden; otherwise it is reserved when the
+    /// transaction is submitted.
+    pub async fn build_transfer(
+        &self,
+        from: Address,
+        to: Address,
+        amount: U256,
+        token_address: Option<Address>,
+        overrides: GasOverrides,
+        memo: Option<&[u8]>,
+    ) -> Result<alloy::rpc::types::TransactionRequest, anyhow::Error> {
+        let gas_price = match overrides.gas_price {
+            Some(gas_price) => gas_price,
+            None => self.suggest_gas_price().await?.gas_price,
+        };
         let rbtc_balance = self
             .provider
-            .get_balance(wallet.address())
+            .get_balance(from)
             .await
             .map_err(|e| anyhow!("Failed to get RBTC balance: {}", e))?;
-        let estimated_gas_cost = U256::from(gas_price) * U256::from(100_000);
+        let estimated_gas_cost =
+            U256::from(gas_price) * U256::from(overrides.gas_limit.unwrap_or(100_000));
         if rbtc_balance < estimated_gas_cost {
             return Err(anyhow!("Insufficient RBTC for gas fees"));
         }
@@ -123,7 +452,7 @@ impl EthClient {
             Some(token_addr) => {
                 let contract = IERC20::new(token_addr, &self.provider);
                 let token_balance = contract
-                    .balanceOf(wallet.address())
+                    .balanceOf(from)
                     .call()
                     .await
                     .map_err(|e| anyhow!("Failed to get token balance: {}", e))?;
@@ -132,31 +461,27 @@ impl EthClient {
                 }
                 
                 use alloy::rpc::types::TransactionRequest;
-                let call_data = contract.transfer(to, amount).calldata().clone();
-                let tx = TransactionRequest::default()
+                let mut call_data = contract.transfer(to, amount).calldata().to_vec();
+                call_data.extend_from_slice(memo.unwrap_or_default());
+                let mut tx = TransactionRequest::default()
                     .with_to(token_addr)
-                    .with_from(wallet.address())
-                    .with_nonce(nonce)
+                    .with_from(from)
                     .with_gas_price(gas_price)
                     .with_value(U256::ZERO)
                     .with_input(call_data)
                     .with_chain_id(chain_id);
+                tx.nonce = overrides.nonce;
                 
-                let gas_estimate = self
-                    .provider
-                    .estimate_gas(&tx)
-                    .await
-                    .map_err(|e| anyhow!("Failed to estimate gas for token transfer: {}", e))?;
+                let gas_estimate = match overrides.gas_limit {
+                    Some(gas_limit) => gas_limit,
+                    None => self.provider.estimate_gas(&tx).await.map_err(|e| {
+                        anyhow!("Failed to estimate gas for token transfer: {}", e)
+                    })?,
+                };
                 
                 let tx = tx.with_gas_limit(gas_estimate);
                 
-                let pending_tx = self
-                    .provider
-                    .send_transaction(tx)
-                    .await
-                    .map_err(|e| anyhow!("Failed to send token transaction: {}", e))?;
-                let tx_hash = pending_tx.tx_hash();
-                Ok(*tx_hash)
+                Ok(tx)
             }
             None => {
                 if rbtc_balance < amount + estimated_gas_cost {
@@ -164,34 +489,199 @@ impl EthClient {
                 }
                 
                 use alloy::rpc::types::TransactionRequest;
-                let tx = TransactionRequest::default()
+                let mut tx = TransactionRequest::default()
                     .with_to(to)
                     .with_value(amount)
-                    .with_from(wallet.address())
-                    .with_nonce(nonce)
+                    .with_from(from)
                     .with_gas_price(gas_price)
                     .with_chain_id(chain_id);
+                tx.nonce = overrides.nonce;
+                if let Some(memo) = memo {
+                    tx = tx.with_input(memo.to_vec());
+                }
                 
-                let gas_estimate = self
-                    .provider
-                    .estimate_gas(&tx)
-                    .await
-                    .map_err(|e| anyhow!("Failed to estimate gas for RBTC transfer: {}", e))?;
+                let gas_estimate = match overrides.gas_limit {
+                    Some(gas_limit) => gas_limit,
+                    None => self.provider.estimate_gas(&tx).await.map_err(|e| {
+                        anyhow!("Failed to estimate gas for RBTC transfer: {}", e)
+                    })?,
+                };
                 
                 let tx = tx.with_gas_limit(gas_estimate);
                 
-                let pending_tx = self
+                Ok(tx)
+            }
+        }
+    }
+
+    /// Send a contract call with `input` and `value` from the configured wallet
+    pub async fn send_call(
+        &self,
+        to: Address,
+        value: U256,
+        input: alloy::primitives::Bytes,
+    ) -> Result<B256, anyhow::Error> {
+        let wallet = self
+            .wallet
+            .as_ref()
+            .ok_or_else(|| anyhow!("No wallet configured"))?;
+        let gas_price = self.suggest_gas_price().await?.gas_price;
+        let chain_id = self.provider.get_chain_id().await?;
+
+        use alloy::rpc::types::TransactionRequest;
+        let tx = TransactionRequest::default()
+            .with_to(to)
+            .with_from(wallet.address())
+            .with_gas_price(gas_price)
+            .with_value(value)
+            .with_input(input)
+            .with_chain_id(chain_id);
+        let gas_estimate = self
+            .provider
+            .estimate_gas(&tx)
+            .await
+            .map_err(|e| anyhow!("Failed to estimate gas for contract call: {}", e))?;
+        let tx = tx.with_gas_limit(gas_estimate);
+
+        self.submit(tx, wallet)
+            .await
+            .map_err(|e| anyhow!("Failed to send contract call: {}", e))
+    }
+
+    /// Pay several recipients of one asset in a single transaction through a
+    /// multisend contract, approving the token total for the contract first.
+    /// Returns the hash of the payout transaction.
+    pub async fn multisend(
+        &self,
+        contract: Address,
+        token: Option<Address>,
+        recipients: Vec<Address>,
+        values: Vec<U256>,
+    ) -> Result<B256, anyhow::Error> {
+        let total = values.iter().fold(U256::ZERO, |sum, value| sum + *value);
+        let multisend = IMultisend::new(contract, &self.provider);
+        match token {
+            Some(token_addr) => {
+                let approve = IERC20::new(token_addr, &self.provider)
+                    .approve(contract, total)
+                    .calldata()
+                    .clone();
+                let approve_hash = self.send_call(token_addr, U256::ZERO, approve).await?;
+                if !self.wait_for_receipt(approve_hash).await?.status() {
+                    return Err(anyhow!(
+                        "Token approval 0x{:x} for the multisend contract reverted",
+                        approve_hash
+                    ));
+                }
+                let input = multisend
+                    .disperseToken(token_addr, recipients, values)
+                    .calldata()
+                    .clone();
+                self.send_call(contract, U256::ZERO, input).await
+            }
+            None => {
+                let input = multisend
+                    .disperseEther(recipients, values)
+                    .calldata()
+                    .clone();
+                self.send_call(contract, total, input).await
+            }
+        }
+    }
+
+    /// Send the entire RBTC balance minus the fee of a plain transfer.
+    /// Returns `None` when the balance does not cover the fee.
+    pub async fn sweep_rbtc(&self, to: Address) -> Result<Option<(B256, U256)>, anyhow::Error> {
+        let wallet = self
+            .wallet
+            .as_ref()
+            .ok_or_else(|| anyhow!("No wallet configured"))?;
+        let gas_price = self.suggest_gas_price().await?.gas_price;
+        let balance = self
+            .provider
+            .get_balance(wallet.address())
+            .await
+            .map_err(|e| anyhow!("Failed to get RBTC balance: {}", e))?;
+        let gas_limit = 21_000u64;
+        let fee = U256::from(gas_price) * U256::from(gas_limit);
+        if balance <= fee {
+            return Ok(None);
+        }
+        let amount = balance - fee;
+        let chain_id = self.provider.get_chain_id().await?;
+
+        use alloy::rpc::types::TransactionRequest;
+        let tx = TransactionRequest::default()
+            .with_to(to)
+            .with_value(amount)
+            .with_from(wallet.address())
+            .with_gas_price(gas_price)
+            .with_gas_limit(gas_limit)
+            .with_chain_id(chain_id);
+
+        let tx_hash = self
+            .submit(tx, wallet)
+            .await
+            .map_err(|e| anyhow!("Failed to send RBTC transaction: {}", e))?;
+        Ok(Some((tx_hash, amount)))
+    }
+
+    /// WebSocket endpoint for live updates, when one is configured for this
+    /// client's network
+    pub fn ws_url(&self) -> Option<&str> {
+        self.ws_url.as_deref()
+    }
+
+    /// Wait for a transaction receipt for as long as the configured interval
+    /// and attempts allow, checking on every new block when subscribed
+    pub async fn wait_for_receipt(
+        &self,
+        tx_hash: B256,
+    ) -> Result<alloy::rpc::types::TransactionReceipt, anyhow::Error> {
+        let polling = crate::config::PollingConfig::current();
+        let deadline = tokio::time::Instant::now()
+            + polling.receipt_poll_interval() * polling.receipt_poll_attempts;
+        let mut ticker = Ticker::connect(self.ws_url(), polling.receipt_poll_interval(), None).await;
+        loop {
+            match self.get_transaction_receipt(tx_hash).await {
+                Ok(receipt) => return Ok(receipt),
+                Err(_) if tokio::time::Instant::now() < deadline => {
+                    let _ = tokio::time::timeout_at(deadline, ticker.tick()).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Wait until the block a transaction was included in has `required`
+    /// confirmations
+    pub async fn wait_for_confirmations(
+        &self,
+        included: u64,
+        required: u64,
+    ) -> Result<(), anyhow::Error> {
+        let polling = crate::config::PollingConfig::current();
+        let mut ticker = Ticker::connect(self.ws_url(), polling.receipt_poll_interval(), None).await;
+        let mut head = self
+            .provider
+            .get_block_number()
+            .await
+            .map_err(|e| anyhow!("Failed to get block number: {}", e))?;
+        while head.saturating_sub(included) + 1 < required {
+            head = match ticker.tick().await {
+                Some(ChainEvent::Head(number)) => number,
+                _ => self
                     .provider
-                    .send_transaction(tx)
+                    .get_block_number()
                     .await
-                    .map_err(|e| anyhow!("Failed to send RBTC transaction: {}", e))?;
-                let tx_hash = pending_tx.tx_hash();
-                Ok(*tx_hash)
-            }
+                    .map_err(|e| anyhow!("Failed to get block number: {}", e))?,
+            };
         }
+        Ok(())
     }
 
-    /// Get transaction receipt by hash
+    /// Get transaction receipt by hash. The first receipt of a transaction
+    /// this run submitted reports its outcome; see [`submissions`].
     pub async fn get_transaction_receipt(
         &self,
         tx_hash: B256,
@@ -201,6 +691,7 @@ impl EthClient {
             .await
             .map_err(|e| anyhow!("Failed to get transaction receipt: {}", e))
             .and_then(|receipt| receipt.ok_or_else(|| anyhow!("Transaction receipt not found")))
+            .inspect(submissions::mined)
     }
 
     pub async fn get_token_info(
@@ -213,39 +704,187 @@ impl EthClient {
         Ok((decimals, symbol))
     }
 
+    /// Amount of `token` that `spender` may still move from `owner`
+    pub async fn get_allowance(
+        &self,
+        token_address: Address,
+        owner: Address,
+        spender: Address,
+    ) -> Result<U256, anyhow::Error> {
+        let contract = IERC20::new(token_address, &self.provider);
+        let allowance = contract
+            .allowance(owner, spender)
+            .call()
+            .await
+            .map_err(|e| anyhow!("Failed to get token allowance: {}", e))?;
+        Ok(allowance._0)
+    }
+
+    /// Set the allowance of `spender` over `token` from the configured wallet
+    /// to `amount`; zero revokes it
+    pub async fn approve(
+        &self,
+        token_address: Address,
+        spender: Address,
+        amount: U256,
+    ) -> Result<B256, anyhow::Error> {
+        let input = IERC20::new(token_address, &self.provider)
+            .approve(spender, amount)
+            .calldata()
+            .clone();
+        self.send_call(token_address, U256::ZERO, input).await
+    }
+
+    /// Get the total supply of an ERC20 token
+    pub async fn get_token_total_supply(&self, token_address: Address) -> Result<U256, anyhow::Error> {
+        let contract = IERC20::new(token_address, &self.provider);
+        let supply = contract
+            .totalSupply()
+            .call()
+            .await
+            .map_err(|e| anyhow!("Failed to get token total supply: {}", e))?;
+        Ok(supply._0)
+    }
+
+    /// Gas price to use when none is given, from the fee history where the
+    /// endpoint supports it
+    pub async fn suggest_gas_price(&self) -> Result<FeeSuggestion, anyhow::Error> {
+        fees::suggest_gas_price(&self.provider).await
+    }
+
+    /// Slow, standard and fast gas prices with their expected wait
+    pub async fn gas_oracle(&self) -> Result<GasOracle, anyhow::Error> {
+        gas_oracle::gas_oracle(&self.provider).await
+    }
+
+    /// Chain id of the network the client was configured for
+    pub fn chain_id(&self) -> u64 {
+        self.network.chain_id()
+    }
+
     /// Get a reference to the underlying provider
-    pub fn provider(&self) -> &RootProvider<Http<Client>> {
+    pub fn provider(&self) -> &RpcProvider {
         &self.provider
     }
 
+    /// Gas needed to send `amount` of `token_address` (RBTC when `None`)
+    /// from `from` to `to`. The sender matters: a token transfer from an
+    /// account without the balance reverts instead of being estimated.
     pub async fn estimate_gas(
         &self,
+        from: Address,
         to: Address,
         amount: U256,
         token_address: Option<Address>,
     ) -> Result<U256, anyhow::Error> {
-        match token_address {
-            Some(token_addr) => {
-                let contract = IERC20::new(token_addr, &self.provider);
-                let call = contract.transfer(to, amount);
-                call.estimate_gas()
-                    .await
-                    .map(|gas| U256::from(gas))
-                    .map_err(|e| anyhow!("Failed to estimate gas for token transfer: {}", e))
-            }
-            None => {
-                use alloy::rpc::types::TransactionRequest;
-                let tx = TransactionRequest::default()
-                    .with_to(to)
-                    .with_value(amount);
-                self.provider
-                    .estimate_gas(&tx)
-                    .await
-                    .map(U256::from)
-                    .map_err(|e| anyhow!("Failed to estimate gas for RBTC transfer: {}", e))
+        let call = simulation::transfer_call(Some(from), to, amount, token_address);
+        self.provider
+            .estimate_gas(&call)
+            .await
+            .map(U256::from)
+            .map_err(|e| match token_address {
+                Some(_) => anyhow!("Failed to estimate gas for token transfer: {}", e),
+                None => anyhow!("Failed to estimate gas for RBTC transfer: {}", e),
+            })
+    }
+}
+
+/// Keep a submitted transaction in the signing log, audit log and pending
+/// store, and announce it to the configured webhooks. Submissions running
+/// at the same time (a parallel bulk transfer) record one at a time, so none
+/// of the store updates is lost.
+fn record_submission(
+    tx_hash: B256,
+    request: &alloy::rpc::types::TransactionRequest,
+    raw: Vec<u8>,
+    from: Address,
+) {
+    static RECORDING: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    let _recording = RECORDING.lock().unwrap_or_else(|e| e.into_inner());
+    if let Err(e) = signing_log::record(&SignedTx::new(tx_hash, request, raw)) {
+        log::warn!("Failed to record signed transaction: {}", e);
+    }
+    audit::record_or_warn(
+        "transaction_signed",
+        None,
+        format!("0x{:x} from 0x{:x}", tx_hash, from),
+    );
+    if let Some(pending) = PendingTx::from_request(tx_hash, request)
+        && let Err(e) = PendingStore::record(pending)
+    {
+        log::warn!("Failed to record pending transaction: {}", e);
+    }
+    submissions::submitted(tx_hash, request);
+}
+
+/// Send a signed raw transaction to all endpoints in parallel and return
+/// the hash from the first one that accepts it. The remaining submissions
+/// keep running in the background so slower endpoints still propagate it.
+pub async fn broadcast_raw_transaction(
+    endpoints: &[String],
+    raw: Vec<u8>,
+) -> Result<B256, anyhow::Error> {
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    for endpoint in endpoints {
+        let endpoint = endpoint.clone();
+        let raw = raw.clone();
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            let result = async {
+                let provider = http::provider(&endpoint)?;
+                let pending_tx = provider.send_raw_transaction(&raw).await?;
+                Ok::<_, anyhow::Error>(*pending_tx.tx_hash())
             }
+            .await;
+            let _ = sender.send((endpoint, result));
+        });
+    }
+    drop(sender);
+
+    let mut errors = Vec::new();
+    while let Some((endpoint, result)) = receiver.recv().await {
+        match result {
+            Ok(tx_hash) => return Ok(tx_hash),
+            Err(e) => errors.push(format!("{}: {}", endpoint_host(&endpoint), e)),
         }
     }
+    Err(anyhow!(
+        "No endpoint accepted the transaction ({})",
+        errors.join("; ")
+    ))
+}
+
+/// Explain a failed state lookup at a past block: nodes that prune old
+/// state reject these calls, so point the user at an archive endpoint
+fn historical_state_error(block: u64, error: &str) -> anyhow::Error {
+    let lower = error.to_lowercase();
+    let pruned = [
+        "missing trie node",
+        "header not found",
+        "pruned",
+        "archive",
+        "state",
+    ]
+    .iter()
+    .any(|pattern| lower.contains(pattern));
+    if pruned {
+        anyhow!(
+            "The RPC endpoint has no state for block {} ({}). Historical balances need an \
+             archive node; add an Alchemy API key under Configuration to use one.",
+            block,
+            error
+        )
+    } else {
+        anyhow!("Failed to get the balance at block {}: {}", block, error)
+    }
+}
+
+/// Host part of an endpoint URL, so API keys in the path are not printed
+pub fn endpoint_host(endpoint: &str) -> String {
+    url::Url::parse(endpoint)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "invalid endpoint".to_string())
 }
 
 /// Generate an explorer URL for a transaction hash
@@ -256,3 +895,18 @@ pub fn get_explorer_url(tx_hash: &str, is_testnet: bool) -> String {
         format!("https://explorer.rsk.co/tx/{}", tx_hash)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_historical_state_error() {
+        let pruned = historical_state_error(42, "missing trie node abc").to_string();
+        assert!(pruned.contains("no state for block 42"), "{}", pruned);
+        assert!(pruned.contains("archive node"), "{}", pruned);
+
+        let other = historical_state_error(42, "connection refused").to_string();
+        assert_eq!(other, "Failed to get the balance at block 42: connection refused");
+    }
+}
@@ -0,0 +1,271 @@
+//! Outgoing webhooks for wallet activity, e.g. to post to Slack or Discord
+//! or to feed internal monitoring.
+//!
+//! Each configured webhook receives a POST when a transfer is sent, when it
+//! confirms or fails, and when `watch incoming` sees a payment, unless it is
+//! limited to some of these events. The body is either the wallet's own JSON
+//! payload or a Slack or Discord message. With a secret, the body is signed
+//! with HMAC-SHA256 in the `X-Rootstock-Wallet-Signature` header, as Alchemy
+//! signs the deliveries the wallet receives. Deliveries run in the
+//! background so they never hold up a transfer, and are waited for before
+//! the wallet exits. A webhook that fails or does not answer within ten
+//! seconds is reported and skipped, not retried.
+
+use crate::config::ConfigManager;
+use crate::utils::eth::endpoint_host;
+use crate::utils::http;
+use anyhow::Result;
+use colored::Colorize;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// Header carrying the hex HMAC-SHA256 of the body
+pub const SIGNATURE_HEADER: &str = "X-Rootstock-Wallet-Signature";
+/// Longest wait for a webhook to answer
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum WalletEvent {
+    /// A transfer was broadcast
+    Sent,
+    /// A sent transfer was mined and succeeded
+    Confirmed,
+    /// A sent transfer was mined and reverted
+    Failed,
+    /// `watch incoming` saw a payment to a wallet
+    Received,
+}
+
+impl WalletEvent {
+    pub const ALL: [WalletEvent; 4] = [Self::Sent, Self::Confirmed, Self::Failed, Self::Received];
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Sent => "Transaction sent",
+            Self::Confirmed => "Transaction confirmed",
+            Self::Failed => "Transaction failed",
+            Self::Received => "Payment received",
+        }
+    }
+}
+
+impl fmt::Display for WalletEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Sent => "sent",
+            Self::Confirmed => "confirmed",
+            Self::Failed => "failed",
+            Self::Received => "received",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// The event with its details as JSON
+    #[default]
+    Json,
+    /// A Slack incoming-webhook message
+    Slack,
+    /// A Discord webhook message
+    Discord,
+}
+
+impl fmt::Display for WebhookFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Json => "json",
+            Self::Slack => "slack",
+            Self::Discord => "discord",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventWebhook {
+    pub url: String,
+    /// Events delivered; every event when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<WalletEvent>,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// Key the body is signed with, in the signature header
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+impl EventWebhook {
+    pub fn wants(&self, event: WalletEvent) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+
+    /// Events delivered, for display
+    pub fn describe_events(&self) -> String {
+        if self.events.is_empty() {
+            return "all events".to_string();
+        }
+        self.events
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Body of the POST for `event` on `network`. `summary` is one line for
+    /// people; `details` is the transfer or payment as in `--json` output.
+    pub fn body(&self, event: WalletEvent, network: &str, summary: &str, details: &Value) -> Value {
+        match self.format {
+            WebhookFormat::Json => json!({
+                "event": event,
+                "network": network,
+                "timestamp": chrono::Utc::now().to_rfc3339(),
+                "summary": summary,
+                "data": details,
+            }),
+            WebhookFormat::Slack => json!({
+                "text": format!("*{}* on {}: {}", event.title(), network, summary),
+            }),
+            WebhookFormat::Discord => json!({
+                "content": format!("**{}** on {}: {}", event.title(), network, summary),
+            }),
+        }
+    }
+
+    /// POST `event` to this webhook
+    pub async fn post(
+        &self,
+        event: WalletEvent,
+        network: &str,
+        summary: &str,
+        details: &Value,
+    ) -> Result<()> {
+        let body = serde_json::to_vec(&self.body(event, network, summary, details))?;
+        let mut request = http::client()?
+            .post(&self.url)
+            .timeout(DELIVERY_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        if let Some(secret) = &self.secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, &body));
+        }
+        request.body(body).send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Hex HMAC-SHA256 of `body` with `secret`
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Deliveries started by `deliver` that may still be running
+static DELIVERIES: Mutex<Vec<JoinHandle<()>>> = Mutex::new(Vec::new());
+
+/// Deliver `event` to every configured webhook that wants it, in the
+/// background. Failures are reported by host only, since webhook URLs often
+/// embed a token.
+pub fn deliver(event: WalletEvent, summary: &str, details: Value) {
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    let Ok(config) = ConfigManager::new().and_then(|manager| manager.load()) else {
+        return;
+    };
+    let network = config.default_network.to_string();
+    let mut deliveries = DELIVERIES.lock().unwrap_or_else(|e| e.into_inner());
+    deliveries.retain(|delivery| !delivery.is_finished());
+    for hook in config
+        .event_webhooks
+        .into_iter()
+        .filter(|hook| hook.wants(event))
+    {
+        let (network, summary, details) = (network.clone(), summary.to_string(), details.clone());
+        deliveries.push(runtime.spawn(async move {
+            if let Err(e) = hook.post(event, &network, &summary, &details).await {
+                eprintln!(
+                    "{}: Webhook to {} failed: {}",
+                    "Warning".yellow().bold(),
+                    endpoint_host(&hook.url),
+                    without_url(e)
+                );
+            }
+        }));
+    }
+}
+
+/// Wait for deliveries still running; each ends within the delivery timeout
+pub async fn flush() {
+    let deliveries = std::mem::take(&mut *DELIVERIES.lock().unwrap_or_else(|e| e.into_inner()));
+    for delivery in deliveries {
+        let _ = delivery.await;
+    }
+}
+
+/// `error` without the request URL, which may embed a token
+fn without_url(error: anyhow::Error) -> String {
+    match error.downcast::<reqwest::Error>() {
+        Ok(e) => e.without_url().to_string(),
+        Err(e) => e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_webhook_bodies() {
+        let hook: EventWebhook =
+            serde_json::from_str(r#"{"url": "https://example.com/hook"}"#).unwrap();
+        assert_eq!(hook.format, WebhookFormat::Json);
+        assert!(WalletEvent::ALL.iter().all(|event| hook.wants(*event)));
+
+        let details = json!({"tx_hash": "0x01", "status": "success"});
+        let body = hook.body(
+            WalletEvent::Confirmed,
+            "RSK Testnet",
+            "0.5 RBTC to 0x02",
+            &details,
+        );
+        assert_eq!(body["event"], "confirmed");
+        assert_eq!(body["network"], "RSK Testnet");
+        assert_eq!(body["data"], details);
+
+        let slack = EventWebhook {
+            events: vec![WalletEvent::Received, WalletEvent::Failed],
+            format: WebhookFormat::Slack,
+            ..hook
+        };
+        assert!(!slack.wants(WalletEvent::Sent));
+        assert_eq!(slack.describe_events(), "received, failed");
+        assert_eq!(
+            slack.body(
+                WalletEvent::Failed,
+                "RSK Mainnet",
+                "1 DOC to 0x03",
+                &details
+            ),
+            json!({"text": "*Transaction failed* on RSK Mainnet: 1 DOC to 0x03"})
+        );
+
+        // Receivers check the signature as the Alchemy listener does
+        let signature = sign("whsec_test", b"{}");
+        assert!(crate::commands::webhook::verify_signature(
+            "whsec_test",
+            b"{}",
+            Some(&signature)
+        ));
+    }
+}
@@ -0,0 +1,187 @@
+//! Decoding of contract event logs against an ABI.
+//!
+//! Used by the event watcher, which polls the chain for logs of the chosen
+//! events and prints (and optionally forwards) each decoded occurrence.
+
+use alloy::dyn_abi::{DynSolValue, EventExt};
+use alloy::json_abi::{Event, JsonAbi};
+use alloy::primitives::{Address, B256, hex};
+use alloy::rpc::types::Log;
+use anyhow::{Context, Result, anyhow};
+use serde_json::{Value, json};
+use std::fs;
+use std::path::Path;
+
+/// ABI of the standard ERC-20 token interface, shipped with the wallet
+pub const ERC20_ABI: &str = include_str!("../../res/erc20.abi.json");
+
+/// Parse an ABI from JSON, accepting either a bare ABI array or a build
+/// artifact with an `abi` field
+pub fn parse_abi(content: &str) -> Result<JsonAbi> {
+    if let Ok(abi) = serde_json::from_str::<JsonAbi>(content) {
+        return Ok(abi);
+    }
+    let artifact: Value = serde_json::from_str(content).context("Failed to parse ABI JSON")?;
+    let abi = artifact
+        .get("abi")
+        .ok_or_else(|| anyhow!("The file is neither an ABI nor a build artifact with an ABI"))?;
+    serde_json::from_value(abi.clone()).context("Failed to parse ABI")
+}
+
+/// Load an ABI from `erc20` (the bundled token ABI) or a JSON file
+pub fn load_abi(source: &str) -> Result<JsonAbi> {
+    if source.eq_ignore_ascii_case("erc20") {
+        return parse_abi(ERC20_ABI);
+    }
+    let content = fs::read_to_string(Path::new(source))
+        .with_context(|| format!("Failed to read ABI file {}", source))?;
+    parse_abi(&content)
+}
+
+/// Pick events from an ABI by name or full signature; all events when
+/// `names` is empty
+pub fn select_events(abi: &JsonAbi, names: &[String]) -> Result<Vec<Event>> {
+    let events: Vec<Event> = abi.events().cloned().collect();
+    if events.is_empty() {
+        return Err(anyhow!("The ABI declares no events"));
+    }
+    if names.is_empty() {
+        return Ok(events);
+    }
+    names
+        .iter()
+        .map(|name| {
+            events
+                .iter()
+                .find(|e| &e.name == name || &e.signature() == name)
+                .cloned()
+                .ok_or_else(|| anyhow!("The ABI has no event '{}'", name))
+        })
+        .collect()
+}
+
+/// An event log decoded against its ABI
+#[derive(Debug, Clone)]
+pub struct DecodedLog {
+    pub contract: Address,
+    pub event: String,
+    pub block: Option<u64>,
+    pub tx_hash: Option<B256>,
+    pub log_index: Option<u64>,
+    /// Parameter names and formatted values, in declaration order
+    pub args: Vec<(String, String)>,
+}
+
+impl DecodedLog {
+    /// Decode a log as whichever of `events` matches its first topic
+    pub fn decode(events: &[Event], log: &Log) -> Option<Self> {
+        let topic = log.topic0()?;
+        let event = events.iter().find(|e| e.selector() == *topic)?;
+        let decoded = event.decode_log(log.data(), true).ok()?;
+
+        let mut indexed = decoded.indexed.iter();
+        let mut body = decoded.body.iter();
+        let args = event
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, param)| {
+                let value = if param.indexed {
+                    indexed.next()
+                } else {
+                    body.next()
+                };
+                let name = if param.name.is_empty() {
+                    format!("arg{}", i)
+                } else {
+                    param.name.clone()
+                };
+                (name, value.map(format_value).unwrap_or_default())
+            })
+            .collect();
+
+        Some(Self {
+            contract: log.address(),
+            event: event.name.clone(),
+            block: log.block_number,
+            tx_hash: log.transaction_hash,
+            log_index: log.log_index,
+            args,
+        })
+    }
+
+    /// Payload forwarded to webhooks
+    pub fn to_json(&self) -> Value {
+        let args: serde_json::Map<String, Value> = self
+            .args
+            .iter()
+            .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+            .collect();
+        json!({
+            "contract": format!("{:#x}", self.contract),
+            "event": self.event,
+            "block": self.block,
+            "tx_hash": self.tx_hash.map(|h| format!("{:#x}", h)),
+            "log_index": self.log_index,
+            "args": args,
+        })
+    }
+}
+
+/// Human-readable form of a decoded ABI value
+pub fn format_value(value: &DynSolValue) -> String {
+    match value {
+        DynSolValue::Address(address) => format!("{:#x}", address),
+        DynSolValue::Bool(b) => b.to_string(),
+        DynSolValue::Uint(n, _) => n.to_string(),
+        DynSolValue::Int(n, _) => n.to_string(),
+        DynSolValue::FixedBytes(bytes, size) => hex::encode_prefixed(&bytes[..*size]),
+        DynSolValue::Bytes(bytes) => hex::encode_prefixed(bytes),
+        DynSolValue::String(s) => s.clone(),
+        DynSolValue::Array(values)
+        | DynSolValue::FixedArray(values)
+        | DynSolValue::Tuple(values) => format!(
+            "[{}]",
+            values
+                .iter()
+                .map(format_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        other => format!("{:?}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{LogData, U256};
+
+    #[test]
+    fn test_decode_erc20_transfer() {
+        let abi = load_abi("erc20").unwrap();
+        let events = select_events(&abi, &["Transfer".to_string()]).unwrap();
+        assert!(select_events(&abi, &["Mint".to_string()]).is_err());
+
+        let from = Address::repeat_byte(1);
+        let to = Address::repeat_byte(2);
+        let data = LogData::new_unchecked(
+            vec![events[0].selector(), from.into_word(), to.into_word()],
+            U256::from(1500).to_be_bytes_vec().into(),
+        );
+        let log = Log {
+            inner: alloy::primitives::Log {
+                address: Address::repeat_byte(9),
+                data,
+            },
+            block_number: Some(42),
+            ..Default::default()
+        };
+
+        let decoded = DecodedLog::decode(&events, &log).unwrap();
+        assert_eq!(decoded.event, "Transfer");
+        assert_eq!(decoded.args[0].1, format!("{:#x}", from));
+        assert_eq!(decoded.args[2].1, "1500");
+        assert_eq!(decoded.to_json()["block"], 42);
+    }
+}
@@ -0,0 +1,126 @@
+//! Gas price suggestions.
+//!
+//! Where the endpoint supports `eth_feeHistory`, the suggestion is the next
+//! block's base fee plus the median priority fee paid in recent blocks, which
+//! follows demand more closely than the single `eth_gasPrice` value. Rootstock
+//! nodes do not implement the method, so there the suggestion falls back to
+//! `eth_gasPrice`. The transfer preview offers slow, standard and fast
+//! prices from [`crate::utils::gas_oracle`] instead.
+
+use crate::utils::http::RpcProvider;
+use alloy::eips::BlockNumberOrTag;
+use alloy::providers::Provider;
+use alloy::rpc::types::FeeHistory;
+use anyhow::{Result, anyhow};
+use std::fmt;
+
+/// Recent blocks sampled for priority fees
+pub const FEE_HISTORY_BLOCKS: u64 = 20;
+/// Percentile of the priority fees paid in each sampled block
+pub const REWARD_PERCENTILE: f64 = 50.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeSource {
+    FeeHistory,
+    /// Gas prices paid in recent blocks, see [`crate::utils::gas_oracle`]
+    Blocks,
+    GasPrice,
+}
+
+impl fmt::Display for FeeSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeeSource::FeeHistory => write!(f, "fee history"),
+            FeeSource::Blocks => write!(f, "recent blocks"),
+            FeeSource::GasPrice => write!(f, "eth_gasPrice"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeSuggestion {
+    /// Legacy gas price in wei
+    pub gas_price: u128,
+    pub source: FeeSource,
+}
+
+/// Suggest a gas price from the fee history, or from `eth_gasPrice` when the
+/// endpoint does not support it or has no usable history
+pub async fn suggest_gas_price(provider: &RpcProvider) -> Result<FeeSuggestion> {
+    match provider
+        .get_fee_history(
+            FEE_HISTORY_BLOCKS,
+            BlockNumberOrTag::Latest,
+            &[REWARD_PERCENTILE],
+        )
+        .await
+    {
+        Ok(history) => {
+            if let Some(gas_price) = gas_price_from_history(&history) {
+                return Ok(FeeSuggestion {
+                    gas_price,
+                    source: FeeSource::FeeHistory,
+                });
+            }
+            log::debug!("Fee history has no usable fees, falling back to eth_gasPrice");
+        }
+        Err(e) => log::debug!("eth_feeHistory unavailable, falling back to eth_gasPrice: {}", e),
+    }
+
+    let gas_price = provider
+        .get_gas_price()
+        .await
+        .map_err(|e| anyhow!("Failed to get gas price: {}", e))?;
+    Ok(FeeSuggestion {
+        gas_price,
+        source: FeeSource::GasPrice,
+    })
+}
+
+/// Next block's base fee plus the median of the sampled priority fees.
+/// Empty blocks report a zero reward and are left out of the median.
+pub fn gas_price_from_history(history: &FeeHistory) -> Option<u128> {
+    let base_fee = history.next_block_base_fee().unwrap_or_default();
+    let mut rewards: Vec<u128> = history
+        .reward
+        .as_ref()?
+        .iter()
+        .filter_map(|block| block.first().copied())
+        .filter(|reward| *reward > 0)
+        .collect();
+    rewards.sort_unstable();
+    let tip = rewards.get(rewards.len() / 2).copied().unwrap_or_default();
+    let gas_price = base_fee.checked_add(tip)?;
+    (gas_price > 0).then_some(gas_price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gas_price_from_history() {
+        let history = FeeHistory {
+            base_fee_per_gas: vec![10, 12, 14],
+            reward: Some(vec![vec![3], vec![0], vec![1], vec![2]]),
+            ..Default::default()
+        };
+        // Base fee of the next block plus the median non-zero reward
+        assert_eq!(gas_price_from_history(&history), Some(16));
+
+        // Nodes without EIP-1559 report no base fee: use the fees paid
+        let legacy = FeeHistory {
+            reward: Some(vec![vec![60_000_000], vec![65_000_000]]),
+            ..Default::default()
+        };
+        assert_eq!(gas_price_from_history(&legacy), Some(65_000_000));
+
+        // No rewards requested or nothing paid: fall back to eth_gasPrice
+        assert_eq!(gas_price_from_history(&FeeHistory::default()), None);
+        let idle = FeeHistory {
+            reward: Some(vec![vec![0], vec![0]]),
+            ..Default::default()
+        };
+        assert_eq!(gas_price_from_history(&idle), None);
+    }
+}
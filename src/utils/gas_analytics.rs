@@ -0,0 +1,101 @@
+use alloy::primitives::{B256, U256};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Fee information recorded for a confirmed transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeRecord {
+    pub tx_hash: B256,
+    pub network: String,
+    pub timestamp: DateTime<Utc>,
+    pub gas_used: U256,
+    pub effective_gas_price: U256,
+    pub fee: U256,
+    /// Fee estimated before sending, if an estimate was available
+    pub estimated_fee: Option<U256>,
+}
+
+/// Persistent store of fees paid, used for gas analytics
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GasAnalytics {
+    #[serde(default)]
+    pub records: Vec<FeeRecord>,
+}
+
+impl GasAnalytics {
+    pub fn path() -> Result<PathBuf> {
+        let dir = dirs::data_local_dir()
+            .context("Failed to get data directory")?
+            .join("rootstock-wallet");
+        fs::create_dir_all(&dir)?;
+        Ok(dir.join("gas_analytics.json"))
+    }
+
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path).context("Failed to read gas analytics")?;
+        serde_json::from_str(&content).context("Failed to parse gas analytics")
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(Self::path()?, content).context("Failed to write gas analytics")
+    }
+
+    /// Append a record and persist the store
+    pub fn record(record: FeeRecord) -> Result<()> {
+        let mut store = Self::load().unwrap_or_default();
+        store.records.retain(|r| r.tx_hash != record.tx_hash);
+        store.records.push(record);
+        store.save()
+    }
+
+    pub fn total_fees(&self, network: &str) -> U256 {
+        self.records
+            .iter()
+            .filter(|r| r.network == network)
+            .fold(U256::ZERO, |acc, r| acc.saturating_add(r.fee))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(byte: u8, network: &str, fee: U256) -> FeeRecord {
+        FeeRecord {
+            tx_hash: B256::repeat_byte(byte),
+            network: network.to_string(),
+            timestamp: Utc::now(),
+            gas_used: U256::from(21_000),
+            effective_gas_price: fee / U256::from(21_000),
+            fee,
+            estimated_fee: None,
+        }
+    }
+
+    #[test]
+    fn test_total_fees() {
+        let analytics = GasAnalytics {
+            records: vec![
+                record(1, "testnet", U256::from(21_000_000)),
+                record(2, "testnet", U256::from(42_000_000)),
+                record(3, "mainnet", U256::from(5)),
+            ],
+        };
+        assert_eq!(analytics.total_fees("testnet"), U256::from(63_000_000));
+        assert_eq!(analytics.total_fees("mainnet"), U256::from(5));
+        assert_eq!(analytics.total_fees("regtest"), U256::ZERO);
+
+        let overflowing = GasAnalytics {
+            records: vec![record(1, "testnet", U256::MAX), record(2, "testnet", U256::MAX)],
+        };
+        assert_eq!(overflowing.total_fees("testnet"), U256::MAX);
+    }
+}
@@ -0,0 +1,373 @@
+//! Slow, standard and fast gas prices with the expected wait for each.
+//!
+//! Prices come from `eth_feeHistory` where the endpoint supports it, and
+//! otherwise from the gas prices paid in the last [`SAMPLE_BLOCKS`] blocks,
+//! which is what Rootstock nodes need. No tier goes below `eth_gasPrice`,
+//! since Rootstock nodes reject transactions under the minimum gas price.
+//!
+//! The wait of a tier is estimated from how many of the sampled blocks it
+//! would have made it into: a price that beat the cheapest transaction of
+//! every other block is expected to wait two blocks. Blocks with no
+//! transactions count as blocks any price would have made.
+
+use crate::utils::http::RpcProvider;
+use crate::utils::fees::{FEE_HISTORY_BLOCKS, FeeSource};
+use alloy::consensus::Transaction as _;
+use alloy::eips::BlockNumberOrTag;
+use alloy::providers::Provider;
+use alloy::rpc::types::{BlockTransactionsKind, FeeHistory};
+use anyhow::{Result, anyhow};
+use std::fmt;
+use std::time::Duration;
+
+/// Recent blocks whose transactions are sampled when fee history is
+/// unavailable
+pub const SAMPLE_BLOCKS: u64 = 10;
+/// Block time assumed when the sampled blocks do not tell
+const DEFAULT_BLOCK_TIME: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasTier {
+    Slow,
+    Standard,
+    Fast,
+}
+
+impl GasTier {
+    pub const ALL: [GasTier; 3] = [GasTier::Slow, GasTier::Standard, GasTier::Fast];
+
+    /// Percentile of the sampled gas prices the tier pays
+    fn percentile(self) -> f64 {
+        match self {
+            GasTier::Slow => 10.0,
+            GasTier::Standard => 50.0,
+            GasTier::Fast => 90.0,
+        }
+    }
+}
+
+impl fmt::Display for GasTier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GasTier::Slow => write!(f, "Slow"),
+            GasTier::Standard => write!(f, "Standard"),
+            GasTier::Fast => write!(f, "Fast"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TierPrice {
+    pub tier: GasTier,
+    /// Legacy gas price in wei
+    pub gas_price: u128,
+    /// Blocks until the transaction is expected to be mined; `None` when the
+    /// price would not have made any sampled block
+    pub wait_blocks: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GasOracle {
+    /// Slow, standard and fast prices, in that order
+    pub tiers: Vec<TierPrice>,
+    pub source: FeeSource,
+    /// Average time between the sampled blocks
+    pub block_time: Duration,
+}
+
+impl GasOracle {
+    pub fn tier(&self, tier: GasTier) -> &TierPrice {
+        self.tiers
+            .iter()
+            .find(|price| price.tier == tier)
+            .unwrap_or(&self.tiers[0])
+    }
+
+    /// Expected time until a transaction paying `price` is mined, e.g.
+    /// `~1 min`
+    pub fn wait(&self, price: &TierPrice) -> String {
+        match price.wait_blocks {
+            Some(blocks) => format_wait(self.block_time * blocks as u32),
+            None => "unknown".to_string(),
+        }
+    }
+}
+
+/// Gas prices of the three tiers for the next block
+pub async fn gas_oracle(provider: &RpcProvider) -> Result<GasOracle> {
+    let minimum = provider
+        .get_gas_price()
+        .await
+        .map_err(|e| anyhow!("Failed to get gas price: {}", e))?;
+
+    let percentiles = GasTier::ALL.map(GasTier::percentile);
+    match provider
+        .get_fee_history(FEE_HISTORY_BLOCKS, BlockNumberOrTag::Latest, &percentiles)
+        .await
+    {
+        Ok(history) => {
+            if let Some(samples) = samples_from_history(&history) {
+                let block_time = block_time(provider, FEE_HISTORY_BLOCKS).await;
+                return Ok(oracle(&samples, minimum, FeeSource::FeeHistory, block_time));
+            }
+        }
+        Err(e) => log::debug!("eth_feeHistory unavailable, sampling blocks: {}", e),
+    }
+
+    match sample_blocks(provider).await {
+        Ok((samples, block_time)) => Ok(oracle(&samples, minimum, FeeSource::Blocks, block_time)),
+        Err(e) => {
+            log::debug!("Block sampling failed, using eth_gasPrice: {}", e);
+            Ok(oracle(
+                &[],
+                minimum,
+                FeeSource::GasPrice,
+                DEFAULT_BLOCK_TIME,
+            ))
+        }
+    }
+}
+
+/// Gas prices paid in one block
+#[derive(Debug, Clone, Default, PartialEq)]
+struct BlockSample {
+    /// Prices of the tiers' percentiles, in tier order; empty when the block
+    /// had no transactions
+    percentiles: Vec<u128>,
+    /// Cheapest price the block included
+    cheapest: Option<u128>,
+}
+
+/// Per-block prices from fee history: the block's base fee plus the reward
+/// paid at each percentile. The cheapest price is that of the slow tier.
+fn samples_from_history(history: &FeeHistory) -> Option<Vec<BlockSample>> {
+    let rewards = history.reward.as_ref()?;
+    let samples: Vec<BlockSample> = rewards
+        .iter()
+        .enumerate()
+        .map(|(block, rewards)| {
+            let base_fee = history
+                .base_fee_per_gas
+                .get(block)
+                .copied()
+                .unwrap_or_default();
+            let percentiles: Vec<u128> = rewards
+                .iter()
+                .map(|reward| base_fee.saturating_add(*reward))
+                .collect();
+            // Nodes without EIP-1559 report zero rewards for empty blocks
+            if percentiles.iter().all(|price| *price == 0) {
+                return BlockSample::default();
+            }
+            BlockSample {
+                cheapest: percentiles.first().copied(),
+                percentiles,
+            }
+        })
+        .collect();
+    samples
+        .iter()
+        .any(|sample| sample.cheapest.is_some())
+        .then_some(samples)
+}
+
+/// Prices paid in the last [`SAMPLE_BLOCKS`] blocks, and the average time
+/// between them
+async fn sample_blocks(
+    provider: &RpcProvider,
+) -> Result<(Vec<BlockSample>, Duration)> {
+    let head = provider
+        .get_block_number()
+        .await
+        .map_err(|e| anyhow!("Failed to get block number: {}", e))?;
+    let mut samples = Vec::new();
+    let mut timestamps = Vec::new();
+    for number in head.saturating_sub(SAMPLE_BLOCKS - 1)..=head {
+        let block = provider
+            .get_block_by_number(
+                BlockNumberOrTag::Number(number),
+                BlockTransactionsKind::Full,
+            )
+            .await
+            .map_err(|e| anyhow!("Failed to get block {}: {}", number, e))?
+            .ok_or_else(|| anyhow!("Block {} not found", number))?;
+        timestamps.push(block.header.timestamp);
+        let mut prices: Vec<u128> = block
+            .transactions
+            .txns()
+            .map(|tx| tx.gas_price().unwrap_or_else(|| tx.max_fee_per_gas()))
+            .filter(|price| *price > 0)
+            .collect();
+        prices.sort_unstable();
+        samples.push(BlockSample {
+            percentiles: GasTier::ALL
+                .iter()
+                .filter_map(|tier| percentile(&prices, tier.percentile()))
+                .collect(),
+            cheapest: prices.first().copied(),
+        });
+    }
+    Ok((samples, average_block_time(&timestamps)))
+}
+
+/// Average time between the last `blocks` blocks
+async fn block_time(provider: &RpcProvider, blocks: u64) -> Duration {
+    let timestamp = |number| async move {
+        provider
+            .get_block_by_number(
+                BlockNumberOrTag::Number(number),
+                BlockTransactionsKind::Hashes,
+            )
+            .await
+            .ok()
+            .flatten()
+            .map(|block| block.header.timestamp)
+    };
+    let Ok(head) = provider.get_block_number().await else {
+        return DEFAULT_BLOCK_TIME;
+    };
+    let first = head.saturating_sub(blocks);
+    match (timestamp(first).await, timestamp(head).await) {
+        (Some(start), Some(end)) if end > start => {
+            Duration::from_secs_f64((end - start) as f64 / (head - first) as f64)
+        }
+        _ => DEFAULT_BLOCK_TIME,
+    }
+}
+
+/// Average spacing of `timestamps`, taken as consecutive blocks
+fn average_block_time(timestamps: &[u64]) -> Duration {
+    match (timestamps.first(), timestamps.last()) {
+        (Some(first), Some(last)) if last > first => {
+            Duration::from_secs_f64((last - first) as f64 / (timestamps.len() - 1) as f64)
+        }
+        _ => DEFAULT_BLOCK_TIME,
+    }
+}
+
+/// Tier prices from the samples: the median over the blocks of each tier's
+/// percentile, never below `minimum`
+fn oracle(
+    samples: &[BlockSample],
+    minimum: u128,
+    source: FeeSource,
+    block_time: Duration,
+) -> GasOracle {
+    let mut tiers: Vec<TierPrice> = Vec::new();
+    for (index, tier) in GasTier::ALL.into_iter().enumerate() {
+        let mut prices: Vec<u128> = samples
+            .iter()
+            .filter_map(|sample| sample.percentiles.get(index).copied())
+            .collect();
+        prices.sort_unstable();
+        let sampled = percentile(&prices, 50.0).unwrap_or(minimum);
+        // A faster tier never pays less than a slower one
+        let floor = tiers.last().map_or(minimum, |slower| slower.gas_price);
+        let gas_price = sampled.max(minimum).max(floor);
+        tiers.push(TierPrice {
+            tier,
+            gas_price,
+            wait_blocks: wait_blocks(samples, gas_price),
+        });
+    }
+    GasOracle {
+        tiers,
+        source,
+        block_time,
+    }
+}
+
+/// Blocks a transaction paying `gas_price` is expected to wait, from the
+/// share of sampled blocks it would have made
+fn wait_blocks(samples: &[BlockSample], gas_price: u128) -> Option<u64> {
+    if samples.is_empty() {
+        return Some(1);
+    }
+    let included = samples
+        .iter()
+        .filter(|sample| sample.cheapest.is_none_or(|cheapest| gas_price >= cheapest))
+        .count();
+    (included > 0).then(|| (samples.len() as u64).div_ceil(included as u64).max(1))
+}
+
+/// Value at `percentile` of the sorted `values`
+fn percentile(values: &[u128], percentile: f64) -> Option<u128> {
+    if values.is_empty() {
+        return None;
+    }
+    let index = ((values.len() - 1) as f64 * percentile / 100.0).round() as usize;
+    values.get(index).copied()
+}
+
+fn format_wait(wait: Duration) -> String {
+    let secs = wait.as_secs();
+    if secs < 90 {
+        format!("~{} s", secs.max(1))
+    } else {
+        format!("~{} min", secs.div_ceil(60))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(prices: &[u128]) -> BlockSample {
+        let mut prices = prices.to_vec();
+        prices.sort_unstable();
+        BlockSample {
+            percentiles: GasTier::ALL
+                .iter()
+                .filter_map(|tier| percentile(&prices, tier.percentile()))
+                .collect(),
+            cheapest: prices.first().copied(),
+        }
+    }
+
+    #[test]
+    fn test_oracle_tiers() {
+        let samples = vec![
+            block(&[60, 60, 65, 70, 100]),
+            block(&[65, 70, 80]),
+            block(&[]),
+            block(&[70, 90]),
+        ];
+        let oracle = oracle(&samples, 60, FeeSource::Blocks, Duration::from_secs(30));
+        let prices: Vec<u128> = oracle.tiers.iter().map(|t| t.gas_price).collect();
+        assert_eq!(prices, vec![65, 70, 90]);
+        // Slow would have made the empty block and two of the three others
+        assert_eq!(oracle.tier(GasTier::Slow).wait_blocks, Some(2));
+        assert_eq!(oracle.tier(GasTier::Fast).wait_blocks, Some(1));
+        assert_eq!(oracle.wait(oracle.tier(GasTier::Slow)), "~60 s");
+
+        // Without samples every tier pays the node's minimum
+        let quiet = super::oracle(&[], 60, FeeSource::GasPrice, DEFAULT_BLOCK_TIME);
+        assert!(quiet.tiers.iter().all(|t| t.gas_price == 60));
+        assert_eq!(quiet.tier(GasTier::Fast).wait_blocks, Some(1));
+
+        assert_eq!(format_wait(Duration::from_secs(300)), "~5 min");
+        assert_eq!(
+            average_block_time(&[100, 130, 160, 190]),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    fn test_samples_from_history() {
+        let history = FeeHistory {
+            base_fee_per_gas: vec![10, 12, 14],
+            reward: Some(vec![vec![1, 2, 5], vec![0, 0, 0]]),
+            ..Default::default()
+        };
+        let samples = samples_from_history(&history).unwrap();
+        assert_eq!(samples[0].percentiles, vec![11, 12, 15]);
+        assert_eq!(samples[0].cheapest, Some(11));
+        assert_eq!(samples[1].cheapest, Some(12));
+
+        let idle = FeeHistory {
+            reward: Some(vec![vec![0, 0, 0]]),
+            ..Default::default()
+        };
+        assert!(samples_from_history(&idle).is_none());
+    }
+}
@@ -1,6 +1,9 @@
 use crate::config::ConfigManager;
+use crate::security::{secrets, session};
 use crate::types::network::{Network, NetworkConfig};
+use crate::types::wallet::Wallet;
 use crate::utils::eth::EthClient;
+use crate::utils::redact;
 use anyhow::Result;
 use colored::Colorize;
 use alloy::primitives::Address;
@@ -25,6 +28,7 @@ impl Default for Config {
                 name: "RSK Mainnet".to_string(),
                 rpc_url: "https://public-node.rsk.co".to_string(),
                 explorer_url: "https://explorer.rsk.co".to_string(),
+                fallback_rpc_urls: Vec::new(),
             },
             wallet: WalletConfig {
                 current_wallet_address: None,
@@ -35,11 +39,65 @@ impl Default for Config {
     }
 }
 
+/// Environment variable that supplies the wallet password to scripted runs
+pub const PASSWORD_ENV: &str = "ROOTSTOCK_WALLET_PASSWORD";
+
+/// Read the wallet password from `ROOTSTOCK_WALLET_PASSWORD`, or prompt for it
+pub fn read_wallet_password(prompt: &str) -> Result<String> {
+    match std::env::var(PASSWORD_ENV) {
+        Ok(password) if !password.is_empty() => Ok(password),
+        _ => Ok(rpassword::prompt_password(prompt)?),
+    }
+}
+
+/// Decrypt the key of account `account` of a wallet.
+///
+/// A wallet with a live unlock session is decrypted with the session keys.
+/// Otherwise the password is taken from `ROOTSTOCK_WALLET_PASSWORD`, the
+/// keyring when it is enabled, or a prompt, and a new session is started.
+/// A prompted password that unlocks the wallet is remembered in the keyring;
+/// a keyring entry that no longer works is dropped and the user is prompted.
+pub fn unlock_wallet(wallet: &Wallet, account: u32, prompt: &str) -> Result<String> {
+    let address = &wallet.address;
+    match session::with_keys(address, |keys| wallet.decrypt_with_keys(keys, account)) {
+        Some(Ok(private_key)) => return Ok(private_key),
+        Some(Err(_)) => session::lock(address),
+        None => {}
+    }
+
+    let unlock = |password: &str| -> Result<String> {
+        let keys = wallet.derive_keys(password)?;
+        let private_key = wallet.decrypt_with_keys(&keys, account)?;
+        session::start(*address, keys);
+        Ok(private_key)
+    };
+    if std::env::var(PASSWORD_ENV).is_ok_and(|password| !password.is_empty()) {
+        return unlock(&read_wallet_password(prompt)?);
+    }
+    if let Some(password) = secrets::wallet_password(address)? {
+        match unlock(&password) {
+            Ok(unlocked) => return Ok(unlocked),
+            Err(_) => {
+                eprintln!(
+                    "{}: The password in the keyring no longer unlocks this wallet",
+                    "Warning".yellow().bold()
+                );
+                secrets::forget_wallet_password(address)?;
+            }
+        }
+    }
+    let password = rpassword::prompt_password(prompt)?;
+    let unlocked = unlock(&password)?;
+    secrets::remember_wallet_password(address, &password)?;
+    Ok(unlocked)
+}
+
 pub struct Helper;
 
 impl Helper {
     pub async fn init_eth_client(network: &str) -> Result<(Config, EthClient)> {
-        let network_enum = Network::from_str(network).unwrap_or(Network::Mainnet);
+        let network_enum = Network::from_str(network)
+            .ok_or_else(|| anyhow::anyhow!("Unknown network '{}'", network))?;
 
         // Load configuration to get API keys
         let config_manager = ConfigManager::new()?;
@@ -56,8 +114,10 @@ impl Helper {
         let mut net_cfg = network_enum.get_config();
         net_cfg.rpc_url = rpc_url.clone();
 
-        let mut config = Config::default();
-        config.network = net_cfg.clone();
+        let config = Config {
+            network: net_cfg.clone(),
+            ..Default::default()
+        };
 
         // Log which RPC endpoint is being used
         let rpc_type = if rsk_api_key.is_some() {
@@ -88,6 +148,9 @@ impl Helper {
     }
 
     pub fn format_address(address: &Address) -> String {
+        if redact::is_active() {
+            return redact::address(address).green().to_string();
+        }
         format!("{}{}", "0x".green(), address.to_string()[2..].green())
     }
 
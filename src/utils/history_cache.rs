@@ -0,0 +1,153 @@
+use crate::types::transaction::RskTransaction;
+use crate::utils::storage::Storage;
+use alloy::primitives::Address;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Local cache of transactions fetched from the history providers, keyed by
+/// network and the lowercase hex address of the wallet they were fetched for
+/// (`mainnet/0x…`). Entries cached before the network was recorded are
+/// dropped when the database is opened and fetched again.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HistoryCache {
+    #[serde(default)]
+    pub transactions: HashMap<String, Vec<RskTransaction>>,
+    /// Last block whose transfers are all in the cache, by the same keys, so
+    /// later fetches only ask for newer blocks
+    #[serde(default)]
+    pub synced_blocks: HashMap<String, u64>,
+    /// Last block the full log scan (`sync --full`) has covered from block
+    /// zero, by the same keys, so an interrupted scan resumes after it
+    #[serde(default)]
+    pub scanned_blocks: HashMap<String, u64>,
+}
+
+/// Cache key for `address` on `network` ("mainnet", "testnet", ...)
+pub fn cache_key(network: &str, address: &Address) -> String {
+    format!("{}/{:#x}", network, address)
+}
+
+impl HistoryCache {
+    pub fn load() -> Result<Self> {
+        Storage::open()?.load_history()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        Storage::open()?.save_history(self)
+    }
+
+    /// Merge freshly fetched transactions into the cache, replacing any
+    /// existing entries with the same hash.
+    pub fn merge(&mut self, network: &str, address: &Address, txs: &[RskTransaction]) {
+        let entry = self
+            .transactions
+            .entry(cache_key(network, address))
+            .or_default();
+        for tx in txs {
+            if let Some(existing) = entry.iter_mut().find(|t| t.hash == tx.hash) {
+                *existing = tx.clone();
+            } else {
+                entry.push(tx.clone());
+            }
+        }
+    }
+
+    pub fn get(&self, network: &str, address: &Address) -> Vec<RskTransaction> {
+        self.transactions
+            .get(&cache_key(network, address))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn synced_block(&self, network: &str, address: &Address) -> Option<u64> {
+        self.synced_blocks
+            .get(&cache_key(network, address))
+            .copied()
+    }
+
+    pub fn set_synced_block(&mut self, network: &str, address: &Address, block: u64) {
+        self.synced_blocks
+            .insert(cache_key(network, address), block);
+    }
+
+    /// Forget the sync progress so the next fetch starts from the first block
+    pub fn reset_sync(&mut self, network: &str, address: &Address) {
+        self.synced_blocks.remove(&cache_key(network, address));
+    }
+
+    pub fn scanned_block(&self, network: &str, address: &Address) -> Option<u64> {
+        self.scanned_blocks
+            .get(&cache_key(network, address))
+            .copied()
+    }
+
+    pub fn set_scanned_block(&mut self, network: &str, address: &Address, block: u64) {
+        self.scanned_blocks
+            .insert(cache_key(network, address), block);
+    }
+
+    /// Forget the full scan progress so the next one starts from block zero
+    pub fn reset_scan(&mut self, network: &str, address: &Address) {
+        self.scanned_blocks.remove(&cache_key(network, address));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::transaction::TransactionStatus;
+    use alloy::primitives::{B256, U256};
+    use std::time::SystemTime;
+
+    fn transaction(hash: u8, from: Address) -> RskTransaction {
+        RskTransaction {
+            hash: B256::repeat_byte(hash),
+            from,
+            to: Some(Address::repeat_byte(9)),
+            value: U256::from(1),
+            gas_price: U256::ZERO,
+            gas: U256::ZERO,
+            nonce: U256::ZERO,
+            input: None,
+            block_number: None,
+            transaction_index: None,
+            timestamp: SystemTime::now(),
+            status: TransactionStatus::Success,
+            token_address: None,
+            confirms: None,
+            cumulative_gas_used: None,
+            gas_used: None,
+            logs: None,
+        }
+    }
+
+    #[test]
+    fn test_cache_is_kept_per_network() {
+        let wallet = Address::repeat_byte(1);
+        let mut cache = HistoryCache::default();
+
+        cache.merge("testnet", &wallet, &[transaction(1, wallet), transaction(2, wallet)]);
+        assert_eq!(cache.get("testnet", &wallet).len(), 2);
+        assert!(cache.get("mainnet", &wallet).is_empty());
+
+        // Merging again replaces entries with the same hash
+        cache.merge("mainnet", &wallet, &[transaction(1, wallet)]);
+        cache.merge("mainnet", &wallet, &[transaction(1, wallet)]);
+        assert_eq!(cache.get("testnet", &wallet).len(), 2);
+        assert_eq!(cache.get("mainnet", &wallet).len(), 1);
+
+        cache.set_synced_block("mainnet", &wallet, 100);
+        assert_eq!(cache.synced_block("mainnet", &wallet), Some(100));
+        assert_eq!(cache.synced_block("testnet", &wallet), None);
+        cache.reset_sync("mainnet", &wallet);
+        assert_eq!(cache.synced_block("mainnet", &wallet), None);
+
+        // The full scan keeps its own progress
+        cache.set_scanned_block("testnet", &wallet, 5_000);
+        assert_eq!(cache.scanned_block("testnet", &wallet), Some(5_000));
+        assert_eq!(cache.synced_block("testnet", &wallet), None);
+        cache.reset_scan("testnet", &wallet);
+        assert_eq!(cache.scanned_block("testnet", &wallet), None);
+    }
+}
@@ -0,0 +1,230 @@
+//! Export of wallet history to CSV and JSON for accounting.
+//!
+//! Each transaction becomes one row with its token symbol, the amount
+//! adjusted by the token's decimals, the fee paid by the wallet and the
+//! status. Transfers of tokens missing from the registry keep their raw
+//! amount and no symbol. The fiat value uses the current USD price reported
+//! by Blockscout, so it is left empty on testnet, for unknown tokens and
+//! when no price is available.
+
+use crate::types::transaction::RskTransaction;
+use crate::utils::accounting::format_amount;
+use crate::utils::blockscout::BlockscoutClient;
+use alloy::primitives::Address;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "json" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportRow {
+    pub hash: String,
+    /// RFC 3339, in UTC
+    pub timestamp: String,
+    pub block_number: Option<u64>,
+    pub from: String,
+    pub to: Option<String>,
+    /// `in`, `out` or `self`, seen from the exported wallet
+    pub direction: String,
+    /// `RBTC` or the registered token symbol
+    pub token: Option<String>,
+    pub token_address: Option<String>,
+    pub amount: String,
+    /// Fee in RBTC, for transactions sent by the wallet
+    pub fee: Option<String>,
+    pub fiat_value_usd: Option<String>,
+    pub status: String,
+}
+
+/// One row per transaction. `tokens` maps token contracts to their symbol and
+/// decimals; `prices` maps assets (`None` for RBTC) to their USD price.
+pub fn rows(
+    txs: &[RskTransaction],
+    wallet: Address,
+    tokens: &HashMap<Address, (String, u8)>,
+    prices: &HashMap<Option<Address>, f64>,
+) -> Vec<ExportRow> {
+    txs.iter()
+        .map(|tx| {
+            let outgoing = tx.from == wallet;
+            let direction = match (outgoing, tx.to == Some(wallet)) {
+                (true, true) => "self",
+                (true, false) => "out",
+                _ => "in",
+            };
+            let token = match tx.token_address {
+                Some(address) => tokens.get(&address).cloned(),
+                None => Some(("RBTC".to_string(), 18)),
+            };
+            let amount = match &token {
+                Some((_, decimals)) => format_amount(tx.value, *decimals),
+                None => tx.value.to_string(),
+            };
+            let fiat_value_usd = token
+                .as_ref()
+                .and_then(|_| prices.get(&tx.token_address))
+                .and_then(|price| Some(amount.parse::<f64>().ok()? * price))
+                .map(|value| format!("{:.2}", value));
+            let fee = tx.gas_price.saturating_mul(tx.gas);
+            let timestamp: DateTime<Utc> = tx.timestamp.into();
+
+            ExportRow {
+                hash: format!("{:#x}", tx.hash),
+                timestamp: timestamp.to_rfc3339(),
+                block_number: tx.block_number.map(|n| n.to::<u64>()),
+                from: format!("{:#x}", tx.from),
+                to: tx.to.map(|a| format!("{:#x}", a)),
+                direction: direction.to_string(),
+                token: token.map(|(symbol, _)| symbol),
+                token_address: tx.token_address.map(|a| format!("{:#x}", a)),
+                amount,
+                fee: (outgoing && !fee.is_zero()).then(|| format_amount(fee, 18)),
+                fiat_value_usd,
+                status: tx.status.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Current USD prices of RBTC and the given tokens, skipping those Blockscout
+/// has no price for. Testnet assets have no value, so nothing is looked up.
+pub async fn fetch_prices(
+    is_testnet: bool,
+    tokens: impl IntoIterator<Item = Address>,
+) -> HashMap<Option<Address>, f64> {
+    let mut prices = HashMap::new();
+    if is_testnet {
+        return prices;
+    }
+    let client = match BlockscoutClient::new(false) {
+        Ok(client) => client,
+        Err(e) => {
+            log::warn!("Failed to fetch prices: {}", e);
+            return prices;
+        }
+    };
+    match client.get_coin_price().await {
+        Ok(Some(price)) => {
+            prices.insert(None, price);
+        }
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to fetch the RBTC price: {}", e),
+    }
+    for token in tokens {
+        match client.get_token_price(&token).await {
+            Ok(Some(price)) => {
+                prices.insert(Some(token), price);
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("Failed to fetch the price of token {:#x}: {}", token, e),
+        }
+    }
+    prices
+}
+
+/// Render the rows as CSV with a header line, or as a JSON array
+pub fn render(format: ExportFormat, rows: &[ExportRow]) -> Result<String> {
+    match format {
+        ExportFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(Vec::new());
+            for row in rows {
+                wtr.serialize(row)?;
+            }
+            Ok(String::from_utf8(wtr.into_inner()?)?)
+        }
+        ExportFormat::Json => Ok(serde_json::to_string_pretty(rows)?),
+    }
+}
+
+pub fn write(format: ExportFormat, rows: &[ExportRow], path: &str) -> Result<()> {
+    fs::write(path, render(format, rows)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::transaction::TransactionStatus;
+    use alloy::primitives::{B256, U64, U256};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn test_export_rows() {
+        let wallet = Address::repeat_byte(1);
+        let usdt = Address::repeat_byte(7);
+        let unknown = Address::repeat_byte(8);
+        let tx = |value: u64, token: Option<Address>, from: Address| RskTransaction {
+            hash: B256::repeat_byte(2),
+            from,
+            to: Some(Address::repeat_byte(9)),
+            value: U256::from(value),
+            gas_price: U256::from(60_000_000u64),
+            gas: U256::from(21_000u64),
+            nonce: U256::ZERO,
+            input: None,
+            block_number: Some(U64::from(100)),
+            transaction_index: None,
+            timestamp: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            status: TransactionStatus::Success,
+            token_address: token,
+            confirms: None,
+            cumulative_gas_used: None,
+            gas_used: None,
+            logs: None,
+        };
+        let tokens = HashMap::from([(usdt, ("USDT".to_string(), 6))]);
+        let prices = HashMap::from([(None, 50_000.0), (Some(usdt), 1.0)]);
+
+        let rows = rows(
+            &[
+                tx(500_000_000_000_000, None, wallet),
+                tx(2_500_000, Some(usdt), Address::repeat_byte(9)),
+                tx(42, Some(unknown), wallet),
+            ],
+            wallet,
+            &tokens,
+            &prices,
+        );
+
+        assert_eq!(rows[0].token.as_deref(), Some("RBTC"));
+        assert_eq!(rows[0].amount, "0.0005");
+        assert_eq!(rows[0].fiat_value_usd.as_deref(), Some("25.00"));
+        assert_eq!(rows[0].fee.as_deref(), Some("0.00000126"));
+        assert_eq!(rows[0].direction, "out");
+        assert_eq!(rows[0].timestamp, "2023-11-14T22:13:20+00:00");
+
+        // Received: the sender paid the fee
+        assert_eq!(rows[1].amount, "2.5");
+        assert_eq!(rows[1].fiat_value_usd.as_deref(), Some("2.50"));
+        assert_eq!((rows[1].direction.as_str(), rows[1].fee.as_ref()), ("in", None));
+
+        // Unregistered token: raw amount, no symbol or price
+        assert_eq!(rows[2].amount, "42");
+        assert_eq!((rows[2].token.as_ref(), rows[2].fiat_value_usd.as_ref()), (None, None));
+
+        let csv = render(ExportFormat::Csv, &rows).unwrap();
+        assert!(csv.starts_with("hash,timestamp,block_number,from,to,direction,token,"));
+        assert_eq!(csv.lines().count(), 4);
+        let json: serde_json::Value =
+            serde_json::from_str(&render(ExportFormat::Json, &rows).unwrap()).unwrap();
+        assert_eq!(json[1]["token"], "USDT");
+        assert_eq!(json[2]["fiat_value_usd"], serde_json::Value::Null);
+    }
+}
@@ -0,0 +1,185 @@
+//! Incoming RBTC and ERC-20 payments to a set of addresses, found block by
+//! block for `watch incoming`.
+//!
+//! RBTC payments are the transactions of a block that send value to one of
+//! the addresses and succeeded, so payments made from inside a contract
+//! (internal transactions) are not seen. Token payments are the ERC-20
+//! `Transfer` logs to one of the addresses.
+
+use crate::types::transaction::{RskTransaction, TransactionStatus};
+use crate::utils::eth::EthClient;
+use crate::utils::transfer_logs::TRANSFER_TOPIC;
+use alloy::consensus::Transaction as _;
+use alloy::eips::BlockNumberOrTag;
+use alloy::primitives::{Address, B256, U64, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::{Block, BlockTransactionsKind, Filter, Log};
+use anyhow::{Result, anyhow};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Most blocks scanned in one call, so that a watcher far behind the chain
+/// head reports progress
+pub const MAX_BLOCKS: u64 = 100;
+
+/// Transactions of `block` that send RBTC to a `watched` address. Whether
+/// they succeeded is only known from their receipts.
+pub fn native_payments(block: &Block, watched: &HashSet<Address>) -> Vec<RskTransaction> {
+    let timestamp = UNIX_EPOCH + Duration::from_secs(block.header.timestamp);
+    block
+        .transactions
+        .txns()
+        .filter(|tx| !tx.value().is_zero())
+        .filter_map(|tx| {
+            let to = tx.to().filter(|to| watched.contains(to))?;
+            Some(RskTransaction {
+                hash: *tx.inner.tx_hash(),
+                from: tx.from,
+                to: Some(to),
+                value: tx.value(),
+                gas_price: U256::from(tx.gas_price().unwrap_or_default()),
+                gas: U256::from(tx.gas_limit()),
+                nonce: U256::from(tx.nonce()),
+                input: None,
+                block_number: Some(U64::from(block.header.number)),
+                transaction_index: tx.transaction_index.map(U64::from),
+                timestamp,
+                status: TransactionStatus::Unknown,
+                token_address: None,
+                confirms: None,
+                cumulative_gas_used: None,
+                gas_used: None,
+                logs: None,
+            })
+        })
+        .collect()
+}
+
+/// ERC-20 transfers to a `watched` address among `logs`, skipping ERC-721
+/// transfers, which share the event signature
+pub fn token_payments(
+    logs: &[Log],
+    watched: &HashSet<Address>,
+    timestamps: &HashMap<u64, u64>,
+) -> Vec<RskTransaction> {
+    logs.iter()
+        .filter_map(|log| {
+            let timestamp = log
+                .block_number
+                .and_then(|block| timestamps.get(&block))
+                .map_or_else(SystemTime::now, |t| UNIX_EPOCH + Duration::from_secs(*t));
+            RskTransaction::from_transfer_log(log, timestamp).ok()
+        })
+        .filter(|tx| tx.to.is_some_and(|to| watched.contains(&to)))
+        .collect()
+}
+
+/// Payments to `watched` addresses in blocks `from_block` through
+/// `to_block`, oldest first
+pub async fn scan(
+    client: &EthClient,
+    watched: &HashSet<Address>,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<RskTransaction>> {
+    let provider = client.provider();
+    let mut payments = Vec::new();
+    let mut timestamps = HashMap::new();
+    for number in from_block..=to_block {
+        let block = provider
+            .get_block_by_number(
+                BlockNumberOrTag::Number(number),
+                BlockTransactionsKind::Full,
+            )
+            .await
+            .map_err(|e| anyhow!("Failed to get block {}: {}", number, e))?
+            .ok_or_else(|| anyhow!("Block {} not found", number))?;
+        timestamps.insert(number, block.header.timestamp);
+        for mut payment in native_payments(&block, watched) {
+            let receipt = provider
+                .get_transaction_receipt(payment.hash)
+                .await
+                .map_err(|e| anyhow!("Failed to get receipt of 0x{:x}: {}", payment.hash, e))?;
+            if receipt.is_some_and(|receipt| receipt.status()) {
+                payment.status = TransactionStatus::Success;
+                payments.push(payment);
+            }
+        }
+    }
+
+    let recipients: Vec<B256> = watched.iter().map(|address| address.into_word()).collect();
+    let filter = Filter::new()
+        .event_signature(TRANSFER_TOPIC)
+        .topic2(recipients)
+        .from_block(from_block)
+        .to_block(to_block);
+    let logs = provider
+        .get_logs(&filter)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch logs: {}", e))?;
+    payments.extend(token_payments(&logs, watched, &timestamps));
+
+    payments.sort_by_key(|tx| {
+        (
+            tx.block_number,
+            tx.transaction_index,
+            tx.token_address.is_some(),
+        )
+    });
+    Ok(payments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Bytes, LogData};
+
+    fn transfer_log(topics: Vec<B256>, value: u64) -> Log {
+        Log {
+            inner: alloy::primitives::Log {
+                address: Address::repeat_byte(0x70),
+                data: LogData::new_unchecked(
+                    topics,
+                    Bytes::from(U256::from(value).to_be_bytes::<32>().to_vec()),
+                ),
+            },
+            block_number: Some(12),
+            transaction_hash: Some(B256::repeat_byte(0xaa)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_token_payments() {
+        let mine = Address::repeat_byte(1);
+        let other = Address::repeat_byte(2);
+        let watched = HashSet::from([mine]);
+        let timestamps = HashMap::from([(12, 1_700_000_000)]);
+        let logs = vec![
+            transfer_log(vec![TRANSFER_TOPIC, other.into_word(), mine.into_word()], 5),
+            // Sent, not received
+            transfer_log(vec![TRANSFER_TOPIC, mine.into_word(), other.into_word()], 6),
+            // ERC-721: the token id is the fourth topic
+            transfer_log(
+                vec![
+                    TRANSFER_TOPIC,
+                    other.into_word(),
+                    mine.into_word(),
+                    B256::with_last_byte(7),
+                ],
+                0,
+            ),
+        ];
+
+        let payments = token_payments(&logs, &watched, &timestamps);
+        assert_eq!(payments.len(), 1);
+        assert_eq!(payments[0].from, other);
+        assert_eq!(payments[0].to, Some(mine));
+        assert_eq!(payments[0].value, U256::from(5));
+        assert_eq!(payments[0].token_address, Some(Address::repeat_byte(0x70)));
+        assert_eq!(
+            payments[0].timestamp,
+            UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+        );
+    }
+}
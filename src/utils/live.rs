@@ -0,0 +1,142 @@
+//! Live chain updates over a WebSocket endpoint.
+//!
+//! Waiting for a receipt or for confirmations normally polls the node at the
+//! configured interval. With a WebSocket endpoint configured for the network,
+//! the wallet subscribes to new heads (and, where the node offers it, to
+//! pending transactions to spot the one being waited for) and checks again as
+//! soon as something changes. Any failure
+//! to connect or a dropped subscription falls back to polling.
+
+use alloy::primitives::B256;
+use alloy::providers::{Provider, ProviderBuilder};
+use anyhow::{Result, anyhow};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Longest wait for a subscription event before checking anyway, so
+/// deadlines are still noticed on a quiet chain
+const EVENT_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainEvent {
+    /// A new block was added at this height
+    Head(u64),
+    /// The node received this transaction
+    Pending(B256),
+}
+
+/// Stream of chain events from `ws_url`: new heads, and the arrival of the
+/// `watched` transaction when the node supports pending subscriptions
+pub async fn subscribe(
+    ws_url: &str,
+    watched: Option<B256>,
+) -> Result<mpsc::Receiver<ChainEvent>> {
+    let provider = ProviderBuilder::new()
+        .on_builtin(ws_url)
+        .await
+        .map_err(|e| anyhow!("Failed to connect to {}: {}", ws_url, e))?;
+    let mut heads = provider
+        .subscribe_blocks()
+        .await
+        .map_err(|e| anyhow!("Failed to subscribe to new heads: {}", e))?;
+    let (sender, receiver) = mpsc::channel(64);
+
+    if let Some(watched) = watched {
+        match provider.subscribe_pending_transactions().await {
+            Ok(mut hashes) => {
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    while let Ok(hash) = hashes.recv().await {
+                        if hash == watched && sender.send(ChainEvent::Pending(hash)).await.is_err()
+                        {
+                            break;
+                        }
+                    }
+                });
+            }
+            Err(e) => log::debug!("Pending transaction subscription unavailable: {}", e),
+        }
+    }
+
+    tokio::spawn(async move {
+        // Keeps the connection open for as long as events are wanted
+        let _provider = provider;
+        while let Ok(header) = heads.recv().await {
+            if sender.send(ChainEvent::Head(header.number)).await.is_err() {
+                break;
+            }
+        }
+    });
+    Ok(receiver)
+}
+
+/// Paces a wait loop: wakes on the next chain event when subscribed, or after
+/// the poll interval otherwise
+pub struct Ticker {
+    events: Option<mpsc::Receiver<ChainEvent>>,
+    interval: Duration,
+}
+
+impl Ticker {
+    /// Subscribe through `ws_url` when one is given, polling every
+    /// `interval` if there is none or the subscription fails
+    pub async fn connect(ws_url: Option<&str>, interval: Duration, watched: Option<B256>) -> Self {
+        let events = match ws_url {
+            Some(url) => match subscribe(url, watched).await {
+                Ok(events) => Some(events),
+                Err(e) => {
+                    log::warn!("Live updates unavailable, polling instead: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+        Self { events, interval }
+    }
+
+    pub fn is_live(&self) -> bool {
+        self.events.is_some()
+    }
+
+    /// Wait for the next reason to check again. Returns the event that
+    /// triggered it, or `None` after a poll interval or an event timeout.
+    pub async fn tick(&mut self) -> Option<ChainEvent> {
+        let Some(events) = &mut self.events else {
+            tokio::time::sleep(self.interval).await;
+            return None;
+        };
+        match tokio::time::timeout(EVENT_TIMEOUT, events.recv()).await {
+            Ok(Some(event)) => Some(event),
+            Ok(None) => {
+                log::warn!("Live updates ended, polling instead");
+                self.events = None;
+                None
+            }
+            Err(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ticker_falls_back_to_polling() {
+        let mut ticker = Ticker::connect(None, Duration::from_millis(1), None).await;
+        assert!(!ticker.is_live());
+        assert_eq!(ticker.tick().await, None);
+
+        // A closed subscription turns into polling
+        let (sender, receiver) = mpsc::channel(1);
+        let mut ticker = Ticker {
+            events: Some(receiver),
+            interval: Duration::from_millis(1),
+        };
+        sender.send(ChainEvent::Head(7)).await.unwrap();
+        drop(sender);
+        assert_eq!(ticker.tick().await, Some(ChainEvent::Head(7)));
+        assert_eq!(ticker.tick().await, None);
+        assert!(!ticker.is_live());
+    }
+}
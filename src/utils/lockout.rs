@@ -0,0 +1,131 @@
+//! Throttling of failed wallet password attempts.
+//!
+//! After [`FREE_ATTEMPTS`] consecutive failures a wallet is locked for a
+//! delay that doubles with every further failure, up to [`MAX_LOCKOUT_SECS`].
+//! State is persisted so restarting the wallet does not reset the counter.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Failures allowed before any delay is enforced
+pub const FREE_ATTEMPTS: u32 = 3;
+/// Upper bound for a single lockout period
+pub const MAX_LOCKOUT_SECS: i64 = 15 * 60;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AttemptRecord {
+    pub failures: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+/// Failed attempt counters keyed by wallet address
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LockoutState {
+    #[serde(default)]
+    pub wallets: HashMap<String, AttemptRecord>,
+}
+
+impl LockoutState {
+    pub fn path() -> Result<PathBuf> {
+        let dir = dirs::data_local_dir()
+            .context("Failed to get data directory")?
+            .join("rootstock-wallet");
+        fs::create_dir_all(&dir)?;
+        Ok(dir.join("lockout.json"))
+    }
+
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path).context("Failed to read lockout state")?;
+        serde_json::from_str(&content).context("Failed to parse lockout state")
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(Self::path()?, content).context("Failed to write lockout state")
+    }
+
+    /// Time remaining until the wallet may be unlocked again, if it is locked
+    pub fn remaining_lockout(&self, wallet: &str, now: DateTime<Utc>) -> Option<Duration> {
+        self.wallets
+            .get(wallet)
+            .and_then(|r| r.locked_until)
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+
+    /// Register a failed attempt and return the lockout it triggers, if any
+    pub fn record_failure(&mut self, wallet: &str, now: DateTime<Utc>) -> Option<Duration> {
+        let record = self.wallets.entry(wallet.to_string()).or_default();
+        record.failures += 1;
+
+        let delay = lockout_for(record.failures)?;
+        record.locked_until = Some(now + delay);
+        Some(delay)
+    }
+
+    /// Forget failures after a successful unlock, returning how many there were
+    pub fn record_success(&mut self, wallet: &str) -> u32 {
+        self.wallets.remove(wallet).map(|r| r.failures).unwrap_or(0)
+    }
+}
+
+/// Lockout after the given number of consecutive failures
+pub fn lockout_for(failures: u32) -> Option<Duration> {
+    if failures < FREE_ATTEMPTS {
+        return None;
+    }
+    let exponent = (failures - FREE_ATTEMPTS).min(20);
+    let secs = (30i64 << exponent).min(MAX_LOCKOUT_SECS);
+    Some(Duration::seconds(secs))
+}
+
+/// Human readable duration such as "2m 30s"
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.num_seconds().max(1);
+    if secs >= 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exponential_lockout() {
+        assert_eq!(lockout_for(1), None);
+        assert_eq!(lockout_for(2), None);
+        assert_eq!(lockout_for(3), Some(Duration::seconds(30)));
+        assert_eq!(lockout_for(4), Some(Duration::seconds(60)));
+        assert_eq!(lockout_for(5), Some(Duration::seconds(120)));
+        assert_eq!(lockout_for(40), Some(Duration::seconds(MAX_LOCKOUT_SECS)));
+    }
+
+    #[test]
+    fn test_success_resets_failures() {
+        let now = Utc::now();
+        let mut state = LockoutState::default();
+        for _ in 0..3 {
+            state.record_failure("0xabc", now);
+        }
+        assert!(state.remaining_lockout("0xabc", now).is_some());
+        assert!(
+            state
+                .remaining_lockout("0xabc", now + Duration::seconds(31))
+                .is_none()
+        );
+        assert_eq!(state.record_success("0xabc"), 3);
+        assert!(state.remaining_lockout("0xabc", now).is_none());
+    }
+}
@@ -0,0 +1,290 @@
+//! BIP39 recovery phrases in every wordlist supported by `coins-bip39`.
+//!
+//! Keys are derived on the Rootstock path (SLIP-44 coin type 137). Phrases
+//! created here only restore the same address in wallets that use that path.
+
+use crate::utils::signer;
+use alloy::signers::local::coins_bip39::{
+    ChineseSimplified, ChineseTraditional, Czech, English, French, Italian, Japanese, Korean,
+    Mnemonic, Portuguese, Spanish, Wordlist,
+};
+use alloy::signers::local::{MnemonicBuilder, PrivateKeySigner};
+use anyhow::{Result, anyhow};
+use std::fmt;
+use std::str::FromStr;
+
+/// Rootstock mainnet derivation path (RSKIP-57) of the first account
+pub const RSK_DERIVATION_PATH: &str = "m/44'/137'/0'/0/0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MnemonicLanguage {
+    English,
+    Spanish,
+    Portuguese,
+    French,
+    Italian,
+    Czech,
+    Japanese,
+    Korean,
+    ChineseSimplified,
+    ChineseTraditional,
+}
+
+/// Run a generic function with the wordlist type matching a language
+macro_rules! with_wordlist {
+    ($language:expr, $f:ident($($arg:expr),*)) => {
+        match $language {
+            MnemonicLanguage::English => $f::<English>($($arg),*),
+            MnemonicLanguage::Spanish => $f::<Spanish>($($arg),*),
+            MnemonicLanguage::Portuguese => $f::<Portuguese>($($arg),*),
+            MnemonicLanguage::French => $f::<French>($($arg),*),
+            MnemonicLanguage::Italian => $f::<Italian>($($arg),*),
+            MnemonicLanguage::Czech => $f::<Czech>($($arg),*),
+            MnemonicLanguage::Japanese => $f::<Japanese>($($arg),*),
+            MnemonicLanguage::Korean => $f::<Korean>($($arg),*),
+            MnemonicLanguage::ChineseSimplified => $f::<ChineseSimplified>($($arg),*),
+            MnemonicLanguage::ChineseTraditional => $f::<ChineseTraditional>($($arg),*),
+        }
+    };
+}
+
+impl MnemonicLanguage {
+    pub const ALL: [MnemonicLanguage; 10] = [
+        MnemonicLanguage::English,
+        MnemonicLanguage::Spanish,
+        MnemonicLanguage::Portuguese,
+        MnemonicLanguage::French,
+        MnemonicLanguage::Italian,
+        MnemonicLanguage::Czech,
+        MnemonicLanguage::Japanese,
+        MnemonicLanguage::Korean,
+        MnemonicLanguage::ChineseSimplified,
+        MnemonicLanguage::ChineseTraditional,
+    ];
+
+    /// Warning to show for phrases other wallets may not understand
+    pub fn compatibility_warning(&self) -> Option<String> {
+        if *self == MnemonicLanguage::English {
+            return None;
+        }
+        Some(format!(
+            "Most hardware wallets and many software wallets only accept English recovery phrases. \
+             Write down that this phrase uses the {} wordlist; restoring it in a wallet without \
+             that wordlist will fail or produce a different address.",
+            self
+        ))
+    }
+
+    /// Short code accepted by [`FromStr`], used when persisting the language
+    pub fn code(&self) -> &'static str {
+        match self {
+            MnemonicLanguage::English => "en",
+            MnemonicLanguage::Spanish => "es",
+            MnemonicLanguage::Portuguese => "pt",
+            MnemonicLanguage::French => "fr",
+            MnemonicLanguage::Italian => "it",
+            MnemonicLanguage::Czech => "cs",
+            MnemonicLanguage::Japanese => "ja",
+            MnemonicLanguage::Korean => "ko",
+            MnemonicLanguage::ChineseSimplified => "zh-hans",
+            MnemonicLanguage::ChineseTraditional => "zh-hant",
+        }
+    }
+}
+
+impl fmt::Display for MnemonicLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MnemonicLanguage::English => "English",
+            MnemonicLanguage::Spanish => "Spanish",
+            MnemonicLanguage::Portuguese => "Portuguese",
+            MnemonicLanguage::French => "French",
+            MnemonicLanguage::Italian => "Italian",
+            MnemonicLanguage::Czech => "Czech",
+            MnemonicLanguage::Japanese => "Japanese",
+            MnemonicLanguage::Korean => "Korean",
+            MnemonicLanguage::ChineseSimplified => "Chinese (Simplified)",
+            MnemonicLanguage::ChineseTraditional => "Chinese (Traditional)",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for MnemonicLanguage {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "en" | "english" => Ok(MnemonicLanguage::English),
+            "es" | "spanish" => Ok(MnemonicLanguage::Spanish),
+            "pt" | "portuguese" => Ok(MnemonicLanguage::Portuguese),
+            "fr" | "french" => Ok(MnemonicLanguage::French),
+            "it" | "italian" => Ok(MnemonicLanguage::Italian),
+            "cs" | "czech" => Ok(MnemonicLanguage::Czech),
+            "ja" | "japanese" => Ok(MnemonicLanguage::Japanese),
+            "ko" | "korean" => Ok(MnemonicLanguage::Korean),
+            "zhhans" | "chinesesimplified" => Ok(MnemonicLanguage::ChineseSimplified),
+            "zhhant" | "chinesetraditional" => Ok(MnemonicLanguage::ChineseTraditional),
+            _ => Err(anyhow!("Unsupported mnemonic language: {}", s)),
+        }
+    }
+}
+
+/// Collapse whitespace (including the ideographic space) and lowercase the phrase
+pub fn normalize_phrase(phrase: &str) -> String {
+    phrase
+        .split_whitespace()
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn generate_in<W: Wordlist>(word_count: usize) -> Result<String> {
+    let mut rng = rand::thread_rng();
+    let mnemonic = Mnemonic::<W>::new_with_count(&mut rng, word_count)
+        .map_err(|e| anyhow!("Failed to generate mnemonic: {}", e))?;
+    Ok(mnemonic.to_phrase())
+}
+
+fn is_valid_in<W: Wordlist>(phrase: &str) -> bool {
+    Mnemonic::<W>::new_from_phrase(phrase).is_ok()
+}
+
+fn signer_in<W: Wordlist>(phrase: &str, path: &str) -> Result<PrivateKeySigner> {
+    MnemonicBuilder::<W>::default()
+        .phrase(phrase)
+        .derivation_path(path)
+        .map_err(|e| anyhow!("Invalid derivation path: {}", e))?
+        .build()
+        .map_err(|e| anyhow!("Failed to derive key from mnemonic: {}", e))
+}
+
+/// Generate a new phrase of 12, 15, 18, 21 or 24 words
+pub fn generate(language: MnemonicLanguage, word_count: usize) -> Result<String> {
+    if ![12, 15, 18, 21, 24].contains(&word_count) {
+        return Err(anyhow!(
+            "Word count must be 12, 15, 18, 21 or 24 (got {})",
+            word_count
+        ));
+    }
+    with_wordlist!(language, generate_in(word_count))
+}
+
+/// All wordlists the phrase is valid in (words and checksum)
+pub fn detect_languages(phrase: &str) -> Vec<MnemonicLanguage> {
+    let phrase = normalize_phrase(phrase);
+    MnemonicLanguage::ALL
+        .into_iter()
+        .filter(|language| with_wordlist!(*language, is_valid_in(&phrase)))
+        .collect()
+}
+
+/// Detect the wordlist of a phrase, failing if it is invalid or ambiguous
+pub fn detect_language(phrase: &str) -> Result<MnemonicLanguage> {
+    match detect_languages(phrase).as_slice() {
+        [] => Err(anyhow!(
+            "The phrase is not a valid BIP39 mnemonic in any supported wordlist. \
+             Check the spelling, word order and number of words."
+        )),
+        [language] => Ok(*language),
+        languages => Err(anyhow!(
+            "The phrase is valid in several wordlists ({}). Please specify the language.",
+            languages
+                .iter()
+                .map(|l| l.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Derive the wallet key for a phrase in the given wordlist
+pub fn signer_from_phrase(phrase: &str, language: MnemonicLanguage) -> Result<PrivateKeySigner> {
+    signer_at(phrase, language, 0)
+}
+
+/// Derive the key of account `index` (`m/44'/137'/0'/0/index`)
+pub fn signer_at(phrase: &str, language: MnemonicLanguage, index: u32) -> Result<PrivateKeySigner> {
+    let phrase = normalize_phrase(phrase);
+    if !with_wordlist!(language, is_valid_in(&phrase)) {
+        return Err(anyhow!(
+            "The phrase is not a valid {} BIP39 mnemonic",
+            language
+        ));
+    }
+    let path = signer::rsk_derivation_path(index);
+    with_wordlist!(language, signer_in(&phrase, &path))
+}
+
+/// Pick `count` distinct 1-based word positions, in order, to quiz the user on
+pub fn quiz_positions(word_count: usize, count: usize) -> Vec<usize> {
+    let mut rng = rand::thread_rng();
+    let mut positions: Vec<usize> =
+        rand::seq::index::sample(&mut rng, word_count, count.min(word_count))
+            .into_iter()
+            .map(|index| index + 1)
+            .collect();
+    positions.sort_unstable();
+    positions
+}
+
+/// Whether `answer` is the word at 1-based `position` in the phrase
+pub fn quiz_answer_matches(phrase: &str, position: usize, answer: &str) -> bool {
+    let answer = normalize_phrase(answer);
+    !answer.is_empty()
+        && position > 0
+        && normalize_phrase(phrase).split(' ').nth(position - 1) == Some(answer.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_and_detect() {
+        for language in [MnemonicLanguage::English, MnemonicLanguage::Spanish] {
+            let phrase = generate(language, 12).unwrap();
+            assert_eq!(phrase.split_whitespace().count(), 12);
+            assert!(detect_languages(&phrase).contains(&language));
+            signer_from_phrase(&phrase, language).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_known_vector() {
+        let phrase = "abandon abandon abandon abandon abandon abandon \
+                      abandon abandon abandon abandon abandon about";
+        assert_eq!(detect_language(phrase).unwrap(), MnemonicLanguage::English);
+        assert!(signer_from_phrase(phrase, MnemonicLanguage::Spanish).is_err());
+        assert!(detect_language("not a real mnemonic phrase").is_err());
+    }
+
+    #[test]
+    fn test_account_derivation() {
+        let phrase = "abandon abandon abandon abandon abandon abandon \
+                      abandon abandon abandon abandon abandon about";
+        let first = signer_from_phrase(phrase, MnemonicLanguage::English).unwrap();
+        let second = signer_at(phrase, MnemonicLanguage::English, 1).unwrap();
+        assert_eq!(
+            first.address(),
+            signer_at(phrase, MnemonicLanguage::English, 0)
+                .unwrap()
+                .address()
+        );
+        assert_ne!(first.address(), second.address());
+    }
+
+    #[test]
+    fn test_quiz() {
+        let phrase = "abandon ability able about above absent";
+        let positions = quiz_positions(6, 3);
+        assert_eq!(positions.len(), 3);
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(positions.iter().all(|position| (1..=6).contains(position)));
+
+        assert!(quiz_answer_matches(phrase, 2, " Ability "));
+        assert!(!quiz_answer_matches(phrase, 3, "ability"));
+        assert!(!quiz_answer_matches(phrase, 0, "abandon"));
+        assert!(!quiz_answer_matches(phrase, 7, ""));
+    }
+}
@@ -1,6 +1,62 @@
+pub mod abi_registry;
+pub mod accounting;
+pub mod address_validation;
 pub mod alchemy;
+pub mod allowances;
+pub mod audit;
+pub mod audit_package;
+pub mod blockscout;
+pub mod bulk_csv;
+pub mod bulk_engine;
+pub mod bulk_job;
+pub mod canary;
+pub mod chain_guard;
+pub mod categories;
+pub mod confirmations;
 pub mod constants;
+pub mod contact_export;
+pub mod contact_formats;
+pub mod cost_centers;
 pub mod eth;
+pub mod event_webhooks;
+pub mod events;
+pub mod fees;
+pub mod gas_analytics;
+pub mod gas_oracle;
 pub mod helper;
+pub mod live;
+pub mod history_cache;
+pub mod history_export;
+pub mod http;
+pub mod incoming;
+pub mod lockout;
+pub mod mnemonic;
+pub mod nft;
+pub mod nonce;
+pub mod notifications;
+pub mod offline;
+pub mod output;
+pub mod payroll;
+pub mod pending;
+pub mod profiles;
+pub mod quick_amounts;
+pub mod recovery_drill;
+pub mod redact;
+pub mod retry;
+pub mod rns;
+pub mod rpc_cache;
+pub mod roles;
+pub mod rsk_explorer;
+pub mod signer;
+pub mod signing_log;
+pub mod simulation;
+pub mod sponsor;
+pub mod staking;
+pub mod storage;
+pub mod store;
+pub mod submissions;
 pub mod table;
 pub mod terminal;
+pub mod transfer_fee;
+pub mod transfer_logs;
+pub mod watchdog;
@@ -0,0 +1,294 @@
+//! ERC-721 tokens (NFTs) owned by a wallet.
+//!
+//! Owned tokens come from Blockscout where the network has it, with the
+//! metadata the explorer has already fetched. Otherwise they are found from
+//! the ERC-721 `Transfer` logs to the wallet in recent blocks, keeping those
+//! the contract's `ownerOf` still reports as the wallet's. Names and images
+//! come from the metadata JSON at the token's `tokenURI`.
+
+use crate::types::network::{Network, NetworkService};
+use crate::utils::blockscout::{self, BlockscoutClient};
+use crate::utils::eth::EthClient;
+use crate::utils::http;
+use crate::utils::transfer_logs::{SCAN_BLOCKS, TRANSFER_TOPIC};
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::{Filter, Log};
+use alloy::sol;
+use anyhow::{Result, anyhow};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use serde_json::Value;
+use std::str::FromStr;
+use std::time::Duration;
+
+sol! {
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    contract IERC721 {
+        function name() external view returns (string);
+        function ownerOf(uint256 tokenId) external view returns (address);
+        function tokenURI(uint256 tokenId) external view returns (string);
+        function safeTransferFrom(address from, address to, uint256 tokenId) external;
+    }
+}
+
+/// Gateway used to fetch `ipfs://` metadata and show images
+const IPFS_GATEWAY: &str = "https://ipfs.io/ipfs/";
+/// Most tokens listed from Blockscout
+const MAX_NFTS: u32 = 200;
+/// Blocks covered by a single log query
+const CHUNK_BLOCKS: u64 = 1_000;
+const METADATA_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone)]
+pub struct Nft {
+    pub contract: Address,
+    /// Collection name, or the contract address when it has none
+    pub collection: String,
+    pub token_id: U256,
+    pub name: Option<String>,
+    /// Image URL, with `ipfs://` links turned into gateway URLs
+    pub image: Option<String>,
+}
+
+impl Nft {
+    /// Build a token from an item of the Blockscout `/addresses/{address}/nft`
+    /// endpoint
+    pub fn from_blockscout(item: &Value) -> Option<Self> {
+        let token = &item["token"];
+        let contract = blockscout::token_address(token)?;
+        let metadata = &item["metadata"];
+        Some(Self {
+            contract,
+            collection: token["name"]
+                .as_str()
+                .or(token["symbol"].as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("0x{:x}", contract)),
+            token_id: U256::from_str(item["id"].as_str()?).ok()?,
+            name: metadata["name"].as_str().map(str::to_string),
+            image: item["image_url"]
+                .as_str()
+                .or(metadata["image"].as_str())
+                .map(gateway_url),
+        })
+    }
+}
+
+/// ERC-721 tokens `owner` holds on `network`
+pub async fn owned(client: &EthClient, network: Network, owner: Address) -> Result<Vec<Nft>> {
+    if network.supports(NetworkService::Blockscout) {
+        match BlockscoutClient::new(network.is_testnet())?
+            .get_nfts(&owner, MAX_NFTS)
+            .await
+        {
+            Ok(nfts) => return Ok(nfts),
+            Err(e) => log::warn!("Blockscout NFT lookup failed, scanning logs: {}", e),
+        }
+    }
+
+    let mut nfts = Vec::new();
+    for (contract, token_id) in received(client, owner).await? {
+        let erc721 = IERC721::new(contract, client.provider());
+        let still_owned = erc721
+            .ownerOf(token_id)
+            .call()
+            .await
+            .is_ok_and(|holder| holder._0 == owner);
+        if !still_owned {
+            continue;
+        }
+        let collection = erc721
+            .name()
+            .call()
+            .await
+            .map(|name| name._0)
+            .unwrap_or_else(|_| format!("0x{:x}", contract));
+        let (name, image) = metadata(client, contract, token_id)
+            .await
+            .unwrap_or_default();
+        nfts.push(Nft {
+            contract,
+            collection,
+            token_id,
+            name,
+            image,
+        });
+    }
+    Ok(nfts)
+}
+
+/// Name and image from the metadata at the token's `tokenURI`
+pub async fn metadata(
+    client: &EthClient,
+    contract: Address,
+    token_id: U256,
+) -> Result<(Option<String>, Option<String>)> {
+    let uri = IERC721::new(contract, client.provider())
+        .tokenURI(token_id)
+        .call()
+        .await
+        .map_err(|e| anyhow!("Failed to get the token URI: {}", e))?
+        ._0;
+    let metadata = fetch_json(&uri).await?;
+    Ok((
+        metadata["name"].as_str().map(str::to_string),
+        metadata["image"].as_str().map(gateway_url),
+    ))
+}
+
+/// Send `token_id` from the client's wallet `from` with `safeTransferFrom`,
+/// which reverts when `to` is a contract that cannot receive NFTs
+pub async fn safe_transfer(
+    client: &EthClient,
+    contract: Address,
+    from: Address,
+    to: Address,
+    token_id: U256,
+) -> Result<B256> {
+    let input = IERC721::new(contract, client.provider())
+        .safeTransferFrom(from, to, token_id)
+        .calldata()
+        .clone();
+    client.send_call(contract, U256::ZERO, input).await
+}
+
+/// Contracts and ids of ERC-721 tokens sent to `owner` in the last
+/// [`SCAN_BLOCKS`] blocks
+async fn received(client: &EthClient, owner: Address) -> Result<Vec<(Address, U256)>> {
+    let provider = client.provider();
+    let head = provider
+        .get_block_number()
+        .await
+        .map_err(|e| anyhow!("Failed to get block number: {}", e))?;
+    let oldest = head.saturating_sub(SCAN_BLOCKS);
+
+    let mut logs = Vec::new();
+    let mut to_block = head;
+    loop {
+        let from_block = to_block.saturating_sub(CHUNK_BLOCKS - 1).max(oldest);
+        let filter = Filter::new()
+            .event_signature(TRANSFER_TOPIC)
+            .topic2(owner.into_word())
+            .from_block(from_block)
+            .to_block(to_block);
+        logs.extend(
+            provider
+                .get_logs(&filter)
+                .await
+                .map_err(|e| anyhow!("Failed to fetch logs: {}", e))?,
+        );
+        if from_block == oldest {
+            break;
+        }
+        to_block = from_block - 1;
+    }
+    Ok(received_tokens(logs))
+}
+
+/// Distinct tokens among `logs`, newest first. ERC-20 transfers (which
+/// index no token id) are left out.
+fn received_tokens(mut logs: Vec<Log>) -> Vec<(Address, U256)> {
+    logs.retain(|log| log.topics().len() == 4);
+    logs.sort_by_key(|log| std::cmp::Reverse((log.block_number, log.log_index)));
+    let mut tokens = Vec::new();
+    for log in logs {
+        let token = (log.address(), U256::from_be_bytes(log.topics()[3].0));
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
+    tokens
+}
+
+async fn fetch_json(uri: &str) -> Result<Value> {
+    if let Some(encoded) = uri.strip_prefix("data:application/json;base64,") {
+        let bytes = STANDARD
+            .decode(encoded)
+            .map_err(|e| anyhow!("Invalid metadata encoding: {}", e))?;
+        return serde_json::from_slice(&bytes).map_err(|e| anyhow!("Invalid metadata: {}", e));
+    }
+    if let Some(json) = uri.strip_prefix("data:application/json,") {
+        return serde_json::from_str(json).map_err(|e| anyhow!("Invalid metadata: {}", e));
+    }
+    http::secure_client()?
+        .get(gateway_url(uri))
+        .timeout(METADATA_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| anyhow!("Failed to fetch metadata: {}", e))?
+        .json()
+        .await
+        .map_err(|e| anyhow!("Invalid metadata: {}", e))
+}
+
+/// `uri` with an `ipfs://` scheme replaced by the public gateway
+pub fn gateway_url(uri: &str) -> String {
+    match uri.strip_prefix("ipfs://") {
+        Some(path) => format!(
+            "{}{}",
+            IPFS_GATEWAY,
+            path.strip_prefix("ipfs/").unwrap_or(path)
+        ),
+        None => uri.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Bytes;
+
+    #[test]
+    fn test_nft_sources() {
+        assert_eq!(
+            gateway_url("ipfs://ipfs/QmHash/1.json"),
+            "https://ipfs.io/ipfs/QmHash/1.json"
+        );
+        assert_eq!(gateway_url("ipfs://QmHash"), "https://ipfs.io/ipfs/QmHash");
+        assert_eq!(gateway_url("https://a.b/1"), "https://a.b/1");
+
+        let item: Value = serde_json::from_str(
+            r#"{"id":"42","image_url":null,
+                "metadata":{"name":"Gem #42","image":"ipfs://QmGem"},
+                "token":{"address_hash":"0x0707070707070707070707070707070707070707",
+                         "name":"Gems","symbol":"GEM","type":"ERC-721"}}"#,
+        )
+        .unwrap();
+        let nft = Nft::from_blockscout(&item).unwrap();
+        assert_eq!(nft.contract, Address::repeat_byte(7));
+        assert_eq!((nft.collection.as_str(), nft.token_id), ("Gems", U256::from(42)));
+        assert_eq!(nft.name.as_deref(), Some("Gem #42"));
+        assert_eq!(nft.image.as_deref(), Some("https://ipfs.io/ipfs/QmGem"));
+
+        let owner = Address::repeat_byte(1);
+        let transfer = |block: u64, token_id: Option<u64>| {
+            let mut topics = vec![TRANSFER_TOPIC, B256::ZERO, owner.into_word()];
+            topics.extend(token_id.map(|id| B256::from(U256::from(id))));
+            Log {
+                inner: alloy::primitives::Log::new_unchecked(
+                    Address::repeat_byte(7),
+                    topics,
+                    Bytes::new(),
+                ),
+                block_number: Some(block),
+                log_index: Some(0),
+                ..Default::default()
+            }
+        };
+        let tokens = received_tokens(vec![
+            transfer(10, Some(1)),
+            transfer(12, Some(2)),
+            transfer(11, Some(1)),
+            transfer(13, None),
+        ]);
+        assert_eq!(
+            tokens,
+            vec![
+                (Address::repeat_byte(7), U256::from(2)),
+                (Address::repeat_byte(7), U256::from(1)),
+            ]
+        );
+    }
+}
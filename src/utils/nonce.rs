@@ -0,0 +1,234 @@
+//! Nonce manager for sending several transactions in quick succession.
+//!
+//! The node's transaction count only covers transactions it has already seen,
+//! so a second send issued right after the first (or sent through another
+//! endpoint) could reuse its nonce. Transactions the wallet submitted are
+//! kept in the pending store, and the next nonce skips past the ones the node
+//! does not report yet. A local entry the node still does not know about
+//! after a grace period is treated as dropped and its nonce is reused, so a
+//! lost transaction cannot leave a gap that blocks every later one.
+//!
+//! Sends running at the same time in one process do not see each other in
+//! the pending store until they are submitted, so every nonce handed out is
+//! also recorded in memory, under a lock held for the whole reservation,
+//! and is not handed out again for a short while. Nonces are reserved right
+//! before signing, and released when the transaction is not broadcast.
+
+use crate::utils::http::RpcProvider;
+use crate::utils::pending::{PendingStore, PendingTx};
+use alloy::primitives::Address;
+use alloy::providers::Provider;
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, Local};
+use std::collections::HashMap;
+use std::time::Instant;
+use tokio::sync::Mutex;
+
+/// Minutes a submitted transaction may be missing from the node's pending
+/// count before its nonce is considered free again
+pub const PROPAGATION_GRACE_MINUTES: i64 = 10;
+/// Seconds a nonce handed out by `reserve` stays taken while its
+/// transaction is signed and submitted
+const RESERVATION_SECONDS: u64 = 30;
+
+/// Next free nonce by chain and sender, with the time it was recorded
+type Reservations = HashMap<(u64, Address), (u64, Instant)>;
+
+static RESERVED: Mutex<Option<Reservations>> = Mutex::const_new(None);
+
+/// Next nonce for `from` on `chain_id`: the node's pending count, moved past
+/// recently submitted local transactions that directly follow it
+pub fn next_nonce(
+    transactions: &[PendingTx],
+    chain_id: u64,
+    from: Address,
+    node_pending: u64,
+    now: DateTime<Local>,
+) -> u64 {
+    let grace = Duration::minutes(PROPAGATION_GRACE_MINUTES);
+    let mut nonce = node_pending;
+    while transactions.iter().any(|tx| {
+        tx.chain_id == chain_id
+            && tx.from == from
+            && tx.nonce == nonce
+            && now - tx.submitted_at < grace
+    }) {
+        nonce += 1;
+    }
+    nonce
+}
+
+/// Take `nonce`, or the nonce after the last one reserved recently for the
+/// same chain and sender when that is higher, and record the one after it
+fn claim(
+    reserved: &mut Reservations,
+    chain_id: u64,
+    from: Address,
+    nonce: u64,
+    now: Instant,
+) -> u64 {
+    let window = std::time::Duration::from_secs(RESERVATION_SECONDS);
+    let nonce = match reserved.get(&(chain_id, from)) {
+        Some((next, at)) if now.duration_since(*at) < window => nonce.max(*next),
+        _ => nonce,
+    };
+    reserved.insert((chain_id, from), (nonce + 1, now));
+    nonce
+}
+
+/// Give back `nonce` when it is the last one `claim` handed out for the
+/// chain and sender, so a send that failed before broadcasting does not
+/// leave a gap in front of the next one
+fn unclaim(reserved: &mut Reservations, chain_id: u64, from: Address, nonce: u64) {
+    if let Some((next, _)) = reserved.get_mut(&(chain_id, from))
+        && *next == nonce + 1
+    {
+        *next = nonce;
+    }
+}
+
+/// Reconcile the pending store with the node and work out the next nonce for
+/// `from`, returning the chain ID, that nonce and the node's pending count
+async fn pending_nonce(provider: &RpcProvider, from: Address) -> Result<(u64, u64, u64)> {
+    let chain_id = provider
+        .get_chain_id()
+        .await
+        .map_err(|e| anyhow!("Failed to get chain ID: {}", e))?;
+    let mined = provider
+        .get_transaction_count(from)
+        .latest()
+        .await
+        .map_err(|e| anyhow!("Failed to get nonce: {}", e))?;
+    let node_pending = provider
+        .get_transaction_count(from)
+        .pending()
+        .await
+        .map_err(|e| anyhow!("Failed to get nonce: {}", e))?;
+
+    let mut store = PendingStore::load()?;
+    let before = store.transactions.len();
+    store
+        .transactions
+        .retain(|tx| tx.chain_id != chain_id || tx.from != from || tx.nonce >= mined);
+    if store.transactions.len() != before {
+        store.save()?;
+    }
+
+    let nonce = next_nonce(
+        &store.transactions,
+        chain_id,
+        from,
+        node_pending.max(mined),
+        Local::now(),
+    );
+    Ok((chain_id, nonce, node_pending))
+}
+
+/// Nonce the next transaction from `from` would get, without reserving it.
+/// For transactions that are signed elsewhere.
+pub async fn peek(provider: &RpcProvider, from: Address) -> Result<u64> {
+    let _reserved = RESERVED.lock().await;
+    Ok(pending_nonce(provider, from).await?.1)
+}
+
+/// Reconcile the pending store with the node and return the nonce for the
+/// next transaction from `from`. Concurrent calls wait for each other and
+/// get consecutive nonces. Call it right before signing, and `release` the
+/// nonce if the transaction is not broadcast.
+pub async fn reserve(provider: &RpcProvider, from: Address) -> Result<u64> {
+    let mut reserved = RESERVED.lock().await;
+    let (chain_id, nonce, node_pending) = pending_nonce(provider, from).await?;
+    let nonce = claim(
+        reserved.get_or_insert_with(HashMap::new),
+        chain_id,
+        from,
+        nonce,
+        Instant::now(),
+    );
+    if nonce > node_pending {
+        log::debug!(
+            "Using nonce {} for 0x{:x}: the node reports {}, {} transaction(s) not yet seen",
+            nonce,
+            from,
+            node_pending,
+            nonce - node_pending
+        );
+    }
+    Ok(nonce)
+}
+
+/// Release a nonce from `reserve` whose transaction was never broadcast
+pub async fn release(chain_id: u64, from: Address, nonce: u64) {
+    if let Some(reserved) = RESERVED.lock().await.as_mut() {
+        unclaim(reserved, chain_id, from, nonce);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{B256, Bytes, U256};
+
+    fn sent(nonce: u64, minutes_ago: i64, now: DateTime<Local>) -> PendingTx {
+        PendingTx {
+            hash: B256::repeat_byte(nonce as u8),
+            chain_id: 31,
+            from: Address::repeat_byte(1),
+            to: Address::repeat_byte(2),
+            value: U256::from(1),
+            input: Bytes::new(),
+            nonce,
+            gas_limit: 21_000,
+            gas_price: 60_000_000,
+            submitted_at: now - Duration::minutes(minutes_ago),
+            replaced_by: None,
+        }
+    }
+
+    #[test]
+    fn test_next_nonce_skips_unseen_transactions() {
+        let now = Local::now();
+        let from = Address::repeat_byte(1);
+
+        // Two sends the node has not seen yet follow its count of 5
+        let txs = vec![sent(5, 0, now), sent(6, 0, now)];
+        assert_eq!(next_nonce(&txs, 31, from, 5, now), 7);
+        // Already counted by the node
+        assert_eq!(next_nonce(&txs, 31, from, 7, now), 7);
+        // Other chains and senders do not matter
+        assert_eq!(next_nonce(&txs, 30, from, 5, now), 5);
+        assert_eq!(next_nonce(&txs, 31, Address::repeat_byte(9), 5, now), 5);
+
+        // A send the node still does not know after the grace period was
+        // dropped, so its nonce is used again
+        let stale = vec![sent(5, PROPAGATION_GRACE_MINUTES + 1, now)];
+        assert_eq!(next_nonce(&stale, 31, from, 5, now), 5);
+    }
+    #[test]
+    fn test_claim_hands_out_each_nonce_once() {
+        let from = Address::repeat_byte(1);
+        let now = Instant::now();
+        let mut reserved = Reservations::new();
+
+        // Two sends that both computed 5 before either was submitted
+        assert_eq!(claim(&mut reserved, 31, from, 5, now), 5);
+        assert_eq!(claim(&mut reserved, 31, from, 5, now), 6);
+        // The node moving ahead wins over the reservation
+        assert_eq!(claim(&mut reserved, 31, from, 9, now), 9);
+        // Other chains and senders are separate
+        assert_eq!(claim(&mut reserved, 30, from, 5, now), 5);
+        assert_eq!(claim(&mut reserved, 31, Address::repeat_byte(9), 5, now), 5);
+
+        // A released nonce is handed out again, unless a later one was
+        // claimed after it
+        unclaim(&mut reserved, 31, from, 9);
+        assert_eq!(claim(&mut reserved, 31, from, 5, now), 9);
+        assert_eq!(claim(&mut reserved, 31, from, 5, now), 10);
+        unclaim(&mut reserved, 31, from, 9);
+        assert_eq!(claim(&mut reserved, 31, from, 5, now), 11);
+
+        // A reservation whose send never showed up expires
+        let later = now + std::time::Duration::from_secs(RESERVATION_SECONDS + 1);
+        assert_eq!(claim(&mut reserved, 31, from, 5, later), 5);
+    }
+}
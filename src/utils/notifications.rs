@@ -0,0 +1,138 @@
+//! Native desktop notifications when a sent transaction confirms or a
+//! watched wallet receives a payment.
+//!
+//! Notifications are on by default and turned off with Configuration →
+//! Toggle Desktop Notifications or `config set desktop-notifications off`.
+//! They are best effort: without a graphical session, e.g. over SSH or on a
+//! server, nothing is shown and the command carries on. Redaction applies to
+//! them as it does to the terminal.
+
+use crate::config::ConfigManager;
+use crate::utils::history_export::ExportRow;
+use crate::utils::redact;
+use alloy::primitives::B256;
+use std::sync::OnceLock;
+
+const APP_NAME: &str = "Rootstock Wallet";
+
+static ENABLED: OnceLock<bool> = OnceLock::new();
+
+/// Whether the configuration allows notifications, read once per run
+pub fn enabled() -> bool {
+    *ENABLED.get_or_init(|| {
+        ConfigManager::new()
+            .and_then(|manager| manager.load())
+            .map(|config| config.desktop_notifications)
+            .unwrap_or(false)
+    })
+}
+
+/// Whether there is a desktop to show notifications on. Linux and the BSDs
+/// need an X11 or Wayland session; macOS and Windows always have one.
+fn has_desktop() -> bool {
+    if cfg!(any(target_os = "macos", target_os = "windows")) {
+        return true;
+    }
+    ["DISPLAY", "WAYLAND_DISPLAY"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some_and(|value| !value.is_empty()))
+}
+
+/// Show a notification, unless they are turned off or there is no desktop
+fn notify(summary: &str, body: &str) {
+    if !enabled() || !has_desktop() {
+        return;
+    }
+    if let Err(e) = notify_rust::Notification::new()
+        .appname(APP_NAME)
+        .summary(summary)
+        .body(body)
+        .show()
+    {
+        log::debug!("Failed to show a desktop notification: {}", e);
+    }
+}
+
+/// Tell that a sent transfer was mined, and whether it succeeded
+pub fn transfer_mined(tx_hash: B256, amount: &str, symbol: &str, succeeded: bool) {
+    let (summary, body) = transfer_message(tx_hash, amount, symbol, succeeded);
+    notify(&summary, &body);
+}
+
+/// Tell that queued transfer `id` could not be executed. Its transaction,
+/// once mined, is reported by `transfer_mined` like any other.
+pub fn queued_transfer_failed(id: u64, error: &str) {
+    notify(&format!("Queued transfer #{} failed", id), error);
+}
+
+/// Tell that wallet `name` received `payment`
+pub fn payment_received(payment: &ExportRow, name: &str) {
+    let (summary, body) = payment_message(payment, name);
+    notify(&summary, &body);
+}
+
+fn transfer_message(
+    tx_hash: B256,
+    amount: &str,
+    symbol: &str,
+    succeeded: bool,
+) -> (String, String) {
+    let summary = if succeeded {
+        "Transaction confirmed"
+    } else {
+        "Transaction failed"
+    };
+    let body = format!(
+        "{} {} · {}",
+        redact::amount(amount),
+        symbol,
+        redact::address(format!("0x{:x}", tx_hash))
+    );
+    (summary.to_string(), body)
+}
+
+fn payment_message(payment: &ExportRow, name: &str) -> (String, String) {
+    let summary = format!(
+        "Received {} {}",
+        redact::amount(&payment.amount),
+        payment.token.as_deref().unwrap_or("tokens")
+    );
+    let body = format!("To {} from {}", name, redact::address(&payment.from));
+    (summary, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_messages() {
+        let (summary, body) = transfer_message(B256::repeat_byte(0xab), "0.5", "RBTC", true);
+        assert_eq!(summary, "Transaction confirmed");
+        assert!(body.starts_with("0.5 RBTC · 0xabab"));
+        let (summary, _) = transfer_message(B256::ZERO, "1", "DOC", false);
+        assert_eq!(summary, "Transaction failed");
+
+        let payment = ExportRow {
+            hash: "0x01".to_string(),
+            timestamp: "2024-01-01T00:00:00+00:00".to_string(),
+            block_number: Some(1),
+            from: "0x02".to_string(),
+            to: Some("0x03".to_string()),
+            direction: "in".to_string(),
+            token: Some("USDRIF".to_string()),
+            token_address: Some("0x04".to_string()),
+            amount: "25".to_string(),
+            fee: None,
+            fiat_value_usd: None,
+            status: "success".to_string(),
+        };
+        assert_eq!(
+            payment_message(&payment, "savings"),
+            (
+                "Received 25 USDRIF".to_string(),
+                "To savings from 0x02".to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,355 @@
+//! Air-gapped signing.
+//!
+//! An online machine builds a transaction and writes it out unsigned; an
+//! offline machine holding the key signs it; the signed blob is carried back
+//! to an online machine and broadcast. Payloads are JSON files. An unsigned
+//! payload can also travel as a sequence of QR frames, and a signed one as a
+//! single QR code, read back from photos or screenshots.
+
+use crate::qr;
+use crate::types::network::Network;
+use alloy::consensus::{SignableTransaction, Transaction, TxEnvelope, TypedTransaction};
+use alloy::eips::eip2718::Decodable2718;
+use alloy::primitives::{Address, B256, Bytes, U256};
+use alloy::rpc::types::TransactionRequest;
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const UNSIGNED_FORMAT: &str = "rootstock-wallet/unsigned-tx/1";
+pub const SIGNED_FORMAT: &str = "rootstock-wallet/signed-tx/1";
+
+/// Prefix of a QR frame: `rwtx:<index>/<total>:<chunk>`
+const FRAME_PREFIX: &str = "rwtx:";
+/// Payload bytes per QR frame, small enough to scan from a phone photo
+const FRAME_SIZE: usize = 300;
+
+/// A transaction ready to be signed on another machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnsignedTx {
+    pub format: String,
+    pub created_at: DateTime<Utc>,
+    /// Summary shown on the offline machine before signing
+    pub description: String,
+    /// Fully populated request: sender, nonce, gas, chain id
+    pub request: TransactionRequest,
+    /// RLP of the transaction as it is signed (EIP-155), for signers that
+    /// work on raw payloads
+    pub signing_payload: Bytes,
+}
+
+impl UnsignedTx {
+    pub fn new(request: TransactionRequest, description: String) -> Result<Self> {
+        Ok(Self {
+            format: UNSIGNED_FORMAT.to_string(),
+            created_at: Utc::now(),
+            description,
+            signing_payload: signing_payload(&request)?,
+            request,
+        })
+    }
+
+    /// Check the format and that the request and signing payload agree, so
+    /// the summary shown is what gets signed
+    pub fn validate(&self) -> Result<()> {
+        if self.format != UNSIGNED_FORMAT {
+            return Err(anyhow!(
+                "Unsupported unsigned transaction format: {}",
+                self.format
+            ));
+        }
+        if signing_payload(&self.request)? != self.signing_payload {
+            return Err(anyhow!(
+                "The signing payload does not match the transaction request"
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Read an unsigned transaction from its JSON file, or from images of
+    /// all its QR frames (in any order)
+    pub fn read(inputs: &[PathBuf]) -> Result<Self> {
+        let json = match inputs {
+            [] => return Err(anyhow!("No input given")),
+            [path] if !is_image(path) => fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?,
+            paths => {
+                let frames = paths
+                    .iter()
+                    .map(|path| qr::scan_image(path))
+                    .collect::<Result<Vec<_>>>()?;
+                join_frames(&frames)?
+            }
+        };
+        let unsigned: Self =
+            serde_json::from_str(&json).context("Not an unsigned transaction payload")?;
+        unsigned.validate()?;
+        Ok(unsigned)
+    }
+}
+
+/// A transaction signed on the offline machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedTxFile {
+    pub format: String,
+    pub hash: B256,
+    pub from: Address,
+    /// EIP-2718 encoded signed transaction
+    pub raw: Bytes,
+}
+
+impl SignedTxFile {
+    pub fn new(raw: Vec<u8>) -> Result<Self> {
+        let decoded = DecodedTx::decode(&raw)?;
+        Ok(Self {
+            format: SIGNED_FORMAT.to_string(),
+            hash: decoded.hash,
+            from: decoded.from,
+            raw: raw.into(),
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// A signed transaction, decoded for review before broadcasting
+#[derive(Debug, Clone)]
+pub struct DecodedTx {
+    pub hash: B256,
+    /// Sender, recovered from the signature
+    pub from: Address,
+    pub to: Option<Address>,
+    pub value: U256,
+    pub nonce: u64,
+    pub gas_limit: u64,
+    pub gas_price: Option<u128>,
+    pub chain_id: Option<u64>,
+    pub input: Bytes,
+    pub envelope: TxEnvelope,
+}
+
+impl DecodedTx {
+    pub fn decode(raw: &[u8]) -> Result<Self> {
+        let envelope = TxEnvelope::decode_2718(&mut &raw[..])
+            .map_err(|e| anyhow!("Not a signed transaction: {}", e))?;
+        let from = envelope
+            .recover_signer()
+            .map_err(|e| anyhow!("Invalid transaction signature: {}", e))?;
+        Ok(Self {
+            hash: *envelope.tx_hash(),
+            from,
+            to: envelope.to(),
+            value: envelope.value(),
+            nonce: envelope.nonce(),
+            gas_limit: envelope.gas_limit(),
+            gas_price: envelope.gas_price(),
+            chain_id: envelope.chain_id(),
+            input: envelope.input().clone(),
+            envelope,
+        })
+    }
+
+    /// The request this transaction was signed from, for the pending store
+    pub fn request(&self) -> TransactionRequest {
+        let request: TransactionRequest = self.envelope.clone().into();
+        request.from(self.from)
+    }
+}
+
+/// Refuse a transaction from elsewhere unless it is bound to the chain of
+/// `network`. Transactions without a chain id (pre-EIP-155) are valid on
+/// every chain, so a testnet one could be replayed on mainnet.
+pub fn check_chain_id(chain_id: Option<u64>, network: Network) -> Result<()> {
+    match chain_id {
+        None => Err(anyhow!(
+            "The transaction has no chain id, so it could be replayed on any network; it is refused"
+        )),
+        Some(id) if id != network.chain_id() => Err(anyhow!(
+            "The transaction is for chain {}, but the active network is {} (chain {}). \
+             Switch networks first if this is intended.",
+            id,
+            network,
+            network.chain_id()
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Read a signed transaction given as 0x-prefixed hex, a file written by
+/// `tx sign`, a file holding the hex, or an image of its QR code
+pub fn read_signed(input: &str) -> Result<Vec<u8>> {
+    let input = input.trim();
+    if let Some(hex) = input.strip_prefix("0x") {
+        return alloy::primitives::hex::decode(hex).context("Invalid hex");
+    }
+
+    let path = Path::new(input);
+    let content = if is_image(path) {
+        qr::scan_image(path)?
+    } else {
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?
+    };
+    let content = content.trim();
+    if let Ok(signed) = serde_json::from_str::<SignedTxFile>(content) {
+        return Ok(signed.raw.to_vec());
+    }
+    let hex = content.strip_prefix("0x").unwrap_or(content);
+    alloy::primitives::hex::decode(hex).context("Not a signed transaction file or hex blob")
+}
+
+/// RLP of the transaction as it is signed
+fn signing_payload(request: &TransactionRequest) -> Result<Bytes> {
+    let tx = request
+        .clone()
+        .build_typed_tx()
+        .map_err(|_| anyhow!("The transaction is missing fields needed for signing"))?;
+    match tx {
+        TypedTransaction::Legacy(tx) => Ok(tx.encoded_for_signing().into()),
+        _ => Err(anyhow!(
+            "Only legacy (gas price) transactions are supported"
+        )),
+    }
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext.to_lowercase().as_str(), "png" | "jpg" | "jpeg"))
+}
+
+/// Split a payload into numbered QR frames
+pub fn to_frames(payload: &str) -> Vec<String> {
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(FRAME_SIZE).collect();
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            format!(
+                "{}{}/{}:{}",
+                FRAME_PREFIX,
+                i + 1,
+                chunks.len(),
+                String::from_utf8_lossy(chunk)
+            )
+        })
+        .collect()
+}
+
+/// Reassemble a payload from its frames, given in any order
+pub fn join_frames(frames: &[String]) -> Result<String> {
+    let mut parts: Vec<(usize, usize, &str)> = frames
+        .iter()
+        .map(|frame| {
+            let rest = frame
+                .strip_prefix(FRAME_PREFIX)
+                .ok_or_else(|| anyhow!("Not a transaction QR frame"))?;
+            let (position, chunk) = rest
+                .split_once(':')
+                .ok_or_else(|| anyhow!("Malformed QR frame"))?;
+            let (index, total) = position
+                .split_once('/')
+                .and_then(|(i, t)| Some((i.parse().ok()?, t.parse().ok()?)))
+                .ok_or_else(|| anyhow!("Malformed QR frame number: {}", position))?;
+            Ok((index, total, chunk))
+        })
+        .collect::<Result<_>>()?;
+    parts.sort_by_key(|(index, _, _)| *index);
+    parts.dedup_by_key(|(index, _, _)| *index);
+
+    let total = parts.first().map(|(_, total, _)| *total).unwrap_or(0);
+    let complete = parts.len() == total
+        && parts
+            .iter()
+            .enumerate()
+            .all(|(i, (index, t, _))| *index == i + 1 && *t == total);
+    if !complete {
+        return Err(anyhow!(
+            "Got {} of {} QR frames; scan all of them",
+            parts.len(),
+            total
+        ));
+    }
+    Ok(parts.iter().map(|(_, _, chunk)| *chunk).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::network::TransactionBuilder;
+    use alloy::signers::local::PrivateKeySigner;
+
+    #[tokio::test]
+    async fn test_unsigned_round_trip() {
+        let signer = PrivateKeySigner::random();
+        let request = TransactionRequest::default()
+            .with_from(signer.address())
+            .with_to(Address::repeat_byte(2))
+            .with_value(U256::from(1000))
+            .with_nonce(7)
+            .with_gas_price(65_000_000)
+            .with_gas_limit(21_000)
+            .with_chain_id(31);
+        let unsigned = UnsignedTx::new(request, "test".to_string()).unwrap();
+
+        // Frames survive being read back in any order
+        let json = serde_json::to_string(&unsigned).unwrap();
+        let mut frames = to_frames(&json);
+        assert!(frames.len() > 1);
+        frames.reverse();
+        assert_eq!(join_frames(&frames).unwrap(), json);
+        assert!(join_frames(&frames[1..]).is_err());
+
+        let mut tampered = unsigned.clone();
+        tampered.request.value = Some(U256::from(2000));
+        assert!(tampered.validate().is_err());
+
+        let raw = crate::utils::signer::WalletSigner::local(signer.clone())
+            .sign_transaction(unsigned.request.clone())
+            .await
+            .unwrap();
+        let decoded = DecodedTx::decode(&raw).unwrap();
+        assert_eq!(decoded.from, signer.address());
+        assert_eq!(
+            (decoded.nonce, decoded.chain_id, decoded.value),
+            (7, Some(31), U256::from(1000))
+        );
+        let hex = format!("0x{}", alloy::primitives::hex::encode(&raw));
+        assert_eq!(read_signed(&hex).unwrap(), raw);
+    }
+
+    #[tokio::test]
+    async fn test_check_chain_id_refuses_other_networks() {
+        let signer = PrivateKeySigner::random();
+        let request = TransactionRequest::default()
+            .with_from(signer.address())
+            .with_to(Address::repeat_byte(2))
+            .with_nonce(0)
+            .with_gas_price(65_000_000)
+            .with_gas_limit(21_000)
+            .with_chain_id(Network::Mainnet.chain_id());
+        let raw = crate::utils::signer::WalletSigner::local(signer)
+            .sign_transaction(request)
+            .await
+            .unwrap();
+        let decoded = DecodedTx::decode(&raw).unwrap();
+
+        assert!(check_chain_id(decoded.chain_id, Network::Mainnet).is_ok());
+        let err = check_chain_id(decoded.chain_id, Network::Testnet)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("chain 30"), "{}", err);
+        assert!(err.contains("(chain 31)"), "{}", err);
+        assert!(check_chain_id(decoded.chain_id, Network::Regtest).is_err());
+        assert!(check_chain_id(None, Network::Mainnet).is_err());
+    }
+}
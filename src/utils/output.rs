@@ -0,0 +1,80 @@
+//! Machine-readable output, turned on with `--json`.
+//!
+//! In JSON mode a command writes one JSON document to stdout, so that it can
+//! be piped into `jq` or read by other tools, and everything else it prints
+//! (tables, progress, warnings) goes to stderr. Rather than teaching every
+//! `println!` about the mode, stdout is moved aside once at start-up: the
+//! JSON goes to a copy of the original stdout and file descriptor 1 is
+//! pointed at stderr. On platforms without `dup2` the human output stays on
+//! stdout next to the JSON.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::fs::File;
+use std::io::Write;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static JSON: AtomicBool = AtomicBool::new(false);
+/// The original stdout, once human output has been moved to stderr
+static STDOUT: OnceLock<File> = OnceLock::new();
+
+pub fn is_json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// Turn on JSON mode for the rest of the process and send whatever is
+/// printed to stdout from now on to stderr
+pub fn enable_json() -> Result<()> {
+    JSON.store(true, Ordering::Relaxed);
+    #[cfg(unix)]
+    {
+        use std::os::fd::AsFd;
+
+        std::io::stdout().flush()?;
+        let original = std::io::stdout().as_fd().try_clone_to_owned()?;
+        rustix::stdio::dup2_stdout(std::io::stderr())?;
+        let _ = STDOUT.set(File::from(original));
+    }
+    Ok(())
+}
+
+/// Write `value` as the command's JSON result. Does nothing outside JSON
+/// mode, so commands can call it unconditionally.
+pub fn emit<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+    if !is_json() {
+        return Ok(());
+    }
+    let json = serde_json::to_string_pretty(value)?;
+    match STDOUT.get() {
+        Some(mut stdout) => {
+            writeln!(stdout, "{}", json)?;
+            stdout.flush()?;
+        }
+        None => {
+            let mut stdout = std::io::stdout().lock();
+            writeln!(stdout, "{}", json)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emit_outside_json_mode() {
+        assert!(!is_json());
+        // Nothing is written, and values that cannot be serialized are not
+        // even looked at
+        struct Unserializable;
+        impl Serialize for Unserializable {
+            fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+                panic!("serialized outside JSON mode")
+            }
+        }
+        assert!(emit(&Unserializable).is_ok());
+    }
+}
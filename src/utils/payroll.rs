@@ -0,0 +1,415 @@
+//! Payroll roster and monthly payout batches.
+//!
+//! The roster lists contacts with their salary and the asset they are paid
+//! in. Each month a batch is generated from it, freezing the recipients'
+//! addresses and amounts; the batch is approved, paid and finally archived
+//! with a report signed by the paying wallet.
+
+use crate::types::contacts::Contact;
+use crate::utils::categories::AssetFilter;
+use crate::utils::storage::Storage;
+use alloy::primitives::{Address, B256, PrimitiveSignature, U256, utils::format_units};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RosterEntry {
+    /// Contact name; the address is looked up when a batch is generated
+    pub contact: String,
+    /// Salary in asset units, e.g. "1500"
+    pub amount: String,
+    pub asset: AssetFilter,
+}
+
+impl RosterEntry {
+    pub fn new(contact: &str, amount: &str, asset: AssetFilter) -> Result<Self> {
+        let entry = Self {
+            contact: contact.to_string(),
+            amount: amount.trim().to_string(),
+            asset,
+        };
+        entry.value()?;
+        Ok(entry)
+    }
+
+    /// The salary in the asset's smallest unit
+    pub fn value(&self) -> Result<U256> {
+        let value: U256 =
+            alloy::primitives::utils::parse_units(&self.amount, self.asset.decimals())
+                .map_err(|e| anyhow!("Invalid amount for {}: {}", self.contact, e))?
+                .into();
+        if value.is_zero() {
+            return Err(anyhow!("The amount for {} must be positive", self.contact));
+        }
+        Ok(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchStatus {
+    /// Generated and waiting for approval
+    Draft,
+    /// Approved and ready to be paid
+    Approved,
+    /// Every payout was sent and the report is archived
+    Executed,
+    /// Some payouts could not be sent; running it again pays the rest
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Payout {
+    pub contact: String,
+    pub address: Address,
+    pub asset: AssetFilter,
+    pub value: U256,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<B256>,
+    /// Broadcast transaction whose receipt has not been seen yet; checked
+    /// before the payout is sent again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_hash: Option<B256>,
+}
+
+impl Payout {
+    pub fn formatted_amount(&self) -> String {
+        format_units(self.value, self.asset.decimals()).unwrap_or_else(|_| self.value.to_string())
+    }
+}
+
+/// Payroll report signed by the wallet that paid it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedReport {
+    /// The report as JSON; the signature covers these exact bytes
+    pub report: String,
+    pub signer: Address,
+    /// personal_sign signature over `report`
+    pub signature: String,
+}
+
+impl SignedReport {
+    /// Check the signature was made by `signer` over the report
+    pub fn verify(&self) -> Result<()> {
+        let signature = PrimitiveSignature::from_str(&self.signature)
+            .map_err(|e| anyhow!("Invalid report signature: {}", e))?;
+        let recovered = signature
+            .recover_address_from_msg(self.report.as_bytes())
+            .map_err(|e| anyhow!("Failed to recover report signer: {}", e))?;
+        if recovered != self.signer {
+            return Err(anyhow!(
+                "Report was signed by 0x{:x}, not by 0x{:x}",
+                recovered,
+                self.signer
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayrollBatch {
+    /// Month being paid, as YYYY-MM
+    pub month: String,
+    /// Wallet (address key) that pays the batch
+    pub wallet: String,
+    pub payouts: Vec<Payout>,
+    pub status: BatchStatus,
+    pub created_at: DateTime<Local>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report: Option<SignedReport>,
+}
+
+impl PayrollBatch {
+    /// Total per asset, in roster order
+    pub fn totals(&self) -> Vec<(AssetFilter, U256)> {
+        let mut totals: Vec<(AssetFilter, U256)> = Vec::new();
+        for payout in &self.payouts {
+            match totals.iter_mut().find(|(asset, _)| *asset == payout.asset) {
+                Some((_, total)) => *total += payout.value,
+                None => totals.push((payout.asset.clone(), payout.value)),
+            }
+        }
+        totals
+    }
+
+    /// Check the batch may be paid: it must have been approved, whether or
+    /// not roles are enabled, and not be fully paid already. A failed batch
+    /// was approved before its first run and may be resumed.
+    pub fn ensure_payable(&self) -> Result<()> {
+        match self.status {
+            BatchStatus::Approved | BatchStatus::Failed => Ok(()),
+            BatchStatus::Executed => Err(anyhow!("The {} batch is already paid", self.month)),
+            BatchStatus::Draft => Err(anyhow!(
+                "The {} batch must be approved with `payroll approve` before it is paid",
+                self.month
+            )),
+        }
+    }
+
+    /// Payouts that have not been sent yet
+    pub fn unpaid(&self) -> impl Iterator<Item = &Payout> {
+        self.payouts.iter().filter(|p| p.tx_hash.is_none())
+    }
+
+    /// Transactions broadcast for unpaid payouts in `asset` whose receipts
+    /// have not been seen yet
+    pub fn pending_hashes(&self, asset: &AssetFilter) -> Vec<B256> {
+        let mut hashes: Vec<B256> = self
+            .unpaid()
+            .filter(|p| &p.asset == asset)
+            .filter_map(|p| p.pending_hash)
+            .collect();
+        hashes.sort();
+        hashes.dedup();
+        hashes
+    }
+
+    /// Mark the payouts waiting on `tx_hash` paid by it
+    pub fn confirm_pending(&mut self, tx_hash: B256) {
+        for payout in &mut self.payouts {
+            if payout.pending_hash == Some(tx_hash) {
+                payout.tx_hash = Some(tx_hash);
+                payout.pending_hash = None;
+            }
+        }
+    }
+
+    /// Forget `tx_hash` so the payouts waiting on it are sent again
+    pub fn clear_pending(&mut self, tx_hash: B256) {
+        for payout in &mut self.payouts {
+            if payout.pending_hash == Some(tx_hash) {
+                payout.pending_hash = None;
+            }
+        }
+    }
+
+    /// JSON report of the paid batch, the content that gets signed
+    pub fn report_json(&self) -> Result<String> {
+        let payouts: Vec<_> = self
+            .payouts
+            .iter()
+            .map(|p| {
+                serde_json::json!({
+                    "contact": p.contact,
+                    "address": format!("{:#x}", p.address),
+                    "asset": p.asset.symbol(),
+                    "amount": p.formatted_amount(),
+                    "tx_hash": p.tx_hash.map(|h| format!("{:#x}", h)),
+                })
+            })
+            .collect();
+        let totals: Vec<_> = self
+            .totals()
+            .iter()
+            .map(|(asset, total)| {
+                serde_json::json!({
+                    "asset": asset.symbol(),
+                    "amount": format_units(*total, asset.decimals()).unwrap_or_default(),
+                })
+            })
+            .collect();
+        Ok(serde_json::to_string_pretty(&serde_json::json!({
+            "month": self.month,
+            "wallet": self.wallet,
+            "created_by": self.created_by,
+            "approved_by": self.approved_by,
+            "paid_at": Local::now().to_rfc3339(),
+            "payouts": payouts,
+            "totals": totals,
+        }))?)
+    }
+}
+
+/// Check a month is given as YYYY-MM
+pub fn parse_month(month: &str) -> Result<String> {
+    NaiveDate::parse_from_str(&format!("{}-01", month.trim()), "%Y-%m-%d")
+        .map(|date| date.format("%Y-%m").to_string())
+        .map_err(|_| anyhow!("Invalid month '{}'. Use YYYY-MM", month))
+}
+
+pub fn current_month() -> String {
+    Local::now().format("%Y-%m").to_string()
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Payroll {
+    #[serde(default)]
+    pub roster: Vec<RosterEntry>,
+    #[serde(default)]
+    pub batches: Vec<PayrollBatch>,
+}
+
+impl Payroll {
+    pub fn load() -> Result<Self> {
+        Storage::open()?.load_payroll()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        Storage::open()?.save_payroll(self)
+    }
+
+    /// Add a roster entry, replacing any existing entry for the contact
+    pub fn set_salary(&mut self, entry: RosterEntry) {
+        match self.roster.iter_mut().find(|e| e.contact == entry.contact) {
+            Some(existing) => *existing = entry,
+            None => self.roster.push(entry),
+        }
+    }
+
+    pub fn remove_from_roster(&mut self, contact: &str) -> Result<()> {
+        let index = self
+            .roster
+            .iter()
+            .position(|e| e.contact == contact)
+            .ok_or_else(|| anyhow!("'{}' is not on the payroll roster", contact))?;
+        self.roster.remove(index);
+        Ok(())
+    }
+
+    pub fn batch(&self, month: &str) -> Result<&PayrollBatch> {
+        self.batches
+            .iter()
+            .find(|b| b.month == month)
+            .ok_or_else(|| anyhow!("No payroll batch for {}", month))
+    }
+
+    pub fn batch_mut(&mut self, month: &str) -> Result<&mut PayrollBatch> {
+        self.batches
+            .iter_mut()
+            .find(|b| b.month == month)
+            .ok_or_else(|| anyhow!("No payroll batch for {}", month))
+    }
+
+    /// Generate the month's batch from the roster, resolving each contact's
+    /// current address. A month can only be generated once.
+    pub fn generate(
+        &mut self,
+        month: &str,
+        wallet: String,
+        contacts: &[Contact],
+        created_by: Option<String>,
+    ) -> Result<&PayrollBatch> {
+        if self.batches.iter().any(|b| b.month == month) {
+            return Err(anyhow!("A payroll batch for {} already exists", month));
+        }
+        if self.roster.is_empty() {
+            return Err(anyhow!("The payroll roster is empty"));
+        }
+
+        let payouts = self
+            .roster
+            .iter()
+            .map(|entry| {
+                let contact = contacts
+                    .iter()
+                    .find(|c| c.name == entry.contact)
+                    .ok_or_else(|| anyhow!("Contact '{}' not found", entry.contact))?;
+                Ok(Payout {
+                    contact: entry.contact.clone(),
+                    address: contact.address,
+                    asset: entry.asset.clone(),
+                    value: entry.value()?,
+                    tx_hash: None,
+                    pending_hash: None,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.batches.push(PayrollBatch {
+            month: month.to_string(),
+            wallet,
+            payouts,
+            status: BatchStatus::Draft,
+            created_at: Local::now(),
+            created_by,
+            approved_by: None,
+            error: None,
+            report: None,
+        });
+        Ok(self.batches.last().expect("batch was just added"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::signers::{SignerSync, local::PrivateKeySigner};
+
+    #[test]
+    fn test_generate_batch() {
+        let usdt = AssetFilter::Token {
+            symbol: "USDT".to_string(),
+            address: Address::repeat_byte(7),
+            decimals: 6,
+        };
+        let contacts = vec![
+            Contact::new("alice".into(), Address::repeat_byte(1), None, vec![]),
+            Contact::new("bob".into(), Address::repeat_byte(2), None, vec![]),
+        ];
+        let mut payroll = Payroll::default();
+        payroll.set_salary(RosterEntry::new("alice", "1000", usdt.clone()).unwrap());
+        payroll.set_salary(RosterEntry::new("bob", "1500.5", usdt.clone()).unwrap());
+        payroll.set_salary(RosterEntry::new("alice", "1200", usdt.clone()).unwrap());
+        assert!(RosterEntry::new("carol", "0", AssetFilter::Rbtc).is_err());
+        assert_eq!(payroll.roster.len(), 2);
+
+        let batch = payroll
+            .generate("2026-10", "0xwallet".to_string(), &contacts, None)
+            .unwrap();
+        assert_eq!(batch.payouts[0].address, Address::repeat_byte(1));
+        assert_eq!(batch.totals(), vec![(usdt.clone(), U256::from(2_700_500_000u64))]);
+        assert!(
+            payroll
+                .generate("2026-10", "0xwallet".to_string(), &contacts, None)
+                .is_err()
+        );
+
+        let mut batch = payroll.batch("2026-10").unwrap().clone();
+        assert!(batch.ensure_payable().is_err());
+        batch.status = BatchStatus::Approved;
+        assert!(batch.ensure_payable().is_ok());
+        batch.status = BatchStatus::Failed;
+        assert!(batch.ensure_payable().is_ok());
+        batch.status = BatchStatus::Executed;
+        assert!(batch.ensure_payable().is_err());
+
+        let (first, second) = (B256::repeat_byte(1), B256::repeat_byte(2));
+        batch.payouts[0].pending_hash = Some(first);
+        batch.payouts[1].pending_hash = Some(second);
+        assert_eq!(batch.pending_hashes(&usdt), vec![first, second]);
+        assert!(batch.pending_hashes(&AssetFilter::Rbtc).is_empty());
+        batch.confirm_pending(first);
+        batch.clear_pending(second);
+        assert_eq!(batch.payouts[0].tx_hash, Some(first));
+        assert_eq!(batch.unpaid().count(), 1);
+        assert!(batch.pending_hashes(&usdt).is_empty());
+
+        assert_eq!(parse_month("2026-3").unwrap(), "2026-03");
+        assert!(parse_month("October").is_err());
+    }
+
+    #[test]
+    fn test_signed_report() {
+        let signer = PrivateKeySigner::random();
+        let report = "{\"month\":\"2026-10\"}".to_string();
+        let signature = signer.sign_message_sync(report.as_bytes()).unwrap();
+        let mut signed = SignedReport {
+            report,
+            signer: signer.address(),
+            signature: format!("0x{}", hex::encode(signature.as_bytes())),
+        };
+        signed.verify().unwrap();
+
+        signed.report.push(' ');
+        assert!(signed.verify().is_err());
+    }
+}
@@ -0,0 +1,165 @@
+//! Local store of submitted transactions that may still be pending.
+//!
+//! Every transaction the wallet submits is recorded with the fields needed
+//! to re-sign it, so a stuck transaction can be replaced with the same nonce:
+//! either the same transaction at a higher gas price (speed-up) or an empty
+//! transfer to the sender (cancel). Entries are dropped once their nonce has
+//! been used by a mined transaction.
+
+use crate::utils::eth::EthClient;
+use crate::utils::storage::Storage;
+use alloy::primitives::{Address, B256, Bytes, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionRequest;
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// Rootstock nodes only accept a replacement whose gas price is this many
+/// percent above the transaction it replaces
+pub const GAS_PRICE_BUMP_PERCENT: u128 = 40;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingTx {
+    pub hash: B256,
+    pub chain_id: u64,
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+    #[serde(default)]
+    pub input: Bytes,
+    pub nonce: u64,
+    pub gas_limit: u64,
+    pub gas_price: u128,
+    pub submitted_at: DateTime<Local>,
+    /// Hash of the transaction that replaced this one, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<B256>,
+}
+
+impl PendingTx {
+    /// The stored form of a submitted transaction request; `None` when the
+    /// request lacks a field needed to replace it
+    pub fn from_request(hash: B256, tx: &TransactionRequest) -> Option<Self> {
+        Some(Self {
+            hash,
+            chain_id: tx.chain_id?,
+            from: tx.from?,
+            to: *tx.to?.to()?,
+            value: tx.value.unwrap_or_default(),
+            input: tx.input.input().cloned().unwrap_or_default(),
+            nonce: tx.nonce?,
+            gas_limit: tx.gas?,
+            gas_price: tx.gas_price?,
+            submitted_at: Local::now(),
+            replaced_by: None,
+        })
+    }
+
+    /// Whether this is an empty transfer to the sender, i.e. a cancellation
+    pub fn is_cancellation(&self) -> bool {
+        self.to == self.from && self.value.is_zero() && self.input.is_empty()
+    }
+}
+
+/// Lowest gas price a replacement of a transaction priced `gas_price` may use
+pub fn min_replacement_gas_price(gas_price: u128) -> u128 {
+    (gas_price * (100 + GAS_PRICE_BUMP_PERCENT)).div_ceil(100)
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PendingStore {
+    #[serde(default)]
+    pub transactions: Vec<PendingTx>,
+}
+
+impl PendingStore {
+    pub fn load() -> Result<Self> {
+        Storage::open()?.load_pending()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        Storage::open()?.save_pending(self)
+    }
+
+    /// Remember a transaction that was just submitted
+    pub fn record(tx: PendingTx) -> Result<()> {
+        let mut store = Self::load()?;
+        store.transactions.retain(|t| t.hash != tx.hash);
+        store.transactions.push(tx);
+        store.save()
+    }
+
+    pub fn mark_replaced(&mut self, hash: B256, replacement: B256) {
+        if let Some(tx) = self.transactions.iter_mut().find(|t| t.hash == hash) {
+            tx.replaced_by = Some(replacement);
+        }
+    }
+
+    /// Drop transactions on the client's chain whose nonce was used by a
+    /// mined transaction, and return the ones still waiting, oldest nonce
+    /// first. Replaced transactions are not returned.
+    pub async fn refresh(&mut self, client: &EthClient) -> Result<Vec<PendingTx>> {
+        let chain_id = client.provider().get_chain_id().await?;
+        let mut senders: Vec<Address> = self
+            .transactions
+            .iter()
+            .filter(|t| t.chain_id == chain_id)
+            .map(|t| t.from)
+            .collect();
+        senders.sort();
+        senders.dedup();
+
+        for sender in senders {
+            let mined = client
+                .provider()
+                .get_transaction_count(sender)
+                .latest()
+                .await
+                .map_err(|e| anyhow!("Failed to get nonce: {}", e))?;
+            self.transactions
+                .retain(|t| t.chain_id != chain_id || t.from != sender || t.nonce >= mined);
+        }
+        self.save()?;
+
+        let mut pending: Vec<PendingTx> = self
+            .transactions
+            .iter()
+            .filter(|t| t.chain_id == chain_id && t.replaced_by.is_none())
+            .cloned()
+            .collect();
+        pending.sort_by_key(|t| (t.from, t.nonce));
+        Ok(pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replacement_from_request() {
+        use alloy::network::TransactionBuilder;
+
+        assert_eq!(min_replacement_gas_price(60_000_000), 84_000_000);
+        assert_eq!(min_replacement_gas_price(1), 2);
+
+        let from = Address::repeat_byte(1);
+        let request = TransactionRequest::default()
+            .with_from(from)
+            .with_to(Address::repeat_byte(2))
+            .with_value(U256::from(5))
+            .with_nonce(7)
+            .with_gas_price(60_000_000)
+            .with_gas_limit(21_000)
+            .with_chain_id(31);
+        let tx = PendingTx::from_request(B256::repeat_byte(3), &request).unwrap();
+        assert_eq!((tx.nonce, tx.gas_limit, tx.chain_id), (7, 21_000, 31));
+        assert!(!tx.is_cancellation());
+
+        // Requests without a nonce cannot be replaced
+        let mut incomplete = request.clone();
+        incomplete.nonce = None;
+        assert!(PendingTx::from_request(B256::ZERO, &incomplete).is_none());
+    }
+}
@@ -0,0 +1,145 @@
+//! Curated contract profiles for well-known Rootstock protocols.
+//!
+//! Addresses are taken from the protocols' published deployments. When adding
+//! or updating an entry, verify it against the protocol documentation and the
+//! Rootstock explorer before committing.
+
+use alloy::primitives::Address;
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+const PROFILES_JSON: &str = include_str!("../../res/profiles.json");
+static PROFILES: OnceLock<Vec<ContractProfile>> = OnceLock::new();
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ContractProfile {
+    pub protocol: String,
+    pub name: String,
+    pub network: String,
+    pub address: Address,
+    /// Key of the bundled ABI for this contract (see [`ContractProfile::abi_json`])
+    pub abi: String,
+}
+
+impl ContractProfile {
+    /// Returns the bundled ABI JSON for this contract, if one is shipped
+    pub fn abi_json(&self) -> Option<&'static str> {
+        match self.abi.as_str() {
+            "erc20" => Some(include_str!("../../res/erc20.abi.json")),
+            "rns_registry" => Some(include_str!("../abi/RNSRegistry.json")),
+            "bridge" => Some(include_str!("../abi/Bridge.json")),
+            _ => None,
+        }
+    }
+}
+
+/// Result of comparing an address against the curated profiles
+#[derive(Debug, Clone)]
+pub enum ProfileMatch {
+    /// The address is a known, verified contract
+    Verified(ContractProfile),
+    /// The address closely resembles a known contract but is not it
+    LookAlike(ContractProfile),
+    /// The address is not related to any curated contract
+    Unknown,
+}
+
+/// All curated profiles shipped with the wallet
+pub fn curated_profiles() -> &'static [ContractProfile] {
+    PROFILES.get_or_init(|| {
+        serde_json::from_str(PROFILES_JSON).expect("Bundled contract profiles are invalid")
+    })
+}
+
+/// Curated profiles for a single network ("mainnet" or "testnet")
+pub fn profiles_for_network(network: &str) -> Vec<&'static ContractProfile> {
+    let network = network.to_lowercase();
+    curated_profiles()
+        .iter()
+        .filter(|p| p.network == network)
+        .collect()
+}
+
+/// Classify an address against the curated profiles of a network
+pub fn check_address(network: &str, address: &Address) -> ProfileMatch {
+    let profiles = profiles_for_network(network);
+
+    if let Some(profile) = profiles.iter().find(|p| p.address == *address) {
+        return ProfileMatch::Verified((*profile).clone());
+    }
+
+    let candidate = hex::encode(address.as_slice());
+    for profile in profiles {
+        let known = hex::encode(profile.address.as_slice());
+        if is_look_alike(&candidate, &known) {
+            return ProfileMatch::LookAlike(profile.clone());
+        }
+    }
+
+    ProfileMatch::Unknown
+}
+
+/// Refuse addresses that imitate a curated contract; verified and unknown
+/// addresses are allowed through.
+pub fn ensure_not_look_alike(network: &str, address: &Address) -> Result<()> {
+    if let ProfileMatch::LookAlike(profile) = check_address(network, address) {
+        return Err(anyhow!(
+            "Address {:#x} looks like {} ({}) at {:#x} but does not match it. Refusing to continue.",
+            address,
+            profile.name,
+            profile.protocol,
+            profile.address
+        ));
+    }
+    Ok(())
+}
+
+/// Find a curated profile by protocol and contract name (case-insensitive)
+pub fn find_profile(network: &str, name: &str) -> Option<&'static ContractProfile> {
+    let name = name.to_lowercase();
+    profiles_for_network(network)
+        .into_iter()
+        .find(|p| p.name.to_lowercase().contains(&name))
+}
+
+/// Two distinct addresses are look-alikes when they share the leading and
+/// trailing characters users typically check by eye.
+fn is_look_alike(candidate: &str, known: &str) -> bool {
+    candidate != known && candidate[..4] == known[..4] && candidate[36..] == known[36..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_bundled_profiles_parse() {
+        assert!(!curated_profiles().is_empty());
+        for profile in curated_profiles() {
+            assert!(profile.abi_json().is_some(), "missing ABI for {}", profile.name);
+        }
+    }
+
+    #[test]
+    fn test_verified_and_look_alike() {
+        let rif = Address::from_str("0x2acc95758f8b5f583470ba265eb685a8f45fc9d5").unwrap();
+        assert!(matches!(
+            check_address("mainnet", &rif),
+            ProfileMatch::Verified(_)
+        ));
+
+        let spoof = Address::from_str("0x2acc0000000000000000000000000000f45fc9d5").unwrap();
+        assert!(matches!(
+            check_address("mainnet", &spoof),
+            ProfileMatch::LookAlike(_)
+        ));
+
+        let unrelated = Address::repeat_byte(0x42);
+        assert!(matches!(
+            check_address("mainnet", &unrelated),
+            ProfileMatch::Unknown
+        ));
+    }
+}
@@ -0,0 +1,67 @@
+//! Quick amounts for the interactive send prompt, so routine payments don't
+//! need the amount typed each time.
+//!
+//! Presets are configured per token symbol under Configuration → Quick
+//! Amounts. The last amount sent to each recipient, per network and token,
+//! is kept in the wallet database.
+
+use crate::config::Config;
+use crate::utils::storage::Storage;
+use alloy::primitives::Address;
+use anyhow::{Result, anyhow};
+
+/// Preset amounts configured for `symbol`
+pub fn presets<'a>(config: &'a Config, symbol: &str) -> &'a [String] {
+    config
+        .quick_amounts
+        .iter()
+        .find(|(configured, _)| configured.eq_ignore_ascii_case(symbol))
+        .map(|(_, amounts)| amounts.as_slice())
+        .unwrap_or_default()
+}
+
+/// Parse a comma-separated list of positive amounts, e.g. `10, 50, 100`
+pub fn parse(input: &str) -> Result<Vec<String>> {
+    let mut amounts = Vec::new();
+    for amount in input.split(',').map(str::trim).filter(|a| !a.is_empty()) {
+        match amount.parse::<f64>() {
+            Ok(value) if value.is_finite() && value > 0.0 => {}
+            _ => return Err(anyhow!("'{}' is not a positive amount", amount)),
+        }
+        if !amounts.iter().any(|a| a == amount) {
+            amounts.push(amount.to_string());
+        }
+    }
+    Ok(amounts)
+}
+
+/// Remember `amount` as the last one sent to `recipient`; RBTC is recorded
+/// under the zero token address
+pub fn record(network: &str, recipient: &Address, token: &Address, amount: &str) -> Result<()> {
+    Storage::open()?.record_last_amount(network, recipient, token, amount)
+}
+
+/// The amount last sent to `recipient` of `token`
+pub fn last(network: &str, recipient: &Address, token: &Address) -> Result<Option<String>> {
+    Storage::open()?.last_amount(network, recipient, token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_presets() {
+        assert_eq!(parse(" 10, 50 ,100,10,").unwrap(), vec!["10", "50", "100"]);
+        assert!(parse("").unwrap().is_empty());
+        assert!(parse("10, -5").is_err());
+        assert!(parse("ten").is_err());
+
+        let mut config = Config::default();
+        config
+            .quick_amounts
+            .insert("DOC".to_string(), vec!["10".to_string(), "50".to_string()]);
+        assert_eq!(presets(&config, "doc"), ["10", "50"]);
+        assert!(presets(&config, "RBTC").is_empty());
+    }
+}
@@ -0,0 +1,121 @@
+//! Scoring for recovery drills, where a backup or recovery phrase is
+//! restored into a throwaway profile to prove it still works.
+
+use std::time::Duration;
+
+/// Time within which a recovery counts as quick
+pub const TARGET_DURATION: Duration = Duration::from_secs(5 * 60);
+
+/// What the user managed to do during a drill
+#[derive(Debug, Clone, Default)]
+pub struct DrillReport {
+    /// The key was recovered from the backup or phrase
+    pub restored: bool,
+    /// Name of the installed wallet the recovered address belongs to
+    pub matched_wallet: Option<String>,
+    /// A message signed with the recovered key verified against its address
+    pub signed: bool,
+    /// A testnet transaction from the recovered address was confirmed
+    pub funds_moved: bool,
+    /// Password or phrase entries needed to restore
+    pub attempts: u32,
+    pub duration: Duration,
+}
+
+impl DrillReport {
+    /// Score out of 100
+    pub fn score(&self) -> u32 {
+        if !self.restored {
+            return 0;
+        }
+        let mut score = 40;
+        if self.matched_wallet.is_some() {
+            score += 20;
+        }
+        if self.signed {
+            score += 15;
+        }
+        if self.funds_moved {
+            score += 15;
+        }
+        if self.attempts <= 1 {
+            score += 5;
+        }
+        if self.duration <= TARGET_DURATION {
+            score += 5;
+        }
+        score
+    }
+
+    pub fn grade(&self) -> &'static str {
+        match self.score() {
+            90.. => "Excellent",
+            70..=89 => "Good",
+            40..=69 => "Needs practice",
+            _ => "Failed",
+        }
+    }
+
+    /// Suggestions for the parts of the drill that fell short
+    pub fn tips(&self) -> Vec<&'static str> {
+        let mut tips = Vec::new();
+        if !self.restored {
+            tips.push(
+                "The backup could not be restored. Make a new backup now while the wallet is still accessible.",
+            );
+            return tips;
+        }
+        if self.matched_wallet.is_none() {
+            tips.push(
+                "The recovered address does not match any installed wallet. Check that you are keeping the right backup.",
+            );
+        }
+        if !self.funds_moved {
+            tips.push(
+                "Fund the recovered address from the testnet faucet and rerun the drill to prove you can move funds.",
+            );
+        }
+        if self.attempts > 1 {
+            tips.push(
+                "It took several tries to enter the password or phrase. Store it where it can be read reliably.",
+            );
+        }
+        if self.duration > TARGET_DURATION {
+            tips.push(
+                "Recovery took over five minutes. Keep the backup and its instructions together.",
+            );
+        }
+        tips
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drill_scoring() {
+        assert_eq!(DrillReport::default().score(), 0);
+
+        let perfect = DrillReport {
+            restored: true,
+            matched_wallet: Some("savings".to_string()),
+            signed: true,
+            funds_moved: true,
+            attempts: 1,
+            duration: Duration::from_secs(60),
+        };
+        assert_eq!(perfect.score(), 100);
+        assert_eq!(perfect.grade(), "Excellent");
+        assert!(perfect.tips().is_empty());
+
+        let shaky = DrillReport {
+            restored: true,
+            attempts: 3,
+            duration: Duration::from_secs(600),
+            ..Default::default()
+        };
+        assert_eq!(shaky.score(), 40);
+        assert_eq!(shaky.tips().len(), 4);
+    }
+}
@@ -0,0 +1,157 @@
+//! Masking of addresses and amounts in on-screen output.
+//!
+//! Redaction is meant for demos, screen shares and terminal recordings. It
+//! only changes what tables, balances and transfer results print; commands
+//! still work on the real values. It is turned on with `--redact`, the
+//! "Redact Output" setting, or automatically inside an asciinema recording
+//! (which sets `ASCIINEMA_REC`).
+
+use crate::config::ConfigManager;
+use std::fmt::Display;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Replacement for a masked amount
+pub const MASKED_AMOUNT: &str = "•••";
+/// Replacement for a masked address or hash
+pub const MASKED_HEX: &str = "0x••••…••••";
+
+/// Environment variables set by terminal recorders
+const RECORDER_VARS: &[&str] = &["ASCIINEMA_REC"];
+
+static ACTIVE: OnceLock<AtomicBool> = OnceLock::new();
+
+fn state() -> &'static AtomicBool {
+    ACTIVE.get_or_init(|| AtomicBool::new(recording() || configured()))
+}
+
+/// Whether a known terminal recorder is capturing this session
+pub fn recording() -> bool {
+    RECORDER_VARS
+        .iter()
+        .any(|var| std::env::var_os(var).is_some_and(|value| !value.is_empty()))
+}
+
+fn configured() -> bool {
+    ConfigManager::new()
+        .and_then(|manager| manager.load())
+        .map(|config| config.redact_output)
+        .unwrap_or(false)
+}
+
+pub fn is_active() -> bool {
+    state().load(Ordering::Relaxed)
+}
+
+pub fn set_active(active: bool) {
+    state().store(active, Ordering::Relaxed);
+}
+
+/// An address or hash as printed, masked while redaction is active
+pub fn address(value: impl Display) -> String {
+    if is_active() {
+        MASKED_HEX.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// An amount as printed, masked while redaction is active
+pub fn amount(value: impl Display) -> String {
+    if is_active() {
+        MASKED_AMOUNT.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Free text with addresses, hashes and decimal amounts masked while
+/// redaction is active
+pub fn text(value: &str) -> String {
+    if is_active() {
+        mask_text(value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// A table cell: a cell holding only a number is an amount or count and is
+/// masked whole; any other cell is masked like free text
+pub fn cell(value: &str) -> String {
+    if !is_active() {
+        return value.to_string();
+    }
+    let trimmed = value.trim().trim_start_matches(['-', '+', '$']);
+    if !trimmed.is_empty() && trimmed.replace(',', "").parse::<f64>().is_ok() {
+        MASKED_AMOUNT.to_string()
+    } else {
+        mask_text(value)
+    }
+}
+
+/// Mask `0x` values of 40 or more hex digits (addresses and hashes) and
+/// numbers with a fractional part
+pub fn mask_text(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    let mut out = String::with_capacity(value.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let word_start = i == 0 || !(chars[i - 1].is_ascii_alphanumeric() || chars[i - 1] == '.');
+        if word_start && chars[i] == '0' && matches!(chars.get(i + 1), Some('x' | 'X')) {
+            let digits = chars[i + 2..]
+                .iter()
+                .take_while(|c| c.is_ascii_hexdigit())
+                .count();
+            if digits >= 40 {
+                out.push_str(MASKED_HEX);
+                i += 2 + digits;
+                continue;
+            }
+        }
+        if word_start && chars[i].is_ascii_digit() {
+            let whole = chars[i..]
+                .iter()
+                .take_while(|c| c.is_ascii_digit() || **c == ',')
+                .count();
+            let fraction = match chars.get(i + whole) {
+                Some('.') => chars[i + whole + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit())
+                    .count(),
+                _ => 0,
+            };
+            if fraction > 0 {
+                out.push_str(MASKED_AMOUNT);
+                i += whole + 1 + fraction;
+                continue;
+            }
+            out.extend(&chars[i..i + whole]);
+            i += whole;
+            continue;
+        }
+        out.push(chars[i]);
+        i += 1;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_text() {
+        assert_eq!(
+            mask_text("Sent 1.25 RBTC to 0x1111111111111111111111111111111111111111"),
+            format!("Sent {} RBTC to {}", MASKED_AMOUNT, MASKED_HEX)
+        );
+        assert_eq!(
+            mask_text(&format!("tx 0x{}", "ab".repeat(32))),
+            format!("tx {}", MASKED_HEX)
+        );
+        // Dates, short hex values and plain counts stay readable
+        assert_eq!(mask_text("2024-05-01 12:30, 3 items"), "2024-05-01 12:30, 3 items");
+        assert_eq!(mask_text("selector 0xa9059cbb"), "selector 0xa9059cbb");
+        assert_eq!(mask_text("v2.0.1 of 1,000.5"), format!("v2.0.1 of {}", MASKED_AMOUNT));
+    }
+}
@@ -0,0 +1,226 @@
+//! Telling apart sends that failed for a passing reason (gas price below the
+//! node's minimum, a nonce taken by another transaction, a flaky endpoint)
+//! from ones that will fail however often they are retried, and retrying
+//! requests that failed for such a reason after a growing pause.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// Exponential backoff with jitter, so that clients rate limited together
+/// do not all come back at the same moment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Pause before the first retry; it doubles with every retry
+    pub base_delay: Duration,
+    /// Longest pause between two attempts
+    pub max_delay: Duration,
+}
+
+impl Backoff {
+    /// A single attempt, without retries
+    pub const NONE: Backoff = Backoff {
+        max_retries: 0,
+        base_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+    };
+
+    /// Pause before retry number `retry` (starting at 0): between half and
+    /// all of `base_delay * 2^retry`, capped at `max_delay`
+    pub fn delay(&self, retry: u32) -> Duration {
+        let ceiling = self
+            .base_delay
+            .saturating_mul(1 << retry.min(16))
+            .min(self.max_delay);
+        ceiling / 2 + ceiling.mul_f64(rand::random::<f64>() / 2.0)
+    }
+
+    /// Run `attempt` until it succeeds, fails with an error `retryable`
+    /// rejects, or the retries are used up
+    pub async fn run<T, E, F, Fut>(
+        &self,
+        mut attempt: F,
+        retryable: impl Fn(&E) -> bool,
+    ) -> Result<T, E>
+    where
+        E: fmt::Display,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut retry = 0;
+        loop {
+            match attempt().await {
+                Err(e) if retry < self.max_retries && retryable(&e) => {
+                    let delay = self.delay(retry);
+                    log::debug!(
+                        "Retry {}/{} in {} ms after: {}",
+                        retry + 1,
+                        self.max_retries,
+                        delay.as_millis(),
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FailureKind {
+    /// Worth retrying with a fresh nonce and gas price
+    Transient,
+    /// Retrying cannot help, e.g. a bad address or insufficient funds
+    Permanent,
+}
+
+impl fmt::Display for FailureKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailureKind::Transient => write!(f, "transient"),
+            FailureKind::Permanent => write!(f, "permanent"),
+        }
+    }
+}
+
+/// Errors that no retry fixes; checked first, as node messages for them can
+/// also mention gas or nonces
+const PERMANENT: &[&str] = &[
+    "insufficient",
+    "invalid address",
+    "invalid recipient",
+    "execution reverted",
+    "reverted",
+    // Stopped by the pre-flight simulation
+    "would fail",
+    "exceeds block gas limit",
+    "intrinsic gas too low",
+    "invalid sender",
+    "invalid chain",
+];
+
+const TRANSIENT: &[&str] = &[
+    "underpriced",
+    "gas price too low",
+    "nonce too low",
+    "nonce too high",
+    "invalid nonce",
+    "nonce has already been used",
+    "already known",
+    "known transaction",
+    "replacement transaction",
+    "timeout",
+    "timed out",
+    "connection",
+    "too many requests",
+    "429",
+    "502",
+    "503",
+    "temporarily",
+];
+
+/// Classify a failed send by its error message. Unknown errors count as
+/// permanent, so a failure is never repeated blindly.
+pub fn classify(error: &str) -> FailureKind {
+    let error = error.to_lowercase();
+    if PERMANENT.iter().any(|marker| error.contains(marker)) {
+        FailureKind::Permanent
+    } else if TRANSIENT.iter().any(|marker| error.contains(marker)) {
+        FailureKind::Transient
+    } else {
+        FailureKind::Permanent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_send_errors() {
+        assert_eq!(
+            classify("Failed to send RBTC transaction: transaction underpriced"),
+            FailureKind::Transient
+        );
+        assert_eq!(
+            classify("server returned an error response: error code -32010: nonce too low"),
+            FailureKind::Transient
+        );
+        assert_eq!(
+            classify("Insufficient RBTC for transfer and gas"),
+            FailureKind::Permanent
+        );
+        assert_eq!(
+            classify("insufficient funds for gas * price + value: nonce too low"),
+            FailureKind::Permanent
+        );
+        assert_eq!(
+            classify("The transaction would fail: revert: Pausable: paused"),
+            FailureKind::Permanent
+        );
+        assert_eq!(classify("something odd"), FailureKind::Permanent);
+    }
+
+    #[tokio::test]
+    async fn test_backoff() {
+        let backoff = Backoff {
+            max_retries: 3,
+            base_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(25),
+        };
+        for retry in 0..6 {
+            let ceiling = Duration::from_millis((10u64 << retry).min(25));
+            let delay = backoff.delay(retry);
+            assert!(delay >= ceiling / 2 && delay <= ceiling, "{:?}", delay);
+        }
+
+        let mut attempts = 0;
+        let result: Result<u32, String> = backoff
+            .run(
+                || {
+                    attempts += 1;
+                    let attempt = attempts;
+                    async move {
+                        if attempt < 3 {
+                            Err("429".to_string())
+                        } else {
+                            Ok(attempt)
+                        }
+                    }
+                },
+                |e| e == "429",
+            )
+            .await;
+        assert_eq!(result, Ok(3));
+
+        let mut attempts = 0;
+        let result: Result<(), String> = backoff
+            .run(
+                || {
+                    attempts += 1;
+                    async { Err("reverted".to_string()) }
+                },
+                |e| e == "429",
+            )
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+
+        let mut attempts = 0;
+        let _: Result<(), String> = backoff
+            .run(
+                || {
+                    attempts += 1;
+                    async { Err("429".to_string()) }
+                },
+                |e| e == "429",
+            )
+            .await;
+        assert_eq!(attempts, 4);
+    }
+}
@@ -0,0 +1,408 @@
+//! RIF Name Service (RNS) resolution, so recipients can be entered as names
+//! such as `alice.rsk`.
+//!
+//! The name is hashed with the EIP-137 namehash, the RNS registry returns the
+//! resolver set for it, and the resolver returns the address. Resolved names
+//! are kept for a few minutes, so a form that checks a name more than once
+//! does not repeat the lookups.
+//!
+//! Addresses shown in contact lists and history are reverse-resolved through
+//! the `addr.reverse` node to the name their owner set for them. A name is
+//! only shown when it resolves back to the same address, since anyone can
+//! claim any name in their reverse record. Reverse lookups, including those
+//! that found no name, are kept on disk for a day.
+
+use crate::utils::http::RpcProvider;
+use crate::commands::address::parse_recipient;
+use crate::utils::redact;
+use alloy::primitives::{Address, B256, address, keccak256};
+use alloy::providers::Provider;
+use alloy::sol;
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, TimeDelta, Utc};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+sol! {
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    contract IRnsRegistry {
+        function resolver(bytes32 node) external view returns (address);
+    }
+}
+
+sol! {
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    contract IRnsResolver {
+        function addr(bytes32 node) external view returns (address);
+    }
+}
+
+sol! {
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    contract IRnsReverseResolver {
+        function name(bytes32 node) external view returns (string);
+    }
+}
+
+/// RNS registry on Rootstock mainnet (chain 30)
+pub const MAINNET_REGISTRY: Address = address!("cb868aeabd31e2b66f74e9a55cf064abb31a4ad5");
+/// RNS registry on Rootstock testnet (chain 31)
+pub const TESTNET_REGISTRY: Address = address!("7d284aaac6e925aad802a53c0c69efe3764597b8");
+
+/// How long a resolved name is reused without asking the resolver again
+const CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// How long a reverse lookup is reused, whether or not it found a name
+const REVERSE_TTL: TimeDelta = TimeDelta::hours(24);
+
+type Cache = Mutex<HashMap<(u64, String), (Address, Instant)>>;
+static CACHE: OnceLock<Cache> = OnceLock::new();
+
+/// Whether `input` is an RNS name (`something.rsk`) rather than an address
+pub fn is_name(input: &str) -> bool {
+    let input = input.trim().to_lowercase();
+    input.ends_with(".rsk") && input.split('.').all(|label| !label.is_empty())
+}
+
+/// EIP-137 namehash of a (lowercase) name
+pub fn namehash(name: &str) -> B256 {
+    let mut node = B256::ZERO;
+    for label in name.rsplit('.').filter(|label| !label.is_empty()) {
+        node = keccak256([node.as_slice(), keccak256(label.as_bytes()).as_slice()].concat());
+    }
+    node
+}
+
+/// The RNS registry on the given chain
+pub fn registry(chain_id: u64) -> Option<Address> {
+    match chain_id {
+        30 => Some(MAINNET_REGISTRY),
+        31 => Some(TESTNET_REGISTRY),
+        _ => None,
+    }
+}
+
+/// Address `name` points to on the provider's chain
+pub async fn resolve(provider: &RpcProvider, name: &str) -> Result<Address> {
+    let name = name.trim().to_lowercase();
+    let chain_id = provider
+        .get_chain_id()
+        .await
+        .map_err(|e| anyhow!("Failed to get chain ID: {}", e))?;
+    let cache = CACHE.get_or_init(Default::default);
+    if let Ok(cache) = cache.lock()
+        && let Some((address, resolved_at)) = cache.get(&(chain_id, name.clone()))
+        && resolved_at.elapsed() < CACHE_TTL
+    {
+        return Ok(*address);
+    }
+
+    let registry = registry(chain_id).ok_or_else(|| {
+        anyhow!(
+            "RNS names cannot be resolved on chain {}; enter the 0x address instead",
+            chain_id
+        )
+    })?;
+    let node = namehash(&name);
+    let resolver = IRnsRegistry::new(registry, provider)
+        .resolver(node)
+        .call()
+        .await
+        .map_err(|e| anyhow!("Failed to look up '{}' in the RNS registry: {}", name, e))?
+        ._0;
+    if resolver.is_zero() {
+        return Err(anyhow!("'{}' is not a registered RNS name", name));
+    }
+    let address = IRnsResolver::new(resolver, provider)
+        .addr(node)
+        .call()
+        .await
+        .map_err(|e| anyhow!("Failed to resolve '{}': {}", name, e))?
+        ._0;
+    if address.is_zero() {
+        return Err(anyhow!("RNS name '{}' does not point to an address", name));
+    }
+
+    if let Ok(mut cache) = cache.lock() {
+        cache.insert((chain_id, name), (address, Instant::now()));
+    }
+    Ok(address)
+}
+
+/// Node of the reverse record of `address`: `<hex address>.addr.reverse`
+pub fn reverse_node(address: Address) -> B256 {
+    namehash(&format!("{:x}.addr.reverse", address))
+}
+
+/// The result of a reverse lookup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ReverseEntry {
+    /// None when the address has no (verified) name
+    name: Option<String>,
+    checked_at: DateTime<Utc>,
+}
+
+/// Reverse lookups kept between runs, by chain and address
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ReverseCache {
+    #[serde(default)]
+    entries: HashMap<String, ReverseEntry>,
+}
+
+impl ReverseCache {
+    pub fn path() -> Result<PathBuf> {
+        let dir = dirs::data_local_dir()
+            .context("Failed to get data directory")?
+            .join("rootstock-wallet");
+        fs::create_dir_all(&dir)?;
+        Ok(dir.join("rns_reverse.json"))
+    }
+
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path).context("Failed to read the RNS cache")?;
+        serde_json::from_str(&content).context("Failed to parse the RNS cache")
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(Self::path()?, content).context("Failed to write the RNS cache")
+    }
+
+    fn key(chain_id: u64, address: Address) -> String {
+        format!("{}:{:#x}", chain_id, address)
+    }
+
+    /// The lookup of `address` if it is still fresh at `now`: `Some(None)`
+    /// when it found no name
+    pub fn get(&self, chain_id: u64, address: Address, now: DateTime<Utc>) -> Option<Option<&str>> {
+        self.entries
+            .get(&Self::key(chain_id, address))
+            .filter(|entry| now - entry.checked_at < REVERSE_TTL)
+            .map(|entry| entry.name.as_deref())
+    }
+
+    pub fn insert(
+        &mut self,
+        chain_id: u64,
+        address: Address,
+        name: Option<String>,
+        now: DateTime<Utc>,
+    ) {
+        self.entries.insert(
+            Self::key(chain_id, address),
+            ReverseEntry {
+                name,
+                checked_at: now,
+            },
+        );
+    }
+
+    /// Drop the lookups that are no longer fresh at `now`
+    pub fn prune(&mut self, now: DateTime<Utc>) {
+        self.entries
+            .retain(|_, entry| now - entry.checked_at < REVERSE_TTL);
+    }
+}
+
+/// Name `address` set as its reverse record, if the name resolves back to
+/// it. Only a failed registry lookup is an error; a resolver that cannot
+/// answer means there is no name.
+async fn lookup_name(
+    provider: &RpcProvider,
+    registry: Address,
+    address: Address,
+) -> Result<Option<String>> {
+    let node = reverse_node(address);
+    let resolver = IRnsRegistry::new(registry, provider)
+        .resolver(node)
+        .call()
+        .await
+        .map_err(|e| {
+            anyhow!(
+                "Failed to look up the reverse record of {:#x}: {}",
+                address,
+                e
+            )
+        })?
+        ._0;
+    if resolver.is_zero() {
+        return Ok(None);
+    }
+    let Ok(name) = IRnsReverseResolver::new(resolver, provider)
+        .name(node)
+        .call()
+        .await
+    else {
+        return Ok(None);
+    };
+    let name = name._0.trim().to_lowercase();
+    if !is_name(&name) {
+        return Ok(None);
+    }
+    Ok(match resolve(provider, &name).await {
+        Ok(resolved) if resolved == address => Some(name),
+        _ => None,
+    })
+}
+
+/// RNS names of `addresses` on `chain_id`, for display next to them.
+/// Addresses without a verified name, and those whose lookup failed, are
+/// left out; nothing is looked up on chains without an RNS registry.
+pub async fn reverse_names(
+    provider: &RpcProvider,
+    chain_id: u64,
+    addresses: impl IntoIterator<Item = Address>,
+) -> HashMap<Address, String> {
+    let mut names = HashMap::new();
+    let Some(registry) = registry(chain_id) else {
+        return names;
+    };
+    let mut cache = ReverseCache::load().unwrap_or_default();
+    let now = Utc::now();
+    let mut lookups = JoinSet::new();
+    for address in addresses.into_iter().collect::<HashSet<_>>() {
+        match cache.get(chain_id, address, now) {
+            Some(name) => names.extend(name.map(|name| (address, name.to_string()))),
+            None => {
+                let provider = provider.clone();
+                lookups.spawn(
+                    async move { (address, lookup_name(&provider, registry, address).await) },
+                );
+            }
+        }
+    }
+    if lookups.is_empty() {
+        return names;
+    }
+    while let Some(result) = lookups.join_next().await {
+        let Ok((address, result)) = result else {
+            continue;
+        };
+        match result {
+            Ok(name) => {
+                if let Some(name) = &name {
+                    names.insert(address, name.clone());
+                }
+                cache.insert(chain_id, address, name, now);
+            }
+            Err(e) => log::debug!("{}", e),
+        }
+    }
+    cache.prune(now);
+    if let Err(e) = cache.save() {
+        log::warn!("Failed to update the RNS cache: {}", e);
+    }
+    names
+}
+
+/// `text`, an address as printed, followed by the address's name from
+/// `names`. Names are left out in redact mode, where they would give the
+/// address away.
+pub fn with_name(text: String, address: &Address, names: &HashMap<Address, String>) -> String {
+    match names.get(address) {
+        Some(name) if !redact::is_active() => format!("{} ({})", text, name),
+        _ => text,
+    }
+}
+
+/// A recipient entered as a 0x address or an RNS name. Addresses without the
+/// checksum for `chain_id` are warned about, and resolved names are printed
+/// with their address, so either can be checked before sending.
+pub async fn resolve_recipient(
+    provider: &RpcProvider,
+    input: &str,
+    chain_id: u64,
+) -> Result<Address> {
+    if !is_name(input) {
+        return parse_recipient(input, chain_id)
+            .map_err(|_| anyhow!("Invalid recipient address: {}", input));
+    }
+    let address = resolve(provider, input).await?;
+    println!(
+        "{}: {} resolves to 0x{:x}",
+        "RNS".cyan().bold(),
+        input.trim().to_lowercase(),
+        address
+    );
+    Ok(address)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::b256;
+
+    #[test]
+    fn test_namehash() {
+        assert_eq!(namehash(""), B256::ZERO);
+        // Reference values from EIP-137
+        assert_eq!(
+            namehash("eth"),
+            b256!("93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae")
+        );
+        assert_eq!(
+            namehash("foo.eth"),
+            b256!("de9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f")
+        );
+
+        assert!(is_name("alice.rsk"));
+        assert!(is_name(" Alice.RSK "));
+        assert!(is_name("pay.alice.rsk"));
+        assert!(!is_name("0x1111111111111111111111111111111111111111"));
+        assert!(!is_name("alice.eth"));
+        assert!(!is_name(".rsk"));
+        assert_eq!(registry(31), Some(TESTNET_REGISTRY));
+        assert_eq!(registry(33), None);
+    }
+
+    #[test]
+    fn test_reverse_cache() {
+        let alice = Address::repeat_byte(0xab);
+        // Reference value of `addr.reverse`
+        assert_eq!(
+            namehash("addr.reverse"),
+            b256!("91d1777781884d03a6757a803996e38de2a42967fb37eeaca72729271025a9e2")
+        );
+        assert_eq!(
+            reverse_node(alice),
+            namehash(&format!("{}.addr.reverse", "ab".repeat(20)))
+        );
+
+        let now = Utc::now();
+        let mut cache = ReverseCache::default();
+        cache.insert(30, alice, Some("alice.rsk".to_string()), now);
+        cache.insert(30, Address::ZERO, None, now - TimeDelta::hours(2));
+        assert_eq!(cache.get(30, alice, now), Some(Some("alice.rsk")));
+        assert_eq!(cache.get(31, alice, now), None);
+        assert_eq!(cache.get(30, Address::ZERO, now), Some(None));
+        // Stale after a day
+        let later = now + TimeDelta::hours(23);
+        assert_eq!(cache.get(30, alice, later), Some(Some("alice.rsk")));
+        assert_eq!(cache.get(30, Address::ZERO, later), None);
+        cache.prune(later);
+        assert_eq!(cache.entries.len(), 1);
+
+        let names = HashMap::from([(alice, "alice.rsk".to_string())]);
+        assert_eq!(
+            with_name("0xabab".to_string(), &alice, &names),
+            "0xabab (alice.rsk)"
+        );
+        assert_eq!(
+            with_name("0x0000".to_string(), &Address::ZERO, &names),
+            "0x0000"
+        );
+    }
+}
@@ -0,0 +1,290 @@
+//! Role profiles for separation of duties within one wallet vault.
+//!
+//! Until a profile is created every action is allowed. Once profiles exist,
+//! each session signs in to one and may only do what its role permits:
+//! operators prepare and queue transfers, approvers review and sign queued
+//! transfers and proposals, and owners can do everything.
+
+use anyhow::{Context, Result, anyhow};
+use rand::{RngCore, rngs::OsRng};
+use scrypt::{Params, scrypt};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Profile to sign in as, for non-interactive use
+pub const PROFILE_ENV: &str = "ROOTSTOCK_WALLET_PROFILE";
+/// Passphrase of that profile, for non-interactive use
+pub const PASSPHRASE_ENV: &str = "ROOTSTOCK_WALLET_PROFILE_PASSPHRASE";
+
+static SESSION: Mutex<Option<Session>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Full access, including managing profiles
+    Owner,
+    /// Prepares and queues transfers but cannot sign
+    Operator,
+    /// Reviews and signs queued transfers and proposals only
+    Approver,
+}
+
+impl Role {
+    pub const ALL: [Role; 3] = [Role::Owner, Role::Operator, Role::Approver];
+
+    pub fn allows(&self, capability: Capability) -> bool {
+        match self {
+            Role::Owner => true,
+            Role::Operator => capability == Capability::Prepare,
+            Role::Approver => capability == Capability::SignQueued,
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Role::Owner => write!(f, "owner"),
+            Role::Operator => write!(f, "operator"),
+            Role::Approver => write!(f, "approver"),
+        }
+    }
+}
+
+/// Actions restricted by role
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    /// Sign and send a transfer directly
+    Transfer,
+    /// Queue or cancel transfers for later signing
+    Prepare,
+    /// Approve and sign queued transfers and proposals
+    SignQueued,
+    /// Create, import, export, rotate or delete wallets
+    ManageWallets,
+    /// Add or remove role profiles
+    ManageRoles,
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Capability::Transfer => write!(f, "send transfers"),
+            Capability::Prepare => write!(f, "queue transfers"),
+            Capability::SignQueued => write!(f, "approve and sign queued transfers"),
+            Capability::ManageWallets => write!(f, "manage wallets"),
+            Capability::ManageRoles => write!(f, "manage role profiles"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleProfile {
+    pub name: String,
+    pub role: Role,
+    salt: String,
+    passphrase_hash: String,
+}
+
+impl RoleProfile {
+    fn new(name: &str, role: Role, passphrase: &str) -> Result<Self> {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        Ok(Self {
+            name: name.to_string(),
+            role,
+            salt: hex::encode(salt),
+            passphrase_hash: hex::encode(hash_passphrase(passphrase, &salt)?),
+        })
+    }
+
+    fn verify(&self, passphrase: &str) -> Result<bool> {
+        let salt = hex::decode(&self.salt).context("Invalid profile salt")?;
+        Ok(hex::encode(hash_passphrase(passphrase, &salt)?) == self.passphrase_hash)
+    }
+}
+
+fn hash_passphrase(passphrase: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    let mut hash = [0u8; 32];
+    scrypt(
+        passphrase.as_bytes(),
+        salt,
+        &Params::recommended(),
+        &mut hash,
+    )
+    .map_err(|e| anyhow!("Failed to hash passphrase: {}", e))?;
+    Ok(hash)
+}
+
+/// The profile signed in for this process
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub name: String,
+    pub role: Role,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RoleStore {
+    #[serde(default)]
+    pub profiles: Vec<RoleProfile>,
+}
+
+impl RoleStore {
+    pub fn path() -> Result<PathBuf> {
+        let dir = dirs::data_local_dir()
+            .context("Failed to get data directory")?
+            .join("rootstock-wallet");
+        fs::create_dir_all(&dir)?;
+        Ok(dir.join("roles.json"))
+    }
+
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path).context("Failed to read role profiles")?;
+        serde_json::from_str(&content).context("Failed to parse role profiles")
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(Self::path()?, content).context("Failed to write role profiles")
+    }
+
+    /// Roles are enforced once at least one profile exists
+    pub fn is_enabled(&self) -> bool {
+        !self.profiles.is_empty()
+    }
+
+    pub fn add(&mut self, name: &str, role: Role, passphrase: &str) -> Result<()> {
+        if self.profiles.iter().any(|p| p.name == name) {
+            return Err(anyhow!("Profile '{}' already exists", name));
+        }
+        if self.profiles.is_empty() && role != Role::Owner {
+            return Err(anyhow!(
+                "The first profile must be an owner, so profiles can still be managed"
+            ));
+        }
+        if passphrase.is_empty() {
+            return Err(anyhow!("The passphrase cannot be empty"));
+        }
+        self.profiles
+            .push(RoleProfile::new(name, role, passphrase)?);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<()> {
+        let index = self
+            .profiles
+            .iter()
+            .position(|p| p.name == name)
+            .ok_or_else(|| anyhow!("Profile '{}' not found", name))?;
+        let owners = self
+            .profiles
+            .iter()
+            .filter(|p| p.role == Role::Owner)
+            .count();
+        if self.profiles[index].role == Role::Owner && owners == 1 && self.profiles.len() > 1 {
+            return Err(anyhow!(
+                "Cannot remove the last owner while other profiles exist"
+            ));
+        }
+        self.profiles.remove(index);
+        Ok(())
+    }
+
+    pub fn sign_in(&self, name: &str, passphrase: &str) -> Result<Session> {
+        let profile = self
+            .profiles
+            .iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| anyhow!("Profile '{}' not found", name))?;
+        if !profile.verify(passphrase)? {
+            return Err(anyhow!("Incorrect passphrase for profile '{}'", name));
+        }
+        Ok(Session {
+            name: profile.name.clone(),
+            role: profile.role,
+        })
+    }
+}
+
+/// The signed-in profile, asking which one to use on first call.
+///
+/// Returns `None` while no profiles exist. The profile and passphrase are
+/// taken from `ROOTSTOCK_WALLET_PROFILE` and
+/// `ROOTSTOCK_WALLET_PROFILE_PASSPHRASE` when set.
+pub fn current_session() -> Result<Option<Session>> {
+    let store = RoleStore::load()?;
+    if !store.is_enabled() {
+        return Ok(None);
+    }
+    let mut session = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(session) = session.as_ref() {
+        return Ok(Some(session.clone()));
+    }
+
+    let name = match std::env::var(PROFILE_ENV) {
+        Ok(name) if !name.is_empty() => name,
+        _ => {
+            let names: Vec<String> = store.profiles.iter().map(|p| p.name.clone()).collect();
+            inquire::Select::new("Sign in as:", names).prompt()?
+        }
+    };
+    let passphrase = match std::env::var(PASSPHRASE_ENV) {
+        Ok(passphrase) if !passphrase.is_empty() => passphrase,
+        _ => rpassword::prompt_password(format!("Passphrase for '{}': ", name))?,
+    };
+    let signed_in = store.sign_in(&name, &passphrase)?;
+    *session = Some(signed_in.clone());
+    Ok(Some(signed_in))
+}
+
+/// Fail unless the signed-in profile may perform the action
+pub fn require(capability: Capability) -> Result<()> {
+    match current_session()? {
+        Some(session) if !session.role.allows(capability) => Err(anyhow!(
+            "Profile '{}' ({}) is not allowed to {}",
+            session.name,
+            session.role,
+            capability
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_capabilities() {
+        assert!(Role::Owner.allows(Capability::ManageRoles));
+        assert!(Role::Operator.allows(Capability::Prepare));
+        assert!(!Role::Operator.allows(Capability::SignQueued));
+        assert!(!Role::Operator.allows(Capability::Transfer));
+        assert!(Role::Approver.allows(Capability::SignQueued));
+        assert!(!Role::Approver.allows(Capability::Prepare));
+        assert!(!Role::Approver.allows(Capability::Transfer));
+    }
+
+    #[test]
+    fn test_store_rules() {
+        let mut store = RoleStore::default();
+        assert!(store.add("ops", Role::Operator, "secret").is_err());
+        store.add("alice", Role::Owner, "secret").unwrap();
+        store.add("ops", Role::Operator, "secret").unwrap();
+        assert!(store.add("ops", Role::Approver, "secret").is_err());
+
+        assert_eq!(store.sign_in("ops", "secret").unwrap().role, Role::Operator);
+        assert!(store.sign_in("ops", "wrong").is_err());
+        assert!(store.remove("alice").is_err());
+        store.remove("ops").unwrap();
+        store.remove("alice").unwrap();
+        assert!(!store.is_enabled());
+    }
+}
@@ -0,0 +1,135 @@
+//! In-process cache of RPC answers that do not change, so that repeated
+//! history and balance runs do not fetch the same chain id, blocks, token
+//! metadata or receipts again.
+//!
+//! Only answers that stay valid for their lifetime are kept: the chain id,
+//! blocks by number or hash, transactions and receipts once they are mined,
+//! and the `decimals()`, `symbol()` and `name()` of tokens. Mined data is
+//! kept for a few minutes only, so a reorganisation is picked up. Empty
+//! answers, such as the receipt of a pending transaction, are never kept.
+
+use serde_json::Value;
+use serde_json::value::RawValue;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Lifetime of answers that never change for an endpoint
+const STATIC_TTL: Duration = Duration::from_secs(60 * 60);
+/// Lifetime of blocks, transactions and receipts
+const MINED_TTL: Duration = Duration::from_secs(5 * 60);
+/// Entries kept before expired ones are dropped
+const MAX_ENTRIES: usize = 10_000;
+
+/// Selectors of `decimals()`, `symbol()` and `name()`
+const METADATA_SELECTORS: [&str; 3] = ["0x313ce567", "0x95d89b41", "0x06fdde03"];
+
+/// Cached answers by key, with the moment they expire
+type Entries = HashMap<String, (Instant, Box<RawValue>)>;
+
+static CACHE: LazyLock<Mutex<Entries>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// How long the answer to `method` with `params` may be reused, or `None`
+/// when it has to be fetched every time
+pub fn ttl(method: &str, params: Option<&RawValue>) -> Option<Duration> {
+    let params: Value = params
+        .and_then(|params| serde_json::from_str(params.get()).ok())
+        .unwrap_or(Value::Null);
+    match method {
+        "eth_chainId" | "net_version" | "eth_getBlockByHash" => Some(STATIC_TTL),
+        // Only numbered blocks; "latest" and "pending" move
+        "eth_getBlockByNumber" => params
+            .get(0)
+            .and_then(Value::as_str)
+            .filter(|block| block.starts_with("0x"))
+            .map(|_| MINED_TTL),
+        "eth_getTransactionReceipt" | "eth_getTransactionByHash" => Some(MINED_TTL),
+        "eth_call" => {
+            let call = params.get(0)?;
+            let data = call.get("input").or_else(|| call.get("data"))?.as_str()?;
+            METADATA_SELECTORS.contains(&data).then_some(STATIC_TTL)
+        }
+        _ => None,
+    }
+}
+
+/// Cache key for `method` with `params` sent to the endpoints in `scope`
+pub fn key(scope: &str, method: &str, params: Option<&RawValue>) -> String {
+    format!("{}|{}|{}", scope, method, params.map_or("", RawValue::get))
+}
+
+/// The cached answer for `key`, unless it has expired
+pub fn get(key: &str) -> Option<Box<RawValue>> {
+    let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache
+        .get(key)
+        .filter(|(expires, _)| *expires > Instant::now())
+        .map(|(_, result)| result.clone())
+}
+
+/// Keep `result` for `ttl`, unless it is empty or a transaction that is not
+/// mined yet
+pub fn insert(key: String, result: &RawValue, ttl: Duration) {
+    let value: Value = match serde_json::from_str(result.get()) {
+        Ok(value) => value,
+        Err(_) => return,
+    };
+    if value.is_null() || value.get("blockNumber").is_some_and(Value::is_null) {
+        return;
+    }
+
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if cache.len() >= MAX_ENTRIES {
+        let now = Instant::now();
+        cache.retain(|_, (expires, _)| *expires > now);
+        if cache.len() >= MAX_ENTRIES {
+            cache.clear();
+        }
+    }
+    cache.insert(key, (Instant::now() + ttl, result.to_owned()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(json: &str) -> Box<RawValue> {
+        RawValue::from_string(json.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_rpc_cache() {
+        assert_eq!(ttl("eth_chainId", None), Some(STATIC_TTL));
+        assert_eq!(
+            ttl("eth_getBlockByNumber", Some(&raw(r#"["0x10",false]"#))),
+            Some(MINED_TTL)
+        );
+        assert_eq!(
+            ttl("eth_getBlockByNumber", Some(&raw(r#"["latest",false]"#))),
+            None
+        );
+        let decimals = raw(r#"[{"to":"0x01","input":"0x313ce567"},"latest"]"#);
+        assert_eq!(ttl("eth_call", Some(&decimals)), Some(STATIC_TTL));
+        let balance_of = raw(r#"[{"to":"0x01","input":"0x70a08231"},"latest"]"#);
+        assert_eq!(ttl("eth_call", Some(&balance_of)), None);
+        assert_eq!(ttl("eth_getBalance", None), None);
+        assert_eq!(ttl("eth_blockNumber", None), None);
+
+        let chain_id = key("http://cache-test", "eth_chainId", None);
+        insert(chain_id.clone(), &raw(r#""0x1f""#), STATIC_TTL);
+        assert_eq!(get(&chain_id).unwrap().get(), r#""0x1f""#);
+        assert!(get(&key("http://other", "eth_chainId", None)).is_none());
+
+        // Pending transactions and missing receipts are fetched again
+        let pending = key("http://cache-test", "eth_getTransactionByHash", None);
+        insert(pending.clone(), &raw(r#"{"blockNumber":null}"#), MINED_TTL);
+        assert!(get(&pending).is_none());
+        let receipt = key("http://cache-test", "eth_getTransactionReceipt", None);
+        insert(receipt.clone(), &raw("null"), MINED_TTL);
+        assert!(get(&receipt).is_none());
+
+        let expired = key("http://cache-test", "net_version", None);
+        insert(expired.clone(), &raw(r#""31""#), Duration::ZERO);
+        assert!(get(&expired).is_none());
+    }
+}
@@ -0,0 +1,149 @@
+//! Client for the official Rootstock explorer API (v3).
+//!
+//! Like Blockscout, the explorer needs no API key and returns transactions
+//! with their receipt and timestamp. It is tried after Alchemy and Blockscout
+//! when fetching history, so history stays available while those are down.
+
+use crate::api::{ApiProvider, rate_limit};
+use crate::types::transaction::RskTransaction;
+use crate::utils::http;
+use alloy::primitives::{Address, U256};
+use anyhow::{Result, anyhow};
+use reqwest::Client;
+use serde_json::Value;
+
+const MAINNET_URL: &str = "https://be.explorer.rootstock.io/api/v3";
+const TESTNET_URL: &str = "https://be.explorer.testnet.rootstock.io/api/v3";
+
+pub struct RskExplorerClient {
+    client: Client,
+    is_testnet: bool,
+}
+
+impl RskExplorerClient {
+    pub fn new(is_testnet: bool) -> Result<Self> {
+        Ok(Self {
+            client: http::secure_client()?,
+            is_testnet,
+        })
+    }
+
+    pub fn get_base_url(&self) -> &'static str {
+        if self.is_testnet {
+            TESTNET_URL
+        } else {
+            MAINNET_URL
+        }
+    }
+
+    async fn get(&self, path: &str, query: &[(&str, String)]) -> Result<Value> {
+        rate_limit::acquire(&ApiProvider::Explorer, 1).await;
+        let response = self
+            .client
+            .get(format!("{}{}", self.get_base_url(), path))
+            .query(query)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Request failed: {}", e))?;
+        let status = response.status();
+        let body = response
+            .json::<Value>()
+            .await
+            .map_err(|e| anyhow!("Failed to parse response: {}", e))?;
+        if !status.is_success() {
+            return Err(anyhow!("Rootstock explorer API error ({}): {}", status, body));
+        }
+        Ok(body)
+    }
+
+    /// Latest transactions sent or received by `address`, newest first,
+    /// following the `pages.next` cursor until `limit` have been collected
+    pub async fn get_transactions(
+        &self,
+        address: &Address,
+        limit: u32,
+    ) -> Result<Vec<RskTransaction>> {
+        let path = format!("/transactions/address/{:#x}", address);
+        let mut items = Vec::new();
+        let mut next: Option<String> = None;
+        loop {
+            let mut query = vec![("limit", limit.min(100).to_string())];
+            if let Some(cursor) = &next {
+                query.push(("next", cursor.clone()));
+            }
+            let page = self.get(&path, &query).await?;
+            let page_items = page["data"]
+                .as_array()
+                .ok_or_else(|| anyhow!("Invalid response format from the Rootstock explorer"))?;
+            items.extend(page_items.iter().cloned());
+            next = page["pages"]["next"].as_str().map(str::to_string);
+            if items.len() >= limit as usize || page_items.is_empty() || next.is_none() {
+                break;
+            }
+        }
+        items.truncate(limit as usize);
+        items
+            .iter()
+            .map(RskTransaction::from_explorer_transaction)
+            .collect()
+    }
+}
+
+/// A number that the explorer sends as a `0x` hex string, a decimal string
+/// or as JSON
+pub fn number<T: TryFrom<U256>>(value: &Value) -> Option<T> {
+    let n = match value {
+        Value::String(s) => match s.strip_prefix("0x") {
+            Some(hex) => U256::from_str_radix(hex, 16).ok()?,
+            None => U256::from_str_radix(s, 10).ok()?,
+        },
+        Value::Number(n) => U256::from(n.as_u64()?),
+        _ => return None,
+    };
+    T::try_from(n).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::transaction::TransactionStatus;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_explorer_transaction() {
+        let tx = RskTransaction::from_explorer_transaction(&json!({
+            "hash": "0x5f3d0a4c1e1b5a2e8b7a8a0f9e6c1d2b3a4f5e6d7c8b9a0f1e2d3c4b5a697887",
+            "blockNumber": 6512345,
+            "transactionIndex": 2,
+            "timestamp": 1725192000,
+            "from": "0x1111111111111111111111111111111111111111",
+            "to": "0x2222222222222222222222222222222222222222",
+            "value": "0x38d7ea4c68000",
+            "gasPrice": "65164000",
+            "nonce": 4,
+            "receipt": {"status": "0x0", "gasUsed": 21000}
+        }))
+        .unwrap();
+        assert_eq!(tx.value, U256::from(1_000_000_000_000_000u64));
+        assert_eq!(tx.gas_price, U256::from(65_164_000u64));
+        assert_eq!(tx.gas, U256::from(21_000));
+        assert_eq!(tx.status, TransactionStatus::Failed);
+        assert_eq!(tx.block_number.map(|n| n.to::<u64>()), Some(6512345));
+        assert_eq!(
+            tx.timestamp,
+            std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_725_192_000)
+        );
+
+        // Contract creations have no recipient; without a receipt it is pending
+        let created = RskTransaction::from_explorer_transaction(&json!({
+            "hash": "0x5f3d0a4c1e1b5a2e8b7a8a0f9e6c1d2b3a4f5e6d7c8b9a0f1e2d3c4b5a697887",
+            "from": "0x1111111111111111111111111111111111111111",
+            "to": null,
+            "value": "0"
+        }))
+        .unwrap();
+        assert_eq!(created.to, None);
+        assert_eq!(created.status, TransactionStatus::Pending);
+        assert!(number::<u64>(&json!("0xzz")).is_none());
+    }
+}
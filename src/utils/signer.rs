@@ -0,0 +1,151 @@
+//! Transaction signers: decrypted local keys and hardware wallets.
+//!
+//! [`WalletSigner`] hides where the key lives so [`crate::utils::eth::EthClient`]
+//! can sign the same transaction request with either. Hardware wallets keep
+//! their keys on the device; every signature is confirmed on its screen.
+
+use crate::types::wallet::HardwareDevice;
+use alloy::eips::eip2718::Encodable2718;
+use alloy::network::{EthereumWallet, TransactionBuilder};
+use alloy::primitives::Address;
+use alloy::rpc::types::TransactionRequest;
+use alloy::signers::Signer;
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::trezor::{HDPath, TrezorSigner};
+use anyhow::{Result, anyhow};
+
+/// Hardened derivation index offset (BIP32)
+const HARDENED: u32 = 0x8000_0000;
+
+/// Derivation path of a Rootstock account (SLIP-44 coin type 137)
+pub fn rsk_derivation_path(account: u32) -> String {
+    format!("m/44'/137'/0'/0/{}", account)
+}
+
+/// Parse a path such as `m/44'/137'/0'/0/0` into BIP32 indices
+pub fn parse_derivation_path(path: &str) -> Result<Vec<u32>> {
+    let mut parts = path.split('/');
+    if parts.next() != Some("m") {
+        return Err(anyhow!("Derivation path must start with 'm/': {}", path));
+    }
+    parts
+        .map(|part| {
+            let (index, hardened) = match part.strip_suffix('\'') {
+                Some(index) => (index, true),
+                None => (part, false),
+            };
+            let index: u32 = index
+                .parse()
+                .ok()
+                .filter(|index| *index < HARDENED)
+                .ok_or_else(|| anyhow!("Invalid derivation path component '{}'", part))?;
+            Ok(if hardened { index | HARDENED } else { index })
+        })
+        .collect()
+}
+
+/// Signs transactions for the current wallet
+#[derive(Clone)]
+pub struct WalletSigner {
+    address: Address,
+    wallet: EthereumWallet,
+    device: Option<HardwareDevice>,
+}
+
+impl WalletSigner {
+    /// Sign with a decrypted private key held in memory
+    pub fn local(signer: PrivateKeySigner) -> Self {
+        Self {
+            address: signer.address(),
+            wallet: EthereumWallet::from(signer),
+            device: None,
+        }
+    }
+
+    /// Sign on a connected Trezor using the account at `derivation_path`
+    pub async fn trezor(derivation_path: &str, chain_id: u64) -> Result<Self> {
+        let trezor = TrezorSigner::new(HDPath::Other(derivation_path.to_string()), Some(chain_id))
+            .await
+            .map_err(|e| anyhow!("Failed to connect to Trezor: {}", e))?;
+        Ok(Self {
+            address: trezor.address(),
+            wallet: EthereumWallet::from(trezor),
+            device: Some(HardwareDevice::Trezor),
+        })
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// The hardware wallet holding the key, if it is not a local key
+    pub fn device(&self) -> Option<HardwareDevice> {
+        self.device
+    }
+
+    /// Sign a fully populated transaction request and return the raw
+    /// EIP-2718 encoded transaction
+    pub async fn sign_transaction(&self, tx: TransactionRequest) -> Result<Vec<u8>> {
+        let envelope = tx
+            .build(&self.wallet)
+            .await
+            .map_err(|e| anyhow!("Failed to sign transaction: {}", e))?;
+        Ok(envelope.encoded_2718())
+    }
+}
+
+/// Show the address at `derivation_path` on the Trezor's screen and return
+/// it once the user has confirmed it on the device
+pub fn display_trezor_address(derivation_path: &str) -> Result<Address> {
+    use trezor_client::TrezorResponse;
+    use trezor_client::protos::{EthereumAddress, EthereumGetAddress};
+
+    let mut client = trezor_client::unique(false).map_err(|e| anyhow!("No Trezor found: {}", e))?;
+    client.init_device(None)?;
+
+    let mut request = EthereumGetAddress::new();
+    request.address_n = parse_derivation_path(derivation_path)?;
+    request.set_show_display(true);
+
+    let mut response = client.call(
+        request,
+        Box::new(|_, m: EthereumAddress| Ok(m.address().to_string())),
+    )?;
+    loop {
+        response = match response {
+            TrezorResponse::Ok(address) => {
+                return address
+                    .parse()
+                    .map_err(|e| anyhow!("Trezor returned an invalid address: {}", e));
+            }
+            TrezorResponse::ButtonRequest(button) => button.ack()?,
+            TrezorResponse::Failure(failure) => {
+                return Err(anyhow!(
+                    "Address was not confirmed on the Trezor: {}",
+                    failure.message()
+                ));
+            }
+            _ => {
+                return Err(anyhow!(
+                    "Unlock the Trezor (PIN and passphrase) with Trezor Suite, then try again"
+                ));
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_derivation_path() {
+        assert_eq!(
+            parse_derivation_path(&rsk_derivation_path(2)).unwrap(),
+            vec![44 | HARDENED, 137 | HARDENED, HARDENED, 0, 2]
+        );
+        assert!(parse_derivation_path("44'/137'/0'/0/0").is_err());
+        assert!(parse_derivation_path("m/44'/x/0").is_err());
+        assert!(parse_derivation_path("m/2147483648").is_err());
+    }
+}
@@ -0,0 +1,45 @@
+//! Permanent record of every transaction the wallet has signed.
+//!
+//! Unlike the pending store, entries are never dropped: each keeps the raw
+//! signed payload alongside the request it was built from, so the signing
+//! history of a period can be handed to an auditor.
+
+use crate::utils::storage::Storage;
+use alloy::primitives::{Address, B256, Bytes};
+use alloy::rpc::types::TransactionRequest;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedTx {
+    pub hash: B256,
+    pub from: Address,
+    pub signed_at: DateTime<Utc>,
+    /// EIP-2718 encoded signed transaction, as broadcast
+    pub raw: Bytes,
+    /// The request that was signed: recipient, value, nonce, gas and chain
+    pub request: TransactionRequest,
+}
+
+impl SignedTx {
+    pub fn new(hash: B256, request: &TransactionRequest, raw: Vec<u8>) -> Self {
+        Self {
+            hash,
+            from: request.from.unwrap_or_default(),
+            signed_at: Utc::now(),
+            raw: raw.into(),
+            request: request.clone(),
+        }
+    }
+}
+
+/// Remember a transaction that was just signed and sent
+pub fn record(tx: &SignedTx) -> Result<()> {
+    Storage::open()?.record_signed(tx)
+}
+
+/// Transactions signed at or after `start` and before `end`, oldest first
+pub fn between(start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<SignedTx>> {
+    Storage::open()?.load_signed(start, end)
+}
@@ -0,0 +1,108 @@
+//! Pre-flight simulation of transfers.
+//!
+//! A transaction is run with `eth_call` against the latest block before it
+//! is signed, so one that would revert (a token transfer beyond the balance,
+//! a paused token, a recipient contract refusing RBTC) is stopped with the
+//! contract's reason instead of being mined as a failure that still costs
+//! gas. Endpoints that cannot be reached leave the transaction unchecked.
+
+use crate::utils::http::RpcProvider;
+use crate::utils::eth::IERC20;
+use alloy::network::TransactionBuilder;
+use alloy::primitives::{Address, Bytes, U256, hex};
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionRequest;
+use alloy::sol_types::{SolCall, decode_revert_reason};
+use alloy::transports::RpcError;
+
+/// Call that transfers `amount` of `token` (RBTC when `None`) from `from`
+/// to `to`, for simulating or estimating a transfer not yet built
+pub fn transfer_call(
+    from: Option<Address>,
+    to: Address,
+    amount: U256,
+    token: Option<Address>,
+) -> TransactionRequest {
+    let tx = match token {
+        Some(token) => TransactionRequest::default().with_to(token).with_input(
+            IERC20::transferCall {
+                recipient: to,
+                amount,
+            }
+            .abi_encode(),
+        ),
+        None => TransactionRequest::default().with_to(to).with_value(amount),
+    };
+    match from {
+        Some(from) => tx.with_from(from),
+        None => tx,
+    }
+}
+
+/// Why `tx` would fail if it were sent now, or `None` when it succeeds or
+/// the endpoint could not run the call
+pub async fn revert_reason(
+    provider: &RpcProvider,
+    tx: &TransactionRequest,
+) -> Option<String> {
+    match provider.call(tx).await {
+        Ok(_) => None,
+        Err(RpcError::ErrorResp(payload)) => {
+            Some(reason(&payload.message, payload.as_revert_data()))
+        }
+        Err(e) => {
+            log::debug!("Skipping the pre-flight simulation: {}", e);
+            None
+        }
+    }
+}
+
+/// Reason for a failed call from the node's error `message` and the revert
+/// data it returned: the `Error(string)` message or `Panic` code when the
+/// contract gave one, the selector of a custom error, or else the node's
+/// message
+fn reason(message: &str, data: Option<Bytes>) -> String {
+    match data {
+        Some(data) if !data.is_empty() => match decode_revert_reason(&data) {
+            Some(reason) => reason,
+            None if data.len() >= 4 => {
+                format!("reverted with custom error 0x{}", hex::encode(&data[..4]))
+            }
+            None => format!("reverted with data 0x{}", hex::encode(&data)),
+        },
+        _ => message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::sol_types::{Panic, Revert, SolError};
+
+    #[test]
+    fn test_reason() {
+        let message = "execution reverted";
+        let revert = Revert::from("ERC20: transfer amount exceeds balance").abi_encode();
+        assert_eq!(
+            reason(message, Some(revert.into())),
+            "revert: ERC20: transfer amount exceeds balance"
+        );
+        let panic = Panic::from(0x11).abi_encode();
+        assert!(reason(message, Some(panic.into())).contains("overflow"));
+        assert_eq!(
+            reason(message, Some(Bytes::from_static(&hex!("fb8f41b2")))),
+            "reverted with custom error 0xfb8f41b2"
+        );
+        assert_eq!(reason(message, Some(Bytes::new())), message);
+        assert_eq!(reason("insufficient funds", None), "insufficient funds");
+
+        let token = Address::repeat_byte(7);
+        let call = transfer_call(None, Address::repeat_byte(2), U256::from(5u64), Some(token));
+        assert_eq!(call.to, Some(token.into()));
+        assert_eq!(
+            &call.input.input().unwrap()[..4],
+            &IERC20::transferCall::SELECTOR
+        );
+        assert_eq!(call.from, None);
+    }
+}
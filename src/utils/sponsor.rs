@@ -0,0 +1,211 @@
+//! Gas sponsorship for onboarding: a funded sponsor wallet tops up wallets
+//! as they are created or imported with a small RBTC allowance, so a new
+//! team member can pay for their first transactions.
+//!
+//! Each address is sponsored at most once per network, and the total handed
+//! out on a network stops at the configured cap. Grants are kept in the
+//! wallet database. Sponsorship never undoes the wallet it was meant for: a
+//! failed top-up is reported and the wallet is kept.
+
+use crate::config::ConfigManager;
+use crate::types::wallet::WalletData;
+use crate::utils::eth::EthClient;
+use crate::utils::helper::{Config as HelperConfig, WalletConfig, unlock_wallet};
+use crate::utils::storage::Storage;
+use alloy::primitives::{Address, B256, U256, utils::format_units, utils::parse_units};
+use anyhow::{Result, anyhow};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+/// Which wallet pays the allowances, and how much
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SponsorPolicy {
+    /// Name of the sponsor wallet; sponsorship is off when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet: Option<String>,
+    /// RBTC a new wallet is topped up to
+    pub amount: f64,
+    /// Most RBTC the sponsor hands out on each network
+    pub cap: f64,
+}
+
+impl Default for SponsorPolicy {
+    fn default() -> Self {
+        Self {
+            wallet: None,
+            amount: 0.0002,
+            cap: 0.01,
+        }
+    }
+}
+
+impl SponsorPolicy {
+    /// RBTC to send to a new wallet holding `balance`, given the sponsor has
+    /// already handed out `granted` on the network. `None` when the wallet
+    /// already holds the allowance or the cap is reached.
+    pub fn top_up_value(&self, balance: U256, granted: U256) -> Option<U256> {
+        let wanted = rbtc(self.amount)?.checked_sub(balance)?;
+        let value = wanted.min(rbtc(self.cap)?.saturating_sub(granted));
+        (!value.is_zero()).then_some(value)
+    }
+}
+
+/// RBTC handed out by the sponsor on `network`, and the addresses it went to
+pub fn grants(network: &str) -> Result<Vec<(Address, U256)>> {
+    Storage::open()?.sponsor_grants(network)
+}
+
+/// Total of `grants`
+pub fn total(grants: &[(Address, U256)]) -> U256 {
+    grants
+        .iter()
+        .fold(U256::ZERO, |sum, (_, value)| sum.saturating_add(*value))
+}
+
+/// Top up the wallet at `address`, just created or imported, from the
+/// sponsor wallet when sponsorship is configured. Problems are printed as
+/// warnings.
+pub async fn sponsor_new_wallet(address: Address) {
+    if let Err(e) = top_up(address).await {
+        eprintln!(
+            "{} Gas sponsorship skipped: {}",
+            "⚠️".yellow(),
+            e.to_string().yellow()
+        );
+    }
+}
+
+async fn top_up(address: Address) -> Result<()> {
+    let config = ConfigManager::new()?.load()?;
+    let policy = config.sponsor.clone();
+    let Some(sponsor_name) = &policy.wallet else {
+        return Ok(());
+    };
+    let wallet_data = WalletData::load()?;
+    let sponsor = wallet_data
+        .get_wallet_by_name(sponsor_name)
+        .ok_or_else(|| anyhow!("sponsor wallet '{}' not found", sponsor_name))?;
+    if sponsor.address == address {
+        return Ok(());
+    }
+    if sponsor.watch_only || sponsor.hardware.is_some() {
+        return Err(anyhow!(
+            "sponsor wallet '{}' must hold its key in this wallet",
+            sponsor_name
+        ));
+    }
+
+    let network = config.default_network;
+    let grants = grants(network.chain_name())?;
+    if grants.iter().any(|(granted_to, _)| *granted_to == address) {
+        println!(
+            "{}",
+            "This address was already sponsored; no top-up sent.".dimmed()
+        );
+        return Ok(());
+    }
+    let client_config = |private_key| HelperConfig {
+        network: network.get_config(),
+        wallet: WalletConfig {
+            current_wallet_address: None,
+            private_key,
+            mnemonic: None,
+        },
+    };
+    let balance = EthClient::new(&client_config(None), None)
+        .await?
+        .get_balance(&address, &None)
+        .await?;
+    let Some(value) = policy.top_up_value(balance, total(&grants)) else {
+        if total(&grants) >= rbtc(policy.cap).unwrap_or_default() {
+            println!(
+                "{}",
+                format!(
+                    "Gas sponsorship cap of {} RBTC on {} is used up; no top-up sent.",
+                    policy.cap, network
+                )
+                .yellow()
+            );
+        }
+        return Ok(());
+    };
+
+    println!(
+        "\n⛽ Topping up the new wallet with {} RBTC from sponsor '{}'",
+        format_units(value, 18)?,
+        sponsor_name
+    );
+    let private_key = unlock_wallet(
+        sponsor,
+        0,
+        &format!("Enter password for sponsor wallet '{}': ", sponsor_name),
+    )?;
+    let client = EthClient::new(&client_config(Some(private_key)), None).await?;
+    let tx_hash = client.send_transaction(address, value, None).await?;
+    // Counted against the cap as soon as it is broadcast
+    Storage::open()?.record_sponsor_grant(network.chain_name(), &address, value, tx_hash)?;
+    confirm(&client, tx_hash).await;
+    Ok(())
+}
+
+async fn confirm(client: &EthClient, tx_hash: B256) {
+    match client.wait_for_receipt(tx_hash).await {
+        Ok(receipt) if receipt.status() => {
+            println!("{} Gas allowance sent (0x{:x})", "✅".green(), tx_hash)
+        }
+        Ok(_) => println!(
+            "{} The top-up transaction failed (0x{:x})",
+            "❌".red(),
+            tx_hash
+        ),
+        Err(_) => println!(
+            "{} Top-up sent but not confirmed yet (0x{:x})",
+            "⏳".yellow(),
+            tx_hash
+        ),
+    }
+}
+
+fn rbtc(amount: f64) -> Option<U256> {
+    Some(parse_units(&amount.to_string(), 18).ok()?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_up_value() {
+        let policy = SponsorPolicy {
+            wallet: Some("treasury".to_string()),
+            amount: 0.0002,
+            cap: 0.0005,
+        };
+        let units = |amount: f64| rbtc(amount).unwrap();
+
+        assert_eq!(
+            policy.top_up_value(U256::ZERO, U256::ZERO),
+            Some(units(0.0002))
+        );
+        // Only the missing part of the allowance is sent
+        assert_eq!(
+            policy.top_up_value(units(0.00015), U256::ZERO),
+            Some(units(0.00005))
+        );
+        assert_eq!(policy.top_up_value(units(0.0003), U256::ZERO), None);
+        // The cap limits the last grant, then stops them
+        assert_eq!(
+            policy.top_up_value(U256::ZERO, units(0.0004)),
+            Some(units(0.0001))
+        );
+        assert_eq!(policy.top_up_value(U256::ZERO, units(0.0005)), None);
+        assert_eq!(
+            total(&[
+                (Address::repeat_byte(1), units(0.0002)),
+                (Address::repeat_byte(2), units(0.0001))
+            ]),
+            units(0.0003)
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Well-known staking and escrow contracts that hold funds on a user's behalf.
+//!
+//! Each contract reports the amount locked for an address through
+//! `balanceOf(address)`. As with the contract profiles, verify new entries
+//! against the protocol's published deployments before adding them.
+
+use crate::utils::eth::EthClient;
+use alloy::primitives::utils::format_units;
+use alloy::primitives::{Address, U256};
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+const STAKING_JSON: &str = include_str!("../../res/staking.json");
+static STAKING_CONTRACTS: OnceLock<Vec<StakingContract>> = OnceLock::new();
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StakingContract {
+    pub protocol: String,
+    pub name: String,
+    pub network: String,
+    pub address: Address,
+    /// Symbol of the asset locked in the contract
+    pub asset: String,
+    /// Token contract of the locked asset
+    pub token: Address,
+    pub decimals: u8,
+}
+
+/// An amount of an asset locked in a staking contract for one address
+#[derive(Debug, Clone)]
+pub struct LockedBalance {
+    pub contract: &'static StakingContract,
+    pub amount: U256,
+}
+
+impl LockedBalance {
+    pub fn formatted(&self) -> String {
+        format_units(self.amount, self.contract.decimals)
+            .unwrap_or_else(|_| self.amount.to_string())
+    }
+}
+
+/// Staking contracts for a single network ("mainnet" or "testnet")
+pub fn staking_contracts(network: &str) -> Vec<&'static StakingContract> {
+    let network = network.to_lowercase();
+    STAKING_CONTRACTS
+        .get_or_init(|| {
+            serde_json::from_str(STAKING_JSON).expect("Bundled staking contracts are invalid")
+        })
+        .iter()
+        .filter(|c| c.network == network)
+        .collect()
+}
+
+/// Non-zero balances locked for `address` in the known staking contracts.
+/// Contracts that cannot be queried are skipped.
+pub async fn locked_balances(
+    eth_client: &EthClient,
+    network: &str,
+    address: &Address,
+) -> Vec<LockedBalance> {
+    let polling = crate::config::PollingConfig::current();
+    let mut locked = Vec::new();
+    for contract in staking_contracts(network) {
+        polling.pace_requests().await;
+        match eth_client
+            .get_balance(address, &Some(contract.address))
+            .await
+        {
+            Ok(amount) if !amount.is_zero() => locked.push(LockedBalance { contract, amount }),
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to query {}: {}", contract.name, e),
+        }
+    }
+    locked
+}
+
+/// Total locked amount of the asset with the given token contract
+pub fn locked_total(locked: &[LockedBalance], token: &Address) -> U256 {
+    locked
+        .iter()
+        .filter(|l| l.contract.token == *token)
+        .fold(U256::ZERO, |total, l| total + l.amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_staking_contracts_parse() {
+        let mainnet = staking_contracts("mainnet");
+        assert!(!mainnet.is_empty());
+        for contract in mainnet {
+            assert_ne!(contract.address, contract.token);
+        }
+    }
+
+    #[test]
+    fn test_locked_total_by_token() {
+        let contracts = staking_contracts("mainnet");
+        let locked = vec![
+            LockedBalance {
+                contract: contracts[0],
+                amount: U256::from(5),
+            },
+            LockedBalance {
+                contract: contracts[0],
+                amount: U256::from(7),
+            },
+        ];
+        assert_eq!(locked_total(&locked, &contracts[0].token), U256::from(12));
+        assert_eq!(locked_total(&locked, &Address::ZERO), U256::ZERO);
+    }
+}
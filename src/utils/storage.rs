@@ -0,0 +1,1324 @@
+//! Encrypted SQLite storage for wallet metadata, contacts, the history cache
+//! and its sync progress per network, the token registry, the transfer queue,
+//! canary transfers, pending transactions, payroll, the log of signed
+//! transactions, the cost centers sent transactions are allocated to and the
+//! registry of contract ABIs.
+//!
+//! The database is encrypted with SQLCipher. Its key is read from
+//! `ROOTSTOCK_WALLET_DB_KEY` when set, otherwise from the OS keyring, where a
+//! random key is created on first use (and where the key file of earlier
+//! versions is copied). Without the key the database cannot be read, so
+//! `wallet backup-key` writes it out and `wallet restore-key` puts it back
+//! into the keyring of another machine.
+//!
+//! The JSON files used by earlier versions, address labels included, are
+//! imported on first open and checked against the database. They and the old
+//! key file are overwritten and deleted only once the key has been backed up
+//! (the token registry, kept in `tokens.json` in the working directory, is
+//! imported once and left in place); [`Storage::export_json`] writes the same
+//! files back out on demand.
+
+use crate::commands::queue::TransferQueue;
+use crate::commands::tokens::{TokenInfo, TokenRegistry};
+use crate::config::profile;
+use crate::security::secrets;
+use crate::types::contacts::Contact;
+use crate::types::wallet::{Wallet, WalletData};
+use crate::utils::abi_registry::{AbiRegistry, RegisteredContract};
+use crate::utils::canary::CanaryTransfers;
+use crate::utils::history_cache::HistoryCache;
+use crate::utils::payroll::Payroll;
+use crate::utils::pending::PendingStore;
+use crate::utils::signing_log::SignedTx;
+use crate::utils::watchdog;
+use alloy::primitives::{Address, B256, U256};
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use rand::{RngCore, rngs::OsRng};
+use rusqlite::{Connection, OptionalExtension, Transaction, params};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::UNIX_EPOCH;
+
+/// Database key, for machines without an OS keyring
+pub const KEY_ENV: &str = "ROOTSTOCK_WALLET_DB_KEY";
+
+const SCHEMA_VERSION: i64 = 1;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS wallets (
+        address TEXT PRIMARY KEY,
+        name TEXT NOT NULL UNIQUE,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS contacts (
+        id INTEGER PRIMARY KEY,
+        address TEXT NOT NULL,
+        name TEXT NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS contacts_address ON contacts (address);
+    CREATE INDEX IF NOT EXISTS contacts_name ON contacts (name);
+    CREATE TABLE IF NOT EXISTS history (
+        wallet TEXT NOT NULL,
+        hash TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        data TEXT NOT NULL,
+        network TEXT NOT NULL DEFAULT '',
+        PRIMARY KEY (wallet, hash)
+    );
+    CREATE INDEX IF NOT EXISTS history_wallet_time ON history (wallet, timestamp);
+    CREATE TABLE IF NOT EXISTS history_sync (
+        network TEXT NOT NULL,
+        wallet TEXT NOT NULL,
+        last_block INTEGER NOT NULL,
+        PRIMARY KEY (network, wallet)
+    );
+    CREATE TABLE IF NOT EXISTS history_scan (
+        network TEXT NOT NULL,
+        wallet TEXT NOT NULL,
+        last_block INTEGER NOT NULL,
+        PRIMARY KEY (network, wallet)
+    );
+    CREATE TABLE IF NOT EXISTS labels (
+        address TEXT PRIMARY KEY,
+        label TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS queue (
+        id INTEGER PRIMARY KEY,
+        wallet TEXT NOT NULL,
+        status TEXT NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS queue_wallet_status ON queue (wallet, status);
+    CREATE TABLE IF NOT EXISTS canary_transfers (
+        id INTEGER PRIMARY KEY,
+        status TEXT NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS pending_transactions (
+        hash TEXT PRIMARY KEY,
+        sender TEXT NOT NULL,
+        nonce INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS payroll_roster (
+        contact TEXT PRIMARY KEY,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS payroll_batches (
+        month TEXT PRIMARY KEY,
+        status TEXT NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS signed_transactions (
+        hash TEXT PRIMARY KEY,
+        signed_at INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS signed_transactions_time ON signed_transactions (signed_at);
+    CREATE TABLE IF NOT EXISTS cost_allocations (
+        hash TEXT PRIMARY KEY,
+        cost_center TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS contract_abis (
+        network TEXT NOT NULL,
+        name TEXT NOT NULL,
+        data TEXT NOT NULL,
+        PRIMARY KEY (network, name)
+    );
+    CREATE TABLE IF NOT EXISTS token_registry (
+        network TEXT NOT NULL,
+        address TEXT NOT NULL,
+        data TEXT NOT NULL,
+        PRIMARY KEY (network, address)
+    );
+    CREATE TABLE IF NOT EXISTS last_amounts (
+        network TEXT NOT NULL,
+        recipient TEXT NOT NULL,
+        token TEXT NOT NULL,
+        amount TEXT NOT NULL,
+        PRIMARY KEY (network, recipient, token)
+    );
+    CREATE TABLE IF NOT EXISTS sponsor_grants (
+        network TEXT NOT NULL,
+        address TEXT NOT NULL,
+        amount TEXT NOT NULL,
+        tx_hash TEXT NOT NULL,
+        granted_at INTEGER NOT NULL,
+        PRIMARY KEY (network, address)
+    );
+";
+
+/// JSON files written by earlier versions, in the data directory
+const LEGACY_WALLETS: &str = "rootstock-wallet.json";
+const LEGACY_CONTACTS: &str = "contacts.json";
+const LEGACY_HISTORY: &str = "history_cache.json";
+const LEGACY_QUEUE: &str = "transfer_queue.json";
+const LEGACY_LABELS: &str = "labels.json";
+/// Token registry of earlier versions, relative to the working directory
+const LEGACY_TOKENS: &str = "tokens.json";
+
+pub struct Storage {
+    conn: Connection,
+}
+
+impl Storage {
+    /// Data directory of the active configuration profile
+    pub fn data_dir() -> Result<PathBuf> {
+        let dir = profile::data_dir()?;
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    pub fn path() -> Result<PathBuf> {
+        Ok(Self::data_dir()?.join("rootstock-wallet.db"))
+    }
+
+    /// Open the database, creating it and importing any JSON files from
+    /// earlier versions on first use
+    pub fn open() -> Result<Self> {
+        let key = database_key()?;
+        let conn = unlock(&Self::path()?, &key).map_err(|_| {
+            anyhow!(
+                "Failed to unlock the wallet database; check {} or the key in the OS keyring",
+                KEY_ENV
+            )
+        })?;
+        let mut storage = Self::with_connection(conn)?;
+        let dir = Self::data_dir()?;
+        storage.import_legacy(&dir)?;
+        storage.import_legacy_labels(&dir)?;
+        storage.import_legacy_tokens(Path::new(LEGACY_TOKENS))?;
+        storage.remove_migrated(&dir, &legacy_key_path()?, &key)?;
+        Ok(storage)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)
+            .context("Failed to create the database schema")?;
+        let mut storage = Self { conn };
+        storage.migrate_history_networks()?;
+        storage.rekey_tokens()?;
+        Ok(storage)
+    }
+
+    /// History cached before it was kept per network cannot be told apart
+    /// by network. It is only a cache, so it is dropped once, with its sync
+    /// progress, and fetched again on the next sync.
+    fn migrate_history_networks(&mut self) -> Result<()> {
+        if self.meta("history_per_network")?.is_some() {
+            return Ok(());
+        }
+        let tx = self.conn.transaction()?;
+        let history_has_network = tx
+            .prepare("SELECT 1 FROM pragma_table_info('history') WHERE name = 'network'")?
+            .exists([])?;
+        if !history_has_network {
+            tx.execute(
+                "ALTER TABLE history ADD COLUMN network TEXT NOT NULL DEFAULT ''",
+                [],
+            )?;
+        }
+        let dropped = tx.execute("DELETE FROM history WHERE network = ''", [])?;
+        for table in ["history_sync", "history_scan"] {
+            tx.execute(&format!("DELETE FROM {} WHERE network = ''", table), [])?;
+        }
+        set_meta(&tx, "history_per_network", Some("1"))?;
+        tx.commit()?;
+        if dropped > 0 {
+            eprintln!(
+                "{}: Cleared {} cached transactions saved before history was kept per \
+                 network; they are fetched again on the next sync",
+                "Info".yellow().bold(),
+                dropped
+            );
+        }
+        Ok(())
+    }
+
+    /// The key the database is encrypted with, for `wallet backup-key`
+    pub fn key(&self) -> Result<String> {
+        database_key()
+    }
+
+    /// Record that the key has been backed up, and delete the files of
+    /// earlier versions that were kept until then
+    pub fn confirm_key_backup(&mut self) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        set_meta(&tx, "key_backed_up", Some("1"))?;
+        tx.commit()?;
+        self.remove_migrated(&Self::data_dir()?, &legacy_key_path()?, &database_key()?)
+    }
+
+    /// Keep `key` in the OS keyring, e.g. on a new machine, after checking
+    /// it unlocks the database
+    pub fn restore_key(key: &str) -> Result<()> {
+        let path = Self::path()?;
+        if path.exists() {
+            unlock(&path, key).map_err(|_| {
+                anyhow!("The key does not unlock the wallet database at {}", path.display())
+            })?;
+        }
+        secrets::set_database_key(key)?;
+        if secrets::database_key()?.as_deref() != Some(key) {
+            return Err(anyhow!("The keyring did not keep the database key"));
+        }
+        Ok(())
+    }
+
+    /// Whether the key is known to exist outside the keyring: backed up with
+    /// `wallet backup-key`, or supplied through `ROOTSTOCK_WALLET_DB_KEY`
+    fn key_backed_up(&self) -> Result<bool> {
+        Ok(env_key().is_some() || self.meta("key_backed_up")?.is_some())
+    }
+
+    /// Delete the imported files of earlier versions and the old key file,
+    /// once the key is backed up; until then they are the only copy of the
+    /// data that does not depend on the keyring
+    fn remove_migrated(&self, dir: &Path, key_file: &Path, key: &str) -> Result<()> {
+        let files = [
+            LEGACY_WALLETS,
+            LEGACY_CONTACTS,
+            LEGACY_HISTORY,
+            LEGACY_QUEUE,
+            LEGACY_LABELS,
+        ];
+        let key_file_matches = key_file.exists() && fs::read_to_string(key_file)?.trim() == key;
+        let pending: Vec<PathBuf> = files
+            .iter()
+            .map(|file| dir.join(file))
+            .chain(key_file_matches.then(|| key_file.to_path_buf()))
+            .filter(|path| path.exists())
+            .collect();
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        if !self.key_backed_up()? {
+            static WARNED: AtomicBool = AtomicBool::new(false);
+            if !WARNED.swap(true, Ordering::Relaxed) {
+                eprintln!(
+                    "{}: {} file(s) of an earlier version are still on disk unencrypted. \
+                     Back up the database key with `wallet backup-key <file>` to delete them.",
+                    "Warning".yellow().bold(),
+                    pending.len()
+                );
+            }
+            return Ok(());
+        }
+        for path in pending {
+            remove_legacy(&path)?;
+        }
+        Ok(())
+    }
+
+    fn meta(&self, key: &str) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| {
+                row.get(0)
+            })
+            .optional()?)
+    }
+
+    /// Import the JSON files of earlier versions in one transaction and check
+    /// the rows made it into the database. The files are deleted later, by
+    /// `remove_migrated`.
+    fn import_legacy(&mut self, dir: &Path) -> Result<()> {
+        if self.meta("schema_version")?.is_some() {
+            return Ok(());
+        }
+
+        let tx = self.conn.transaction()?;
+        if let Some(value) = read_legacy::<serde_json::Value>(&dir.join(LEGACY_WALLETS))? {
+            // `history` kept its API key in a field of its own
+            let alchemy_key = value["alchemyApiKey"].as_str().map(str::to_string);
+            let mut wallet_data: WalletData =
+                serde_json::from_value(value).context("Failed to parse the wallet file")?;
+            if wallet_data.api_key.is_none() {
+                wallet_data.api_key = alchemy_key;
+            }
+            write_wallets(&tx, &wallet_data)?;
+            verify_import(&tx, "wallets", wallet_data.wallets.len(), LEGACY_WALLETS)?;
+        }
+        if let Some(contacts) = read_legacy::<Vec<Contact>>(&dir.join(LEGACY_CONTACTS))? {
+            write_contacts(&tx, &contacts)?;
+            verify_import(&tx, "contacts", contacts.len(), LEGACY_CONTACTS)?;
+        }
+        // Only a cache, fetched again when rows are missing
+        if let Some(cache) = read_legacy::<HistoryCache>(&dir.join(LEGACY_HISTORY))? {
+            write_history(&tx, &cache)?;
+        }
+        if let Some(queue) = read_legacy::<TransferQueue>(&dir.join(LEGACY_QUEUE))? {
+            write_queue(&tx, &queue)?;
+            verify_import(&tx, "queue", queue.transfers.len(), LEGACY_QUEUE)?;
+        }
+        tx.execute(
+            "INSERT INTO meta (key, value) VALUES ('schema_version', ?1)",
+            [SCHEMA_VERSION.to_string()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Import the address labels of earlier versions once. They were kept
+    /// apart from the other files, so databases created before labels were
+    /// stored here pick them up as well.
+    fn import_legacy_labels(&mut self, dir: &Path) -> Result<()> {
+        if self.meta("labels_imported")?.is_some() {
+            return Ok(());
+        }
+        let tx = self.conn.transaction()?;
+        if let Some(labels) = read_legacy::<BTreeMap<Address, String>>(&dir.join(LEGACY_LABELS))? {
+            write_labels(&tx, &labels)?;
+            verify_import(&tx, "labels", labels.len(), LEGACY_LABELS)?;
+        }
+        set_meta(&tx, "labels_imported", Some("1"))?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Move tokens from the table keyed by symbol, used before tokens sharing
+    /// a symbol could be registered, to the one keyed by address
+    fn rekey_tokens(&mut self) -> Result<()> {
+        let has_symbol_table = self
+            .conn
+            .prepare("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'tokens'")?
+            .exists([])?;
+        if !has_symbol_table {
+            return Ok(());
+        }
+        let mut registry = TokenRegistry::default();
+        {
+            let mut stmt = self.conn.prepare("SELECT network, symbol, data FROM tokens")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?;
+            for row in rows {
+                let (network, symbol, data) = row?;
+                let info: TokenInfo =
+                    serde_json::from_str(&data).context("Failed to parse the token registry")?;
+                match network.as_str() {
+                    "mainnet" => registry.mainnet.insert(symbol, info),
+                    _ => registry.testnet.insert(symbol, info),
+                };
+            }
+        }
+        let tx = self.conn.transaction()?;
+        write_tokens(&tx, &registry.normalized())?;
+        tx.execute("DROP TABLE tokens", [])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Import the token registry file once; later edits go to the database
+    fn import_legacy_tokens(&mut self, path: &Path) -> Result<()> {
+        if self.meta("tokens_imported")?.is_some() {
+            return Ok(());
+        }
+        let tx = self.conn.transaction()?;
+        if let Some(registry) = read_legacy::<TokenRegistry>(path)? {
+            write_tokens(&tx, &registry.normalized())?;
+        }
+        set_meta(&tx, "tokens_imported", Some("1"))?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn load_wallets(&self) -> Result<WalletData> {
+        let mut wallet_data = WalletData::new();
+        let mut stmt = self.conn.prepare("SELECT address, data FROM wallets")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (address, data) = row?;
+            let wallet: Wallet = serde_json::from_str(&data)
+                .with_context(|| format!("Failed to parse stored wallet {}", address))?;
+            wallet_data.wallets.insert(address, wallet);
+        }
+        wallet_data.current_wallet = self.meta("current_wallet")?.unwrap_or_default();
+        wallet_data.api_key = self.meta("api_key")?;
+        if let Some(contacts) = self.meta("wallet_contacts")? {
+            wallet_data.contacts = serde_json::from_str(&contacts)?;
+        }
+        Ok(wallet_data)
+    }
+
+    pub fn save_wallets(&mut self, wallet_data: &WalletData) -> Result<()> {
+        watchdog::before_write(self)?;
+        let tx = self.conn.transaction()?;
+        write_wallets(&tx, wallet_data)?;
+        tx.commit()?;
+        watchdog::after_write(self);
+        Ok(())
+    }
+
+    pub fn load_contacts(&self) -> Result<Vec<Contact>> {
+        let mut stmt = self.conn.prepare("SELECT data FROM contacts ORDER BY id")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        rows.map(|data| Ok(serde_json::from_str(&data?)?))
+            .collect::<Result<_>>()
+            .context("Failed to parse stored contacts")
+    }
+
+    pub fn save_contacts(&mut self, contacts: &[Contact]) -> Result<()> {
+        watchdog::before_write(self)?;
+        let tx = self.conn.transaction()?;
+        write_contacts(&tx, contacts)?;
+        tx.commit()?;
+        watchdog::after_write(self);
+        Ok(())
+    }
+
+    /// Label of every labelled address
+    pub fn load_labels(&self) -> Result<BTreeMap<Address, String>> {
+        let mut stmt = self.conn.prepare("SELECT address, label FROM labels")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        rows.map(|row| {
+            let (address, label) = row?;
+            Ok((address.parse()?, label))
+        })
+        .collect::<Result<_>>()
+        .context("Failed to parse the stored labels")
+    }
+
+    pub fn save_labels(&mut self, labels: &BTreeMap<Address, String>) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        write_labels(&tx, labels)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Hash of the stored wallets and contacts: the data a save made from
+    /// stale copies would overwrite (see [`crate::utils::watchdog`])
+    pub fn fingerprint(&self) -> Result<u64> {
+        let mut hasher = DefaultHasher::new();
+        for query in [
+            "SELECT address || data FROM wallets ORDER BY address",
+            "SELECT key || value FROM meta
+             WHERE key IN ('current_wallet', 'api_key', 'wallet_contacts') ORDER BY key",
+            "SELECT data FROM contacts ORDER BY id",
+        ] {
+            let mut stmt = self.conn.prepare(query)?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            for row in rows {
+                row?.hash(&mut hasher);
+            }
+        }
+        Ok(hasher.finish())
+    }
+
+    pub fn load_tokens(&self) -> Result<TokenRegistry> {
+        let mut registry = TokenRegistry::default();
+        let mut stmt = self
+            .conn
+            .prepare("SELECT network, address, data FROM token_registry")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+        for row in rows {
+            let (network, address, data) = row?;
+            let info: TokenInfo =
+                serde_json::from_str(&data).context("Failed to parse the token registry")?;
+            match network.as_str() {
+                "mainnet" => registry.mainnet.insert(address, info),
+                _ => registry.testnet.insert(address, info),
+            };
+        }
+        Ok(registry)
+    }
+
+    pub fn save_tokens(&mut self, registry: &TokenRegistry) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        write_tokens(&tx, registry)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn load_history(&self) -> Result<HistoryCache> {
+        let mut cache = HistoryCache::default();
+        let mut stmt = self
+            .conn
+            .prepare("SELECT network, wallet, data FROM history ORDER BY wallet, timestamp")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+        for row in rows {
+            let (network, wallet, data) = row?;
+            cache
+                .transactions
+                .entry(history_key(&network, &wallet))
+                .or_default()
+                .push(serde_json::from_str(&data).context("Failed to parse cached transaction")?);
+        }
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT network, wallet, last_block FROM history_sync")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })?;
+        for row in rows {
+            let (network, wallet, block) = row?;
+            cache
+                .synced_blocks
+                .insert(history_key(&network, &wallet), block as u64);
+        }
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT network, wallet, last_block FROM history_scan")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })?;
+        for row in rows {
+            let (network, wallet, block) = row?;
+            cache
+                .scanned_blocks
+                .insert(history_key(&network, &wallet), block as u64);
+        }
+        Ok(cache)
+    }
+
+    pub fn save_history(&mut self, cache: &HistoryCache) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        write_history(&tx, cache)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn load_queue(&self) -> Result<TransferQueue> {
+        let mut stmt = self.conn.prepare("SELECT data FROM queue ORDER BY id")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        let transfers = rows
+            .map(|data| Ok(serde_json::from_str(&data?)?))
+            .collect::<Result<_>>()
+            .context("Failed to parse the stored transfer queue")?;
+        Ok(TransferQueue { transfers })
+    }
+
+    pub fn save_queue(&mut self, queue: &TransferQueue) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        write_queue(&tx, queue)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn load_canaries(&self) -> Result<CanaryTransfers> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT data FROM canary_transfers ORDER BY id")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        let transfers = rows
+            .map(|data| Ok(serde_json::from_str(&data?)?))
+            .collect::<Result<_>>()
+            .context("Failed to parse the stored canary transfers")?;
+        Ok(CanaryTransfers { transfers })
+    }
+
+    pub fn save_canaries(&mut self, canaries: &CanaryTransfers) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM canary_transfers", [])?;
+        for transfer in &canaries.transfers {
+            tx.execute(
+                "INSERT INTO canary_transfers (id, status, data) VALUES (?1, ?2, ?3)",
+                params![
+                    transfer.id as i64,
+                    format!("{:?}", transfer.status),
+                    serde_json::to_string(transfer)?
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn load_pending(&self) -> Result<PendingStore> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT data FROM pending_transactions ORDER BY sender, nonce")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        let transactions = rows
+            .map(|data| Ok(serde_json::from_str(&data?)?))
+            .collect::<Result<_>>()
+            .context("Failed to parse the stored pending transactions")?;
+        Ok(PendingStore { transactions })
+    }
+
+    pub fn save_pending(&mut self, store: &PendingStore) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM pending_transactions", [])?;
+        for pending in &store.transactions {
+            tx.execute(
+                "INSERT INTO pending_transactions (hash, sender, nonce, data) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    format!("0x{:x}", pending.hash),
+                    format!("0x{:x}", pending.from),
+                    pending.nonce as i64,
+                    serde_json::to_string(pending)?
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn load_payroll(&self) -> Result<Payroll> {
+        let mut payroll = Payroll::default();
+        let mut stmt = self
+            .conn
+            .prepare("SELECT data FROM payroll_roster ORDER BY rowid")?;
+        for data in stmt.query_map([], |row| row.get::<_, String>(0))? {
+            payroll
+                .roster
+                .push(serde_json::from_str(&data?).context("Failed to parse the payroll roster")?);
+        }
+        let mut stmt = self
+            .conn
+            .prepare("SELECT data FROM payroll_batches ORDER BY month")?;
+        for data in stmt.query_map([], |row| row.get::<_, String>(0))? {
+            payroll
+                .batches
+                .push(serde_json::from_str(&data?).context("Failed to parse a payroll batch")?);
+        }
+        Ok(payroll)
+    }
+
+    pub fn save_payroll(&mut self, payroll: &Payroll) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM payroll_roster", [])?;
+        for entry in &payroll.roster {
+            tx.execute(
+                "INSERT INTO payroll_roster (contact, data) VALUES (?1, ?2)",
+                params![entry.contact, serde_json::to_string(entry)?],
+            )?;
+        }
+        tx.execute("DELETE FROM payroll_batches", [])?;
+        for batch in &payroll.batches {
+            tx.execute(
+                "INSERT INTO payroll_batches (month, status, data) VALUES (?1, ?2, ?3)",
+                params![
+                    batch.month,
+                    format!("{:?}", batch.status),
+                    serde_json::to_string(batch)?
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Append a signed transaction to the log; the log is never rewritten
+    pub fn record_signed(&self, signed: &SignedTx) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO signed_transactions (hash, signed_at, data) VALUES (?1, ?2, ?3)",
+            params![
+                format!("0x{:x}", signed.hash),
+                signed.signed_at.timestamp(),
+                serde_json::to_string(signed)?
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn load_signed(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<SignedTx>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT data FROM signed_transactions
+             WHERE signed_at >= ?1 AND signed_at < ?2 ORDER BY signed_at",
+        )?;
+        let rows = stmt.query_map(params![start.timestamp(), end.timestamp()], |row| {
+            row.get::<_, String>(0)
+        })?;
+        rows.map(|data| Ok(serde_json::from_str(&data?)?))
+            .collect::<Result<_>>()
+            .context("Failed to parse the signed transaction log")
+    }
+
+    pub fn record_cost_allocation(&self, hash: B256, cost_center: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO cost_allocations (hash, cost_center) VALUES (?1, ?2)",
+            params![format!("0x{:x}", hash), cost_center],
+        )?;
+        Ok(())
+    }
+
+    pub fn load_cost_allocations(&self) -> Result<HashMap<B256, String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT hash, cost_center FROM cost_allocations")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        rows.map(|row| {
+            let (hash, cost_center) = row?;
+            Ok((hash.parse()?, cost_center))
+        })
+        .collect::<Result<_>>()
+        .context("Failed to parse the stored cost allocations")
+    }
+
+    pub fn record_last_amount(
+        &self,
+        network: &str,
+        recipient: &Address,
+        token: &Address,
+        amount: &str,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO last_amounts (network, recipient, token, amount) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                network,
+                format!("{:#x}", recipient),
+                format!("{:#x}", token),
+                amount
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn last_amount(
+        &self,
+        network: &str,
+        recipient: &Address,
+        token: &Address,
+    ) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT amount FROM last_amounts WHERE network = ?1 AND recipient = ?2 AND token = ?3",
+                params![network, format!("{:#x}", recipient), format!("{:#x}", token)],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub fn record_sponsor_grant(
+        &self,
+        network: &str,
+        address: &Address,
+        amount: U256,
+        tx_hash: B256,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO sponsor_grants (network, address, amount, tx_hash, granted_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                network,
+                format!("{:#x}", address),
+                amount.to_string(),
+                format!("{:#x}", tx_hash),
+                Utc::now().timestamp()
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn sponsor_grants(&self, network: &str) -> Result<Vec<(Address, U256)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT address, amount FROM sponsor_grants WHERE network = ?1 ORDER BY granted_at",
+        )?;
+        let rows = stmt.query_map(params![network], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        rows.map(|row| {
+            let (address, amount) = row?;
+            Ok((address.parse()?, amount.parse()?))
+        })
+        .collect::<Result<_>>()
+        .context("Failed to parse the sponsor grants")
+    }
+
+    pub fn load_contract_abis(&self) -> Result<AbiRegistry> {
+        let mut registry = AbiRegistry::default();
+        let mut stmt = self
+            .conn
+            .prepare("SELECT network, name, data FROM contract_abis")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+        for row in rows {
+            let (network, name, data) = row?;
+            let contract: RegisteredContract =
+                serde_json::from_str(&data).context("Failed to parse the ABI registry")?;
+            match network.as_str() {
+                "mainnet" => registry.mainnet.insert(name, contract),
+                _ => registry.testnet.insert(name, contract),
+            };
+        }
+        Ok(registry)
+    }
+
+    pub fn save_contract_abis(&mut self, registry: &AbiRegistry) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM contract_abis", [])?;
+        let networks = [
+            ("mainnet", &registry.mainnet),
+            ("testnet", &registry.testnet),
+        ];
+        for (network, contracts) in networks {
+            for (name, contract) in contracts {
+                tx.execute(
+                    "INSERT INTO contract_abis (network, name, data) VALUES (?1, ?2, ?3)",
+                    params![network, name, serde_json::to_string(contract)?],
+                )?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Write everything out as the JSON files used by earlier versions
+    pub fn export_json(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir)?;
+        let files = [
+            (LEGACY_WALLETS, to_json(&self.load_wallets()?)?),
+            (LEGACY_CONTACTS, to_json(&self.load_contacts()?)?),
+            (LEGACY_HISTORY, to_json(&self.load_history()?)?),
+            (LEGACY_QUEUE, to_json(&self.load_queue()?)?),
+            (LEGACY_LABELS, to_json(&self.load_labels()?)?),
+        ];
+        let mut written = Vec::new();
+        for (file, content) in files {
+            let path = dir.join(file);
+            fs::write(&path, content)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+}
+
+/// Open the database at `path` with `key`; reading the schema fails when
+/// the key is wrong
+fn unlock(path: &Path, key: &str) -> Result<Connection> {
+    let conn = Connection::open(path).context("Failed to open the wallet database")?;
+    conn.pragma_update(None, "key", key)?;
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))?;
+    Ok(conn)
+}
+
+fn env_key() -> Option<String> {
+    std::env::var(KEY_ENV).ok().filter(|key| !key.is_empty())
+}
+
+/// Key file of earlier versions
+fn legacy_key_path() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .context("Could not find config directory")?
+        .join("rootstock-wallet")
+        .join("storage.key"))
+}
+
+/// The database key, from the OS keyring. A key file left by earlier
+/// versions is copied there on first use; otherwise a random key is created.
+fn database_key() -> Result<String> {
+    if let Some(key) = env_key() {
+        return Ok(key);
+    }
+
+    let legacy = legacy_key_path()?;
+    let unavailable = |e: anyhow::Error| {
+        anyhow!(
+            "{}. Set {} to the database key{}",
+            e,
+            KEY_ENV,
+            if legacy.exists() {
+                format!(" kept in {}", legacy.display())
+            } else {
+                String::new()
+            }
+        )
+    };
+    if let Some(key) = secrets::database_key().map_err(unavailable)? {
+        return Ok(key);
+    }
+
+    let created = !legacy.exists();
+    let key = if legacy.exists() {
+        fs::read_to_string(&legacy)
+            .context("Failed to read the database key file")?
+            .trim()
+            .to_string()
+    } else {
+        let mut key = [0u8; 32];
+        OsRng.fill_bytes(&mut key);
+        hex::encode(key)
+    };
+    secrets::set_database_key(&key).map_err(unavailable)?;
+    if secrets::database_key().map_err(unavailable)?.as_deref() != Some(key.as_str()) {
+        return Err(unavailable(anyhow!("The keyring did not keep the database key")));
+    }
+    if created {
+        eprintln!(
+            "{}: Created a new database key in the OS keyring. Back it up with \
+             `wallet backup-key <file>`; without it the wallets cannot be read if the \
+             keyring is lost.",
+            "Info".yellow().bold()
+        );
+    }
+    Ok(key)
+}
+
+/// Check an import stored as many rows as the legacy file had entries
+fn verify_import(tx: &Transaction, table: &str, expected: usize, file: &str) -> Result<()> {
+    let stored: i64 =
+        tx.query_row(&format!("SELECT count(*) FROM {}", table), [], |row| row.get(0))?;
+    if stored as usize != expected {
+        return Err(anyhow!(
+            "Importing {} stored {} of its {} entries; the file was left in place",
+            file,
+            stored,
+            expected
+        ));
+    }
+    Ok(())
+}
+
+/// Delete a legacy file once its content is in the database
+fn remove_legacy(path: &Path) -> Result<()> {
+    if path.exists() {
+        remove_securely(path).with_context(|| format!("Failed to delete {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Overwrite a file with zeros before deleting it, so its plaintext does not
+/// stay in the freed blocks. Journaling, copy-on-write and flash storage may
+/// still keep old copies; this only removes the obvious one.
+fn remove_securely(path: &Path) -> Result<()> {
+    let len = fs::metadata(path)?.len();
+    let mut file = fs::OpenOptions::new().write(true).open(path)?;
+    file.write_all(&vec![0u8; len as usize])?;
+    file.sync_all()?;
+    drop(file);
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+fn read_legacy<T: DeserializeOwned>(path: &Path) -> Result<Option<T>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String> {
+    Ok(serde_json::to_string_pretty(value)?)
+}
+
+fn set_meta(tx: &Transaction, key: &str, value: Option<&str>) -> Result<()> {
+    match value {
+        Some(value) => tx.execute(
+            "INSERT INTO meta (key, value) VALUES (?1, ?2)
+             ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?,
+        None => tx.execute("DELETE FROM meta WHERE key = ?1", [key])?,
+    };
+    Ok(())
+}
+
+fn write_wallets(tx: &Transaction, wallet_data: &WalletData) -> Result<()> {
+    tx.execute("DELETE FROM wallets", [])?;
+    for (address, wallet) in &wallet_data.wallets {
+        tx.execute(
+            "INSERT INTO wallets (address, name, data) VALUES (?1, ?2, ?3)",
+            params![address, wallet.name, serde_json::to_string(wallet)?],
+        )
+        .with_context(|| format!("Failed to store wallet '{}'", wallet.name))?;
+    }
+    set_meta(tx, "current_wallet", Some(&wallet_data.current_wallet))?;
+    set_meta(tx, "api_key", wallet_data.api_key.as_deref())?;
+    set_meta(
+        tx,
+        "wallet_contacts",
+        Some(&serde_json::to_string(&wallet_data.contacts)?),
+    )
+}
+
+fn write_contacts(tx: &Transaction, contacts: &[Contact]) -> Result<()> {
+    tx.execute("DELETE FROM contacts", [])?;
+    for contact in contacts {
+        tx.execute(
+            "INSERT INTO contacts (address, name, data) VALUES (?1, ?2, ?3)",
+            params![
+                format!("{:#x}", contact.address),
+                contact.name,
+                serde_json::to_string(contact)?
+            ],
+        )
+        .with_context(|| format!("Failed to store contact '{}'", contact.name))?;
+    }
+    Ok(())
+}
+
+fn write_labels(tx: &Transaction, labels: &BTreeMap<Address, String>) -> Result<()> {
+    tx.execute("DELETE FROM labels", [])?;
+    for (address, label) in labels {
+        tx.execute(
+            "INSERT INTO labels (address, label) VALUES (?1, ?2)",
+            params![format!("{:#x}", address), label],
+        )?;
+    }
+    Ok(())
+}
+
+fn write_tokens(tx: &Transaction, registry: &TokenRegistry) -> Result<()> {
+    tx.execute("DELETE FROM token_registry", [])?;
+    let networks = [
+        ("mainnet", &registry.mainnet),
+        ("testnet", &registry.testnet),
+    ];
+    for (network, tokens) in networks {
+        for (address, info) in tokens {
+            tx.execute(
+                "INSERT INTO token_registry (network, address, data) VALUES (?1, ?2, ?3)",
+                params![network, address, serde_json::to_string(info)?],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// In-memory history cache key for a row: `network/wallet`
+fn history_key(network: &str, wallet: &str) -> String {
+    format!("{}/{}", network, wallet)
+}
+
+/// Split a history cache key back into network and wallet; keys of earlier
+/// versions that lack the network have none
+fn split_history_key(key: &str) -> Option<(&str, &str)> {
+    key.split_once('/')
+}
+
+fn write_history(tx: &Transaction, cache: &HistoryCache) -> Result<()> {
+    tx.execute("DELETE FROM history", [])?;
+    tx.execute("DELETE FROM history_sync", [])?;
+    tx.execute("DELETE FROM history_scan", [])?;
+    // Entries without a network are skipped, like the rows they came from
+    for (key, txs) in &cache.transactions {
+        let Some((network, wallet)) = split_history_key(key) else {
+            continue;
+        };
+        for transaction in txs {
+            let timestamp = transaction
+                .timestamp
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            tx.execute(
+                "INSERT OR REPLACE INTO history (wallet, hash, timestamp, data, network)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    wallet,
+                    format!("{:#x}", transaction.hash),
+                    timestamp,
+                    serde_json::to_string(transaction)?,
+                    network
+                ],
+            )?;
+        }
+    }
+    for (key, block) in &cache.synced_blocks {
+        let Some((network, wallet)) = split_history_key(key) else {
+            continue;
+        };
+        tx.execute(
+            "INSERT INTO history_sync (network, wallet, last_block) VALUES (?1, ?2, ?3)",
+            params![network, wallet, *block as i64],
+        )?;
+    }
+    for (key, block) in &cache.scanned_blocks {
+        let Some((network, wallet)) = split_history_key(key) else {
+            continue;
+        };
+        tx.execute(
+            "INSERT INTO history_scan (network, wallet, last_block) VALUES (?1, ?2, ?3)",
+            params![network, wallet, *block as i64],
+        )?;
+    }
+    Ok(())
+}
+
+fn write_queue(tx: &Transaction, queue: &TransferQueue) -> Result<()> {
+    tx.execute("DELETE FROM queue", [])?;
+    for transfer in &queue.transfers {
+        tx.execute(
+            "INSERT INTO queue (id, wallet, status, data) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                transfer.id as i64,
+                transfer.wallet,
+                format!("{:?}", transfer.status),
+                serde_json::to_string(transfer)?
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_and_export_legacy_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let contact = Contact::new("alice".to_string(), Address::repeat_byte(1), None, vec![]);
+        fs::write(
+            dir.path().join(LEGACY_CONTACTS),
+            serde_json::to_string(&vec![contact]).unwrap(),
+        )
+        .unwrap();
+
+        let mut storage = Storage::with_connection(Connection::open_in_memory().unwrap()).unwrap();
+        storage.import_legacy(dir.path()).unwrap();
+        assert_eq!(storage.load_contacts().unwrap()[0].name, "alice");
+
+        // Labels are imported on their own, also into existing databases
+        let cold = Address::repeat_byte(3);
+        fs::write(
+            dir.path().join(LEGACY_LABELS),
+            format!("{{\"{:#x}\": \"cold storage\"}}", cold),
+        )
+        .unwrap();
+        storage.import_legacy_labels(dir.path()).unwrap();
+        assert_eq!(storage.load_labels().unwrap()[&cold], "cold storage");
+        assert!(storage.load_wallets().unwrap().wallets.is_empty());
+
+        // The files and the old key file stay until the key is backed up
+        let key_file = dir.path().join("storage.key");
+        fs::write(&key_file, "00ff\n").unwrap();
+        storage.remove_migrated(dir.path(), &key_file, "00ff").unwrap();
+        assert!(dir.path().join(LEGACY_CONTACTS).exists());
+        assert!(key_file.exists());
+        let tx = storage.conn.transaction().unwrap();
+        set_meta(&tx, "key_backed_up", Some("1")).unwrap();
+        tx.commit().unwrap();
+        // A key file holding another key is not the one in use
+        storage.remove_migrated(dir.path(), &key_file, "11ee").unwrap();
+        assert!(key_file.exists());
+        storage.remove_migrated(dir.path(), &key_file, "00ff").unwrap();
+        assert!(!dir.path().join(LEGACY_CONTACTS).exists());
+        assert!(!dir.path().join(LEGACY_LABELS).exists());
+        assert!(!key_file.exists());
+
+        // A second open must not import again
+        let fingerprint = storage.fingerprint().unwrap();
+        storage.save_contacts(&[]).unwrap();
+        assert_ne!(storage.fingerprint().unwrap(), fingerprint);
+        storage.import_legacy(dir.path()).unwrap();
+        assert!(storage.load_contacts().unwrap().is_empty());
+
+        let export = dir.path().join("export");
+        assert_eq!(storage.export_json(&export).unwrap().len(), 5);
+        assert!(export.join(LEGACY_LABELS).exists());
+        assert_eq!(
+            fs::read_to_string(export.join(LEGACY_CONTACTS))
+                .unwrap()
+                .trim(),
+            "[]"
+        );
+
+        let bob = Address::repeat_byte(2);
+        storage
+            .record_last_amount("testnet", &bob, &Address::ZERO, "0.5")
+            .unwrap();
+        assert_eq!(
+            storage
+                .last_amount("testnet", &bob, &Address::ZERO)
+                .unwrap()
+                .as_deref(),
+            Some("0.5")
+        );
+        assert!(storage.last_amount("mainnet", &bob, &Address::ZERO).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_history_without_network_is_dropped() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        conn.execute(
+            "INSERT INTO history (wallet, hash, timestamp, data, network)
+             VALUES ('0x01', '0x02', 0, '{}', '')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO history_sync (network, wallet, last_block) VALUES ('', '0x01', 7)",
+            [],
+        )
+        .unwrap();
+        let storage = Storage::with_connection(conn).unwrap();
+        let cache = storage.load_history().unwrap();
+        assert!(cache.transactions.is_empty());
+        assert!(cache.synced_blocks.is_empty());
+
+        // The migration runs once, not on every open
+        storage
+            .conn
+            .execute(
+                "INSERT INTO history_scan (network, wallet, last_block) VALUES ('', '0x01', 3)",
+                [],
+            )
+            .unwrap();
+        let mut storage = Storage::with_connection(storage.conn).unwrap();
+        let scans: i64 = storage
+            .conn
+            .query_row("SELECT count(*) FROM history_scan", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(scans, 1);
+
+        // Legacy JSON keys without a network are not imported either
+        let mut legacy = HistoryCache::default();
+        legacy.synced_blocks.insert("0x01".to_string(), 7);
+        legacy.synced_blocks.insert("testnet/0x01".to_string(), 9);
+        storage.save_history(&legacy).unwrap();
+        let cache = storage.load_history().unwrap();
+        assert_eq!(cache.synced_blocks.len(), 1);
+        assert_eq!(cache.synced_blocks["testnet/0x01"], 9);
+    }
+}
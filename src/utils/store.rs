@@ -0,0 +1,204 @@
+//! Storage backends for contacts (with their tags and notes), address
+//! labels and the token registry.
+//!
+//! Command code goes through [`open`] and the [`Store`] trait instead of a
+//! concrete backend, so a new backend (e.g. a remote one) only needs an
+//! implementation here. The encrypted SQLite database is the default; set
+//! `ROOTSTOCK_WALLET_JSON_STORE` to a directory to keep plain JSON files
+//! there instead. [`MemoryStore`] keeps everything in memory for tests.
+
+use crate::commands::tokens::TokenRegistry;
+use crate::types::contacts::Contact;
+use crate::utils::storage::Storage;
+use alloy::primitives::Address;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Directory for the JSON backend, when it should be used
+pub const JSON_STORE_ENV: &str = "ROOTSTOCK_WALLET_JSON_STORE";
+
+pub trait Store {
+    fn load_contacts(&self) -> Result<Vec<Contact>>;
+    fn save_contacts(&mut self, contacts: &[Contact]) -> Result<()>;
+    fn load_labels(&self) -> Result<BTreeMap<Address, String>>;
+    fn save_labels(&mut self, labels: &BTreeMap<Address, String>) -> Result<()>;
+    fn load_tokens(&self) -> Result<TokenRegistry>;
+    fn save_tokens(&mut self, registry: &TokenRegistry) -> Result<()>;
+}
+
+/// The configured backend
+pub fn open() -> Result<Box<dyn Store>> {
+    match std::env::var(JSON_STORE_ENV) {
+        Ok(dir) if !dir.is_empty() => Ok(Box::new(JsonStore::new(dir))),
+        _ => Ok(Box::new(Storage::open()?)),
+    }
+}
+
+impl Store for Storage {
+    fn load_contacts(&self) -> Result<Vec<Contact>> {
+        Storage::load_contacts(self)
+    }
+
+    fn save_contacts(&mut self, contacts: &[Contact]) -> Result<()> {
+        Storage::save_contacts(self, contacts)
+    }
+
+    fn load_labels(&self) -> Result<BTreeMap<Address, String>> {
+        Storage::load_labels(self)
+    }
+
+    fn save_labels(&mut self, labels: &BTreeMap<Address, String>) -> Result<()> {
+        Storage::save_labels(self, labels)
+    }
+
+    fn load_tokens(&self) -> Result<TokenRegistry> {
+        Storage::load_tokens(self)
+    }
+
+    fn save_tokens(&mut self, registry: &TokenRegistry) -> Result<()> {
+        Storage::save_tokens(self, registry)
+    }
+}
+
+/// Plain JSON files in a directory, in the format of earlier versions; token
+/// registries keyed by symbol are re-keyed by address when read
+pub struct JsonStore {
+    dir: PathBuf,
+}
+
+impl JsonStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn read<T: DeserializeOwned + Default>(&self, file: &str) -> Result<T> {
+        let path = self.dir.join(file);
+        if !path.exists() {
+            return Ok(T::default());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    fn write<T: Serialize + ?Sized>(&self, file: &str, value: &T) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(file);
+        fs::write(&path, serde_json::to_string_pretty(value)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+impl Store for JsonStore {
+    fn load_contacts(&self) -> Result<Vec<Contact>> {
+        self.read("contacts.json")
+    }
+
+    fn save_contacts(&mut self, contacts: &[Contact]) -> Result<()> {
+        self.write("contacts.json", contacts)
+    }
+
+    fn load_labels(&self) -> Result<BTreeMap<Address, String>> {
+        self.read("labels.json")
+    }
+
+    fn save_labels(&mut self, labels: &BTreeMap<Address, String>) -> Result<()> {
+        self.write("labels.json", labels)
+    }
+
+    fn load_tokens(&self) -> Result<TokenRegistry> {
+        self.read::<TokenRegistry>("tokens.json")
+            .map(TokenRegistry::normalized)
+    }
+
+    fn save_tokens(&mut self, registry: &TokenRegistry) -> Result<()> {
+        self.write("tokens.json", registry)
+    }
+}
+
+/// Keeps everything in memory; nothing is persisted
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    pub contacts: Vec<Contact>,
+    pub labels: BTreeMap<Address, String>,
+    pub tokens: TokenRegistry,
+}
+
+impl Store for MemoryStore {
+    fn load_contacts(&self) -> Result<Vec<Contact>> {
+        Ok(self.contacts.clone())
+    }
+
+    fn save_contacts(&mut self, contacts: &[Contact]) -> Result<()> {
+        self.contacts = contacts.to_vec();
+        Ok(())
+    }
+
+    fn load_labels(&self) -> Result<BTreeMap<Address, String>> {
+        Ok(self.labels.clone())
+    }
+
+    fn save_labels(&mut self, labels: &BTreeMap<Address, String>) -> Result<()> {
+        self.labels = labels.clone();
+        Ok(())
+    }
+
+    fn load_tokens(&self) -> Result<TokenRegistry> {
+        Ok(self.tokens.clone())
+    }
+
+    fn save_tokens(&mut self, registry: &TokenRegistry) -> Result<()> {
+        self.tokens = registry.clone();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every backend must round-trip contacts (with tags), labels and tokens
+    fn round_trip(store: &mut dyn Store) {
+        assert!(store.load_contacts().unwrap().is_empty());
+        let contact = Contact::new(
+            "alice".to_string(),
+            Address::repeat_byte(1),
+            None,
+            vec!["exchange".to_string()],
+        );
+        store.save_contacts(&[contact]).unwrap();
+        assert_eq!(store.load_contacts().unwrap()[0].tags, vec!["exchange"]);
+
+        assert!(store.load_labels().unwrap().is_empty());
+        let labels = BTreeMap::from([(Address::repeat_byte(3), "cold storage".to_string())]);
+        store.save_labels(&labels).unwrap();
+        assert_eq!(store.load_labels().unwrap(), labels);
+
+        let mut registry = store.load_tokens().unwrap();
+        registry
+            .add_token(
+                "testnet",
+                "rif",
+                &format!("{:#x}", Address::repeat_byte(2)),
+                18,
+            )
+            .unwrap();
+        store.save_tokens(&registry).unwrap();
+        let tokens = store.load_tokens().unwrap();
+        assert_eq!(tokens.resolve("testnet", "rif").unwrap().decimals, 18);
+    }
+
+    #[test]
+    fn test_backends_round_trip() {
+        round_trip(&mut MemoryStore::default());
+
+        let dir = tempfile::tempdir().unwrap();
+        round_trip(&mut JsonStore::new(dir.path()));
+        assert!(dir.path().join("tokens.json").exists());
+    }
+}
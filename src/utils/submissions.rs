@@ -0,0 +1,179 @@
+//! Follow-up on the transactions this run submits, whatever command sent
+//! them: transfers, queued and bulk transfers, payroll, canaries, contract
+//! and NFT calls, speed-ups and cancellations, sponsoring and rotations.
+//!
+//! A `sent` webhook goes out as soon as a transaction is broadcast. The
+//! first receipt fetched for it through the client then sends `confirmed`
+//! or `failed` and shows a desktop notification, so every path that waits
+//! for its transaction reports the outcome the same way.
+
+use crate::commands::tokens::TokenRegistry;
+use crate::utils::eth::IERC20;
+use crate::utils::event_webhooks::{self, WalletEvent};
+use crate::utils::notifications;
+use alloy::primitives::utils::format_units;
+use alloy::primitives::{Address, B256, U256};
+use alloy::rpc::types::{TransactionReceipt, TransactionRequest};
+use alloy::sol_types::SolCall;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// A submitted transaction as webhooks and notifications describe it
+#[derive(Debug, Clone, PartialEq)]
+struct Submission {
+    /// Amount moved, in whole RBTC or tokens
+    amount: String,
+    symbol: String,
+    /// One line for people, e.g. "0.5 RBTC to 0x… (tx 0x…)"
+    summary: String,
+    /// Shaped like a transfer's `--json` output
+    details: Value,
+}
+
+/// Submissions of this run whose receipt has not been seen yet
+static AWAITING: Mutex<Option<HashMap<B256, Submission>>> = Mutex::new(None);
+
+/// Announce a transaction that was just broadcast and remember it until its
+/// receipt comes in
+pub fn submitted(tx_hash: B256, request: &TransactionRequest) {
+    let submission = describe(tx_hash, request, registered_token);
+    event_webhooks::deliver(WalletEvent::Sent, &submission.summary, submission.details.clone());
+    AWAITING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(HashMap::new)
+        .insert(tx_hash, submission);
+}
+
+/// Report the outcome of a transaction from `submitted` the first time its
+/// receipt is seen; receipts of other transactions are ignored
+pub fn mined(receipt: &TransactionReceipt) {
+    let Some(mut submission) = AWAITING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_mut()
+        .and_then(|awaiting| awaiting.remove(&receipt.transaction_hash))
+    else {
+        return;
+    };
+    let succeeded = receipt.status();
+    submission.details["status"] = json!(if succeeded { "success" } else { "failed" });
+    submission.details["gas_used"] = json!(receipt.gas_used.to_string());
+    submission.details["gas_price"] = json!(receipt.effective_gas_price.to_string());
+    let event = if succeeded {
+        WalletEvent::Confirmed
+    } else {
+        WalletEvent::Failed
+    };
+    event_webhooks::deliver(event, &submission.summary, submission.details);
+    notifications::transfer_mined(
+        receipt.transaction_hash,
+        &submission.amount,
+        &submission.symbol,
+        succeeded,
+    );
+}
+
+/// Symbol and decimals of a token in the registry of either network
+fn registered_token(token: Address) -> Option<(String, u8)> {
+    let registry = TokenRegistry::load().ok()?;
+    let key = format!("0x{:x}", token);
+    registry
+        .mainnet
+        .get(&key)
+        .or_else(|| registry.testnet.get(&key))
+        .map(|info| (info.symbol.clone(), info.decimals))
+}
+
+/// Describe a submission. Token transfers are told by their `transfer`
+/// call and name the token recipient; other contract calls name the
+/// contract.
+fn describe(
+    tx_hash: B256,
+    request: &TransactionRequest,
+    token_info: impl Fn(Address) -> Option<(String, u8)>,
+) -> Submission {
+    let to = request.to.and_then(|kind| kind.to().copied());
+    let input = request.input.input().cloned().unwrap_or_default();
+    let transfer = IERC20::transferCall::abi_decode(&input, true).ok();
+
+    let (recipient, value, token) = match (&transfer, to) {
+        (Some(call), Some(token)) => (Some(call.recipient), call.amount, Some(token)),
+        _ => (to, request.value.unwrap_or(U256::ZERO), None),
+    };
+    let (symbol, decimals) = match token {
+        Some(token) => token_info(token).unwrap_or_else(|| (format!("token 0x{:x}", token), 0)),
+        None => ("RBTC".to_string(), 18),
+    };
+    let amount = format_units(value, decimals).unwrap_or_else(|_| value.to_string());
+    let target = recipient.map_or_else(|| "a new contract".to_string(), |r| format!("0x{:x}", r));
+    let summary = if transfer.is_none() && !input.is_empty() {
+        format!("Contract call to {} with {} RBTC (tx 0x{:x})", target, amount, tx_hash)
+    } else {
+        format!("{} {} to {} (tx 0x{:x})", amount, symbol, target, tx_hash)
+    };
+
+    let details = json!({
+        "tx_hash": format!("{:#x}", tx_hash),
+        "from": request.from.map(|from| format!("{:#x}", from)),
+        "to": recipient.map(|r| format!("{:#x}", r)),
+        "value": value.to_string(),
+        "token_address": token.map(|t| format!("{:#x}", t)),
+        "token_symbol": symbol.clone(),
+        "nonce": request.nonce,
+        "status": "pending",
+    });
+    Submission {
+        amount,
+        symbol,
+        summary,
+        details,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::network::TransactionBuilder;
+
+    #[test]
+    fn test_describe_submissions() {
+        let hash = B256::repeat_byte(0xab);
+        let from = Address::repeat_byte(1);
+        let to = Address::repeat_byte(2);
+        let token = Address::repeat_byte(7);
+        let known = |address: Address| (address == token).then(|| ("USDT".to_string(), 6));
+
+        let rbtc = TransactionRequest::default()
+            .with_from(from)
+            .with_to(to)
+            .with_value(U256::from(500_000_000_000_000_000u64))
+            .with_nonce(4);
+        let submission = describe(hash, &rbtc, known);
+        assert!(submission.summary.starts_with("0.500000000000000000 RBTC to 0x0202"));
+        assert_eq!(submission.details["token_address"], Value::Null);
+        assert_eq!(submission.details["nonce"], 4);
+        assert_eq!(submission.details["status"], "pending");
+
+        let call = IERC20::transferCall {
+            recipient: to,
+            amount: U256::from(1_500_000u64),
+        };
+        let transfer = TransactionRequest::default()
+            .with_from(from)
+            .with_to(token)
+            .with_input(call.abi_encode());
+        let submission = describe(hash, &transfer, known);
+        assert!(submission.summary.starts_with("1.500000 USDT to 0x0202"));
+        assert_eq!((submission.amount.as_str(), submission.symbol.as_str()), ("1.500000", "USDT"));
+        assert_eq!(submission.details["to"], format!("{:#x}", to));
+        assert_eq!(submission.details["token_address"], format!("{:#x}", token));
+
+        let contract = TransactionRequest::default()
+            .with_to(token)
+            .with_input(vec![0x12, 0x34, 0x56, 0x78]);
+        let submission = describe(hash, &contract, known);
+        assert!(submission.summary.starts_with("Contract call to 0x0707"));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::utils::redact;
 use colored::Colorize;
 use prettytable::{Cell, Row, Table};
 
@@ -25,7 +26,7 @@ impl TableBuilder {
     }
 
     pub fn add_row(&mut self, cells: &[&str]) -> &mut Self {
-        let row = Row::new(cells.iter().map(|c| Cell::new(c)).collect());
+        let row = Row::new(cells.iter().map(|c| Cell::new(&redact::cell(c))).collect());
         self.table.add_row(row);
         self
     }
@@ -0,0 +1,164 @@
+//! Fee-on-transfer token detection.
+//!
+//! Some tokens keep part of every transfer (a burn, tax or reflection), so the
+//! recipient is credited less than the amount sent. The transfer is simulated
+//! with `eth_call`: the sender's code is replaced by a small probe that reads
+//! the recipient's balance, makes the transfer and reads the balance again,
+//! returning the difference. Token calls made by the probe come from the
+//! sender's address, so balances and allowances are the real ones. Endpoints
+//! without state override support cannot run the simulation, and the transfer
+//! then goes ahead without the check.
+
+use crate::utils::http::RpcProvider;
+use alloy::network::TransactionBuilder;
+use alloy::primitives::{Address, Bytes, U256, hex, utils::format_units};
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionRequest;
+use alloy::rpc::types::state::{AccountOverride, StateOverride};
+use anyhow::{Result, anyhow};
+
+/// Probe run at the sender's address. Calldata is `token | to | amount`, each
+/// a 32-byte word; it returns `balanceOf(to)` after the transfer minus before,
+/// and bubbles up the revert data of a failing call.
+///
+/// ```text
+/// balanceOf(to)           STATICCALL token, result at 0x80, MLOAD
+/// transfer(to, amount)    CALL token
+/// balanceOf(to)           STATICCALL token, result at 0x80, MLOAD
+/// SUB, return the word
+/// fail: JUMPDEST, RETURNDATACOPY, REVERT
+/// ```
+const PROBE: [u8; 139] = hex!(
+    "6370a0823160e01b60005260203560045260206080602460006000355afa156100805760805163a9059cbb60e01b600052602035600452604035602452600060006044600060006000355af115610080576370a0823160e01b60005260203560045260206080602460006000355afa15610080576080510360005260206000f35b3d600060003e3d6000fd"
+);
+
+/// A transfer whose recipient is credited less than the amount sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferFee {
+    pub sent: U256,
+    pub received: U256,
+}
+
+impl TransferFee {
+    /// Compare the amount sent with the amount credited; `None` when the
+    /// recipient gets the full amount (or more, for rebasing tokens)
+    pub fn detect(sent: U256, received: U256) -> Option<Self> {
+        (received < sent).then_some(Self { sent, received })
+    }
+
+    pub fn fee(&self) -> U256 {
+        self.sent - self.received
+    }
+
+    /// Share of the amount kept by the token, in percent
+    pub fn percent(&self) -> f64 {
+        if self.sent.is_zero() {
+            return 0.0;
+        }
+        let fee: f64 = self.fee().into();
+        let sent: f64 = self.sent.into();
+        fee / sent * 100.0
+    }
+
+    /// Warning shown to the sender before the transfer goes out
+    pub fn warning(&self, symbol: &str, decimals: u8) -> String {
+        let amount = |value: U256| format_units(value, decimals).unwrap_or_else(|_| value.to_string());
+        format!(
+            "{} takes a fee on transfer: the recipient will receive {} {} of the {} sent ({} {} or {:.2}% kept by the token)",
+            symbol,
+            amount(self.received),
+            symbol,
+            amount(self.sent),
+            amount(self.fee()),
+            symbol,
+            self.percent()
+        )
+    }
+}
+
+/// Amount credited to `to` when `from` transfers `amount` of `token`
+pub async fn simulate(
+    provider: &RpcProvider,
+    token: Address,
+    from: Address,
+    to: Address,
+    amount: U256,
+) -> Result<U256> {
+    let mut input = Vec::with_capacity(96);
+    input.extend_from_slice(token.into_word().as_slice());
+    input.extend_from_slice(to.into_word().as_slice());
+    input.extend_from_slice(&amount.to_be_bytes::<32>());
+    let tx = TransactionRequest::default()
+        .with_to(from)
+        .with_input(Bytes::from(input));
+
+    let mut overrides = StateOverride::default();
+    overrides.insert(
+        from,
+        AccountOverride {
+            code: Some(Bytes::from_static(&PROBE)),
+            ..Default::default()
+        },
+    );
+    let output = provider
+        .call(&tx)
+        .overrides(&overrides)
+        .await
+        .map_err(|e| anyhow!("Failed to simulate the transfer: {}", e))?;
+    received_amount(&output)
+}
+
+/// Decode the probe's return value
+fn received_amount(output: &[u8]) -> Result<U256> {
+    let word: [u8; 32] = output
+        .try_into()
+        .map_err(|_| anyhow!("Unexpected simulation result of {} bytes", output.len()))?;
+    Ok(U256::from_be_bytes(word))
+}
+
+/// Simulate the transfer and report a fee on transfer. Transfers to
+/// oneself and endpoints that cannot simulate are not checked.
+pub async fn check(
+    provider: &RpcProvider,
+    token: Address,
+    from: Address,
+    to: Address,
+    amount: U256,
+) -> Option<TransferFee> {
+    if from == to || amount.is_zero() {
+        return None;
+    }
+    match simulate(provider, token, from, to, amount).await {
+        Ok(received) => TransferFee::detect(amount, received),
+        Err(e) => {
+            log::debug!("Skipping the fee-on-transfer check: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_fee() {
+        let sent = U256::from(1_000u64);
+        assert_eq!(TransferFee::detect(sent, sent), None);
+        // Rebasing tokens may credit slightly more
+        assert_eq!(TransferFee::detect(sent, U256::from(1_001u64)), None);
+
+        let fee = TransferFee::detect(sent, U256::from(980u64)).unwrap();
+        assert_eq!(fee.fee(), U256::from(20u64));
+        assert!((fee.percent() - 2.0).abs() < 1e-9);
+        assert_eq!(
+            fee.warning("TAX", 2),
+            "TAX takes a fee on transfer: the recipient will receive 9.80 TAX of the 10.00 sent (0.20 TAX or 2.00% kept by the token)"
+        );
+
+        let mut output = [0u8; 32];
+        output[31] = 98;
+        assert_eq!(received_amount(&output).unwrap(), U256::from(98u64));
+        assert!(received_amount(&[]).is_err());
+    }
+}
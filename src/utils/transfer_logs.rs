@@ -0,0 +1,174 @@
+//! History from ERC-20 `Transfer` logs read straight from the node.
+//!
+//! The last resort when no indexer (Alchemy, Blockscout or the Rootstock
+//! explorer) can be reached, or the network has none, such as a local
+//! Regtest node. Logs only show token transfers, not RBTC payments, and only
+//! recent blocks are scanned because nodes limit the range of log queries;
+//! `sync --full` walks the whole chain over as many sessions as it takes.
+
+use crate::types::transaction::RskTransaction;
+use crate::utils::eth::EthClient;
+use alloy::primitives::{Address, B256, b256};
+use alloy::providers::Provider;
+use alloy::rpc::types::{Filter, Log};
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// `keccak256("Transfer(address,address,uint256)")`
+pub const TRANSFER_TOPIC: B256 =
+    b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+/// Blocks scanned back from the head, about a week of Rootstock blocks
+pub const SCAN_BLOCKS: u64 = 20_000;
+/// Blocks covered by a single log query
+const CHUNK_BLOCKS: u64 = 1_000;
+
+/// Latest token transfers sent or received by `address` in the last
+/// [`SCAN_BLOCKS`] blocks, newest first
+pub async fn recent_transfers(
+    client: &EthClient,
+    address: Address,
+    limit: u32,
+) -> Result<Vec<RskTransaction>> {
+    let provider = client.provider();
+    let head = provider
+        .get_block_number()
+        .await
+        .map_err(|e| anyhow!("Failed to get block number: {}", e))?;
+    let oldest = head.saturating_sub(SCAN_BLOCKS);
+
+    let mut logs = Vec::new();
+    let mut to_block = head;
+    loop {
+        let from_block = to_block.saturating_sub(CHUNK_BLOCKS - 1).max(oldest);
+        logs.extend(chunk_logs(client, address, from_block, to_block).await?);
+        if from_block == oldest || logs.len() >= limit as usize {
+            break;
+        }
+        to_block = from_block - 1;
+    }
+
+    let logs = newest_transfers(logs, limit as usize);
+    to_transactions(client, &logs).await
+}
+
+/// Token transfers sent or received by `address` in blocks `from_block`
+/// through `to_block`, oldest first
+pub async fn transfers_between(
+    client: &EthClient,
+    address: Address,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<RskTransaction>> {
+    let mut logs = Vec::new();
+    let mut start = from_block;
+    while start <= to_block {
+        let end = start.saturating_add(CHUNK_BLOCKS - 1).min(to_block);
+        logs.extend(chunk_logs(client, address, start, end).await?);
+        start = end + 1;
+    }
+
+    let mut logs = newest_transfers(logs, usize::MAX);
+    logs.reverse();
+    to_transactions(client, &logs).await
+}
+
+/// Transfer logs from or to `address` in one range small enough for a
+/// single query
+async fn chunk_logs(
+    client: &EthClient,
+    address: Address,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<Log>> {
+    let topic = address.into_word();
+    let mut logs = Vec::new();
+    for filter in [
+        Filter::new().event_signature(TRANSFER_TOPIC).topic1(topic),
+        Filter::new().event_signature(TRANSFER_TOPIC).topic2(topic),
+    ] {
+        let filter = filter.from_block(from_block).to_block(to_block);
+        logs.extend(
+            client
+                .provider()
+                .get_logs(&filter)
+                .await
+                .map_err(|e| anyhow!("Failed to fetch logs: {}", e))?,
+        );
+    }
+    Ok(logs)
+}
+
+async fn to_transactions(client: &EthClient, logs: &[Log]) -> Result<Vec<RskTransaction>> {
+    let mut timestamps = HashMap::new();
+    let mut txs = Vec::new();
+    for log in logs {
+        let Some(block) = log.block_number else {
+            continue;
+        };
+        let timestamp = match timestamps.get(&block) {
+            Some(timestamp) => *timestamp,
+            None => {
+                let timestamp = client.get_block_timestamp(block).await?;
+                timestamps.insert(block, timestamp);
+                timestamp
+            }
+        };
+        txs.push(RskTransaction::from_transfer_log(
+            log,
+            UNIX_EPOCH + Duration::from_secs(timestamp),
+        )?);
+    }
+    Ok(txs)
+}
+
+/// The newest `limit` ERC-20 transfers among `logs`. A self-transfer matches
+/// both the sender and the recipient query, so duplicates are dropped, and
+/// ERC-721 transfers (which index the token id as a fourth topic) are left out.
+fn newest_transfers(mut logs: Vec<Log>, limit: usize) -> Vec<Log> {
+    logs.retain(|log| log.topics().len() == 3);
+    logs.sort_by_key(|log| std::cmp::Reverse((log.block_number, log.log_index)));
+    logs.dedup_by_key(|log| (log.transaction_hash, log.log_index));
+    logs.truncate(limit);
+    logs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Bytes, U256};
+
+    #[test]
+    fn test_newest_transfers() {
+        let wallet = Address::repeat_byte(1);
+        let transfer = |block: u64, index: u64, topics: Vec<B256>| Log {
+            inner: alloy::primitives::Log::new_unchecked(
+                Address::repeat_byte(7),
+                topics,
+                Bytes::from(U256::from(2500).to_be_bytes::<32>().to_vec()),
+            ),
+            block_number: Some(block),
+            log_index: Some(index),
+            transaction_hash: Some(B256::repeat_byte(block as u8)),
+            ..Default::default()
+        };
+        let erc20 = vec![TRANSFER_TOPIC, wallet.into_word(), wallet.into_word()];
+        let erc721 = vec![TRANSFER_TOPIC, wallet.into_word(), B256::ZERO, B256::ZERO];
+
+        // Found by both queries
+        let logs = vec![
+            transfer(10, 0, erc20.clone()),
+            transfer(12, 1, erc20.clone()),
+            transfer(10, 0, erc20.clone()),
+            transfer(11, 0, erc721),
+        ];
+        let newest = newest_transfers(logs, 5);
+        assert_eq!(newest.len(), 2);
+        assert_eq!(newest[0].block_number, Some(12));
+
+        let tx = RskTransaction::from_transfer_log(&newest[1], UNIX_EPOCH).unwrap();
+        assert_eq!((tx.from, tx.to), (wallet, Some(wallet)));
+        assert_eq!(tx.value, U256::from(2500));
+        assert_eq!(tx.token_address, Some(Address::repeat_byte(7)));
+    }
+}
@@ -0,0 +1,100 @@
+//! Watchdog for changes other programs make to the wallet database.
+//!
+//! Wallets and contacts are saved by replacing what is stored, so a save made
+//! from data read before another program changed the database (a second
+//! wallet session, a sync client restoring a copy) would silently undo that
+//! change. The interactive menus arm the watchdog with a fingerprint of the
+//! stored wallets and contacts. Between menu actions a changed fingerprint is
+//! simply taken in, since every action reads the data afresh; a save that
+//! finds it changed asks whether to reload or abort instead of writing.
+
+use crate::utils::storage::Storage;
+use anyhow::{Result, anyhow};
+use colored::Colorize;
+use std::sync::{Mutex, MutexGuard};
+
+const RELOAD: &str = "Reload: keep the other program's changes and don't save this one";
+const ABORT: &str = "Abort: quit without saving";
+
+/// Fingerprint of the data as last read or written; `None` until armed
+static KNOWN: Mutex<Option<u64>> = Mutex::new(None);
+
+fn known() -> MutexGuard<'static, Option<u64>> {
+    KNOWN.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Start watching for changes made by other programs
+pub fn arm() -> Result<()> {
+    let fingerprint = Storage::open()?.fingerprint()?;
+    *known() = Some(fingerprint);
+    Ok(())
+}
+
+/// Take in changes made by other programs since the last check and report
+/// whether there were any. Call only when no data read earlier is held.
+pub fn reload() -> Result<bool> {
+    let Some(last) = *known() else {
+        return Ok(false);
+    };
+    let fingerprint = Storage::open()?.fingerprint()?;
+    *known() = Some(fingerprint);
+    Ok(fingerprint != last)
+}
+
+/// Whether a save would overwrite changes made elsewhere, given the
+/// fingerprint last seen here and the one stored now
+fn is_stale(last: Option<u64>, stored: u64) -> bool {
+    last.is_some_and(|last| last != stored)
+}
+
+/// Check for outside changes before wallets or contacts are overwritten
+pub(crate) fn before_write(storage: &Storage) -> Result<()> {
+    let last = *known();
+    if last.is_none() {
+        return Ok(());
+    }
+    let stored = storage.fingerprint()?;
+    if !is_stale(last, stored) {
+        return Ok(());
+    }
+
+    println!(
+        "\n{}: The wallets or contacts were changed by another program since they were read here. Saving now would overwrite those changes.",
+        "Warning".yellow().bold()
+    );
+    let choice = inquire::Select::new("How would you like to continue?", vec![RELOAD, ABORT])
+        .prompt()
+        .unwrap_or(ABORT);
+    if choice == ABORT {
+        println!("Nothing was saved.");
+        std::process::exit(1);
+    }
+    *known() = Some(stored);
+    Err(anyhow!(
+        "Nothing was saved; the latest wallet data is loaded again. Repeat the action to apply it."
+    ))
+}
+
+/// Remember the data this session just wrote
+pub(crate) fn after_write(storage: &Storage) {
+    let mut known = known();
+    if known.is_some() {
+        match storage.fingerprint() {
+            Ok(fingerprint) => *known = Some(fingerprint),
+            Err(e) => log::warn!("Failed to fingerprint the wallet database: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_stale() {
+        // Not armed: one-shot commands save without checks
+        assert!(!is_stale(None, 1));
+        assert!(!is_stale(Some(1), 1));
+        assert!(is_stale(Some(1), 2));
+    }
+}
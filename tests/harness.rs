@@ -0,0 +1,392 @@
+//! Command paths exercised against the mock JSON-RPC server.
+//!
+//! Run with `cargo test --features test-harness`.
+#![cfg(feature = "test-harness")]
+
+use rootstock_wallet::commands::history::HistoryCommand;
+use rootstock_wallet::commands::transfer::check_gas_overrides;
+use rootstock_wallet::commands::tx::{FollowOutcome, TxCommand};
+use rootstock_wallet::commands::wallet::rotate_wallet;
+use rootstock_wallet::testing::{MockRpcServer, fixtures};
+use rootstock_wallet::types::contacts::Contact;
+use rootstock_wallet::types::network::NetworkConfig;
+use rootstock_wallet::types::wallet::{HardwareAccount, HardwareDevice, Wallet, WalletData};
+use rootstock_wallet::utils::alchemy::AlchemyClient;
+use rootstock_wallet::utils::eth::{EthClient, GasOverrides, broadcast_raw_transaction};
+use rootstock_wallet::utils::gas_analytics::{FeeRecord, GasAnalytics};
+use rootstock_wallet::security::session;
+use rootstock_wallet::utils::helper::{Config, PASSWORD_ENV, WalletConfig, unlock_wallet};
+use rootstock_wallet::utils::store;
+use alloy::consensus::{Transaction, TxEnvelope};
+use alloy::eips::eip2718::Decodable2718;
+use alloy::primitives::{Address, U256};
+use alloy::signers::local::PrivateKeySigner;
+use chrono::DateTime;
+use std::collections::BTreeMap;
+
+/// Client settings for the mock server, optionally with a signing key
+fn client_config(server: &MockRpcServer, private_key: Option<String>) -> Config {
+    Config {
+        network: NetworkConfig {
+            name: "Mock".to_string(),
+            rpc_url: server.url.clone(),
+            fallback_rpc_urls: Vec::new(),
+            explorer_url: String::new(),
+        },
+        wallet: WalletConfig {
+            current_wallet_address: None,
+            private_key,
+            mnemonic: None,
+        },
+    }
+}
+
+fn history_command(export_csv: Option<String>) -> HistoryCommand {
+    HistoryCommand {
+        address: Some(format!("{:#x}", fixtures::WALLET_ADDRESS)),
+        contact: None,
+        account: None,
+        limit: 10,
+        detailed: true,
+        status: None,
+        token: None,
+        from: None,
+        to: None,
+        sort_by: "timestamp".to_string(),
+        sort_order: "desc".to_string(),
+        export_csv,
+        export_beancount: None,
+        export_ledger: None,
+        export: None,
+        output: None,
+        incoming: false,
+        outgoing: false,
+        api_key: Some("test-key".to_string()),
+        network: "testnet".to_string(),
+        resync: false,
+        provider: None,
+    }
+}
+
+#[tokio::test]
+async fn history_fetches_and_exports_transfers() {
+    let server = MockRpcServer::shared();
+    let csv_path = server.data_dir.join("history.csv");
+
+    history_command(Some(csv_path.display().to_string()))
+        .execute()
+        .await
+        .expect("history command failed");
+
+    let csv = std::fs::read_to_string(&csv_path).unwrap();
+    assert_eq!(csv.lines().count(), 2);
+    assert!(csv.contains(&format!("{:#x}", fixtures::TX_HASH)));
+    assert!(server.methods().contains(&"alchemy_getAssetTransfers".to_string()));
+}
+
+#[tokio::test]
+async fn token_details_query_one_contract() {
+    let server = MockRpcServer::shared();
+    let token = Address::repeat_byte(0x7e);
+    let contract = format!("{:#x}", token);
+    let wallet = format!("{:#x}", fixtures::WALLET_ADDRESS);
+
+    let client = AlchemyClient::new("test-key".to_string(), true).unwrap();
+    for incoming in [false, true] {
+        let response = client
+            .get_token_transfers(&wallet, &contract, 5, incoming)
+            .await
+            .expect("token transfers failed");
+        assert!(response["result"]["transfers"].is_array());
+    }
+    let queries: Vec<_> = server
+        .requests()
+        .into_iter()
+        .filter(|r| r["params"][0]["contractAddresses"][0] == contract.as_str())
+        .map(|r| r["params"][0].clone())
+        .collect();
+    assert_eq!(queries.len(), 2);
+    assert_eq!(queries[0]["fromAddress"], wallet.as_str());
+    assert_eq!(queries[1]["toAddress"], wallet.as_str());
+    assert!(queries.iter().all(|q| q["category"] == serde_json::json!(["erc20"])));
+    assert!(queries.iter().all(|q| q["maxCount"] == "0x5"));
+
+    let eth_client = EthClient::new(&client_config(server, None), None).await.unwrap();
+    assert_eq!(eth_client.get_token_total_supply(token).await.unwrap(), U256::ZERO);
+}
+
+#[tokio::test]
+async fn tx_command_reads_receipt_and_details() {
+    let server = MockRpcServer::shared();
+
+    let cmd = TxCommand {
+        action: None,
+        tx_hash: Some(format!("{:#x}", fixtures::TX_HASH)),
+        testnet: true,
+        api_key: Some("test-key".to_string()),
+        follow: false,
+        confirmations: None,
+        timeout: 600,
+    };
+    cmd.execute().await.expect("tx command failed");
+
+    let methods = server.methods();
+    assert!(methods.contains(&"eth_getTransactionReceipt".to_string()));
+    assert!(methods.contains(&"eth_getTransactionByHash".to_string()));
+}
+
+#[tokio::test]
+async fn tx_follow_waits_for_the_requested_confirmations() {
+    let _server = MockRpcServer::shared();
+
+    // The receipt's block is the head: one confirmation
+    let mut cmd = TxCommand {
+        action: None,
+        tx_hash: Some(format!("{:#x}", fixtures::TX_HASH)),
+        testnet: true,
+        api_key: Some("test-key".to_string()),
+        follow: true,
+        confirmations: Some(1),
+        timeout: 0,
+    };
+    assert_eq!(cmd.follow().await.unwrap(), FollowOutcome::Confirmed);
+
+    cmd.confirmations = Some(3);
+    assert_eq!(cmd.follow().await.unwrap(), FollowOutcome::TimedOut);
+}
+
+#[tokio::test]
+async fn transfer_sends_rbtc_transaction() {
+    let server = MockRpcServer::shared();
+
+    let config = client_config(server, Some(fixtures::PRIVATE_KEY.to_string()));
+    let client = EthClient::new(&config, None).await.unwrap();
+
+    let tx_hash = client
+        .send_transaction(fixtures::RECIPIENT_ADDRESS, U256::from(1_000u64), None)
+        .await
+        .expect("transfer failed");
+    assert_eq!(tx_hash, fixtures::TX_HASH);
+
+    let receipt = client.wait_for_receipt(tx_hash).await.unwrap();
+    assert!(receipt.status());
+}
+
+#[tokio::test]
+async fn balance_snapshots_read_past_blocks() {
+    let server = MockRpcServer::shared();
+    let client = EthClient::new(&client_config(server, None), None).await.unwrap();
+
+    let balance = client
+        .get_balance_at(&fixtures::WALLET_ADDRESS, &None, 8)
+        .await
+        .unwrap();
+    assert_eq!(balance, U256::from(1_000_000_000_000_000_000u64));
+    let at_block = server
+        .requests()
+        .into_iter()
+        .rev()
+        .find(|r| r["method"] == "eth_getBalance")
+        .unwrap();
+    assert_eq!(at_block["params"][1], "0x8");
+
+    // Every mock block has the same timestamp: later times resolve to the
+    // head, earlier ones to no block at all
+    let mined = DateTime::from_timestamp(fixtures::BLOCK_TIMESTAMP as i64, 0).unwrap();
+    let after = client.block_before(mined + chrono::Duration::hours(1)).await;
+    assert_eq!(after.unwrap(), 16);
+    assert!(client.block_before(mined).await.is_err());
+}
+
+#[tokio::test]
+async fn memo_is_carried_as_the_transfer_input() {
+    let server = MockRpcServer::shared();
+    let config = client_config(server, Some(fixtures::PRIVATE_KEY.to_string()));
+    let client = EthClient::new(&config, None).await.unwrap();
+
+    let tx = client
+        .prepare_transfer(
+            fixtures::RECIPIENT_ADDRESS,
+            U256::from(1_000u64),
+            None,
+            GasOverrides::default(),
+            Some(b"tag 1234"),
+        )
+        .await
+        .expect("transfer with memo failed");
+    assert_eq!(tx.input.input().map(|input| input.to_vec()), Some(b"tag 1234".to_vec()));
+}
+
+#[test]
+fn fee_records_are_kept_once_per_transaction() {
+    let _server = MockRpcServer::shared();
+    let record = |fee: u64| FeeRecord {
+        tx_hash: fixtures::TX_HASH,
+        network: "testnet".to_string(),
+        timestamp: chrono::Utc::now(),
+        gas_used: U256::from(21_000u64),
+        effective_gas_price: U256::from(fee / 21_000),
+        fee: U256::from(fee),
+        estimated_fee: Some(U256::from(21_000_000_000u64)),
+    };
+
+    GasAnalytics::record(record(21_000_000_000)).unwrap();
+    // A transaction recorded again replaces its earlier record
+    GasAnalytics::record(record(42_000_000_000)).unwrap();
+
+    let analytics = GasAnalytics::load().unwrap();
+    assert_eq!(analytics.records.len(), 1);
+    assert_eq!(analytics.total_fees("testnet"), U256::from(42_000_000_000u64));
+}
+
+#[tokio::test]
+async fn broadcast_returns_the_first_accepted_hash() {
+    let server = MockRpcServer::shared();
+    let dead = "http://127.0.0.1:1".to_string();
+
+    let tx_hash = broadcast_raw_transaction(&[dead.clone(), server.url.clone()], vec![0x02])
+        .await
+        .expect("broadcast failed");
+    assert_eq!(tx_hash, fixtures::TX_HASH);
+
+    let err = broadcast_raw_transaction(&[dead], vec![0x02]).await.unwrap_err();
+    assert!(err.to_string().contains("127.0.0.1"), "{}", err);
+}
+
+#[tokio::test]
+async fn gas_overrides_are_checked_for_the_sending_wallet() {
+    let server = MockRpcServer::shared();
+    let client = EthClient::new(&client_config(server, None), None).await.unwrap();
+    let amount = U256::from(5_000_000u64);
+
+    // The mock estimates 21000 gas at 1 gwei with nothing sent yet
+    let overrides = GasOverrides {
+        gas_limit: Some(1_000_000),
+        gas_price: Some(1),
+        nonce: Some(3),
+    };
+    let warnings = check_gas_overrides(
+        &client,
+        fixtures::WALLET_ADDRESS,
+        fixtures::RECIPIENT_ADDRESS,
+        amount,
+        None,
+        &overrides,
+    )
+    .await;
+    assert_eq!(warnings.len(), 3, "{:?}", warnings);
+    assert!(warnings[0].contains("more than 3x the estimate of 21000"));
+    assert!(warnings[1].contains("less than half the network price"));
+    assert!(warnings[2].contains("leaves a gap (next is 0)"));
+
+    // The estimate is made from the wallet for the amount being sent
+    let estimate = server
+        .requests()
+        .into_iter()
+        .find(|r| {
+            r["method"] == "eth_estimateGas"
+                && r["params"][0]["value"] == format!("{:#x}", amount)
+        })
+        .expect("no gas estimate requested");
+    assert_eq!(
+        estimate["params"][0]["from"],
+        format!("{:#x}", fixtures::WALLET_ADDRESS)
+    );
+
+    // Values close to the node's pass
+    let overrides = GasOverrides {
+        gas_limit: Some(21_000),
+        gas_price: Some(1_000_000_000),
+        nonce: Some(0),
+    };
+    assert!(
+        check_gas_overrides(
+            &client,
+            fixtures::WALLET_ADDRESS,
+            fixtures::RECIPIENT_ADDRESS,
+            amount,
+            None,
+            &overrides,
+        )
+        .await
+        .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn rotation_sweeps_to_the_new_key_and_moves_its_label() {
+    let server = MockRpcServer::shared();
+    let old_address = fixtures::WALLET_ADDRESS;
+
+    // The wallet being rotated is not the current one
+    let mut wallet_data = WalletData::load().unwrap();
+    let signer: PrivateKeySigner = fixtures::PRIVATE_KEY.parse().unwrap();
+    wallet_data
+        .add_wallet(Wallet::new(signer, "rotation-old", "old-password").unwrap())
+        .unwrap();
+    let current = Address::repeat_byte(0x42);
+    let hardware = HardwareAccount {
+        device: HardwareDevice::Trezor,
+        derivation_path: "m/44'/137'/0'/0/0".to_string(),
+        address_verified_at: None,
+    };
+    wallet_data
+        .add_wallet(Wallet::new_hardware(current, "rotation-current", hardware))
+        .unwrap();
+    wallet_data.save().unwrap();
+
+    let mut store = store::open().unwrap();
+    store
+        .save_labels(&BTreeMap::from([(old_address, "treasury".to_string())]))
+        .unwrap();
+    let contact = Contact::new("self".to_string(), old_address, None, vec![]);
+    store.save_contacts(&[contact]).unwrap();
+
+    let network = client_config(server, None).network;
+    rotate_wallet(&network, "testnet", "rotation-old", "old-password", "rotation-new", "pw")
+        .await
+        .expect("rotation failed");
+
+    let wallet_data = WalletData::load().unwrap();
+    let new_address = wallet_data.get_wallet_by_name("rotation-new").unwrap().address;
+    assert!(wallet_data.get_wallet_by_name("rotation-old").unwrap().watch_only);
+    assert_eq!(wallet_data.get_current_wallet().unwrap().address, new_address);
+
+    // The RBTC was swept to the new key
+    let sweeps: Vec<TxEnvelope> = server
+        .requests()
+        .iter()
+        .filter(|r| r["method"] == "eth_sendRawTransaction")
+        .filter_map(|r| hex::decode(r["params"][0].as_str()?.trim_start_matches("0x")).ok())
+        .filter_map(|raw| TxEnvelope::decode_2718(&mut raw.as_slice()).ok())
+        .filter(|tx| tx.to() == Some(new_address))
+        .collect();
+    assert_eq!(sweeps.len(), 1);
+    assert!(!sweeps[0].value().is_zero());
+
+    let store = store::open().unwrap();
+    let labels = store.load_labels().unwrap();
+    assert_eq!(labels.get(&new_address).map(String::as_str), Some("treasury"));
+    assert!(!labels.contains_key(&old_address));
+    assert_eq!(store.load_contacts().unwrap()[0].address, new_address);
+}
+
+#[tokio::test]
+async fn unlock_wallet_with_password_env() {
+    // Wrong passwords go to the isolated lockout state and audit log
+    let _server = MockRpcServer::shared();
+    let signer = PrivateKeySigner::random();
+    let wallet = Wallet::new(signer.clone(), "scripted", "from-env").unwrap();
+
+    // The variable wins over the keyring and the prompt, right or wrong.
+    // SAFETY: no other test reads this variable.
+    unsafe { std::env::set_var(PASSWORD_ENV, "wrong") };
+    assert!(unlock_wallet(&wallet, 0, "Password: ").is_err());
+    unsafe { std::env::set_var(PASSWORD_ENV, "from-env") };
+    let private_key = unlock_wallet(&wallet, 0, "Password: ");
+    unsafe { std::env::remove_var(PASSWORD_ENV) };
+    session::lock(&wallet.address);
+
+    let unlocked: PrivateKeySigner = private_key.unwrap().parse().unwrap();
+    assert_eq!(unlocked.address(), signer.address());
+}